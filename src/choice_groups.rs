@@ -1,8 +1,11 @@
-use crate::data_types::{EntityId, ValidationError};
+use crate::data_types::{EntityId, DRAFT_ID, ValidationError};
 use crate::Action;
 use crate::entity_component::{self, Entity, EditState};
+use crate::text_style::TextStyle;
+use crate::items::Item;
 use serde::{Serialize, Deserialize};
 use iced::Element;
+use iced::widget::scrollable;
 use std::collections::BTreeMap;
 
 #[derive(Debug, Clone)]
@@ -14,6 +17,8 @@ pub enum Message {
     UpdateName(EntityId, String),
     CreateNew,
     CancelEdit(EntityId),
+    ToggleSortByUsage,
+    ListScrolled(scrollable::Viewport),
 }
 
 #[derive(Debug, Clone)]
@@ -25,12 +30,18 @@ pub enum Operation {
     UpdateName(EntityId, String),
     CreateNew,
     CancelEdit(EntityId),
+    ToggleSortByUsage,
+    ListScrolled(scrollable::Viewport),
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ChoiceGroup {
     pub id: EntityId,
     pub name: String,
+    #[serde(default)]
+    pub created_at: Option<String>,
+    #[serde(default)]
+    pub updated_at: Option<String>,
 }
 
 impl std::fmt::Display for ChoiceGroup {
@@ -42,8 +53,10 @@ impl std::fmt::Display for ChoiceGroup {
 impl Default for ChoiceGroup {
     fn default() -> Self {
         Self {
-            id: -1,
+            id: DRAFT_ID,
             name: String::new(),
+            created_at: None,
+            updated_at: None,
         }
     }
 }
@@ -72,6 +85,14 @@ impl Entity for ChoiceGroup {
     fn default_new() -> Self {
         Self::default()
     }
+
+    fn created_at(&self) -> Option<&str> {
+        self.created_at.as_deref()
+    }
+
+    fn updated_at(&self) -> Option<&str> {
+        self.updated_at.as_deref()
+    }
 }
 
 impl ChoiceGroup {
@@ -104,8 +125,12 @@ impl ChoiceGroup {
     }
 }
 
+// Title of the screen, and the id of its scrollable list
+pub const LIST_ID: &str = "Choice Groups";
+
 pub fn update(
     message: Message,
+    groups: &BTreeMap<EntityId, ChoiceGroup>,
 ) -> Action<Operation, Message> {
     match message {
         Message::RequestDelete(id) => {
@@ -119,9 +144,11 @@ pub fn update(
         },
         Message::CreateNew => {
             Action::operation(Operation::CreateNew)
+                .with_task(entity_component::reveal_new_row(LIST_ID, groups))
         },
         Message::SaveAll(id, edit_state) => {
-            Action::operation(Operation::SaveAll(id, edit_state))
+            let focus = entity_component::focus_duplicate_name(LIST_ID, groups, id, &edit_state.name);
+            Action::operation(Operation::SaveAll(id, edit_state)).with_task(focus)
         }
         Message::UpdateName(id, new_name) => {
             Action::operation(Operation::UpdateName(id, new_name))
@@ -129,27 +156,56 @@ pub fn update(
         Message::CancelEdit(id) => {
             Action::operation(Operation::CancelEdit(id))
         }
+        Message::ToggleSortByUsage => {
+            Action::operation(Operation::ToggleSortByUsage)
+        }
+        Message::ListScrolled(viewport) => {
+            Action::operation(Operation::ListScrolled(viewport))
+        }
     }
 }
 
 pub fn view<'a>(
     all_groups: &'a BTreeMap<EntityId, ChoiceGroup>,
     edit_states: &'a Vec<EditState>,
+    usage: BTreeMap<EntityId, usize>,
+    sort_by_usage: bool,
+    text_style: TextStyle,
 ) -> Element<'a, Message> {
-    entity_component::entity_view(
-        "Choice Groups",
+    entity_component::entity_usage_view(
+        LIST_ID,
         Message::CreateNew,
         all_groups,
         edit_states,
-        |choice_group, edit_states| render_choice_group_row(choice_group, edit_states)
+        usage,
+        sort_by_usage,
+        Message::ToggleSortByUsage,
+        Message::ListScrolled,
+        move |choice_group, edit_states| render_choice_group_row(choice_group, edit_states, text_style),
+        text_style,
     )
 }
 
+/// Number of items referencing each choice group
+pub fn usage_counts(items: &BTreeMap<EntityId, Item>) -> BTreeMap<EntityId, usize> {
+    let mut counts = BTreeMap::new();
+
+    for item in items.values() {
+        for (id, _) in item.choice_groups.iter().flatten() {
+            *counts.entry(*id).or_insert(0) += 1;
+        }
+    }
+
+    counts
+}
+
 fn render_choice_group_row<'a>(
     choice_group: &'a ChoiceGroup,
-    edit_states: &'a Vec<EditState>
+    edit_states: &'a Vec<EditState>,
+    text_style: TextStyle,
 ) -> Element<'a, Message> {
     entity_component::entity_quick_edit_view(
+        LIST_ID,
         choice_group,
         edit_states,
         Message::EditChoiceGroup,
@@ -158,13 +214,7 @@ fn render_choice_group_row<'a>(
         Message::RequestDelete,
         Message::CancelEdit,
         Message::UpdateName,
-        "Choice Group Name"
+        "Choice Group Name",
+        text_style,
     )
 }
-
-fn get_next_id(groups: &BTreeMap<EntityId, ChoiceGroup>) -> EntityId {
-    groups
-        .keys()
-        .max()
-        .map_or(1, |max_id| max_id + 1)
-}
\ No newline at end of file
@@ -0,0 +1,191 @@
+use crate::items::{export_items, json_interchange};
+use crate::{persistence, MenuBuilder};
+use std::path::Path;
+
+// Exit codes for scheduled jobs
+const EXIT_OK: i32 = 0;
+const EXIT_FAILED: i32 = 1;
+const EXIT_USAGE: i32 = 2;
+
+const USAGE: &str = "\
+Usage:
+  property_menu_builder --export pos-csv --db <path> --out <path>
+  property_menu_builder --export json --db <path> --out <path>
+  property_menu_builder --validate --db <path>
+
+Runs without opening a window. Exports are refused when validation finds problems.";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ExportFormat {
+    PosCsv,
+    Json,
+}
+
+#[derive(Debug)]
+enum Command {
+    Export { format: ExportFormat, db: String, out: String },
+    Validate { db: String },
+}
+
+/// Runs a headless command from the command line arguments (without the
+/// program name) and returns the process exit code
+pub fn run(args: &[String]) -> i32 {
+    let command = match parse(args) {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, USAGE);
+            return EXIT_USAGE;
+        }
+    };
+
+    match command {
+        Command::Validate { db } => {
+            let Some(app) = load(&db) else {
+                return EXIT_FAILED;
+            };
+            if print_problems(&app) {
+                EXIT_FAILED
+            } else {
+                println!("{}: no problems found in {} items", db, app.items.len());
+                EXIT_OK
+            }
+        }
+        Command::Export { format, db, out } => {
+            let Some(app) = load(&db) else {
+                return EXIT_FAILED;
+            };
+            if print_problems(&app) {
+                eprintln!("Export to {} skipped, fix the problems above first", out);
+                return EXIT_FAILED;
+            }
+
+            let content = match format {
+                ExportFormat::PosCsv => export_items::items_to_export_string(app.items.values(), &app.settings.export_profile),
+                ExportFormat::Json => {
+                    let export = json_interchange::ItemsExport::new(
+                        &app.items,
+                        &app.item_groups,
+                        &app.price_levels,
+                        &app.product_classes,
+                        &app.tax_groups,
+                        &app.security_levels,
+                        &app.revenue_categories,
+                        &app.report_categories,
+                        &app.choice_groups,
+                        &app.printer_logicals,
+                    );
+                    match export.to_json_string() {
+                        Ok(json) => json,
+                        Err(e) => {
+                            eprintln!("Could not serialize the export: {}", e);
+                            return EXIT_FAILED;
+                        }
+                    }
+                }
+            };
+
+            if let Err(e) = std::fs::write(&out, content) {
+                eprintln!("Could not write {}: {}", out, e);
+                return EXIT_FAILED;
+            }
+
+            println!("Exported {} items from {} to {}", app.items.len(), db, out);
+            EXIT_OK
+        }
+    }
+}
+
+fn parse(args: &[String]) -> Result<Command, String> {
+    let mut export = None;
+    let mut validate = false;
+    let mut db = None;
+    let mut out = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().cloned().ok_or(format!("{} needs a value", arg));
+        match arg.as_str() {
+            "--export" => export = Some(match value()?.as_str() {
+                "pos-csv" => ExportFormat::PosCsv,
+                "json" => ExportFormat::Json,
+                other => return Err(format!("Unknown export format \"{}\"", other)),
+            }),
+            "--validate" => validate = true,
+            "--db" => db = Some(value()?),
+            "--out" => out = Some(value()?),
+            other => return Err(format!("Unknown argument \"{}\"", other)),
+        }
+    }
+
+    let db = db.ok_or("--db is required".to_string())?;
+
+    match (export, validate) {
+        (Some(_), true) => Err("Use either --export or --validate, not both".to_string()),
+        (Some(format), false) => {
+            let out = out.ok_or("--out is required for --export".to_string())?;
+            Ok(Command::Export { format, db, out })
+        }
+        (None, true) => Ok(Command::Validate { db }),
+        (None, false) => Err("Nothing to do, pass --export or --validate".to_string()),
+    }
+}
+
+// Loads the database the same way the app does on startup, without a window.
+// The app's data directory is left alone, nothing here writes outside --out.
+fn load(db: &str) -> Option<MenuBuilder> {
+    let (state, repairs) = match persistence::load_repaired(db) {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            eprintln!("Could not load {}: {}", db, e);
+            return None;
+        }
+    };
+
+    for repair in repairs {
+        println!("Repaired duplicate ID: {}", repair);
+    }
+
+    let db_dir = Path::new(db).parent().unwrap_or(Path::new(".")).to_path_buf();
+    let mut app = MenuBuilder::with_file_manager(persistence::FileManager::in_dir(db_dir), None);
+    app.settings = state.settings.clone();
+    app.restore_app_state(state);
+    Some(app)
+}
+
+// Prints every validation problem, returns true when there were any
+fn print_problems(app: &MenuBuilder) -> bool {
+    let problems = app.validate_all();
+    for problem in &problems {
+        eprintln!("{}", problem);
+    }
+    if !problems.is_empty() {
+        eprintln!("{} problems found", problems.len());
+    }
+    !problems.is_empty()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::items::Item;
+    use crate::persistence::AppState;
+
+    #[test]
+    fn load_keeps_to_the_database_folder() {
+        let dir = std::env::temp_dir().join(format!("menu_builder_{}_cli_load", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let db = dir.join("menu.ron");
+        let state = AppState {
+            items: vec![Item { id: 1, name: "Cola".to_string(), ..Item::default() }],
+            ..AppState::default()
+        };
+        persistence::save_now(&state, &db, false).unwrap();
+
+        let app = load(&db.to_string_lossy()).unwrap();
+        assert_eq!(app.items[&1].name, "Cola");
+        assert_eq!(app.file_manager.data_dir(), dir.as_path());
+        assert!(app.startup_error.is_none());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -1,11 +1,52 @@
 use std::ops::Range;
+use std::collections::BTreeMap;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
+use chrono::{DateTime, Duration, Local, NaiveDateTime, SecondsFormat, TimeZone, Utc};
 
 // Custom type for IDs to make it easier to change the underlying type if needed
 pub type EntityId = i32;
 
+/// Id of an entity that hasn't been saved yet, saving gives it a real one
+pub const DRAFT_ID: EntityId = -1;
+
+/// Item ids the catalog allows, set in Settings to match the POS
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IdRange {
+    pub min: EntityId,
+    pub max: EntityId,
+}
+
+impl Default for IdRange {
+    fn default() -> Self {
+        Self { min: 1, max: EntityId::MAX }
+    }
+}
+
+impl IdRange {
+    pub fn contains(&self, id: EntityId) -> bool {
+        id >= self.min && id <= self.max
+    }
+
+    /// Why an id can't be used, None when it's inside the range
+    pub fn problem(&self, id: EntityId) -> Option<String> {
+        (!self.contains(id)).then(|| {
+            format!("ID {} is outside the allowed range {}-{}", id, self.min, self.max)
+        })
+    }
+
+    /// One past the highest id in use, or the start of the range; errors when the range is full
+    pub fn next_id<T>(&self, entities: &BTreeMap<EntityId, T>) -> Result<EntityId, String> {
+        let next = match entities.keys().next_back() {
+            Some(highest) => highest.checked_add(1).map(|id| id.max(self.min)),
+            None => Some(self.min),
+        };
+        next.filter(|id| *id <= self.max)
+            .ok_or_else(|| format!("No item IDs left, the range ends at {}", self.max))
+    }
+}
+
 // Custom type for currency values
 pub type Currency = Decimal;
 
@@ -15,6 +56,49 @@ pub fn string_to_decimal(input: &str) -> Result<Decimal, String> {
         .map_err(|e| format!("Failed to convert '{}' to Decimal: {}", input, e))
 }
 
+/// Money as shown in the app, "$4.50" and "-$0.25", rounded to the given places
+pub fn format_money(amount: Decimal, decimals: u32) -> String {
+    let rounded = amount.round_dp(decimals);
+    let sign = if rounded.is_sign_negative() && !rounded.is_zero() { "-" } else { "" };
+    format!("{}${:.*}", sign, decimals as usize, rounded.abs())
+}
+
+/// Timestamps before they were stored in UTC, local time with no offset
+const LOCAL_TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+
+/// Created/updated metadata is stored as UTC in RFC 3339, so shared files compare
+/// the same from any time zone and across DST changes
+pub fn timestamp_now() -> String {
+    Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+/// Reads a stored timestamp. Older files hold local time without an offset, those
+/// are taken as this machine's local time.
+pub fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(value) {
+        return Some(timestamp.with_timezone(&Utc));
+    }
+
+    let naive = NaiveDateTime::parse_from_str(value, LOCAL_TIMESTAMP_FORMAT).ok()?;
+    local_to_utc(naive)
+}
+
+/// Local wall-clock time to UTC. A time repeated when the clocks go back takes the
+/// earlier one, a time skipped when they go forward takes the hour after.
+pub fn local_to_utc(naive: NaiveDateTime) -> Option<DateTime<Utc>> {
+    Local.from_local_datetime(&naive).earliest()
+        .or_else(|| Local.from_local_datetime(&(naive + Duration::hours(1))).earliest())
+        .map(|local| local.with_timezone(&Utc))
+}
+
+/// A stored timestamp in local time for showing in the app, text that isn't a timestamp is shown as is
+pub fn display_timestamp(value: &str) -> String {
+    parse_timestamp(value)
+        .map(|timestamp| timestamp.with_timezone(&Local).format(LOCAL_TIMESTAMP_FORMAT).to_string())
+        .unwrap_or_else(|| value.to_string())
+}
+
 //Struct to handle PriceLevel: Price pairs
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
 pub struct ItemPrice {
@@ -107,4 +191,42 @@ pub trait EntityResolver {
             .cloned()
             .unwrap_or_else(|| format!("Unknown Group ({})", id))
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_timestamps_are_utc_rfc3339() {
+        let now = timestamp_now();
+        assert!(now.ends_with('Z'), "{}", now);
+        assert!(DateTime::parse_from_rfc3339(&now).is_ok(), "{}", now);
+    }
+
+    #[test]
+    fn offsets_are_read_as_the_same_instant() {
+        let utc = parse_timestamp("2025-03-01T12:30:00Z").unwrap();
+        assert_eq!(parse_timestamp("2025-03-01T14:30:00+02:00"), Some(utc));
+        assert_eq!(parse_timestamp("2025-03-01T07:30:00-05:00"), Some(utc));
+    }
+
+    #[test]
+    fn old_local_timestamps_are_read_as_local_time() {
+        let naive = NaiveDateTime::parse_from_str("2025-03-01 14:30:00", LOCAL_TIMESTAMP_FORMAT).unwrap();
+        let expected = Local.from_local_datetime(&naive).earliest().unwrap().with_timezone(&Utc);
+
+        assert_eq!(parse_timestamp("2025-03-01 14:30:00"), Some(expected));
+        assert_eq!(display_timestamp("2025-03-01 14:30:00"), "2025-03-01 14:30:00");
+    }
+
+    #[test]
+    fn display_converts_to_local_time() {
+        let expected = Utc.with_ymd_and_hms(2025, 3, 1, 12, 30, 0).unwrap()
+            .with_timezone(&Local)
+            .format(LOCAL_TIMESTAMP_FORMAT)
+            .to_string();
+
+        assert_eq!(display_timestamp("2025-03-01T12:30:00Z"), expected);
+        assert_eq!(display_timestamp("not a time"), "not a time");
+    }
+}
@@ -1,9 +1,11 @@
-use crate::data_types::{EntityId, ValidationError};
+use crate::data_types::{EntityId, ValidationError, display_timestamp};
 use crate::icon;
+use crate::focus_style;
+use crate::text_style::TextStyle;
 use iced_modern_theme::Modern;
-use iced::{Element, Length};
-use iced::widget::{button, column, container, row, text, scrollable, text_input, tooltip, TextInput};
-use std::collections::BTreeMap;
+use iced::{Element, Length, Task};
+use iced::widget::{button, column, combo_box, container, row, text, scrollable, text_input, tooltip, TextInput};
+use std::collections::{BTreeMap, HashMap};
 
 /// Trait that defines common behavior for entity types
 pub trait Entity: Clone + std::fmt::Display {
@@ -12,6 +14,8 @@ pub trait Entity: Clone + std::fmt::Display {
     fn with_id(&self, id: EntityId) -> Self;
     fn with_name(&self, name: String) -> Self;
     fn default_new() -> Self;
+    fn created_at(&self) -> Option<&str>;
+    fn updated_at(&self) -> Option<&str>;
 }
 
 /// Generic edit state for editing entities
@@ -70,17 +74,293 @@ impl EditState {
     }
 }
 
+/// A picker entry, shown as "Name (#ID)" so entities sharing a name can be told apart
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntityOption {
+    pub id: EntityId,
+    pub name: String,
+}
+
+impl std::fmt::Display for EntityOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (#{})", self.name, self.id)
+    }
+}
+
+pub fn entity_options<'a, T: Entity + 'a>(entities: impl IntoIterator<Item = &'a T>) -> Vec<EntityOption> {
+    entities
+        .into_iter()
+        .map(|entity| EntityOption { id: entity.id(), name: entity.name().to_string() })
+        .collect()
+}
+
+/// Type-to-filter picker for entities. The combo box state has to live in app
+/// state, along with what was last typed so an empty result can be shown.
+#[derive(Debug, Clone, Default)]
+pub struct EntityPicker {
+    state: combo_box::State<EntityOption>,
+    options: Vec<EntityOption>,
+    query: String,
+}
+
+impl EntityPicker {
+    pub fn new(options: Vec<EntityOption>) -> Self {
+        Self {
+            state: combo_box::State::new(options.clone()),
+            options,
+            query: String::new(),
+        }
+    }
+
+    /// Rebuilds the picker only when the options changed, so typing isn't reset
+    pub fn sync(&mut self, options: Vec<EntityOption>) {
+        if options != self.options {
+            *self = Self::new(options);
+        }
+    }
+
+    pub fn set_query(&mut self, query: String) {
+        self.query = query;
+    }
+
+    pub fn options(&self) -> &[EntityOption] {
+        &self.options
+    }
+
+    // Same matching as the combo box: every typed word appears in the label, ignoring case
+    fn has_matches(&self) -> bool {
+        let query = self.query.to_lowercase();
+        self.options.iter().any(|option| {
+            let label = option.to_string().to_lowercase();
+            query.split_whitespace().all(|term| label.contains(term))
+        })
+    }
+
+    pub fn view<'a, Message>(
+        &'a self,
+        placeholder: &str,
+        selected: Option<EntityId>,
+        on_select: impl Fn(EntityId) -> Message + 'static,
+        on_query: impl Fn(String) -> Message + 'static,
+        width: f32,
+        text_style: TextStyle,
+    ) -> Element<'a, Message>
+    where
+        Message: Clone + 'a,
+    {
+        let selection = selected.and_then(|id| self.options.iter().find(|option| option.id == id));
+
+        let picker = combo_box(
+            &self.state,
+            placeholder,
+            selection,
+            move |option: EntityOption| on_select(option.id),
+        )
+        .on_input(on_query)
+        .input_style(focus_style::text_input(Modern::combo_box()))
+        .menu_style(Modern::combo_box_menu())
+        .width(width);
+
+        if self.query.trim().is_empty() || self.has_matches() {
+            picker.into()
+        } else {
+            column![
+                picker,
+                text("No matches").size(text_style.caption()).style(Modern::secondary_text()),
+            ]
+            .spacing(2)
+            .into()
+        }
+    }
+}
+
+/// Hover text on an entity's ID showing when it was created and last modified
+pub fn with_timestamps<'a, T, Message>(
+    content: impl Into<Element<'a, Message>>,
+    entity: &T,
+    text_style: TextStyle,
+) -> Element<'a, Message>
+where
+    T: Entity,
+    Message: 'a,
+{
+    let timestamps = format!(
+        "Created: {}\nModified: {}",
+        entity.created_at().map_or_else(|| "Unknown".to_string(), display_timestamp),
+        entity.updated_at().map_or_else(|| "Unknown".to_string(), display_timestamp),
+    );
+
+    tooltip(
+        content,
+        container(text(timestamps).size(text_style.caption())).padding(10).style(Modern::card_container()),
+        tooltip::Position::Top,
+    ).into()
+}
+
+/// Export key input for an edit row, the hover text says why it's separate from the name
+pub fn export_key_input<'a, Message>(
+    list_id: &str,
+    id: EntityId,
+    export_key: &str,
+    on_input: impl Fn(String) -> Message + 'a,
+    text_style: TextStyle,
+) -> Element<'a, Message>
+where
+    Message: Clone + 'a,
+{
+    tooltip(
+        text_input("Export key", export_key)
+            .id(field_input_id(list_id, id, "export key"))
+            .on_input(on_input)
+            .style(Modern::validated_text_input(export_key.trim().is_empty()))
+            .width(Length::Fixed(120.0)),
+        container(
+            text("Exports name it by this key, so renaming it here doesn't break systems matching on the old name")
+                .size(text_style.caption())
+        )
+        .padding(10)
+        .max_width(300)
+        .style(Modern::card_container()),
+        tooltip::Position::Top,
+    ).into()
+}
+
+/// Notice for a rename that leaves exports on a key other than the new name
+pub fn export_key_rename_warning(original_name: &str, new_name: &str, export_key: &str) -> Option<String> {
+    if original_name.is_empty() || original_name == new_name || export_key == new_name {
+        return None;
+    }
+    Some(format!(
+        "Renamed '{}' to '{}', but exports will still say '{}'. Update the export key too if they should change.",
+        original_name, new_name, export_key
+    ))
+}
+
+/// Validation report lines for empty export keys and keys used by more than one entity
+pub fn export_key_problems<'a>(
+    type_name: &str,
+    keys: impl IntoIterator<Item = (EntityId, &'a str, &'a str)>, // Id, name and export key
+) -> Vec<String> {
+    let mut problems = Vec::new();
+    let mut seen: HashMap<String, EntityId> = HashMap::new();
+
+    for (id, name, key) in keys {
+        if key.trim().is_empty() {
+            problems.push(format!("{} {} ({}): export key is empty", type_name, id, name));
+        } else if let Some(first) = seen.get(&key.trim().to_lowercase()) {
+            problems.push(format!("{} {} ({}): export key '{}' is also used by {} {}", type_name, id, name, key, type_name, first));
+        } else {
+            seen.insert(key.trim().to_lowercase(), id);
+        }
+    }
+    problems
+}
+
+/// Id for a new entity, one past the highest so it lands at the end of the list
+pub fn next_id<T>(entities: &BTreeMap<EntityId, T>) -> EntityId {
+    entities
+        .keys()
+        .max()
+        .map_or(1, |max_id| max_id + 1)
+}
+
+/// Id of the name input on an entity's row, so an update can put the cursor there
+pub fn name_input_id(list_id: &str, id: EntityId) -> text_input::Id {
+    field_input_id(list_id, id, "name")
+}
+
+/// Id of another input on an entity's row, like a tax rate or range start
+pub fn field_input_id(list_id: &str, id: EntityId, field: &str) -> text_input::Id {
+    text_input::Id::new(format!("{} {} {}", list_id, field, id))
+}
+
+/// Follow-up for CreateNew: scrolls the list down to the new row and focuses its name.
+/// Run after the operation, when `entities` still holds the list from before the new row.
+pub fn reveal_new_row<T, Message>(list_id: &'static str, entities: &BTreeMap<EntityId, T>) -> Task<Message>
+where
+    Message: Send + 'static,
+{
+    Task::batch([
+        scrollable::snap_to(scrollable::Id::new(list_id), scrollable::RelativeOffset::END),
+        text_input::focus(name_input_id(list_id, next_id(entities))),
+    ])
+}
+
+/// Where a list was last scrolled to, and how many rows it had then
+#[derive(Debug, Clone, Copy)]
+struct ListPosition {
+    offset: scrollable::AbsoluteOffset,
+    content_height: f32,
+    viewport_height: f32,
+    rows: usize,
+}
+
+/// Scroll positions of the long lists, so a change that rebuilds one can put it back
+#[derive(Debug, Clone, Default)]
+pub struct ListScroll {
+    positions: HashMap<&'static str, ListPosition>,
+}
+
+impl ListScroll {
+    pub fn remember(&mut self, list_id: &'static str, viewport: scrollable::Viewport, rows: usize) {
+        self.positions.insert(list_id, ListPosition {
+            offset: viewport.absolute_offset(),
+            content_height: viewport.content_bounds().height,
+            viewport_height: viewport.bounds().height,
+            rows,
+        });
+    }
+
+    /// Scrolls a rebuilt list back to where it was. Rows are taken to be about the same height,
+    /// so a list that shrank past the old offset is shown from its bottom instead of blank
+    pub fn restore<Message>(&self, list_id: &'static str, rows: usize) -> Task<Message>
+    where
+        Message: Send + 'static,
+    {
+        let Some(position) = self.positions.get(list_id) else {
+            return Task::none();
+        };
+        let id = scrollable::Id::new(list_id);
+
+        if rows < position.rows {
+            let content_height = position.content_height * rows as f32 / position.rows as f32;
+            if position.offset.y > content_height - position.viewport_height {
+                return scrollable::snap_to(id, scrollable::RelativeOffset::END);
+            }
+        }
+
+        scrollable::scroll_to(id, position.offset)
+    }
+}
+
+/// Follow-up for a save that will be turned back for a duplicate name, focuses the name to fix
+pub fn focus_duplicate_name<T: Entity, Message>(
+    list_id: &'static str,
+    entities: &BTreeMap<EntityId, T>,
+    id: EntityId,
+    name: &str,
+) -> Task<Message>
+where
+    Message: Send + 'static,
+{
+    match duplicate_name_error(entities, id, name) {
+        Some(_) => text_input::focus(name_input_id(list_id, id)),
+        None => Task::none(),
+    }
+}
+
 /// Generic function to render title row
 pub fn render_title_row<'a, Message>(
     title: &'a str, 
     create_message: Message,
-    width: f32
+    width: f32,
+    text_style: TextStyle,
 ) -> Element<'a, Message> 
 where
     Message: Clone + 'a,
 {
     row![
-        text(title).size(18).style(Modern::primary_text()),
+        text(title).size(text_style.heading()).style(Modern::primary_text()),
         iced::widget::horizontal_space(),
         button(icon::new().size(14))
             .on_press(create_message)
@@ -107,8 +387,10 @@ where
 
 /// Generic function to render entity list
 pub fn render_entity_list<'a, T, Message, F>(
+    list_id: &'static str,
     entities: &'a BTreeMap<EntityId, T>,
     edit_states: &'a Vec<EditState>,
+    on_scroll: fn(scrollable::Viewport) -> Message,
     row_renderer: F
 ) -> Element<'a, Message> 
 where
@@ -129,26 +411,30 @@ where
                 .collect::<Vec<_>>()
         )
     )
+    .id(scrollable::Id::new(list_id)) // Stable id, so edits don't reset the scroll position
+    .on_scroll(on_scroll)
     .height(Length::Fill)
     .into()
 }
 
 /// Generic function for entity view layout
 pub fn entity_view<'a, T, Message, F>(
-    title: &'a str,
+    title: &'static str,
     create_message: Message,
     entities: &'a BTreeMap<EntityId, T>,
     edit_states: &'a Vec<EditState>,
+    on_scroll: fn(scrollable::Viewport) -> Message,
     row_renderer: F,
+    text_style: TextStyle,
 ) -> Element<'a, Message> 
 where
     T: Entity + 'a,
     Message: Clone + 'a,
     F: Fn(&'a T, &'a Vec<EditState>) -> Element<'a, Message> + 'a,
 {
-    let title_row = render_title_row(title, create_message, 505.0);
+    let title_row = render_title_row(title, create_message, 505.0, text_style);
     let header_row = render_header_row();
-    let entity_list = render_entity_list(entities, edit_states, row_renderer);
+    let entity_list = render_entity_list(title, entities, edit_states, on_scroll, row_renderer);
 
     let all_content = column![title_row, header_row, entity_list];
 
@@ -160,8 +446,116 @@ where
     .into()
 }
 
+/// Generic entity view with a column showing how many items use each entity.
+/// Unused entities show 0, which makes them easy to spot for cleanup.
+pub fn entity_usage_view<'a, T, Message, F>(
+    title: &'static str,
+    create_message: Message,
+    entities: &'a BTreeMap<EntityId, T>,
+    edit_states: &'a Vec<EditState>,
+    usage: BTreeMap<EntityId, usize>,
+    sort_by_usage: bool,
+    toggle_sort_message: Message,
+    on_scroll: fn(scrollable::Viewport) -> Message,
+    row_renderer: F,
+    text_style: TextStyle,
+) -> Element<'a, Message>
+where
+    T: Entity + 'a,
+    Message: Clone + 'a,
+    F: Fn(&'a T, &'a Vec<EditState>) -> Element<'a, Message> + 'a,
+{
+    let title_row = render_title_row(title, create_message, 575.0, text_style);
+
+    let header_row = row![
+        text("ID").width(Length::Fixed(75.0)),
+        text("Name").width(Length::Fixed(250.0)),
+        text("Actions").width(Length::Fixed(150.0)),
+        button(text(if sort_by_usage { "Used ▼" } else { "Used" }))
+            .on_press(toggle_sort_message)
+            .style(Modern::system_button())
+            .width(Length::Fixed(70.0)),
+    ]
+    .align_y(iced::Alignment::Center)
+    .padding(15);
+
+    let rows = sorted_by_usage(entities, &usage, sort_by_usage)
+        .into_iter()
+        .map(|(entity, count)| {
+            row![
+                row_renderer(entity, edit_states),
+                text(count.to_string()).width(Length::Fixed(60.0)),
+            ]
+            .align_y(iced::Alignment::Center)
+            .padding(5)
+            .into()
+        })
+        .collect::<Vec<_>>();
+
+    let all_content = column![
+        title_row,
+        header_row,
+        scrollable(column(rows))
+            .id(scrollable::Id::new(title))
+            .on_scroll(on_scroll)
+            .height(Length::Fill),
+    ];
+
+    column![
+        container(all_content)
+            .height(Length::Shrink)
+            .style(Modern::card_container())
+    ]
+    .into()
+}
+
+/// Error for a name another entity of the same type already has. Case-insensitive,
+/// and the entity being edited doesn't count against itself.
+pub fn duplicate_name_error<T: Entity>(
+    entities: &BTreeMap<EntityId, T>,
+    id: EntityId,
+    name: &str,
+) -> Option<String> {
+    let name = name.trim().to_lowercase();
+    entities
+        .values()
+        .find(|other| other.id() != id && other.name().trim().to_lowercase() == name)
+        .map(|other| format!("ID {} is already named \"{}\"", other.id(), other.name()))
+}
+
+/// Error to show while a name is typed: all spaces, or another entity's name
+pub fn name_input_error<T: Entity>(
+    entities: &BTreeMap<EntityId, T>,
+    id: EntityId,
+    name: &str,
+) -> Option<String> {
+    if !name.is_empty() && name.trim().is_empty() {
+        return Some("Name cannot be only spaces".to_string());
+    }
+    duplicate_name_error(entities, id, name)
+}
+
+/// Pairs entities with their usage count, most used first when `sort_by_usage` is set
+pub fn sorted_by_usage<'a, T>(
+    entities: &'a BTreeMap<EntityId, T>,
+    usage: &BTreeMap<EntityId, usize>,
+    sort_by_usage: bool,
+) -> Vec<(&'a T, usize)> {
+    let mut rows: Vec<(&'a T, usize)> = entities
+        .iter()
+        .map(|(id, entity)| (entity, usage.get(id).copied().unwrap_or(0)))
+        .collect();
+
+    if sort_by_usage {
+        rows.sort_by(|a, b| b.1.cmp(&a.1));
+    }
+
+    rows
+}
+
 /// Generic function for quick edit view of an entity
 pub fn entity_quick_edit_view<'a, T, Message>(
+    list_id: &'static str,
     entity: &'a T,
     edit_states: &'a Vec<EditState>,
     edit_message: impl Fn(EntityId) -> Message + 'a,
@@ -170,7 +564,8 @@ pub fn entity_quick_edit_view<'a, T, Message>(
     delete_message: impl Fn(EntityId) -> Message + 'a,
     cancel_message: impl Fn(EntityId) -> Message + 'a,
     update_name_message: impl Fn(EntityId, String) -> Message + 'a,
-    input_placeholder: &'a str
+    input_placeholder: &'a str,
+    text_style: TextStyle,
 ) -> Element<'a, Message> 
 where
     T: Entity + 'a,
@@ -195,7 +590,8 @@ where
 
     let id_input: Element<'_, Message> = {
         let input: TextInput<'_, Message> = text_input("ID", &entity.id().to_string())
-            .style(Modern::validated_text_input(id_validation_error.is_some()))
+            .font(text_style.data_font())
+            .style(focus_style::text_input(Modern::validated_text_input(id_validation_error.is_some())))
             .width(Length::Fixed(75.0));
 
         if let Some(error) = id_validation_error.as_ref() {
@@ -205,12 +601,13 @@ where
                 tooltip::Position::Top,
             ).into()
         } else {
-            input.into()
+            with_timestamps(input, entity, text_style)
         }
     };
 
     let name_input: Element<'_, Message> = {
         let input: TextInput<'_, Message> = text_input(input_placeholder, &display_name)
+            .id(name_input_id(list_id, entity.id()))
             .on_input_maybe(
                 if editing {
                     Some(move |name| update_name_message(entity.id(), name))
@@ -218,7 +615,7 @@ where
                     None 
                 }
             )
-            .style(Modern::validated_text_input(name_validation_error.is_some()))
+            .style(focus_style::text_input(Modern::validated_text_input(name_validation_error.is_some())))
             .width(Length::Fixed(250.0));
 
         if let Some(error) = name_validation_error.as_ref() {
@@ -269,4 +666,29 @@ where
         .align_y(iced::Alignment::Center)
         .width(Length::Fixed(495.0))
         .into()
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security_levels::SecurityLevel;
+
+    fn levels() -> BTreeMap<EntityId, SecurityLevel> {
+        BTreeMap::from([(1, SecurityLevel { id: 1, name: "Manager".to_string(), ..SecurityLevel::default() })])
+    }
+
+    #[test]
+    fn whitespace_only_names_are_flagged_while_typing() {
+        assert_eq!(name_input_error(&levels(), 2, "   "), Some("Name cannot be only spaces".to_string()));
+        assert_eq!(name_input_error(&levels(), 2, "\t"), Some("Name cannot be only spaces".to_string()));
+        // An empty field is left for save to report, like a field not filled in yet
+        assert_eq!(name_input_error(&levels(), 2, ""), None);
+        assert_eq!(name_input_error(&levels(), 2, " Cashier "), None);
+    }
+
+    #[test]
+    fn padded_names_still_count_as_duplicates() {
+        assert!(name_input_error(&levels(), 2, " manager ").is_some());
+        assert_eq!(name_input_error(&levels(), 1, "Manager"), None);
+    }
+}
@@ -0,0 +1,149 @@
+use crate::text_style::TextStyle;
+use iced_modern_theme::Modern;
+use iced::{Element, Length};
+use iced::widget::{button, column, container, row, scrollable, text};
+use std::path::PathBuf;
+
+/// Lines of the first file shown before anything is written
+pub const PREVIEW_LINES: usize = 100;
+
+/// Which export made the files, so the result can be reported the way that export does
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExportKind {
+    Items,
+    ChangedItems { changed: usize, deleted: usize },
+    Json,
+    PrintableMenu,
+    PricePivot,
+    ByGroup { folder: PathBuf },
+    LoadReport,
+    RoutingMap,
+    GroupStatistics,
+    Patch { changes: usize },
+    DatabaseDiff { changes: usize },
+}
+
+/// An export that has been generated but not written yet. Confirm writes the
+/// files exactly as they are held here.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportPreview {
+    pub kind: ExportKind,
+    pub files: Vec<(PathBuf, String)>, // The first one is shown in the preview
+}
+
+impl ExportPreview {
+    pub fn new(kind: ExportKind, files: Vec<(PathBuf, String)>) -> Self {
+        Self { kind, files }
+    }
+
+    /// Where the export goes, the first file or the folder for exports by group
+    pub fn target(&self) -> PathBuf {
+        match &self.kind {
+            ExportKind::ByGroup { folder } => folder.clone(),
+            _ => self.files.first().map(|(path, _)| path.clone()).unwrap_or_default(),
+        }
+    }
+
+    pub fn line_count(&self) -> usize {
+        self.files.iter().map(|(_, content)| content.lines().count()).sum()
+    }
+
+    pub fn byte_count(&self) -> usize {
+        self.files.iter().map(|(_, content)| content.len()).sum()
+    }
+
+    /// The start of the first file, cut at PREVIEW_LINES
+    pub fn head(&self) -> String {
+        self.files
+            .first()
+            .map(|(_, content)| content.lines().take(PREVIEW_LINES).collect::<Vec<_>>().join("\n"))
+            .unwrap_or_default()
+    }
+
+    pub fn view<'a, Message: Clone + 'a>(&'a self, on_confirm: Message, on_cancel: Message, text_style: TextStyle) -> Element<'a, Message> {
+        let file_count = self.files.len();
+        let totals = format!(
+            "{} file{}, {} lines, {} bytes",
+            file_count,
+            if file_count == 1 { "" } else { "s" },
+            self.line_count(),
+            self.byte_count(),
+        );
+
+        let first_file = match self.files.first() {
+            Some((path, content)) if content.lines().count() > PREVIEW_LINES || file_count > 1 => {
+                format!("Showing the first {} lines of {}", PREVIEW_LINES, path.to_string_lossy())
+            }
+            Some((path, _)) => path.to_string_lossy().to_string(),
+            None => "Nothing to write".to_string(),
+        };
+
+        container(
+            container(
+                column![
+                    text("Export Preview").style(Modern::primary_text()).size(text_style.heading()),
+                    text(format!("Saving to {}", self.target().to_string_lossy()))
+                        .style(Modern::secondary_text())
+                        .size(text_style.caption()),
+                    text(totals).size(text_style.body()),
+                    text(first_file).style(Modern::secondary_text()).size(text_style.caption()),
+                    container(
+                        scrollable(
+                            text(self.head())
+                                .font(iced::Font::MONOSPACE)
+                                .size(text_style.caption())
+                        )
+                        .width(Length::Fill)
+                        .height(300)
+                    )
+                    .padding(5)
+                    .style(Modern::card_container()),
+                    row![
+                        button(if file_count == 1 { "Write File" } else { "Write Files" }).on_press(on_confirm).style(Modern::primary_button()),
+                        iced::widget::horizontal_space(),
+                        button("Cancel").on_press(on_cancel).style(Modern::secondary_button()),
+                    ].spacing(10),
+                ].width(640).spacing(8).padding(10)
+            ).style(Modern::accent_container())
+        ).padding(120)
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::persistence::FileManager;
+
+    #[test]
+    fn confirmed_export_writes_the_previewed_bytes() {
+        let dir = std::env::temp_dir().join(format!("export_preview_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let preview = ExportPreview::new(ExportKind::LoadReport, vec![
+            (dir.join("report.csv"), "\"Printer\",\"Items\"\r\n\"Kitchen \"\"Hot\"\"\",12\r\n".to_string()),
+            (dir.join("map.md"), "| Printer | Café |\n|---|---|\n| Bar | ✓ |".to_string()),
+            (dir.join("empty.json"), String::new()),
+        ]);
+
+        FileManager::new().write_export(&preview.files).unwrap();
+
+        for (path, content) in &preview.files {
+            assert_eq!(std::fs::read(path).unwrap(), content.as_bytes(), "{}", path.display());
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn preview_counts_every_file() {
+        let preview = ExportPreview::new(ExportKind::GroupStatistics, vec![
+            (PathBuf::from("a.csv"), "one\ntwo\n".to_string()),
+            (PathBuf::from("b.csv"), "three".to_string()),
+        ]);
+
+        assert_eq!(preview.line_count(), 3);
+        assert_eq!(preview.byte_count(), 13);
+        assert_eq!(preview.target(), PathBuf::from("a.csv"));
+        assert_eq!(preview.head(), "one\ntwo");
+    }
+}
@@ -0,0 +1,21 @@
+use iced::widget::text_input;
+use iced::Theme;
+
+// Wide enough to stand out next to the 1px borders the Modern inputs use
+const FOCUS_BORDER_WIDTH: f32 = 2.0;
+
+/// Wraps a text input style so the focused input gets a strong border in the
+/// theme's primary color, whether the light or dark Modern theme is active.
+/// Works for combo box inputs too, since they are styled as text inputs.
+pub fn text_input<'a>(
+    base: impl Fn(&Theme, text_input::Status) -> text_input::Style + 'a,
+) -> impl Fn(&Theme, text_input::Status) -> text_input::Style + 'a {
+    move |theme, status| {
+        let mut style = base(theme, status);
+        if matches!(status, text_input::Status::Focused { .. }) {
+            style.border.color = theme.extended_palette().primary.strong.color;
+            style.border.width = FOCUS_BORDER_WIDTH;
+        }
+        style
+    }
+}
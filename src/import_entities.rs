@@ -0,0 +1,448 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::path::Path;
+use csv::ReaderBuilder;
+use rust_decimal::Decimal;
+use serde::{Serialize, Deserialize};
+use crate::{
+    data_types::{EntityId, timestamp_now},
+    entity_component::Entity,
+    items::import_items::ImportWarning,
+    price_levels::{PriceLevel, PriceLevelType, MAX_DECIMALS, DEFAULT_DECIMALS},
+    printer_logicals::PrinterLogical,
+    report_categories::ReportCategory,
+    revenue_categories::RevenueCategory,
+    settings::DIALOG_CLOSED,
+    tax_groups::TaxGroup,
+};
+
+/// The entity types that can be bootstrapped from a spreadsheet
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum EntityCsvKind {
+    #[default]
+    TaxGroups,
+    PriceLevels,
+    ReportCategories,
+    RevenueCategories,
+    PrinterLogicals,
+}
+
+impl EntityCsvKind {
+    pub const ALL: [EntityCsvKind; 5] = [
+        EntityCsvKind::TaxGroups,
+        EntityCsvKind::PriceLevels,
+        EntityCsvKind::ReportCategories,
+        EntityCsvKind::RevenueCategories,
+        EntityCsvKind::PrinterLogicals,
+    ];
+
+    /// Columns the file is expected to have, in order. Bracketed ones are optional.
+    pub fn columns(&self) -> &'static str {
+        match self {
+            Self::TaxGroups => "ID, Name, Rate %",
+            Self::PriceLevels => "ID, Name, [Enterprise/Store], [Decimals]",
+            Self::ReportCategories | Self::RevenueCategories | Self::PrinterLogicals => "ID, Name",
+        }
+    }
+}
+
+impl fmt::Display for EntityCsvKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TaxGroups => write!(f, "Tax Groups"),
+            Self::PriceLevels => write!(f, "Price Levels"),
+            Self::ReportCategories => write!(f, "Report Categories"),
+            Self::RevenueCategories => write!(f, "Revenue Categories"),
+            Self::PrinterLogicals => write!(f, "Printer Logicals"),
+        }
+    }
+}
+
+/// An entity that can be read from one CSV row
+pub trait CsvEntity: Entity {
+    /// IDs the POS accepts for this type, imports outside it are rejected
+    fn id_range() -> RangeInclusive<EntityId>;
+
+    /// Builds the entity from the fields after ID and name
+    fn from_fields(id: EntityId, name: String, rest: &[&str]) -> Result<Self, String>;
+
+    /// Takes the creation time of the entity this row replaces
+    fn replacing(self, existing: &Self) -> Self;
+}
+
+impl CsvEntity for TaxGroup {
+    fn id_range() -> RangeInclusive<EntityId> {
+        1..=99
+    }
+
+    fn from_fields(id: EntityId, name: String, rest: &[&str]) -> Result<Self, String> {
+        let rate = rest.first().copied().unwrap_or_default();
+        let rate = parse_tax_rate(rate)?;
+
+        Ok(TaxGroup {
+            id,
+            export_key: name.clone(),
+            name,
+            rate,
+            created_at: Some(timestamp_now()),
+            updated_at: Some(timestamp_now()),
+        })
+    }
+
+    fn replacing(mut self, existing: &Self) -> Self {
+        self.created_at = existing.created_at.clone();
+        self.export_key = existing.export_key.clone();
+        self
+    }
+}
+
+impl CsvEntity for PriceLevel {
+    fn id_range() -> RangeInclusive<EntityId> {
+        1..=999
+    }
+
+    fn from_fields(id: EntityId, name: String, rest: &[&str]) -> Result<Self, String> {
+        let level_type = match rest.first().map(|s| s.trim().to_lowercase()).as_deref() {
+            None | Some("") | Some("enterprise") => PriceLevelType::Enterprise,
+            Some("store") => PriceLevelType::Store,
+            Some(other) => return Err(format!("price level type \"{}\" must be Enterprise or Store", other)),
+        };
+
+        let decimals = match rest.get(1).map(|s| s.trim()) {
+            None | Some("") => DEFAULT_DECIMALS,
+            Some(value) => match value.parse::<u8>() {
+                Ok(decimals) if decimals <= MAX_DECIMALS => decimals,
+                _ => return Err(format!("decimal places \"{}\" must be between 0 and {}", value, MAX_DECIMALS)),
+            },
+        };
+
+        Ok(PriceLevel {
+            id,
+            name,
+            level_type,
+            decimals,
+            created_at: Some(timestamp_now()),
+            updated_at: Some(timestamp_now()),
+            ..PriceLevel::default()
+        })
+    }
+
+    fn replacing(mut self, existing: &Self) -> Self {
+        self.price = existing.price;
+        self.created_at = existing.created_at.clone();
+        self
+    }
+}
+
+impl CsvEntity for ReportCategory {
+    fn id_range() -> RangeInclusive<EntityId> {
+        0..=25
+    }
+
+    fn from_fields(id: EntityId, name: String, _rest: &[&str]) -> Result<Self, String> {
+        Ok(ReportCategory {
+            id,
+            name,
+            created_at: Some(timestamp_now()),
+            updated_at: Some(timestamp_now()),
+        })
+    }
+
+    fn replacing(mut self, existing: &Self) -> Self {
+        self.created_at = existing.created_at.clone();
+        self
+    }
+}
+
+impl CsvEntity for RevenueCategory {
+    fn id_range() -> RangeInclusive<EntityId> {
+        0..=25
+    }
+
+    fn from_fields(id: EntityId, name: String, _rest: &[&str]) -> Result<Self, String> {
+        Ok(RevenueCategory {
+            id,
+            name,
+            created_at: Some(timestamp_now()),
+            updated_at: Some(timestamp_now()),
+        })
+    }
+
+    fn replacing(mut self, existing: &Self) -> Self {
+        self.created_at = existing.created_at.clone();
+        self
+    }
+}
+
+impl CsvEntity for PrinterLogical {
+    fn id_range() -> RangeInclusive<EntityId> {
+        0..=25
+    }
+
+    fn from_fields(id: EntityId, name: String, _rest: &[&str]) -> Result<Self, String> {
+        Ok(PrinterLogical {
+            id,
+            export_key: name.clone(),
+            name,
+            active: true,
+            created_at: Some(timestamp_now()),
+            updated_at: Some(timestamp_now()),
+        })
+    }
+
+    fn replacing(mut self, existing: &Self) -> Self {
+        self.created_at = existing.created_at.clone();
+        self.active = existing.active;
+        self.export_key = existing.export_key.clone();
+        self
+    }
+}
+
+/// Accepts "8.25", "8.25%" or " 8.25 % ", as a percentage between 0 and 100
+pub fn parse_tax_rate(value: &str) -> Result<Decimal, String> {
+    let trimmed = value.trim().trim_end_matches('%').trim();
+    if trimmed.is_empty() {
+        return Err("tax rate is empty".to_string());
+    }
+
+    match trimmed.parse::<Decimal>() {
+        Ok(rate) if (Decimal::ZERO..=Decimal::from(100)).contains(&rate) => Ok(rate / Decimal::from(100)),
+        Ok(_) => Err(format!("tax rate \"{}\" must be between 0 and 100%", value.trim())),
+        Err(_) => Err(format!("invalid tax rate format \"{}\"", value.trim())),
+    }
+}
+
+/// Rows read from an entity CSV, one variant per type
+#[derive(Debug, Clone)]
+pub enum EntityRows {
+    TaxGroups(Vec<TaxGroup>),
+    PriceLevels(Vec<PriceLevel>),
+    ReportCategories(Vec<ReportCategory>),
+    RevenueCategories(Vec<RevenueCategory>),
+    PrinterLogicals(Vec<PrinterLogical>),
+}
+
+impl EntityRows {
+    pub fn len(&self) -> usize {
+        match self {
+            Self::TaxGroups(rows) => rows.len(),
+            Self::PriceLevels(rows) => rows.len(),
+            Self::ReportCategories(rows) => rows.len(),
+            Self::RevenueCategories(rows) => rows.len(),
+            Self::PrinterLogicals(rows) => rows.len(),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct EntityImport {
+    pub kind: EntityCsvKind,
+    pub rows: EntityRows,
+    pub warnings: Vec<ImportWarning>,
+}
+
+/// Reads every row of the file, keeping the valid ones and a warning for each
+/// row that was skipped. A first row without a numeric ID is taken as a header.
+pub fn collect_entities<T: CsvEntity>(contents: &str) -> Result<(Vec<T>, Vec<ImportWarning>), Box<dyn std::error::Error>> {
+    let mut reader = ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(contents.trim_start_matches('\u{feff}').as_bytes());
+
+    let mut entities: Vec<T> = Vec::new();
+    let mut warnings = Vec::new();
+
+    for (index, result) in reader.records().enumerate() {
+        let record = result?;
+        let row = index + 1;
+        let fields: Vec<&str> = record.iter().map(str::trim).collect();
+
+        if fields.iter().all(|field| field.is_empty()) {
+            continue;
+        }
+
+        let raw_id = fields.first().copied().unwrap_or_default();
+        let id = match raw_id.parse::<EntityId>() {
+            Ok(id) => id,
+            Err(_) if row == 1 => continue,
+            Err(_) => {
+                warnings.push(ImportWarning {
+                    row,
+                    item_id: None,
+                    message: format!("skipped, ID \"{}\" is not a number", raw_id),
+                });
+                continue;
+            }
+        };
+
+        let skip = |message: String| ImportWarning { row, item_id: None, message: format!("skipped ID {}, {}", id, message) };
+
+        if !T::id_range().contains(&id) {
+            warnings.push(skip(format!(
+                "ID must be between {} and {}",
+                T::id_range().start(),
+                T::id_range().end()
+            )));
+            continue;
+        }
+
+        let name = fields.get(1).copied().unwrap_or_default();
+        if name.is_empty() {
+            warnings.push(skip("name is empty".to_string()));
+            continue;
+        }
+
+        match T::from_fields(id, name.to_string(), fields.get(2..).unwrap_or_default()) {
+            Ok(entity) => {
+                if let Some(index) = entities.iter().position(|e| e.id() == id) {
+                    warnings.push(ImportWarning {
+                        row,
+                        item_id: None,
+                        message: format!("duplicate ID {}, replaced the earlier row", id),
+                    });
+                    entities.remove(index);
+                }
+                entities.push(entity);
+            }
+            Err(e) => warnings.push(skip(e)),
+        }
+    }
+
+    if entities.is_empty() {
+        return Err("No valid rows found in the file.".into());
+    }
+
+    println!("Collected {} entities with {} warnings", entities.len(), warnings.len());
+
+    Ok((entities, warnings))
+}
+
+pub fn read_entity_csv(kind: EntityCsvKind, path: &Path) -> Result<EntityImport, Box<dyn std::error::Error>> {
+    println!("Reading {} from: {:?}", kind, path);
+    let contents = std::fs::read_to_string(path)?;
+
+    let (rows, warnings) = match kind {
+        EntityCsvKind::TaxGroups => {
+            let (rows, warnings) = collect_entities(&contents)?;
+            (EntityRows::TaxGroups(rows), warnings)
+        }
+        EntityCsvKind::PriceLevels => {
+            let (rows, warnings) = collect_entities(&contents)?;
+            (EntityRows::PriceLevels(rows), warnings)
+        }
+        EntityCsvKind::ReportCategories => {
+            let (rows, warnings) = collect_entities(&contents)?;
+            (EntityRows::ReportCategories(rows), warnings)
+        }
+        EntityCsvKind::RevenueCategories => {
+            let (rows, warnings) = collect_entities(&contents)?;
+            (EntityRows::RevenueCategories(rows), warnings)
+        }
+        EntityCsvKind::PrinterLogicals => {
+            let (rows, warnings) = collect_entities(&contents)?;
+            (EntityRows::PrinterLogicals(rows), warnings)
+        }
+    };
+
+    Ok(EntityImport { kind, rows, warnings })
+}
+
+/// Picks a CSV and reads it as the given entity type
+pub async fn open_entity_csv(kind: EntityCsvKind) -> Result<EntityImport, String> {
+    let handle = rfd::AsyncFileDialog::new()
+        .set_title(format!("Import {} from CSV", kind))
+        .add_filter("CSV Files", &["csv", "txt"])
+        .pick_file()
+        .await
+        .ok_or(DIALOG_CLOSED.to_string())?;
+
+    read_entity_csv(kind, handle.path()).map_err(|e| e.to_string())
+}
+
+/// What merging an entity CSV did, shown once the import is finished
+#[derive(Debug, Clone, Default)]
+pub struct EntityImportReport {
+    pub created: usize,
+    pub updated: usize,
+    pub reassigned: Vec<(EntityId, EntityId)>,
+    pub skipped: Vec<(EntityId, String)>,
+}
+
+impl EntityImportReport {
+    pub fn summary(&self, kind: EntityCsvKind) -> String {
+        let mut lines = vec![format!(
+            "Imported {}: {} created, {} updated.",
+            kind, self.created, self.updated
+        )];
+
+        if !self.reassigned.is_empty() {
+            lines.push(format!(
+                "{} were given new IDs because their ID was already in use: {}",
+                self.reassigned.len(),
+                self.reassigned
+                    .iter()
+                    .map(|(old, new)| format!("{} -> {}", old, new))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+
+        if !self.skipped.is_empty() {
+            lines.push(format!(
+                "{} were skipped because no free ID was left: {}",
+                self.skipped.len(),
+                self.skipped
+                    .iter()
+                    .map(|(id, name)| format!("{} (#{})", name, id))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+
+        lines.join("\n")
+    }
+}
+
+/// Merges imported entities into an existing map.
+///
+/// A row whose ID belongs to an entity with the same name (case-insensitive)
+/// updates it. A row whose ID belongs to a differently named entity is moved
+/// to the first free ID in range, or skipped when the range is full.
+pub fn merge_entities<T: CsvEntity>(
+    incoming: Vec<T>,
+    existing: &mut BTreeMap<EntityId, T>,
+) -> EntityImportReport {
+    let mut report = EntityImportReport::default();
+
+    for entity in incoming {
+        let id = entity.id();
+
+        match existing.get(&id) {
+            None => {
+                existing.insert(id, entity);
+                report.created += 1;
+            }
+            Some(current) if current.name().trim().eq_ignore_ascii_case(entity.name().trim()) => {
+                let replacement = entity.replacing(current);
+                existing.insert(id, replacement);
+                report.updated += 1;
+            }
+            Some(_) => match T::id_range().find(|id| !existing.contains_key(id)) {
+                Some(new_id) => {
+                    println!("{} ID {} already in use, reassigning to {}", entity.name(), id, new_id);
+                    existing.insert(new_id, entity.with_id(new_id));
+                    report.reassigned.push((id, new_id));
+                    report.created += 1;
+                }
+                None => {
+                    println!("No free ID left for {}", entity.name());
+                    report.skipped.push((id, entity.name().to_string()));
+                }
+            },
+        }
+    }
+
+    println!("Entity import finished: {:?}", report);
+    report
+}
@@ -1,13 +1,22 @@
-use crate::data_types::{EntityId, ValidationError};
+use crate::data_types::{EntityId, DRAFT_ID, ValidationError};
 use crate::Action;
 use crate::entity_component::{self, Entity, EditState as BaseEditState};
 use crate::icon;
+use crate::text_style::TextStyle;
+use crate::data_types::format_money;
+use crate::settings::DIALOG_CLOSED;
+use crate::export_preview::{ExportKind, ExportPreview};
 use iced_modern_theme::Modern;
 use serde::{Serialize, Deserialize};
-use iced::{Element, Length};
-use iced::widget::{button, row, column, container, text, text_input, scrollable, tooltip};
+use iced::{Element, Length, Task};
+use iced::widget::{button, checkbox, row, column, container, text, text_input, scrollable, tooltip};
+use crate::items::Item;
+use crate::data_types::ItemPrice;
+use crate::price_levels::PriceLevel;
+use rust_decimal::Decimal;
 use std::ops::Range;
 use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone)]
 pub enum Message {
@@ -18,8 +27,18 @@ pub enum Message {
     UpdateName(EntityId, String),
     UpdateIdRangeStart(EntityId, String),
     UpdateIdRangeEnd(EntityId, String),
+    ToggleDefaultPriceLevel(EntityId, EntityId, bool),
     CreateNew,
     CancelEdit(EntityId),
+    SaveAnyway(EntityId),
+    ReassignAndSave(EntityId),
+    ToggleStatistics,
+    SortStatistics(StatsColumn),
+    ShowGroupItems(EntityId),
+    ExportStatistics,
+    StatisticsReady(Result<ExportPreview, String>),
+    StatisticsExported(Result<PathBuf, String>),
+    ListScrolled(scrollable::Viewport),
 }
 
 #[derive(Debug, Clone)]
@@ -31,8 +50,25 @@ pub enum Operation {
     UpdateName(EntityId, String),
     UpdateIdRangeStart(EntityId, String),
     UpdateIdRangeEnd(EntityId, String),
+    ToggleDefaultPriceLevel(EntityId, EntityId, bool),
     CreateNew,
     CancelEdit(EntityId),
+    SaveAnyway(EntityId),
+    ReassignAndSave(EntityId),
+    ToggleStatistics,
+    SortStatistics(StatsColumn),
+    ShowGroupItems(EntityId),
+    ExportStatistics,
+    PreviewExport(ExportPreview),
+    UpdateStatisticsMessage(String),
+    ListScrolled(scrollable::Viewport),
+}
+
+/// What to do with items left outside a group's range after it is shrunk
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutOfRangeResolution {
+    SaveAnyway,
+    Reassign,
 }
 
 #[derive(Default, Debug, Clone)]
@@ -42,7 +78,13 @@ pub struct ItemGroupEditState {
     pub original_id_range_start: String,
     pub id_range_end: String,
     pub original_id_range_end: String,
+    pub default_price_levels: Vec<EntityId>,
+    pub original_default_price_levels: Vec<EntityId>,
     pub range_validation_error: Option<String>,
+    // (old id, new id) for items that would fall outside the edited range.
+    // New id is None when the range has no free ids left.
+    pub reassign_preview: Vec<(EntityId, Option<EntityId>)>,
+    pub out_of_range_resolution: Option<OutOfRangeResolution>,
 }
 
 impl ItemGroupEditState {
@@ -53,7 +95,11 @@ impl ItemGroupEditState {
             original_id_range_start: item_group.id_range.start.clone().to_string(),
             id_range_end: item_group.id_range.end.to_string(),
             original_id_range_end: item_group.id_range.end.clone().to_string(),
+            default_price_levels: item_group.default_price_levels.clone(),
+            original_default_price_levels: item_group.default_price_levels.clone(),
             range_validation_error: None,
+            reassign_preview: Vec::new(),
+            out_of_range_resolution: None,
         }
     }
 
@@ -61,7 +107,10 @@ impl ItemGroupEditState {
         self.base.reset();
         self.id_range_start = self.original_id_range_start.clone();
         self.id_range_end = self.original_id_range_end.clone();
+        self.default_price_levels = self.original_default_price_levels.clone();
         self.range_validation_error = None;
+        self.reassign_preview.clear();
+        self.out_of_range_resolution = None;
     }
  
     pub fn validate(&self) -> Result<(), ValidationError> {
@@ -91,6 +140,12 @@ pub struct ItemGroup {
     pub id: EntityId,
     pub name: String,
     pub id_range: Range<EntityId>,
+    #[serde(default)]
+    pub default_price_levels: Vec<EntityId>, // Given to new items in the group, prices left to fill in
+    #[serde(default)]
+    pub created_at: Option<String>,
+    #[serde(default)]
+    pub updated_at: Option<String>,
 }
 
 impl std::fmt::Display for ItemGroup {
@@ -102,9 +157,12 @@ impl std::fmt::Display for ItemGroup {
 impl Default for ItemGroup {
     fn default() -> Self {
         Self {
-            id: -1,
+            id: DRAFT_ID,
             name: String::new(),
-            id_range: Range { start: 1, end: 1000 }
+            id_range: Range { start: 1, end: 1000 },
+            default_price_levels: Vec::new(),
+            created_at: None,
+            updated_at: None,
         }
     }
 }
@@ -133,6 +191,14 @@ impl Entity for ItemGroup {
     fn default_new() -> Self {
         Self::default()
     }
+
+    fn created_at(&self) -> Option<&str> {
+        self.created_at.as_deref()
+    }
+
+    fn updated_at(&self) -> Option<&str> {
+        self.updated_at.as_deref()
+    }
 }
 
 impl ItemGroup {
@@ -140,12 +206,49 @@ impl ItemGroup {
         Self::default()
     }
 
-    pub fn validate(&self, other_groups: &[&ItemGroup]) -> Result<(), ValidationError> {
+    /// Adds the group's default price levels the item doesn't have yet, at a zero
+    /// price. Returns the ids that were added.
+    pub fn apply_default_price_levels(&self, item: &mut Item) -> Vec<EntityId> {
+        let mut added = Vec::new();
+
+        for level_id in &self.default_price_levels {
+            let assigned = item.item_prices.iter().flatten().any(|price| price.price_level_id == *level_id)
+                || item.price_levels.iter().flatten().any(|id| id == level_id);
+            if assigned {
+                continue;
+            }
+
+            item.price_levels.get_or_insert_with(Vec::new).push(*level_id);
+            item.item_prices.get_or_insert_with(Vec::new).push(ItemPrice {
+                price_level_id: *level_id,
+                price: Decimal::ZERO,
+            });
+            added.push(*level_id);
+        }
+
+        added
+    }
+
+    /// `min_item_id` is the lowest item id allowed in settings, the range can't start below it
+    pub fn validate(&self, other_groups: &[&ItemGroup], min_item_id: EntityId) -> Result<(), ValidationError> {
         if !(1..=999).contains(&self.id) {
             return Err(ValidationError::InvalidId(
                 "Item group ID must be between 1 and 999".to_string()
             ));
         }
+
+        // Drafts use a negative id, a range reaching below zero would take them in
+        if self.id_range.start < 0 {
+            return Err(ValidationError::InvalidValue(
+                "Range start cannot be negative".to_string()
+            ));
+        }
+
+        if self.id_range.start < min_item_id {
+            return Err(ValidationError::InvalidValue(
+                format!("Range start cannot be below the lowest item ID allowed in settings ({})", min_item_id)
+            ));
+        }
  
         // Check for duplicate IDs
         for other in other_groups {
@@ -179,12 +282,50 @@ impl ItemGroup {
     }
 }
 
+/// Drops a deleted price level from every group's defaults
+pub fn remove_default_price_level(item_groups: &mut BTreeMap<EntityId, ItemGroup>, level_id: EntityId) {
+    for group in item_groups.values_mut() {
+        group.default_price_levels.retain(|id| *id != level_id);
+    }
+}
+
+/// Ids of the items assigned to `group_id` that fall outside `range` (end inclusive,
+/// matching item validation)
+pub fn items_outside_range(
+    group_id: EntityId,
+    range: &Range<EntityId>,
+    items: &BTreeMap<EntityId, Item>,
+) -> Vec<EntityId> {
+    items.values()
+        .filter(|item| item.item_group == Some(group_id))
+        .filter(|item| item.id < range.start || item.id > range.end)
+        .map(|item| item.id)
+        .collect()
+}
+
+/// Pairs each id with the next free id in `range`, or None once the range is full
+pub fn plan_reassignment(
+    ids: &[EntityId],
+    range: &Range<EntityId>,
+    items: &BTreeMap<EntityId, Item>,
+) -> Vec<(EntityId, Option<EntityId>)> {
+    let mut free_ids = (range.start..=range.end).filter(|id| !items.contains_key(id));
+
+    ids.iter()
+        .map(|old_id| (*old_id, free_ids.next()))
+        .collect()
+}
+
 fn ranges_overlap<T: Ord>(range1: &std::ops::RangeInclusive<T>, range2: &std::ops::RangeInclusive<T>) -> bool {
     range1.start() <= range2.end() && range2.start() <= range1.end()
 }
 
+// Title of the screen, and the id of its scrollable list
+pub const LIST_ID: &str = "Item Groups";
+
 pub fn update(
     message: Message,
+    groups: &BTreeMap<EntityId, ItemGroup>,
 ) -> Action<Operation, Message> {
     match message {
         Message::RequestDelete(id) => {
@@ -198,9 +339,17 @@ pub fn update(
         },
         Message::CreateNew => {
             Action::operation(Operation::CreateNew)
+                .with_task(entity_component::reveal_new_row(LIST_ID, groups))
         },
         Message::Save(id, edit_state) => {
-            Action::operation(Operation::Save(id, edit_state))
+            let focus = if edit_state.id_range_start.parse::<i32>().is_err() {
+                text_input::focus(entity_component::field_input_id(LIST_ID, id, "range start"))
+            } else if edit_state.id_range_end.parse::<i32>().is_err() {
+                text_input::focus(entity_component::field_input_id(LIST_ID, id, "range end"))
+            } else {
+                entity_component::focus_duplicate_name(LIST_ID, groups, id, &edit_state.base.name)
+            };
+            Action::operation(Operation::Save(id, edit_state)).with_task(focus)
         }
         Message::UpdateName(id, new_name) => {
             Action::operation(Operation::UpdateName(id, new_name))
@@ -211,20 +360,66 @@ pub fn update(
         Message::UpdateIdRangeEnd(id, new_end) => {
             Action::operation(Operation::UpdateIdRangeEnd(id, new_end))
         }
+        Message::ToggleDefaultPriceLevel(id, level_id, checked) => {
+            Action::operation(Operation::ToggleDefaultPriceLevel(id, level_id, checked))
+        }
         Message::CancelEdit(id) => {
             Action::operation(Operation::CancelEdit(id))
         }
+        Message::SaveAnyway(id) => {
+            Action::operation(Operation::SaveAnyway(id))
+        }
+        Message::ReassignAndSave(id) => {
+            Action::operation(Operation::ReassignAndSave(id))
+        }
+        Message::ToggleStatistics => {
+            Action::operation(Operation::ToggleStatistics)
+        }
+        Message::SortStatistics(column) => {
+            Action::operation(Operation::SortStatistics(column))
+        }
+        Message::ShowGroupItems(id) => {
+            Action::operation(Operation::ShowGroupItems(id))
+        }
+        Message::ExportStatistics => {
+            Action::operation(Operation::ExportStatistics)
+        }
+        Message::StatisticsReady(result) => {
+            match result {
+                Ok(preview) => Action::operation(Operation::PreviewExport(preview)),
+                Err(e) if e == DIALOG_CLOSED => Action::none(),
+                Err(e) => Action::operation(Operation::UpdateStatisticsMessage(format!("Group statistics export failed: {}", e))),
+            }
+        }
+        Message::StatisticsExported(result) => {
+            let message = match result {
+                Ok(path) => format!("Group statistics exported to {}", path.to_string_lossy()),
+                Err(e) if e == DIALOG_CLOSED => return Action::none(),
+                Err(e) => format!("Group statistics export failed: {}", e),
+            };
+            Action::operation(Operation::UpdateStatisticsMessage(message))
+        }
+        Message::ListScrolled(viewport) => {
+            Action::operation(Operation::ListScrolled(viewport))
+        }
     }
 }
 
 pub fn view<'a>(
     all_groups: &'a BTreeMap<EntityId, ItemGroup>,
     edit_states: &'a Vec<ItemGroupEditState>,
+    items: &'a BTreeMap<EntityId, Item>,
+    price_levels: &'a BTreeMap<EntityId, PriceLevel>,
+    show_statistics: bool,
+    statistics_sort: StatsSort,
+    statistics_message: &'a str,
+    text_style: TextStyle,
 ) -> Element<'a, Message> {
     let title_row = entity_component::render_title_row(
-        "Item Groups", 
+        LIST_ID,
         Message::CreateNew,
-        805.0 // view width
+        805.0, // view width
+        text_style,
     );
 
     // Custom header row for columns including range fields
@@ -244,29 +439,325 @@ pub fn view<'a>(
                 .values()
                 .map(|group| 
                     row![
-                        render_item_group_row(group, edit_states)
+                        render_item_group_row(group, edit_states, price_levels, text_style)
                     ]
                     .padding(5)
                     .into()
                 )
                 .collect::<Vec<_>>()
         )
-    ).height(Length::Fill);
+    )
+    .id(scrollable::Id::new(LIST_ID))
+    .on_scroll(Message::ListScrolled)
+    .height(Length::Fill);
 
     // Combine all elements
     let all_content = column![title_row, header_row, groups_list];
 
-    column![
-        container(all_content)
-            .height(Length::Shrink)
-            .style(Modern::card_container())
-    ]
+    let groups_card = container(all_content)
+        .height(Length::Shrink)
+        .style(Modern::card_container());
+
+    let statistics_toggle = button(if show_statistics { "Hide Group Statistics" } else { "Group Statistics" })
+        .on_press(Message::ToggleStatistics)
+        .style(Modern::system_button());
+
+    if show_statistics {
+        let mut stats = group_statistics(all_groups, items);
+        statistics_sort.apply(&mut stats);
+
+        column![
+            statistics_toggle,
+            row![
+                groups_card,
+                statistics_view(stats, statistics_sort, statistics_message, text_style),
+            ].spacing(20),
+        ]
+        .spacing(10)
+        .into()
+    } else {
+        column![
+            statistics_toggle,
+            groups_card,
+        ]
+        .spacing(10)
+        .into()
+    }
+}
+
+/// Menu engineering numbers for one item group. Prices are the items' default
+/// prices, items without one (or at zero) count as missing and are left out of min/avg/max.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GroupStats {
+    pub group_id: Option<EntityId>, // None for items without a group
+    pub name: String,
+    pub item_count: usize,
+    pub min_price: Option<Decimal>,
+    pub avg_price: Option<Decimal>,
+    pub max_price: Option<Decimal>,
+    pub missing_prices: usize,
+    pub inactive: usize,
+}
+
+/// One row per item group, empty groups included, plus a "No Item Group" row
+/// when some items don't belong to any group
+pub fn group_statistics(
+    item_groups: &BTreeMap<EntityId, ItemGroup>,
+    items: &BTreeMap<EntityId, Item>,
+) -> Vec<GroupStats> {
+    let stats_for = |group_id: Option<EntityId>, name: String, group_items: Vec<&Item>| -> GroupStats {
+        let prices: Vec<Decimal> = group_items
+            .iter()
+            .filter_map(|item| item.default_price)
+            .filter(|price| !price.is_zero())
+            .collect();
+
+        // Decimal all the way, so the average is exact to the cent before rounding.
+        // Prices too large to add up leave the average blank.
+        let avg_price = (!prices.is_empty())
+            .then(|| prices.iter().try_fold(Decimal::ZERO, |total, price| total.checked_add(*price)))
+            .flatten()
+            .and_then(|total| total.checked_div(Decimal::from(prices.len())))
+            .map(|avg| avg.round_dp(2));
+
+        GroupStats {
+            group_id,
+            name,
+            item_count: group_items.len(),
+            min_price: prices.iter().min().copied(),
+            avg_price,
+            max_price: prices.iter().max().copied(),
+            missing_prices: group_items.len() - prices.len(),
+            inactive: group_items.iter().filter(|item| item.not_active).count(),
+        }
+    };
+
+    let mut stats: Vec<GroupStats> = item_groups
+        .values()
+        .map(|group| {
+            let group_items = items.values().filter(|item| item.item_group == Some(group.id)).collect();
+            stats_for(Some(group.id), group.name.clone(), group_items)
+        })
+        .collect();
+
+    let ungrouped: Vec<&Item> = items
+        .values()
+        .filter(|item| item.item_group.map_or(true, |id| !item_groups.contains_key(&id)))
+        .collect();
+    if !ungrouped.is_empty() {
+        stats.push(stats_for(None, "No Item Group".to_string(), ungrouped));
+    }
+
+    stats
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StatsColumn {
+    #[default]
+    Name,
+    Items,
+    MinPrice,
+    AvgPrice,
+    MaxPrice,
+    MissingPrices,
+    Inactive,
+}
+
+impl StatsColumn {
+    const ALL: [StatsColumn; 7] = [
+        StatsColumn::Name,
+        StatsColumn::Items,
+        StatsColumn::MinPrice,
+        StatsColumn::AvgPrice,
+        StatsColumn::MaxPrice,
+        StatsColumn::MissingPrices,
+        StatsColumn::Inactive,
+    ];
+
+    fn width(&self) -> f32 {
+        match self {
+            StatsColumn::Name => 150.0,
+            _ => 70.0,
+        }
+    }
+}
+
+impl std::fmt::Display for StatsColumn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StatsColumn::Name => write!(f, "Group"),
+            StatsColumn::Items => write!(f, "Items"),
+            StatsColumn::MinPrice => write!(f, "Min"),
+            StatsColumn::AvgPrice => write!(f, "Avg"),
+            StatsColumn::MaxPrice => write!(f, "Max"),
+            StatsColumn::MissingPrices => write!(f, "No Price"),
+            StatsColumn::Inactive => write!(f, "Inactive"),
+        }
+    }
+}
+
+/// Column the statistics table is sorted by, clicking the same header again reverses it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct StatsSort {
+    pub column: StatsColumn,
+    pub descending: bool,
+}
+
+impl StatsSort {
+    pub fn toggle(self, column: StatsColumn) -> Self {
+        if self.column == column {
+            Self { column, descending: !self.descending }
+        } else {
+            // Numbers are most useful largest first, names A to Z
+            Self { column, descending: column != StatsColumn::Name }
+        }
+    }
+
+    /// Groups without any priced items always sort after the ones with prices
+    pub fn apply(&self, stats: &mut [GroupStats]) {
+        let price = |row: &GroupStats| match self.column {
+            StatsColumn::MinPrice => row.min_price,
+            StatsColumn::AvgPrice => row.avg_price,
+            _ => row.max_price,
+        };
+
+        stats.sort_by(|a, b| {
+            let ordering = match self.column {
+                StatsColumn::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+                StatsColumn::Items => a.item_count.cmp(&b.item_count),
+                StatsColumn::MissingPrices => a.missing_prices.cmp(&b.missing_prices),
+                StatsColumn::Inactive => a.inactive.cmp(&b.inactive),
+                StatsColumn::MinPrice | StatsColumn::AvgPrice | StatsColumn::MaxPrice => {
+                    match (price(a), price(b)) {
+                        (Some(a), Some(b)) => a.cmp(&b),
+                        (Some(_), None) => return std::cmp::Ordering::Less,
+                        (None, Some(_)) => return std::cmp::Ordering::Greater,
+                        (None, None) => std::cmp::Ordering::Equal,
+                    }
+                }
+            };
+
+            if self.descending { ordering.reverse() } else { ordering }
+        });
+    }
+}
+
+pub fn statistics_to_csv_string(stats: &[GroupStats]) -> String {
+    let price = |price: Option<Decimal>| price.map(|price| price.to_string()).unwrap_or_default();
+    let mut content = String::from("Item Group ID,Item Group,Items,Min Price,Avg Price,Max Price,Missing Prices,Inactive\n");
+
+    for row in stats {
+        content.push_str(&format!(
+            "{},\"{}\",{},{},{},{},{},{}\n",
+            row.group_id.map(|id| id.to_string()).unwrap_or_default(),
+            row.name.replace('"', "\"\""),
+            row.item_count,
+            price(row.min_price),
+            price(row.avg_price),
+            price(row.max_price),
+            row.missing_prices,
+            row.inactive,
+        ));
+    }
+
+    content
+}
+
+fn statistics_view<'a>(
+    stats: Vec<GroupStats>,
+    sort: StatsSort,
+    statistics_message: &'a str,
+    text_style: TextStyle,
+) -> Element<'a, Message> {
+    let header_row = row(
+        StatsColumn::ALL.iter().map(|column| {
+            let label = if sort.column == *column {
+                format!("{} {}", column, if sort.descending { "▼" } else { "▲" })
+            } else {
+                column.to_string()
+            };
+
+            button(text(label).size(text_style.caption()))
+                .on_press(Message::SortStatistics(*column))
+                .style(Modern::system_button())
+                .width(Length::Fixed(column.width()))
+                .into()
+        })
+        .collect::<Vec<_>>()
+    )
+    .padding(5);
+
+    let price = |price: Option<Decimal>| price.map(|price| format_money(price, 2)).unwrap_or_else(|| "-".to_string());
+
+    let rows = stats.into_iter().map(|group| {
+        let cells = row![
+            text(group.name).width(Length::Fixed(StatsColumn::Name.width())),
+            text(group.item_count.to_string()).width(Length::Fixed(StatsColumn::Items.width())),
+            text(price(group.min_price)).font(text_style.data_font()).width(Length::Fixed(StatsColumn::MinPrice.width())),
+            text(price(group.avg_price)).font(text_style.data_font()).width(Length::Fixed(StatsColumn::AvgPrice.width())),
+            text(price(group.max_price)).font(text_style.data_font()).width(Length::Fixed(StatsColumn::MaxPrice.width())),
+            text(group.missing_prices.to_string())
+                .style(if group.missing_prices > 0 { Modern::red_text() } else { Modern::primary_text() })
+                .width(Length::Fixed(StatsColumn::MissingPrices.width())),
+            text(group.inactive.to_string()).width(Length::Fixed(StatsColumn::Inactive.width())),
+        ]
+        .align_y(iced::Alignment::Center);
+
+        // Only real groups can be filtered to on the items screen
+        button(cells)
+            .on_press_maybe(group.group_id.map(Message::ShowGroupItems))
+            .style(Modern::system_button())
+            .into()
+    })
+    .collect::<Vec<_>>();
+
+    container(
+        column![
+            row![
+                text("Group Statistics").size(text_style.heading()).style(Modern::primary_text()),
+                iced::widget::horizontal_space(),
+                button("Export CSV")
+                    .on_press(Message::ExportStatistics)
+                    .style(Modern::system_button()),
+            ],
+            header_row,
+            scrollable(column(rows).spacing(5))
+                .id(scrollable::Id::new("Group Statistics"))
+                .height(Length::Fill),
+            text(statistics_message).size(text_style.caption()).style(Modern::secondary_text()),
+        ]
+        .spacing(10)
+        .padding(15)
+        .width(Length::Fixed(620.0))
+    )
+    .style(Modern::card_container())
     .into()
 }
 
+/// Asks where to save the statistics, they're written once the preview is confirmed
+pub async fn preview_statistics(stats: Vec<GroupStats>) -> Result<ExportPreview, String> {
+    let path = rfd::AsyncFileDialog::new()
+        .set_title("Save Group Statistics")
+        .add_filter("CSV Files", &["csv"])
+        .save_file()
+        .await
+        .as_ref()
+        .map(rfd::FileHandle::path)
+        .map(Path::to_owned)
+        .ok_or(DIALOG_CLOSED.to_string())?;
+
+    Ok(ExportPreview::new(ExportKind::GroupStatistics, vec![(path, statistics_to_csv_string(&stats))]))
+}
+
+pub fn export_statistics_task(stats: Vec<GroupStats>) -> Task<Message> {
+    Task::perform(preview_statistics(stats), Message::StatisticsReady)
+}
+
 fn render_item_group_row<'a>(
     item_group: &'a ItemGroup,
-    edit_states: &'a Vec<ItemGroupEditState>
+    edit_states: &'a Vec<ItemGroupEditState>,
+    price_levels: &'a BTreeMap<EntityId, PriceLevel>,
+    text_style: TextStyle,
 ) -> Element<'a, Message> {
     // Find edit state for this item_group if it exists
     let edit_state = edit_states.iter()
@@ -310,13 +801,14 @@ fn render_item_group_row<'a>(
                 tooltip::Position::Top,
             ).into()
         } else {
-            input.into()
+            entity_component::with_timestamps(input, item_group, text_style)
         }
     };
 
     // Name input with validation
     let name_input: Element<'_, Message> = {
         let input = text_input("Item Group Name", &display_name)
+            .id(entity_component::name_input_id(LIST_ID, item_group.id))
             .on_input_maybe(
                 if editing {
                     Some(|name| Message::UpdateName(item_group.id, name))
@@ -341,6 +833,7 @@ fn render_item_group_row<'a>(
     // Range start input with validation
     let range_start_input: Element<'_, Message> = {
         let input = text_input("Range Start", &range_start)
+            .id(entity_component::field_input_id(LIST_ID, item_group.id, "range start"))
             .on_input_maybe(
                 if editing {
                     Some(|start| Message::UpdateIdRangeStart(item_group.id, start))
@@ -365,6 +858,7 @@ fn render_item_group_row<'a>(
     // Range end input with validation
     let range_end_input: Element<'_, Message> = {
         let input = text_input("Range End", &range_end)
+            .id(entity_component::field_input_id(LIST_ID, item_group.id, "range end"))
             .on_input_maybe(
                 if editing {
                     Some(|end| Message::UpdateIdRangeEnd(item_group.id, end))
@@ -414,7 +908,7 @@ fn render_item_group_row<'a>(
     ].width(150);
 
 
-    row![
+    let group_row = row![
         iced::widget::horizontal_space().width(3),
         id_input,
         name_input,
@@ -424,6 +918,210 @@ fn render_item_group_row<'a>(
         action_row,
     ]
     .align_y(iced::Alignment::Center)
-    .width(Length::Fixed(795.0))
-    .into()
-}
\ No newline at end of file
+    .width(Length::Fixed(795.0));
+
+    // Price levels new items in this group start with, checkboxes while editing
+    let default_levels: Option<Element<'a, Message>> = match edit_state {
+        Some(state) if !price_levels.is_empty() => Some(
+            row![
+                text("Default price levels:").size(text_style.caption()).style(Modern::secondary_text()),
+                row(
+                    price_levels.values().map(|level| {
+                        let group_id = item_group.id;
+                        let level_id = level.id;
+                        checkbox(level.name.clone(), state.default_price_levels.contains(&level_id))
+                            .on_toggle(move |checked| Message::ToggleDefaultPriceLevel(group_id, level_id, checked))
+                            .style(Modern::checkbox())
+                            .into()
+                    })
+                    .collect::<Vec<_>>()
+                )
+                .spacing(15)
+                .wrap(),
+            ]
+            .spacing(10)
+            .padding([0, 80])
+            .into()
+        ),
+        None if !item_group.default_price_levels.is_empty() => {
+            let names = item_group.default_price_levels
+                .iter()
+                .map(|id| price_levels.get(id).map_or_else(|| format!("#{}", id), |level| level.name.clone()))
+                .collect::<Vec<_>>()
+                .join(", ");
+            Some(
+                container(
+                    text(format!("Default price levels: {}", names))
+                        .size(text_style.caption())
+                        .style(Modern::secondary_text())
+                )
+                .padding([0, 80])
+                .into()
+            )
+        }
+        _ => None,
+    };
+    let group_row = match default_levels {
+        Some(levels) => column![group_row, levels].spacing(5).into(),
+        None => Element::from(group_row),
+    };
+
+    // Items would be left outside the new range, show what re-assigning would do
+    match edit_state.filter(|state| !state.reassign_preview.is_empty()) {
+        Some(state) => {
+            let preview = state.reassign_preview
+                .iter()
+                .map(|(old_id, new_id)| match new_id {
+                    Some(new_id) => format!("{} -> {}", old_id, new_id),
+                    None => format!("{} -> no free ID", old_id),
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            column![
+                group_row,
+                container(
+                    column![
+                        text(format!("Re-assign preview: {}", preview)).size(text_style.caption()).style(Modern::secondary_text()),
+                        row![
+                            button("Save anyway")
+                                .on_press(Message::SaveAnyway(item_group.id))
+                                .style(Modern::warning_button()),
+                            button("Re-assign those items into the new range")
+                                .on_press(Message::ReassignAndSave(item_group.id))
+                                .style(Modern::primary_button()),
+                        ].spacing(10),
+                    ].spacing(5)
+                )
+                .padding(5)
+                .width(Length::Fixed(795.0)),
+            ]
+            .into()
+        }
+        None => group_row.into(),
+    }
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn dec(value: &str) -> Decimal {
+        Decimal::from_str(value).unwrap()
+    }
+
+    fn group(id: EntityId, name: &str) -> (EntityId, ItemGroup) {
+        (id, ItemGroup { id, name: name.to_string(), ..ItemGroup::default() })
+    }
+
+    fn item(id: EntityId, group: Option<EntityId>, price: Option<&str>) -> (EntityId, Item) {
+        (id, Item {
+            id,
+            name: format!("Item {}", id),
+            item_group: group,
+            default_price: price.map(dec),
+            ..Item::default()
+        })
+    }
+
+    #[test]
+    fn group_without_items_gets_an_empty_row() {
+        let groups = BTreeMap::from([group(1, "Drinks")]);
+        let stats = group_statistics(&groups, &BTreeMap::new());
+
+        assert_eq!(stats, vec![GroupStats {
+            group_id: Some(1),
+            name: "Drinks".to_string(),
+            item_count: 0,
+            min_price: None,
+            avg_price: None,
+            max_price: None,
+            missing_prices: 0,
+            inactive: 0,
+        }]);
+    }
+
+    #[test]
+    fn ungrouped_and_orphaned_items_share_the_no_item_group_row() {
+        let groups = BTreeMap::from([group(1, "Drinks")]);
+        let items = BTreeMap::from([
+            item(10, Some(1), Some("2.00")),
+            item(11, None, Some("3.00")),
+            item(12, Some(99), None), // Its group was deleted
+        ]);
+        let stats = group_statistics(&groups, &items);
+
+        assert_eq!(stats.len(), 2);
+        let ungrouped = &stats[1];
+        assert_eq!(ungrouped.group_id, None);
+        assert_eq!(ungrouped.name, "No Item Group");
+        assert_eq!(ungrouped.item_count, 2);
+        assert_eq!(ungrouped.missing_prices, 1);
+        assert_eq!(ungrouped.avg_price, Some(dec("3.00")));
+    }
+
+    #[test]
+    fn no_item_group_row_is_left_out_when_every_item_has_a_group() {
+        let groups = BTreeMap::from([group(1, "Drinks")]);
+        let items = BTreeMap::from([item(10, Some(1), Some("2.00"))]);
+
+        assert!(group_statistics(&groups, &items).iter().all(|row| row.group_id.is_some()));
+    }
+
+    #[test]
+    fn average_is_rounded_to_the_cent() {
+        let groups = BTreeMap::from([group(1, "Drinks")]);
+        let items = BTreeMap::from([
+            item(10, Some(1), Some("10.00")),
+            item(11, Some(1), Some("10.01")),
+            item(12, Some(1), Some("10.01")),
+            item(13, Some(1), Some("0")), // Zero counts as missing
+        ]);
+        let stats = &group_statistics(&groups, &items)[0];
+
+        assert_eq!(stats.avg_price, Some(dec("10.01")));
+        assert_eq!(stats.min_price, Some(dec("10.00")));
+        assert_eq!(stats.max_price, Some(dec("10.01")));
+        assert_eq!(stats.missing_prices, 1);
+    }
+
+    #[test]
+    fn average_midpoint_rounds_to_even() {
+        let groups = BTreeMap::from([group(1, "Drinks")]);
+        let items = BTreeMap::from([
+            item(10, Some(1), Some("1.00")),
+            item(11, Some(1), Some("1.01")),
+        ]);
+
+        assert_eq!(group_statistics(&groups, &items)[0].avg_price, Some(dec("1.00")));
+    }
+
+    #[test]
+    fn prices_too_large_to_add_leave_the_average_blank() {
+        let groups = BTreeMap::from([group(1, "Drinks")]);
+        let mut items = BTreeMap::from([item(10, Some(1), None), item(11, Some(1), None)]);
+        for item in items.values_mut() {
+            item.default_price = Some(Decimal::MAX);
+        }
+        let stats = &group_statistics(&groups, &items)[0];
+
+        assert_eq!(stats.avg_price, None);
+        assert_eq!(stats.max_price, Some(Decimal::MAX));
+    }
+
+    #[test]
+    fn negative_range_start_is_rejected() {
+        let drinks = ItemGroup { id: 1, name: "Drinks".to_string(), id_range: -5..100, ..ItemGroup::default() };
+
+        // Even with no lower limit from settings, drafts' negative ids must stay out of every range
+        for min_item_id in [0, -10] {
+            assert!(matches!(
+                drinks.validate(&[], min_item_id),
+                Err(ValidationError::InvalidValue(message)) if message == "Range start cannot be negative"
+            ));
+        }
+
+        let drinks = ItemGroup { id_range: 0..100, ..drinks };
+        assert!(drinks.validate(&[], 0).is_ok());
+    }
+}
@@ -2,11 +2,27 @@ pub mod edit;
 pub mod view;
 pub mod import_items;
 pub mod export_items;
+pub mod export_layout;
 pub mod preview_changes;
-
-use std::collections::BTreeMap;
+pub mod json_interchange;
+pub mod printable_menu;
+pub mod store_fields;
+pub mod find_replace;
+pub mod name_case;
+pub mod abbreviate;
+pub mod item_patch;
+pub mod delta_export;
+pub mod price_compare;
+pub mod price_pivot;
+pub mod button_collisions;
+pub mod button_preview;
+pub mod copy_assignments;
+pub mod field_status;
+pub mod price_fill;
+
+use std::collections::{BTreeMap, BTreeSet};
 use crate::data_types::{
-    EntityId, ValidationError, ItemPrice, EntityResolver
+    EntityId, ValidationError, ItemPrice, EntityResolver, DRAFT_ID
 };
 use crate::Action;
 use iced_modern_theme::Modern;
@@ -24,7 +40,10 @@ use crate::{
     choice_groups::ChoiceGroup,
     printer_logicals::PrinterLogical,
     price_levels::PriceLevel,
+    entity_component::{self, EntityPicker},
+    search_index::SearchKind,
     icon,
+    text_style::TextStyle,
 };
 
 
@@ -40,6 +59,16 @@ pub enum Message {
     HideModal,
     ShowModal,
     LaunchMassItemEditWindow,
+    ClearPrinterFilter,
+    ClearGroupFilter,
+    ToggleGroupCollapsed(Option<EntityId>),
+    CreateInGroup(EntityId),
+    ToggleMissingDashboard,
+    FilterMissing(Classification),
+    ClearMissingFilter,
+    OpenFindReplace,
+    OpenNameCase(Vec<EntityId>),
+    ListScrolled(scrollable::Viewport),
 }
 
 #[derive(Debug, Clone)]
@@ -57,6 +86,29 @@ pub enum Operation {
     ShowModal,
     UpdatePrice(EntityId, EntityId, String),
     LaunchMassItemEditWindow,
+    ClearPrinterFilter,
+    ClearGroupFilter,
+    TogglePin(EntityId),
+    StartQuickEdit(QuickEditField),
+    UpdateQuickEdit(String),
+    CommitQuickEdit,
+    CancelQuickEdit,
+    UndoQuickEdit(EntityId),
+    FixPrimaryPrinter(EntityId),
+    ToggleGroupCollapsed(Option<EntityId>),
+    ToggleMissingDashboard,
+    FilterMissing(Classification),
+    ClearMissingFilter,
+    OpenFindReplace,
+    OpenNameCase(Vec<EntityId>),
+    SuggestButtonText(abbreviate::ButtonSlot),
+    ToggleEditSection(edit::EditSection),
+    SetAllEditSectionsCollapsed(bool),
+    ListScrolled(scrollable::Viewport),
+    OpenCopyAssignments,
+    SelectCopySource(EntityId),
+    ApplyGroupDefaults,
+    ItemGroupSelected,
 }
 
 #[derive(Debug, Clone)]
@@ -65,6 +117,147 @@ pub enum Mode {
     Edit,
 }
 
+/// Classifications that downstream exports expect every item to have
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Classification {
+    TaxGroup,
+    RevenueCategory,
+    ReportCategory,
+    ItemGroup,
+    Price,
+}
+
+impl Classification {
+    pub const ALL: [Classification; 5] = [
+        Classification::TaxGroup,
+        Classification::RevenueCategory,
+        Classification::ReportCategory,
+        Classification::ItemGroup,
+        Classification::Price,
+    ];
+
+    /// Whether the item lacks this classification
+    pub fn is_missing(&self, item: &Item) -> bool {
+        match self {
+            Classification::TaxGroup => item.tax_group.is_none(),
+            Classification::RevenueCategory => item.revenue_category.is_none(),
+            Classification::ReportCategory => item.report_category.is_none(),
+            Classification::ItemGroup => item.item_group.is_none(),
+            Classification::Price => item.default_price.is_none()
+                && item.item_prices.as_ref().map_or(true, |prices| prices.is_empty()),
+        }
+    }
+}
+
+impl std::fmt::Display for Classification {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Classification::TaxGroup => write!(f, "Tax Group"),
+            Classification::RevenueCategory => write!(f, "Revenue Category"),
+            Classification::ReportCategory => write!(f, "Report Category"),
+            Classification::ItemGroup => write!(f, "Item Group"),
+            Classification::Price => write!(f, "Price"),
+        }
+    }
+}
+
+/// Parses a whole number field that can't be negative, the error is shown inline on the edit form
+pub fn parse_non_negative(value: &str, field_name: &str) -> Result<i32, String> {
+    match value.trim().parse::<i32>() {
+        Ok(number) if number < 0 => Err(format!("{} can't be negative", field_name)),
+        Ok(number) => Ok(number),
+        Err(e) if *e.kind() == std::num::IntErrorKind::PosOverflow => Err(format!("{} is too large", field_name)),
+        Err(e) if *e.kind() == std::num::IntErrorKind::NegOverflow => Err(format!("{} can't be negative", field_name)),
+        Err(_) => Err(format!("Invalid {} format", field_name)),
+    }
+}
+
+/// Sequence for a choice group added after these. One past the highest, so a group
+/// added after a removal doesn't repeat a number still in use.
+pub fn next_choice_sequence(groups: &[(EntityId, i32)]) -> i32 {
+    groups.iter().map(|(_, sequence)| sequence.saturating_add(1)).max().unwrap_or(0)
+}
+
+/// Counts items missing each of the required classifications
+pub fn missing_classifications(
+    items: &BTreeMap<EntityId, Item>,
+    required: &[Classification],
+) -> Vec<(Classification, usize)> {
+    required
+        .iter()
+        .map(|classification| {
+            let count = items.values().filter(|item| classification.is_missing(item)).count();
+            (*classification, count)
+        })
+        .collect()
+}
+
+/// Which single field the quick edit popover is changing
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum QuickEditField {
+    Name,
+    Price(EntityId),
+}
+
+/// Lightweight edit of one field from the item view, without a full EditState
+#[derive(Debug, Clone)]
+pub struct QuickEdit {
+    pub item_id: EntityId,
+    pub field: QuickEditField,
+    pub value: String,
+    pub original: String, // The value when the edit started, to tell whether anything changed
+    pub error: Option<String>,
+}
+
+impl QuickEdit {
+    pub fn new(item_id: EntityId, field: QuickEditField, value: String) -> Self {
+        Self {
+            item_id,
+            field,
+            original: value.clone(),
+            value,
+            error: None,
+        }
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.value != self.original
+    }
+
+    /// Applies the edited value to a copy of the item, or returns why it can't be applied.
+    /// The result goes through the same Item::validate as the full edit form.
+    pub fn apply(&self, item: &Item, context: &ViewContext) -> Result<Item, String> {
+        let mut item = item.clone();
+
+        match self.field {
+            QuickEditField::Name => {
+                if self.value.trim().is_empty() {
+                    return Err("Item name cannot be empty".to_string());
+                }
+                item.name = self.value.clone();
+            }
+            QuickEditField::Price(price_level_id) => {
+                let price = self.value.trim().parse::<Decimal>()
+                    .map_err(|_| "Invalid price format".to_string())?;
+
+                if price.is_sign_negative() {
+                    return Err("Price cannot be negative".to_string());
+                }
+
+                match item.item_prices.as_mut().and_then(|prices| {
+                    prices.iter_mut().find(|p| p.price_level_id == price_level_id)
+                }) {
+                    Some(item_price) => item_price.price = price,
+                    None => return Err("Item has no price for this price level".to_string()),
+                }
+            }
+        }
+
+        item.validate(context, Some(self.item_id)).map_err(|e| e.to_string())?;
+        Ok(item)
+    }
+}
+
 #[derive(Default, Debug, Clone)]
 pub struct EditState {
     // Basic info
@@ -123,6 +316,7 @@ pub struct EditState {
     pub covers: String,
     pub image_id: String,
     pub language_iso_code: String,
+    pub language_other: bool, // Typing a code that isn't in the ISO-639-1 list
 
     // Related Items
     pub choice_groups: Vec<(EntityId, i32)>,
@@ -132,8 +326,74 @@ pub struct EditState {
     pub printer_logicals_combo: combo_box::State<PrinterLogical>,
     pub printer_logicals_selection: Option<PrinterLogical>,
 
+    // Classification pickers, filled in by the caller since they need every entity list
+    pub pickers: ClassificationPickers,
+
+    // Abbreviated button text offered for one of the button fields, filled in by the caller
+    pub button_suggestion: Option<(abbreviate::ButtonSlot, String)>,
+
+    // Open "Copy assignments from…" panel, its item list is filled in by the caller
+    pub assignment_copy: Option<copy_assignments::AssignmentCopy>,
+
+    // "Apply to all price levels" under the price grid
+    pub price_fill: price_fill::PriceFill,
+
     // Validation
     pub validation_error: Option<String>,
+    pub error_section: Option<edit::EditSection>, // Section of the field the error is about
+}
+
+/// Which classification picker is being typed into
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PickerField {
+    ItemGroup,
+    ProductClass,
+    RevenueCategory,
+    TaxGroup,
+    SecurityLevel,
+    ReportCategory,
+}
+
+/// Searchable pickers for the single-choice classifications in the edit view
+#[derive(Default, Debug, Clone)]
+pub struct ClassificationPickers {
+    pub item_group: EntityPicker,
+    pub product_class: EntityPicker,
+    pub revenue_category: EntityPicker,
+    pub tax_group: EntityPicker,
+    pub security_level: EntityPicker,
+    pub report_category: EntityPicker,
+}
+
+impl ClassificationPickers {
+    pub fn new(
+        item_groups: &BTreeMap<EntityId, ItemGroup>,
+        product_classes: &BTreeMap<EntityId, ProductClass>,
+        revenue_categories: &BTreeMap<EntityId, RevenueCategory>,
+        tax_groups: &BTreeMap<EntityId, TaxGroup>,
+        security_levels: &BTreeMap<EntityId, SecurityLevel>,
+        report_categories: &BTreeMap<EntityId, ReportCategory>,
+    ) -> Self {
+        Self {
+            item_group: EntityPicker::new(entity_component::entity_options(item_groups.values())),
+            product_class: EntityPicker::new(entity_component::entity_options(product_classes.values())),
+            revenue_category: EntityPicker::new(entity_component::entity_options(revenue_categories.values())),
+            tax_group: EntityPicker::new(entity_component::entity_options(tax_groups.values())),
+            security_level: EntityPicker::new(entity_component::entity_options(security_levels.values())),
+            report_category: EntityPicker::new(entity_component::entity_options(report_categories.values())),
+        }
+    }
+
+    pub fn get_mut(&mut self, field: PickerField) -> &mut EntityPicker {
+        match field {
+            PickerField::ItemGroup => &mut self.item_group,
+            PickerField::ProductClass => &mut self.product_class,
+            PickerField::RevenueCategory => &mut self.revenue_category,
+            PickerField::TaxGroup => &mut self.tax_group,
+            PickerField::SecurityLevel => &mut self.security_level,
+            PickerField::ReportCategory => &mut self.report_category,
+        }
+    }
 }
 
 impl EditState {
@@ -187,13 +447,23 @@ impl EditState {
             covers: item.covers.to_string(),
             image_id: item.image_id.to_string(),
             language_iso_code: item.language_iso_code.clone(),
+            language_other: !store_fields::is_valid_language_code(&item.language_iso_code),
             choice_groups: item.choice_groups.clone().unwrap_or_default(),
             choice_groups_combo: combo_box::State::with_selection(choice_group_list.clone(), None),
             choice_group_selection: None,
             printer_logicals: item.printer_logicals.clone().unwrap_or_default(),
-            printer_logicals_combo: combo_box::State::with_selection(printer_logical_list.clone(), None),
+            // Inactive printers stay on the items that have them, but can't be added
+            printer_logicals_combo: combo_box::State::with_selection(
+                printer_logical_list.iter().filter(|printer| printer.active).cloned().collect(),
+                None
+            ),
             printer_logicals_selection: None,
+            pickers: ClassificationPickers::default(),
+            button_suggestion: None,
+            assignment_copy: None,
+            price_fill: price_fill::PriceFill::default(),
             validation_error: None,
+            error_section: None,
         }
     }
 
@@ -249,11 +519,7 @@ impl EditState {
 
         // Integer field validations
         for (field_name, value) in [
-            ("KDS Cook Time", &self.kds_cooktime),
             ("KDS Department", &self.kds_dept),
-            ("Store ID", &self.store_id),
-            ("Covers", &self.covers),
-            ("Image ID", &self.image_id),
         ] {
             if !value.is_empty() {
                 if let Err(_) = value.parse::<i32>() {
@@ -264,6 +530,17 @@ impl EditState {
             }
         }
 
+        for (field_name, value) in [
+            ("KDS Cook Time", &self.kds_cooktime),
+            ("Store ID", &self.store_id),
+            ("Covers", &self.covers),
+            ("Image ID", &self.image_id),
+        ] {
+            if !value.is_empty() {
+                parse_non_negative(value, field_name).map_err(ValidationError::InvalidValue)?;
+            }
+        }
+
         Ok(())
     }
 }
@@ -290,6 +567,10 @@ pub struct Item {
     pub cost_amount: Option<Decimal>,
     pub reserved1: bool,
     pub ask_price: bool,
+    #[serde(default)]
+    pub ask_price_prompt: Option<String>, // Shown on the till when it asks for the price
+    #[serde(default)]
+    pub ask_price_suggested: Option<Decimal>, // Filled in on the till's price prompt
     pub print_on_check: bool,
     pub discountable: bool,
     pub voidable: bool,
@@ -311,12 +592,16 @@ pub struct Item {
     pub image_id: i32,
     pub stock_item: bool,
     pub language_iso_code: String,
+    #[serde(default)]
+    pub created_at: Option<String>,
+    #[serde(default)]
+    pub updated_at: Option<String>,
 }
 
 impl Default for Item {
     fn default() -> Self {
         Self {
-            id: -1,
+            id: DRAFT_ID,
             name: "New Item".to_string(),
             button1: String::new(),
             button2: None,
@@ -336,6 +621,8 @@ impl Default for Item {
             cost_amount: None,
             reserved1: false,
             ask_price: false,
+            ask_price_prompt: None,
+            ask_price_suggested: None,
             print_on_check: true,
             discountable: true,
             voidable: true,
@@ -357,6 +644,8 @@ impl Default for Item {
             image_id: 0,
             stock_item: false,
             language_iso_code: String::new(),
+            created_at: None,
+            updated_at: None,
         }
     }
 }
@@ -383,7 +672,9 @@ impl Item {
         Ok(())
     }
 
-    pub fn validate(&self, context: &ViewContext) -> Result<(), ValidationError> {
+    /// `stored_id` is the id the item is saved under now. An id that isn't being
+    /// changed is left alone even if it sits outside the group's range.
+    pub fn validate(&self, context: &ViewContext, stored_id: Option<EntityId>) -> Result<(), ValidationError> {
         // Name validation
         if self.name.trim().is_empty() {
             return Err(ValidationError::EmptyName(
@@ -406,10 +697,25 @@ impl Item {
             }
         }
 
-        // ID validation within item group range
+        if let Some(ref prompt) = self.ask_price_prompt {
+            if prompt.chars().count() > ASK_PRICE_PROMPT_MAX {
+                return Err(ValidationError::InvalidValue(
+                    format!("Till prompt exceeds {} characters", ASK_PRICE_PROMPT_MAX)
+                ));
+            }
+        }
+
+        if self.ask_price_suggested.is_some_and(|price| price < Decimal::ZERO) {
+            return Err(ValidationError::InvalidValue(
+                "Suggested price cannot be negative".to_string()
+            ));
+        }
+
+        // ID validation within item group range, new items only get their id once saved
         if let Some(group_id) = self.item_group {
             if let Some(group) = context.available_item_groups.get(&group_id) {
-                if self.id < group.id_range.start || self.id > group.id_range.end {
+                let id_changed = stored_id != Some(self.id);
+                if self.id >= 0 && id_changed && (self.id < group.id_range.start || self.id > group.id_range.end) {
                     return Err(ValidationError::InvalidId(
                         format!("ID must be within group range ({}-{})",
                             group.id_range.start, group.id_range.end)
@@ -474,13 +780,34 @@ impl Item {
             }
         }
 
+        if let Some(ref prices) = self.item_prices {
+            for item_price in prices {
+                if let Some(level) = context.available_price_levels.get(&item_price.price_level_id) {
+                    if !level.fits_precision(item_price.price) {
+                        return Err(ValidationError::InvalidValue(
+                            format!("{} prices allow at most {} decimal places", level.name, level.decimals)
+                        ));
+                    }
+                } else {
+                    return Err(ValidationError::InvalidReference(
+                        "Referenced price level does not exist".to_string()
+                    ));
+                }
+            }
+        }
+
         if let Some(ref groups) = self.choice_groups {
-            for (group_id, _) in groups {
+            for (index, (group_id, _)) in groups.iter().enumerate() {
                 if !context.available_choice_groups.contains_key(group_id) {
                     return Err(ValidationError::InvalidReference(
                         "Referenced choice group does not exist".to_string()
                     ));
                 }
+                if groups[..index].iter().any(|(earlier, _)| earlier == group_id) {
+                    return Err(ValidationError::InvalidReference(
+                        "A choice group is assigned more than once".to_string()
+                    ));
+                }
             }
         }
 
@@ -496,18 +823,171 @@ impl Item {
 
         Ok(())
     }
+
+    /// True when the level's price is the only price the item has, so deleting
+    /// the level would leave the item unpriced
+    pub fn has_sole_price_level(&self, level_id: EntityId) -> bool {
+        self.default_price.is_none()
+            && self.item_prices.as_ref().is_some_and(|prices| {
+                !prices.is_empty() && prices.iter().all(|price| price.price_level_id == level_id)
+            })
+    }
+
+    /// Price level ids that appear in only one of `price_levels` and `item_prices`.
+    /// Both are kept around until the migration off `price_levels` is finished.
+    pub fn price_level_mismatches(&self) -> Vec<EntityId> {
+        let listed: BTreeSet<EntityId> = self.price_levels
+            .iter()
+            .flatten()
+            .copied()
+            .collect();
+        let priced: BTreeSet<EntityId> = self.item_prices
+            .iter()
+            .flatten()
+            .map(|price| price.price_level_id)
+            .collect();
+
+        listed.symmetric_difference(&priced).copied().collect()
+    }
+
+    /// Choice group and price level assignments that don't hold up, one line each: groups
+    /// or levels that no longer exist, a group assigned twice, groups sharing a sequence number
+    pub fn reference_problems(
+        &self,
+        choice_groups: &BTreeMap<EntityId, ChoiceGroup>,
+        price_levels: &BTreeMap<EntityId, PriceLevel>,
+    ) -> Vec<String> {
+        let mut problems = Vec::new();
+        let assigned = self.choice_groups.as_deref().unwrap_or_default();
+
+        for (index, (group_id, sequence)) in assigned.iter().enumerate() {
+            if !choice_groups.contains_key(group_id) {
+                problems.push(format!("Choice group {} no longer exists", group_id));
+            }
+
+            let earlier = &assigned[..index];
+            if earlier.iter().any(|(id, _)| id == group_id) {
+                problems.push(format!("Choice group {} is assigned more than once", group_id));
+            } else if let Some((other, _)) = earlier.iter().find(|(_, other_sequence)| other_sequence == sequence) {
+                problems.push(format!("Choice groups {} and {} share sequence {}", other, group_id, sequence));
+            }
+        }
+
+        for price in self.item_prices.iter().flatten() {
+            if !price_levels.contains_key(&price.price_level_id) {
+                problems.push(format!("Price level {} no longer exists", price.price_level_id));
+            }
+        }
+
+        problems
+    }
+
+    /// Why the item has no single primary printer, None when exactly one assigned printer
+    /// is primary. Items without printers don't print, so they have nothing to check.
+    pub fn primary_printer_problem(&self, printers: &BTreeMap<EntityId, PrinterLogical>) -> Option<String> {
+        let assigned = self.printer_logicals.as_deref().unwrap_or_default();
+        let existing = || assigned.iter().filter(|(id, _)| printers.contains_key(id));
+
+        if existing().next().is_none() {
+            return None;
+        }
+
+        match existing().filter(|(_, is_primary)| *is_primary).count() {
+            1 => None,
+            0 if assigned.iter().any(|(id, is_primary)| *is_primary && !printers.contains_key(id)) => {
+                Some("Its primary printer no longer exists".to_string())
+            }
+            0 => Some("None of its printers is primary".to_string()),
+            count => Some(format!("{} of its printers are marked primary", count)),
+        }
+    }
+
+    /// Makes the first assigned printer the only primary one. Returns true if anything changed.
+    pub fn fix_primary_printer(&mut self, printers: &BTreeMap<EntityId, PrinterLogical>) -> bool {
+        if self.primary_printer_problem(printers).is_none() {
+            return false;
+        }
+        let Some(assigned) = self.printer_logicals.as_mut() else {
+            return false;
+        };
+
+        let first = assigned.iter().position(|(id, _)| printers.contains_key(id));
+        for (index, (_, is_primary)) in assigned.iter_mut().enumerate() {
+            *is_primary = Some(index) == first;
+        }
+        true
+    }
+
+    /// Brings `price_levels` and `item_prices` back in line. Levels that were only
+    /// listed get a price (the default price, or zero), and the list is rebuilt
+    /// from the prices. Returns true if anything changed.
+    pub fn sync_price_levels(&mut self) -> bool {
+        if self.price_level_mismatches().is_empty() {
+            return false;
+        }
+
+        let mut prices = self.item_prices.take().unwrap_or_default();
+
+        for level_id in self.price_levels.iter().flatten() {
+            if !prices.iter().any(|price| price.price_level_id == *level_id) {
+                prices.push(ItemPrice {
+                    price_level_id: *level_id,
+                    price: self.default_price.unwrap_or(Decimal::new(0, 2)),
+                });
+            }
+        }
+
+        let mut level_ids: Vec<EntityId> = prices.iter().map(|price| price.price_level_id).collect();
+        level_ids.sort();
+        level_ids.dedup();
+
+        self.price_levels = Some(level_ids);
+        self.item_prices = Some(prices);
+        true
+    }
 }
 
+/// Longest till prompt the POS shows in full
+pub const ASK_PRICE_PROMPT_MAX: usize = 40;
+
+// Id of the scrollable item list
+pub const LIST_ID: &str = "Item List";
+
 pub fn update(
     item: &mut Item,
     message: Message,
     state: &mut EditState,
-    context: &mut ViewContext,
+) -> Action<Operation, Message> {
+    let section = match &message {
+        Message::Edit(msg) => edit::EditSection::of_message(msg),
+        _ => None,
+    };
+    let previous_error = state.validation_error.clone();
+
+    let action = update_message(item, message, state);
+
+    // Remember where a new error came from, so its section opens even when collapsed
+    if state.validation_error.is_none() {
+        state.error_section = None;
+    } else if state.validation_error != previous_error && section.is_some() {
+        state.error_section = section;
+    }
+
+    action
+}
+
+fn update_message(
+    item: &mut Item,
+    message: Message,
+    state: &mut EditState,
 ) -> Action<Operation, Message> {
     match message {
         Message::Edit(msg) => match msg  {
             // Basic Info
             edit::Message::UpdateName(name) => {
+                // Empty is caught on save, but all spaces looks filled in until then
+                state.validation_error = (!name.is_empty() && name.trim().is_empty())
+                    .then(|| "Item name cannot be only spaces".to_string());
                 item.name = name;
                 Action::none()
             }
@@ -528,6 +1008,22 @@ pub fn update(
 
                 Action::none()
             }
+            edit::Message::SuggestButtonText(slot) => {
+                Action::operation(Operation::SuggestButtonText(slot))
+            }
+            edit::Message::AcceptButtonSuggestion => {
+                match state.button_suggestion.take() {
+                    Some((abbreviate::ButtonSlot::Button1, text)) => item.button1 = text,
+                    Some((abbreviate::ButtonSlot::Button2, text)) => item.button2 = Some(text),
+                    None => {}
+                }
+                state.validation_error = None;
+                Action::none()
+            }
+            edit::Message::DismissButtonSuggestion => {
+                state.button_suggestion = None;
+                Action::none()
+            }
             edit::Message::UpdateButton1(text) => {
                 if text.len() <= 15 {
                     item.button1 = text;
@@ -556,7 +1052,11 @@ pub fn update(
             // Classifications
             edit::Message::SelectItemGroup(group_id) => {
                 item.item_group = group_id;
-                Action::none()
+                if group_id.is_some() {
+                    Action::operation(Operation::ItemGroupSelected)
+                } else {
+                    Action::none()
+                }
             }
             edit::Message::SelectProductClass(class_id) => {
                 item.product_class = class_id;
@@ -578,6 +1078,10 @@ pub fn update(
                 item.report_category = category_id;
                 Action::none()
             }
+            edit::Message::UpdatePickerQuery(field, query) => {
+                state.pickers.get_mut(field).set_query(query);
+                Action::none()
+            }
             edit::Message::ChoiceGroupSelected(group_id) => {
                 match &mut item.choice_groups {
                     Some(choice_groups) => {
@@ -585,7 +1089,7 @@ pub fn update(
                             // do nothing, it's already selected
                         } else {
                             //add group to the selected groups list
-                            let next_sequence: i32 = choice_groups.len().try_into().unwrap_or_else(|_| { i32::MAX });
+                            let next_sequence = next_choice_sequence(choice_groups);
                             choice_groups.push((group_id, next_sequence ))
                         }
                     }
@@ -652,6 +1156,24 @@ pub fn update(
                 item.ask_price = value;
                 Action::none()
             }
+            edit::Message::UpdateAskPricePrompt(prompt) => {
+                item.ask_price_prompt = (!prompt.is_empty()).then_some(prompt);
+                Action::none()
+            }
+            edit::Message::UpdateAskPriceSuggested(amount) => {
+                item.ask_price_suggested = if amount.is_empty() {
+                    None
+                } else {
+                    match amount.parse() {
+                        Ok(amount) => Some(amount),
+                        Err(_) => {
+                            state.validation_error = Some("Invalid suggested price format".to_string());
+                            return Action::none();
+                        }
+                    }
+                };
+                Action::none()
+            }
             edit::Message::ToggleAllowPriceOverride(value) => {
                 item.allow_price_override = value;
                 Action::none()
@@ -697,6 +1219,54 @@ pub fn update(
                 // Update edit_state.prices for UI
                 Action::operation(Operation::UpdatePrice(item.id, price_level_id, item_price))
             }
+            edit::Message::ApplyGroupDefaults => Action::operation(Operation::ApplyGroupDefaults),
+            edit::Message::UpdateFillAmount(amount) => {
+                state.price_fill.amount = amount;
+                state.price_fill.confirming = false;
+                Action::none()
+            }
+            edit::Message::SetFillMode(mode) => {
+                state.price_fill.mode = mode;
+                state.price_fill.confirming = false;
+                Action::none()
+            }
+            edit::Message::ToggleFillDefault(include) => {
+                state.price_fill.include_default = include;
+                state.price_fill.confirming = false;
+                Action::none()
+            }
+            fill @ (edit::Message::ApplyFill | edit::Message::ConfirmFill) => {
+                let confirmed = matches!(fill, edit::Message::ConfirmFill);
+                let changes = match state.price_fill.changes(item, &state.prices) {
+                    Ok(changes) => changes,
+                    Err(e) => {
+                        state.validation_error = Some(e);
+                        return Action::none();
+                    }
+                };
+
+                if !confirmed && price_fill::PriceFill::overwrites(&changes) {
+                    state.price_fill.confirming = true;
+                    return Action::none();
+                }
+
+                println!("Filling {} prices on item {}", changes.len(), item.id);
+                state.price_fill.confirming = false;
+
+                // Each price goes through the same messages as typing it in
+                let updates = changes.into_iter().map(|change| {
+                    let price = change.new.to_string();
+                    Task::done(Message::Edit(match change.level {
+                        Some(level_id) => edit::Message::UpdatePrice(level_id, price),
+                        None => edit::Message::UpdateBasePrice(price),
+                    }))
+                });
+                Action::none().with_task(Task::batch(updates))
+            }
+            edit::Message::CancelFill => {
+                state.price_fill.confirming = false;
+                Action::none()
+            }
             edit::Message::UpdateStorePriceLevel(level_id) => {
                 if let Some(level_id) = level_id {
                     if let Some(ref mut levels) = item.store_price_level {
@@ -787,13 +1357,13 @@ pub fn update(
                 Action::none()
             }
             edit::Message::UpdateKdsCooktime(time) => {
-                match time.parse() {
+                match parse_non_negative(&time, "Cook time") {
                     Ok(time) => {
                         item.kds_cooktime = time;
                         state.validation_error = None;
                     }
-                    Err(_) => {
-                        state.validation_error = Some("Invalid cook time format".to_string());
+                    Err(e) => {
+                        state.validation_error = Some(e);
                     }
                 }
                 Action::none()
@@ -813,43 +1383,67 @@ pub fn update(
 
             // Store Settings
             edit::Message::UpdateStoreId(id) => {
-                match id.parse() {
+                match parse_non_negative(&id, "Store ID") {
                     Ok(id) => {
                         item.store_id = id;
                         state.validation_error = None;
                     }
-                    Err(_) => {
-                        state.validation_error = Some("Invalid store ID".to_string());
+                    Err(e) => {
+                        state.validation_error = Some(e);
                     }
                 }
                 Action::none()
             }
             edit::Message::UpdateCovers(covers) => {
-                match covers.parse() {
+                match parse_non_negative(&covers, "Covers") {
+                    Ok(covers) if covers > store_fields::MAX_COVERS => {
+                        state.validation_error = Some(format!("Covers must be between 0 and {}", store_fields::MAX_COVERS));
+                    }
                     Ok(covers) => {
                         item.covers = covers;
                         state.validation_error = None;
                     }
-                    Err(_) => {
-                        state.validation_error = Some("Invalid covers number".to_string());
+                    Err(e) => {
+                        state.validation_error = Some(e);
                     }
                 }
                 Action::none()
             }
             edit::Message::UpdateImageId(id) => {
-                match id.parse() {
+                match parse_non_negative(&id, "Image ID") {
                     Ok(id) => {
                         item.image_id = id;
                         state.validation_error = None;
                     }
-                    Err(_) => {
-                        state.validation_error = Some("Invalid image ID".to_string());
+                    Err(e) => {
+                        state.validation_error = Some(e);
                     }
                 }
                 Action::none()
             }
             edit::Message::UpdateLanguageIsoCode(code) => {
-                item.language_iso_code = code;
+                item.language_iso_code = code.trim().to_string();
+                state.validation_error = if store_fields::is_valid_language_code(&item.language_iso_code) {
+                    None
+                } else {
+                    Some(match store_fields::suggest_language_code(&item.language_iso_code) {
+                        Some(suggestion) => format!("Not an ISO-639-1 code, did you mean \"{}\"?", suggestion),
+                        None => "Not an ISO-639-1 code".to_string(),
+                    })
+                };
+                Action::none()
+            }
+            edit::Message::SelectLanguage(choice) => {
+                match choice {
+                    store_fields::LanguageChoice::Iso(code, _) => {
+                        item.language_iso_code = code.to_string();
+                        state.language_other = false;
+                        state.validation_error = None;
+                    }
+                    store_fields::LanguageChoice::Other => {
+                        state.language_other = true;
+                    }
+                }
                 Action::none()
             }
 
@@ -862,7 +1456,7 @@ pub fn update(
                         }
                         else {
                             //get the sequence number
-                            let next_sequence: i32 = choice_groups.len().try_into().unwrap_or_else(|_| { i32::MAX });
+                            let next_sequence = next_choice_sequence(choice_groups);
                             //Add the choice group
                             choice_groups.push((group_id, next_sequence))
                         }
@@ -906,6 +1500,13 @@ pub fn update(
                         if printers.iter().any(|(id, _)| *id == printer_id ) {
                             // Keep all Ids, not matching the id we want to remove.
                             printers.retain(|&(id, _)| id != printer_id);
+
+                            // Removing the primary printer hands it on to the next one
+                            if !printers.iter().any(|(_, is_primary)| *is_primary) {
+                                if let Some((_, is_primary)) = printers.first_mut() {
+                                    *is_primary = true;
+                                }
+                            }
                         } else {} // Printer doesn't exist, do nothing
                     }
                     None => {} // Printer doesn't exist, do nothing
@@ -913,6 +1514,42 @@ pub fn update(
                 Action::none()
             }
 
+            edit::Message::OpenCopyAssignments => Action::operation(Operation::OpenCopyAssignments),
+            edit::Message::UpdateCopySourceQuery(query) => {
+                if let Some(copy) = state.assignment_copy.as_mut() {
+                    copy.picker.set_query(query);
+                }
+                Action::none()
+            }
+            edit::Message::SelectCopySource(source_id) => Action::operation(Operation::SelectCopySource(source_id)),
+            edit::Message::ToggleCopyKind(kind, checked) => {
+                if let Some(copy) = state.assignment_copy.as_mut() {
+                    copy.toggle_kind(kind, checked);
+                }
+                Action::none()
+            }
+            edit::Message::SetCopyMerge(merge) => {
+                if let Some(copy) = state.assignment_copy.as_mut() {
+                    copy.merge = merge;
+                }
+                Action::none()
+            }
+            edit::Message::ApplyCopyAssignments => {
+                if let Some(copy) = state.assignment_copy.take() {
+                    println!("Copying {:?} to item {} from {:?}", copy.kinds, item.id, copy.source.as_ref().map(|source| source.id));
+                    copy.apply(item, &mut state.prices);
+                }
+                Action::none()
+            }
+            edit::Message::CancelCopyAssignments => {
+                state.assignment_copy = None;
+                Action::none()
+            }
+
+            edit::Message::ToggleSection(section) => Action::operation(Operation::ToggleEditSection(section)),
+            edit::Message::ExpandAllSections => Action::operation(Operation::SetAllEditSectionsCollapsed(false)),
+            edit::Message::CollapseAllSections => Action::operation(Operation::SetAllEditSectionsCollapsed(true)),
+
             edit::Message::Save => Action::operation(Operation::Save(item.clone())),
             edit::Message::Cancel => Action::operation(Operation::Cancel),
         }
@@ -921,7 +1558,14 @@ pub fn update(
                 Action::operation(Operation::StartEdit)
             },
             view::Message::Back => Action::operation(Operation::Back),
-            view::Message::ExportToCsv => Action::none() //Need to implement export and imports
+            view::Message::ExportToCsv => Action::none(), //Need to implement export and imports
+            view::Message::StartQuickEdit(field) => Action::operation(Operation::StartQuickEdit(field)),
+            view::Message::QuickEditInput(value) => Action::operation(Operation::UpdateQuickEdit(value)),
+            view::Message::CommitQuickEdit => Action::operation(Operation::CommitQuickEdit),
+            view::Message::CancelQuickEdit => Action::operation(Operation::CancelQuickEdit),
+            view::Message::UndoQuickEdit => Action::operation(Operation::UndoQuickEdit(item.id)),
+            view::Message::TogglePin => Action::operation(Operation::TogglePin(item.id)),
+            view::Message::FixPrimaryPrinter => Action::operation(Operation::FixPrimaryPrinter(item.id)),
         }
         Message::CreateNew => {
             let new_item = Item::default();
@@ -948,25 +1592,75 @@ pub fn update(
         Message::LaunchMassItemEditWindow => {
             Action::operation(Operation::LaunchMassItemEditWindow)
         }
+        Message::ClearPrinterFilter => {
+            Action::operation(Operation::ClearPrinterFilter)
+        }
+        Message::ClearGroupFilter => {
+            Action::operation(Operation::ClearGroupFilter)
+        }
+        Message::ToggleMissingDashboard => {
+            Action::operation(Operation::ToggleMissingDashboard)
+        }
+        Message::FilterMissing(classification) => {
+            Action::operation(Operation::FilterMissing(classification))
+        }
+        Message::ClearMissingFilter => {
+            Action::operation(Operation::ClearMissingFilter)
+        }
+        Message::OpenFindReplace => {
+            Action::operation(Operation::OpenFindReplace)
+        }
+        Message::OpenNameCase(item_ids) => {
+            Action::operation(Operation::OpenNameCase(item_ids))
+        }
+        Message::ToggleGroupCollapsed(group_id) => {
+            Action::operation(Operation::ToggleGroupCollapsed(group_id))
+        }
+        Message::CreateInGroup(group_id) => {
+            let new_item = Item {
+                item_group: Some(group_id),
+                ..Item::default()
+            };
+            Action::operation(Operation::CreateNew(new_item))
+        }
+        Message::ListScrolled(viewport) => {
+            Action::operation(Operation::ListScrolled(viewport))
+        }
     }
 }
 
 pub fn view<'a>(
     item: &'a Item, 
     mode: &'a Mode,
-    items: &'a BTreeMap<EntityId, Item>,
+    context: ViewContext<'a>,
     item_search: &'a String,
+    search_matches: &BTreeSet<(SearchKind, EntityId)>,
+    printer_filter: Option<&'a PrinterLogical>,
+    group_filter: Option<&'a ItemGroup>,
+    pinned_items: &'a [EntityId],
+    missing_filter: Option<Classification>,
+    missing_counts: Option<&'a Vec<(Classification, usize)>>,
+    quick_edit: Option<&'a QuickEdit>,
+    quick_edit_undo: Option<EntityId>, // Item whose last quick edit can be undone
+    collapsed_groups: &'a BTreeSet<Option<EntityId>>,
+    collapsed_sections: &'a [edit::EditSection],
+    custom_flag_labels: &'a [String; 2],
+    price_rules: price_compare::PriceSanityRules,
     item_edit_state: &'a EditState,
-    item_groups: &'a BTreeMap<EntityId, ItemGroup>,
-    tax_groups: &'a BTreeMap<EntityId, TaxGroup>,
-    security_levels: &'a BTreeMap<EntityId, SecurityLevel>,
-    revenue_categories: &'a BTreeMap<EntityId, RevenueCategory>,
-    report_categories: &'a BTreeMap<EntityId, ReportCategory>,
-    product_classes: &'a BTreeMap<EntityId, ProductClass>,
-    choice_groups: &'a BTreeMap<EntityId, ChoiceGroup>,
-    printer_logicals: &'a BTreeMap<EntityId, PrinterLogical>,
-    price_levels: &'a BTreeMap<EntityId, PriceLevel>,
+    text_style: TextStyle,
 ) -> Element<'a, Message> {
+    let ViewContext {
+        available_items: items,
+        available_item_groups: item_groups,
+        available_tax_groups: tax_groups,
+        available_security_levels: security_levels,
+        available_revenue_categories: revenue_categories,
+        available_report_categories: report_categories,
+        available_product_classes: product_classes,
+        available_choice_groups: choice_groups,
+        available_printer_logicals: printer_logicals,
+        available_price_levels: price_levels,
+    } = context;
 
     let search_bar = row![
         iced::widget::text_input(
@@ -982,62 +1676,233 @@ pub fn view<'a>(
         button(icon::superpowers().size(13).center())
             .on_press(Message::LaunchMassItemEditWindow)
             .style(Modern::primary_button()),
+
+        iced::widget::Space::with_width(5),
+
+        button(icon::search().size(13).center())
+            .on_press(Message::OpenFindReplace)
+            .style(Modern::gray_button()),
     ];
 
+    // Pins to deleted items are pruned on load, skip any deleted since
+    let pinned_row = row(
+        pinned_items
+            .iter()
+            .filter_map(|id| items.get(id))
+            .map(|pinned| {
+                button(text(pinned.name.as_str()).size(text_style.caption()))
+                    .on_press(Message::Select(pinned.id))
+                    .style(Modern::conditional_button_style(
+                        pinned.id == item.id,
+                        Modern::selected_button_style(Modern::gray_button()),
+                        Modern::gray_button()
+                    ))
+                    .into()
+            })
+            .collect::<Vec<_>>()
+    )
+    .spacing(5)
+    .wrap();
+
+    let printer_filter_row: Element<'a, Message> = match printer_filter {
+        Some(printer) => row![
+            text(format!("Printer: {}", printer.name)).size(text_style.caption()).width(Length::Fixed(206.0)),
+            button(icon::cancel().size(12).center())
+                .on_press(Message::ClearPrinterFilter)
+                .style(Modern::system_button()),
+        ]
+        .align_y(Alignment::Center)
+        .into(),
+        None => row![].into(),
+    };
+
+    let group_filter_row: Element<'a, Message> = match group_filter {
+        Some(group) => row![
+            text(format!("Item Group: {}", group.name)).size(text_style.caption()).width(Length::Fixed(206.0)),
+            button(icon::cancel().size(12).center())
+                .on_press(Message::ClearGroupFilter)
+                .style(Modern::system_button()),
+        ]
+        .align_y(Alignment::Center)
+        .into(),
+        None => row![].into(),
+    };
+
+    let missing_filter_row: Element<'a, Message> = match missing_filter {
+        Some(classification) => row![
+            text(format!("Missing: {}", classification)).size(text_style.caption()).width(Length::Fixed(206.0)),
+            button(icon::cancel().size(12).center())
+                .on_press(Message::ClearMissingFilter)
+                .style(Modern::system_button()),
+        ]
+        .align_y(Alignment::Center)
+        .into(),
+        None => row![].into(),
+    };
+
+    // Counts are only computed when the dashboard is expanded
+    let missing_rows: Element<'a, Message> = match missing_counts {
+        Some(counts) => column(
+            counts.iter().map(|(classification, count)| {
+                button(
+                    row![
+                        text(classification.to_string()).size(text_style.caption()),
+                        iced::widget::horizontal_space(),
+                        text(count.to_string())
+                            .size(text_style.caption())
+                            .style(Modern::validated_text(*count > 0)),
+                    ]
+                )
+                .on_press_maybe((*count > 0).then_some(Message::FilterMissing(*classification)))
+                .style(Modern::system_button())
+                .width(Length::Fixed(250.0))
+                .into()
+            })
+            .collect::<Vec<_>>()
+        )
+        .spacing(2)
+        .into(),
+        None => column![].into(),
+    };
+
+    let missing_dashboard = column![
+        button(
+            text(format!(
+                "{} Missing classifications",
+                if missing_counts.is_some() { "▼" } else { "▶" }
+            ))
+            .size(text_style.caption())
+        )
+        .on_press(Message::ToggleMissingDashboard)
+        .style(Modern::gray_button())
+        .width(Length::Fixed(250.0)),
+        missing_rows,
+    ]
+    .spacing(5);
+
+    let entity_matches = entity_name_matches(&context, item_search);
     let filtered_items = items.values()
-        .filter(|item| matches_search(
-            item, 
-            &item_search,
-            item_groups,
-            tax_groups,
-            security_levels,
-            revenue_categories,
-            report_categories,
-            product_classes,
-            choice_groups,
-            printer_logicals,
-            price_levels,
-        ))
+        .filter(|item| printer_filter.map_or(true, |printer| {
+            item.printer_logicals
+                .as_ref()
+                .map_or(false, |printers| printers.iter().any(|(id, _)| *id == printer.id))
+        }))
+        .filter(|item| group_filter.map_or(true, |group| item.item_group == Some(group.id)))
+        .filter(|item| missing_filter.map_or(true, |classification| classification.is_missing(item)))
+        .filter(|item| matches_search(item, &item_search, search_matches, &entity_matches))
         .collect::<Vec<_>>();
 
+    // Casing only touches the items currently listed
+    let search_bar = search_bar.push(
+        button(text("Aa").size(text_style.caption()).center())
+            .on_press_maybe((!filtered_items.is_empty()).then(|| {
+                Message::OpenNameCase(filtered_items.iter().map(|an_item| an_item.id).collect())
+            }))
+            .style(Modern::gray_button())
+    ).spacing(5);
+
     let header_row = row![
         text("Name").width(Length::Fixed(175.0)),
         text("Actions").width(Length::Fixed(150.0)),
     ]
     .padding(5);
 
-    let items_list = scrollable(
-        column(
-            filtered_items
-                .iter()
-                .map(|an_item| {
-                    button(
-                        list_item(
-                            an_item.name.as_str(),
-                            button(icon::copy().size(14))
-                                .on_press(Message::CopyItem(an_item.id)),
-                            button(icon::trash().size(14))
-                                .on_press(Message::RequestDelete(an_item.id)),
-                        )
-                    )
-                    .on_press(Message::Select(an_item.id))
-                    .style(
-                        Modern::conditional_button_style(
-                            an_item.id == item.id,
-                            Modern::selected_button_style(Modern::system_button()),
-                            Modern::system_button()
-                        )
-                    ).into()
-                })
-                .collect::<Vec<_>>()
+    let item_button = |an_item: &'a Item| -> Element<'a, Message> {
+        button(
+            list_item(
+                an_item.name.as_str(),
+                button(icon::copy().size(14))
+                    .on_press(Message::CopyItem(an_item.id)),
+                button(icon::trash().size(14))
+                    .on_press(Message::RequestDelete(an_item.id)),
+                text_style,
+            )
         )
+        .on_press(Message::Select(an_item.id))
+        .style(
+            Modern::conditional_button_style(
+                an_item.id == item.id,
+                Modern::selected_button_style(Modern::system_button()),
+                Modern::system_button()
+            )
+        ).into()
+    };
+
+    // While filtering, groups with matches are expanded and empty groups are hidden
+    let filtering = !item_search.trim().is_empty() || printer_filter.is_some() || group_filter.is_some() || missing_filter.is_some();
+
+    let mut sections: Vec<(Option<&'a ItemGroup>, Vec<&'a Item>)> = item_groups
+        .values()
+        .map(|group| {
+            let group_items = filtered_items
+                .iter()
+                .filter(|an_item| an_item.item_group == Some(group.id))
+                .copied()
+                .collect();
+            (Some(group), group_items)
+        })
+        .collect();
+
+    let unassigned: Vec<&'a Item> = filtered_items
+        .iter()
+        .filter(|an_item| an_item.item_group.map_or(true, |id| !item_groups.contains_key(&id)))
+        .copied()
+        .collect();
+    sections.push((None, unassigned));
+
+    let mut list_rows: Vec<Element<'a, Message>> = Vec::new();
+
+    for (group, group_items) in sections {
+        if group_items.is_empty() && (filtering || group.is_none()) {
+            continue;
+        }
+
+        let group_key = group.map(|g| g.id);
+        let expanded = filtering || !collapsed_groups.contains(&group_key);
+
+        let title = match group {
+            Some(g) => format!("{} ({}–{}) — {} items", g.name, g.id_range.start, g.id_range.end, group_items.len()),
+            None => format!("Unassigned — {} items", group_items.len()),
+        };
+
+        let header = row![
+            button(text(format!("{} {}", if expanded { "▼" } else { "▶" }, title)).size(text_style.caption()))
+                .on_press(Message::ToggleGroupCollapsed(group_key))
+                .style(Modern::gray_button())
+                .width(Length::Fill),
+        ];
+
+        let header = match group {
+            Some(g) => header.push(
+                button(icon::new().size(12).center())
+                    .on_press(Message::CreateInGroup(g.id))
+                    .style(Modern::primary_button())
+            ),
+            None => header,
+        };
+
+        list_rows.push(header.spacing(2).align_y(Alignment::Center).into());
+
+        if expanded {
+            list_rows.extend(group_items.into_iter().map(&item_button));
+        }
+    }
+
+    let items_list = scrollable(
+        column(list_rows)
         .spacing(5)
         .width(iced::Length::Fixed(250.0))
-    ).height(Length::Fill);
+    )
+    .id(scrollable::Id::new(LIST_ID)) // Stable id, so saves can put the list back where it was
+    .on_scroll(Message::ListScrolled)
+    .height(Length::Fill);
 
     let content = match mode {
         Mode::View => view::view(
             item,
+            pinned_items.contains(&item.id),
+            quick_edit.filter(|edit| edit.item_id == item.id),
+            quick_edit_undo == Some(item.id),
             item_groups,
             tax_groups,
             security_levels,
@@ -1047,20 +1912,21 @@ pub fn view<'a>(
             choice_groups,
             printer_logicals,
             price_levels,
+            custom_flag_labels,
+            price_rules,
+            text_style,
         ).map(Message::View),
         Mode::Edit => {
             edit::view(
                 item,
                 item_edit_state,
-                item_groups,
-                tax_groups,
-                security_levels,
-                revenue_categories,
-                report_categories,
-                product_classes,
+                collapsed_sections,
+                custom_flag_labels,
                 choice_groups,
                 printer_logicals,
                 price_levels,
+                button_collisions::collision_warning(items, item),
+                text_style,
             ).map(Message::Edit)
         }
     };
@@ -1069,13 +1935,18 @@ pub fn view<'a>(
         container(
             column![
                 row![
-                    container(text("Items").size(18).style(Modern::primary_text())).padding(5),
+                    container(text("Items").size(text_style.heading()).style(Modern::primary_text())).padding(5),
                     iced::widget::horizontal_space(),
                     button(icon::new().size(14).center())
                         .on_press(Message::CreateNew)
                         .style(Modern::primary_button()),
                 ].width(250),
+                missing_dashboard,
+                pinned_row,
                 search_bar,
+                printer_filter_row,
+                group_filter_row,
+                missing_filter_row,
                 header_row,   
                 items_list,
             ]
@@ -1098,31 +1969,60 @@ pub fn view<'a>(
 
 }
 
+/// Everything an item is looked up against, borrowed from MenuBuilder::view_context
+#[derive(Debug, Clone, Copy)]
 pub struct ViewContext<'a> {
-    pub available_items: &'a mut BTreeMap<EntityId, Item>,
-    pub available_item_groups: &'a mut BTreeMap<EntityId, ItemGroup>,
-    pub available_tax_groups: &'a mut BTreeMap<EntityId, TaxGroup>,
-    pub available_security_levels: &'a mut BTreeMap<EntityId, SecurityLevel>,
-    pub available_revenue_categories: &'a mut BTreeMap<EntityId, RevenueCategory>,
-    pub available_report_categories: &'a mut BTreeMap<EntityId, ReportCategory>,
-    pub available_product_classes: &'a mut BTreeMap<EntityId, ProductClass>,
-    pub available_choice_groups: &'a mut BTreeMap<EntityId, ChoiceGroup>,
-    pub available_printer_logicals: &'a mut BTreeMap<EntityId, PrinterLogical>,
-    pub available_price_levels: &'a mut BTreeMap<EntityId, PriceLevel>,
+    pub available_items: &'a BTreeMap<EntityId, Item>,
+    pub available_item_groups: &'a BTreeMap<EntityId, ItemGroup>,
+    pub available_tax_groups: &'a BTreeMap<EntityId, TaxGroup>,
+    pub available_security_levels: &'a BTreeMap<EntityId, SecurityLevel>,
+    pub available_revenue_categories: &'a BTreeMap<EntityId, RevenueCategory>,
+    pub available_report_categories: &'a BTreeMap<EntityId, ReportCategory>,
+    pub available_product_classes: &'a BTreeMap<EntityId, ProductClass>,
+    pub available_choice_groups: &'a BTreeMap<EntityId, ChoiceGroup>,
+    pub available_printer_logicals: &'a BTreeMap<EntityId, PrinterLogical>,
+    pub available_price_levels: &'a BTreeMap<EntityId, PriceLevel>,
+}
+
+/// Entities whose name contains the query. Entity lists are short, so this
+/// is done per view rather than kept in the search index.
+fn entity_name_matches(context: &ViewContext, query: &str) -> BTreeSet<(SearchKind, EntityId)> {
+    let query = query.trim().to_lowercase();
+    let mut matches = BTreeSet::new();
+    if query.is_empty() {
+        return matches;
+    }
+
+    fn add<'a, T: entity_component::Entity + 'a>(
+        matches: &mut BTreeSet<(SearchKind, EntityId)>,
+        kind: SearchKind,
+        entities: impl Iterator<Item = &'a T>,
+        query: &str,
+    ) {
+        matches.extend(entities
+            .filter(|entity| entity.name().to_lowercase().contains(query))
+            .map(|entity| (kind, entity.id())));
+    }
+
+    add(&mut matches, SearchKind::ItemGroup, context.available_item_groups.values(), &query);
+    add(&mut matches, SearchKind::TaxGroup, context.available_tax_groups.values(), &query);
+    add(&mut matches, SearchKind::SecurityLevel, context.available_security_levels.values(), &query);
+    add(&mut matches, SearchKind::RevenueCategory, context.available_revenue_categories.values(), &query);
+    add(&mut matches, SearchKind::ReportCategory, context.available_report_categories.values(), &query);
+    add(&mut matches, SearchKind::ProductClass, context.available_product_classes.values(), &query);
+    add(&mut matches, SearchKind::ChoiceGroup, context.available_choice_groups.values(), &query);
+    add(&mut matches, SearchKind::PrinterLogical, context.available_printer_logicals.values(), &query);
+    add(&mut matches, SearchKind::PriceLevel, context.available_price_levels.values(), &query);
+    matches
 }
 
+/// Whether the item is in the search index hits, or anything it's classified
+/// under has a matching name
 fn matches_search(
     item: &Item, 
     query: &str,
-    item_groups: &BTreeMap<EntityId, ItemGroup>,
-    tax_groups: &BTreeMap<EntityId, TaxGroup>,
-    security_levels: &BTreeMap<EntityId, SecurityLevel>,
-    revenue_categories: &BTreeMap<EntityId, RevenueCategory>,
-    report_categories: &BTreeMap<EntityId, ReportCategory>,
-    product_classes: &BTreeMap<EntityId, ProductClass>,
-    choice_groups: &BTreeMap<EntityId, ChoiceGroup>,
-    printer_logicals: &BTreeMap<EntityId, PrinterLogical>,
-    price_levels: &BTreeMap<EntityId, PriceLevel>,
+    item_hits: &BTreeSet<(SearchKind, EntityId)>,
+    entity_matches: &BTreeSet<(SearchKind, EntityId)>,
 ) -> bool {
 
     // If the search bar is empty, show all items
@@ -1130,102 +2030,266 @@ fn matches_search(
         return true;
     }
 
-    // Convert everything to lowercase for case-insensitive matching
-    let query_lower = query.to_lowercase();
+    let hit = |kind: SearchKind, id: EntityId| entity_matches.contains(&(kind, id));
+
+    item_hits.contains(&(SearchKind::Item, item.id))
+        || item.item_group.is_some_and(|id| hit(SearchKind::ItemGroup, id))
+        || item.tax_group.is_some_and(|id| hit(SearchKind::TaxGroup, id))
+        || item.security_level.is_some_and(|id| hit(SearchKind::SecurityLevel, id))
+        || item.revenue_category.is_some_and(|id| hit(SearchKind::RevenueCategory, id))
+        || item.report_category.is_some_and(|id| hit(SearchKind::ReportCategory, id))
+        || item.product_class.is_some_and(|id| hit(SearchKind::ProductClass, id))
+        || item.choice_groups.iter().flatten().any(|(id, _)| hit(SearchKind::ChoiceGroup, *id))
+        || item.printer_logicals.iter().flatten().any(|(id, _)| hit(SearchKind::PrinterLogical, *id))
+        || item.price_levels.iter().flatten().any(|id| hit(SearchKind::PriceLevel, *id))
+}
 
-    if item.name.to_lowercase().contains(&query_lower) {
-        return true;
+
+pub fn list_item<'a>(list_text: &'a str, copy_button: iced::widget::Button<'a, Message>,delete_button: iced::widget::Button<'a, Message>, text_style: TextStyle) -> Element<'a, Message> {
+    let button_content = row![
+        text(list_text).size(text_style.caption()).align_x(iced::Alignment::Start).width(150),
+        iced::widget::horizontal_space(),
+        copy_button.style(Modern::primary_button()),
+        delete_button.style(Modern::danger_button())
+    ].align_y(Alignment::Center);
+    
+    button_content.into()
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Validation reads the same context the app builds for the item views
+    fn app_with_drinks_group() -> crate::MenuBuilder {
+        let mut app = crate::MenuBuilder::default();
+        app.item_groups.insert(1, ItemGroup {
+            id: 1,
+            name: "Drinks".to_string(),
+            id_range: 100..199,
+            ..ItemGroup::default()
+        });
+        app
     }
 
-    //Match on the item's item_group name
-    if let Some(ig_id) = &item.item_group {
-        if let Some(ig) = item_groups.get(ig_id) {
-            if ig.name.to_lowercase().contains(&query_lower) {
-                return true;
-            }
+    fn drink(id: EntityId) -> Item {
+        Item {
+            id,
+            name: "Cola".to_string(),
+            item_group: Some(1),
+            ..Item::default()
         }
-    } false;
+    }
 
-    //Match on the item's tax_group name
-    if let Some(tg_id) = &item.tax_group {
-        if let Some(tg) = tax_groups.get(tg_id) {
-            if tg.name.to_lowercase().contains(&query_lower) {
-                return true;
-            }
-        }
-    } false;
+    #[test]
+    fn unchanged_id_outside_group_range_still_saves() {
+        let app = app_with_drinks_group();
+        let mut item = drink(500);
+        item.name = "Diet Cola".to_string();
 
-    //Match on the item's security_level name
-    if let Some(sl_id) = &item.security_level {
-        if let Some(sl) = security_levels.get(sl_id) {
-            if sl.name.to_lowercase().contains(&query_lower) {
-                return true;
-            }
-        }
-    } false;
+        assert!(item.validate(&app.view_context(), Some(500)).is_ok());
+    }
 
-    //Match on the item's report_category name
-    if let Some(rc_id) = &item.report_category {
-        if let Some(rc) = report_categories.get(rc_id) {
-            if rc.name.to_lowercase().contains(&query_lower) {
-                return true;
-            }
-        }
-    } false;
+    #[test]
+    fn changed_id_outside_group_range_is_rejected() {
+        let app = app_with_drinks_group();
+        let item = drink(500);
+
+        assert!(matches!(
+            item.validate(&app.view_context(), Some(150)),
+            Err(ValidationError::InvalidId(_))
+        ));
+        assert!(matches!(
+            item.validate(&app.view_context(), None),
+            Err(ValidationError::InvalidId(_))
+        ));
+    }
 
-    //Match on the item's security_level name
-    if let Some(sl_id) = &item.security_level {
-        if let Some(sl) = security_levels.get(sl_id) {
-            if sl.name.to_lowercase().contains(&query_lower) {
-                return true;
-            }
-        }
-    } false;
-
-    //Match on each choice_group's name
-    if let Some(cg_ids) = &item.choice_groups {
-        for cg_id in cg_ids {
-            if let Some(cg) = choice_groups.get(&cg_id.0) {
-                if cg.name.to_lowercase().contains(&query_lower) {
-                    return true;
-                }
-            }
-        }
-    } false;
-
-    //Match on each printer_logical's name
-    if let Some(pl_ids) = &item.printer_logicals {
-        for (pl_id, _) in pl_ids {
-            if let Some(pl) = printer_logicals.get(pl_id) {
-                if pl.name.to_lowercase().contains(&query_lower) {
-                    return true;
-                }
+    #[test]
+    fn id_inside_group_range_saves() {
+        let app = app_with_drinks_group();
+
+        assert!(drink(150).validate(&app.view_context(), None).is_ok());
+        assert!(drink(150).validate(&app.view_context(), Some(120)).is_ok());
+    }
+
+    #[test]
+    fn new_item_without_id_skips_range_check() {
+        let app = app_with_drinks_group();
+
+        assert!(drink(DRAFT_ID).validate(&app.view_context(), None).is_ok());
+    }
+
+    #[test]
+    fn quick_edit_runs_the_full_validation() {
+        let mut app = app_with_drinks_group();
+        app.price_levels.insert(2, PriceLevel { id: 2, name: "Bar".to_string(), decimals: 2, ..PriceLevel::default() });
+        let mut item = drink(150);
+        item.item_prices = Some(vec![ItemPrice { price_level_id: 2, price: Decimal::ONE }]);
+
+        let price = QuickEdit::new(150, QuickEditField::Price(2), "1.005".to_string());
+        assert!(price.apply(&item, &app.view_context()).is_err());
+
+        let price = QuickEdit::new(150, QuickEditField::Price(2), "1.05".to_string());
+        assert_eq!(price.apply(&item, &app.view_context()).unwrap().item_prices.unwrap()[0].price, Decimal::new(105, 2));
+
+        // The form would refuse the item for its missing tax group, so a name change can't save it either
+        item.tax_group = Some(9);
+        let name = QuickEdit::new(150, QuickEditField::Name, "Diet Cola".to_string());
+        assert!(name.apply(&item, &app.view_context()).is_err());
+    }
+
+    #[test]
+    fn quick_edit_is_dirty_only_when_changed() {
+        let mut edit = QuickEdit::new(1, QuickEditField::Name, "Cola".to_string());
+        assert!(!edit.is_dirty());
+
+        edit.value = "Coke".to_string();
+        assert!(edit.is_dirty());
+
+        edit.value = "Cola".to_string();
+        assert!(!edit.is_dirty());
+    }
+
+    #[test]
+    fn missing_item_group_is_rejected() {
+        let app = app_with_drinks_group();
+        let mut item = drink(150);
+        item.item_group = Some(9);
+
+        assert!(matches!(
+            item.validate(&app.view_context(), Some(150)),
+            Err(ValidationError::InvalidReference(_))
+        ));
+    }
+
+    fn choice_group(id: EntityId) -> ChoiceGroup {
+        ChoiceGroup { id, name: format!("Group {}", id), created_at: None, updated_at: None }
+    }
+
+    #[test]
+    fn shared_choice_sequence_is_reported_but_saves() {
+        let mut app = app_with_drinks_group();
+        app.choice_groups.insert(1, choice_group(1));
+        app.choice_groups.insert(2, choice_group(2));
+        let mut item = drink(150);
+        item.choice_groups = Some(vec![(1, 0), (2, 0)]);
+
+        assert_eq!(
+            item.reference_problems(&app.choice_groups, &app.price_levels),
+            vec!["Choice groups 1 and 2 share sequence 0".to_string()]
+        );
+        // Older data has clashes and the editor can't renumber, so they don't block saving
+        assert!(item.validate(&app.view_context(), Some(150)).is_ok());
+    }
+
+    #[test]
+    fn repeated_choice_group_is_reported_and_rejected() {
+        let mut app = app_with_drinks_group();
+        app.choice_groups.insert(1, choice_group(1));
+        let mut item = drink(150);
+        item.choice_groups = Some(vec![(1, 0), (1, 1)]);
+
+        assert_eq!(
+            item.reference_problems(&app.choice_groups, &app.price_levels),
+            vec!["Choice group 1 is assigned more than once".to_string()]
+        );
+        assert!(matches!(
+            item.validate(&app.view_context(), Some(150)),
+            Err(ValidationError::InvalidReference(_))
+        ));
+    }
+
+    #[test]
+    fn missing_price_level_is_reported_and_rejected() {
+        let mut app = app_with_drinks_group();
+        app.price_levels.insert(2, PriceLevel { id: 2, name: "Bar".to_string(), ..PriceLevel::default() });
+        let mut item = drink(150);
+        item.item_prices = Some(vec![
+            ItemPrice { price_level_id: 2, price: Decimal::ONE },
+            ItemPrice { price_level_id: 7, price: Decimal::ONE },
+        ]);
+
+        assert_eq!(
+            item.reference_problems(&app.choice_groups, &app.price_levels),
+            vec!["Price level 7 no longer exists".to_string()]
+        );
+        assert!(matches!(
+            item.validate(&app.view_context(), Some(150)),
+            Err(ValidationError::InvalidReference(_))
+        ));
+    }
+
+    #[test]
+    fn next_choice_sequence_skips_numbers_in_use() {
+        assert_eq!(next_choice_sequence(&[]), 0);
+        // Group at 1 was removed, adding by list length would repeat 2
+        assert_eq!(next_choice_sequence(&[(1, 0), (3, 2)]), 3);
+    }
+
+    #[test]
+    fn non_negative_fields_reject_negative_and_overflowing_input() {
+        assert_eq!(parse_non_negative(" 45 ", "Covers"), Ok(45));
+        assert_eq!(parse_non_negative("0", "Covers"), Ok(0));
+        assert_eq!(parse_non_negative("-1", "Covers"), Err("Covers can't be negative".to_string()));
+        assert_eq!(parse_non_negative("2147483648", "Covers"), Err("Covers is too large".to_string()));
+        assert_eq!(parse_non_negative("-2147483649", "Covers"), Err("Covers can't be negative".to_string()));
+        assert_eq!(parse_non_negative("4.5", "Covers"), Err("Invalid Covers format".to_string()));
+    }
+
+    #[test]
+    fn bad_store_field_input_keeps_the_previous_value() {
+        let mut item = Item { kds_cooktime: 30, covers: 2, store_id: 1, image_id: 4, ..drink(150) };
+        let mut state = EditState::default();
+
+        for value in ["-5", "99999999999"] {
+            for message in [
+                edit::Message::UpdateKdsCooktime(value.to_string()),
+                edit::Message::UpdateCovers(value.to_string()),
+                edit::Message::UpdateStoreId(value.to_string()),
+                edit::Message::UpdateImageId(value.to_string()),
+            ] {
+                state.validation_error = None;
+                let _ = update(&mut item, Message::Edit(message), &mut state);
+                assert!(state.validation_error.is_some(), "{} should be refused", value);
             }
         }
-    } false;
 
-    
-    //Match on each price_level's name
-    if let Some(pl_ids) = &item.price_levels {
-        for pl_id in pl_ids {
-            if let Some(pl) = price_levels.get(pl_id) {
-                if pl.name.to_lowercase().contains(&query_lower) {
-                    return true;
-                }
+        assert_eq!((item.kds_cooktime, item.covers, item.store_id, item.image_id), (30, 2, 1, 4));
+    }
+
+    #[test]
+    fn saving_the_form_refuses_negative_and_overflowing_fields() {
+        let valid = EditState { name: "Cola".to_string(), weight_amount: "0".to_string(), ..EditState::default() };
+        assert!(valid.validate(None).is_ok());
+
+        for field in 0..4 {
+            for value in ["-1", "2147483648"] {
+                let mut state = valid.clone();
+                let target = match field {
+                    0 => &mut state.kds_cooktime,
+                    1 => &mut state.covers,
+                    2 => &mut state.store_id,
+                    _ => &mut state.image_id,
+                };
+                *target = value.to_string();
+
+                assert!(matches!(state.validate(None), Err(ValidationError::InvalidValue(_))));
             }
         }
-    } false
+    }
 
-}
+    #[test]
+    fn whitespace_only_item_names_are_flagged_while_typing_and_refused_on_save() {
+        let mut item = drink(150);
+        let mut state = EditState::default();
 
+        let _ = update(&mut item, Message::Edit(edit::Message::UpdateName("   ".to_string())), &mut state);
+        assert_eq!(state.validation_error, Some("Item name cannot be only spaces".to_string()));
 
-pub fn list_item<'a>(list_text: &'a str, copy_button: iced::widget::Button<'a, Message>,delete_button: iced::widget::Button<'a, Message>) -> Element<'a, Message> {
-    let button_content = row![
-        text(list_text).size(12).align_x(iced::Alignment::Start).width(150),
-        iced::widget::horizontal_space(),
-        copy_button.style(Modern::primary_button()),
-        delete_button.style(Modern::danger_button())
-    ].align_y(Alignment::Center);
-    
-    button_content.into()
-}
\ No newline at end of file
+        let _ = update(&mut item, Message::Edit(edit::Message::UpdateName("Cola".to_string())), &mut state);
+        assert_eq!(state.validation_error, None);
+
+        let state = EditState { name: "   ".to_string(), weight_amount: "0".to_string(), ..EditState::default() };
+        assert!(matches!(state.validate(None), Err(ValidationError::EmptyName(_))));
+    }
+}
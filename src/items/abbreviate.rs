@@ -0,0 +1,126 @@
+use serde::{Serialize, Deserialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use crate::data_types::EntityId;
+use super::Item;
+
+/// Button text longer than this is rejected by the POS
+pub const BUTTON_TEXT_MAX: usize = 15;
+
+/// One entry of the house abbreviation dictionary, "Chicken" -> "Chkn"
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Abbreviation {
+    pub full: String,
+    pub short: String,
+}
+
+pub fn default_abbreviations() -> Vec<Abbreviation> {
+    [
+        ("Chicken", "Chkn"),
+        ("Sandwich", "Sndwch"),
+        ("Cheese", "Chz"),
+        ("Large", "Lg"),
+        ("Medium", "Med"),
+        ("Small", "Sm"),
+        ("With", "W/"),
+    ]
+    .into_iter()
+    .map(|(full, short)| Abbreviation { full: full.to_string(), short: short.to_string() })
+    .collect()
+}
+
+/// Which button text field a suggestion is for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ButtonSlot {
+    Button1,
+    Button2,
+}
+
+impl fmt::Display for ButtonSlot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Button1 => write!(f, "Button 1"),
+            Self::Button2 => write!(f, "Button 2"),
+        }
+    }
+}
+
+/// Replaces whole words and phrases from the dictionary, longest entry first so
+/// "Grilled Cheese" wins over "Cheese". Matching ignores case.
+pub fn abbreviate(name: &str, abbreviations: &[Abbreviation]) -> String {
+    let mut entries: Vec<(Vec<char>, &str)> = abbreviations
+        .iter()
+        .filter(|entry| !entry.full.trim().is_empty())
+        .map(|entry| (entry.full.trim().to_lowercase().chars().collect(), entry.short.as_str()))
+        .collect();
+    entries.sort_by(|a, b| b.0.len().cmp(&a.0.len()));
+
+    let chars: Vec<char> = name.chars().collect();
+    let lowered: Vec<char> = chars.iter().map(|c| c.to_lowercase().next().unwrap_or(*c)).collect();
+    let mut result = String::with_capacity(name.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        let at_word_start = i == 0 || !chars[i - 1].is_alphanumeric();
+        let matched = at_word_start.then(|| {
+            entries.iter().find(|(full, _)| {
+                let end = i + full.len();
+                end <= chars.len()
+                    && lowered[i..end] == full[..]
+                    && (end == chars.len() || !chars[end].is_alphanumeric())
+            })
+        }).flatten();
+
+        match matched {
+            Some((full, short)) => {
+                result.push_str(short);
+                i += full.len();
+            }
+            None => {
+                result.push(chars[i]);
+                i += 1;
+            }
+        }
+    }
+
+    result
+}
+
+/// Abbreviated name cut down to fit a button, on a character boundary
+pub fn suggest_button_text(name: &str, abbreviations: &[Abbreviation]) -> String {
+    let abbreviated = abbreviate(name, abbreviations);
+    let collapsed = abbreviated.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    let mut suggestion = String::new();
+    for c in collapsed.chars() {
+        if suggestion.len() + c.len_utf8() > BUTTON_TEXT_MAX {
+            break;
+        }
+        suggestion.push(c);
+    }
+
+    suggestion.trim_end().to_string()
+}
+
+#[derive(Debug, Clone)]
+pub struct ButtonFill {
+    pub item_id: EntityId,
+    pub name: String,
+    pub button1: String,
+}
+
+/// Button 1 suggestions for every item that has none yet
+pub fn fill_empty_button1(items: &BTreeMap<EntityId, Item>, abbreviations: &[Abbreviation]) -> Vec<ButtonFill> {
+    items
+        .values()
+        .filter(|item| item.button1.trim().is_empty())
+        .filter_map(|item| {
+            let button1 = suggest_button_text(&item.name, abbreviations);
+            (!button1.is_empty()).then(|| ButtonFill {
+                item_id: item.id,
+                name: item.name.clone(),
+                button1,
+            })
+        })
+        .collect()
+}
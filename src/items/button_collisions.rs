@@ -0,0 +1,93 @@
+use std::collections::BTreeMap;
+use crate::data_types::EntityId;
+use crate::item_groups::ItemGroup;
+use super::Item;
+
+/// Items in one item group whose POS buttons read the same
+#[derive(Debug, Clone)]
+pub struct ButtonCollision {
+    pub group_name: String,
+    pub button_text: String,
+    pub items: Vec<(EntityId, String)>,
+}
+
+// Cashiers can't tell "Chkn Sndwch" from "chkn sndwch "
+fn normalized(text: &str) -> String {
+    text.trim().to_lowercase()
+}
+
+/// Button texts an item shows, each once, blank ones left out
+fn button_texts(item: &Item) -> Vec<String> {
+    let mut texts: Vec<String> = std::iter::once(item.button1.as_str())
+        .chain(item.button2.as_deref())
+        .map(normalized)
+        .filter(|text| !text.is_empty())
+        .collect();
+    texts.dedup();
+    texts
+}
+
+/// Every button text used by more than one item in the same group, by group name.
+/// Items without a group aren't on a shared POS screen and are skipped.
+pub fn button_collisions(
+    items: &BTreeMap<EntityId, Item>,
+    item_groups: &BTreeMap<EntityId, ItemGroup>,
+) -> Vec<ButtonCollision> {
+    let mut by_text: BTreeMap<(EntityId, String), Vec<&Item>> = BTreeMap::new();
+    for item in items.values() {
+        if let Some(group_id) = item.item_group {
+            for text in button_texts(item) {
+                by_text.entry((group_id, text)).or_default().push(item);
+            }
+        }
+    }
+
+    let mut collisions: Vec<ButtonCollision> = by_text
+        .into_iter()
+        .filter(|(_, group_items)| group_items.len() > 1)
+        .map(|((group_id, text), group_items)| ButtonCollision {
+            group_name: item_groups
+                .get(&group_id)
+                .map_or_else(|| format!("Item Group {}", group_id), |group| group.name.clone()),
+            button_text: text,
+            items: group_items.iter().map(|item| (item.id, item.name.clone())).collect(),
+        })
+        .collect();
+
+    collisions.sort_by(|a, b| a.group_name.to_lowercase().cmp(&b.group_name.to_lowercase()));
+    collisions
+}
+
+/// Other items in the same group with a button that reads like one of this item's
+pub fn colliding_items<'a>(
+    items: &'a BTreeMap<EntityId, Item>,
+    item: &Item,
+) -> Vec<&'a Item> {
+    let Some(group_id) = item.item_group else {
+        return Vec::new();
+    };
+    let texts = button_texts(item);
+
+    items
+        .values()
+        .filter(|other| other.id != item.id && other.item_group == Some(group_id))
+        .filter(|other| button_texts(other).iter().any(|text| texts.contains(text)))
+        .collect()
+}
+
+/// Inline message for the item edit form, None when the buttons are unique in the group
+pub fn collision_warning(items: &BTreeMap<EntityId, Item>, item: &Item) -> Option<String> {
+    let others = colliding_items(items, item);
+    if others.is_empty() {
+        return None;
+    }
+
+    Some(format!(
+        "Button text matches {} in the same item group",
+        others
+            .iter()
+            .map(|other| format!("{} ({})", other.name, other.id))
+            .collect::<Vec<_>>()
+            .join(", ")
+    ))
+}
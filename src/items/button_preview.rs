@@ -0,0 +1,108 @@
+use iced::widget::{column, container, row, text};
+use iced::widget::text::Wrapping;
+use iced::{Background, Border, Color, Element, Font, Length};
+use iced_modern_theme::Modern;
+use crate::text_style::TextStyle;
+use super::Item;
+
+/// Characters a POS key shows per line, the rest is cut off
+pub const BUTTON_TEXT_LIMIT: usize = 15;
+
+const TILE_WIDTH: f32 = 130.0;
+const TILE_HEIGHT: f32 = 64.0;
+
+/// Colors of a POS key in one of the terminal themes
+#[derive(Clone, Copy)]
+struct TileColors {
+    background: Color,
+    text: Color,
+    border: Color,
+}
+
+const DARK_TILE: TileColors = TileColors {
+    background: Color::from_rgb(0.16, 0.18, 0.22),
+    text: Color::from_rgb(0.95, 0.95, 0.95),
+    border: Color::from_rgb(0.35, 0.38, 0.45),
+};
+
+const LIGHT_TILE: TileColors = TileColors {
+    background: Color::from_rgb(0.93, 0.94, 0.96),
+    text: Color::from_rgb(0.1, 0.1, 0.12),
+    border: Color::from_rgb(0.7, 0.72, 0.76),
+};
+
+/// A line of button text as the POS prints it on the key
+fn key_line(button_text: &str) -> String {
+    button_text.chars().take(BUTTON_TEXT_LIMIT).collect()
+}
+
+/// How many characters of a button text don't fit on the key
+fn cut_off(button_text: &str) -> usize {
+    button_text.chars().count().saturating_sub(BUTTON_TEXT_LIMIT)
+}
+
+fn tile<'a, M: 'a>(item: &Item, colors: TileColors) -> Element<'a, M> {
+    // Inactive items stay on the screen greyed out
+    let alpha = if item.not_active { 0.45 } else { 1.0 };
+    let text_color = Color { a: alpha, ..colors.text };
+    let background = Color { a: alpha, ..colors.background };
+
+    let line = |content: String| {
+        text(content)
+            .size(12)
+            .font(Font::MONOSPACE)
+            .wrapping(Wrapping::None)
+            .style(move |_| text::Style { color: Some(text_color) })
+    };
+
+    let mut lines = column![line(key_line(&item.button1))].align_x(iced::Alignment::Center);
+    if let Some(button2) = item.button2.as_deref().filter(|button2| !button2.is_empty()) {
+        lines = lines.push(line(key_line(button2)));
+    }
+    if item.ask_price {
+        lines = lines.push(
+            text("$?")
+                .size(10)
+                .style(move |_| text::Style { color: Some(text_color) })
+        );
+    }
+
+    container(lines)
+        .center_x(Length::Fixed(TILE_WIDTH))
+        .center_y(Length::Fixed(TILE_HEIGHT))
+        .padding(4)
+        .clip(true)
+        .style(move |_| container::Style {
+            background: Some(Background::Color(background)),
+            border: Border { color: colors.border, width: 1.0, radius: 6.0.into() },
+            ..Default::default()
+        })
+        .into()
+}
+
+/// The item's POS key in the dark and light terminal themes, with a note for text that is cut off
+pub fn view<'a, M: 'a>(item: &Item, text_style: TextStyle) -> Element<'a, M> {
+    let mut notes: Vec<String> = Vec::new();
+    let button2 = item.button2.as_deref().unwrap_or_default();
+
+    for (label, button_text) in [("Button 1", item.button1.as_str()), ("Button 2", button2)] {
+        let hidden = cut_off(button_text);
+        if hidden > 0 {
+            notes.push(format!("{} loses {} characters: \"{}\"", label, hidden, button_text.chars().skip(BUTTON_TEXT_LIMIT).collect::<String>()));
+        }
+    }
+
+    column![
+        text("POS Key Preview").size(text_style.caption()).style(Modern::secondary_text()),
+        row![tile(item, DARK_TILE), tile(item, LIGHT_TILE)].spacing(10),
+        column(
+            notes
+                .into_iter()
+                .map(|note| text(note).size(text_style.caption()).style(Modern::red_text()).into())
+                .collect::<Vec<_>>()
+        ),
+    ]
+    .spacing(5)
+    .padding([0, 10])
+    .into()
+}
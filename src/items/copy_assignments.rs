@@ -0,0 +1,279 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use iced::Element;
+use iced::widget::{button, checkbox, column, container, row, text};
+use iced_modern_theme::Modern;
+use rust_decimal::Decimal;
+use crate::data_types::{EntityId, ItemPrice};
+use crate::entity_component::{EntityOption, EntityPicker};
+use crate::{
+    choice_groups::ChoiceGroup,
+    price_levels::PriceLevel,
+    printer_logicals::PrinterLogical,
+    text_style::TextStyle,
+};
+use super::Item;
+use super::edit::Message;
+
+/// The kinds of assignment that can be copied from another item
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssignmentKind {
+    Printers,
+    ChoiceGroups,
+    PriceLevels,
+}
+
+impl AssignmentKind {
+    pub const ALL: &'static [Self] = &[
+        Self::Printers,
+        Self::ChoiceGroups,
+        Self::PriceLevels,
+    ];
+}
+
+impl fmt::Display for AssignmentKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Printers => write!(f, "Printers"),
+            Self::ChoiceGroups => write!(f, "Choice Groups"),
+            Self::PriceLevels => write!(f, "Price Levels"),
+        }
+    }
+}
+
+/// "Copy assignments from…" in the item editor. Only the draft changes, nothing is kept until Save.
+#[derive(Debug, Clone)]
+pub struct AssignmentCopy {
+    pub picker: EntityPicker,
+    pub source: Option<Item>, // Snapshot of the picked item, filled in by the caller
+    pub kinds: Vec<AssignmentKind>,
+    pub merge: bool, // Add to what the draft has instead of replacing it
+}
+
+impl AssignmentCopy {
+    pub fn new(options: Vec<EntityOption>) -> Self {
+        Self {
+            picker: EntityPicker::new(options),
+            source: None,
+            kinds: AssignmentKind::ALL.to_vec(),
+            merge: false,
+        }
+    }
+
+    pub fn toggle_kind(&mut self, kind: AssignmentKind, checked: bool) {
+        self.kinds.retain(|k| *k != kind);
+        if checked {
+            self.kinds.push(kind);
+        }
+    }
+
+    /// Copies the chosen kinds onto the draft. `edited_prices` are the price inputs
+    /// the editor saves from, so copied prices are added there too.
+    pub fn apply(&self, item: &mut Item, edited_prices: &mut Option<Vec<(EntityId, String)>>) {
+        let Some(source) = &self.source else {
+            return;
+        };
+
+        if self.kinds.contains(&AssignmentKind::Printers) {
+            let copied = source.printer_logicals.clone().unwrap_or_default();
+            let mut printers = if self.merge { item.printer_logicals.clone().unwrap_or_default() } else { Vec::new() };
+            let has_primary = printers.iter().any(|(_, is_primary)| *is_primary);
+
+            for (id, is_primary) in copied {
+                if !printers.iter().any(|(existing, _)| *existing == id) {
+                    // A merge keeps the draft's primary printer if it has one
+                    printers.push((id, is_primary && !has_primary));
+                }
+            }
+            item.printer_logicals = (!printers.is_empty()).then_some(printers);
+        }
+
+        if self.kinds.contains(&AssignmentKind::ChoiceGroups) {
+            let copied = source.choice_groups.clone().unwrap_or_default();
+            let groups = if self.merge {
+                let mut groups = item.choice_groups.clone().unwrap_or_default();
+                for (id, _) in copied {
+                    if !groups.iter().any(|(existing, _)| *existing == id) {
+                        let next_sequence = super::next_choice_sequence(&groups);
+                        groups.push((id, next_sequence));
+                    }
+                }
+                groups
+            } else {
+                copied
+            };
+            item.choice_groups = (!groups.is_empty()).then_some(groups);
+        }
+
+        if self.kinds.contains(&AssignmentKind::PriceLevels) {
+            let mut prices = if self.merge { current_prices(item, edited_prices) } else { Vec::new() };
+            for copied in source_prices(source) {
+                if !prices.iter().any(|price| price.price_level_id == copied.price_level_id) {
+                    prices.push(copied);
+                }
+            }
+
+            *edited_prices = Some(prices.iter().map(|price| (price.price_level_id, price.price.to_string())).collect());
+            item.price_levels = (!prices.is_empty()).then(|| prices.iter().map(|price| price.price_level_id).collect());
+            item.item_prices = Some(prices);
+        }
+    }
+
+    /// One line per chosen kind saying what the draft gets, for the confirmation
+    pub fn summary(
+        &self,
+        item: &Item,
+        choice_groups: &BTreeMap<EntityId, ChoiceGroup>,
+        printer_logicals: &BTreeMap<EntityId, PrinterLogical>,
+        price_levels: &BTreeMap<EntityId, PriceLevel>,
+    ) -> Vec<String> {
+        let Some(source) = &self.source else {
+            return Vec::new();
+        };
+
+        AssignmentKind::ALL
+            .iter()
+            .filter(|kind| self.kinds.contains(kind))
+            .map(|kind| {
+                let (current, copied): (Vec<String>, Vec<String>) = match kind {
+                    AssignmentKind::Printers => (
+                        printer_names(item, printer_logicals, !self.merge),
+                        printer_names(source, printer_logicals, !self.merge),
+                    ),
+                    AssignmentKind::ChoiceGroups => (
+                        names(item.choice_groups.iter().flatten().map(|(id, _)| *id), choice_groups, |g| &g.name),
+                        names(source.choice_groups.iter().flatten().map(|(id, _)| *id), choice_groups, |g| &g.name),
+                    ),
+                    AssignmentKind::PriceLevels => (
+                        names(price_level_ids(item), price_levels, |l| &l.name),
+                        names(price_level_ids(source), price_levels, |l| &l.name),
+                    ),
+                };
+
+                if self.merge {
+                    let added: Vec<String> = copied.into_iter().filter(|name| !current.contains(name)).collect();
+                    match (added.is_empty(), current.is_empty()) {
+                        (true, _) => format!("{}: nothing new to add", kind),
+                        (false, true) => format!("{}: adds {}", kind, added.join(", ")),
+                        (false, false) => format!("{}: adds {}, keeps {}", kind, added.join(", "), current.join(", ")),
+                    }
+                } else {
+                    match (current.is_empty(), copied.is_empty()) {
+                        (true, true) => format!("{}: neither item has any", kind),
+                        (true, false) => format!("{}: sets {}", kind, copied.join(", ")),
+                        (false, true) => format!("{}: clears {}", kind, current.join(", ")),
+                        (false, false) => format!("{}: replaces {} with {}", kind, current.join(", "), copied.join(", ")),
+                    }
+                }
+            })
+            .collect()
+    }
+
+    pub fn view<'a>(
+        &'a self,
+        item: &'a Item,
+        choice_groups: &'a BTreeMap<EntityId, ChoiceGroup>,
+        printer_logicals: &'a BTreeMap<EntityId, PrinterLogical>,
+        price_levels: &'a BTreeMap<EntityId, PriceLevel>,
+        text_style: TextStyle,
+    ) -> Element<'a, Message> {
+        let kinds = row(
+            AssignmentKind::ALL.iter().map(|kind| {
+                let kind = *kind;
+                checkbox(kind.to_string(), self.kinds.contains(&kind))
+                    .on_toggle(move |checked| Message::ToggleCopyKind(kind, checked))
+                    .style(Modern::checkbox())
+                    .into()
+            })
+            .collect::<Vec<_>>()
+        )
+        .spacing(15);
+
+        let summary = column(
+            self.summary(item, choice_groups, printer_logicals, price_levels)
+                .into_iter()
+                .map(|line| text(line).size(text_style.caption()).into())
+                .collect::<Vec<_>>()
+        )
+        .spacing(2);
+
+        let can_apply = self.source.is_some() && !self.kinds.is_empty();
+
+        container(
+            column![
+                text("Copy assignments from another item").style(Modern::primary_text()),
+                self.picker.view(
+                    "Source item",
+                    self.source.as_ref().map(|source| source.id),
+                    Message::SelectCopySource,
+                    Message::UpdateCopySourceQuery,
+                    250.0,
+                    text_style,
+                ),
+                kinds,
+                checkbox("Merge with this item's assignments instead of replacing them", self.merge)
+                    .on_toggle(Message::SetCopyMerge)
+                    .style(Modern::checkbox()),
+                summary,
+                row![
+                    button("Copy").on_press_maybe(can_apply.then_some(Message::ApplyCopyAssignments)).style(Modern::primary_button()),
+                    button("Cancel").on_press(Message::CancelCopyAssignments).style(Modern::system_button()),
+                ]
+                .spacing(10),
+            ]
+            .spacing(10)
+        )
+        .padding(10)
+        .style(Modern::card_container())
+        .into()
+    }
+}
+
+/// What the draft is priced at now, with the price inputs taking precedence over what's stored
+pub fn current_prices(item: &Item, edited_prices: &Option<Vec<(EntityId, String)>>) -> Vec<ItemPrice> {
+    let mut prices = item.item_prices.clone().unwrap_or_default();
+    for price in prices.iter_mut() {
+        let edited = edited_prices.iter().flatten().find(|(id, _)| *id == price.price_level_id);
+        if let Some(Ok(edited)) = edited.map(|(_, text)| text.parse::<Decimal>()) {
+            price.price = edited;
+        }
+    }
+    prices
+}
+
+// Older items only list their levels, those are copied at zero
+fn source_prices(source: &Item) -> Vec<ItemPrice> {
+    match &source.item_prices {
+        Some(prices) => prices.clone(),
+        None => source.price_levels.iter().flatten()
+            .map(|id| ItemPrice { price_level_id: *id, price: Decimal::ZERO })
+            .collect(),
+    }
+}
+
+fn price_level_ids(item: &Item) -> Vec<EntityId> {
+    match &item.item_prices {
+        Some(prices) => prices.iter().map(|price| price.price_level_id).collect(),
+        None => item.price_levels.clone().unwrap_or_default(),
+    }
+}
+
+// Merging keeps the draft's primary printer, so only a replace says which one is primary
+fn printer_names(item: &Item, printer_logicals: &BTreeMap<EntityId, PrinterLogical>, show_primary: bool) -> Vec<String> {
+    item.printer_logicals.iter().flatten()
+        .map(|(id, is_primary)| {
+            let name = printer_logicals.get(id).map_or_else(|| format!("#{}", id), |printer| printer.name.clone());
+            if *is_primary && show_primary { format!("{} (primary)", name) } else { name }
+        })
+        .collect()
+}
+
+fn names<T>(
+    ids: impl IntoIterator<Item = EntityId>,
+    entities: &BTreeMap<EntityId, T>,
+    name: impl Fn(&T) -> &String,
+) -> Vec<String> {
+    ids.into_iter()
+        .map(|id| entities.get(&id).map_or_else(|| format!("#{}", id), |entity| name(entity).clone()))
+        .collect()
+}
@@ -0,0 +1,213 @@
+use serde::{Serialize, Deserialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use crate::data_types::{self, EntityId};
+use super::Item;
+
+/// How long deletions are remembered for delta exports by default
+pub const DEFAULT_TOMBSTONE_RETENTION_DAYS: u32 = 90;
+
+/// Record of a deleted item, so a delta export can tell the POS to remove it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ItemTombstone {
+    pub id: EntityId,
+    pub name: String,
+    pub deleted_at: String,
+}
+
+impl ItemTombstone {
+    pub fn new(item: &Item, deleted_at: String) -> Self {
+        Self {
+            id: item.id,
+            name: item.name.clone(),
+            deleted_at,
+        }
+    }
+}
+
+/// Reads "2025-03-01" or "2025-03-01 14:30:00" as local time, or a stored RFC 3339
+/// timestamp, into the UTC instant created_at/updated_at are compared against
+pub fn parse_since(value: &str) -> Result<DateTime<Utc>, String> {
+    let value = value.trim();
+
+    if let Some(timestamp) = data_types::parse_timestamp(value) {
+        return Ok(timestamp);
+    }
+
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .and_then(data_types::local_to_utc)
+        .ok_or_else(|| format!("\"{}\" is not a date, use YYYY-MM-DD", value))
+}
+
+/// Items created or changed after `since`. Items without a timestamp predate
+/// change tracking, so they are included rather than silently left out.
+pub fn modified_since<'a>(items: &'a BTreeMap<EntityId, Item>, since: DateTime<Utc>) -> Vec<&'a Item> {
+    items
+        .values()
+        .filter(|item| {
+            match item.updated_at.as_deref().or(item.created_at.as_deref()) {
+                Some(changed) => after(changed, since),
+                None => true,
+            }
+        })
+        .collect()
+}
+
+pub fn deleted_since<'a>(tombstones: &'a [ItemTombstone], since: DateTime<Utc>) -> Vec<&'a ItemTombstone> {
+    tombstones
+        .iter()
+        .filter(|tombstone| after(&tombstone.deleted_at, since))
+        .collect()
+}
+
+// A timestamp that can't be read is counted as changed, same as a missing one
+fn after(timestamp: &str, since: DateTime<Utc>) -> bool {
+    data_types::parse_timestamp(timestamp).is_none_or(|timestamp| timestamp > since)
+}
+
+/// Drops deletions older than the retention age, zero keeps them forever
+pub fn prune_tombstones(tombstones: &mut Vec<ItemTombstone>, retention_days: u32) {
+    if retention_days == 0 {
+        return;
+    }
+
+    let cutoff = Utc::now() - Duration::days(retention_days as i64);
+    let before = tombstones.len();
+    tombstones.retain(|tombstone| {
+        data_types::parse_timestamp(&tombstone.deleted_at).is_none_or(|deleted_at| deleted_at >= cutoff)
+    });
+
+    if tombstones.len() != before {
+        println!("Pruned {} item deletions older than {} days", before - tombstones.len(), retention_days);
+    }
+}
+
+pub fn deletions_to_csv_string(tombstones: &[ItemTombstone]) -> String {
+    let mut lines = vec!["\"Item ID\",\"Item Name\",\"Deleted At\"".to_string()];
+    lines.extend(tombstones.iter().map(|tombstone| {
+        format!(
+            "{},\"{}\",\"{}\"",
+            tombstone.id,
+            tombstone.name.replace('"', "\"\""),
+            tombstone.deleted_at
+        )
+    }));
+
+    lines.join("\n")
+}
+
+/// The deletions file sits next to the item export, "items.csv" -> "items_deleted.csv"
+pub fn deletions_path(export_path: &Path) -> PathBuf {
+    let stem = export_path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "items".to_string());
+    let extension = export_path
+        .extension()
+        .map(|ext| ext.to_string_lossy().into_owned())
+        .unwrap_or_else(|| "csv".to_string());
+
+    export_path.with_file_name(format!("{}_deleted.{}", stem, extension))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(id: EntityId, updated_at: Option<&str>) -> Item {
+        Item {
+            id,
+            name: format!("Item {}", id),
+            updated_at: updated_at.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    fn items(list: Vec<Item>) -> BTreeMap<EntityId, Item> {
+        list.into_iter().map(|item| (item.id, item)).collect()
+    }
+
+    fn ids(items: Vec<&Item>) -> Vec<EntityId> {
+        items.into_iter().map(|item| item.id).collect()
+    }
+
+    #[test]
+    fn items_from_other_time_zones_compare_by_instant() {
+        let since = parse_since("2025-03-01T13:00:00Z").unwrap();
+        let items = items(vec![
+            item(1, Some("2025-03-01T09:00:00-05:00")), // 14:00 UTC, after
+            item(2, Some("2025-03-01T14:00:00+05:00")), // 09:00 UTC, sorts after as text but is before
+            item(3, Some("2025-03-01T12:59:59Z")),
+        ]);
+
+        assert_eq!(ids(modified_since(&items, since)), vec![1]);
+    }
+
+    #[test]
+    fn an_hour_repeated_by_dst_is_not_missed_or_repeated() {
+        // Both are 01:30 in New York on the night the clocks go back
+        let first = "2025-11-02T05:30:00Z";
+        let second = "2025-11-02T06:30:00Z";
+        let items = items(vec![item(1, Some(first)), item(2, Some(second))]);
+
+        assert_eq!(ids(modified_since(&items, parse_since(first).unwrap())), vec![2]);
+        assert!(modified_since(&items, parse_since(second).unwrap()).is_empty());
+    }
+
+    #[test]
+    fn untracked_and_unreadable_timestamps_are_included() {
+        let since = parse_since("2025-03-01T00:00:00Z").unwrap();
+        let items = items(vec![item(1, None), item(2, Some("yesterday"))]);
+
+        assert_eq!(ids(modified_since(&items, since)), vec![1, 2]);
+    }
+
+    #[test]
+    fn old_local_timestamps_still_compare() {
+        let since = parse_since("2025-03-01").unwrap();
+        let items = items(vec![
+            item(1, Some("2025-02-28 23:59:59")),
+            item(2, Some("2025-03-01 00:00:01")),
+        ]);
+
+        assert_eq!(ids(modified_since(&items, since)), vec![2]);
+    }
+
+    #[test]
+    fn deletions_compare_by_instant() {
+        let since = parse_since("2025-03-01T13:00:00Z").unwrap();
+        let tombstones = vec![
+            ItemTombstone { id: 1, name: "Kept".to_string(), deleted_at: "2025-03-01T14:00:00+05:00".to_string() },
+            ItemTombstone { id: 2, name: "Gone".to_string(), deleted_at: "2025-03-01T09:00:00-05:00".to_string() },
+        ];
+
+        let deleted: Vec<EntityId> = deleted_since(&tombstones, since).into_iter().map(|t| t.id).collect();
+        assert_eq!(deleted, vec![2]);
+    }
+
+    #[test]
+    fn since_is_typed_as_a_local_date() {
+        let midnight = NaiveDate::from_ymd_opt(2025, 3, 1).unwrap().and_hms_opt(0, 0, 0).unwrap();
+
+        assert_eq!(parse_since("2025-03-01"), Ok(data_types::local_to_utc(midnight).unwrap()));
+        assert!(parse_since("March first").is_err());
+    }
+
+    #[test]
+    fn pruning_compares_by_instant() {
+        let recent = (Utc::now() - Duration::days(1)).to_rfc3339();
+        let old = (Utc::now() - Duration::days(100)).to_rfc3339();
+        let mut tombstones = vec![
+            ItemTombstone { id: 1, name: "Recent".to_string(), deleted_at: recent },
+            ItemTombstone { id: 2, name: "Old".to_string(), deleted_at: old },
+        ];
+
+        prune_tombstones(&mut tombstones, DEFAULT_TOMBSTONE_RETENTION_DAYS);
+
+        assert_eq!(tombstones.len(), 1);
+        assert_eq!(tombstones[0].id, 1);
+    }
+}
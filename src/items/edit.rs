@@ -4,24 +4,26 @@ use iced::widget::{
     text, text_input, horizontal_space, scrollable
 };
 use iced_modern_theme::Modern;
-use iced::{Element, Length};
+use iced::{Element, Length, Task};
+use serde::{Serialize, Deserialize};
 use std::collections::BTreeMap;
+use std::fmt;
 use crate::data_types::{EntityId, ItemPrice};
 use crate::data_types;
 use crate::{
     choice_groups::ChoiceGroup,
-    item_groups::ItemGroup,
     price_levels::PriceLevel,
     printer_logicals::PrinterLogical,
-    product_classes::ProductClass,
-    report_categories::ReportCategory,
-    revenue_categories::RevenueCategory,
-    security_levels::SecurityLevel,
-    tax_groups::TaxGroup,
     icon,
+    focus_style,
+    text_style::TextStyle,
 };
 use crate::HotKey;
-use super::{Item, Action, Operation, EditState};
+use super::{Item, Action, Operation, EditState, PickerField, store_fields, button_preview};
+use super::abbreviate::ButtonSlot;
+use super::copy_assignments::AssignmentKind;
+use super::field_status::{self, Requirement};
+use super::price_fill::FillMode;
 
 #[derive(Debug, Clone)]
 pub enum Message {
@@ -30,6 +32,9 @@ pub enum Message {
     UpdateBasePrice(String),
     UpdateButton1(String),
     UpdateButton2(String),
+    SuggestButtonText(ButtonSlot),
+    AcceptButtonSuggestion,
+    DismissButtonSuggestion,
     UpdatePrinterText(String),
 
     // Classifications
@@ -39,6 +44,7 @@ pub enum Message {
     SelectTaxGroup(Option<EntityId>),
     SelectSecurityLevel(Option<EntityId>),
     SelectReportCategory(Option<EntityId>),
+    UpdatePickerQuery(PickerField, String),
     ChoiceGroupSelected(EntityId),
     PriceLevelSelected(EntityId),
     PrinterLogicalSelected(EntityId),
@@ -46,11 +52,20 @@ pub enum Message {
     // Pricing
     UpdateCostAmount(String),
     ToggleAskPrice(bool),
+    UpdateAskPricePrompt(String),
+    UpdateAskPriceSuggested(String),
     ToggleAllowPriceOverride(bool),
     AddPriceLevel(EntityId),
     RemovePriceLevel(EntityId),
     UpdateStorePriceLevel(Option<EntityId>),
     UpdatePrice(EntityId, String),
+    ApplyGroupDefaults,
+    UpdateFillAmount(String),
+    SetFillMode(FillMode),
+    ToggleFillDefault(bool),
+    ApplyFill,
+    ConfirmFill,
+    CancelFill,
 
     // Weight
     ToggleUseWeight(bool),
@@ -82,572 +97,585 @@ pub enum Message {
     UpdateCovers(String),
     UpdateImageId(String),
     UpdateLanguageIsoCode(String),
+    SelectLanguage(store_fields::LanguageChoice),
 
     // Related Items
     AddChoiceGroup(EntityId),
     RemoveChoiceGroup(EntityId),
     AddPrinterLogical(EntityId),
     RemovePrinterLogical(EntityId),
+    OpenCopyAssignments,
+    UpdateCopySourceQuery(String),
+    SelectCopySource(EntityId),
+    ToggleCopyKind(AssignmentKind, bool),
+    SetCopyMerge(bool),
+    ApplyCopyAssignments,
+    CancelCopyAssignments,
+
+    // Sections
+    ToggleSection(EditSection),
+    ExpandAllSections,
+    CollapseAllSections,
 
     Save,
     Cancel,
 }
 
+/// A collapsible part of the edit form, in the order the sections are shown
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EditSection {
+    Basic,
+    Classification,
+    Pricing,
+    PrintersAndChoices,
+    Flags,
+    Kitchen,
+    Identifiers,
+    Store,
+}
+
+impl EditSection {
+    pub const ALL: &'static [Self] = &[
+        Self::Basic,
+        Self::Classification,
+        Self::Pricing,
+        Self::PrintersAndChoices,
+        Self::Flags,
+        Self::Kitchen,
+        Self::Identifiers,
+        Self::Store,
+    ];
+
+    /// The section holding the field a message edits, used to open the section
+    /// when that field fails validation
+    pub fn of_message(message: &Message) -> Option<Self> {
+        match message {
+            Message::UpdateName(_)
+            | Message::UpdateButton1(_)
+            | Message::UpdateButton2(_)
+            | Message::SuggestButtonText(_)
+            | Message::AcceptButtonSuggestion
+            | Message::DismissButtonSuggestion
+            | Message::UpdateCustomerReceipt(_) => Some(Self::Basic),
+            Message::SelectItemGroup(_)
+            | Message::SelectProductClass(_)
+            | Message::SelectRevenueCategory(_)
+            | Message::SelectTaxGroup(_)
+            | Message::SelectSecurityLevel(_)
+            | Message::SelectReportCategory(_)
+            | Message::UpdatePickerQuery(_, _) => Some(Self::Classification),
+            Message::UpdateBasePrice(_)
+            | Message::UpdateCostAmount(_)
+            | Message::PriceLevelSelected(_)
+            | Message::AddPriceLevel(_)
+            | Message::RemovePriceLevel(_)
+            | Message::UpdateStorePriceLevel(_)
+            | Message::UpdatePrice(_, _)
+            | Message::UpdateAskPricePrompt(_)
+            | Message::UpdateAskPriceSuggested(_)
+            | Message::ApplyGroupDefaults
+            | Message::UpdateFillAmount(_)
+            | Message::SetFillMode(_)
+            | Message::ToggleFillDefault(_)
+            | Message::ApplyFill
+            | Message::ConfirmFill
+            | Message::CancelFill => Some(Self::Pricing),
+            Message::ChoiceGroupSelected(_)
+            | Message::PrinterLogicalSelected(_)
+            | Message::AddChoiceGroup(_)
+            | Message::RemoveChoiceGroup(_)
+            | Message::AddPrinterLogical(_)
+            | Message::RemovePrinterLogical(_)
+            | Message::OpenCopyAssignments
+            | Message::UpdateCopySourceQuery(_)
+            | Message::SelectCopySource(_)
+            | Message::ToggleCopyKind(_, _)
+            | Message::SetCopyMerge(_)
+            | Message::ApplyCopyAssignments
+            | Message::CancelCopyAssignments => Some(Self::PrintersAndChoices),
+            Message::ToggleAskPrice(_)
+            | Message::ToggleAllowPriceOverride(_)
+            | Message::ToggleUseWeight(_)
+            | Message::UpdateWeightAmount(_)
+            | Message::ToggleReserved1(_)
+            | Message::TogglePrintOnCheck(_)
+            | Message::ToggleDiscountable(_)
+            | Message::ToggleVoidable(_)
+            | Message::ToggleNotActive(_)
+            | Message::ToggleTaxIncluded(_)
+            | Message::ToggleStockItem(_)
+            | Message::ToggleReserved2(_) => Some(Self::Flags),
+            Message::UpdatePrinterText(_)
+            | Message::UpdateKitchenVideo(_)
+            | Message::UpdateKdsCategory(_)
+            | Message::UpdateKdsCooktime(_)
+            | Message::UpdateKdsDept(_) => Some(Self::Kitchen),
+            Message::UpdateSku(_)
+            | Message::UpdateBarGunCode(_) => Some(Self::Identifiers),
+            Message::UpdateStoreId(_)
+            | Message::UpdateCovers(_)
+            | Message::UpdateImageId(_)
+            | Message::UpdateLanguageIsoCode(_)
+            | Message::SelectLanguage(_) => Some(Self::Store),
+            Message::ToggleSection(_)
+            | Message::ExpandAllSections
+            | Message::CollapseAllSections
+            | Message::Save
+            | Message::Cancel => None,
+        }
+    }
+}
+
+impl fmt::Display for EditSection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Basic => write!(f, "Basic"),
+            Self::Classification => write!(f, "Classification"),
+            Self::Pricing => write!(f, "Pricing"),
+            Self::PrintersAndChoices => write!(f, "Printers & Choices"),
+            Self::Flags => write!(f, "Flags"),
+            Self::Kitchen => write!(f, "Kitchen / KDS"),
+            Self::Identifiers => write!(f, "Identifiers"),
+            Self::Store => write!(f, "Store"),
+        }
+    }
+}
+
+fn form_scroll_id() -> scrollable::Id {
+    scrollable::Id::new("item-edit-form")
+}
+
+/// Scrolls the form to roughly where a section starts. Sections differ in height,
+/// so this lands near the section rather than exactly on it.
+pub fn scroll_to_section<T>(section: EditSection) -> Task<T> {
+    let index = EditSection::ALL.iter().position(|s| *s == section).unwrap_or(0);
+    let y = index as f32 / (EditSection::ALL.len() - 1) as f32;
+    scrollable::snap_to(form_scroll_id(), scrollable::RelativeOffset { x: 0.0, y })
+}
+
 pub fn view<'a>(
     item: &'a Item,
     state: &'a EditState,
-    item_groups: &'a BTreeMap<EntityId, ItemGroup>,
-    tax_groups: &'a BTreeMap<EntityId, TaxGroup>,
-    security_levels: &'a BTreeMap<EntityId, SecurityLevel>,
-    revenue_categories: &'a BTreeMap<EntityId, RevenueCategory>,
-    report_categories: &'a BTreeMap<EntityId, ReportCategory>,
-    product_classes: &'a BTreeMap<EntityId, ProductClass>,
+    collapsed_sections: &'a [EditSection],
+    custom_flag_labels: &'a [String; 2],
     choice_groups: &'a BTreeMap<EntityId, ChoiceGroup>,
     printer_logicals: &'a BTreeMap<EntityId, PrinterLogical>,
     price_levels: &'a BTreeMap<EntityId, PriceLevel>,
+    button_warning: Option<String>,
+    text_style: TextStyle,
 ) -> Element<'a, Message> {
     let header = row![
         button(icon::save().size(14)).on_press(Message::Save).style(Modern::primary_button()),
         button(icon::cancel().size(14)).on_press(Message::Cancel).style(Modern::danger_button()),
         horizontal_space().width(4),
+        text(state.validation_error.clone().unwrap_or_default())
+            .size(text_style.caption())
+            .style(Modern::error_text()),
+        horizontal_space(),
+        button(text("Expand all").size(text_style.caption()))
+            .on_press(Message::ExpandAllSections)
+            .style(Modern::gray_button()),
+        button(text("Collapse all").size(text_style.caption()))
+            .on_press(Message::CollapseAllSections)
+            .style(Modern::gray_button()),
     ]
-    .spacing(10);
+    .spacing(10)
+    .align_y(iced::Alignment::Center);
+
+    // What still needs filling in, required fields first
+    let missing_required = field_status::incomplete(item, Requirement::Required);
+    let missing_recommended = field_status::incomplete(item, Requirement::Recommended);
+    let completeness = row![
+        text("* required").size(text_style.caption()).style(Modern::red_text()),
+        if missing_required.is_empty() {
+            Element::from(row![])
+        } else {
+            text(format!("Missing: {}", missing_required.join(", "))).size(text_style.caption()).style(Modern::red_text()).into()
+        },
+        if missing_recommended.is_empty() {
+            Element::from(row![])
+        } else {
+            text(format!("Recommended but not set: {}", missing_recommended.join(", ")))
+                .size(text_style.caption())
+                .style(Modern::secondary_text())
+                .into()
+        },
+    ]
+    .spacing(15);
+
+    // A section with a field error stays open even if it was collapsed
+    let error_section = state.validation_error.as_ref().and(state.error_section);
 
-    let validation_error = &state.validation_error;
+    // Tab follows the order the sections are built in, so keep them in reading order
+    let sections = EditSection::ALL.iter().map(|section| {
+        let expanded = error_section == Some(*section) || !collapsed_sections.contains(section);
 
-    let basic_info = container(
+        let body = expanded.then(|| match section {
+            EditSection::Basic => basic_section(item, state, button_warning.clone(), text_style),
+            EditSection::Classification => classification_section(item, state, text_style),
+            EditSection::Pricing => pricing_section(item, state, price_levels, text_style),
+            EditSection::PrintersAndChoices => printers_and_choices_section(item, state, choice_groups, printer_logicals, price_levels, text_style),
+            EditSection::Flags => flags_section(item, custom_flag_labels),
+            EditSection::Kitchen => kitchen_section(item),
+            EditSection::Identifiers => identifiers_section(item),
+            EditSection::Store => store_section(item, state, text_style),
+        });
+
+        section_container(*section, expanded, field_status::incomplete_in(item, *section), body, text_style)
+    });
+
+    container(
         column![
-            row![
-                column![
-                    text("Item Name").style(Modern::primary_text()),
-                    text_input("Item Name", &item.name)
-                        .on_input(Message::UpdateName)
-                        .style(Modern::inline_text_input())
-                        .width(420)
-                        .padding(5)
-                ].spacing(10).padding(10),
-                column![
-                    text("Base Price").style(Modern::primary_text()),
-                    text_input(
-                        "Base Price",
-                        &item.default_price.map_or(String::new(), |c| c.to_string())
-                    )
-                    .on_input(Message::UpdateBasePrice)
-                    .style(Modern::inline_text_input())
-                    .width(200)
-                    .padding(5)
-                ].spacing(10).padding(10),
-            ].wrap(),
-            row![
-                column![
-                    text("Button Text 1").style(Modern::primary_text()),
-                    text_input("Button Text 1", &item.button1)
-                        .on_input(Message::UpdateButton1)
-                        .style(Modern::inline_text_input())
-                        .width(200)
-                        .padding(5)
-                ].spacing(10).padding(10),
-                column![
-                    text("Button Text 2").style(Modern::primary_text()),
-                    text_input("Button Text 2", &item.button2.clone().unwrap_or_default())
-                        .on_input(Message::UpdateButton2)
-                        .style(Modern::inline_text_input())
-                        .width(200)
-                        .padding(5)
-                ].spacing(10).padding(10),
-                column![
-                    text("Customer Receipt Text").style(Modern::primary_text()),
-                    text_input(
-                        "Customer Receipt Text", 
-                        &item.customer_receipt
-                    )
-                    .on_input(Message::UpdateCustomerReceipt)
-                    .style(Modern::inline_text_input())
-                    .width(200)
-                    .padding(5)
-                ].spacing(10).padding(10),
-            ],
-            row![
-                column![
-                    text("Kitchen Printer Text").style(Modern::primary_text()),
-                    text_input(
-                        "Kitchen Printer Text", 
-                        &item.printer_text
-                    )
-                    .on_input(Message::UpdatePrinterText)
-                    .style(Modern::inline_text_input())
-                    .width(200)
-                    .padding(5)
-                ].spacing(10).padding(10),
-                column![
-                    text("Kitchen Video Text").style(Modern::primary_text()),
-                    text_input(
-                        "Kitchen Video Text", 
-                        &item.kitchen_video
-                    )
-                    .on_input(Message::UpdateKitchenVideo)
-                    .style(Modern::inline_text_input())
-                    .width(200)
-                    .padding(5)
-                ].spacing(10).padding(10)
-            ].wrap(),
+            column![header, completeness].spacing(5),
+            scrollable(
+                column(sections.collect::<Vec<_>>())
+                .spacing(20)
+            )
+            .id(form_scroll_id())
+            .spacing(10)
+            .width(Length::Fill)
+            .height(Length::Fill)
         ]
+        .spacing(20)
     )
-    .style(Modern::sheet_container())
-    .width(Length::Fill)
-    .padding(10);
+    .padding(10)
+    .into()
+}
 
-    let classifications = container(
-        column![
-            row![
-                column![
-                    text("Item Group").style(Modern::primary_text()),
-                    pick_list(
-                        item_groups.values().collect::<Vec<_>>(),
-                        item.item_group.and_then(|id| item_groups.get(&id)),
-                        |group: &ItemGroup| Message::SelectItemGroup(Some(group.id))
-                    ).width(200).style(Modern::pick_list())
-                ].spacing(10).padding(10),
-                column![
-                    text("Product Class").style(Modern::primary_text()),
-                    pick_list(
-                        product_classes.values().collect::<Vec<_>>(),
-                        item.product_class.and_then(|id| product_classes.get(&id)),
-                        |product_class: &ProductClass| Message::SelectProductClass(Some(product_class.id))
-                    ).width(200).style(Modern::pick_list())
-                ].spacing(10).padding(10),
-                column![
-                    text("Revenue Category").style(Modern::primary_text()),
-                    pick_list(
-                        revenue_categories.values().collect::<Vec<_>>(),
-                        item.revenue_category.and_then(|id| revenue_categories.get(&id)),
-                        |revenue_category: &RevenueCategory| Message::SelectRevenueCategory(Some(revenue_category.id))
-                    ).width(200).style(Modern::pick_list())
-                ].spacing(10).padding(10),
-            ].wrap(),
-            row![
-                column![
-                    text("Tax Group").style(Modern::primary_text()),
-                    pick_list(
-                        tax_groups.values().collect::<Vec<_>>(),
-                        item.tax_group.and_then(|id| tax_groups.get(&id)),
-                        |tax_group: &TaxGroup| Message::SelectTaxGroup(Some(tax_group.id))
-                    ).width(200).style(Modern::pick_list())
-                ].spacing(10).padding(10),
-                column![
-                    text("Security Level").style(Modern::primary_text()),
-                    pick_list(
-                        security_levels.values().collect::<Vec<_>>(),
-                        item.security_level.and_then(|id| security_levels.get(&id)),
-                        |security_level| Message::SelectSecurityLevel(Some(security_level.id))
-                    ).width(200).style(Modern::pick_list())
-                ].spacing(10).padding(10),
-                column![
-                    text("Report Category").style(Modern::primary_text()),
-                    pick_list(
-                        report_categories.values().collect::<Vec<_>>(),
-                        item.report_category.and_then(|id| report_categories.get(&id)),
-                        |report_category: &ReportCategory| Message::SelectReportCategory(Some(report_category.id))
-                    ).width(200).style(Modern::pick_list())
-                ].spacing(10).padding(10),
-            ].wrap(),
-            row![
-                column![
-                    checkbox(
-                        "Sold by weight".to_string(),
-                        item.use_weight
-                    )
-                    .on_toggle(Message::ToggleUseWeight)
-                        .width(200)
-                        .spacing(10)
-                        .style(Modern::checkbox()),
-                ].spacing(10).padding(10),
-
-                column![
-                    row![
-                        horizontal_space().width(5),
-                        text("Tar Weight").style(Modern::primary_text()),
-                    ],
-                    row![
-                        horizontal_space().width(5),
-                        text_input(
-                            "Weight",
-                            &item.weight_amount.to_string()
-                        )
-                        .on_input(Message::UpdateWeightAmount)
-                        .style(Modern::inline_text_input())
-                        .padding(5)
-                        .width(200)
-                    ],
-                ].spacing(10).padding(10),
-            ]
-            .wrap(),
+// A section card with a header that expands or collapses it, and says how many
+// marked fields inside are still empty
+fn section_container<'a>(
+    section: EditSection,
+    expanded: bool,
+    incomplete: usize,
+    body: Option<Element<'a, Message>>,
+    text_style: TextStyle,
+) -> Element<'a, Message> {
+    let title = format!("{} {}", if expanded { "▼" } else { "▶" }, section);
+    let header = button(
+        row![
+            text(title).size(text_style.subheading()),
+            if incomplete == 0 {
+                Element::from(row![])
+            } else {
+                text(format!("{} to fill in", incomplete)).size(text_style.caption()).style(Modern::secondary_text()).into()
+            },
         ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center)
+    )
+    .on_press(Message::ToggleSection(section))
+    .style(Modern::gray_button())
+    .width(Length::Fill);
+
+    container(
+        match body {
+            Some(body) => column![header, body].spacing(5),
+            None => column![header],
+        }
         .width(Length::Fill)
     )
     .style(Modern::sheet_container())
     .width(Length::Fill)
-    .padding(10);
+    .padding(10)
+    .into()
+}
 
-/*     let weight_info = container(
-        column![
-            text("Weight").size(16).style(iced::widget::Modern::primary_text()),
-            checkbox(
-                "Use Weight",
-                item.use_weight
-            ).on_toggle(Message::ToggleUseWeight),
-            row![
-                text("Weight Amount").width(Length::Fixed(150.0)),
-                text_input(
-                    "Weight",
-                    &item.weight_amount.to_string()
-                )
-                .on_input(Message::UpdateWeightAmount)
-                .padding(5)
-            ],
-        ]
-    )
-    .style(Modern::card_container())
-    .width(Length::Fill)
-    .padding(10); */
+// A labelled text input at the standard form width
+// Required fields get a star, recommended ones say so while they're empty
+fn field_label<'a>(label: &'a str, item: &Item, text_style: TextStyle) -> Element<'a, Message> {
+    let marker: Element<'a, Message> = match field_status::info(label) {
+        Some(field) if field.requirement == Requirement::Required => text("*").style(Modern::red_text()).into(),
+        Some(field) if (field.is_missing)(item) => {
+            text("recommended").size(text_style.caption()).style(Modern::secondary_text()).into()
+        }
+        _ => row![].into(),
+    };
 
-    let flags = container(
-        column![
-            column![
-                row![
-                    checkbox(
-                        "Print on Check",
-                        item.print_on_check
-                    )
-                    .on_toggle(Message::TogglePrintOnCheck)
-                    .style(Modern::checkbox())
-                    .spacing(10)
-                    .width(200),
-                    checkbox(
-                        "Discountable",
-                        item.discountable
-                    )
-                    .on_toggle(Message::ToggleDiscountable)
-                    .style(Modern::checkbox())
-                    .spacing(10)
-                    .width(200),
-                    checkbox(
-                        "Voidable",
-                        item.voidable
-                    )
-                    .on_toggle(Message::ToggleVoidable)
-                    .style(Modern::checkbox())
-                    .spacing(10)
-                    .width(200),
-                ].wrap(),
-            ],
-            column![
-                row![
-                    checkbox(
-                        "Active",
-                        item.not_active
-                    )
-                    .on_toggle(Message::ToggleNotActive)
-                    .style(Modern::checkbox())
-                    .spacing(10)
-                    .width(200),
-                    checkbox(
-                        "Tax Included",
-                        item.tax_included
-                    )
-                    .on_toggle(Message::ToggleTaxIncluded)
-                    .style(Modern::checkbox())
-                    .spacing(10)
-                    .width(200),
-                    checkbox(
-                        "Stock Item",
-                        item.stock_item
-                    )
-                    .on_toggle(Message::ToggleStockItem)
-                    .style(Modern::checkbox())
-                    .spacing(10)
-                    .width(200),
-                ].wrap(),
-            ],
-            column![
-                row![
-                    checkbox(
-                        "Prompt for price",
-                        item.ask_price
-                    )
-                    .on_toggle(Message::ToggleAskPrice)
-                    .style(Modern::checkbox())
-                    .spacing(10)
-                    .width(200),
-                    checkbox(
-                        "Allow price override",
-                        item.allow_price_override
-                    )
-                    .on_toggle(Message::ToggleAllowPriceOverride)
-                    .style(Modern::checkbox())
-                    .spacing(10)
-                    .width(200),
-                ].wrap(),
-            ],
+    row![text(label).style(Modern::primary_text()), marker]
+        .spacing(4)
+        .align_y(iced::Alignment::Center)
+        .into()
+}
+
+fn labelled_input<'a>(
+    label: &'a str,
+    placeholder: &'a str,
+    value: &str,
+    on_input: impl Fn(String) -> Message + 'a,
+) -> Element<'a, Message> {
+    column![
+        text(label).style(Modern::primary_text()),
+        text_input(placeholder, value)
+            .on_input(on_input)
+            .style(focus_style::text_input(Modern::inline_text_input()))
+            .width(200)
+            .padding(5)
+    ]
+    .spacing(10)
+    .padding(10)
+    .into()
+}
+
+fn basic_section<'a>(item: &'a Item, state: &'a EditState, button_warning: Option<String>, text_style: TextStyle) -> Element<'a, Message> {
+    // Offered under the button fields, nothing changes until it's accepted
+    let suggestion: Element<'a, Message> = match &state.button_suggestion {
+        Some((slot, suggested)) => row![
+            text(format!("Suggested {}:", slot)).size(text_style.caption()).style(Modern::secondary_text()),
+            button(text(format!("Use \"{}\"", suggested)).size(text_style.caption()))
+                .on_press(Message::AcceptButtonSuggestion)
+                .style(Modern::primary_button()),
+            button(icon::cancel().size(12))
+                .on_press(Message::DismissButtonSuggestion)
+                .style(Modern::system_button()),
         ]
-    )
-    .style(Modern::sheet_container())
-    .width(Length::Fill)
-    .padding(10);
+        .spacing(10)
+        .padding([0, 10])
+        .align_y(iced::Alignment::Center)
+        .into(),
+        None => row![].into(),
+    };
 
-/*     let kitchen_info = container(
-        column![
-/*       row![
-            text("Kitchen Video").style(text::secondary),
-            text_input("Kitchen Video", &item.kitchen_video)
-                .on_input(Message::UpdateKitchenVideo)
-                .padding(5)
-        ], */
+    column![
         row![
-            text("KDS Category").style(text::secondary),
-            text_input("KDS Category", &item.kds_category)
-                .on_input(Message::UpdateKdsCategory)
-                .padding(5)
-        ],
+            column![
+                field_label("Item Name", item, text_style),
+                text_input("Item Name", &item.name)
+                    .on_input(Message::UpdateName)
+                    .style(focus_style::text_input(Modern::inline_text_input()))
+                    .width(420)
+                    .padding(5)
+            ].spacing(10).padding(10),
+        ].wrap(),
         row![
-            text("KDS Cook Time").style(text::secondary),
-            text_input("Cook Time", &item.kds_cooktime.to_string())
-                .on_input(Message::UpdateKdsCooktime)
-                .padding(5)
-        ],
+            labelled_input("Button Text 1", "Button Text 1", &item.button1, Message::UpdateButton1),
+            labelled_input("Button Text 2", "Button Text 2", item.button2.as_deref().unwrap_or_default(), Message::UpdateButton2),
+            labelled_input("Customer Receipt Text", "Customer Receipt Text", &item.customer_receipt, Message::UpdateCustomerReceipt),
+        ].wrap(),
+        button_preview::view(item, text_style),
+        match button_warning {
+            Some(warning) => row![
+                text("⚠").style(Modern::red_text()),
+                text(warning).size(text_style.caption()).style(Modern::red_text()),
+            ]
+            .spacing(5)
+            .padding([0, 10])
+            .into(),
+            None => Element::from(row![]),
+        },
         row![
-            text("KDS Department").style(text::secondary),
-            text_input("Department", &item.kds_dept.to_string())
-                .on_input(Message::UpdateKdsDept)
-                .padding(5)
-        ],
-    ]
-    )
-    .style(Modern::card_container())
-    .width(Length::Fill)
-    .padding(10); */
-
-/*     let store_info = container(
-        column![
-            row![
-                text("Store ID").style(text::secondary),
-                text_input("Store ID", &item.store_id.to_string())
-                    .on_input(Message::UpdateStoreId)
-                    .padding(5)
-            ],
-            row![
-                text("Covers").style(text::secondary),
-                text_input("Covers", &item.covers.to_string())
-                    .on_input(Message::UpdateCovers)
-                    .padding(5)
-            ],
-            row![
-                text("Image ID").style(text::secondary),
-                text_input("Image ID", &item.image_id.to_string())
-                    .on_input(Message::UpdateImageId)
-                    .padding(5)
-            ],
-            row![
-                text("Language ISO Code").style(text::secondary),
-                text_input("Language Code", &item.language_iso_code)
-                    .on_input(Message::UpdateLanguageIsoCode)
-                    .padding(5)
-            ],
+            button(text("Suggest Button 1").size(text_style.caption()))
+                .on_press_maybe((!item.name.trim().is_empty()).then_some(Message::SuggestButtonText(ButtonSlot::Button1)))
+                .style(Modern::gray_button()),
+            button(text("Suggest Button 2").size(text_style.caption()))
+                .on_press_maybe((!item.name.trim().is_empty()).then_some(Message::SuggestButtonText(ButtonSlot::Button2)))
+                .style(Modern::gray_button()),
         ]
-    )
-    .style(Modern::card_container())
-    .width(Length::Fill)
-    .padding(10); */
+        .spacing(10)
+        .padding([0, 10]),
+        suggestion,
+    ]
+    .spacing(5)
+    .into()
+}
 
-    let choice_groups = container(
-        column![
+fn classification_section<'a>(item: &'a Item, state: &'a EditState, text_style: TextStyle) -> Element<'a, Message> {
+    column![
+        row![
             column![
-                text("Choice Groups").style(Modern::primary_text()),
-                iced::widget::horizontal_space().height(5),
-                combo_box(
-                    &state.choice_groups_combo,
-                    "Add Choice Group",
-                    state.choice_group_selection.as_ref(),
-                    |choice_group: ChoiceGroup| Message::ChoiceGroupSelected(choice_group.id)
+                field_label("Item Group", item, text_style),
+                state.pickers.item_group.view(
+                    "Item Group",
+                    item.item_group,
+                    |id| Message::SelectItemGroup(Some(id)),
+                    |query| Message::UpdatePickerQuery(PickerField::ItemGroup, query),
+                    200.0,
+                    text_style,
                 )
-                .input_style(Modern::combo_box())
-                .menu_style(Modern::combo_box_menu())
-                .width(200),
-            ].spacing(5),
-            iced::widget::horizontal_space().height(5),
-            row![ // Display Selected Choice Groups
-                if let Some(selected_groups) = &item.choice_groups {
-                    row(
-                        selected_groups
-                            .iter()
-                            .filter_map(|id| choice_groups.get(&id.0))
-                            .map(|group| {
-                                container(
-                                    button(
-                                        row![
-                                            text(&group.name),
-                                        ].spacing(10)
-                                    )
-                                    .on_press(Message::RemoveChoiceGroup(group.id))
-                                    .style(Modern::gray_button())
-                                    .width(Length::Shrink)
-                                ).padding(5).into()
-                            })
-                            .collect::<Vec<_>>()
-                    ).wrap()
-                } else {
-                    row![].wrap()
-                }
-            ],
-        ],
-    )
-    .style(Modern::sheet_container())
-    .width(Length::Fill)
-    .padding(10);
-
-    let printer_info = container(
-        column![
+            ].spacing(10).padding(10),
             column![
-                text("Printer Logicals").style(Modern::primary_text()),
-                iced::widget::horizontal_space().height(5),
-                combo_box(
-                    &state.printer_logicals_combo,
-                    "Add Printer Logical",
-                    state.printer_logicals_selection.as_ref(),
-                    |printer_logical: PrinterLogical| Message::PrinterLogicalSelected(printer_logical.id)
+                text("Product Class").style(Modern::primary_text()),
+                state.pickers.product_class.view(
+                    "Product Class",
+                    item.product_class,
+                    |id| Message::SelectProductClass(Some(id)),
+                    |query| Message::UpdatePickerQuery(PickerField::ProductClass, query),
+                    200.0,
+                    text_style,
                 )
-                .input_style(Modern::combo_box())
-                .menu_style(Modern::combo_box_menu())
-                .width(200),
-            ].spacing(5),
-            iced::widget::horizontal_space().height(5),
-            row![ // Display Selected Printer Logicals
-            if let Some(selected_logicals) = &item.printer_logicals {
-                row(
-                    selected_logicals
-                        .iter()
-                        .filter_map(|id| printer_logicals.get(&id.0))
-                        .map(|logical| {
-                            container(
-                                button(
-                                    row![
-                                        text(&logical.name),
-                                    ].spacing(10)
-                                )
-                                .on_press(Message::RemovePrinterLogical(logical.id))
-                                .style(Modern::gray_button())
-                                .width(Length::Shrink)
-                            ).padding(5).into()
-                        })
-                        .collect::<Vec<_>>()
-                ).wrap()
-            } else {
-                row![].wrap()
-            }
-            ],
-        ],
-    )
-    .style(Modern::sheet_container())
+            ].spacing(10).padding(10),
+            column![
+                field_label("Revenue Category", item, text_style),
+                state.pickers.revenue_category.view(
+                    "Revenue Category",
+                    item.revenue_category,
+                    |id| Message::SelectRevenueCategory(Some(id)),
+                    |query| Message::UpdatePickerQuery(PickerField::RevenueCategory, query),
+                    200.0,
+                    text_style,
+                )
+            ].spacing(10).padding(10),
+        ].wrap(),
+        row![
+            column![
+                field_label("Tax Group", item, text_style),
+                state.pickers.tax_group.view(
+                    "Tax Group",
+                    item.tax_group,
+                    |id| Message::SelectTaxGroup(Some(id)),
+                    |query| Message::UpdatePickerQuery(PickerField::TaxGroup, query),
+                    200.0,
+                    text_style,
+                )
+            ].spacing(10).padding(10),
+            column![
+                text("Security Level").style(Modern::primary_text()),
+                state.pickers.security_level.view(
+                    "Security Level",
+                    item.security_level,
+                    |id| Message::SelectSecurityLevel(Some(id)),
+                    |query| Message::UpdatePickerQuery(PickerField::SecurityLevel, query),
+                    200.0,
+                    text_style,
+                )
+            ].spacing(10).padding(10),
+            column![
+                field_label("Report Category", item, text_style),
+                state.pickers.report_category.view(
+                    "Report Category",
+                    item.report_category,
+                    |id| Message::SelectReportCategory(Some(id)),
+                    |query| Message::UpdatePickerQuery(PickerField::ReportCategory, query),
+                    200.0,
+                    text_style,
+                )
+            ].spacing(10).padding(10),
+        ].wrap(),
+    ]
     .width(Length::Fill)
-    .padding(10);
-
-// temp variables for pricing
-let assigned_price_level_ids = if let Some(item_prices) = &item.item_prices {
-    // Extract price_level_ids from item_prices
-    item_prices.iter().map(|price| price.price_level_id).collect::<Vec<_>>()
-} else {
-    item.price_levels.clone().unwrap_or_default()
-};
+    .into()
+}
+
+fn pricing_section<'a>(
+    item: &'a Item,
+    state: &'a EditState,
+    price_levels: &'a BTreeMap<EntityId, PriceLevel>,
+    text_style: TextStyle,
+) -> Element<'a, Message> {
+    let assigned_price_level_ids = if let Some(item_prices) = &item.item_prices {
+        // Extract price_level_ids from item_prices
+        item_prices.iter().map(|price| price.price_level_id).collect::<Vec<_>>()
+    } else {
+        item.price_levels.clone().unwrap_or_default()
+    };
+
+    let available_price_levels: Vec<PriceLevel> = price_levels.iter()
+        .filter(|(id, _)| !assigned_price_level_ids.contains(id))
+        .map(|(_, price_level)| price_level.clone())
+        .collect();
+
+    // The edited price if there is one, otherwise what is stored
+    let current_price = move |price_level: &PriceLevel, stored: String| -> String {
+        state.prices.as_ref()
+            .and_then(|all_prices| {
+                all_prices.iter()
+                    .find(|(id, _)| *id == price_level.id)
+                    .map(|(_, price_str)| price_str.clone())
+            })
+            .unwrap_or(stored)
+    };
+
+    let price_row = move |price_level: &'a PriceLevel, price: String| -> Element<'a, Message> {
+        row![
+            text(&price_level.name).width(100),
+            text_input("Price", &price)
+                .on_input(|price|
+                    Message::UpdatePrice(price_level.id, price)
+                )
+                .style(focus_style::text_input(Modern::inline_text_input()))
+                .width(125),
+            horizontal_space().width(10),
+            button(icon::trash().size(14))
+                .on_press(Message::RemovePriceLevel(price_level.id))
+                .style(Modern::danger_button()),
+            horizontal_space().width(10),
+        ].align_y(iced::Alignment::Center).into()
+    };
+
+    let assigned_prices = if let Some(item_prices) = &item.item_prices {
+        row(
+            item_prices
+                .iter()
+                .filter_map(|price| {
+                    // Get the price level from the id
+                    price_levels.get(&price.price_level_id).map(|level| (price, level))
+                })
+                .map(|(item_price, price_level)| {
+                    let price = current_price(price_level, price_level.format_price(item_price.price));
+                    price_row(price_level, price)
+                })
+                .collect::<Vec<_>>()
+        ).width(900).wrap()
+    } else if let Some(selected_prices) = &item.price_levels {
+        // Fall back to price_levels if item_prices is missing
+        row(
+            selected_prices
+                .iter()
+                .filter_map(|id| price_levels.get(id))
+                .map(|price_level| {
+                    let price = current_price(price_level, String::new());
+                    price_row(price_level, price)
+                })
+                .collect::<Vec<_>>()
+        ).width(900).wrap()
+    } else {
+        row![button(text("No Price Levels Assigned")).style(Modern::gray_button())].wrap()
+    };
 
-let available_price_levels: Vec<PriceLevel> = price_levels.iter()
-    .filter(|(id, _)| !assigned_price_level_ids.contains(id))
-    .map(|(_, price_level)| price_level.clone())
-    .collect();
+    // Only asked for when the till prompts for the price
+    let ask_price: Element<'a, Message> = if item.ask_price {
+        row![
+            labelled_input(
+                "Till prompt",
+                "e.g. Enter market price",
+                item.ask_price_prompt.as_deref().unwrap_or_default(),
+                Message::UpdateAskPricePrompt,
+            ),
+            labelled_input(
+                "Suggested price",
+                "Suggested price",
+                &item.ask_price_suggested.map_or(String::new(), |c| c.to_string()),
+                Message::UpdateAskPriceSuggested,
+            ),
+        ].wrap().into()
+    } else {
+        row![].into()
+    };
 
-let pricing = container(
     column![
+        row![
+            column![
+                field_label("Base Price", item, text_style),
+                text_input("Base Price", &item.default_price.map_or(String::new(), |c| c.to_string()))
+                    .on_input(Message::UpdateBasePrice)
+                    .style(focus_style::text_input(Modern::inline_text_input()))
+                    .width(200)
+                    .padding(5)
+            ]
+            .spacing(10)
+            .padding(10),
+            labelled_input(
+                "Cost",
+                "Cost",
+                &item.cost_amount.map_or(String::new(), |c| c.to_string()),
+                Message::UpdateCostAmount,
+            ),
+        ].wrap(),
+        ask_price,
         text("Price Levels").style(Modern::primary_text()),
-        iced::widget::horizontal_space().height(10),
-
-        row![ // Display Selected Price Levels
-        if let Some(item_prices) = &item.item_prices {
-            row(
-                item_prices
-                    .iter()
-                    .filter_map(|price| {
-                        // Get the price level from the id
-                        price_levels.get(&price.price_level_id).map(|level| (price, level))
-                    })
-                    .map(|(item_price, price_level)| {
-                        // Start with the actual price from item_prices
-                        let display_price = item_price.price.to_string();
-                        
-                        // Look up any updated price from state.prices
-                        let current_price = state.prices.as_ref()
-                            .and_then(|all_prices| {
-                                all_prices.iter()
-                                    .find(|(id, _)| *id == price_level.id)
-                                    .map(|(_, price_str)| price_str.as_str())
-                            })
-                            .unwrap_or(&display_price);
-
-                        row![
-                            text(&price_level.name).width(100),
-                            text_input("Price", current_price)
-                                .on_input(|price|
-                                    Message::UpdatePrice(price_level.id, price)
-                                )
-                                .style(Modern::inline_text_input())
-                                .width(125),
-                                horizontal_space().width(10),
-                            button(icon::trash().size(14))
-                                .on_press(Message::RemovePriceLevel(price_level.id))
-                                .style(Modern::danger_button()),
-                            horizontal_space().width(10),
-                        ].align_y(iced::Alignment::Center).into()
-                    })
-                    .collect::<Vec<_>>()
-            ).width(900).wrap()
-        } else if let Some(selected_prices) = &item.price_levels {
-            // Fall back to price_levels if item_prices is missing
-            row(
-                selected_prices
-                    .iter()
-                    .filter_map(|id| price_levels.get(id))
-                    .map(|price_level| {
-                        // ... existing code for price_levels ...
-                        // This is a fallback, ideally you want to migrate to using item_prices
-                        
-                        let current_price = state.prices.as_ref()
-                        .and_then(|all_prices| {
-                            all_prices.iter()
-                                .find(|(id, _)| *id == price_level.id)
-                                .map(|(_, price_str)| price_str.as_str())
-                        })
-                        .unwrap_or("");
-
-                        row![
-                            text(&price_level.name).width(100),
-                            text_input("Price", current_price)
-                                .on_input(|price|
-                                    Message::UpdatePrice(price_level.id, price)
-                                )
-                                .style(Modern::inline_text_input())
-                                .width(125),
-                                horizontal_space().width(10),
-                            button(icon::trash().size(14))
-                                .on_press(Message::RemovePriceLevel(price_level.id))
-                                .style(Modern::danger_button()),
-                            horizontal_space().width(10),
-                        ].align_y(iced::Alignment::Center).into()
-                    })
-                    .collect::<Vec<_>>()
-            ).width(900).wrap()
-        } else {
-            row![button(text("No Price Levels Assigned")).style(Modern::gray_button())].wrap()
-        }
-        ],
-        iced::widget::horizontal_space().height(5),
+        horizontal_space().height(10),
+        assigned_prices,
+        horizontal_space().height(5),
         row![
             pick_list(
                 available_price_levels,
@@ -656,114 +684,214 @@ let pricing = container(
             )
             .width(100)
             .placeholder("Add Price Levels")
-            .style(Modern::pick_list())
+            .style(Modern::pick_list()),
+            button(text("Apply group defaults").size(text_style.caption()))
+                .on_press_maybe(item.item_group.map(|_| Message::ApplyGroupDefaults))
+                .style(Modern::gray_button()),
+        ].spacing(5).align_y(iced::Alignment::Center),
+        horizontal_space().height(5),
+        state.price_fill.view(item, &state.prices, price_levels, text_style),
+    ]
+    .into()
+}
+
+fn printers_and_choices_section<'a>(
+    item: &'a Item,
+    state: &'a EditState,
+    choice_groups: &'a BTreeMap<EntityId, ChoiceGroup>,
+    printer_logicals: &'a BTreeMap<EntityId, PrinterLogical>,
+    price_levels: &'a BTreeMap<EntityId, PriceLevel>,
+    text_style: TextStyle,
+) -> Element<'a, Message> {
+    // A removable chip for each assigned entity
+    let chip = |name: &'a str, on_remove: Message| -> Element<'a, Message> {
+        container(
+            button(row![text(name)].spacing(10))
+                .on_press(on_remove)
+                .style(Modern::gray_button())
+                .width(Length::Shrink)
+        ).padding(5).into()
+    };
+
+    let selected_groups = item.choice_groups.iter()
+        .flatten()
+        .filter_map(|id| choice_groups.get(&id.0))
+        .map(|group| chip(&group.name, Message::RemoveChoiceGroup(group.id)))
+        .collect::<Vec<_>>();
+
+    let selected_logicals = item.printer_logicals.iter()
+        .flatten()
+        .filter_map(|id| printer_logicals.get(&id.0))
+        .map(|logical| chip(&logical.name, Message::RemovePrinterLogical(logical.id)))
+        .collect::<Vec<_>>();
+
+    let copy_assignments: Element<'a, Message> = match &state.assignment_copy {
+        Some(copy) => copy.view(item, choice_groups, printer_logicals, price_levels, text_style),
+        None => button(text("Copy assignments from…").size(text_style.caption()))
+            .on_press(Message::OpenCopyAssignments)
+            .style(Modern::gray_button())
+            .into(),
+    };
+
+    column![
+        copy_assignments,
+        horizontal_space().height(10),
+        column![
+            text("Choice Groups").style(Modern::primary_text()),
+            horizontal_space().height(5),
+            combo_box(
+                &state.choice_groups_combo,
+                "Add Choice Group",
+                state.choice_group_selection.as_ref(),
+                |choice_group: ChoiceGroup| Message::ChoiceGroupSelected(choice_group.id)
+            )
+            .input_style(focus_style::text_input(Modern::combo_box()))
+            .menu_style(Modern::combo_box_menu())
+            .width(200),
         ].spacing(5),
-    ],
-)
-.style(Modern::sheet_container())
-.width(Length::Fill)
-.padding(10);
+        row(selected_groups).wrap(),
+        horizontal_space().height(10),
+        column![
+            text("Printer Logicals").style(Modern::primary_text()),
+            horizontal_space().height(5),
+            combo_box(
+                &state.printer_logicals_combo,
+                "Add Printer Logical",
+                state.printer_logicals_selection.as_ref(),
+                |printer_logical: PrinterLogical| Message::PrinterLogicalSelected(printer_logical.id)
+            )
+            .input_style(focus_style::text_input(Modern::combo_box()))
+            .menu_style(Modern::combo_box_menu())
+            .width(200),
+        ].spacing(5),
+        row(selected_logicals).wrap(),
+    ]
+    .spacing(5)
+    .into()
+}
 
-/*  //updating to use item_prices instead of price_levels
+fn flags_section<'a>(item: &'a Item, custom_flag_labels: &'a [String; 2]) -> Element<'a, Message> {
+    let flag = |label: &'a str, checked: bool, on_toggle: fn(bool) -> Message| {
+        checkbox(label, checked)
+            .on_toggle(on_toggle)
+            .style(Modern::checkbox())
+            .spacing(10)
+            .width(200)
+    };
 
-    // temp variables for pricing,
-    let assigned_price_level_ids = item.price_levels.clone().unwrap_or_default();
-    let available_price_levels: Vec<PriceLevel> = price_levels.iter().filter(|(id, _)| !assigned_price_level_ids.contains(id) ).map(|(_, price_level)| price_level.clone()).collect();
+    column![
+        row![
+            flag("Print on Check", item.print_on_check, Message::TogglePrintOnCheck),
+            flag("Discountable", item.discountable, Message::ToggleDiscountable),
+            flag("Voidable", item.voidable, Message::ToggleVoidable),
+        ].wrap(),
+        row![
+            flag("Active", item.not_active, Message::ToggleNotActive),
+            flag("Tax Included", item.tax_included, Message::ToggleTaxIncluded),
+            flag("Stock Item", item.stock_item, Message::ToggleStockItem),
+        ].wrap(),
+        row![
+            flag("Prompt for price", item.ask_price, Message::ToggleAskPrice),
+            flag("Allow price override", item.allow_price_override, Message::ToggleAllowPriceOverride),
+        ].wrap(),
+        // Reserved in the POS export, named in Settings
+        row![
+            flag(&custom_flag_labels[0], item.reserved1, Message::ToggleReserved1),
+            flag(&custom_flag_labels[1], item.reserved2, Message::ToggleReserved2),
+        ].wrap(),
+        row![
+            column![
+                flag("Sold by weight", item.use_weight, Message::ToggleUseWeight),
+            ].spacing(10).padding(10),
+            labelled_input("Tar Weight", "Weight", &item.weight_amount.to_string(), Message::UpdateWeightAmount),
+        ]
+        .align_y(iced::Alignment::End)
+        .wrap(),
+    ]
+    .spacing(10)
+    .into()
+}
 
-    let pricing = container(
-        column![
-            text("Price Levels").style(Modern::primary_text()),
-            iced::widget::horizontal_space().height(10),
-
-            row![ // Display Selected Price Levels
-            if let Some(selected_prices) = &item.price_levels {
-                row(
-                    selected_prices
-                        .iter()
-                        .filter_map(|id| price_levels.get(id))
-                        .map( |price_level| {
-                            // Look up the current price from state.prices using the price_level id.
-                            // If no matching price is found, default to an empty string.
-                            let current_price = state.prices.as_ref()
-                            .and_then(|all_prices| {
-                                all_prices.iter()
-                                    .find(|(id, _)| *id == price_level.id)
-                                    .map(|(_, price_str)| price_str.as_str())
-                            })
-                            .unwrap_or("");
-
-                            row![
-                                text(&price_level.name).width(100),
-                                text_input("Price", current_price)
-                                    .on_input( |price|
-                                        Message::UpdatePrice(price_level.id, price)
-                                    )
-                                    .style(Modern::inline_text_input())
-                                    .width(125),
-                                    horizontal_space().width(10),
-                                button(icon::trash().size(14)).on_press(Message::RemovePriceLevel(price_level.id)).style(Modern::danger_button()),
-                                horizontal_space().width(10),
-                            ].align_y(iced::Alignment::Center).into()
-                        })
-                        .collect::<Vec<_>>()
-                ).width(900).wrap()
-            } else {
-                row![].wrap()
-            }
-            ],
-            iced::widget::horizontal_space().height(5),
-            row![
-                pick_list(
-                    available_price_levels,
-                    None::<PriceLevel>,
-                    |price_level: PriceLevel| Message::PriceLevelSelected(price_level.id)
-                )
-                .width(100)
-                .placeholder("Add Price Levels")
-                .style(Modern::pick_list())
-            ].spacing(5),
-        ],
-    )
-    .style(Modern::sheet_container())
-    .width(Length::Fill)
-    .padding(10);
+fn kitchen_section(item: &Item) -> Element<'_, Message> {
+    column![
+        row![
+            labelled_input("Kitchen Printer Text", "Kitchen Printer Text", &item.printer_text, Message::UpdatePrinterText),
+            labelled_input("Kitchen Video Text", "Kitchen Video Text", &item.kitchen_video, Message::UpdateKitchenVideo),
+        ].wrap(),
+        row![
+            labelled_input("KDS Category", "KDS Category", &item.kds_category, Message::UpdateKdsCategory),
+            labelled_input("KDS Cook Time", "Cook Time", &item.kds_cooktime.to_string(), Message::UpdateKdsCooktime),
+            labelled_input("KDS Department", "Department", &item.kds_dept.to_string(), Message::UpdateKdsDept),
+        ].wrap(),
+    ]
+    .into()
+}
 
-     */
+fn identifiers_section(item: &Item) -> Element<'_, Message> {
+    row![
+        labelled_input("SKU", "SKU", item.sku.as_deref().unwrap_or_default(), Message::UpdateSku),
+        labelled_input("Bar Gun Code", "Bar Gun Code", item.bar_gun_code.as_deref().unwrap_or_default(), Message::UpdateBarGunCode),
+    ]
+    .wrap()
+    .into()
+}
 
-/*     // Validation Error
-    if let Some(error) = validation_error {
-        container(
-            text(error.to_string()).style(text::danger)
-        )
-        .padding(10)
+fn store_section<'a>(item: &'a Item, state: &'a EditState, text_style: TextStyle) -> Element<'a, Message> {
+    let selected_language = if state.language_other {
+        Some(store_fields::LanguageChoice::Other)
     } else {
-        container(text("".to_string()))
-    } */
+        store_fields::language_choice(&item.language_iso_code)
+    };
 
-    container(
+    row![
         column![
-            header,
-            scrollable(
-                column![
-                    basic_info,
-                    classifications,
-                    //weight_info,
-                    flags,
-                    //kitchen_info,
-                    //store_info,
-                    choice_groups,
-                    printer_info,
-                    pricing,
-                ]
-                .spacing(20)
+            text("Store ID").style(Modern::primary_text()),
+            text_input("Store ID", &item.store_id.to_string())
+                .on_input(Message::UpdateStoreId)
+                .style(focus_style::text_input(Modern::inline_text_input()))
+                .font(text_style.data_font())
+                .width(200)
+                .padding(5)
+        ].spacing(10).padding(10),
+        column![
+            text(format!("Covers (0-{})", store_fields::MAX_COVERS)).style(Modern::primary_text()),
+            text_input("Covers", &item.covers.to_string())
+                .on_input(Message::UpdateCovers)
+                .style(focus_style::text_input(Modern::inline_text_input()))
+                .font(text_style.data_font())
+                .width(200)
+                .padding(5),
+            text("Guests a combo or banquet item seats, for cover reports. 0 for regular items.")
+                .size(text_style.caption())
+                .style(Modern::secondary_text())
+                .width(200),
+        ].spacing(10).padding(10),
+        labelled_input("Image ID", "Image ID", &item.image_id.to_string(), Message::UpdateImageId),
+        column![
+            text("Language").style(Modern::primary_text()),
+            pick_list(
+                store_fields::language_choices(),
+                selected_language,
+                Message::SelectLanguage
             )
-            .spacing(10)
-            .width(Length::Fill)
-            .height(Length::Fill)
-        ]
-        .spacing(20)
-    )
-    .padding(10)
+            .placeholder("No language")
+            .style(Modern::pick_list())
+            .width(200),
+        ].spacing(10).padding(10),
+        if state.language_other {
+            column![
+                text("Language ISO Code").style(Modern::primary_text()),
+                text_input("e.g. en", &item.language_iso_code)
+                    .on_input(Message::UpdateLanguageIsoCode)
+                    .style(focus_style::text_input(Modern::validated_text_input(!store_fields::is_valid_language_code(&item.language_iso_code))))
+                    .width(200)
+                    .padding(5)
+            ].spacing(10).padding(10)
+        } else {
+            column![]
+        },
+    ]
+    .wrap()
     .into()
 }
 
@@ -1,201 +1,108 @@
 use crate::items::{Item, ItemPrice};
+use crate::items::export_layout::{ExportColumn, ExportField, ExportProfile};
 use rust_decimal::Decimal;
 use std::path::{Path, PathBuf};
 
-pub fn item_to_export_string(item: &Item) -> String {
-
-    // Pre-allocate buffer to avoid reallocations
-    let mut result = String::with_capacity(1024);
-    
-    // Helper function to wrap text in quotes and append to buffer
-    fn append_quoted(buffer: &mut String, text: &str) {
-        buffer.push('"');
-        buffer.push_str(text);
-        buffer.push('"');
+/// One export line per item, in the order given, with the profile's columns
+pub fn items_to_export_string<'a>(items: impl IntoIterator<Item = &'a Item>, profile: &ExportProfile) -> String {
+    let columns: Vec<ExportColumn> = profile
+        .columns()
+        .into_iter()
+        .filter(|column| column.included)
+        .collect();
+
+    let mut lines = Vec::new();
+    if profile.include_header {
+        lines.push(
+            columns
+                .iter()
+                .map(|column| format!("\"{}\"", column.header))
+                .collect::<Vec<_>>()
+                .join(",")
+        );
     }
-    
-    // add_edit_delete
-    result.push_str("\"A\"");
-    result.push(',');
-    
-    // item_id
-    result.push_str(&item.id.to_string());
-    result.push(',');
-    
-    // item_name
-    append_quoted(&mut result, &item.name);
-    result.push(',');
-    
-    // button_1
-    append_quoted(&mut result, &item.button1);
-    result.push(',');
-    
-    // button_2
-    append_quoted(&mut result, &item.button2.as_deref().unwrap_or(""));
-    result.push(',');
-    
-    // printer_text
-    append_quoted(&mut result, &item.printer_text);
-    result.push(',');
-    
-    // prices
-    let prices = prepare_item_prices(item.default_price, item.item_prices.clone());
-    result.push_str(&prices);
-    result.push(',');
-    
-    // product_class
-    result.push_str(&item.product_class.unwrap_or_default().to_string());
-    result.push(',');
-    
-    // revenue_category
-    result.push_str(&item.revenue_category.unwrap_or_default().to_string());
-    result.push(',');
-    
-    // tax_group
-    result.push_str(&item.tax_group.unwrap_or_default().to_string());
-    result.push(',');
-    
-    // security_level
-    result.push_str(&item.security_level.unwrap_or_default().to_string());
-    result.push(',');
-    
-    // report_category
-    result.push_str(&item.report_category.unwrap_or_default().to_string());
-    result.push(',');
-    
-    // weight_flag
-    result.push_str(if item.use_weight { "1" } else { "0" });
-    result.push(',');
-    
-    // weight_tar
-    result.push_str(&item.weight_amount.to_string());
-    result.push(',');
-    
-    // sku
-    result.push_str(&item.sku.as_deref().unwrap_or_default());
-    result.push(',');
-    
-    // bar_gun_code
-    result.push_str(&item.bar_gun_code.as_deref().unwrap_or_default());
-    result.push(',');
-    
-    // cost_amount
-    let cost_str = prepare_item_cost(item.cost_amount);
-    result.push_str(&cost_str);
-    result.push(',');
-    
-    // reserved1
-    result.push('0');
-    result.push(',');
-    
-    // ask_price
-    result.push_str(if item.ask_price { "1" } else { "0" });
-    result.push(',');
-    
-    // print_on_check
-    result.push_str(if item.print_on_check { "1" } else { "0" });
-    result.push(',');
-    
-    // discountable
-    result.push_str(if item.discountable { "1" } else { "0" });
-    result.push(',');
-    
-    // voidable
-    result.push_str(if item.voidable { "1" } else { "0" });
-    result.push(',');
-    
-    // not_active
-    result.push_str(if item.not_active { "1" } else { "0" });
-    result.push(',');
-    
-    // tax_included
-    result.push_str(if item.tax_included { "1" } else { "0" });
-    result.push(',');
-    
-    // item_group_id
-    result.push_str(&item.item_group.unwrap_or_default().to_string());
-    result.push(',');
-    
-    // receipt_text
-    append_quoted(&mut result, &item.customer_receipt);
-    result.push(',');
-    
-    // allow_price_override
-    result.push_str(if item.allow_price_override { "1" } else { "0" });
-    result.push(',');
-    
-    // reserved2
-    result.push('0');
-    result.push(',');
-    
-    // choice_groups
-    let choice_str = prepare_choice_groups(item.choice_groups.clone());
-    result.push_str(&choice_str);
-    result.push(',');
-    
-    // printer_logicals
-    let printer_str = prepare_kitchen_printers(item.printer_logicals.clone());
-    result.push_str(&printer_str);
-    result.push(',');
-    
-    // covers
-    result.push_str(&item.covers.to_string());
-    result.push(',');
-    
-    // store_id
-    result.push_str(&item.store_id.to_string());
-    result.push(',');
-    
-    // kds_text
-    append_quoted(&mut result, &item.kitchen_video);
-    result.push(',');
-    
-    // kds_dept
-    result.push_str(&item.kds_dept.to_string());
-    result.push(',');
-    
-    // kds_category
-    result.push_str(item.kds_category.as_str());
-    result.push(',');
-    
-    // kds_time
-    result.push_str(&item.kds_cooktime.to_string());
-    result.push(',');
-    
-    // store_price
-    result.push_str("{}");
-    result.push(',');
-    
-    // image_id
-    result.push_str(&item.image_id.to_string());
-    result.push(',');
-    
-    // stock_item_flag
-    result.push_str(if item.stock_item { "1" } else { "0" });
-    result.push(',');
-    
-    // lang_iso
-    if !item.language_iso_code.as_str().is_empty() {
-        result.push_str(item.language_iso_code.as_str());
-    } else {
-        result.push_str("\"\"");
-    }
-    result.push(',');
-    
-    // reserved3
-    result.push('0');
-    result.push(',');
-    
-    // reserved4
-    result.push_str("\"\"");
-    
+    lines.extend(items.into_iter().map(|item| item_to_export_string(item, &columns)));
+
+    lines.join("\n")
+}
+
+pub fn item_to_export_string(item: &Item, columns: &[ExportColumn]) -> String {
+    let result = columns
+        .iter()
+        .filter(|column| column.included)
+        .map(|column| field_value(item, column.field))
+        .collect::<Vec<_>>()
+        .join(",");
+
     // Debug print (optional)
     println!("One Item's Export format:");
     println!("{}", &result);
-    
+
     result
 }
 
+fn field_value(item: &Item, field: ExportField) -> String {
+    // Wraps text in quotes
+    fn quoted(text: &str) -> String {
+        format!("\"{}\"", text)
+    }
+
+    fn flag(value: bool) -> String {
+        if value { "1" } else { "0" }.to_string()
+    }
+
+    match field {
+        ExportField::AddEditDelete => quoted("A"),
+        ExportField::ItemId => item.id.to_string(),
+        ExportField::ItemName => quoted(&item.name),
+        ExportField::Button1 => quoted(&item.button1),
+        ExportField::Button2 => quoted(item.button2.as_deref().unwrap_or("")),
+        ExportField::PrinterText => quoted(&item.printer_text),
+        ExportField::Prices => prepare_item_prices(item.default_price, item.item_prices.clone()),
+        ExportField::ProductClass => item.product_class.unwrap_or_default().to_string(),
+        ExportField::RevenueCategory => item.revenue_category.unwrap_or_default().to_string(),
+        ExportField::TaxGroup => item.tax_group.unwrap_or_default().to_string(),
+        ExportField::SecurityLevel => item.security_level.unwrap_or_default().to_string(),
+        ExportField::ReportCategory => item.report_category.unwrap_or_default().to_string(),
+        ExportField::WeightFlag => flag(item.use_weight),
+        ExportField::WeightTare => item.weight_amount.to_string(),
+        ExportField::Sku => item.sku.clone().unwrap_or_default(),
+        ExportField::BarGunCode => item.bar_gun_code.clone().unwrap_or_default(),
+        ExportField::CostAmount => prepare_item_cost(item.cost_amount),
+        ExportField::Reserved1 | ExportField::Reserved2 | ExportField::Reserved3 => "0".to_string(),
+        ExportField::AskPrice => flag(item.ask_price),
+        ExportField::PrintOnCheck => flag(item.print_on_check),
+        ExportField::Discountable => flag(item.discountable),
+        ExportField::Voidable => flag(item.voidable),
+        ExportField::NotActive => flag(item.not_active),
+        ExportField::TaxIncluded => flag(item.tax_included),
+        ExportField::ItemGroup => item.item_group.unwrap_or_default().to_string(),
+        ExportField::ReceiptText => quoted(&item.customer_receipt),
+        ExportField::AllowPriceOverride => flag(item.allow_price_override),
+        ExportField::ChoiceGroups => prepare_choice_groups(item.choice_groups.clone()),
+        ExportField::PrinterLogicals => prepare_kitchen_printers(item.printer_logicals.clone()),
+        ExportField::Covers => item.covers.to_string(),
+        ExportField::StoreId => item.store_id.to_string(),
+        ExportField::KdsText => quoted(&item.kitchen_video),
+        ExportField::KdsDept => item.kds_dept.to_string(),
+        ExportField::KdsCategory => item.kds_category.clone(),
+        ExportField::KdsTime => item.kds_cooktime.to_string(),
+        ExportField::StorePrice => "{}".to_string(),
+        ExportField::ImageId => item.image_id.to_string(),
+        ExportField::StockItem => flag(item.stock_item),
+        ExportField::LanguageIso => {
+            if item.language_iso_code.is_empty() {
+                quoted("")
+            } else {
+                item.language_iso_code.clone()
+            }
+        }
+        ExportField::Reserved4 => quoted(""),
+        ExportField::AskPricePrompt => quoted(item.ask_price_prompt.as_deref().unwrap_or_default()),
+        ExportField::AskPriceSuggested => item.ask_price_suggested.map(|price| price.to_string()).unwrap_or_default(),
+    }
+}
+
 
 
 
@@ -0,0 +1,285 @@
+use serde::{Serialize, Deserialize};
+use std::fmt;
+
+/// A column the item CSV export can write. New variants must also go in `ALL`,
+/// saved layouts pick them up as excluded columns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExportField {
+    AddEditDelete,
+    ItemId,
+    ItemName,
+    Button1,
+    Button2,
+    PrinterText,
+    Prices,
+    ProductClass,
+    RevenueCategory,
+    TaxGroup,
+    SecurityLevel,
+    ReportCategory,
+    WeightFlag,
+    WeightTare,
+    Sku,
+    BarGunCode,
+    CostAmount,
+    Reserved1,
+    AskPrice,
+    PrintOnCheck,
+    Discountable,
+    Voidable,
+    NotActive,
+    TaxIncluded,
+    ItemGroup,
+    ReceiptText,
+    AllowPriceOverride,
+    Reserved2,
+    ChoiceGroups,
+    PrinterLogicals,
+    Covers,
+    StoreId,
+    KdsText,
+    KdsDept,
+    KdsCategory,
+    KdsTime,
+    StorePrice,
+    ImageId,
+    StockItem,
+    LanguageIso,
+    Reserved3,
+    Reserved4,
+    AskPricePrompt,
+    AskPriceSuggested,
+}
+
+impl ExportField {
+    /// In the order the POS import expects them
+    pub const ALL: &'static [Self] = &[
+        Self::AddEditDelete,
+        Self::ItemId,
+        Self::ItemName,
+        Self::Button1,
+        Self::Button2,
+        Self::PrinterText,
+        Self::Prices,
+        Self::ProductClass,
+        Self::RevenueCategory,
+        Self::TaxGroup,
+        Self::SecurityLevel,
+        Self::ReportCategory,
+        Self::WeightFlag,
+        Self::WeightTare,
+        Self::Sku,
+        Self::BarGunCode,
+        Self::CostAmount,
+        Self::Reserved1,
+        Self::AskPrice,
+        Self::PrintOnCheck,
+        Self::Discountable,
+        Self::Voidable,
+        Self::NotActive,
+        Self::TaxIncluded,
+        Self::ItemGroup,
+        Self::ReceiptText,
+        Self::AllowPriceOverride,
+        Self::Reserved2,
+        Self::ChoiceGroups,
+        Self::PrinterLogicals,
+        Self::Covers,
+        Self::StoreId,
+        Self::KdsText,
+        Self::KdsDept,
+        Self::KdsCategory,
+        Self::KdsTime,
+        Self::StorePrice,
+        Self::ImageId,
+        Self::StockItem,
+        Self::LanguageIso,
+        Self::Reserved3,
+        Self::Reserved4,
+        Self::AskPricePrompt,
+        Self::AskPriceSuggested,
+    ];
+
+    // Not part of the POS import template, so no preset writes them until they're turned on
+    const EXTRAS: &'static [Self] = &[
+        Self::AskPricePrompt,
+        Self::AskPriceSuggested,
+    ];
+
+    // Fields the minimal preset keeps
+    const MINIMAL: &'static [Self] = &[
+        Self::AddEditDelete,
+        Self::ItemId,
+        Self::ItemName,
+        Self::Button1,
+        Self::Button2,
+        Self::PrinterText,
+        Self::Prices,
+        Self::ProductClass,
+        Self::RevenueCategory,
+        Self::TaxGroup,
+        Self::ReportCategory,
+        Self::ItemGroup,
+    ];
+
+    /// Header used by the POS import template
+    pub fn default_header(&self) -> &'static str {
+        match self {
+            Self::AddEditDelete => "Add",
+            Self::ItemId => "Item ID",
+            Self::ItemName => "Item Name",
+            Self::Button1 => "Button 1 (Upper half)",
+            Self::Button2 => "Button 2 (Lower half)",
+            Self::PrinterText => "Kitchen Printer Text",
+            Self::Prices => "Default Price & Price Levels",
+            Self::ProductClass => "Product Class ID",
+            Self::RevenueCategory => "Revenue Category ID",
+            Self::TaxGroup => "Tax Group ID",
+            Self::SecurityLevel => "Security Level ID",
+            Self::ReportCategory => "Report Category ID",
+            Self::WeightFlag => "Use Weight Flag",
+            Self::WeightTare => "Weight Tare Amount",
+            Self::Sku => "SKU #",
+            Self::BarGunCode => "Bar Gun Code",
+            Self::CostAmount => "Cost Amount",
+            Self::Reserved1 | Self::Reserved2 | Self::Reserved3 | Self::Reserved4 => "Reserved",
+            Self::AskPrice => "Ask Price",
+            Self::PrintOnCheck => "Print on Check",
+            Self::Discountable => "Discountable",
+            Self::Voidable => "Voidable",
+            Self::NotActive => "Not Active (86'd)",
+            Self::TaxIncluded => "Tax Included",
+            Self::ItemGroup => "Item Group ID",
+            Self::ReceiptText => "Customer Receipt Text",
+            Self::AllowPriceOverride => "Allow Price Override",
+            Self::ChoiceGroups => "Choice Groups",
+            Self::PrinterLogicals => "Kitchen Printers (Logical)",
+            Self::Covers => "Covers",
+            Self::StoreId => "Store ID",
+            Self::KdsText => "Kitchen Video Text",
+            Self::KdsDept => "KDS Department",
+            Self::KdsCategory => "KDS Category",
+            Self::KdsTime => "KDS Cook Time (secs.)",
+            Self::StorePrice => "Store Price Level",
+            Self::ImageId => "Image ID",
+            Self::StockItem => "Stock Item Flag",
+            Self::LanguageIso => "Language ISO Code*",
+            Self::AskPricePrompt => "Ask Price Prompt",
+            Self::AskPriceSuggested => "Ask Price Suggested",
+        }
+    }
+}
+
+impl fmt::Display for ExportField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            // The reserved columns all share a header, number them so they can be told apart
+            Self::Reserved1 => write!(f, "Reserved 1"),
+            Self::Reserved2 => write!(f, "Reserved 2"),
+            Self::Reserved3 => write!(f, "Reserved 3"),
+            Self::Reserved4 => write!(f, "Reserved 4"),
+            _ => write!(f, "{}", self.default_header()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportColumn {
+    pub field: ExportField,
+    pub header: String,
+    pub included: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ExportPreset {
+    #[default]
+    Standard,
+    Minimal,
+}
+
+impl ExportPreset {
+    pub const ALL: &'static [Self] = &[
+        Self::Standard,
+        Self::Minimal,
+    ];
+
+    pub fn profile(&self) -> ExportProfile {
+        let included = |field: &ExportField| match self {
+            Self::Standard => !ExportField::EXTRAS.contains(field),
+            Self::Minimal => ExportField::MINIMAL.contains(field),
+        };
+
+        ExportProfile {
+            preset: *self,
+            include_header: false,
+            columns: ExportField::ALL
+                .iter()
+                .map(|field| ExportColumn {
+                    field: *field,
+                    header: field.default_header().to_string(),
+                    included: included(field),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl fmt::Display for ExportPreset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Standard => write!(f, "Standard (all columns)"),
+            Self::Minimal => write!(f, "Minimal"),
+        }
+    }
+}
+
+/// Which item CSV columns are written, in what order and under which headers
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExportProfile {
+    pub preset: ExportPreset, // What "Reset to Preset" goes back to
+    pub include_header: bool,
+    pub columns: Vec<ExportColumn>,
+}
+
+impl Default for ExportProfile {
+    fn default() -> Self {
+        ExportPreset::default().profile()
+    }
+}
+
+impl ExportProfile {
+    /// The saved columns, followed by any fields the layout was saved without
+    /// (added to Item since), excluded so older layouts export the same as before
+    pub fn columns(&self) -> Vec<ExportColumn> {
+        let mut columns = self.columns.clone();
+        for field in ExportField::ALL {
+            if !columns.iter().any(|column| column.field == *field) {
+                columns.push(ExportColumn {
+                    field: *field,
+                    header: field.default_header().to_string(),
+                    included: false,
+                });
+            }
+        }
+        columns
+    }
+
+    /// Stores the missing fields so the editor can address every column by index
+    pub fn fill_missing(&mut self) {
+        self.columns = self.columns();
+    }
+
+    /// Swaps a column with its neighbour, `up` towards the start of the line
+    pub fn move_column(&mut self, index: usize, up: bool) {
+        self.fill_missing();
+        let target = if up {
+            index.checked_sub(1)
+        } else {
+            Some(index + 1).filter(|i| *i < self.columns.len())
+        };
+
+        if let Some(target) = target {
+            self.columns.swap(index, target);
+        }
+    }
+}
@@ -0,0 +1,77 @@
+use super::{Classification, Item};
+use super::edit::EditSection;
+
+/// How much the editor pushes for a field to be filled in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Requirement {
+    Required,    // Save refuses the item without it
+    Recommended, // Saves without it, but the POS and reports expect it
+}
+
+/// A field the edit form marks, and how to tell it has been left empty
+pub struct FieldInfo {
+    pub label: &'static str, // Same as the label on the form
+    pub section: EditSection,
+    pub requirement: Requirement,
+    pub is_missing: fn(&Item) -> bool,
+}
+
+pub const FIELDS: &[FieldInfo] = &[
+    FieldInfo {
+        label: "Item Name",
+        section: EditSection::Basic,
+        requirement: Requirement::Required,
+        is_missing: |item| item.name.trim().is_empty(),
+    },
+    FieldInfo {
+        label: "Item Group",
+        section: EditSection::Classification,
+        requirement: Requirement::Recommended,
+        is_missing: |item| Classification::ItemGroup.is_missing(item),
+    },
+    FieldInfo {
+        label: "Revenue Category",
+        section: EditSection::Classification,
+        requirement: Requirement::Recommended,
+        is_missing: |item| Classification::RevenueCategory.is_missing(item),
+    },
+    FieldInfo {
+        label: "Tax Group",
+        section: EditSection::Classification,
+        requirement: Requirement::Recommended,
+        is_missing: |item| Classification::TaxGroup.is_missing(item),
+    },
+    FieldInfo {
+        label: "Report Category",
+        section: EditSection::Classification,
+        requirement: Requirement::Recommended,
+        is_missing: |item| Classification::ReportCategory.is_missing(item),
+    },
+    FieldInfo {
+        label: "Base Price",
+        section: EditSection::Pricing,
+        requirement: Requirement::Recommended,
+        is_missing: |item| Classification::Price.is_missing(item),
+    },
+];
+
+pub fn info(label: &str) -> Option<&'static FieldInfo> {
+    FIELDS.iter().find(|field| field.label == label)
+}
+
+/// Labels of the fields at this requirement the item leaves empty, in form order
+pub fn incomplete(item: &Item, requirement: Requirement) -> Vec<&'static str> {
+    FIELDS
+        .iter()
+        .filter(|field| field.requirement == requirement && (field.is_missing)(item))
+        .map(|field| field.label)
+        .collect()
+}
+
+/// How many marked fields in a section are still empty
+pub fn incomplete_in(item: &Item, section: EditSection) -> usize {
+    FIELDS
+        .iter()
+        .filter(|field| field.section == section && (field.is_missing)(item))
+        .count()
+}
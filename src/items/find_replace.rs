@@ -0,0 +1,89 @@
+use std::collections::BTreeMap;
+use crate::data_types::EntityId;
+use super::Item;
+
+/// State of the find-and-replace popup for item names
+#[derive(Debug, Clone, Default)]
+pub struct FindReplace {
+    pub find: String,
+    pub replace: String,
+    pub case_sensitive: bool,
+    pub whole_word: bool,
+    // Cleared whenever an option changes, so Apply always matches what was previewed
+    pub preview: Option<Vec<NameChange>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct NameChange {
+    pub item_id: EntityId,
+    pub old_name: String,
+    pub new_name: String,
+    pub replacements: usize,
+}
+
+impl FindReplace {
+    /// Every item whose name would change, in item order
+    pub fn preview(&self, items: &BTreeMap<EntityId, Item>) -> Vec<NameChange> {
+        items.values()
+            .filter_map(|item| {
+                let (new_name, replacements) = self.replace_in(&item.name);
+                (replacements > 0 && new_name != item.name).then(|| NameChange {
+                    item_id: item.id,
+                    old_name: item.name.clone(),
+                    new_name,
+                    replacements,
+                })
+            })
+            .collect()
+    }
+
+    /// Replaces every match in `name`, returning the new name and how many matches there were
+    pub fn replace_in(&self, name: &str) -> (String, usize) {
+        let find: Vec<char> = self.find.chars().collect();
+        if find.is_empty() {
+            return (name.to_string(), 0);
+        }
+
+        let chars: Vec<char> = name.chars().collect();
+        let mut result = String::with_capacity(name.len());
+        let mut replacements = 0;
+        let mut i = 0;
+
+        while i < chars.len() {
+            if self.matches_at(&chars, i, &find) {
+                result.push_str(&self.replace);
+                replacements += 1;
+                i += find.len();
+            } else {
+                result.push(chars[i]);
+                i += 1;
+            }
+        }
+
+        (result, replacements)
+    }
+
+    fn matches_at(&self, chars: &[char], start: usize, find: &[char]) -> bool {
+        let end = start + find.len();
+        if end > chars.len() {
+            return false;
+        }
+
+        let same = chars[start..end].iter().zip(find).all(|(a, b)| {
+            if self.case_sensitive {
+                a == b
+            } else {
+                a.to_lowercase().eq(b.to_lowercase())
+            }
+        });
+
+        if !same || !self.whole_word {
+            return same;
+        }
+
+        // A word match can't be part of a longer word on either side
+        let starts_word = start == 0 || !chars[start - 1].is_alphanumeric();
+        let ends_word = end == chars.len() || !chars[end].is_alphanumeric();
+        starts_word && ends_word
+    }
+}
@@ -1,7 +1,7 @@
 use std::fs;
 use std::path::PathBuf;
 use std::collections::{BTreeMap, HashSet};
-use csv::ReaderBuilder;
+use csv::{ReaderBuilder, StringRecord};
 use rust_decimal::Decimal;
 use crate::{
     items::{Item, ItemPrice},
@@ -14,30 +14,187 @@ use crate::{
     revenue_categories::RevenueCategory,
     security_levels::SecurityLevel,
     tax_groups::TaxGroup, 
-    data_types::EntityId
+    data_types::{EntityId, timestamp_now}
 };
 
 
 const EXPECTED_FIELD_COUNT: usize = 42;
 
-pub fn verify_csv_format(path: PathBuf) -> Result<(), Box<dyn std::error::Error>> {
-    let contents = fs::read_to_string(&path)?;
+// Auto-detection gives up after this many title lines, so a broken file still reports its errors
+const MAX_LEADING_LINES: usize = 10;
 
-    let masked_contents = mask_braced_commas(&contents);
+/// Where in the import file a row could not be read
+#[derive(Debug, Clone)]
+pub struct CsvParseError {
+    pub line: u64,
+    pub column: Option<usize>,
+    pub message: String,
+}
+
+impl std::fmt::Display for CsvParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.column {
+            Some(column) => write!(f, "Line {}, column {}: {}", self.line, column, self.message),
+            None => write!(f, "Line {}: {}", self.line, self.message),
+        }
+    }
+}
+
+impl std::error::Error for CsvParseError {}
+
+impl CsvParseError {
+    fn from_csv(error: csv::Error, line: u64) -> Self {
+        let line = error.position().map_or(line, |position| position.line());
+        let column = match error.kind() {
+            csv::ErrorKind::Deserialize { err, .. } => err.field().map(|field| field as usize + 1),
+            _ => None,
+        };
+        let message = match error.kind() {
+            csv::ErrorKind::Deserialize { err, .. } => err.kind().to_string(),
+            csv::ErrorKind::Utf8 { .. } => "the row is not valid UTF-8 text".to_string(),
+            _ => error.to_string(),
+        };
+
+        Self { line, column, message }
+    }
+
+    fn field_count(line: u64, found: usize) -> Self {
+        // Point at the first missing column, or the first one too many
+        Self {
+            line,
+            column: Some(found.min(EXPECTED_FIELD_COUNT) + 1),
+            message: format!("expected {} fields, found {}", EXPECTED_FIELD_COUNT, found),
+        }
+    }
+}
+
+/// How the bytes of an import file were read as text
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextEncoding {
+    Utf8,
+    Utf8WithBom,
+    Latin1,
+}
+
+impl std::fmt::Display for TextEncoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TextEncoding::Utf8 => write!(f, "UTF-8"),
+            TextEncoding::Utf8WithBom => write!(f, "UTF-8 with a byte order mark"),
+            TextEncoding::Latin1 => write!(f, "Latin-1"),
+        }
+    }
+}
+
+/// Reads an import file as text. A UTF-8 BOM is dropped so it can't end up in the first
+/// column, and a file that isn't UTF-8 is read as Latin-1 when `latin1_fallback` is on.
+pub fn read_import_text(path: &PathBuf, latin1_fallback: bool) -> Result<(String, TextEncoding), Box<dyn std::error::Error>> {
+    let bytes = fs::read(path)?;
+
+    match String::from_utf8(bytes) {
+        Ok(text) => match text.strip_prefix('\u{feff}') {
+            Some(rest) => Ok((rest.to_string(), TextEncoding::Utf8WithBom)),
+            None => Ok((text, TextEncoding::Utf8)),
+        },
+        // Every byte is a Latin-1 character, so this can't fail
+        Err(e) if latin1_fallback => {
+            Ok((e.into_bytes().iter().map(|&byte| byte as char).collect(), TextEncoding::Latin1))
+        }
+        Err(e) => Err(format!(
+            "The file is not UTF-8 text, the first bad byte is at position {}. \
+            Turn on \"Read non-UTF-8 files as Latin-1\" in Settings to import it anyway.",
+            e.utf8_error().valid_up_to()
+        ).into()),
+    }
+}
+
+pub fn verify_csv_format(path: PathBuf, leading_lines: Option<usize>, latin1_fallback: bool) -> Result<TextEncoding, Box<dyn std::error::Error>> {
+    let (contents, encoding) = read_import_text(&path, latin1_fallback)?;
+
+    let records = item_records(&contents, leading_lines)?;
+    if records.is_empty() {
+        return Err("No item rows found in the file.".into());
+    }
+
+    for (line, record) in &records {
+        if record.len() != EXPECTED_FIELD_COUNT {
+            return Err(CsvParseError::field_count(*line, record.len()).into());
+        }
+    }
+
+    Ok(encoding)
+}
+
+/// Reads the item rows of an import file with their line numbers. Quoted fields
+/// follow RFC 4180, a UTF-8 BOM and blank rows are ignored, and `leading_lines`
+/// title lines are skipped before the data. With None the title lines, and a
+/// header row, are found by looking at the rows themselves.
+fn item_records(contents: &str, leading_lines: Option<usize>) -> Result<Vec<(u64, StringRecord)>, CsvParseError> {
+    let contents = contents.trim_start_matches('\u{feff}');
+    let masked_contents = mask_braced_commas(contents);
 
     let mut reader = ReaderBuilder::new()
         .has_headers(false)
+        .flexible(true)
         .from_reader(masked_contents.as_bytes());
 
+    let mut records = Vec::new();
+    let mut last_line = 1;
     for result in reader.records() {
-        let record = result?;
+        let record = result.map_err(|e| CsvParseError::from_csv(e, last_line))?;
+        let line = record.position().map_or(last_line, |position| position.line());
+        last_line = line;
 
-        if record.len() != EXPECTED_FIELD_COUNT {
-            return Err(format!("Unexpected field count: {}", record.len()).into());
+        if let Some(skip) = leading_lines {
+            if line <= skip as u64 {
+                continue;
+            }
+        }
+        if record.iter().all(|field| field.trim().is_empty()) {
+            continue;
         }
+
+        records.push((line, record));
     }
 
-    Ok(())
+    if leading_lines.is_none() {
+        let skip = records
+            .iter()
+            .take(MAX_LEADING_LINES)
+            .position(|(_, record)| !is_title_line(record))
+            .unwrap_or(0);
+        let skip = match records.get(skip) {
+            Some((_, record)) if is_header_row(record) => skip + 1,
+            _ => skip,
+        };
+
+        if skip > 0 {
+            println!("Skipping {} lines before the item data", skip);
+            records.drain(..skip);
+        }
+    }
+
+    Ok(records)
+}
+
+// Title and note lines don't have an item ID where one would be
+fn is_title_line(record: &StringRecord) -> bool {
+    record.len() != EXPECTED_FIELD_COUNT
+        && !record.get(1).is_some_and(|id| id.trim().parse::<i32>().is_ok())
+}
+
+// The export's own header row, "Add,Item ID,Item Name,..."
+fn is_header_row(record: &StringRecord) -> bool {
+    let normalize = |field: Option<&str>| -> String {
+        field
+            .unwrap_or_default()
+            .chars()
+            .filter(|c| c.is_alphanumeric())
+            .collect::<String>()
+            .to_lowercase()
+    };
+
+    normalize(record.get(1)) == "itemid" || normalize(record.get(2)) == "itemname"
 }
 
 pub fn is_csv_or_txt(path: PathBuf) -> bool {
@@ -46,13 +203,72 @@ pub fn is_csv_or_txt(path: PathBuf) -> bool {
         .map_or(false, |ext_str| ext_str == "csv" || ext_str == "txt")
 }
 
+/// What a dropped file contains, regardless of its extension
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileKind {
+    Csv,
+    Json,
+    Unknown,
+}
+
+const SNIFF_BYTES: usize = 4096;
+
+/// Peeks at the start of the file to tell CSV from JSON, since users often drop
+/// a .txt that is really JSON or a .json that is really a CSV export
+pub fn sniff_file_kind(path: &PathBuf) -> Result<FileKind, std::io::Error> {
+    use std::io::Read;
+
+    let mut buffer = Vec::with_capacity(SNIFF_BYTES);
+    fs::File::open(path)?
+        .take(SNIFF_BYTES as u64)
+        .read_to_end(&mut buffer)?;
+
+    // Binary files (spreadsheets, images) have NUL bytes early on
+    if buffer.contains(&0) {
+        return Ok(FileKind::Unknown);
+    }
+
+    // The peek can cut a multi-byte character in half and Latin-1 files aren't UTF-8,
+    // neither matters for telling the kinds apart
+    let text = String::from_utf8_lossy(&buffer);
+    let text = text.trim_start_matches('\u{feff}').trim_start();
+
+    if text.starts_with('{') || text.starts_with('[') {
+        return Ok(FileKind::Json);
+    }
+
+    // Title lines can come before the data, look as far past them as item_records does
+    let has_csv_line = text
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .take(MAX_LEADING_LINES + 1)
+        .any(|line| line.contains(','));
+    if has_csv_line {
+        return Ok(FileKind::Csv);
+    }
+
+    Ok(FileKind::Unknown)
+}
 
 fn mask_braced_commas(s: &str) -> String {
     let mut result = String::with_capacity(s.len());
     let mut inside_braces = false;
+    let mut inside_quotes = false;
 
     for c in s.chars() {
         match c {
+            // An escaped "" flips twice, so it leaves the state as it was. Price and
+            // choice lists are never quoted, so a brace in quoted text ends with the quote.
+            '"' => {
+                inside_quotes = !inside_quotes;
+                inside_braces = false;
+                result.push(c);
+            }
+            // A stray brace can't carry over into the next row
+            '\n' if !inside_quotes => {
+                inside_braces = false;
+                result.push(c);
+            }
             '{' => {
                 inside_braces = true;
                 result.push(c);
@@ -93,24 +309,83 @@ fn unmask_braced_commas(s: &str) -> String {
     result
 }
 
-pub fn collect_item_information(path: &PathBuf) -> Result<BTreeMap<EntityId, Item>, Box<dyn std::error::Error>> {
+/// A value in the import file that was skipped or coerced into something else
+#[derive(Debug, Clone)]
+pub struct ImportWarning {
+    pub row: usize,
+    pub item_id: Option<EntityId>,
+    pub message: String,
+}
+
+impl std::fmt::Display for ImportWarning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.item_id {
+            // Row 0 is about the file as a whole
+            None if self.row == 0 => write!(f, "File: {}", self.message),
+            Some(id) => write!(f, "Row {} (item {}): {}", self.row, id, self.message),
+            None => write!(f, "Row {}: {}", self.row, self.message),
+        }
+    }
+}
+
+pub fn collect_item_information(path: &PathBuf, leading_lines: Option<usize>, latin1_fallback: bool) -> Result<(BTreeMap<EntityId, Item>, Vec<ImportWarning>), Box<dyn std::error::Error>> {
     println!("Running collect_item_information function on path: {:?}", &path);
-    let contents = fs::read_to_string(&path)?;
-    let masked_contents = mask_braced_commas(&contents);
-    let mut reader = ReaderBuilder::new()
-        .has_headers(false)
-        .from_reader(masked_contents.as_bytes());
+    let (contents, encoding) = read_import_text(path, latin1_fallback)?;
+    println!("Import file read as {}", encoding);
+    let records = item_records(&contents, leading_lines)?;
 
     let mut items_map = BTreeMap::new();
+    let mut warnings = Vec::new();
+
+    match encoding {
+        TextEncoding::Utf8 => {}
+        TextEncoding::Utf8WithBom => warnings.push(ImportWarning {
+            row: 0,
+            item_id: None,
+            message: "read as UTF-8, the byte order mark at the start was removed".to_string(),
+        }),
+        TextEncoding::Latin1 => warnings.push(ImportWarning {
+            row: 0,
+            item_id: None,
+            message: "not UTF-8, read as Latin-1. Check that accented names came through right".to_string(),
+        }),
+    }
     
-    for result in reader.deserialize() {
-        let record: ImportStructure = result?;
+    for (index, (line, record)) in records.iter().enumerate() {
+        if record.len() != EXPECTED_FIELD_COUNT {
+            return Err(CsvParseError::field_count(*line, record.len()).into());
+        }
+        let record: ImportStructure = record
+            .deserialize(None)
+            .map_err(|e| CsvParseError::from_csv(e, *line))?;
+        let row = index + 1;
         
         // Parse the item ID
         let entity_id = match record.item_id.parse::<i32>() {
             Ok(id) => id,
-            Err(_) => continue, // Skip records with invalid IDs
+            Err(_) => {
+                // Skip records with invalid IDs
+                warnings.push(ImportWarning {
+                    row,
+                    item_id: None,
+                    message: format!("skipped, item ID \"{}\" is not a number", record.item_id),
+                });
+                continue;
+            }
         };
+
+        warnings.extend(
+            record_warnings(&record)
+                .into_iter()
+                .map(|message| ImportWarning { row, item_id: Some(entity_id), message })
+        );
+        if items_map.contains_key(&entity_id) {
+            warnings.push(ImportWarning {
+                row,
+                item_id: Some(entity_id),
+                message: "duplicate item ID, replaced the earlier row".to_string(),
+            });
+        }
         
         // Helper function to parse a string into an Optional EntityId
         let parse_entity_id = |s: &str| -> Option<EntityId> {
@@ -161,12 +436,17 @@ pub fn collect_item_information(path: &PathBuf) -> Result<BTreeMap<EntityId, Ite
             s.parse::<i32>().unwrap_or(0)
         };
         
+        // Text can have braces of its own, put back any commas masked inside them
+        let text_field = |s: &str| -> String {
+            s.replace('␟', ",")
+        };
+
         // Helper to convert empty strings to None
         let string_or_none = |s: &str| -> Option<String> {
             if s.trim().is_empty() {
                 None
             } else {
-                Some(s.to_string())
+                Some(text_field(s))
             }
         };
         
@@ -211,10 +491,10 @@ pub fn collect_item_information(path: &PathBuf) -> Result<BTreeMap<EntityId, Ite
         // Create Item from ImportStructure with proper type conversions
         let item = Item {
             id: entity_id,
-            name: record.item_name.clone(),
-            button1: record.button_1.clone(),
+            name: text_field(&record.item_name),
+            button1: text_field(&record.button_1),
             button2: string_or_none(&record.button_2),
-            printer_text: record.kitchen_printer_text.clone(),
+            printer_text: text_field(&record.kitchen_printer_text),
             default_price: Some(default_price.price),
             price_levels: price_level_ids,
             item_prices: item_prices,
@@ -236,21 +516,25 @@ pub fn collect_item_information(path: &PathBuf) -> Result<BTreeMap<EntityId, Ite
             not_active: parse_bool(&record.not_active),
             tax_included: parse_bool(&record.tax_included),
             item_group: parse_entity_id(&record.item_group_id),
-            customer_receipt: record.customer_receipt_text.clone(),
+            customer_receipt: text_field(&record.customer_receipt_text),
             allow_price_override: parse_bool(&record.allow_price_override),
             reserved2: parse_bool(&record.reserved2),
             choice_groups: parse_choice_groups(&record.choice_groups),
             printer_logicals: parse_printer_logicals(&record.kitchen_printers),
             covers: parse_i32(&record.covers),
             store_id: parse_i32(&record.store_id),
-            kitchen_video: record.kitchen_video_text.clone(),
+            kitchen_video: text_field(&record.kitchen_video_text),
             kds_dept: parse_i32(&record.kds_department),
-            kds_category: record.kds_category.clone(),
+            kds_category: text_field(&record.kds_category),
             kds_cooktime: parse_i32(&record.kdc_cook_time),
             store_price_level: parse_entity_ids(&record.store_price_level),
             image_id: parse_i32(&record.image_id),
             stock_item: parse_bool(&record.stock_item_flag),
             language_iso_code: record.language_iso_code.clone(),
+            ask_price_prompt: None,
+            ask_price_suggested: None,
+            created_at: Some(timestamp_now()),
+            updated_at: Some(timestamp_now()),
         };
         //println!("{:?}", &item);
         
@@ -260,8 +544,70 @@ pub fn collect_item_information(path: &PathBuf) -> Result<BTreeMap<EntityId, Ite
     if items_map.is_empty() {
         return Err(format!("No valid items found in the file.").into());
     }
+
+    println!("Collected {} items with {} warnings", items_map.len(), warnings.len());
     
-    Ok(items_map)
+    Ok((items_map, warnings))
+}
+
+// Finds values that collect_item_information will zero out or drop instead of importing as-is
+fn record_warnings(record: &ImportStructure) -> Vec<String> {
+    let mut messages = Vec::new();
+
+    if record.item_name.trim().is_empty() {
+        messages.push("item name is empty".to_string());
+    }
+    if record.button_1.len() > 15 {
+        messages.push(format!("button 1 text \"{}\" is longer than 15 characters and will be cut off", record.button_1));
+    }
+    if record.button_2.len() > 15 {
+        messages.push(format!("button 2 text \"{}\" is longer than 15 characters and will be cut off", record.button_2));
+    }
+
+    if record.price_levels.trim().is_empty() {
+        messages.push("no prices, default price set to $0.00".to_string());
+    } else if parse_item_prices(&record.price_levels).is_none() {
+        messages.push(format!("prices \"{}\" could not be read, default price set to $0.00", record.price_levels));
+    }
+
+    let decimal_fields = [
+        ("weight tare amount", &record.weight_tare_amount),
+        ("cost amount", &record.cost_amount),
+    ];
+    for (field, value) in decimal_fields {
+        if !value.trim().is_empty() && value.parse::<Decimal>().is_err() {
+            messages.push(format!("{} \"{}\" is not a number and was not imported", field, value));
+        }
+    }
+
+    let number_fields = [
+        ("covers", &record.covers),
+        ("store ID", &record.store_id),
+        ("KDS department", &record.kds_department),
+        ("KDS cook time", &record.kdc_cook_time),
+        ("image ID", &record.image_id),
+    ];
+    for (field, value) in number_fields {
+        if !value.trim().is_empty() && value.parse::<i32>().is_err() {
+            messages.push(format!("{} \"{}\" is not a whole number, set to 0", field, value));
+        }
+    }
+
+    let reference_fields = [
+        ("product class", &record.product_class_id),
+        ("revenue category", &record.revenue_category_id),
+        ("tax group", &record.tax_group_id),
+        ("security level", &record.security_level_id),
+        ("report category", &record.report_category_id),
+        ("item group", &record.item_group_id),
+    ];
+    for (field, value) in reference_fields {
+        if !value.trim().is_empty() && value.parse::<i32>().is_err() {
+            messages.push(format!("{} \"{}\" is not an ID, left empty", field, value));
+        }
+    }
+
+    messages
 }
 
 // Helper function to parse price levels and item prices from a string
@@ -489,6 +835,8 @@ fn ensure_price_levels_exist(
             let price_level = PriceLevel {
                 id,
                 name: format!("Price Level {}", id),
+                created_at: Some(timestamp_now()),
+                updated_at: Some(timestamp_now()),
                 // Set other fields to defaults
                 ..PriceLevel::default()
             };
@@ -517,6 +865,8 @@ fn ensure_product_classes_exist(
             let product_class = ProductClass {
                 id,
                 name: format!("Product Class {}", id),
+                created_at: Some(timestamp_now()),
+                updated_at: Some(timestamp_now()),
                 // Other default fields
                 ..ProductClass::default()
             };
@@ -544,6 +894,8 @@ fn ensure_revenue_categories_exist(
             let revenue_category = RevenueCategory {
                 id,
                 name: format!("Revenue Category {}", id),
+                created_at: Some(timestamp_now()),
+                updated_at: Some(timestamp_now()),
                 // Other default fields
                 ..RevenueCategory::default()
             };
@@ -568,9 +920,13 @@ fn ensure_tax_groups_exist(
     
     for id in referenced_ids {
         if !tax_groups.contains_key(&id) {
+            let name = format!("Tax Group {}", id);
             let tax_group = TaxGroup {
                 id,
-                name: format!("Tax Group {}", id),
+                export_key: name.clone(),
+                name,
+                created_at: Some(timestamp_now()),
+                updated_at: Some(timestamp_now()),
                 // Other default fields
                 ..TaxGroup::default()
             };
@@ -598,6 +954,8 @@ fn ensure_security_levels_exist(
             let security_level = SecurityLevel {
                 id,
                 name: format!("Security Level {}", id),
+                created_at: Some(timestamp_now()),
+                updated_at: Some(timestamp_now()),
                 // Other default fields
                 ..SecurityLevel::default()
             };
@@ -625,6 +983,8 @@ fn ensure_report_categories_exist(
             let report_category = ReportCategory {
                 id,
                 name: format!("Report Category {}", id),
+                created_at: Some(timestamp_now()),
+                updated_at: Some(timestamp_now()),
                 // Other default fields
                 ..ReportCategory::default()
             };
@@ -652,6 +1012,8 @@ fn ensure_item_groups_exist(
             let item_group = ItemGroup {
                 id,
                 name: format!("Item Group {}", id),
+                created_at: Some(timestamp_now()),
+                updated_at: Some(timestamp_now()),
                 // Other default fields
                 ..ItemGroup::default()
             };
@@ -684,6 +1046,8 @@ fn ensure_choice_groups_exist(
             let choice_group = ChoiceGroup {
                 id,
                 name: format!("Choice Group {}", id),
+                created_at: Some(timestamp_now()),
+                updated_at: Some(timestamp_now()),
                 // Set other fields to defaults
                 ..ChoiceGroup::default()
             };
@@ -713,9 +1077,13 @@ fn ensure_printer_logicals_exist(
     // Create missing printer logicals
     for (id, _) in referenced_ids {
         if !printer_logicals.contains_key(&id) {
+            let name = format!("Printer Logical {}", id);
             let printer_logical = PrinterLogical {
                 id,
-                name: format!("Printer Logical {}", id),
+                export_key: name.clone(),
+                name,
+                created_at: Some(timestamp_now()),
+                updated_at: Some(timestamp_now()),
                 // Set other fields to defaults
                 ..PrinterLogical::default()
             };
@@ -724,4 +1092,225 @@ fn ensure_printer_logicals_exist(
             println!("Created missing printer logical with ID: {}", id);
         }
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Each fixture gets its own file, tests run in parallel
+    fn fixture(name: &str, bytes: &[u8]) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("import_items_{}_{}", std::process::id(), name));
+        fs::write(&path, bytes).unwrap();
+        path
+    }
+
+    fn item_row(id: EntityId, name: &str) -> String {
+        let mut fields = vec!["A".to_string(), id.to_string(), name.to_string()];
+        fields.resize(EXPECTED_FIELD_COUNT, String::new());
+        fields.join(",")
+    }
+
+    fn header_row() -> String {
+        let mut fields = vec!["Add".to_string(), "Item ID".to_string(), "Item Name".to_string()];
+        fields.resize(EXPECTED_FIELD_COUNT, "Field".to_string());
+        fields.join(",")
+    }
+
+    fn sniff(name: &str, bytes: &[u8]) -> FileKind {
+        let path = fixture(name, bytes);
+        let kind = sniff_file_kind(&path).unwrap();
+        fs::remove_file(path).unwrap();
+        kind
+    }
+
+    #[test]
+    fn title_line_before_csv_is_csv() {
+        let contents = format!("Menu export 2024\n{}\n{}\n{}\n", header_row(), item_row(1, "Cola"), item_row(2, "Tea"));
+
+        assert_eq!(sniff("title.csv", contents.as_bytes()), FileKind::Csv);
+
+        let records = item_records(&contents, None).unwrap();
+        assert_eq!(records.iter().map(|(line, _)| *line).collect::<Vec<_>>(), vec![3, 4]);
+    }
+
+    #[test]
+    fn bom_and_blank_lines_before_csv_is_csv() {
+        let contents = format!("\u{feff}\n\n   \n{}\n", item_row(1, "Cola"));
+
+        assert_eq!(sniff("bom_blank.csv", contents.as_bytes()), FileKind::Csv);
+        assert_eq!(item_records(&contents, None).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn json_behind_bom_and_whitespace_is_json() {
+        assert_eq!(sniff("export.txt", "\u{feff}\r\n  {\"items\": []}".as_bytes()), FileKind::Json);
+        assert_eq!(sniff("list.csv", b"[1, 2, 3]"), FileKind::Json);
+    }
+
+    #[test]
+    fn binary_spreadsheet_is_unknown() {
+        assert_eq!(sniff("menu.csv", b"PK\x03\x04\x14\x00\x06\x00,item,data"), FileKind::Unknown);
+    }
+
+    #[test]
+    fn latin1_title_before_csv_is_csv() {
+        let mut contents = b"Carte du caf\xe9\n".to_vec();
+        contents.extend_from_slice(item_row(1, "Cola").as_bytes());
+
+        assert_eq!(sniff("latin1.csv", &contents), FileKind::Csv);
+    }
+
+    #[test]
+    fn prose_without_commas_is_unknown() {
+        assert_eq!(sniff("notes.txt", b"Menu notes\nCheck the prices on Friday\n"), FileKind::Unknown);
+        assert_eq!(sniff("empty.csv", b""), FileKind::Unknown);
+    }
+
+    #[test]
+    fn more_title_lines_than_detection_allows_is_unknown() {
+        let mut contents = "Title line\n".repeat(MAX_LEADING_LINES + 1);
+        contents.push_str(&item_row(1, "Cola"));
+
+        assert_eq!(sniff("long_title.csv", contents.as_bytes()), FileKind::Unknown);
+    }
+
+    #[test]
+    fn quoted_newlines_and_crlf_keep_their_rows() {
+        let contents = format!(
+            "{}\r\n{}\r\n",
+            item_row(1, "\"Cola\r\nLarge\""),
+            item_row(2, "\"Tea, Iced\""),
+        );
+
+        assert_eq!(sniff("quoted.csv", contents.as_bytes()), FileKind::Csv);
+
+        let records = item_records(&contents, None).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(&records[0].1[2], "Cola\r\nLarge");
+        assert_eq!(&records[1].1[2], "Tea, Iced");
+    }
+
+    fn read(name: &str, bytes: &[u8], latin1_fallback: bool) -> Result<(String, TextEncoding), String> {
+        let path = fixture(name, bytes);
+        let result = read_import_text(&path, latin1_fallback).map_err(|e| e.to_string());
+        fs::remove_file(path).unwrap();
+        result
+    }
+
+    #[test]
+    fn bom_is_dropped_before_the_first_column() {
+        let mut contents = b"\xef\xbb\xbf".to_vec();
+        contents.extend_from_slice(item_row(1, "Cola").as_bytes());
+
+        let (text, encoding) = read("bom.csv", &contents, false).unwrap();
+        assert_eq!(encoding, TextEncoding::Utf8WithBom);
+        assert!(text.starts_with("A,1,Cola"));
+
+        let records = item_records(&text, None).unwrap();
+        assert_eq!(&records[0].1[0], "A");
+    }
+
+    #[test]
+    fn utf8_without_bom_is_read_as_is() {
+        let (text, encoding) = read("utf8.csv", item_row(1, "Café").as_bytes(), false).unwrap();
+        assert_eq!(encoding, TextEncoding::Utf8);
+        assert!(text.contains("Café"));
+    }
+
+    #[test]
+    fn latin1_needs_the_fallback_turned_on() {
+        let contents = item_row(1, "Caf\u{e9}").chars().map(|c| c as u8).collect::<Vec<u8>>();
+
+        let error = read("latin1_off.csv", &contents, false).unwrap_err();
+        assert!(error.contains("not UTF-8"));
+        assert!(error.contains("position 7"));
+
+        let (text, encoding) = read("latin1_on.csv", &contents, true).unwrap();
+        assert_eq!(encoding, TextEncoding::Latin1);
+        assert!(text.starts_with("A,1,Café,"));
+    }
+
+    fn row(fields: &[&str]) -> String {
+        let mut fields = fields.iter().map(|field| field.to_string()).collect::<Vec<_>>();
+        fields.resize(EXPECTED_FIELD_COUNT, String::new());
+        fields.join(",")
+    }
+
+    fn verify(name: &str, contents: &str, leading_lines: Option<usize>) -> Result<TextEncoding, String> {
+        let path = fixture(name, contents.as_bytes());
+        let result = verify_csv_format(path.clone(), leading_lines, false).map_err(|e| e.to_string());
+        fs::remove_file(path).unwrap();
+        result
+    }
+
+    fn collect(name: &str, contents: &str, leading_lines: Option<usize>) -> Result<(BTreeMap<EntityId, Item>, Vec<ImportWarning>), String> {
+        let path = fixture(name, contents.as_bytes());
+        let result = collect_item_information(&path, leading_lines, false).map_err(|e| e.to_string());
+        fs::remove_file(path).unwrap();
+        result
+    }
+
+    #[test]
+    fn escaped_quotes_are_read_as_one_quote() {
+        let contents = format!("{}\n{}\n", item_row(1, "\"Cola \"\"Large\"\"\""), item_row(2, "Tea"));
+
+        assert_eq!(verify("escaped.csv", &contents, None), Ok(TextEncoding::Utf8));
+
+        let (items, _) = collect("escaped_collect.csv", &contents, None).unwrap();
+        assert_eq!(items[&1].name, "Cola \"Large\"");
+        assert_eq!(items[&2].name, "Tea");
+    }
+
+    #[test]
+    fn wrong_field_count_names_the_line_and_column() {
+        let short = vec!["A"; EXPECTED_FIELD_COUNT - 2].join(",");
+        let long = vec!["A"; EXPECTED_FIELD_COUNT + 1].join(",");
+
+        let contents = format!("{}\n{}\n{}\n", item_row(1, "Cola"), item_row(2, "Tea"), short);
+        let expected = "Line 3, column 41: expected 42 fields, found 40".to_string();
+        assert_eq!(verify("short.csv", &contents, None), Err(expected.clone()));
+        assert_eq!(collect("short_collect.csv", &contents, None).unwrap_err(), expected);
+
+        let contents = format!("{}\n{}\n", item_row(1, "Cola"), long);
+        let expected = "Line 2, column 43: expected 42 fields, found 43".to_string();
+        assert_eq!(verify("long.csv", &contents, None), Err(expected.clone()));
+        assert_eq!(collect("long_collect.csv", &contents, None).unwrap_err(), expected);
+    }
+
+    #[test]
+    fn stray_brace_in_quoted_text_does_not_swallow_the_row() {
+        let contents = format!(
+            "{}\n{}\n",
+            row(&["A", "1", "\"Cola {Large, Iced\"", "Cola", "", "", "{1,$2.50,2,$3.00}"]),
+            row(&["A", "2", "Tea", "Tea", "", "", "{1,$1.75}"]),
+        );
+
+        assert_eq!(verify("stray_brace.csv", &contents, None), Ok(TextEncoding::Utf8));
+
+        let (items, _) = collect("stray_brace_collect.csv", &contents, None).unwrap();
+        assert_eq!(items[&1].name, "Cola {Large, Iced");
+        assert_eq!(items[&1].button1, "Cola");
+        assert_eq!(items[&1].default_price, Some(Decimal::new(250, 2)));
+        assert_eq!(items[&1].item_prices.as_ref().map(|prices| prices.len()), Some(1));
+        assert_eq!(items[&2].default_price, Some(Decimal::new(175, 2)));
+    }
+
+    #[test]
+    fn configured_leading_lines_are_skipped_and_lines_stay_file_relative() {
+        let contents = format!("Menu export\nPrinted Monday\n{}\n{}\n", item_row(1, "Cola"), item_row(2, "Tea"));
+
+        assert_eq!(verify("leading.csv", &contents, Some(2)), Ok(TextEncoding::Utf8));
+        let (items, _) = collect("leading_collect.csv", &contents, Some(2)).unwrap();
+        assert_eq!(items.keys().copied().collect::<Vec<_>>(), vec![1, 2]);
+
+        // One more than the title lines takes the first item with them
+        let (items, _) = collect("leading_extra.csv", &contents, Some(3)).unwrap();
+        assert_eq!(items.keys().copied().collect::<Vec<_>>(), vec![2]);
+
+        let contents = format!("Menu export\nPrinted Monday\n{}\nA,3,Water\n", item_row(1, "Cola"));
+        assert_eq!(
+            verify("leading_short.csv", &contents, Some(2)),
+            Err("Line 4, column 4: expected 42 fields, found 3".to_string())
+        );
+    }
+}
@@ -0,0 +1,257 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use serde::{Serialize, Deserialize};
+use serde_json::Value;
+use crate::data_types::EntityId;
+use crate::persistence;
+use crate::settings::DIALOG_CLOSED;
+use crate::export_preview::{ExportKind, ExportPreview};
+use super::Item;
+
+/// Field name for adding or removing a whole item. `old` is null for an added
+/// item and `new` is null for a removed one.
+pub const WHOLE_ITEM: &str = "*";
+
+// Always differ between databases, so they would only ever cause conflicts
+const IGNORED_FIELDS: &[&str] = &["created_at", "updated_at"];
+
+// Conflicts listed in the summary before the rest are counted
+const MAX_LISTED_CONFLICTS: usize = 20;
+
+/// One changed field, with the value it must still have for the change to apply
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldChange {
+    pub id: EntityId,
+    pub field: String,
+    pub old: Value,
+    pub new: Value,
+}
+
+/// Item changes that can be reviewed and then applied to another database,
+/// e.g. to promote changes from a test menu to production
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ItemPatch {
+    pub changes: Vec<FieldChange>,
+}
+
+impl ItemPatch {
+    /// Every field that differs between the two item lists
+    pub fn diff(before: &BTreeMap<EntityId, Item>, after: &BTreeMap<EntityId, Item>) -> Self {
+        let ids: BTreeSet<EntityId> = before.keys().chain(after.keys()).copied().collect();
+        let mut changes = Vec::new();
+
+        for id in ids {
+            match (before.get(&id).map(item_value), after.get(&id).map(item_value)) {
+                (Some(Value::Object(old)), Some(Value::Object(new))) => {
+                    for (field, new_value) in new {
+                        let old_value = old.get(&field).cloned().unwrap_or(Value::Null);
+                        if old_value != new_value {
+                            changes.push(FieldChange { id, field, old: old_value, new: new_value });
+                        }
+                    }
+                }
+                (old, new) => changes.push(FieldChange {
+                    id,
+                    field: WHOLE_ITEM.to_string(),
+                    old: old.unwrap_or(Value::Null),
+                    new: new.unwrap_or(Value::Null),
+                }),
+            }
+        }
+
+        Self { changes }
+    }
+
+    pub fn item_count(&self) -> usize {
+        self.changes.iter().map(|change| change.id).collect::<BTreeSet<_>>().len()
+    }
+
+    pub fn to_json_string(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Checks every change before writing anything, so a patch is applied
+    /// completely or not at all. Returns how many items changed, or the conflicts.
+    pub fn apply(&self, items: &mut BTreeMap<EntityId, Item>, updated_at: &str) -> Result<usize, Vec<String>> {
+        let mut working: BTreeMap<EntityId, Value> = BTreeMap::new();
+        let mut conflicts = Vec::new();
+
+        for change in &self.changes {
+            let current = working
+                .entry(change.id)
+                .or_insert_with(|| items.get(&change.id).map(item_value).unwrap_or(Value::Null));
+
+            if change.field == WHOLE_ITEM {
+                if *current != without_ignored(&change.old) {
+                    conflicts.push(match (&change.old, &*current) {
+                        (Value::Null, _) => format!("Item {} already exists", change.id),
+                        (_, Value::Null) => format!("Item {} no longer exists", change.id),
+                        _ => format!("Item {} was changed since the patch was made", change.id),
+                    });
+                    continue;
+                }
+                *current = without_ignored(&change.new);
+                continue;
+            }
+
+            let Value::Object(fields) = current else {
+                conflicts.push(format!("Item {} does not exist, can't change {}", change.id, change.field));
+                continue;
+            };
+
+            let found = fields.get(&change.field).cloned().unwrap_or(Value::Null);
+            if found != change.old {
+                conflicts.push(format!(
+                    "Item {} {}: expected {}, found {}",
+                    change.id, change.field, change.old, found
+                ));
+                continue;
+            }
+            fields.insert(change.field.clone(), change.new.clone());
+        }
+
+        if !conflicts.is_empty() {
+            return Err(conflicts);
+        }
+
+        // Convert everything before touching the items, a bad value rejects the whole patch
+        let mut updated = Vec::new();
+        for (id, value) in working {
+            if value.is_null() {
+                updated.push((id, None));
+                continue;
+            }
+            match serde_json::from_value::<Item>(value) {
+                Ok(mut item) => {
+                    item.id = id;
+                    updated.push((id, Some(item)));
+                }
+                Err(e) => conflicts.push(format!("Item {} can't be read after patching: {}", id, e)),
+            }
+        }
+
+        if !conflicts.is_empty() {
+            return Err(conflicts);
+        }
+
+        let mut changed = 0;
+        for (id, item) in updated {
+            let existing = items.get(&id);
+            match item {
+                None => {
+                    if items.remove(&id).is_some() {
+                        changed += 1;
+                    }
+                }
+                Some(mut item) => {
+                    if existing.map(item_value) == Some(item_value(&item)) {
+                        continue;
+                    }
+                    item.created_at = existing.and_then(|e| e.created_at.clone()).or_else(|| Some(updated_at.to_string()));
+                    item.updated_at = Some(updated_at.to_string());
+                    items.insert(id, item);
+                    changed += 1;
+                }
+            }
+        }
+
+        Ok(changed)
+    }
+}
+
+// The item as JSON, without the fields that never take part in a patch
+fn item_value(item: &Item) -> Value {
+    without_ignored(&serde_json::to_value(item).unwrap_or(Value::Null))
+}
+
+fn without_ignored(value: &Value) -> Value {
+    let mut value = value.clone();
+    if let Value::Object(fields) = &mut value {
+        for field in IGNORED_FIELDS {
+            fields.remove(*field);
+        }
+    }
+    value
+}
+
+/// "Rejected, nothing was changed" with the first conflicts listed
+pub fn conflict_summary(conflicts: &[String]) -> String {
+    let mut summary = format!(
+        "Patch rejected, nothing was changed. {} changes no longer match this database:",
+        conflicts.len()
+    );
+    for conflict in conflicts.iter().take(MAX_LISTED_CONFLICTS) {
+        summary.push_str(&format!("\n{}", conflict));
+    }
+    if conflicts.len() > MAX_LISTED_CONFLICTS {
+        summary.push_str(&format!("\n...and {} more", conflicts.len() - MAX_LISTED_CONFLICTS));
+    }
+    summary
+}
+
+async fn choose_patch_save_path() -> Result<PathBuf, String> {
+    rfd::AsyncFileDialog::new()
+        .set_title("Save Patch")
+        .add_filter("JSON Files", &["json"])
+        .set_file_name("patch.json")
+        .save_file()
+        .await
+        .as_ref()
+        .map(rfd::FileHandle::path)
+        .map(Path::to_owned)
+        .ok_or(DIALOG_CLOSED.to_string())
+}
+
+async fn choose_database(title: &str) -> Result<String, String> {
+    rfd::AsyncFileDialog::new()
+        .set_title(title)
+        .add_filter("RON Files", &["ron"])
+        .pick_file()
+        .await
+        .map(|handle| handle.path().to_string_lossy().into_owned())
+        .ok_or(DIALOG_CLOSED.to_string())
+}
+
+/// Asks where to save the patch, it's written once the preview is confirmed
+pub async fn preview_patch(patch: ItemPatch, kind: ExportKind) -> Result<ExportPreview, String> {
+    let path = choose_patch_save_path().await?;
+    let content = patch.to_json_string().map_err(|e| e.to_string())?;
+
+    Ok(ExportPreview::new(kind, vec![(path, content)]))
+}
+
+/// Picks a patch file and reads it
+pub async fn open_patch() -> Result<ItemPatch, String> {
+    let handle = rfd::AsyncFileDialog::new()
+        .set_title("Apply Patch")
+        .add_filter("JSON Files", &["json"])
+        .pick_file()
+        .await
+        .ok_or(DIALOG_CLOSED.to_string())?;
+
+    let content = tokio::fs::read_to_string(handle.path())
+        .await
+        .map_err(|e| e.to_string())?;
+
+    serde_json::from_str(&content).map_err(|e| format!("Not a patch file: {}", e))
+}
+
+/// Diffs the items of two database files, the patch is saved once the preview is confirmed
+pub async fn diff_database_files() -> Result<ExportPreview, String> {
+    let before_path = choose_database("Choose the database before the changes").await?;
+    let after_path = choose_database("Choose the database with the changes").await?;
+
+    let items_by_id = |state: persistence::AppState| -> BTreeMap<EntityId, Item> {
+        state.items.into_iter().map(|item| (item.id, item)).collect()
+    };
+    let before = items_by_id(persistence::load_from_file(&before_path)?);
+    let after = items_by_id(persistence::load_from_file(&after_path)?);
+
+    let patch = ItemPatch::diff(&before, &after);
+    if patch.changes.is_empty() {
+        return Err("The two databases have the same items".to_string());
+    }
+
+    let changes = patch.changes.len();
+    preview_patch(patch, ExportKind::DatabaseDiff { changes }).await
+}
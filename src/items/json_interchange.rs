@@ -0,0 +1,548 @@
+//! JSON interchange format for moving items between databases.
+//!
+//! Schema (version 1):
+//! ```text
+//! {
+//!   "schema_version": 1,
+//!   "exported_at": "2025-01-01T12:00:00Z",
+//!   "item_groups":        [ ItemGroup, .. ],
+//!   "price_levels":       [ PriceLevel, .. ],
+//!   "product_classes":    [ ProductClass, .. ],
+//!   "tax_groups":         [ TaxGroup, .. ],
+//!   "security_levels":    [ SecurityLevel, .. ],
+//!   "revenue_categories": [ RevenueCategory, .. ],
+//!   "report_categories":  [ ReportCategory, .. ],
+//!   "choice_groups":      [ ChoiceGroup, .. ],
+//!   "printer_logicals":   [ PrinterLogical, .. ],
+//!   "items":              [ Item, .. ]
+//! }
+//! ```
+//! Entities and items use the same field names as the RON save file. Item
+//! references point at the entity ids inside the same file; on import those
+//! entities are matched to existing ones by name, so ids don't need to agree
+//! between the two databases.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::PathBuf;
+use serde::{Serialize, Deserialize};
+use crate::{
+    items::Item,
+    choice_groups::ChoiceGroup,
+    item_groups::ItemGroup,
+    price_levels::PriceLevel,
+    printer_logicals::PrinterLogical,
+    product_classes::ProductClass,
+    report_categories::ReportCategory,
+    revenue_categories::RevenueCategory,
+    security_levels::SecurityLevel,
+    tax_groups::TaxGroup,
+    data_types::EntityId,
+    entity_component::Entity,
+};
+
+/// Bump whenever the shape of `ItemsExport` changes.
+pub const SCHEMA_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ItemsExport {
+    pub schema_version: u32,
+    #[serde(default)]
+    pub exported_at: String,
+    #[serde(default)]
+    pub item_groups: Vec<ItemGroup>,
+    #[serde(default)]
+    pub price_levels: Vec<PriceLevel>,
+    #[serde(default)]
+    pub product_classes: Vec<ProductClass>,
+    #[serde(default)]
+    pub tax_groups: Vec<TaxGroup>,
+    #[serde(default)]
+    pub security_levels: Vec<SecurityLevel>,
+    #[serde(default)]
+    pub revenue_categories: Vec<RevenueCategory>,
+    #[serde(default)]
+    pub report_categories: Vec<ReportCategory>,
+    #[serde(default)]
+    pub choice_groups: Vec<ChoiceGroup>,
+    #[serde(default)]
+    pub printer_logicals: Vec<PrinterLogical>,
+    pub items: Vec<Item>,
+}
+
+impl ItemsExport {
+    pub fn new(
+        items: &BTreeMap<EntityId, Item>,
+        item_groups: &BTreeMap<EntityId, ItemGroup>,
+        price_levels: &BTreeMap<EntityId, PriceLevel>,
+        product_classes: &BTreeMap<EntityId, ProductClass>,
+        tax_groups: &BTreeMap<EntityId, TaxGroup>,
+        security_levels: &BTreeMap<EntityId, SecurityLevel>,
+        revenue_categories: &BTreeMap<EntityId, RevenueCategory>,
+        report_categories: &BTreeMap<EntityId, ReportCategory>,
+        choice_groups: &BTreeMap<EntityId, ChoiceGroup>,
+        printer_logicals: &BTreeMap<EntityId, PrinterLogical>,
+    ) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            exported_at: crate::data_types::timestamp_now(),
+            item_groups: item_groups.values().cloned().collect(),
+            price_levels: price_levels.values().cloned().collect(),
+            product_classes: product_classes.values().cloned().collect(),
+            tax_groups: tax_groups.values().cloned().collect(),
+            security_levels: security_levels.values().cloned().collect(),
+            revenue_categories: revenue_categories.values().cloned().collect(),
+            report_categories: report_categories.values().cloned().collect(),
+            choice_groups: choice_groups.values().cloned().collect(),
+            printer_logicals: printer_logicals.values().cloned().collect(),
+            items: items.values().cloned().collect(),
+        }
+    }
+
+    pub fn to_json_string(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+}
+
+/// Summary of what a JSON import did, shown to the user once the merge is finished
+#[derive(Debug, Clone, Default)]
+pub struct JsonImportReport {
+    pub items_imported: usize,
+    pub items_reassigned: Vec<(EntityId, EntityId)>,
+    pub entities_matched: usize,
+    pub entities_created: usize,
+    pub references_dropped: usize,
+}
+
+impl JsonImportReport {
+    pub fn summary(&self) -> String {
+        let mut lines = vec![
+            format!("Imported {} items.", self.items_imported),
+            format!(
+                "{} entities matched by name, {} created.",
+                self.entities_matched, self.entities_created
+            ),
+        ];
+
+        if !self.items_reassigned.is_empty() {
+            lines.push(format!(
+                "{} items were given new IDs because their ID was already in use.",
+                self.items_reassigned.len()
+            ));
+        }
+
+        if self.references_dropped > 0 {
+            lines.push(format!(
+                "{} references to missing entities were removed.",
+                self.references_dropped
+            ));
+        }
+
+        lines.join("\n")
+    }
+}
+
+pub fn is_json(path: &PathBuf) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map_or(false, |ext_str| ext_str.eq_ignore_ascii_case("json"))
+}
+
+/// Reads and parses a JSON export, rejecting files written by a newer schema
+pub fn read_items_export(path: &PathBuf) -> Result<ItemsExport, Box<dyn std::error::Error>> {
+    println!("Reading JSON export from: {:?}", path);
+    let contents = fs::read_to_string(path)?;
+
+    // Check the version before parsing the whole thing, so a newer file gives
+    // a version error instead of a confusing missing-field error.
+    let value: serde_json::Value = serde_json::from_str(&contents)?;
+    let version = value
+        .get("schema_version")
+        .and_then(|v| v.as_u64())
+        .ok_or("File is missing a schema_version, it doesn't look like a Menu Builder export")?;
+
+    if version > SCHEMA_VERSION as u64 {
+        return Err(format!(
+            "This file was exported with schema version {}, but this version of Menu Builder only supports up to version {}. Please update Menu Builder to import it.",
+            version, SCHEMA_VERSION
+        ).into());
+    }
+
+    let export: ItemsExport = serde_json::from_value(value)?;
+    Ok(export)
+}
+
+/// Merges a JSON export into the given maps.
+///
+/// Entities are matched to existing ones by name (case-insensitive). Unmatched
+/// entities are created when `create_missing` is set, otherwise references to
+/// them are removed from the imported items. Items whose id is already taken
+/// are moved to the first free id in their item group, or after the highest id.
+pub fn merge_items_export(
+    export: ItemsExport,
+    create_missing: bool,
+    items: &mut BTreeMap<EntityId, Item>,
+    item_groups: &mut BTreeMap<EntityId, ItemGroup>,
+    price_levels: &mut BTreeMap<EntityId, PriceLevel>,
+    product_classes: &mut BTreeMap<EntityId, ProductClass>,
+    tax_groups: &mut BTreeMap<EntityId, TaxGroup>,
+    security_levels: &mut BTreeMap<EntityId, SecurityLevel>,
+    revenue_categories: &mut BTreeMap<EntityId, RevenueCategory>,
+    report_categories: &mut BTreeMap<EntityId, ReportCategory>,
+    choice_groups: &mut BTreeMap<EntityId, ChoiceGroup>,
+    printer_logicals: &mut BTreeMap<EntityId, PrinterLogical>,
+) -> JsonImportReport {
+    let mut report = JsonImportReport::default();
+
+    let item_group_map = map_entities(&export.item_groups, item_groups, create_missing, &mut report);
+    let price_level_map = map_entities(&export.price_levels, price_levels, create_missing, &mut report);
+    let product_class_map = map_entities(&export.product_classes, product_classes, create_missing, &mut report);
+    let tax_group_map = map_entities(&export.tax_groups, tax_groups, create_missing, &mut report);
+    let security_level_map = map_entities(&export.security_levels, security_levels, create_missing, &mut report);
+    let revenue_category_map = map_entities(&export.revenue_categories, revenue_categories, create_missing, &mut report);
+    let report_category_map = map_entities(&export.report_categories, report_categories, create_missing, &mut report);
+    let choice_group_map = map_entities(&export.choice_groups, choice_groups, create_missing, &mut report);
+    let printer_logical_map = map_entities(&export.printer_logicals, printer_logicals, create_missing, &mut report);
+
+    for mut item in export.items {
+        let dropped = &mut report.references_dropped;
+
+        item.item_group = remap_single(item.item_group, &item_group_map, dropped);
+        item.product_class = remap_single(item.product_class, &product_class_map, dropped);
+        item.tax_group = remap_single(item.tax_group, &tax_group_map, dropped);
+        item.security_level = remap_single(item.security_level, &security_level_map, dropped);
+        item.revenue_category = remap_single(item.revenue_category, &revenue_category_map, dropped);
+        item.report_category = remap_single(item.report_category, &report_category_map, dropped);
+
+        item.price_levels = item.price_levels.map(|ids| remap_list(ids, &price_level_map, dropped));
+        item.store_price_level = item.store_price_level.map(|ids| remap_list(ids, &price_level_map, dropped));
+        item.item_prices = item.item_prices.map(|prices| {
+            prices.into_iter()
+                .filter_map(|mut price| {
+                    match price_level_map.get(&price.price_level_id) {
+                        Some(new_id) => {
+                            price.price_level_id = *new_id;
+                            Some(price)
+                        }
+                        None => {
+                            *dropped += 1;
+                            None
+                        }
+                    }
+                })
+                .collect()
+        });
+        item.choice_groups = item.choice_groups.map(|groups| {
+            groups.into_iter()
+                .filter_map(|(id, sequence)| {
+                    match choice_group_map.get(&id) {
+                        Some(new_id) => Some((*new_id, sequence)),
+                        None => {
+                            *dropped += 1;
+                            None
+                        }
+                    }
+                })
+                .collect()
+        });
+        item.printer_logicals = item.printer_logicals.map(|printers| {
+            printers.into_iter()
+                .filter_map(|(id, primary)| {
+                    match printer_logical_map.get(&id) {
+                        Some(new_id) => Some((*new_id, primary)),
+                        None => {
+                            *dropped += 1;
+                            None
+                        }
+                    }
+                })
+                .collect()
+        });
+
+        if items.contains_key(&item.id) {
+            let old_id = item.id;
+            let group_range = item.item_group
+                .and_then(|group_id| item_groups.get(&group_id))
+                .map(|group| group.id_range.clone());
+            let new_id = next_free_item_id(items, group_range);
+
+            println!("Item ID {} already in use, reassigning to {}", old_id, new_id);
+            item.id = new_id;
+            report.items_reassigned.push((old_id, new_id));
+        }
+
+        items.insert(item.id, item);
+        report.items_imported += 1;
+    }
+
+    println!("JSON import finished: {:?}", report);
+    report
+}
+
+/// What merging the export would do, worked out on copies so nothing changes until it's confirmed
+pub fn preview_merge(
+    export: &ItemsExport,
+    create_missing: bool,
+    items: &BTreeMap<EntityId, Item>,
+    item_groups: &BTreeMap<EntityId, ItemGroup>,
+    price_levels: &BTreeMap<EntityId, PriceLevel>,
+    product_classes: &BTreeMap<EntityId, ProductClass>,
+    tax_groups: &BTreeMap<EntityId, TaxGroup>,
+    security_levels: &BTreeMap<EntityId, SecurityLevel>,
+    revenue_categories: &BTreeMap<EntityId, RevenueCategory>,
+    report_categories: &BTreeMap<EntityId, ReportCategory>,
+    choice_groups: &BTreeMap<EntityId, ChoiceGroup>,
+    printer_logicals: &BTreeMap<EntityId, PrinterLogical>,
+) -> JsonImportReport {
+    merge_items_export(
+        export.clone(),
+        create_missing,
+        &mut items.clone(),
+        &mut item_groups.clone(),
+        &mut price_levels.clone(),
+        &mut product_classes.clone(),
+        &mut tax_groups.clone(),
+        &mut security_levels.clone(),
+        &mut revenue_categories.clone(),
+        &mut report_categories.clone(),
+        &mut choice_groups.clone(),
+        &mut printer_logicals.clone(),
+    )
+}
+
+/// Maps ids from the incoming file to ids in the existing map, matching by name
+fn map_entities<T: Entity>(
+    incoming: &[T],
+    existing: &mut BTreeMap<EntityId, T>,
+    create_missing: bool,
+    report: &mut JsonImportReport,
+) -> HashMap<EntityId, EntityId> {
+    let mut id_map = HashMap::new();
+
+    for entity in incoming {
+        let matched = existing
+            .values()
+            .find(|e| e.name().trim().eq_ignore_ascii_case(entity.name().trim()))
+            .map(|e| e.id());
+
+        match matched {
+            Some(existing_id) => {
+                id_map.insert(entity.id(), existing_id);
+                report.entities_matched += 1;
+            }
+            None if create_missing => {
+                let new_id = if existing.contains_key(&entity.id()) {
+                    existing.keys().max().map_or(1, |max| max + 1)
+                } else {
+                    entity.id()
+                };
+
+                existing.insert(new_id, entity.with_id(new_id));
+                id_map.insert(entity.id(), new_id);
+                report.entities_created += 1;
+                println!("Created {} with ID: {}", entity.name(), new_id);
+            }
+            None => {
+                println!("No match for {}, references will be removed", entity.name());
+            }
+        }
+    }
+
+    id_map
+}
+
+fn remap_single(
+    id: Option<EntityId>,
+    id_map: &HashMap<EntityId, EntityId>,
+    dropped: &mut usize,
+) -> Option<EntityId> {
+    let id = id?;
+    match id_map.get(&id) {
+        Some(new_id) => Some(*new_id),
+        None => {
+            *dropped += 1;
+            None
+        }
+    }
+}
+
+fn remap_list(
+    ids: Vec<EntityId>,
+    id_map: &HashMap<EntityId, EntityId>,
+    dropped: &mut usize,
+) -> Vec<EntityId> {
+    ids.into_iter()
+        .filter_map(|id| remap_single(Some(id), id_map, dropped))
+        .collect()
+}
+
+fn next_free_item_id(
+    items: &BTreeMap<EntityId, Item>,
+    group_range: Option<std::ops::Range<EntityId>>,
+) -> EntityId {
+    if let Some(range) = group_range {
+        if let Some(id) = range.clone().find(|id| !items.contains_key(id)) {
+            return id;
+        }
+    }
+
+    items.keys().max().map_or(1, |max| max + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_types::ItemPrice;
+    use rust_decimal::Decimal;
+
+    #[derive(Default)]
+    struct Database {
+        items: BTreeMap<EntityId, Item>,
+        item_groups: BTreeMap<EntityId, ItemGroup>,
+        price_levels: BTreeMap<EntityId, PriceLevel>,
+        product_classes: BTreeMap<EntityId, ProductClass>,
+        tax_groups: BTreeMap<EntityId, TaxGroup>,
+        security_levels: BTreeMap<EntityId, SecurityLevel>,
+        revenue_categories: BTreeMap<EntityId, RevenueCategory>,
+        report_categories: BTreeMap<EntityId, ReportCategory>,
+        choice_groups: BTreeMap<EntityId, ChoiceGroup>,
+        printer_logicals: BTreeMap<EntityId, PrinterLogical>,
+    }
+
+    impl Database {
+        fn sample() -> Self {
+            let mut db = Database::default();
+            db.item_groups.insert(1, ItemGroup { id: 1, name: "Drinks".to_string(), id_range: 100..199, ..ItemGroup::default() });
+            db.price_levels.insert(2, PriceLevel { id: 2, name: "Happy Hour".to_string(), ..PriceLevel::default() });
+            db.tax_groups.insert(3, TaxGroup { id: 3, name: "Food Tax".to_string(), ..TaxGroup::default() });
+            db.choice_groups.insert(4, ChoiceGroup { id: 4, name: "Sizes".to_string(), ..ChoiceGroup::default() });
+            db.printer_logicals.insert(5, PrinterLogical { id: 5, name: "Bar".to_string(), ..PrinterLogical::default() });
+
+            db.items.insert(100, Item {
+                id: 100,
+                name: "Cola".to_string(),
+                button1: "Cola".to_string(),
+                item_group: Some(1),
+                tax_group: Some(3),
+                default_price: Some(Decimal::new(250, 2)),
+                price_levels: Some(vec![2]),
+                item_prices: Some(vec![ItemPrice { price_level_id: 2, price: Decimal::new(199, 2) }]),
+                choice_groups: Some(vec![(4, 1)]),
+                printer_logicals: Some(vec![(5, true)]),
+                ..Item::default()
+            });
+            db.items.insert(101, Item {
+                id: 101,
+                name: "Tea \"Iced\"".to_string(),
+                item_group: Some(1),
+                ..Item::default()
+            });
+            db
+        }
+
+        fn export(&self) -> ItemsExport {
+            ItemsExport::new(
+                &self.items, &self.item_groups, &self.price_levels, &self.product_classes,
+                &self.tax_groups, &self.security_levels, &self.revenue_categories,
+                &self.report_categories, &self.choice_groups, &self.printer_logicals,
+            )
+        }
+
+        fn merge(&mut self, export: ItemsExport, create_missing: bool) -> JsonImportReport {
+            merge_items_export(
+                export, create_missing,
+                &mut self.items, &mut self.item_groups, &mut self.price_levels, &mut self.product_classes,
+                &mut self.tax_groups, &mut self.security_levels, &mut self.revenue_categories,
+                &mut self.report_categories, &mut self.choice_groups, &mut self.printer_logicals,
+            )
+        }
+
+        fn preview(&self, export: &ItemsExport, create_missing: bool) -> JsonImportReport {
+            preview_merge(
+                export, create_missing,
+                &self.items, &self.item_groups, &self.price_levels, &self.product_classes,
+                &self.tax_groups, &self.security_levels, &self.revenue_categories,
+                &self.report_categories, &self.choice_groups, &self.printer_logicals,
+            )
+        }
+    }
+
+    // Goes through the file, the way a user moves data between databases
+    fn through_file(name: &str, export: &ItemsExport) -> ItemsExport {
+        let path = std::env::temp_dir().join(format!("json_interchange_{}_{}.json", std::process::id(), name));
+        fs::write(&path, export.to_json_string().unwrap()).unwrap();
+        let read = read_items_export(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        read
+    }
+
+    #[test]
+    fn export_then_import_into_empty_database_round_trips() {
+        let source = Database::sample();
+        let mut target = Database::default();
+
+        let report = target.merge(through_file("empty", &source.export()), true);
+
+        assert_eq!(report.items_imported, 2);
+        assert!(report.items_reassigned.is_empty());
+        assert_eq!(report.references_dropped, 0);
+        assert_eq!(target.items, source.items);
+        assert_eq!(target.item_groups, source.item_groups);
+        assert_eq!(target.price_levels, source.price_levels);
+        assert_eq!(target.tax_groups, source.tax_groups);
+        assert_eq!(target.choice_groups, source.choice_groups);
+        assert_eq!(target.printer_logicals, source.printer_logicals);
+    }
+
+    #[test]
+    fn reassigned_ids_keep_their_relationships() {
+        let source = Database::sample();
+        let mut target = Database::default();
+        // Same names under other ids, and the item ids already taken
+        target.item_groups.insert(7, ItemGroup { id: 7, name: "drinks".to_string(), id_range: 100..199, ..ItemGroup::default() });
+        target.tax_groups.insert(8, TaxGroup { id: 8, name: "Food Tax".to_string(), ..TaxGroup::default() });
+        target.items.insert(100, Item { id: 100, name: "Water".to_string(), ..Item::default() });
+
+        let report = target.merge(through_file("taken", &source.export()), true);
+
+        // The first free ids in the matched group's range
+        assert_eq!(report.items_reassigned, vec![(100, 101), (101, 102)]);
+        assert_eq!(target.items[&102].name, "Tea \"Iced\"");
+        let cola = &target.items[&101];
+        assert_eq!(cola.name, "Cola");
+        assert_eq!(cola.item_group, Some(7));
+        assert_eq!(cola.tax_group, Some(8));
+        assert_eq!(cola.item_prices, source.items[&100].item_prices);
+        assert_eq!(target.items[&100].name, "Water");
+    }
+
+    #[test]
+    fn preview_reports_the_merge_without_changing_anything() {
+        let source = Database::sample();
+        let mut target = Database::default();
+        target.items.insert(100, Item { id: 100, name: "Water".to_string(), ..Item::default() });
+        let export = source.export();
+
+        let preview = target.preview(&export, false);
+        assert_eq!(target.items.len(), 1);
+        assert!(target.item_groups.is_empty());
+
+        let report = target.merge(export, false);
+        assert_eq!(preview.items_imported, report.items_imported);
+        assert_eq!(preview.items_reassigned, report.items_reassigned);
+        assert_eq!(preview.references_dropped, report.references_dropped);
+        assert!(preview.references_dropped > 0);
+    }
+
+    #[test]
+    fn newer_schema_is_rejected() {
+        let mut export = Database::sample().export();
+        export.schema_version = SCHEMA_VERSION + 1;
+        let path = std::env::temp_dir().join(format!("json_interchange_newer_{}.json", std::process::id()));
+        fs::write(&path, export.to_json_string().unwrap()).unwrap();
+
+        let error = read_items_export(&path).unwrap_err().to_string();
+        fs::remove_file(&path).unwrap();
+        assert!(error.contains("schema version"), "{}", error);
+    }
+}
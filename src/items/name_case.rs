@@ -0,0 +1,127 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use crate::data_types::EntityId;
+use super::Item;
+
+// Left lowercase in title case unless they start or end the name
+const SMALL_WORDS: &[&str] = &[
+    "a", "an", "and", "as", "at", "but", "by", "en", "for", "in", "n", "nor",
+    "of", "on", "or", "the", "to", "w", "with",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NameCase {
+    #[default]
+    Title,
+    Upper,
+    Lower,
+}
+
+impl NameCase {
+    pub const ALL: &'static [Self] = &[
+        Self::Title,
+        Self::Upper,
+        Self::Lower,
+    ];
+
+    pub fn apply(&self, name: &str) -> String {
+        match self {
+            Self::Title => title_case(name),
+            Self::Upper => name.to_uppercase(),
+            Self::Lower => name.to_lowercase(),
+        }
+    }
+}
+
+impl fmt::Display for NameCase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Title => write!(f, "Title Case"),
+            Self::Upper => write!(f, "UPPERCASE"),
+            Self::Lower => write!(f, "lowercase"),
+        }
+    }
+}
+
+/// "CHICKEN AND WAFFLES" -> "Chicken and Waffles", spacing is kept as is
+pub fn title_case(name: &str) -> String {
+    let words: Vec<&str> = name.split(' ').collect();
+    let first = words.iter().position(|word| !word.is_empty());
+    let last = words.iter().rposition(|word| !word.is_empty());
+
+    words
+        .iter()
+        .enumerate()
+        .map(|(i, word)| {
+            let lowered = word.to_lowercase();
+            let edge = Some(i) == first || Some(i) == last;
+            if !edge && SMALL_WORDS.contains(&lowered.as_str()) {
+                lowered
+            } else {
+                capitalize(&lowered)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+// Capitalizes the first letter of each hyphenated part, so "(bbq" -> "(Bbq" and "half-pound" -> "Half-Pound"
+fn capitalize(word: &str) -> String {
+    let mut result = String::with_capacity(word.len());
+    let mut at_start = true;
+
+    for c in word.chars() {
+        if at_start && c.is_alphabetic() {
+            result.extend(c.to_uppercase());
+            at_start = false;
+        } else {
+            result.push(c);
+            if c == '-' || c == '/' {
+                at_start = true;
+            }
+        }
+    }
+
+    result
+}
+
+#[derive(Debug, Clone)]
+pub struct CaseChange {
+    pub item_id: EntityId,
+    pub old_name: String,
+    pub new_name: String,
+}
+
+/// State of the name casing popup, for the items that were listed when it was opened
+#[derive(Debug, Clone)]
+pub struct NameCaseTool {
+    pub case: NameCase,
+    pub item_ids: Vec<EntityId>,
+    pub confirming: bool,
+}
+
+impl NameCaseTool {
+    pub fn new(item_ids: Vec<EntityId>) -> Self {
+        Self {
+            case: NameCase::default(),
+            item_ids,
+            confirming: false,
+        }
+    }
+
+    /// Items whose name would change
+    pub fn preview(&self, items: &BTreeMap<EntityId, Item>) -> Vec<CaseChange> {
+        self.item_ids
+            .iter()
+            .filter_map(|id| items.get(id))
+            .filter_map(|item| {
+                let new_name = self.case.apply(&item.name);
+                (new_name != item.name).then(|| CaseChange {
+                    item_id: item.id,
+                    old_name: item.name.clone(),
+                    new_name,
+                })
+            })
+            .collect()
+    }
+}
@@ -11,12 +11,13 @@ use crate::{
     printer_logicals::PrinterLogical,
     price_levels::PriceLevel,
     icon,
+    text_style::TextStyle,
 };
-use std::collections::{HashMap, BTreeMap};
+use std::collections::{HashMap, BTreeMap, BTreeSet};
 use rust_decimal::Decimal;
 use iced_modern_theme::Modern;
-use iced::{Element, Length, Theme, Renderer, Color};
-use iced::widget::{column, row, scrollable, container, responsive, text, horizontal_space};
+use iced::{keyboard, Element, Length, Theme, Renderer, Color, Task};
+use iced::widget::{button, checkbox, column, row, scrollable, container, mouse_area, responsive, text, horizontal_space};
 use iced_table::{table, ColumnVisibilityMessage};
 use crate::superedit::HasName;
 
@@ -27,6 +28,91 @@ pub enum Message {
     Resized,
     ColumnVisibilityEnabled(bool),
     ColumnVisibility(ColumnVisibilityMessage),
+    ToggleFlag(EntityId, ItemFlag, bool),
+    SetFlagForAll(ItemFlag),
+
+    // Row selection, only in the filtered table
+    PressRow(EntityId),
+    ModifiersChanged(keyboard::Modifiers),
+    MoveFocus(bool), // True moves down
+    ToggleFocused,
+    ClearSelection,
+    OpenSelected,
+    DuplicateSelected,
+    RequestDeleteSelected,
+    ConfirmDeleteSelected,
+    CancelDeleteSelected,
+    PinSelected,
+    UseSelectionAsScope,
+}
+
+/// Item flags that can be switched straight from the filtered table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ItemFlag {
+    PrintOnCheck,
+    Discountable,
+    Voidable,
+    NotActive,
+    TaxIncluded,
+    StockItem,
+}
+
+impl ItemFlag {
+    pub const ALL: [ItemFlag; 6] = [
+        ItemFlag::PrintOnCheck,
+        ItemFlag::Discountable,
+        ItemFlag::Voidable,
+        ItemFlag::NotActive,
+        ItemFlag::TaxIncluded,
+        ItemFlag::StockItem,
+    ];
+
+    pub fn get(&self, item: &Item) -> bool {
+        match self {
+            ItemFlag::PrintOnCheck => item.print_on_check,
+            ItemFlag::Discountable => item.discountable,
+            ItemFlag::Voidable => item.voidable,
+            ItemFlag::NotActive => item.not_active,
+            ItemFlag::TaxIncluded => item.tax_included,
+            ItemFlag::StockItem => item.stock_item,
+        }
+    }
+
+    pub fn set(&self, item: &mut Item, value: bool) {
+        match self {
+            ItemFlag::PrintOnCheck => item.print_on_check = value,
+            ItemFlag::Discountable => item.discountable = value,
+            ItemFlag::Voidable => item.voidable = value,
+            ItemFlag::NotActive => item.not_active = value,
+            ItemFlag::TaxIncluded => item.tax_included = value,
+            ItemFlag::StockItem => item.stock_item = value,
+        }
+    }
+
+    fn from_column(column_type: ColumnType) -> Option<ItemFlag> {
+        match column_type {
+            ColumnType::PrintOnCheck => Some(ItemFlag::PrintOnCheck),
+            ColumnType::Discountable => Some(ItemFlag::Discountable),
+            ColumnType::Voidable => Some(ItemFlag::Voidable),
+            ColumnType::NotActive => Some(ItemFlag::NotActive),
+            ColumnType::TaxIncluded => Some(ItemFlag::TaxIncluded),
+            ColumnType::StockItem => Some(ItemFlag::StockItem),
+            _ => None,
+        }
+    }
+}
+
+impl std::fmt::Display for ItemFlag {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ItemFlag::PrintOnCheck => write!(f, "Print On Check"),
+            ItemFlag::Discountable => write!(f, "Discountable"),
+            ItemFlag::Voidable => write!(f, "Voidable"),
+            ItemFlag::NotActive => write!(f, "Not Active"),
+            ItemFlag::TaxIncluded => write!(f, "Tax Included"),
+            ItemFlag::StockItem => write!(f, "Stock Item"),
+        }
+    }
 }
 
 // Enum to track cell changes
@@ -80,6 +166,46 @@ pub struct ItemsTableView {
     pub column_visibility_enabled: bool,
     pub column_visibility: HashMap<String, bool>,
     pub show_diff: bool, // Flag to enable diff visualization
+    pub row_ids: Vec<EntityId>, // Items in the rows, to tell whether a rebuild shows the same list
+    pub scroll_offset: scrollable::AbsoluteOffset, // Last offset reported through SyncHeader
+    pub selected: BTreeSet<EntityId>, // Rows picked with click, ctrl-click and shift-click
+    pub focused: Option<EntityId>, // Row the arrow keys move and space toggles
+    pub confirm_delete: bool, // Deleting more than one row asks first
+    selection_anchor: Option<EntityId>, // Where a shift-click range starts
+    modifiers: keyboard::Modifiers, // Held keys, a click alone can't tell
+    text_style: TextStyle, // Cells are drawn by the columns, see set_text_style
+}
+
+/// Which of a screen's tables a scroll position belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TableContext {
+    Filtered,
+    Preview,
+}
+
+/// Last scroll position of each table context, kept across rebuilds
+#[derive(Debug, Clone, Default)]
+pub struct ScrollMemory {
+    positions: HashMap<TableContext, (Vec<EntityId>, scrollable::AbsoluteOffset)>,
+}
+
+impl ScrollMemory {
+    /// Keeps where a table being replaced was scrolled to
+    pub fn remember(&mut self, table: ItemsTableView) {
+        self.positions.insert(table.context(), (table.row_ids, table.scroll_offset));
+    }
+
+    /// Puts a rebuilt table back where its context was, if it still shows the same rows.
+    /// Returns whether the table has an offset to scroll to.
+    pub fn recall(&self, table: &mut ItemsTableView) -> bool {
+        match self.positions.get(&table.context()) {
+            Some((row_ids, offset)) if *row_ids == table.row_ids => {
+                table.scroll_offset = *offset;
+                true
+            }
+            _ => false,
+        }
+    }
 }
 
 impl ItemsTableView {
@@ -159,9 +285,135 @@ impl ItemsTableView {
             column_visibility_enabled: true,
             column_visibility,
             show_diff: false,
+            row_ids: items.keys().copied().collect(),
+            scroll_offset: scrollable::AbsoluteOffset { x: 0.0, y: 0.0 },
+            selected: BTreeSet::new(),
+            focused: None,
+            confirm_delete: false,
+            selection_anchor: None,
+            modifiers: keyboard::Modifiers::default(),
+            text_style: TextStyle::default(),
         }
     }
 
+    /// The change preview is read only, only the filtered table has selectable rows
+    pub fn is_selectable(&self) -> bool {
+        !self.show_diff
+    }
+
+    pub fn set_modifiers(&mut self, modifiers: keyboard::Modifiers) {
+        self.modifiers = modifiers;
+    }
+
+    /// The table widget only hands the columns to `cell`, so each column keeps a copy
+    pub fn set_text_style(&mut self, text_style: TextStyle) {
+        self.text_style = text_style;
+        for column in &mut self.columns {
+            column.text_style = text_style;
+        }
+    }
+
+    /// A click selects just that row, ctrl-click (cmd on macOS) toggles it
+    /// and shift-click selects everything from the last clicked row
+    pub fn press_row(&mut self, id: EntityId) {
+        if self.modifiers.shift() {
+            let range = self.anchor_range(id);
+            if !self.modifiers.command() {
+                self.selected.clear();
+            }
+            self.selected.extend(range);
+        } else if self.modifiers.command() {
+            if !self.selected.remove(&id) {
+                self.selected.insert(id);
+            }
+            self.selection_anchor = Some(id);
+        } else {
+            self.selected = BTreeSet::from([id]);
+            self.selection_anchor = Some(id);
+        }
+        self.focused = Some(id);
+        self.confirm_delete = false;
+        self.sync_selection();
+    }
+
+    // Rows from the anchor to `id` in table order, either way round
+    fn anchor_range(&self, id: EntityId) -> Vec<EntityId> {
+        let position = |id: EntityId| self.row_ids.iter().position(|row| *row == id);
+        let anchor = self.selection_anchor.unwrap_or(id);
+
+        match (position(anchor), position(id)) {
+            (Some(a), Some(b)) => self.row_ids[a.min(b)..=a.max(b)].to_vec(),
+            _ => vec![id],
+        }
+    }
+
+    pub fn move_focus(&mut self, down: bool) {
+        let index = self.focused.and_then(|id| self.row_ids.iter().position(|row| *row == id));
+        let next = match index {
+            None => 0,
+            Some(index) if down => (index + 1).min(self.row_ids.len().saturating_sub(1)),
+            Some(index) => index.saturating_sub(1),
+        };
+        self.focused = self.row_ids.get(next).copied();
+        self.sync_selection();
+    }
+
+    pub fn toggle_focused(&mut self) {
+        if let Some(id) = self.focused {
+            if !self.selected.remove(&id) {
+                self.selected.insert(id);
+            }
+            self.selection_anchor = Some(id);
+            self.confirm_delete = false;
+            self.sync_selection();
+        }
+    }
+
+    pub fn clear_selection(&mut self) {
+        self.selected.clear();
+        self.confirm_delete = false;
+        self.sync_selection();
+    }
+
+    /// Selected items in table order
+    pub fn selected_ids(&self) -> Vec<EntityId> {
+        self.row_ids.iter().filter(|id| self.selected.contains(id)).copied().collect()
+    }
+
+    /// Carries the selection over from the table this one replaces, dropping rows it no longer has
+    pub fn keep_selection(&mut self, previous: &ItemsTableView) {
+        self.selected = previous.selected.iter().filter(|id| self.row_ids.contains(id)).copied().collect();
+        self.focused = previous.focused.filter(|id| self.row_ids.contains(id));
+        self.selection_anchor = previous.selection_anchor;
+        self.modifiers = previous.modifiers;
+        self.sync_selection();
+    }
+
+    // Rows carry their highlight so cells can draw it
+    fn sync_selection(&mut self) {
+        let selectable = self.is_selectable();
+        for row in self.rows.iter_mut() {
+            row.selected = selectable && self.selected.contains(&row.item_id);
+            row.focused = selectable && self.focused == Some(row.item_id);
+        }
+    }
+
+    pub fn context(&self) -> TableContext {
+        if self.show_diff {
+            TableContext::Preview
+        } else {
+            TableContext::Filtered
+        }
+    }
+
+    /// Scrolls the body, and the header along with it, to the stored offset
+    pub fn restore_scroll<T>(&self) -> Task<T> {
+        Task::batch([
+            scrollable::scroll_to(self.body_id.clone(), self.scroll_offset),
+            scrollable::scroll_to(self.header_id.clone(), self.scroll_offset),
+        ])
+    }
+
     // New method to create a table with diff view
     pub fn new_with_diff(
         original_items: &BTreeMap<EntityId, Item>,
@@ -226,6 +478,55 @@ impl ItemsTableView {
         table
     }
 
+    /// Turns the flag columns into checkboxes showing `pending` on top of the items' own
+    /// values. Pending cells get the diff styling until they are applied.
+    pub fn edit_flags(&mut self, pending: &BTreeMap<(EntityId, ItemFlag), bool>) {
+        for row in self.rows.iter_mut() {
+            for flag in ItemFlag::ALL {
+                let cell = row.flag_cell_mut(flag);
+                let original = cell.original.clone();
+                *cell = match pending.get(&(row.item_id, flag)) {
+                    Some(value) if value.to_string() != original => CellValue::modified(original, value.to_string()),
+                    _ => CellValue::unchanged(original),
+                };
+            }
+        }
+
+        for column in self.columns.iter_mut() {
+            column.flag = ItemFlag::from_column(column.column_type);
+            if let Some(flag) = column.flag {
+                let values: Vec<bool> = self.rows.iter().map(|row| row.flag_value(flag)).collect();
+                column.flag_state = if !values.is_empty() && values.iter().all(|value| *value) {
+                    Some(true)
+                } else if values.iter().all(|value| !*value) {
+                    Some(false)
+                } else {
+                    None
+                };
+            }
+        }
+    }
+
+    /// Each row's item and what its flag shows, pending changes included
+    pub fn flag_values(&self, flag: ItemFlag) -> Vec<(EntityId, bool)> {
+        self.rows.iter().map(|row| (row.item_id, row.flag_value(flag))).collect()
+    }
+
+    /// Adds a column after Name listing which conditions each row's item passed,
+    /// keyed by item id. Rows without an entry are left blank.
+    pub fn show_matched_conditions(&mut self, results: &BTreeMap<EntityId, String>) {
+        for (row, id) in self.rows.iter_mut().zip(self.row_ids.iter()) {
+            if let Some(result) = results.get(id) {
+                row.matchedConditions = CellValue::unchanged(result.clone());
+            }
+        }
+
+        self.column_visibility.insert("Matched Conditions".to_string(), true);
+        let mut column = Column::new(ColumnType::MatchedConditions);
+        column.text_style = self.text_style;
+        self.columns.insert(2, column);
+    }
+
     pub fn render(&self) -> Element<Message> {
         let table = responsive(|_size| {
             let mut table = table(
@@ -248,11 +549,47 @@ impl ItemsTableView {
 
 
         let content = column![
+            self.selection_bar(),
             table,
-        ];
+        ]
+        .spacing(10);
 
         container(content).padding(20).center_x(Length::Fill).center_y(Length::Fill).into()
     }
+
+    // What can be done with the selected rows. Opening and duplicating work on one item at a time.
+    fn selection_bar(&self) -> Element<Message> {
+        let count = self.selected.len();
+        if !self.is_selectable() || count == 0 {
+            return row![].into();
+        }
+
+        if self.confirm_delete {
+            return row![
+                text(format!("Delete {} items? Deleted items are kept for delta exports.", count))
+                    .style(Modern::red_text()),
+                button("Delete").on_press(Message::ConfirmDeleteSelected).style(Modern::danger_button()),
+                button("Cancel").on_press(Message::CancelDeleteSelected).style(Modern::system_button()),
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center)
+            .into();
+        }
+
+        let single = count == 1;
+        row![
+            text(format!("{} selected", count)).style(Modern::primary_text()),
+            button("Open").on_press_maybe(single.then_some(Message::OpenSelected)).style(Modern::system_button()),
+            button("Duplicate").on_press_maybe(single.then_some(Message::DuplicateSelected)).style(Modern::system_button()),
+            button("Delete").on_press(Message::RequestDeleteSelected).style(Modern::danger_button()),
+            button("Pin").on_press(Message::PinSelected).style(Modern::system_button()),
+            button("Use as SuperEdit Scope").on_press(Message::UseSelectionAsScope).style(Modern::system_button()),
+            button("Clear").on_press(Message::ClearSelection).style(Modern::gray_button()),
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center)
+        .into()
+    }
 }
 
 fn create_columns() -> Vec<Column> {
@@ -302,6 +639,9 @@ pub struct Column {
     pub width: f32,
     pub resize_offset: Option<f32>,
     pub visible: bool,
+    pub flag: Option<ItemFlag>, // Set while the column is editable, see edit_flags
+    pub flag_state: Option<bool>, // All rows set, none set, or None when mixed
+    pub text_style: TextStyle,
 }
 
 impl Column {
@@ -343,6 +683,7 @@ impl Column {
             ColumnType::LanguageISOCode =>  150.0,
             ColumnType::ChoiceGroups =>  500.0, // Wider for list
             ColumnType::PrinterLogicals =>  300.0, // Wider for list
+            ColumnType::MatchedConditions =>  300.0,
         };
 
         let visible = match columntype { // Hidden by default
@@ -366,6 +707,9 @@ impl Column {
             width,
             resize_offset: None,
             visible,
+            flag: None,
+            flag_state: None,
+            text_style: TextStyle::default(),
         }
     }
 
@@ -407,6 +751,7 @@ impl Column {
             ColumnType::LanguageISOCode =>  "Language ISO Code",
             ColumnType::ChoiceGroups =>  "Choice Groups",
             ColumnType::PrinterLogicals =>  "Printer Logicals",
+            ColumnType::MatchedConditions =>  "Matched Conditions",
         }
     }
 }
@@ -449,10 +794,21 @@ pub enum ColumnType {
     LanguageISOCode,
     ChoiceGroups,
     PrinterLogicals,
+    MatchedConditions, // Only added by show_matched_conditions
+}
+
+impl ColumnType {
+    // ID and price columns, drawn in the data font so digits line up
+    fn is_data(&self) -> bool {
+        matches!(self, ColumnType::Id | ColumnType::CostAmount | ColumnType::PriceLevels | ColumnType::WeightAmount)
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct Row {
+    item_id: EntityId,
+    selected: bool, // Highlight state, see ItemsTableView::sync_selection
+    focused: bool,
     id: CellValue,
     name: CellValue,
     button1: CellValue,
@@ -489,6 +845,7 @@ pub struct Row {
     languageISOCode: CellValue,
     choiceGroups: CellValue,
     printerLogicals: CellValue,
+    matchedConditions: CellValue,
 }
 
 impl Row {
@@ -570,6 +927,9 @@ impl Row {
         ));        
 
         Self {
+            item_id: item.id,
+            selected: false,
+            focused: false,
             id,
             name,
             button1,
@@ -606,6 +966,7 @@ impl Row {
             languageISOCode,
             choiceGroups,
             printerLogicals,
+            matchedConditions: CellValue::unchanged(String::new()),
         }
     }
 
@@ -639,6 +1000,9 @@ impl Row {
         };
 
         Self {
+            item_id: original.id,
+            selected: false,
+            focused: false,
             id: CellValue::unchanged(original.id.to_string()),
             name: diff_value(original.name.clone(), modified.name.clone()),
             button1: diff_value(original.button1.clone(), modified.button1.clone()),
@@ -807,10 +1171,39 @@ impl Row {
                 get_printer_logicals_string_with_names(original.printer_logicals.as_ref(), printer_logicals),
                 get_printer_logicals_string_with_names(modified.printer_logicals.as_ref(), printer_logicals)
             ),
+            matchedConditions: CellValue::unchanged(String::new()),
         }
     }
 }
 
+impl Row {
+    fn flag_cell_mut(&mut self, flag: ItemFlag) -> &mut CellValue {
+        match flag {
+            ItemFlag::PrintOnCheck => &mut self.printOnCheck,
+            ItemFlag::Discountable => &mut self.discountable,
+            ItemFlag::Voidable => &mut self.voidable,
+            ItemFlag::NotActive => &mut self.notActive,
+            ItemFlag::TaxIncluded => &mut self.taxIncluded,
+            ItemFlag::StockItem => &mut self.stockItem,
+        }
+    }
+
+    fn flag_cell(&self, flag: ItemFlag) -> &CellValue {
+        match flag {
+            ItemFlag::PrintOnCheck => &self.printOnCheck,
+            ItemFlag::Discountable => &self.discountable,
+            ItemFlag::Voidable => &self.voidable,
+            ItemFlag::NotActive => &self.notActive,
+            ItemFlag::TaxIncluded => &self.taxIncluded,
+            ItemFlag::StockItem => &self.stockItem,
+        }
+    }
+
+    fn flag_value(&self, flag: ItemFlag) -> bool {
+        self.flag_cell(flag).display() == "true"
+    }
+}
+
 fn get_prices_string_with_names(
     default_price: Option<Decimal>,
     item_prices: Option<&Vec<ItemPrice>>, 
@@ -899,13 +1292,30 @@ fn parse_list_string(s: &str) -> Vec<String> {
     }
 }
 
+// Selected rows get a tint of the theme's primary color, the focused row an outline
+fn row_highlight(selected: bool, focused: bool) -> impl Fn(&Theme) -> container::Style {
+    move |theme: &Theme| container::Style {
+        background: selection_background(theme, selected),
+        border: iced::Border {
+            color: theme.extended_palette().primary.strong.color,
+            width: if focused { 1.0 } else { 0.0 },
+            radius: 0.0.into(),
+        },
+        ..Default::default()
+    }
+}
+
+fn selection_background(theme: &Theme, selected: bool) -> Option<iced::Background> {
+    selected.then(|| theme.extended_palette().primary.weak.color.scale_alpha(0.4).into())
+}
+
 // Helper function to create a diff view for list-like fields
-fn create_list_diff_view<'a>(original: &str, modified: &str) -> Element<'a, Message> {
+fn create_list_diff_view<'a>(original: &str, modified: &str, text_style: TextStyle) -> Element<'a, Message> {
     let orig_items = parse_list_string(original);
     let mod_items = parse_list_string(modified);
 
     let mut elements: Vec<Element<'a, Message>> = vec![
-        text("[").size(14).style(Modern::primary_text()).into()
+        text("[").size(text_style.body()).style(Modern::primary_text()).into()
     ];
     
     let mut first = true;
@@ -921,19 +1331,19 @@ fn create_list_diff_view<'a>(original: &str, modified: &str) -> Element<'a, Mess
             .find(|(idx, m)| m == &orig_item && !used_mod_indices.contains(idx));
         
         if !first {
-            elements.push(text(", ").size(14).into());
+            elements.push(text(", ").size(text_style.body()).into());
         }
         first = false;
         
         if let Some((idx, _)) = found_in_modified {
             // Item still exists - show in normal color
-            elements.push(text(orig_item.clone()).size(14).into());
+            elements.push(text(orig_item.clone()).size(text_style.body()).into());
             used_mod_indices.insert(idx);
         } else {
             // Item was removed - show in red with strikethrough
             elements.push(
                 text("-".to_string() + &orig_item.clone())
-                    .size(14)
+                    .size(text_style.body())
                     .style(Modern::red_text())
                     .into()
             );
@@ -944,21 +1354,21 @@ fn create_list_diff_view<'a>(original: &str, modified: &str) -> Element<'a, Mess
     for (idx, mod_item) in mod_items.iter().enumerate() {
         if !used_mod_indices.contains(&idx) {
             if !first {
-                elements.push(text(", ").size(14).into());
+                elements.push(text(", ").size(text_style.body()).into());
             }
             first = false;
             
             // New item - show in green
             elements.push(
                 text("+".to_string() + &mod_item.clone())
-                    .size(14)
+                    .size(text_style.body())
                     .style(Modern::green_text())
                     .into()
             );
         }
     }
 
-    elements.push(text("]").size(14).style(Modern::primary_text()).into());
+    elements.push(text("]").size(text_style.body()).style(Modern::primary_text()).into());
 
     let content = iced::widget::Row::from_vec(elements);
     
@@ -970,6 +1380,24 @@ impl<'a> table::Column<'a, Message, Theme, Renderer> for Column {
 
     fn header(&'a self, _col_index: usize) -> Element<'a, Message> {
         let content = self.display_name();
+
+        // Sets the flag on every row, or clears it when every row already has it
+        if let Some(flag) = self.flag {
+            let mark = match self.flag_state {
+                Some(true) => "☑",
+                Some(false) => "☐",
+                None => "▣",
+            };
+            return container(
+                button(row![text(mark), text(content)].spacing(5))
+                    .on_press(Message::SetFlagForAll(flag))
+                    .style(Modern::system_button())
+                    .padding(0)
+            )
+            .center_y(24)
+            .into();
+        }
+
         container(text(content)).center_y(24).into()
     }
 
@@ -1011,6 +1439,39 @@ impl<'a> table::Column<'a, Message, Theme, Renderer> for Column {
             ColumnType::LanguageISOCode => &row.languageISOCode,
             ColumnType::ChoiceGroups => &row.choiceGroups,
             ColumnType::PrinterLogicals => &row.printerLogicals,
+            ColumnType::MatchedConditions => &row.matchedConditions,
+        };
+
+        // Editable flags are checkboxes, pending ones outlined in the diff color
+        if let Some(flag) = self.flag {
+            let item_id = row.item_id;
+            let pending = cell_value.change_type == CellChange::Modified;
+            let selected = row.selected;
+
+            return container(
+                checkbox("", cell_value.display() == "true")
+                    .on_toggle(move |value| Message::ToggleFlag(item_id, flag, value))
+                    .style(Modern::checkbox())
+            )
+            .width(Length::Fill)
+            .center_y(32)
+            .padding([2, 4])
+            .style(move |theme| container::Style {
+                background: selection_background(theme, selected),
+                border: iced::Border {
+                    color: Color::from_rgb(0.0, 0.7, 0.0),
+                    width: if pending { 1.0 } else { 0.0 },
+                    radius: 4.0.into(),
+                },
+                ..Default::default()
+            })
+            .into();
+        }
+
+        let font = if self.column_type.is_data() {
+            self.text_style.data_font()
+        } else {
+            iced::Font::DEFAULT
         };
 
         let content: Element<_> = match &cell_value.change_type {
@@ -1027,20 +1488,22 @@ impl<'a> table::Column<'a, Message, Theme, Renderer> for Column {
                 
                 if is_list_field {
                     // Use special list diff view
-                    create_list_diff_view(old_value, new_value)
+                    create_list_diff_view(old_value, new_value, self.text_style)
                 } else {
                     // For non-list fields, show inline diff
                     row![
                         text(old_value)
-                            .size(14)
+                            .size(self.text_style.body())
+                            .font(font)
                             .style(|_| text::Style {
                                 color: Some(Color::from_rgb(0.5, 0.5, 0.5)),
                             }),
-                        text(" → ").size(14).style(|_| text::Style {
+                        text(" → ").size(self.text_style.body()).style(|_| text::Style {
                             color: Some(Color::from_rgb(0.4, 0.4, 0.4)),
                         }),
                         text(new_value)
-                            .size(14)
+                            .size(self.text_style.body())
+                            .font(font)
                             .style(|_| text::Style {
                                 color: Some(Color::from_rgb(0.0, 0.7, 0.0)),
                             })
@@ -1053,12 +1516,12 @@ impl<'a> table::Column<'a, Message, Theme, Renderer> for Column {
             CellChange::Added => {
                 row![
                     text("+")
-                        .size(14)
+                        .size(self.text_style.body())
                         .style(|_| text::Style {
                             color: Some(Color::from_rgb(0.0, 0.7, 0.0)),
                         }),
                     text(cell_value.display())
-                        .size(14)
+                        .size(self.text_style.body())
                         .style(|_| text::Style {
                             color: Some(Color::from_rgb(0.0, 0.7, 0.0)),
                         })
@@ -1070,12 +1533,12 @@ impl<'a> table::Column<'a, Message, Theme, Renderer> for Column {
             CellChange::Removed => {
                 row![
                     text("-")
-                        .size(14)
+                        .size(self.text_style.body())
                         .style(|_| text::Style {
                             color: Some(Color::from_rgb(0.7, 0.0, 0.0)),
                         }),
                     text(cell_value.display())
-                        .size(14)
+                        .size(self.text_style.body())
                         .style(|_| text::Style {
                             color: Some(Color::from_rgb(0.7, 0.0, 0.0)),
                         })
@@ -1085,15 +1548,19 @@ impl<'a> table::Column<'a, Message, Theme, Renderer> for Column {
                 .into()
             },
             CellChange::None => {
-                text(cell_value.display()).size(14).into()
+                text(cell_value.display()).size(self.text_style.body()).font(font).into()
             }
         };
 
-        container(content)
-            .width(Length::Fill)
-            .center_y(32)
-            .padding([2, 4])
-            .into()
+        mouse_area(
+            container(content)
+                .width(Length::Fill)
+                .center_y(32)
+                .padding([2, 4])
+                .style(row_highlight(row.selected, row.focused))
+        )
+        .on_press(Message::PressRow(row.item_id))
+        .into()
     }
 
     fn footer(&'a self, _col_index: usize, rows: &'a [Row]) -> Option<Element<'a, Message>> {
@@ -0,0 +1,88 @@
+use serde::{Serialize, Deserialize};
+use rust_decimal::Decimal;
+use std::collections::BTreeMap;
+use crate::data_types::EntityId;
+use crate::price_levels::{PriceLevel, DEFAULT_DECIMALS};
+use super::Item;
+
+/// Which pricing mistakes the item view flags, set in Settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PriceSanityRules {
+    pub level_above_default: bool, // e.g. Happy Hour priced above the regular price
+    pub below_cost: bool,
+}
+
+impl Default for PriceSanityRules {
+    fn default() -> Self {
+        Self {
+            level_above_default: true,
+            below_cost: true,
+        }
+    }
+}
+
+/// One line of the price comparison on the item view
+#[derive(Debug, Clone)]
+pub struct PriceRow {
+    pub label: String,
+    pub price: Decimal,
+    pub decimals: u32,
+    pub delta: Option<Decimal>,
+    pub delta_percent: Option<Decimal>,
+    pub warnings: Vec<String>,
+}
+
+/// The default price followed by each price level the item has. Levels that
+/// no longer exist are shown by id so their price isn't hidden.
+pub fn price_rows(
+    item: &Item,
+    price_levels: &BTreeMap<EntityId, PriceLevel>,
+    rules: PriceSanityRules,
+) -> Vec<PriceRow> {
+    let default = item.default_price;
+    let mut rows = Vec::new();
+
+    let below_cost = |price: Decimal| -> Option<String> {
+        let cost = item.cost_amount.filter(|_| rules.below_cost)?;
+        (price < cost).then(|| format!("Below the cost of {}", crate::data_types::format_money(cost, DEFAULT_DECIMALS as u32)))
+    };
+
+    if let Some(price) = default {
+        rows.push(PriceRow {
+            label: "Default".to_string(),
+            price,
+            decimals: DEFAULT_DECIMALS as u32,
+            delta: None,
+            delta_percent: None,
+            warnings: below_cost(price).into_iter().collect(),
+        });
+    }
+
+    for item_price in item.item_prices.iter().flatten() {
+        let level = price_levels.get(&item_price.price_level_id);
+        let price = item_price.price;
+
+        let delta = default.map(|default| price - default);
+        let delta_percent = default
+            .filter(|default| !default.is_zero())
+            .and_then(|default| delta?.checked_mul(Decimal::ONE_HUNDRED)?.checked_div(default))
+            .map(|percent| percent.round_dp(1));
+
+        let mut warnings = Vec::new();
+        if rules.level_above_default && delta.is_some_and(|delta| delta > Decimal::ZERO) {
+            warnings.push("Above the default price".to_string());
+        }
+        warnings.extend(below_cost(price));
+
+        rows.push(PriceRow {
+            label: level.map_or_else(|| format!("Level {}", item_price.price_level_id), |level| level.name.clone()),
+            price,
+            decimals: level.map_or(DEFAULT_DECIMALS, |level| level.decimals) as u32,
+            delta,
+            delta_percent,
+            warnings,
+        });
+    }
+
+    rows
+}
@@ -0,0 +1,163 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use iced::Element;
+use iced::widget::{button, checkbox, column, container, pick_list, row, text, text_input};
+use iced_modern_theme::Modern;
+use rust_decimal::Decimal;
+use crate::data_types::EntityId;
+use crate::price_levels::PriceLevel;
+use crate::focus_style;
+use crate::text_style::TextStyle;
+use super::Item;
+use super::copy_assignments::current_prices;
+use super::edit::Message;
+
+/// Whether the amount replaces each price or is added to it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FillMode {
+    #[default]
+    Set,
+    Adjust,
+}
+
+impl FillMode {
+    pub const ALL: &'static [Self] = &[
+        Self::Set,
+        Self::Adjust,
+    ];
+}
+
+impl fmt::Display for FillMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Set => write!(f, "Set to"),
+            Self::Adjust => write!(f, "Add"),
+        }
+    }
+}
+
+/// One price the fill would change, `level` is None for the base price
+#[derive(Debug, Clone, PartialEq)]
+pub struct PriceChange {
+    pub level: Option<EntityId>,
+    pub old: Decimal,
+    pub new: Decimal,
+}
+
+/// "Apply to all price levels" in the item editor. Only the draft changes, nothing is kept until Save.
+#[derive(Debug, Clone, Default)]
+pub struct PriceFill {
+    pub amount: String,
+    pub mode: FillMode,
+    pub include_default: bool,
+    pub confirming: bool, // Existing prices would be overwritten, waiting on Overwrite or Cancel
+}
+
+impl PriceFill {
+    /// What each price would become. `edited_prices` are the price inputs, they
+    /// take precedence over what's stored like they do on Save.
+    pub fn changes(
+        &self,
+        item: &Item,
+        edited_prices: &Option<Vec<(EntityId, String)>>,
+    ) -> Result<Vec<PriceChange>, String> {
+        let amount = self.amount.trim().parse::<Decimal>()
+            .map_err(|_| "Invalid amount for all price levels".to_string())?;
+
+        let mut current: Vec<(Option<EntityId>, Decimal)> = match &item.item_prices {
+            Some(_) => current_prices(item, edited_prices)
+                .into_iter()
+                .map(|price| (Some(price.price_level_id), price.price))
+                .collect(),
+            // Older items only list their levels, those start from zero
+            None => item.price_levels.iter().flatten().map(|id| (Some(*id), Decimal::ZERO)).collect(),
+        };
+        if self.include_default {
+            current.insert(0, (None, item.default_price.unwrap_or(Decimal::ZERO)));
+        }
+
+        current
+            .into_iter()
+            .map(|(level, old)| {
+                let new = match self.mode {
+                    FillMode::Set => amount,
+                    FillMode::Adjust => old + amount,
+                };
+                if new < Decimal::ZERO {
+                    return Err("Prices can't go below zero".to_string());
+                }
+                Ok(PriceChange { level, old, new })
+            })
+            .filter(|change| !matches!(change, Ok(change) if change.old == change.new))
+            .collect()
+    }
+
+    /// Whether applying replaces prices that were already set, those need confirming first
+    pub fn overwrites(changes: &[PriceChange]) -> bool {
+        changes.iter().any(|change| change.old != Decimal::ZERO)
+    }
+
+    pub fn view<'a>(
+        &'a self,
+        item: &Item,
+        edited_prices: &Option<Vec<(EntityId, String)>>,
+        price_levels: &'a BTreeMap<EntityId, PriceLevel>,
+        text_style: TextStyle,
+    ) -> Element<'a, Message> {
+        let controls = row![
+            text("All price levels").style(Modern::primary_text()),
+            pick_list(FillMode::ALL, Some(self.mode), Message::SetFillMode)
+                .style(Modern::pick_list()),
+            text_input("Amount", &self.amount)
+                .on_input(Message::UpdateFillAmount)
+                .on_submit(Message::ApplyFill)
+                .style(focus_style::text_input(Modern::inline_text_input()))
+                .width(100)
+                .padding(5),
+            checkbox("Include base price", self.include_default)
+                .on_toggle(Message::ToggleFillDefault)
+                .style(Modern::checkbox()),
+            button(text("Apply").size(text_style.caption()))
+                .on_press_maybe((!self.amount.trim().is_empty() && !self.confirming).then_some(Message::ApplyFill))
+                .style(Modern::gray_button()),
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center);
+
+        if !self.confirming {
+            return controls.into();
+        }
+
+        let lines = self.changes(item, edited_prices).unwrap_or_default()
+            .into_iter()
+            .filter(|change| change.old != Decimal::ZERO)
+            .map(|change| {
+                let name = match change.level {
+                    Some(id) => price_levels.get(&id).map_or_else(|| format!("#{}", id), |level| level.name.clone()),
+                    None => "Base Price".to_string(),
+                };
+                text(format!("{}: {} → {}", name, change.old, change.new)).size(text_style.caption()).into()
+            })
+            .collect::<Vec<_>>();
+
+        column![
+            controls,
+            container(
+                column![
+                    text("These prices are already set and will be replaced").style(Modern::primary_text()),
+                    column(lines).spacing(2),
+                    row![
+                        button("Overwrite").on_press(Message::ConfirmFill).style(Modern::danger_button()),
+                        button("Cancel").on_press(Message::CancelFill).style(Modern::system_button()),
+                    ]
+                    .spacing(10),
+                ]
+                .spacing(10)
+            )
+            .padding(10)
+            .style(Modern::card_container()),
+        ]
+        .spacing(10)
+        .into()
+    }
+}
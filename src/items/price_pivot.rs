@@ -0,0 +1,47 @@
+use std::collections::BTreeMap;
+use crate::data_types::EntityId;
+use crate::price_levels::PriceLevel;
+use super::Item;
+
+/// One row per item with the default price and a column for each included price
+/// level. Levels in `excluded` are left out, a blank cell means no override.
+pub fn price_pivot_csv(
+    items: &BTreeMap<EntityId, Item>,
+    price_levels: &BTreeMap<EntityId, PriceLevel>,
+    excluded: &[EntityId],
+) -> String {
+    let levels: Vec<&PriceLevel> = price_levels
+        .values()
+        .filter(|level| !excluded.contains(&level.id))
+        .collect();
+
+    let mut header = vec!["Item ID".to_string(), "Item".to_string(), "Default Price".to_string()];
+    header.extend(levels.iter().map(|level| quoted(&level.name)));
+
+    let mut lines = vec![header.join(",")];
+    for item in items.values() {
+        let mut cells = vec![
+            item.id.to_string(),
+            quoted(&item.name),
+            item.default_price.map(|price| price.to_string()).unwrap_or_default(),
+        ];
+        cells.extend(levels.iter().map(|level| {
+            item.item_prices
+                .iter()
+                .flatten()
+                .find(|price| price.price_level_id == level.id)
+                .map(|price| price.price.to_string())
+                .unwrap_or_default()
+        }));
+        lines.push(cells.join(","));
+    }
+
+    println!("Price pivot: {} items, {} price levels", items.len(), levels.len());
+
+    lines.join("\n")
+}
+
+// Names can have commas and quotes in them
+fn quoted(text: &str) -> String {
+    format!("\"{}\"", text.replace('"', "\"\""))
+}
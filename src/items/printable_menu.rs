@@ -0,0 +1,123 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use serde::{Serialize, Deserialize};
+use crate::{
+    data_types::EntityId,
+    items::Item,
+    item_groups::ItemGroup,
+    report_categories::ReportCategory,
+};
+
+/// Which entity the printable menu sections are built from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum MenuGrouping {
+    #[default]
+    ItemGroup,
+    ReportCategory,
+}
+
+impl MenuGrouping {
+    pub const ALL: &'static [Self] = &[
+        Self::ItemGroup,
+        Self::ReportCategory,
+    ];
+}
+
+impl fmt::Display for MenuGrouping {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ItemGroup => write!(f, "Item Group"),
+            Self::ReportCategory => write!(f, "Report Category"),
+        }
+    }
+}
+
+const STYLE: &str = "
+body { font-family: Georgia, 'Times New Roman', serif; max-width: 800px; margin: 40px auto; color: #222; }
+h1 { text-align: center; letter-spacing: 2px; }
+h2 { border-bottom: 1px solid #999; padding-bottom: 4px; margin-top: 32px; }
+.item { display: flex; justify-content: space-between; margin: 8px 0; break-inside: avoid; }
+.name { font-weight: bold; }
+.description { font-size: 0.9em; color: #555; }
+.price { white-space: nowrap; padding-left: 16px; }
+@media print { body { margin: 0 auto; } h2 { break-after: avoid; } }
+";
+
+/// Builds a self-contained HTML menu with one section per group and prices on the right
+pub fn menu_html(
+    items: &BTreeMap<EntityId, Item>,
+    item_groups: &BTreeMap<EntityId, ItemGroup>,
+    report_categories: &BTreeMap<EntityId, ReportCategory>,
+    grouping: MenuGrouping,
+    include_inactive: bool,
+) -> String {
+    let mut sections: BTreeMap<(u8, String), Vec<&Item>> = BTreeMap::new();
+
+    for item in items.values().filter(|item| include_inactive || !item.not_active) {
+        let section = match grouping {
+            MenuGrouping::ItemGroup => item.item_group
+                .and_then(|id| item_groups.get(&id))
+                .map(|group| group.name.clone()),
+            MenuGrouping::ReportCategory => item.report_category
+                .and_then(|id| report_categories.get(&id))
+                .map(|category| category.name.clone()),
+        };
+
+        // Ungrouped items go last
+        let key = match section {
+            Some(name) => (0, name),
+            None => (1, "Other".to_string()),
+        };
+        sections.entry(key).or_default().push(item);
+    }
+
+    let mut html = String::new();
+    html.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>Menu</title>\n");
+    html.push_str(&format!("<style>{}</style>\n", STYLE));
+    html.push_str("</head>\n<body>\n<h1>Menu</h1>\n");
+
+    for ((_, section), mut section_items) in sections {
+        section_items.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+
+        html.push_str(&format!("<h2>{}</h2>\n", escape(&section)));
+
+        for item in section_items {
+            let price = item.default_price
+                .map(|price| format!("${:.2}", price))
+                .unwrap_or_default();
+
+            html.push_str("<div class=\"item\">\n<div>\n");
+            html.push_str(&format!("<div class=\"name\">{}</div>\n", escape(&item.name)));
+            if !item.customer_receipt.trim().is_empty() {
+                html.push_str(&format!("<div class=\"description\">{}</div>\n", escape(&item.customer_receipt)));
+            }
+            html.push_str("</div>\n");
+            html.push_str(&format!("<div class=\"price\">{}</div>\n</div>\n", price));
+        }
+    }
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+fn escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Opens the exported file in the system's default browser
+pub fn open_in_browser(path: &std::path::Path) {
+    #[cfg(target_os = "windows")]
+    let result = std::process::Command::new("cmd").arg("/C").arg("start").arg("").arg(path).spawn();
+    #[cfg(target_os = "macos")]
+    let result = std::process::Command::new("open").arg(path).spawn();
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let result = std::process::Command::new("xdg-open").arg(path).spawn();
+
+    if let Err(e) = result {
+        println!("Could not open {:?} in a browser: {}", path, e);
+    }
+}
@@ -0,0 +1,162 @@
+use std::fmt;
+use super::Item;
+
+pub const MAX_COVERS: i32 = 999; // Seats a combo item accounts for, banquet packages run into the hundreds
+pub const DEFAULT_MAX_STORE_ID: i32 = 99;
+
+// Shown first in the language picker
+const COMMON_LANGUAGES: &[(&str, &str)] = &[
+    ("en", "English"),
+    ("es", "Spanish"),
+    ("fr", "French"),
+    ("de", "German"),
+    ("it", "Italian"),
+    ("pt", "Portuguese"),
+    ("zh", "Chinese"),
+    ("ja", "Japanese"),
+    ("ko", "Korean"),
+];
+
+// The rest of ISO-639-1, alphabetical by code
+const OTHER_LANGUAGES: &[(&str, &str)] = &[
+    ("aa", "Afar"), ("ab", "Abkhazian"), ("ae", "Avestan"), ("af", "Afrikaans"),
+    ("ak", "Akan"), ("am", "Amharic"), ("an", "Aragonese"), ("ar", "Arabic"),
+    ("as", "Assamese"), ("av", "Avaric"), ("ay", "Aymara"), ("az", "Azerbaijani"),
+    ("ba", "Bashkir"), ("be", "Belarusian"), ("bg", "Bulgarian"), ("bi", "Bislama"),
+    ("bm", "Bambara"), ("bn", "Bengali"), ("bo", "Tibetan"), ("br", "Breton"),
+    ("bs", "Bosnian"), ("ca", "Catalan"), ("ce", "Chechen"), ("ch", "Chamorro"),
+    ("co", "Corsican"), ("cr", "Cree"), ("cs", "Czech"), ("cu", "Church Slavic"),
+    ("cv", "Chuvash"), ("cy", "Welsh"), ("da", "Danish"), ("dv", "Divehi"),
+    ("dz", "Dzongkha"), ("ee", "Ewe"), ("el", "Greek"), ("eo", "Esperanto"),
+    ("et", "Estonian"), ("eu", "Basque"), ("fa", "Persian"), ("ff", "Fulah"),
+    ("fi", "Finnish"), ("fj", "Fijian"), ("fo", "Faroese"), ("fy", "Western Frisian"),
+    ("ga", "Irish"), ("gd", "Scottish Gaelic"), ("gl", "Galician"), ("gn", "Guarani"),
+    ("gu", "Gujarati"), ("gv", "Manx"), ("ha", "Hausa"), ("he", "Hebrew"),
+    ("hi", "Hindi"), ("ho", "Hiri Motu"), ("hr", "Croatian"), ("ht", "Haitian"),
+    ("hu", "Hungarian"), ("hy", "Armenian"), ("hz", "Herero"), ("ia", "Interlingua"),
+    ("id", "Indonesian"), ("ie", "Interlingue"), ("ig", "Igbo"), ("ii", "Sichuan Yi"),
+    ("ik", "Inupiaq"), ("io", "Ido"), ("is", "Icelandic"), ("iu", "Inuktitut"),
+    ("jv", "Javanese"), ("ka", "Georgian"), ("kg", "Kongo"), ("ki", "Kikuyu"),
+    ("kj", "Kuanyama"), ("kk", "Kazakh"), ("kl", "Kalaallisut"), ("km", "Khmer"),
+    ("kn", "Kannada"), ("kr", "Kanuri"), ("ks", "Kashmiri"), ("ku", "Kurdish"),
+    ("kv", "Komi"), ("kw", "Cornish"), ("ky", "Kyrgyz"), ("la", "Latin"),
+    ("lb", "Luxembourgish"), ("lg", "Ganda"), ("li", "Limburgish"), ("ln", "Lingala"),
+    ("lo", "Lao"), ("lt", "Lithuanian"), ("lu", "Luba-Katanga"), ("lv", "Latvian"),
+    ("mg", "Malagasy"), ("mh", "Marshallese"), ("mi", "Maori"), ("mk", "Macedonian"),
+    ("ml", "Malayalam"), ("mn", "Mongolian"), ("mr", "Marathi"), ("ms", "Malay"),
+    ("mt", "Maltese"), ("my", "Burmese"), ("na", "Nauru"), ("nb", "Norwegian Bokmal"),
+    ("nd", "North Ndebele"), ("ne", "Nepali"), ("ng", "Ndonga"), ("nl", "Dutch"),
+    ("nn", "Norwegian Nynorsk"), ("no", "Norwegian"), ("nr", "South Ndebele"), ("nv", "Navajo"),
+    ("ny", "Chichewa"), ("oc", "Occitan"), ("oj", "Ojibwa"), ("om", "Oromo"),
+    ("or", "Oriya"), ("os", "Ossetian"), ("pa", "Punjabi"), ("pi", "Pali"),
+    ("pl", "Polish"), ("ps", "Pashto"), ("qu", "Quechua"), ("rm", "Romansh"),
+    ("rn", "Rundi"), ("ro", "Romanian"), ("ru", "Russian"), ("rw", "Kinyarwanda"),
+    ("sa", "Sanskrit"), ("sc", "Sardinian"), ("sd", "Sindhi"), ("se", "Northern Sami"),
+    ("sg", "Sango"), ("si", "Sinhala"), ("sk", "Slovak"), ("sl", "Slovenian"),
+    ("sm", "Samoan"), ("sn", "Shona"), ("so", "Somali"), ("sq", "Albanian"),
+    ("sr", "Serbian"), ("ss", "Swati"), ("st", "Southern Sotho"), ("su", "Sundanese"),
+    ("sv", "Swedish"), ("sw", "Swahili"), ("ta", "Tamil"), ("te", "Telugu"),
+    ("tg", "Tajik"), ("th", "Thai"), ("ti", "Tigrinya"), ("tk", "Turkmen"),
+    ("tl", "Tagalog"), ("tn", "Tswana"), ("to", "Tonga"), ("tr", "Turkish"),
+    ("ts", "Tsonga"), ("tt", "Tatar"), ("tw", "Twi"), ("ty", "Tahitian"),
+    ("ug", "Uyghur"), ("uk", "Ukrainian"), ("ur", "Urdu"), ("uz", "Uzbek"),
+    ("ve", "Venda"), ("vi", "Vietnamese"), ("vo", "Volapuk"), ("wa", "Walloon"),
+    ("wo", "Wolof"), ("xh", "Xhosa"), ("yi", "Yiddish"), ("yo", "Yoruba"),
+    ("za", "Zhuang"), ("zu", "Zulu"),
+];
+
+/// An entry in the language picker
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LanguageChoice {
+    Iso(&'static str, &'static str),
+    Other,
+}
+
+impl fmt::Display for LanguageChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Iso(code, name) => write!(f, "{} - {}", code, name),
+            Self::Other => write!(f, "Other..."),
+        }
+    }
+}
+
+fn all_languages() -> impl Iterator<Item = &'static (&'static str, &'static str)> {
+    COMMON_LANGUAGES.iter().chain(OTHER_LANGUAGES.iter())
+}
+
+/// Common languages first, then the rest of ISO-639-1, then the "other" escape
+pub fn language_choices() -> Vec<LanguageChoice> {
+    all_languages()
+        .map(|(code, name)| LanguageChoice::Iso(code, name))
+        .chain(std::iter::once(LanguageChoice::Other))
+        .collect()
+}
+
+/// The picker entry for a stored code, if it is a known ISO-639-1 code
+pub fn language_choice(code: &str) -> Option<LanguageChoice> {
+    all_languages()
+        .find(|(iso, _)| *iso == code)
+        .map(|(iso, name)| LanguageChoice::Iso(iso, name))
+}
+
+/// Empty is allowed, the export leaves the field blank
+pub fn is_valid_language_code(code: &str) -> bool {
+    code.is_empty() || language_choice(code).is_some()
+}
+
+/// Best guess at the code that was meant, e.g. "EN-us" -> "en", "engl" -> "en"
+pub fn suggest_language_code(code: &str) -> Option<&'static str> {
+    let lowered = code.trim().to_lowercase();
+    let primary = lowered
+        .split(|c: char| c == '-' || c == '_')
+        .next()
+        .unwrap_or_default();
+
+    if let Some((iso, _)) = all_languages().find(|(iso, _)| *iso == primary) {
+        return Some(iso);
+    }
+
+    // Names and three letter codes, like "engl" or "spa"
+    if primary.len() >= 3 {
+        return all_languages()
+            .find(|(_, name)| name.to_lowercase().starts_with(primary))
+            .map(|(iso, _)| *iso);
+    }
+
+    None
+}
+
+/// Store field values the POS will reject, each with a suggested fix
+pub fn store_field_problems(item: &Item, max_store_id: i32) -> Vec<String> {
+    let mut problems = Vec::new();
+
+    if !is_valid_language_code(&item.language_iso_code) {
+        problems.push(match suggest_language_code(&item.language_iso_code) {
+            Some(suggestion) => format!(
+                "language code \"{}\" is not ISO-639-1, did you mean \"{}\"?",
+                item.language_iso_code, suggestion
+            ),
+            None => format!(
+                "language code \"{}\" is not ISO-639-1, pick one from the language list",
+                item.language_iso_code
+            ),
+        });
+    }
+
+    if item.store_id < 0 || item.store_id > max_store_id {
+        problems.push(format!(
+            "store ID {} is outside 0-{}, check for a typo or raise the highest store ID in settings",
+            item.store_id, max_store_id
+        ));
+    }
+
+    if item.covers < 0 || item.covers > MAX_COVERS {
+        problems.push(format!(
+            "covers {} is outside 0-{}, did you mean {}?",
+            item.covers, MAX_COVERS, item.covers.clamp(0, MAX_COVERS)
+        ));
+    }
+
+    problems
+}
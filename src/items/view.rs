@@ -1,13 +1,14 @@
 use iced::widget::{
     button, checkbox, column, container, row, text, scrollable,
-    horizontal_space, text_input
+    horizontal_space, text_input, tooltip
 };
 use iced::{Element, Length};
 use iced_modern_theme::Modern;
 use std::collections::BTreeMap;
 //use crate::HotKey;
 use crate::{
-    items::{Item, EntityId},
+    items::{Item, EntityId, QuickEdit, QuickEditField},
+    items::price_compare::{self, PriceSanityRules},
     item_groups::ItemGroup,
     price_levels::PriceLevel,
     product_classes::ProductClass,
@@ -18,6 +19,7 @@ use crate::{
     choice_groups::ChoiceGroup,
     printer_logicals::PrinterLogical,
     icon,
+    text_style::TextStyle,
     data_types,
 };
 
@@ -27,10 +29,32 @@ pub enum Message {
     Edit,
     Back,
     ExportToCsv,
+    StartQuickEdit(QuickEditField),
+    QuickEditInput(String),
+    CommitQuickEdit,
+    CancelQuickEdit,
+    UndoQuickEdit,
+    TogglePin,
+    FixPrimaryPrinter,
+}
+
+// Combo items that seat guests say so up top, so the covers count isn't buried in the Store section
+fn covers_badge<'a>(item: &Item, text_style: TextStyle) -> Element<'a, Message> {
+    if item.covers > 0 {
+        container(text(format!("{} covers", item.covers)).size(text_style.caption()))
+            .padding([2, 6])
+            .style(Modern::accent_container())
+            .into()
+    } else {
+        row![].into()
+    }
 }
 
 pub fn view<'a>(
     item: &'a Item,
+    pinned: bool,
+    quick_edit: Option<&'a QuickEdit>,
+    can_undo_quick_edit: bool,
     item_groups: &'a BTreeMap<EntityId, ItemGroup>,
     tax_groups: &'a BTreeMap<EntityId, TaxGroup>,
     security_levels: &'a BTreeMap<EntityId, SecurityLevel>,
@@ -40,21 +64,38 @@ pub fn view<'a>(
     choice_groups: &'a BTreeMap<EntityId, ChoiceGroup>,
     printer_logicals: &'a BTreeMap<EntityId, PrinterLogical>,
     price_levels: &'a BTreeMap<EntityId, PriceLevel>,
+    custom_flag_labels: &'a [String; 2],
+    price_rules: PriceSanityRules,
+    text_style: TextStyle,
 ) -> Element<'a, Message> {
     let header = row![
         button(icon::edit().size(14))
             .on_press(Message::Edit)
             .style(Modern::primary_button()),
+        button(text(if pinned { "Unpin" } else { "Pin" }).size(text_style.caption()))
+            .on_press(Message::TogglePin)
+            .style(Modern::system_button()),
+        button(text("Undo Quick Edit").size(text_style.caption()))
+            .on_press_maybe(can_undo_quick_edit.then_some(Message::UndoQuickEdit))
+            .style(Modern::system_button()),
         horizontal_space().width(4),
+        covers_badge(item, text_style),
     ]
-    .spacing(10);
+    .spacing(10)
+    .align_y(iced::Alignment::Center);
 
     let basic_info = container(
         column![
                 row![
-                    long_info_column(
-                        "Item Name".to_string(),
-                        item.name.clone()),
+                    match quick_edit.filter(|edit| edit.field == QuickEditField::Name) {
+                        Some(edit) => quick_edit_input("Item Name", edit, text_style),
+                        None => row![
+                            long_info_column(
+                                "Item Name".to_string(),
+                                item.name.clone()),
+                            quick_edit_button(QuickEditField::Name),
+                        ].align_y(iced::Alignment::End).into(),
+                    },
                     info_column(
                         "Base Price".to_string(),
                         //item.default_price.map_or("Not Set".to_string(), |c| format!("${:.2}", c))),
@@ -164,6 +205,20 @@ pub fn view<'a>(
     .style(Modern::sheet_container())
     .padding(10);
 
+    // What the till shows when it asks for the price
+    let ask_price: Element<'a, Message> = if item.ask_price {
+        let prompt = match &item.ask_price_prompt {
+            Some(prompt) => format!("Till prompt: \"{}\"", prompt),
+            None => "No till prompt set".to_string(),
+        };
+        let suggested = item.ask_price_suggested
+            .map(|price| format!(", suggests ${}", price))
+            .unwrap_or_default();
+        text(prompt + &suggested).size(text_style.caption()).style(Modern::secondary_text()).into()
+    } else {
+        row![].into()
+    };
+
     let pricing = container(
         column![
             text("Price Levels").style(Modern::primary_text()),
@@ -174,12 +229,21 @@ pub fn view<'a>(
                         prices.iter()
                             .filter_map(|item_price| {
                                 price_levels.get(&item_price.price_level_id).map(|price_level|{
+                                    let field = QuickEditField::Price(item_price.price_level_id);
+
+                                    if let Some(edit) = quick_edit.filter(|edit| edit.field == field) {
+                                        return quick_edit_input(&price_level.name, edit, text_style);
+                                    }
+
                                     let label2 = row![
                                         text(price_level.name.clone() + ": ").style(Modern::primary_text()),
-                                        text("$".to_string() + item_price.price.to_string().as_str()),
+                                        text("$".to_string() + price_level.format_price(item_price.price).as_str()),
                                         ];
 
-                                    button(label2).style(Modern::gray_button()).into()
+                                    row![
+                                        button(label2).style(Modern::gray_button()),
+                                        quick_edit_button(field),
+                                    ].spacing(2).into()
                                 })
                             }).collect::<Vec<_>>()
                     ).spacing(10).wrap()
@@ -187,12 +251,68 @@ pub fn view<'a>(
                     row![button(text("No Price Level Assigned")).style(Modern::gray_button())].wrap()
                 }
             ],
+            ask_price,
         ]
     )
     .style(Modern::sheet_container())
     .width(Length::Fill)
     .padding(10);
 
+    // Only worth comparing when there's more than one price
+    let price_rows = price_compare::price_rows(item, price_levels, price_rules);
+    let price_comparison: Element<'a, Message> = if price_rows.len() > 1 {
+        let signed = |value: String, positive: bool| if positive { format!("+{}", value) } else { value };
+
+        container(
+            column![
+                text("Price Comparison").style(Modern::primary_text()),
+                row![
+                    text("Level").size(text_style.caption()).width(Length::FillPortion(2)),
+                    text("Price").size(text_style.caption()).width(Length::FillPortion(1)),
+                    text("vs Default").size(text_style.caption()).width(Length::FillPortion(1)),
+                    text("%").size(text_style.caption()).width(Length::FillPortion(1)),
+                    horizontal_space().width(20),
+                ].spacing(10),
+                column(
+                    price_rows.into_iter().map(|price_row| {
+                        let delta = price_row.delta.map_or(String::new(), |delta| {
+                            signed(data_types::format_money(delta, price_row.decimals), delta > rust_decimal::Decimal::ZERO)
+                        });
+                        let percent = price_row.delta_percent.map_or(String::new(), |percent| {
+                            signed(format!("{}%", percent), percent > rust_decimal::Decimal::ZERO)
+                        });
+
+                        let warning: Element<'a, Message> = if price_row.warnings.is_empty() {
+                            horizontal_space().width(20).into()
+                        } else {
+                            tooltip(
+                                text("⚠").style(Modern::red_text()).width(20),
+                                container(text(price_row.warnings.join("\n"))).padding(10).style(Modern::danger_tooltip_container()),
+                                tooltip::Position::Left,
+                            ).into()
+                        };
+
+                        row![
+                            text(price_row.label).width(Length::FillPortion(2)),
+                            text(data_types::format_money(price_row.price, price_row.decimals)).width(Length::FillPortion(1)),
+                            text(delta).style(Modern::secondary_text()).width(Length::FillPortion(1)),
+                            text(percent).style(Modern::secondary_text()).width(Length::FillPortion(1)),
+                            warning,
+                        ]
+                        .spacing(10)
+                        .into()
+                    })
+                ).spacing(4),
+            ].spacing(5)
+        )
+        .style(Modern::sheet_container())
+        .width(Length::Fill)
+        .padding(10)
+        .into()
+    } else {
+        column![].into()
+    };
+
     let flags = container(
         column![
             column![
@@ -239,6 +359,18 @@ pub fn view<'a>(
                     ).spacing(10).width(200).style(Modern::checkbox()),
                 ].wrap()
             ],
+            column![
+                row![
+                    checkbox(
+                        custom_flag_labels[0].as_str(), 
+                        item.reserved1
+                    ).spacing(10).width(200).style(Modern::checkbox()),
+                    checkbox(
+                        custom_flag_labels[1].as_str(), 
+                        item.reserved2
+                    ).spacing(10).width(200).style(Modern::checkbox()),
+                ].wrap()
+            ],
         ],
     )
     .style(Modern::sheet_container())
@@ -263,13 +395,27 @@ pub fn view<'a>(
             if let Some(ref printers) = item.printer_logicals {
                 row(
                     printers.iter()
-                        .filter_map(|id| printer_logicals.get(&id.0))
-                        .map(|printer| button( &*printer.name).style(Modern::gray_button()).into())
+                        .filter_map(|(id, is_primary)| printer_logicals.get(id).map(|printer| (printer, *is_primary)))
+                        .map(|(printer, is_primary)| {
+                            let label = if is_primary { format!("{} (primary)", printer.name) } else { printer.name.clone() };
+                            button(text(label)).style(Modern::gray_button()).into()
+                        })
                         .collect::<Vec<_>>()
                 ).spacing(10).wrap()
             } else {
                 row![button(text("No Printer Logicals Assigned")).style(Modern::gray_button())].wrap()
-            }
+            },
+            match item.primary_printer_problem(printer_logicals) {
+                Some(problem) => row![
+                    text(problem).size(text_style.caption()).style(Modern::red_text()),
+                    button(text("Make First Printer Primary").size(text_style.caption()))
+                        .on_press(Message::FixPrimaryPrinter)
+                        .style(Modern::warning_button()),
+                ]
+                .spacing(10)
+                .align_y(iced::Alignment::Center),
+                None => row![],
+            },
         ]
     )
     .style(Modern::sheet_container())
@@ -278,13 +424,13 @@ pub fn view<'a>(
 
 /*     let store_info = container(
         column![
-            text("Store Information").size(16).style(iced::widget::text::primary),
+            text("Store Information").size(text_style.subheading()).style(iced::widget::text::primary),
             info_row("Store ID:".to_string(), item.store_id.to_string()),
             info_row("Covers:".to_string(), item.covers.to_string()),
             info_row("Language:".to_string(), item.language_iso_code.clone()),
             if let Some(ref levels) = item.store_price_level {
                 column![
-                    text("Store Price Levels:").size(14),
+                    text("Store Price Levels:").size(text_style.body()),
                     column(
                         levels.iter()
                             .filter_map(|id| price_levels.get(id))
@@ -324,11 +470,25 @@ pub fn view<'a>(
     .width(Length::Fill)
     .padding(10);
 
+    let summary = summary_card(
+        item,
+        item_groups,
+        tax_groups,
+        security_levels,
+        revenue_categories,
+        report_categories,
+        product_classes,
+        choice_groups,
+        printer_logicals,
+        price_levels,
+    );
+
     container(
         column![
             header,
             scrollable(
                 column![
+                    summary,
                     basic_info,
                     classifications,
                     //weight_info,
@@ -338,6 +498,7 @@ pub fn view<'a>(
                     choice_groups,
                     printer_info,
                     pricing,
+                    price_comparison,
                 ]
                 .spacing(20)
             )
@@ -351,6 +512,132 @@ pub fn view<'a>(
     .into()
 }
 
+/// Everything about an item on one card, with every reference resolved to its name
+fn summary_card<'a>(
+    item: &'a Item,
+    item_groups: &'a BTreeMap<EntityId, ItemGroup>,
+    tax_groups: &'a BTreeMap<EntityId, TaxGroup>,
+    security_levels: &'a BTreeMap<EntityId, SecurityLevel>,
+    revenue_categories: &'a BTreeMap<EntityId, RevenueCategory>,
+    report_categories: &'a BTreeMap<EntityId, ReportCategory>,
+    product_classes: &'a BTreeMap<EntityId, ProductClass>,
+    choice_groups: &'a BTreeMap<EntityId, ChoiceGroup>,
+    printer_logicals: &'a BTreeMap<EntityId, PrinterLogical>,
+    price_levels: &'a BTreeMap<EntityId, PriceLevel>,
+) -> Element<'a, Message> {
+    fn resolve<T>(id: Option<EntityId>, map: &BTreeMap<EntityId, T>, name: impl Fn(&T) -> String) -> String {
+        match id {
+            None => "None".to_string(),
+            Some(id) => map.get(&id).map_or(format!("{} (missing)", id), name),
+        }
+    }
+
+    let tax_group = resolve(item.tax_group, tax_groups, |g| {
+        format!("{} ({}%)", g.name, (g.rate * rust_decimal::Decimal::ONE_HUNDRED).normalize())
+    });
+
+    let choice_group_names = match &item.choice_groups {
+        Some(groups) if !groups.is_empty() => groups.iter()
+            .map(|(id, _)| resolve(Some(*id), choice_groups, |g| g.name.clone()))
+            .collect::<Vec<_>>()
+            .join(", "),
+        _ => "None".to_string(),
+    };
+
+    let printer_names = match &item.printer_logicals {
+        Some(printers) if !printers.is_empty() => printers.iter()
+            .map(|(id, primary)| {
+                let name = resolve(Some(*id), printer_logicals, |p| p.name.clone());
+                if *primary { format!("{} (primary)", name) } else { name }
+            })
+            .collect::<Vec<_>>()
+            .join(", "),
+        _ => "None".to_string(),
+    };
+
+    let prices = match &item.item_prices {
+        Some(prices) if !prices.is_empty() => prices.iter()
+            .map(|p| {
+                let price = price_levels.get(&p.price_level_id)
+                    .map_or(p.price.to_string(), |level| level.format_price(p.price));
+                format!("{}: ${}", resolve(Some(p.price_level_id), price_levels, |l| l.name.clone()), price)
+            })
+            .collect::<Vec<_>>()
+            .join(", "),
+        _ => "None".to_string(),
+    };
+
+    let lines = vec![
+        ("ID", item.id.to_string()),
+        ("Name", item.name.clone()),
+        ("Item Group", resolve(item.item_group, item_groups, |g| g.name.clone())),
+        ("Product Class", resolve(item.product_class, product_classes, |c| c.name.clone())),
+        ("Revenue Category", resolve(item.revenue_category, revenue_categories, |c| c.name.clone())),
+        ("Report Category", resolve(item.report_category, report_categories, |c| c.name.clone())),
+        ("Tax Group", tax_group),
+        ("Security Level", resolve(item.security_level, security_levels, |l| l.name.clone())),
+        ("Choice Groups", choice_group_names),
+        ("Printer Logicals", printer_names),
+        ("Prices", prices),
+        ("Created", item.created_at.as_deref().map_or_else(|| "Unknown".to_string(), data_types::display_timestamp)),
+        ("Modified", item.updated_at.as_deref().map_or_else(|| "Unknown".to_string(), data_types::display_timestamp)),
+    ];
+
+    container(
+        column![
+            text("Summary").style(Modern::primary_text()),
+            column(
+                lines.into_iter()
+                    .map(|(label, value)| {
+                        row![
+                            text(label).width(Length::Fixed(150.0)).style(Modern::secondary_text()),
+                            text(value),
+                        ].into()
+                    })
+                    .collect::<Vec<_>>()
+            ).spacing(4),
+        ]
+        .spacing(10)
+    )
+    .style(Modern::sheet_container())
+    .width(Length::Fill)
+    .padding(10)
+    .into()
+}
+
+fn quick_edit_button<'a>(field: QuickEditField) -> Element<'a, Message> {
+    button(icon::edit().size(12))
+        .on_press(Message::StartQuickEdit(field))
+        .style(Modern::system_button())
+        .into()
+}
+
+/// Inline editor for a single field. Enter commits, Escape (or cancel) discards.
+fn quick_edit_input<'a>(label: &'a str, edit: &'a QuickEdit, text_style: TextStyle) -> Element<'a, Message> {
+    column![
+        text(label).style(Modern::primary_text()),
+        row![
+            text_input(label, &edit.value)
+                .on_input(Message::QuickEditInput)
+                .on_submit(Message::CommitQuickEdit)
+                .style(Modern::validated_text_input(edit.error.is_some()))
+                .width(200),
+            button(icon::save().size(12))
+                .on_press(Message::CommitQuickEdit)
+                .style(Modern::primary_button()),
+            button(icon::cancel().size(12))
+                .on_press(Message::CancelQuickEdit)
+                .style(Modern::danger_button()),
+        ]
+        .spacing(4)
+        .align_y(iced::Alignment::Center),
+        text(edit.error.clone().unwrap_or_default()).size(text_style.caption()).style(Modern::error_text()),
+    ]
+    .spacing(5)
+    .padding(10)
+    .into()
+}
+
 fn info_column(label: String, value: String) -> Element<'static, Message> {
     container(
         column![
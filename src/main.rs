@@ -5,19 +5,20 @@ use iced::event;
 use iced::keyboard::{self, Key, Modifiers};
 use iced::widget::{
     focus_next, focus_previous,
-    button, column, container, row, text, vertical_space, opaque, stack
+    button, column, container, row, scrollable, text, vertical_space, opaque, stack
 };
 use iced::{Element, Length, Size, Subscription, Task, Theme};
 use iced_table::{ColumnVisibilityMessage, table::Column};
 use persistence::FileManager;
 use price_levels::PriceLevelType;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use rust_decimal::Decimal;
 use std::path::PathBuf;
 use std::ops::Range;
 use iced_modern_theme::Modern;
 
 mod action;
+mod cli;
 mod settings;
 mod items;
 mod item_groups;
@@ -34,9 +35,19 @@ mod persistence;
 mod entity_component;
 mod icon;
 mod superedit;
+mod text_style;
+mod focus_style;
+mod sidebar;
+mod search_index;
+mod import_entities;
+mod templates;
+mod references;
+mod export_preview;
 
 use crate::{
     items::import_items,
+    import_entities::EntityRows,
+    items::json_interchange,
     items::{Item, ViewContext, preview_changes::ItemsTableView},
     item_groups::ItemGroup,
     price_levels::PriceLevel,
@@ -49,12 +60,20 @@ use crate::{
     printer_logicals::PrinterLogical,
     data_types::{ValidationError, EntityResolver},
     superedit::SuperEdit,
+    search_index::SearchKind,
+    text_style::TextStyle,
 };
 
-use data_types::{EntityId, ItemPrice};
+use data_types::{EntityId, ItemPrice, timestamp_now, DRAFT_ID};
 pub use action::Action;
 
 fn main() -> iced::Result {
+    // Any arguments mean a headless command, e.g. a scheduled export
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.is_empty() {
+        std::process::exit(cli::run(&args));
+    }
+
     iced::daemon(MenuBuilder::new, MenuBuilder::update, MenuBuilder::view)
         .subscription(MenuBuilder::subscription)
         .theme(MenuBuilder::theme)
@@ -82,9 +101,31 @@ pub enum Screen {
 #[derive(Debug, Clone)]
 pub enum Message {
     Navigate(Screen),
+    ToggleSidebarEditing,
+    MoveSidebarScreen(sidebar::SidebarScreen, bool),
+    ToggleFavoriteScreen(sidebar::SidebarScreen),
+    UpdateFindText(String),
+    UpdateReplaceText(String),
+    ToggleFindCaseSensitive(bool),
+    ToggleFindWholeWord(bool),
+    FindReplaceNames { find: String, replace: String, preview: bool },
+    CloseFindReplace,
+    ConfirmExport,
+    CancelExport,
+    SelectNameCase(items::name_case::NameCase),
+    ReviewNameCase,
+    ApplyNameCase,
+    CloseNameCase,
+    ApplyButtonFill,
+    CloseButtonFill,
+    SelectTemplate(templates::StarterTemplate),
+    ApplyTemplate,
+    CloseTemplates,
     HotKey(HotKey),
+    TableKey(iced::window::Id, items::preview_changes::Message), // Keys for a SuperEdit table's row selection
     ConfirmDelete(data_types::DeletionInfo),
     CancelDelete,
+    ToggleCopySolePrices(bool),
     ToggleTheme(bool),
 
     //Message handles
@@ -99,17 +140,44 @@ pub enum Message {
     RevenueCategories(EntityId, revenue_categories::Message),
     ReportCategories(EntityId, report_categories::Message),
     ChoiceGroups(EntityId, choice_groups::Message),
-    SuperEdit(superedit::Message),
+    SuperEdit(iced::window::Id, superedit::Message),
 
     //import handles
     FileDropped(PathBuf),
+    ReviewOverwriteImport,
+    UpdateOverwriteConfirmation(String),
+    CancelOverwriteImport,
     ImportItemsOverwriteExisting,
     ImportItemsIntoExisting,
+    ReviewJsonMerge,
+    BackFromJsonMerge,
     CancelItemImport,
+    ToggleImportCreateMissing(bool),
+    DismissImportMessage,
+    ToggleImportWarnings,
+    UndoImport,
+    AutosaveDraft,
+    RecoverDraft,
+    DiscardDraftRecovery,
+    DiscardItemEdit,
+    KeepEditingItem,
+    SaveItemEdit,
+
+    SaveComplete(Result<(), String>),
+    ChooseStartupDataDir,
+    Quit,
+    OpenReferencingItem(EntityId),
+    CloseReferences,
+    StartupDataDirChosen(Result<PathBuf, settings::Error>),
 
     //window handles
+    WindowCloseRequested(iced::window::Id),
+    SaveAndQuit,
+    DiscardAndQuit,
+    CancelQuit,
     WindowClosed(iced::window::Id),
-    WindowResized(iced::Size),
+    WindowResized(iced::window::Id, iced::Size),
+    WindowFocused(iced::window::Id, bool),
     RequestOpenWindow(WindowEnum),
     WindowOpened(iced::window::Id, WindowEnum),
     None,
@@ -137,14 +205,43 @@ pub struct MenuBuilder {
     settings: settings::AppSettings,
     theme: iced::Theme,
     file_manager: persistence::FileManager,
+    startup_error: Option<String>, // The data directory can't be used, nothing is loaded until one is picked
     deletion_info: data_types::DeletionInfo,
+    copy_sole_prices_to_default: bool, // Deleting a price level keeps it as the default price where it was the only price
     show_modal: bool,
     show_super_edit: bool,
     show_item_import_confirmation: bool,
+    sidebar_editing: bool,
+    find_replace: Option<items::find_replace::FindReplace>,
+    export_preview: Option<export_preview::ExportPreview>, // Generated export waiting to be confirmed
+    name_case: Option<items::name_case::NameCaseTool>,
+    button_fill: Option<Vec<items::abbreviate::ButtonFill>>, // Button 1 text offered for items without any
+    template_picker: Option<templates::TemplatePicker>,
     error_message: Option<String>,
     toggle_theme: bool,
     import_item_path: PathBuf,
-    superedit: SuperEdit,
+    pending_json_import: Option<json_interchange::ItemsExport>,
+    json_merge_review: Option<json_interchange::JsonImportReport>, // What adding the JSON export would do, waiting on Import or Back
+    import_create_missing: bool,
+    overwrite_review: Option<OverwriteReview>,
+    import_message: Option<String>,
+    pending_draft_recovery: Option<persistence::DraftRecovery>,
+    last_draft_autosave: Option<String>, // Fingerprint of the draft when it was last written or opened
+    item_edit_baseline: Option<String>, // Fingerprint of the item when editing started, None for new or recovered drafts
+    confirm_leave_edit: bool,
+    pending_item_navigation: Option<(EntityId, items::Operation)>, // Item list click waiting on the unsaved changes prompt
+    confirm_quit: Option<iced::window::Id>, // Main window waiting on Save / Discard / Cancel
+    import_undo: Option<persistence::AppState>, // Data as it was before the last import, until the notice is dismissed
+    import_warnings: Vec<import_items::ImportWarning>,
+    show_import_warnings: bool,
+    data_checks: settings::DataChecks,
+    data_dir_state: settings::DataDirState,
+    settings_nav: settings::SettingsNav,
+    list_scroll: entity_component::ListScroll, // Where the item and entity lists were scrolled to
+    references_panel: Option<(SearchKind, EntityId)>, // Entity whose referencing items are listed beside its screen
+    save_requested: bool, // Changes waiting for the next background save
+    save_in_flight: bool, // A background save is still writing
+    close_after_save: Option<(iced::window::Id, bool)>, // Main window waiting on that save to close, and whether a failed save keeps it open
     preview: Option<ItemsTableView>,
 
     // Items
@@ -154,10 +251,23 @@ pub struct MenuBuilder {
     selected_item_id: Option<EntityId>,
     item_edit_state: items::EditState,
     item_search: String,
+    search_index: search_index::SearchIndex, // Lowercased search text, updated as items and entities change
+    search_hits: search_index::CachedHits, // What the item search currently matches, so views don't query
+    item_printer_filter: Option<EntityId>,
+    item_group_filter: Option<EntityId>,
+    item_missing_filter: Option<items::Classification>,
+    missing_classification_counts: Option<Vec<(items::Classification, usize)>>,
+    item_quick_edit: Option<items::QuickEdit>,
+    quick_edit_undo: Option<(Item, Item)>, // An item before and after its last quick edit
+    collapsed_item_groups: BTreeSet<Option<EntityId>>,
+    deleted_items: Vec<items::delta_export::ItemTombstone>, // Deletions the next delta export reports
  
     // Item Groups 
     item_groups: BTreeMap<EntityId, ItemGroup>,
     item_group_edit_state_vec: Vec<item_groups::ItemGroupEditState>,
+    show_group_statistics: bool,
+    group_statistics_sort: item_groups::StatsSort,
+    group_statistics_message: String,
  
     // Price Levels
     price_levels: BTreeMap<EntityId, PriceLevel>,
@@ -190,32 +300,71 @@ pub struct MenuBuilder {
     // Printer Logicals
     printer_logicals: BTreeMap<EntityId, PrinterLogical>,
     printer_logical_edit_state_vec: Vec<entity_component::EditState>,
+    show_printer_load_report: bool,
+    printer_load_report_message: String,
+    printer_reroute: Option<printer_logicals::Reroute>,
+
+    // Sort choice groups, printer logicals and price levels by item usage
+    sort_entities_by_usage: bool,
  }
  
  impl Default for MenuBuilder {
     fn default() -> Self {
-        // Initialize file manager first
-        let file_manager = FileManager::new()
-            .expect("Failed to initialize file manager");
-        
-        // Ensure data directory exists
-        file_manager.ensure_data_dir()
-            .expect("Failed to create data directory");
+        // Initialize file manager first, a data directory that can't be used is reported on the startup screen
+        let file_manager = FileManager::new();
+        let startup_error = file_manager.startup_problem();
 
+        Self::with_file_manager(file_manager, startup_error)
+    }
+}
+
+impl MenuBuilder {
+    /// Empty state around a file manager whose data directory was already checked,
+    /// or doesn't need to be as with the command line
+    fn with_file_manager(file_manager: FileManager, startup_error: Option<String>) -> Self {
         Self {
             windows: BTreeMap::new(),
             screen: Screen::Items(items::Mode::View),
             settings: settings::AppSettings::default(),
             theme: iced_modern_theme::Modern::dark_theme(),
             file_manager: file_manager,
+            startup_error,
             show_item_import_confirmation: false,
+            sidebar_editing: false,
+            find_replace: None,
+            export_preview: None,
+            name_case: None,
+            button_fill: None,
+            template_picker: None,
             show_modal: false,
             show_super_edit: false,
             deletion_info: data_types::DeletionInfo::new(),
+            copy_sole_prices_to_default: false,
             error_message: None,
             toggle_theme: true,
             import_item_path: PathBuf::new(),
-            superedit: SuperEdit::new(),
+            pending_json_import: None,
+            json_merge_review: None,
+            import_create_missing: true,
+            overwrite_review: None,
+            import_message: None,
+            pending_draft_recovery: None,
+            last_draft_autosave: None,
+            item_edit_baseline: None,
+            confirm_leave_edit: false,
+            pending_item_navigation: None,
+            confirm_quit: None,
+            import_undo: None,
+            import_warnings: Vec::new(),
+            show_import_warnings: false,
+            data_checks: settings::DataChecks::default(),
+            data_dir_state: settings::DataDirState::default(),
+            settings_nav: settings::SettingsNav::default(),
+            list_scroll: entity_component::ListScroll::default(),
+            references_panel: None,
+            save_requested: false,
+            save_in_flight: false,
+            close_after_save: None,
             preview: None,
 
             // Items
@@ -225,10 +374,23 @@ pub struct MenuBuilder {
             selected_item_id: None,
             item_edit_state: items::EditState::default(),
             item_search: String::new(),
+            search_index: search_index::SearchIndex::default(),
+            search_hits: search_index::CachedHits::default(),
+            item_printer_filter: None,
+            item_group_filter: None,
+            item_missing_filter: None,
+            missing_classification_counts: None,
+            item_quick_edit: None,
+            quick_edit_undo: None,
+            collapsed_item_groups: BTreeSet::new(),
+            deleted_items: Vec::new(),
  
             // Item Groups
             item_groups: BTreeMap::new(),
             item_group_edit_state_vec: Vec::new(),
+            show_group_statistics: false,
+            group_statistics_sort: item_groups::StatsSort::default(),
+            group_statistics_message: String::new(),
 
             // Price Levels 
             price_levels: BTreeMap::new(),
@@ -261,9 +423,14 @@ pub struct MenuBuilder {
             // Printer Logicals
             printer_logicals: BTreeMap::new(),
             printer_logical_edit_state_vec: Vec::new(),
+            show_printer_load_report: false,
+            printer_load_report_message: String::new(),
+            printer_reroute: None,
+
+            sort_entities_by_usage: false,
         }
     }
- }
+}
 
 impl MenuBuilder {
 
@@ -276,34 +443,50 @@ impl MenuBuilder {
     }
 
     fn new() -> (Self, Task<Message>) {
-        
         let mut menu_builder = MenuBuilder::default();
 
-        let available_choice_groups: Vec<ChoiceGroup> = menu_builder.choice_groups.values().cloned().collect();
-        let available_printer_logicals: Vec<PrinterLogical> = menu_builder.printer_logicals.values().cloned().collect();
-        let available_price_levels: Vec<PriceLevel> = menu_builder.price_levels.values().cloned().collect();
+        // Nothing is read until there's a data directory that works, the startup screen asks for one
+        if menu_builder.startup_error.is_none() {
+            menu_builder.load_at_startup();
+        }
+
+        (menu_builder, Task::done(Message::RequestOpenWindow(WindowEnum::MainWindow)))
+    }
+
+    /// Reads the saved data when the app starts, or once a usable data directory was picked
+    fn load_at_startup(&mut self) {
+        let available_choice_groups: Vec<ChoiceGroup> = self.choice_groups.values().cloned().collect();
+        let available_printer_logicals: Vec<PrinterLogical> = self.printer_logicals.values().cloned().collect();
+        let available_price_levels: Vec<PriceLevel> = self.price_levels.values().cloned().collect();
+        let first_run = !std::path::Path::new(&self.settings.file_path).exists();
         // Try to load state from file
-        match menu_builder.load_state() {
+        match self.load_state() {
             Ok(()) => {
                 println!("Successfully loaded saved data");
-                menu_builder.item_edit_state = items::EditState::new(
-                    &menu_builder.draft_item,
+                self.item_edit_state = items::EditState::new(
+                    &self.draft_item,
                     available_choice_groups,
                     available_printer_logicals,
                     available_price_levels,
                 );
+                self.item_edit_state.pickers = self.classification_pickers();
 
                 // If no items were loaded, create a default one
-                if menu_builder.items.is_empty() {
+                if self.items.is_empty() {
                     let mut default_item = Item::default();
                     default_item.name = "Default".to_string();
-                    menu_builder.items.insert(1, default_item);
-                    menu_builder.selected_item_id = Some(1);
+                    self.items.insert(1, default_item);
+                    self.selected_item_id = Some(1);
                 }
 
-                menu_builder.settings.export_message = "".to_string();
-                menu_builder.settings.export_success = true;
-                menu_builder.error_message = None;
+                self.settings.export_message = "".to_string();
+                self.settings.export_success = true;
+                self.error_message = None;
+
+                self.run_data_checks();
+                if !self.data_checks.report.is_empty() {
+                    println!("Loaded data has {} problems, see Settings > Data Checks", self.data_checks.report.len());
+                }
             }
             Err(e) => {
                 eprintln!("Failed to load state: {}", e);
@@ -311,16 +494,28 @@ impl MenuBuilder {
                 // Create a default item for new users
                 let mut default_item = Item::default();
                 default_item.name = "Default".to_string();
-                menu_builder.items.insert(1, default_item);
-                menu_builder.selected_item_id = Some(1);
-                menu_builder.error_message = Some(format!("Failed to load saved data: {}", e));
+                self.items.insert(1, default_item);
+                self.selected_item_id = Some(1);
+                self.error_message = Some(format!("Failed to load saved data: {}", e));
             }
         }
 
-        (menu_builder, Task::done(Message::RequestOpenWindow(WindowEnum::MainWindow)))
+        // New users get to start from a template instead of a single Default item
+        if first_run {
+            self.template_picker = Some(templates::TemplatePicker { selected: None, first_run: true });
+        }
+
+        // A leftover sidecar means the last item edit was never saved or discarded
+        self.pending_draft_recovery = self.file_manager.read_draft_recovery();
     }
 
     fn update(&mut self, message: Message) -> Task<Message> {
+        let task = self.handle_message(message);
+        self.search_hits.refresh(&self.search_index, &self.item_search);
+        Task::batch([task, self.start_pending_save()])
+    }
+
+    fn handle_message(&mut self, message: Message) -> Task<Message> {
         println!("Update Message received: {:?}", &message);
         match message {
             Message::Settings(msg) => {
@@ -345,24 +540,10 @@ impl MenuBuilder {
             Message::Items(id, msg) => {
                 println!("What the ID?: {}", id);
 
-                let mut context = ViewContext {
-                    available_items: &mut self.items,
-                    available_choice_groups: &mut self.choice_groups,
-                    available_item_groups: &mut self.item_groups,
-                    available_price_levels: &mut self.price_levels,
-                    available_printer_logicals: &mut self.printer_logicals,
-                    available_product_classes: &mut self.product_classes,
-                    available_report_categories: &mut self.report_categories,
-                    available_revenue_categories: &mut self.revenue_categories,
-                    available_security_levels: &mut self.security_levels,
-                    available_tax_groups: &mut self.tax_groups,
-                };  
-
                 let action = items::update(
                     &mut self.draft_item,
                     msg,
                     &mut self.item_edit_state,
-                    &mut context
                 )
                 .map_operation(move |o| Operation::Items(id, o))
                 .map(move |m| Message::Items(id, m));
@@ -385,7 +566,7 @@ impl MenuBuilder {
                     .filter(|ig| ig.id != id)
                     .collect();
 
-                let action = item_groups::update(msg)
+                let action = item_groups::update(msg, &self.item_groups)
                     .map_operation(move |o| Operation::ItemGroups(id, o))
                     .map(move |m| Message::ItemGroups(id, m));
                 
@@ -405,7 +586,7 @@ impl MenuBuilder {
                     .filter(|pl| pl.id != id)
                     .collect();
 
-                let action = price_levels::update(msg)
+                let action = price_levels::update(msg, &self.price_levels)
                     .map_operation(move |o| Operation::PriceLevels(id, o))
                     .map(move |m| Message::PriceLevels(id, m));
 
@@ -425,7 +606,7 @@ impl MenuBuilder {
                     .filter(|pc| pc.id != id)
                     .collect();
 
-                let action = product_classes::update(msg)
+                let action = product_classes::update(msg, &self.product_classes)
                     .map_operation(move |o| Operation::ProductClasses(id, o))
                     .map(move |m| Message::ProductClasses(id, m));
 
@@ -445,7 +626,7 @@ impl MenuBuilder {
                     .filter(|tg| tg.id != id)
                     .collect();
 
-                let action = tax_groups::update(msg, )
+                let action = tax_groups::update(msg, &self.tax_groups)
                     .map_operation(move |o| Operation::TaxGroups(id, o))
                     .map(move |m| Message::TaxGroups(id, m));
 
@@ -465,7 +646,7 @@ impl MenuBuilder {
                     .filter(|sl| sl.id != id)
                     .collect();
 
-                let action = security_levels::update(msg)
+                let action = security_levels::update(msg, &self.security_levels)
                     .map_operation(move |o| Operation::SecurityLevels(id, o))
                     .map(move |m| Message::SecurityLevels(id, m));
 
@@ -485,7 +666,7 @@ impl MenuBuilder {
                     .filter(|rc| rc.id != id)
                     .collect();
 
-                let action = revenue_categories::update(msg)
+                let action = revenue_categories::update(msg, &self.revenue_categories)
                     .map_operation(move |o| Operation::RevenueCategories(id, o))
                     .map(move |m| Message::RevenueCategories(id, m));
 
@@ -506,7 +687,7 @@ impl MenuBuilder {
                     .collect();
 
 
-                let action = report_categories::update(msg)
+                let action = report_categories::update(msg, &self.report_categories)
                     .map_operation(move |o| Operation::ReportCategories(id, o))
                     .map(move |m| Message::ReportCategories(id, m));
 
@@ -526,7 +707,7 @@ impl MenuBuilder {
                     .filter(|c| c.id != id)
                     .collect();
 
-                    let action = choice_groups::update(msg)
+                    let action = choice_groups::update(msg, &self.choice_groups)
                         .map_operation(move |o| Operation::ChoiceGroups(id, o))
                         .map(move |m| Message::ChoiceGroups(id, m));
 
@@ -546,7 +727,7 @@ impl MenuBuilder {
                     .filter(|p| p.id != id)
                     .collect();
             
-                let action = printer_logicals::update(msg)
+                let action = printer_logicals::update(msg, &self.printer_logicals)
                     .map_operation(move |o| Operation::PrinterLogicals(id, o))
                     .map(move |m| Message::PrinterLogicals(id, m));
             
@@ -562,6 +743,284 @@ impl MenuBuilder {
                 self.screen = screen;
                 Task::none()
             },
+            Message::UpdateFindText(find) => {
+                if let Some(find_replace) = &mut self.find_replace {
+                    find_replace.find = find;
+                    find_replace.preview = None;
+                }
+                Task::none()
+            },
+            Message::UpdateReplaceText(replace) => {
+                if let Some(find_replace) = &mut self.find_replace {
+                    find_replace.replace = replace;
+                    find_replace.preview = None;
+                }
+                Task::none()
+            },
+            Message::ToggleFindCaseSensitive(case_sensitive) => {
+                if let Some(find_replace) = &mut self.find_replace {
+                    find_replace.case_sensitive = case_sensitive;
+                    find_replace.preview = None;
+                }
+                Task::none()
+            },
+            Message::ToggleFindWholeWord(whole_word) => {
+                if let Some(find_replace) = &mut self.find_replace {
+                    find_replace.whole_word = whole_word;
+                    find_replace.preview = None;
+                }
+                Task::none()
+            },
+            Message::FindReplaceNames { find, replace, preview } => {
+                let Some(find_replace) = &mut self.find_replace else {
+                    return Task::none();
+                };
+                find_replace.find = find;
+                find_replace.replace = replace;
+
+                let changes = find_replace.preview(&self.items);
+                println!("Find \"{}\" replace \"{}\": {} items match", find_replace.find, find_replace.replace, changes.len());
+
+                if preview {
+                    find_replace.preview = Some(changes);
+                    return Task::none();
+                }
+
+                let snapshot_note = self.snapshot_before("find-replace");
+                let updated_at = timestamp_now();
+                let mut renamed = 0;
+                let mut replacements = 0;
+                let mut skipped = Vec::new();
+
+                for change in changes {
+                    // An empty name can't be saved, leave those items alone
+                    if change.new_name.trim().is_empty() {
+                        skipped.push(change.item_id.to_string());
+                        continue;
+                    }
+                    if let Some(item) = self.items.get_mut(&change.item_id) {
+                        item.name = change.new_name;
+                        item.updated_at = Some(updated_at.clone());
+                        renamed += 1;
+                        replacements += change.replacements;
+                    }
+                }
+
+                self.search_index.reindex_items(&self.items);
+                self.save_state();
+
+                let mut message = format!("Renamed {} items ({} replacements).", renamed, replacements);
+                if !skipped.is_empty() {
+                    message.push_str(&format!(
+                        "\nSkipped items whose name would be empty: {}",
+                        skipped.join(", ")
+                    ));
+                }
+                self.import_message = Some(with_note(message, snapshot_note));
+                self.find_replace = None;
+                Task::none()
+            },
+            Message::CloseFindReplace => {
+                self.find_replace = None;
+                Task::none()
+            },
+            Message::ConfirmExport => {
+                let Some(preview) = self.export_preview.take() else {
+                    return Task::none();
+                };
+
+                let result = self.file_manager.write_export(&preview.files);
+
+                // Exports from other screens report back to them, the rest to Settings
+                let written = result.as_ref().map(|_| preview.target()).map_err(|e| e.to_string());
+                match &preview.kind {
+                    export_preview::ExportKind::LoadReport => self.update(
+                        Message::PrinterLogicals(-1, printer_logicals::Message::LoadReportExported(written))
+                    ),
+                    export_preview::ExportKind::RoutingMap => self.update(
+                        Message::PrinterLogicals(-1, printer_logicals::Message::RoutingMapExported(written))
+                    ),
+                    export_preview::ExportKind::GroupStatistics => self.update(
+                        Message::ItemGroups(-1, item_groups::Message::StatisticsExported(written))
+                    ),
+                    export_preview::ExportKind::Patch { .. } => {
+                        let editors: Vec<iced::window::Id> = self.windows
+                            .iter()
+                            .filter(|(_, window)| matches!(window.kind, WindowKind::SuperEdit(_)))
+                            .map(|(id, _)| *id)
+                            .collect();
+                        Task::batch(editors.into_iter().map(|id| {
+                            self.update(Message::SuperEdit(id, superedit::Message::PatchExported(written.clone())))
+                        }).collect::<Vec<_>>())
+                    }
+                    _ => {
+                        let result = result.map_err(|e| settings::Error::IoError(e.kind()));
+                        self.update(Message::Settings(settings::Message::ExportWritten(preview, result)))
+                    }
+                }
+            },
+            Message::CancelExport => {
+                self.export_preview = None;
+                self.update(Message::Settings(settings::Message::UpdateExportMessage("Export canceled, nothing was written".to_string())))
+            },
+            Message::SelectNameCase(case) => {
+                if let Some(name_case) = &mut self.name_case {
+                    name_case.case = case;
+                    name_case.confirming = false;
+                }
+                Task::none()
+            },
+            Message::ReviewNameCase => {
+                if let Some(name_case) = &mut self.name_case {
+                    name_case.confirming = true;
+                }
+                Task::none()
+            },
+            Message::ApplyNameCase => {
+                let Some(name_case) = self.name_case.take() else {
+                    return Task::none();
+                };
+
+                let changes = name_case.preview(&self.items);
+                println!("Changing {} item names to {}", changes.len(), name_case.case);
+
+                let snapshot_note = self.snapshot_before("name-case");
+                let updated_at = timestamp_now();
+
+                for change in &changes {
+                    if let Some(item) = self.items.get_mut(&change.item_id) {
+                        item.name = change.new_name.clone();
+                        item.updated_at = Some(updated_at.clone());
+                    }
+                }
+
+                self.search_index.reindex_items(&self.items);
+                self.save_state();
+
+                self.import_message = Some(with_note(
+                    format!("Changed {} item names to {}.", changes.len(), name_case.case),
+                    snapshot_note,
+                ));
+                Task::none()
+            },
+            Message::CloseNameCase => {
+                self.name_case = None;
+                Task::none()
+            },
+            Message::ApplyButtonFill => {
+                let Some(fills) = self.button_fill.take() else {
+                    return Task::none();
+                };
+                println!("Filling button 1 text on {} items", fills.len());
+
+                let snapshot_note = self.snapshot_before("button-fill");
+                self.import_undo = Some(self.app_state());
+                let updated_at = timestamp_now();
+                let mut filled = 0;
+
+                for fill in &fills {
+                    // Only items still without button text, in case one was edited meanwhile
+                    if let Some(item) = self.items.get_mut(&fill.item_id) {
+                        if item.button1.trim().is_empty() {
+                            item.button1 = fill.button1.clone();
+                            item.updated_at = Some(updated_at.clone());
+                            filled += 1;
+                        }
+                    }
+                }
+
+                self.search_index.reindex_items(&self.items);
+                self.save_state();
+
+                self.import_message = Some(with_note(
+                    format!("Filled in Button 1 text on {} items.", filled),
+                    snapshot_note,
+                ));
+                Task::none()
+            },
+            Message::CloseButtonFill => {
+                self.button_fill = None;
+                Task::none()
+            },
+            Message::SelectTemplate(template) => {
+                if let Some(picker) = &mut self.template_picker {
+                    picker.selected = Some(template);
+                }
+                Task::none()
+            },
+            Message::ApplyTemplate => {
+                let Some(template) = self.template_picker.take().and_then(|picker| picker.selected) else {
+                    return Task::none();
+                };
+
+                let state = match template.load() {
+                    Ok(state) => state,
+                    Err(e) => {
+                        println!("{}", e);
+                        self.import_message = Some(e);
+                        return Task::none();
+                    }
+                };
+
+                // Replacing real data always gets a snapshot and an undo, like an overwrite import
+                let replacing = !self.is_empty_database();
+                let snapshot_note = replacing.then(|| self.write_snapshot("template"));
+                if replacing {
+                    self.import_undo = Some(self.app_state());
+                }
+
+                self.restore_app_state(state);
+                if self.items.is_empty() {
+                    let mut default_item = Item::default();
+                    default_item.name = "Default".to_string();
+                    self.items.insert(1, default_item);
+                    self.selected_item_id = Some(1);
+                    self.rebuild_search_index();
+                    self.reset_super_editors();
+                }
+
+                self.save_state();
+                self.run_data_checks();
+                self.import_message = Some(with_note(
+                    format!(
+                        "Loaded the {} template: {} tax groups, {} price levels, {} revenue categories and {} report categories.",
+                        template,
+                        self.tax_groups.len(),
+                        self.price_levels.len(),
+                        self.revenue_categories.len(),
+                        self.report_categories.len()
+                    ),
+                    snapshot_note,
+                ));
+                Task::none()
+            },
+            Message::CloseTemplates => {
+                self.template_picker = None;
+                Task::none()
+            },
+            Message::ToggleSidebarEditing => {
+                self.sidebar_editing = !self.sidebar_editing;
+                Task::none()
+            },
+            Message::MoveSidebarScreen(screen, up) => {
+                sidebar::move_screen(
+                    &mut self.settings.sidebar_order,
+                    &self.settings.favorite_screens,
+                    screen,
+                    up,
+                );
+                self.save_state();
+                Task::none()
+            },
+            Message::ToggleFavoriteScreen(screen) => {
+                if self.settings.favorite_screens.contains(&screen) {
+                    self.settings.favorite_screens.retain(|s| *s != screen);
+                } else {
+                    self.settings.favorite_screens.push(screen);
+                }
+                self.save_state();
+                Task::none()
+            },
             Message::HotKey(hotkey) => {
                 match hotkey {
                     HotKey::Tab(modifiers) => {
@@ -571,12 +1030,48 @@ impl MenuBuilder {
                             focus_next()
                         }
                     }
-                    HotKey::Escape => Task::none(),
+                    HotKey::Escape => self.escape(),
+                    HotKey::ToggleEdit => match self.screen {
+                        Screen::Items(items::Mode::View) => match self.selected_item_id {
+                            Some(item_id) if self.items.contains_key(&item_id) => {
+                                self.perform(Operation::Items(item_id, items::Operation::StartEdit))
+                            }
+                            _ => Task::none(),
+                        },
+                        Screen::Items(items::Mode::Edit) => {
+                            if self.has_unsaved_item_edit() {
+                                self.confirm_leave_edit = true;
+                                Task::none()
+                            } else {
+                                self.perform(Operation::Items(self.draft_item.id, items::Operation::Cancel))
+                            }
+                        }
+                        _ => Task::none(),
+                    },
+                    HotKey::SaveEdit => match self.save_entity_edit_message() {
+                        Some(message) => self.update(message),
+                        None => Task::none(),
+                    },
+                }
+            }
+            Message::TableKey(window_id, msg) => {
+                // Only SuperEdit windows have a table, keys in other windows are left alone
+                match self.windows.get(&window_id).map(|w| &w.kind) {
+                    Some(WindowKind::SuperEdit(_)) => Task::done(Message::SuperEdit(window_id, superedit::Message::Preview(msg))),
+                    _ => Task::none(),
                 }
             }
             Message::ConfirmDelete(deletion_info) => {
                 println!("Deleting Type: {}, id: {}", deletion_info.entity_type, deletion_info.entity_id);
 
+                // Deleting an entity items still use clears it from all of them, snapshot first
+                let clears_references = SearchKind::from_entity_type(&deletion_info.entity_type)
+                    .filter(|kind| *kind != SearchKind::Item)
+                    .is_some_and(|kind| !references::referencing_items(&self.items, kind, deletion_info.entity_id).is_empty());
+                if clears_references {
+                    self.import_message = Some(self.write_snapshot("delete"));
+                }
+
                 match deletion_info.entity_type.as_str() {
                     "ChoiceGroup" => {
                         // Clean up references in all items
@@ -613,11 +1108,29 @@ impl MenuBuilder {
                     }
                     "Item" => {
                         //Delete the item
-                        if self.items.contains_key(&deletion_info.entity_id) { self.items.remove(&deletion_info.entity_id); }
+                        if let Some(item) = self.items.remove(&deletion_info.entity_id) {
+                            self.deleted_items.push(items::delta_export::ItemTombstone::new(&item, timestamp_now()));
+                        }
                     }
                     "PriceLevel" => {
                         // Clean up references in all items
                         for (_, item) in self.items.iter_mut() {
+                            // Keep the price on items that would otherwise have none
+                            if self.copy_sole_prices_to_default && item.has_sole_price_level(deletion_info.entity_id) {
+                                item.default_price = item.item_prices
+                                    .iter()
+                                    .flatten()
+                                    .find(|price| price.price_level_id == deletion_info.entity_id)
+                                    .map(|price| price.price);
+                            }
+
+                            if let Some(prices) = &mut item.item_prices {
+                                prices.retain(|price| price.price_level_id != deletion_info.entity_id);
+                                if prices.is_empty() {
+                                    item.item_prices = None;
+                                }
+                            }
+
                             if let Some(price_levels) = &mut item.price_levels {
                                 // Remove this specific price level ID from the Item.price_levels vec
                                 price_levels.retain(|&price_id| price_id != deletion_info.entity_id);
@@ -629,6 +1142,12 @@ impl MenuBuilder {
                             }
                         }
 
+                        // Groups stop giving it to new items
+                        item_groups::remove_default_price_level(&mut self.item_groups, deletion_info.entity_id);
+                        for edit_state in self.item_group_edit_state_vec.iter_mut() {
+                            edit_state.default_price_levels.retain(|id| *id != deletion_info.entity_id);
+                        }
+
                         // Delete the price level
                         self.price_levels.remove(&deletion_info.entity_id);
                         self.screen = Screen::PriceLevels;
@@ -729,17 +1248,28 @@ impl MenuBuilder {
                     _ => {println!("Oh No! You've tried to delete an unknown type: {}", deletion_info.entity_type);}
                 }
 
+                if deletion_info.entity_type == "Item" {
+                    self.search_index.remove_item(deletion_info.entity_id);
+                }
+
+                let restore_scroll = self.restore_list_scroll(&deletion_info.entity_type);
                 self.deletion_info = data_types::DeletionInfo::new();
+                self.copy_sole_prices_to_default = false;
                 self.show_modal = false;
-                self.save_state().expect("Failed to save to file.");
-                Task::none()
+                self.save_state();
+                restore_scroll
             }
             Message::CancelDelete => {
                 println!("Canceling Delete Request");
                 self.deletion_info = data_types::DeletionInfo::new();
+                self.copy_sole_prices_to_default = false;
                 self.show_modal = false;
                 Task::none()
             }
+            Message::ToggleCopySolePrices(copy) => {
+                self.copy_sole_prices_to_default = copy;
+                Task::none()
+            }
             Message::ToggleTheme(bool) => {
                 if bool {
                     self.theme = iced_modern_theme::Modern::dark_theme()
@@ -753,37 +1283,166 @@ impl MenuBuilder {
 
                 println!("File Dropped: {:?}", &path);
                 self.import_item_path = path.clone();
-                if import_items::is_csv_or_txt(path.clone()) {
-                    match import_items::verify_csv_format(path) {
-                        Ok(_) => {
+                self.pending_json_import = None;
+
+                let kind = match import_items::sniff_file_kind(&path) {
+                    Ok(kind) => kind,
+                    Err(e) => {
+                        println!("Could not read dropped file: {:?}", e);
+                        self.import_message = Some(format!("Could not read {}:\n{}", path.display(), e));
+                        return Task::none();
+                    }
+                };
+
+                let labeled_json = json_interchange::is_json(&path);
+                let labeled_csv = import_items::is_csv_or_txt(path.clone());
+                if (kind == import_items::FileKind::Json && !labeled_json)
+                    || (kind == import_items::FileKind::Csv && !labeled_csv) {
+                    println!("File extension doesn't match its contents, importing as {:?}", kind);
+                }
+
+                if kind == import_items::FileKind::Json {
+                    match json_interchange::read_items_export(&path) {
+                        Ok(export) => {
+                            println!("JSON export confirmed, {} items.", export.items.len());
+                            self.pending_json_import = Some(export);
+                            self.show_item_import_confirmation = true;
+                        }
+                        Err(e) => {
+                            println!("{:?}", e);
+                            self.import_message = Some(format!("Could not import {}:\n{}", path.display(), e));
+                        }
+                    }
+                } else if kind == import_items::FileKind::Csv {
+                    match import_items::verify_csv_format(path.clone(), self.settings.csv_leading_lines, self.settings.import_latin1_fallback) {
+                        Ok(encoding) => {
                             self.show_item_import_confirmation = true;
-                            println!("File format confirmed.")
+                            println!("File format confirmed, read as {}.", encoding)
+                        }
+                        Err(e) => {
+                            println!("{:?}", e);
+                            self.import_message = Some(format!("Could not import {}:\n{}", path.display(), e));
                         }
-                        Err(e) => {println!("{:?}", e);}
                     }
+                } else {
+                    println!("Dropped file is neither CSV nor JSON: {:?}", &path);
+                    self.import_message = Some(format!(
+                        "{} doesn't look like an item CSV or a JSON export.",
+                        path.display()
+                    ));
                 }
 
                 Task::none()
             }
+            Message::ReviewOverwriteImport => {
+                let incoming = match &self.pending_json_import {
+                    Some(export) => Ok(export.items.len()),
+                    None => import_items::collect_item_information(&self.import_item_path, self.settings.csv_leading_lines, self.settings.import_latin1_fallback)
+                        .map(|(items, _)| items.len())
+                        .map_err(|e| e.to_string()),
+                };
+
+                let last_saved = std::fs::metadata(&self.settings.file_path)
+                    .and_then(|metadata| metadata.modified())
+                    .ok()
+                    .map(|modified| chrono::DateTime::<chrono::Local>::from(modified).format("%Y-%m-%d %H:%M").to_string());
+
+                let review = OverwriteReview {
+                    current_items: self.items.len(),
+                    current_entities: self.reference_entity_count(),
+                    last_saved,
+                    incoming,
+                    confirmation: String::new(),
+                };
+                println!("Reviewing overwrite import: {:?}", review);
+                self.overwrite_review = Some(review);
+                Task::none()
+            },
+            Message::UpdateOverwriteConfirmation(value) => {
+                if let Some(review) = &mut self.overwrite_review {
+                    review.confirmation = value;
+                }
+                Task::none()
+            },
+            Message::CancelOverwriteImport => {
+                self.overwrite_review = None;
+                Task::none()
+            },
             Message::ImportItemsOverwriteExisting => {
                 println!("File Path to import items: {:?}", &self.import_item_path.clone());
 
+                if !self.overwrite_review.as_ref().is_some_and(OverwriteReview::is_confirmed) {
+                    println!("Overwrite import was not confirmed");
+                    return Task::none();
+                }
+                self.overwrite_review = None;
+
+                // Always snapshot before a wipe, even if bulk change snapshots are turned off
+                let snapshot_note = Some(self.write_snapshot("import"));
+                let import_undo = self.app_state();
+
                 //clear existing data, keep settings
                 let default = MenuBuilder::default();
                 let windows = self.windows.clone();
                 let settings = self.settings.clone();
                 let import_path = &self.import_item_path.clone();
+                let pending_json_import = self.pending_json_import.take();
+                let create_missing = self.import_create_missing;
+                // A background save may still be writing, the reset must not forget it
+                let save_in_flight = self.save_in_flight;
+                let save_requested = self.save_requested;
+                let close_after_save = self.close_after_save.take();
                 *self = default;
                 self.settings = settings;
                 self.windows = windows;
+                self.save_in_flight = save_in_flight;
+                self.save_requested = save_requested;
+                self.close_after_save = close_after_save;
+
+                // The theme lives outside settings, so it has to be derived again after the reset
+                self.theme = match self.settings.app_theme {
+                    settings::ThemeChoice::Light => Modern::light_theme(),
+                    settings::ThemeChoice::Dark => Modern::dark_theme(),
+                };
+                self.import_undo = Some(import_undo);
+
+                //JSON exports carry their own entities, merge them into the empty database
+                if let Some(export) = pending_json_import {
+                    let report = json_interchange::merge_items_export(
+                        export,
+                        create_missing,
+                        &mut self.items,
+                        &mut self.item_groups,
+                        &mut self.price_levels,
+                        &mut self.product_classes,
+                        &mut self.tax_groups,
+                        &mut self.security_levels,
+                        &mut self.revenue_categories,
+                        &mut self.report_categories,
+                        &mut self.choice_groups,
+                        &mut self.printer_logicals,
+                    );
+                    let summary = self.apply_default_tax_group(report.summary(), |_| true);
+                    self.import_message = Some(with_note(summary, snapshot_note));
+                    self.rebuild_search_index();
+                    self.run_data_checks();
+                    self.reset_super_editors();
+                    self.show_item_import_confirmation = false;
+                    return Task::none()
+                }
 
                 //import items from the import file.
-                match import_items::collect_item_information(import_path) {
-                    Ok(imported_items) => { 
-                        println!("It worked!");
+                let mut import_error = None;
+                match import_items::collect_item_information(import_path, self.settings.csv_leading_lines, self.settings.import_latin1_fallback) {
+                    Ok((imported_items, warnings)) => { 
+                        println!("Imported {} items with {} warnings", imported_items.len(), warnings.len());
                         self.items = imported_items;
+                        self.import_warnings = warnings;
                     },
-                    Err(e) => { println!("Error collecting item information!: {}", e); }
+                    Err(e) => {
+                        println!("Error collecting item information!: {}", e);
+                        import_error = Some(e.to_string());
+                    }
                 };
                 println!("{:?}", self.items.last_entry());
 
@@ -800,22 +1459,232 @@ impl MenuBuilder {
                     &mut self.printer_logicals,
                 );
 
+                self.rebuild_search_index();
+                self.run_data_checks();
+                let mut summary = match import_error {
+                    Some(e) => format!("Could not read items from {}:\n{}", import_path.display(), e),
+                    None if self.import_warnings.is_empty() => {
+                        format!("Imported {} items into a new database.", self.items.len())
+                    }
+                    None => format!(
+                        "Imported {} items into a new database, {} warnings.",
+                        self.items.len(),
+                        self.import_warnings.len()
+                    ),
+                };
+                if !self.items.is_empty() {
+                    summary = self.apply_default_tax_group(summary, |_| true);
+                }
+                self.reset_super_editors();
+                self.import_message = Some(with_note(summary, snapshot_note));
                 self.show_item_import_confirmation = false;
                 Task::none()
             },
+            Message::ReviewJsonMerge => {
+                if let Some(export) = &self.pending_json_import {
+                    let report = json_interchange::preview_merge(
+                        export,
+                        self.import_create_missing,
+                        &self.items,
+                        &self.item_groups,
+                        &self.price_levels,
+                        &self.product_classes,
+                        &self.tax_groups,
+                        &self.security_levels,
+                        &self.revenue_categories,
+                        &self.report_categories,
+                        &self.choice_groups,
+                        &self.printer_logicals,
+                    );
+                    self.json_merge_review = Some(report);
+                    self.show_item_import_confirmation = false;
+                }
+                Task::none()
+            },
+            Message::BackFromJsonMerge => {
+                self.json_merge_review = None;
+                self.show_item_import_confirmation = self.pending_json_import.is_some();
+                Task::none()
+            },
             Message::ImportItemsIntoExisting => {
+                self.json_merge_review = None;
+                if let Some(export) = self.pending_json_import.take() {
+                    let snapshot_note = self.snapshot_before("import");
+                    self.import_undo = Some(self.app_state());
+                    let existing_ids: BTreeSet<EntityId> = self.items.keys().copied().collect();
+                    let report = json_interchange::merge_items_export(
+                        export,
+                        self.import_create_missing,
+                        &mut self.items,
+                        &mut self.item_groups,
+                        &mut self.price_levels,
+                        &mut self.product_classes,
+                        &mut self.tax_groups,
+                        &mut self.security_levels,
+                        &mut self.revenue_categories,
+                        &mut self.report_categories,
+                        &mut self.choice_groups,
+                        &mut self.printer_logicals,
+                    );
+                    // Only the imported items, existing items without a tax group are left to the coverage report
+                    let summary = self.apply_default_tax_group(report.summary(), |id| !existing_ids.contains(&id));
+                    self.import_message = Some(with_note(summary, snapshot_note));
+                    self.rebuild_search_index();
+                    self.run_data_checks();
+                }
                 self.show_item_import_confirmation = false;
                 Task::none()
             },
             Message::CancelItemImport => {
+                self.pending_json_import = None;
+                self.json_merge_review = None;
+                self.overwrite_review = None;
                 self.show_item_import_confirmation = false;
                 Task::none()
             },
-            Message::SuperEdit(msg) => {
+            Message::ToggleImportCreateMissing(create_missing) => {
+                self.import_create_missing = create_missing;
+                Task::none()
+            },
+            Message::DismissImportMessage => {
+                self.import_message = None;
+                self.import_undo = None;
+                self.import_warnings.clear();
+                self.show_import_warnings = false;
+                Task::none()
+            },
+            Message::ToggleImportWarnings => {
+                self.show_import_warnings = !self.show_import_warnings;
+                Task::none()
+            },
+            Message::AutosaveDraft => {
+                if !matches!(self.screen, Screen::Items(items::Mode::Edit)) {
+                    return Task::none();
+                }
+
+                let fingerprint = draft_fingerprint(&self.draft_item, &self.item_edit_state.prices);
+                if self.last_draft_autosave.as_ref() == Some(&fingerprint) {
+                    return Task::none();
+                }
+
+                let draft = persistence::DraftRecovery {
+                    saved_at: chrono::Local::now().format("%Y-%m-%d %H:%M:%S").to_string(),
+                    draft_item_id: self.draft_item_id,
+                    item: self.draft_item.clone(),
+                    prices: self.item_edit_state.prices.clone(),
+                };
+
+                match self.file_manager.write_draft_recovery(&draft) {
+                    Ok(()) => {
+                        println!("Autosaved item draft {:?}", self.draft_item_id);
+                        self.last_draft_autosave = Some(fingerprint);
+                    }
+                    Err(e) => println!("Draft autosave failed: {}", e),
+                }
+                Task::none()
+            },
+            Message::RecoverDraft => {
+                if let Some(draft) = self.pending_draft_recovery.take() {
+                    println!("Recovering item draft from {}", draft.saved_at);
+
+                    self.draft_item = draft.item;
+                    self.draft_item_id = draft.draft_item_id;
+                    self.item_edit_state = items::EditState::new(
+                        &self.draft_item,
+                        self.choice_groups.values().cloned().collect(),
+                        self.printer_logicals.values().cloned().collect(),
+                        self.price_levels.values().cloned().collect(),
+                    );
+                    self.item_edit_state.pickers = self.classification_pickers();
+                    self.item_edit_state.prices = draft.prices;
+                    self.last_draft_autosave = Some(draft_fingerprint(&self.draft_item, &self.item_edit_state.prices));
+                    self.item_edit_baseline = None;
+
+                    if self.items.contains_key(&self.draft_item.id) {
+                        self.selected_item_id = Some(self.draft_item.id);
+                    }
+                    self.screen = Screen::Items(items::Mode::Edit);
+                }
+                Task::none()
+            },
+            Message::DiscardDraftRecovery => {
+                self.pending_draft_recovery = None;
+                self.file_manager.remove_draft_recovery();
+                Task::none()
+            },
+            Message::DiscardItemEdit => {
+                self.confirm_leave_edit = false;
+                let cancel_task = self.perform(Operation::Items(self.draft_item.id, items::Operation::Cancel));
+
+                match self.pending_item_navigation.take() {
+                    Some((id, op)) => Task::batch([cancel_task, self.perform(Operation::Items(id, op))]),
+                    None => cancel_task,
+                }
+            },
+            Message::KeepEditingItem => {
+                self.confirm_leave_edit = false;
+                self.pending_item_navigation = None;
+                Task::none()
+            },
+            Message::SaveItemEdit => {
+                self.confirm_leave_edit = false;
+                let item = self.draft_item.clone();
+                let save_task = self.perform(Operation::Items(item.id, items::Operation::Save(item)));
+
+                // Still editing means the item failed validation, stay on the form so it can be fixed
+                if matches!(self.screen, Screen::Items(items::Mode::Edit)) {
+                    self.pending_item_navigation = None;
+                    return save_task;
+                }
+
+                match self.pending_item_navigation.take() {
+                    Some((id, op)) => Task::batch([save_task, self.perform(Operation::Items(id, op))]),
+                    None => save_task,
+                }
+            },
+            Message::UndoImport => {
+                if let Some(state) = self.import_undo.take() {
+                    println!("Undoing import, restoring {} items", state.items.len());
+                    self.restore_app_state(state);
+                    self.run_data_checks();
+
+                    self.save_state();
+                }
+                self.import_message = None;
+                Task::none()
+            },
+            Message::SuperEdit(window_id, msg) => {
+                // The editor's state lives on its window, a message from a closed window is dropped
+                let has_preview = match self.windows.get(&window_id).map(|w| &w.kind) {
+                    Some(WindowKind::SuperEdit(editor)) => editor.has_preview(),
+                    _ => {
+                        println!("SuperEdit message for a window that is gone: {:?}", window_id);
+                        return Task::none();
+                    }
+                };
+
+                // Accepting can rewrite every item in scope, so a preview being accepted is always snapshotted
+                if matches!(msg, superedit::Message::AcceptChanges) && has_preview {
+                    self.import_message = Some(self.write_snapshot("superedit"));
+                }
+                if matches!(msg, superedit::Message::Preview(items::preview_changes::Message::ConfirmDeleteSelected)) {
+                    self.import_message = Some(self.write_snapshot("delete"));
+                    self.import_undo = Some(self.app_state());
+                }
+                if matches!(msg, superedit::Message::ApplyFlagChanges) {
+                    self.import_message = self.snapshot_before("flag-changes");
+                    self.import_undo = Some(self.app_state());
+                }
 
+                let Some(WindowKind::SuperEdit(editor)) = self.windows.get_mut(&window_id).map(|w| &mut w.kind) else {
+                    return Task::none();
+                };
+                // Picks up a page size changed in Settings while the window was open
+                editor.set_page_size(self.settings.preview_page_size);
+                editor.set_text_style(TextStyle::from_settings(&self.settings));
 
                 let action = superedit::SuperEdit::update(
-                    &mut self.superedit, 
+                    editor,
                     msg,
                     &mut self.items,
                     &self.item_groups,
@@ -829,7 +1698,7 @@ impl MenuBuilder {
                     &self.price_levels,
                 )
                     .map_operation(move |o| Operation::SuperEdit(o))
-                    .map(move |m| Message::SuperEdit(m));
+                    .map(move |m| Message::SuperEdit(window_id, m));
                 
                 let operation_task = if let Some(operation) = action.operation {
                     self.perform(operation)
@@ -839,53 +1708,146 @@ impl MenuBuilder {
                 
                 operation_task.chain(action.task)
             }
-           Message::WindowClosed(id) => {
-                println!("Window close requested: {:?}", &id);
-                self.windows.remove(&id);
-                println!("Window BTreeMap: {:?}", &self.windows);
-
-                if self.windows.is_empty() {
-                    iced::exit()
-                } else {
+            Message::WindowCloseRequested(id) => {
+                let is_main = self.windows.get(&id).map_or(true, Window::is_main);
+                if is_main && !self.unsaved_changes().is_empty() {
+                    println!("Close requested with unsaved changes");
+                    self.confirm_quit = Some(id);
                     Task::none()
+                } else if is_main {
+                    self.close_after_saving(id, false)
+                } else {
+                    iced::window::close(id)
                 }
-           },
-            Message::WindowResized(size) => {
-                Task::none()
             },
-            Message::RequestOpenWindow(windowenum) => {
-                match windowenum {
-                    WindowEnum::MainWindow => { 
-                        let (_id, open) = iced::window::open(
-                            iced::window::Settings {
-                                size: Size::new(1201.0, 700.0),
-                                position: window::Position::Centered,
-                                min_size: Some(Size::new( 1250_f32, 700_f32)),
-                                exit_on_close_request: true,
-                                icon: settings::load_icon(),
-                                ..iced::window::Settings::default()
-                            }
-                        );
-                        return open.map(|id| Message::WindowOpened(id, WindowEnum::MainWindow))
+            Message::SaveComplete(result) => {
+                self.save_in_flight = false;
+                match result {
+                    Ok(()) => println!("Saved to {}", self.settings.file_path),
+                    Err(e) => {
+                        println!("Background save failed: {}", e);
+                        self.import_message = Some(format!("Could not save your changes:\n{}", e));
+                        self.error_message = Some(e);
                     }
-                    WindowEnum::SuperEdit => {
-                        if self.windows.values().any(|w| w.windowtype == WindowEnum::SuperEdit) {
-                            return Task::none()
-                        };
+                }
 
-                        self.superedit.fill_table(
-                            &self.items, 
-                            &self.item_groups, 
-                            &self.tax_groups, 
-                            &self.security_levels, 
-                            &self.revenue_categories, 
-                            &self.report_categories, 
-                            &self.product_classes, 
-                            &self.choice_groups, 
-                            &self.printer_logicals, 
-                            &self.price_levels
-                        );
-                        
+                // A close was waiting on this save, the newest state can be written now
+                match self.close_after_save.take() {
+                    Some((id, stay_open_on_error)) => self.close_after_saving(id, stay_open_on_error),
+                    None => Task::none(),
+                }
+            },
+            Message::ChooseStartupDataDir => {
+                Task::perform(settings::choose_data_directory(), Message::StartupDataDirChosen)
+            },
+            Message::StartupDataDirChosen(Ok(dir)) => {
+                if let Err(e) = persistence::validate_data_dir(&dir) {
+                    self.startup_error = Some(format!("The data folder {} can't be used. {}", dir.display(), e));
+                    return Task::none();
+                }
+
+                // Locked down machines may not let us write the config folder either, the folder still works until the app closes
+                if let Err(e) = self.file_manager.set_data_dir(dir.clone()) {
+                    println!("Using {} for this session only: {}", dir.display(), e);
+                    self.import_message = Some(format!(
+                        "{} will be used until the app closes, but it couldn't be remembered for next time:\n{}",
+                        dir.display(), e
+                    ));
+                    self.file_manager.use_data_dir(dir);
+                }
+
+                self.settings.file_path = self.file_manager.get_default_path().to_string_lossy().into_owned();
+                self.startup_error = None;
+                self.load_at_startup();
+                Task::none()
+            },
+            Message::StartupDataDirChosen(Err(_)) => Task::none(),
+            Message::Quit => iced::exit(),
+            Message::OpenReferencingItem(id) => {
+                self.selected_item_id = Some(id);
+                self.item_quick_edit = None;
+                self.screen = Screen::Items(items::Mode::View);
+                Task::none()
+            }
+            Message::CloseReferences => {
+                self.references_panel = None;
+                Task::none()
+            }
+            Message::SaveAndQuit => {
+                let Some(id) = self.confirm_quit.take() else {
+                    return Task::none();
+                };
+
+                if matches!(self.screen, Screen::Items(items::Mode::Edit)) {
+                    let item = self.draft_item.clone();
+                    let save_task = self.perform(Operation::Items(item.id, items::Operation::Save(item)));
+
+                    // Still editing means the item failed validation, stay open so it can be fixed
+                    if matches!(self.screen, Screen::Items(items::Mode::Edit)) {
+                        return save_task;
+                    }
+                }
+
+                self.save_state();
+                self.close_after_saving(id, true)
+            },
+            Message::DiscardAndQuit => {
+                // Only the open edits are discarded, changes already saved still get written
+                match self.confirm_quit.take() {
+                    Some(id) => self.close_after_saving(id, false),
+                    None => Task::none(),
+                }
+            },
+            Message::CancelQuit => {
+                self.confirm_quit = None;
+                Task::none()
+            },
+           Message::WindowClosed(id) => {
+                println!("Window closed: {:?}", &id);
+                // Dropping the entry drops whatever state the window owned
+                let closed = self.windows.remove(&id);
+                println!("Window BTreeMap: {:?}", self.windows.keys());
+
+                // The unsaved changes guard already ran on the close request
+                if self.windows.is_empty() || closed.as_ref().map_or(false, Window::is_main) {
+                    self.file_manager.remove_draft_recovery();
+                    iced::exit()
+                } else {
+                    Task::none()
+                }
+           },
+            Message::WindowResized(id, size) => {
+                if let Some(window) = self.windows.get_mut(&id) {
+                    window.size = size;
+                }
+                Task::none()
+            },
+            Message::WindowFocused(id, focused) => {
+                if let Some(window) = self.windows.get_mut(&id) {
+                    window.focused = focused;
+                }
+                Task::none()
+            },
+            Message::RequestOpenWindow(windowenum) => {
+                match windowenum {
+                    WindowEnum::MainWindow => { 
+                        let (_id, open) = iced::window::open(
+                            iced::window::Settings {
+                                size: Size::new(1201.0, 700.0),
+                                position: window::Position::Centered,
+                                min_size: Some(Size::new( 1250_f32, 700_f32)),
+                                exit_on_close_request: false, // Asks about unsaved changes first
+                                icon: settings::load_icon(),
+                                ..iced::window::Settings::default()
+                            }
+                        );
+                        return open.map(|id| Message::WindowOpened(id, WindowEnum::MainWindow))
+                    }
+                    WindowEnum::SuperEdit => {
+                        // Only one SuperEdit at a time, bring the open one forward instead
+                        if let Some((id, _)) = self.windows.iter().find(|(_, w)| w.windowtype() == WindowEnum::SuperEdit) {
+                            return iced::window::gain_focus(*id)
+                        };
 
                         let (_id, open) = iced::window::open(window::Settings {
                             icon: settings::load_icon(),
@@ -902,7 +1864,12 @@ impl MenuBuilder {
                     WindowEnum::SuperEdit => { String::from("Super Editor AKA Mass Edit AKA aight bro") }
                 };
 
-                let new_window = Window::new(id, title, windowenum);
+                let kind = match windowenum {
+                    WindowEnum::MainWindow => WindowKind::Main,
+                    WindowEnum::SuperEdit => WindowKind::SuperEdit(self.new_super_editor()),
+                };
+
+                let new_window = Window::new(title, kind);
 
                 self.windows.insert(id, new_window);
 
@@ -916,116 +1883,59 @@ impl MenuBuilder {
     }
 
     fn view(&self, window_id: window::Id) -> Element<Message> {
+        let text_style = TextStyle::from_settings(&self.settings);
 
+        let sidebar_screens = sidebar::ordered(&self.settings.sidebar_order, &self.settings.favorite_screens);
 
-                    
         let sidebar = container(
             column![
-                button("Items")
-                    .on_press(Message::Navigate(Screen::Items(items::Mode::View)))
-                    .width(Length::Fill)
-                    .style(
-                        Modern::conditional_button_style(
-                            matches!(self.screen, Screen::Items(_)),
-                            Modern::selected_button_style(Modern::system_button()),
-                            Modern::system_button()
-                        )
-                    ),
-                button("Item Groups")
-                    .on_press(Message::Navigate(Screen::ItemGroups))
-                    .width(Length::Fill)
-                    .style(
-                        Modern::conditional_button_style(
-                            matches!(self.screen, Screen::ItemGroups),
-                            Modern::selected_button_style(Modern::system_button()),
-                            Modern::system_button()
-                        )
-                    ),
-                button("Price Levels")
-                    .on_press(Message::Navigate(Screen::PriceLevels))
-                    .width(Length::Fill)
-                    .style(
-                        Modern::conditional_button_style(
-                            matches!(self.screen, Screen::PriceLevels),
-                            Modern::selected_button_style(Modern::system_button()),
-                            Modern::system_button()
-                        )
-                    ),
-                button("Product Classes")
-                    .on_press(Message::Navigate(Screen::ProductClasses))
-                    .width(Length::Fill)
-                    .style(
-                        Modern::conditional_button_style(
-                            matches!(self.screen, Screen::ProductClasses),
-                            Modern::selected_button_style(Modern::system_button()),
-                            Modern::system_button()
-                        )
-                    ),
-                button("Tax Groups")
-                    .on_press(Message::Navigate(Screen::TaxGroups))
-                    .width(Length::Fill)
-                    .style(
-                        Modern::conditional_button_style(
-                            matches!(self.screen, Screen::TaxGroups),
-                            Modern::selected_button_style(Modern::system_button()),
-                            Modern::system_button()
-                        )
-                    ),
-                button("Security Levels")
-                    .on_press(Message::Navigate(Screen::SecurityLevels))
-                    .width(Length::Fill)
-                    .style(
-                        Modern::conditional_button_style(
-                            matches!(self.screen, Screen::SecurityLevels),
-                            Modern::selected_button_style(Modern::system_button()),
-                            Modern::system_button()
-                        )
-                    ),
-                button("Revenue Categories")
-                    .on_press(Message::Navigate(Screen::RevenueCategories))
-                    .width(Length::Fill)
-                    .style(
-                        Modern::conditional_button_style(
-                            matches!(self.screen, Screen::RevenueCategories),
-                            Modern::selected_button_style(Modern::system_button()),
-                            Modern::system_button()
-                        )
-                    ),
-                button("Report Categories")
-                    .on_press(Message::Navigate(Screen::ReportCategories))
-                    .width(Length::Fill)
-                    .style(
-                        Modern::conditional_button_style(
-                            matches!(self.screen, Screen::ReportCategories),
-                            Modern::selected_button_style(Modern::system_button()),
-                            Modern::system_button()
-                        )
-                    ),
-                button("Choice Groups")
-                    .on_press(Message::Navigate(Screen::ChoiceGroups))
-                    .width(Length::Fill)
-                    .style(
-                        Modern::conditional_button_style(
-                            matches!(self.screen, Screen::ChoiceGroups),
-                            Modern::selected_button_style(Modern::system_button()),
-                            Modern::system_button()
-                        )
-                    ),
-                button("Printer Logicals")
-                    .on_press(Message::Navigate(Screen::PrinterLogicals))
-                    .width(Length::Fill)
-                    .style(
-                        Modern::conditional_button_style(
-                            matches!(self.screen, Screen::PrinterLogicals),
-                            Modern::selected_button_style(Modern::system_button()),
-                            Modern::system_button()
-                        )
-                    ),
+                column(
+                    sidebar_screens.iter().map(|screen| {
+                        let screen = *screen;
+                        let favorite = self.settings.favorite_screens.contains(&screen);
+
+                        let nav_button = button(text(screen.to_string()))
+                            .on_press(Message::Navigate(screen.screen()))
+                            .width(Length::Fill)
+                            .style(
+                                Modern::conditional_button_style(
+                                    screen.is_selected(&self.screen),
+                                    Modern::selected_button_style(Modern::system_button()),
+                                    Modern::system_button()
+                                )
+                            );
+
+                        if self.sidebar_editing {
+                            row![
+                                nav_button,
+                                button(text(if favorite { "★" } else { "☆" }).size(text_style.caption()))
+                                    .on_press(Message::ToggleFavoriteScreen(screen))
+                                    .style(Modern::system_button()),
+                                button(text("▲").size(text_style.caption()))
+                                    .on_press(Message::MoveSidebarScreen(screen, true))
+                                    .style(Modern::system_button()),
+                                button(text("▼").size(text_style.caption()))
+                                    .on_press(Message::MoveSidebarScreen(screen, false))
+                                    .style(Modern::system_button()),
+                            ]
+                            .spacing(2)
+                            .align_y(iced::Alignment::Center)
+                            .into()
+                        } else {
+                            nav_button.into()
+                        }
+                    })
+                )
+                .spacing(5),
+
+                button(text(if self.sidebar_editing { "Done" } else { "Arrange" }).size(text_style.caption()))
+                    .on_press(Message::ToggleSidebarEditing)
+                    .style(Modern::gray_button()),
 
                 vertical_space(),
                 row![
                     column![
-                        text("Toggle Theme").size(10),
+                        text("Toggle Theme").size(text_style.tiny()),
                         iced::widget::vertical_space().height(2),
                         iced::widget::toggler(self.toggle_theme).on_toggle(Message::ToggleTheme),
                     ],
@@ -1051,9 +1961,11 @@ impl MenuBuilder {
 
         let content = match &self.screen {
             Screen::Settings(settings) => {
-                settings::view(settings, self.error_message.as_deref()).map(Message::Settings)
+                settings::view(settings, &self.settings_nav, self.error_message.as_deref(), &self.data_checks, &self.tax_groups, &self.printer_logicals, &self.price_levels, self.file_manager.data_dir(), &self.data_dir_state).map(Message::Settings)
             },
             Screen::Items(mode) => {
+                let search_matches = self.search_hits.hits();
+
                 if let Some(id) = self.selected_item_id {
                     // When an item is selected, determine whether it represents a new item
                     // (negative ID) or an existing one, and if there’s a draft override.
@@ -1073,18 +1985,22 @@ impl MenuBuilder {
                     items::view(
                         item,
                         mode,
-                        &self.items,
+                        self.view_context(),
                         &self.item_search,
+                        search_matches,
+                        self.item_printer_filter.and_then(|id| self.printer_logicals.get(&id)),
+                        self.item_group_filter.and_then(|id| self.item_groups.get(&id)),
+                        &self.settings.pinned_items,
+                        self.item_missing_filter,
+                        self.missing_classification_counts.as_ref(),
+                        self.item_quick_edit.as_ref(),
+                        self.quick_edit_undo.as_ref().map(|(before, _)| before.id),
+                        &self.collapsed_item_groups,
+                        &self.settings.collapsed_edit_sections,
+                        &self.settings.custom_flag_labels,
+                        self.settings.price_sanity,
                         &self.item_edit_state,
-                        &self.item_groups,
-                        &self.tax_groups,
-                        &self.security_levels,
-                        &self.revenue_categories,
-                        &self.report_categories,
-                        &self.product_classes,
-                        &self.choice_groups,
-                        &self.printer_logicals,
-                        &self.price_levels,
+                        text_style,
                     )
                     .map(move |msg| Message::Items(id, msg))
                 } else if let Some((&first_id, first_item)) = self.items.iter().next() {
@@ -1092,18 +2008,22 @@ impl MenuBuilder {
                     items::view(
                         first_item,
                         mode,
-                        &self.items,
+                        self.view_context(),
                         &self.item_search,
+                        search_matches,
+                        self.item_printer_filter.and_then(|id| self.printer_logicals.get(&id)),
+                        self.item_group_filter.and_then(|id| self.item_groups.get(&id)),
+                        &self.settings.pinned_items,
+                        self.item_missing_filter,
+                        self.missing_classification_counts.as_ref(),
+                        self.item_quick_edit.as_ref(),
+                        self.quick_edit_undo.as_ref().map(|(before, _)| before.id),
+                        &self.collapsed_item_groups,
+                        &self.settings.collapsed_edit_sections,
+                        &self.settings.custom_flag_labels,
+                        self.settings.price_sanity,
                         &self.item_edit_state,
-                        &self.item_groups,
-                        &self.tax_groups,
-                        &self.security_levels,
-                        &self.revenue_categories,
-                        &self.report_categories,
-                        &self.product_classes,
-                        &self.choice_groups,
-                        &self.printer_logicals,
-                        &self.price_levels,
+                        text_style,
                     )
                     .map(move |msg| Message::Items(first_id, msg))
                 } else {
@@ -1111,7 +2031,7 @@ impl MenuBuilder {
                     container(
                         column![
                             text("Item Management")
-                                .size(24)
+                                .size(text_style.title())
                                 .width(Length::Fill),
                             vertical_space(),
                             text("No items have been created yet.")
@@ -1135,71 +2055,105 @@ impl MenuBuilder {
             Screen::ItemGroups => {
                 item_groups::view(
                     &self.item_groups,
-                    &self.item_group_edit_state_vec)
+                    &self.item_group_edit_state_vec,
+                    &self.items,
+                    &self.price_levels,
+                    self.show_group_statistics,
+                    self.group_statistics_sort,
+                    &self.group_statistics_message,
+                    text_style)
                 .map(move |msg| Message::ItemGroups(-1, msg)) // Default ID for new messages
             }
             Screen::PriceLevels => {
                 price_levels::view(
                     &self.price_levels,
-                    &self.price_level_edit_state_vec)
+                    &self.price_level_edit_state_vec,
+                    price_levels::usage_counts(&self.items),
+                    self.sort_entities_by_usage,
+                    text_style)
                 .map(move |msg| Message::PriceLevels(-1, msg))
             }
             Screen::ProductClasses => {
 
                 product_classes::view(
                     &self.product_classes,
-                    &self.product_class_edit_state_vec)
+                    &self.product_class_edit_state_vec,
+                    text_style)
                 .map(move |msg| Message::ProductClasses(-1, msg))
             }
             Screen::TaxGroups => {
+                let mismatches = tax_groups::tax_included_mismatches(&self.items);
                 tax_groups::view(
                     &self.tax_groups,
-                    &self.tax_group_edit_state_vec)
+                    &self.tax_group_edit_state_vec,
+                    &mismatches,
+                    tax_groups::TaxCoverage::of(&self.items),
+                    self.default_tax_group().and_then(|id| self.tax_groups.get(&id)),
+                    text_style)
                 .map(move |msg| Message::TaxGroups(-1, msg))
             }
             Screen::SecurityLevels => {
                 security_levels::view(
                     &self.security_levels,
-                    &self.security_level_edit_state_vec)
+                    &self.security_level_edit_state_vec,
+                    text_style)
                 .map(move |msg| Message::SecurityLevels(-1, msg))
             }
             Screen::RevenueCategories => {
                 revenue_categories::view(
                     &self.revenue_categories,
-                    &self.revenue_category_edit_state_vec)
+                    &self.revenue_category_edit_state_vec,
+                    text_style)
                 .map(move |msg| Message::RevenueCategories(-1, msg))
             }
             Screen::ReportCategories => {
 
                 report_categories::view(
                     &self.report_categories,
-                    &self.report_category_edit_state_vec)
+                    &self.report_category_edit_state_vec,
+                    text_style)
                 .map(move |msg| Message::ReportCategories(-1, msg))
             }
             Screen::ChoiceGroups => {
                 choice_groups::view(
                     &self.choice_groups,
-                    &self.choice_group_edit_state_vec)
+                    &self.choice_group_edit_state_vec,
+                    choice_groups::usage_counts(&self.items),
+                    self.sort_entities_by_usage,
+                    text_style)
                 .map(move |msg| Message::ChoiceGroups(-1, msg))
             }
             Screen::PrinterLogicals => {
                 printer_logicals::view(
                     &self.printer_logicals, 
-                    &self.printer_logical_edit_state_vec)
+                    &self.printer_logical_edit_state_vec,
+                    &self.items,
+                    &self.item_groups,
+                    self.show_printer_load_report,
+                    &self.printer_load_report_message,
+                    self.sort_entities_by_usage,
+                    text_style)
                 .map(move |msg| Message::PrinterLogicals(-1, msg))
             }
         };
 
+        // Items pointing at the entity last opened for editing, beside its list
+        let content = match self.references_view() {
+            Some(panel) => row![content, panel].spacing(20).into(),
+            None => content,
+        };
+
         let delete_confirmation_popup = container(
             container(
                 column![
                     vertical_space().height(10),
                     row![
                         iced::widget::horizontal_space().width(6),
-                        text("Are you sure you want to delete this ".to_string() + &self.deletion_info.entity_type).style(Modern::primary_text()).size(16),
+                        text("Are you sure you want to delete this ".to_string() + &self.deletion_info.entity_type).style(Modern::primary_text()).size(text_style.subheading()),
                         iced::widget::horizontal_space().width(6),
                     ],
-                    
+                    self.deletion_references(),
+                    self.sole_price_warning(),
                     iced::widget::vertical_space().height(15),
                     row![
                         iced::widget::horizontal_space().width(6),
@@ -1207,164 +2161,1219 @@ impl MenuBuilder {
                         iced::widget::horizontal_space(),
                         button("Cancel").on_press(Message::CancelDelete).style(Modern::system_button()),
                         iced::widget::horizontal_space().width(6),
-                    ]
-                ].width(275).height(100)
+                    ],
+                    vertical_space().height(10),
+                ].width(275)
             ).style(Modern::separated_container())
         ).padding(250);
 
+        let unsaved_changes = if self.show_item_import_confirmation {
+            self.unsaved_changes()
+        } else {
+            Vec::new()
+        };
+
         let import_items_confirmation = container(
             container(
                 column![
                     vertical_space().height(10),
                     row![
                         iced::widget::horizontal_space().width(6),
-                        text("Import Items").style(Modern::primary_text()).size(18),
+                        text("Import Items").style(Modern::primary_text()).size(text_style.heading()),
                         iced::widget::horizontal_space().width(6),
                     ],
                     iced::widget::vertical_space().height(10),
                     row![
                         iced::widget::horizontal_space().width(6),
-                        text("Do you want to overwrite existing data, or import into the existing database?").style(Modern::secondary_text()).size(14),
+                        text("Do you want to overwrite existing data, or import into the existing database?").style(Modern::secondary_text()).size(text_style.body()),
                         iced::widget::horizontal_space().width(6),
                     ],
+
+                    if unsaved_changes.is_empty() {
+                        row![]
+                    } else {
+                        row![
+                            iced::widget::horizontal_space().width(6),
+                            text(format!(
+                                "Unsaved changes will be lost with New Database:\n{}",
+                                unsaved_changes.join("\n")
+                            )).style(Modern::error_text()).size(text_style.caption()),
+                            iced::widget::horizontal_space().width(6),
+                        ]
+                    },
                     
+                    if self.pending_json_import.is_some() {
+                        row![
+                            iced::widget::horizontal_space().width(6),
+                            iced::widget::checkbox("Create entities missing from this database", self.import_create_missing)
+                                .on_toggle(Message::ToggleImportCreateMissing)
+                                .style(Modern::checkbox()),
+                        ]
+                    } else {
+                        row![]
+                    },
                     iced::widget::vertical_space().height(15),
                     row![
                         iced::widget::horizontal_space().width(6),
-                        button("New Database").on_press(Message::ImportItemsOverwriteExisting).style(Modern::warning_button()),
+                        button("New Database").on_press(Message::ReviewOverwriteImport).style(Modern::warning_button()),
                         iced::widget::horizontal_space(),
-                        button("Add to existing").on_press(Message::ImportItemsIntoExisting).style(Modern::primary_button()),
+                        button("Add to existing")
+                            .on_press(if self.pending_json_import.is_some() { Message::ReviewJsonMerge } else { Message::ImportItemsIntoExisting })
+                            .style(Modern::primary_button()),
                         iced::widget::horizontal_space(),
                         button("Cancel").on_press(Message::CancelItemImport).style(Modern::system_button()),
                         iced::widget::horizontal_space().width(6),
                     ]
-                ].width(335).height(135)
+                ].width(335).spacing(5)
             ).style(Modern::accent_container())
         ).padding(250);
 
-        //iced::widget::stack
-        let app_view = row![
-            sidebar,
-            container(content)
-                .width(Length::Fill)
-                .padding(20),
-        ];
-        
-        println!("List of Windows: {:?}", self.windows.get(&window_id));
-        let window_view = match self.windows.get(&window_id) {
-            Some(window) => match window.windowtype {
-                WindowEnum::MainWindow => {
-                    println!("Launched Main Window!");
-                    if self.show_modal { //Show Deletion confirmation popup
-                        stack![
-                            app_view,
-                            opaque(delete_confirmation_popup)
-                        ].into()
-                    } else if self.show_item_import_confirmation { // Show Item Import Confirmation popup
-                        stack![
-                            app_view,
-                            opaque(import_items_confirmation)
-                        ].into()
-                    }
-                    else {
-                        app_view.into()
-                    }
-                }
-                WindowEnum::SuperEdit => {
-                    println!("Launched SuperEdit!");
-                    self.superedit.view(
-                        &self.items,
-                        &self.item_groups,
-                        &self.tax_groups,
-                        &self.security_levels,
-                        &self.revenue_categories,
-                        &self.report_categories,
-                        &self.product_classes,
-                        &self.choice_groups,
-                        &self.printer_logicals,
-                        &self.price_levels
-                    ).map(move |msg| Message::SuperEdit(msg))
-                }
-            }
-            None => { 
-                let content = column![
-                    text(format!("Something has gone terribly wrong. Window Id: {:?}", window_id)),
-                ];
+        let overwrite_review_popup = if let Some(review) = &self.overwrite_review {
+            let incoming = match &review.incoming {
+                Ok(count) => format!("{} items parsed from {}", count, self.import_item_path.display()),
+                Err(e) => format!("The file could not be read: {}", e),
+            };
+
+            container(
                 container(
-                    content
-                ).into() 
-            }
+                    column![
+                        text("Replace Current Database?").style(Modern::primary_text()).size(text_style.heading()),
+                        text("Current database").style(Modern::primary_text()).size(text_style.body()),
+                        text(format!(
+                            "{} items, {} groups, levels and other reference entities\nLast saved: {}",
+                            review.current_items,
+                            review.current_entities,
+                            review.last_saved.clone().unwrap_or_else(|| "never".to_string())
+                        )).style(Modern::secondary_text()).size(text_style.caption()),
+                        text("Incoming file").style(Modern::primary_text()).size(text_style.body()),
+                        text(incoming).style(Modern::secondary_text()).size(text_style.caption()),
+                        text(format!(
+                            "This will permanently delete your current {} items.",
+                            review.current_items
+                        )).style(Modern::error_text()).size(text_style.body()),
+                        if review.needs_typed_confirmation() {
+                            column![
+                                text(format!("Type DELETE or {} to confirm:", review.current_items)).size(text_style.caption()),
+                                iced::widget::text_input("DELETE", &review.confirmation)
+                                    .on_input(Message::UpdateOverwriteConfirmation)
+                                    .style(Modern::inline_text_input())
+                                    .padding(5),
+                            ].spacing(5)
+                        } else {
+                            column![]
+                        },
+                        vertical_space().height(10),
+                        row![
+                            button("Delete and Import")
+                                .on_press_maybe(review.is_confirmed().then_some(Message::ImportItemsOverwriteExisting))
+                                .style(Modern::danger_button()),
+                            iced::widget::horizontal_space(),
+                            button("Back").on_press(Message::CancelOverwriteImport).style(Modern::system_button()),
+                        ],
+                    ].width(335).spacing(8).padding(10)
+                ).style(Modern::accent_container())
+            ).padding(250)
+        } else {
+            container(column![])
         };
 
-        window_view
-
-     }
+        let json_merge_popup = if let Some(report) = &self.json_merge_review {
+            let conflicts: Element<Message> = if report.items_reassigned.is_empty() {
+                text("No item IDs are already in use.").style(Modern::secondary_text()).size(text_style.caption()).into()
+            } else {
+                column![
+                    text("These item IDs are already in use and will be given new ones:").size(text_style.caption()),
+                    scrollable(
+                        column(
+                            report.items_reassigned.iter().map(|(old_id, new_id)| {
+                                text(format!("{} → {}", old_id, new_id)).size(text_style.caption()).into()
+                            })
+                        )
+                        .spacing(2)
+                    )
+                    .height(150),
+                ].spacing(5).into()
+            };
 
+            container(
+                container(
+                    column![
+                        text("Review JSON Import").style(Modern::primary_text()).size(text_style.heading()),
+                        text(format!("From {}", self.import_item_path.display())).style(Modern::secondary_text()).size(text_style.caption()),
+                        text(report.summary()).size(text_style.body()),
+                        conflicts,
+                        vertical_space().height(10),
+                        row![
+                            button("Import").on_press(Message::ImportItemsIntoExisting).style(Modern::primary_button()),
+                            iced::widget::horizontal_space(),
+                            button("Back").on_press(Message::BackFromJsonMerge).style(Modern::system_button()),
+                        ],
+                    ].width(335).spacing(8).padding(10)
+                ).style(Modern::accent_container())
+            ).padding(250)
+        } else {
+            container(column![])
+        };
 
-    fn perform(&mut self, operation: Operation) -> Task<Message> {
-        match operation {
-            Operation::Settings(op) => {
-                match op {
-                    settings::Operation::Save(new_settings) => {
-                        self.settings = new_settings;
+        let export_preview_popup: Element<Message> = match &self.export_preview {
+            Some(preview) => preview.view(Message::ConfirmExport, Message::CancelExport, text_style),
+            None => container(column![]).into(),
+        };
 
-                        if let Err(e) = self.save_state() {
-                            self.error_message = Some(e);
-                        } else {
-                            self.error_message = None;
-                        }
+        let find_replace_popup = if let Some(find_replace) = &self.find_replace {
+            let preview: Element<Message> = match &find_replace.preview {
+                Some(changes) => column![
+                    text(format!(
+                        "{} items, {} replacements",
+                        changes.len(),
+                        changes.iter().map(|change| change.replacements).sum::<usize>()
+                    )).style(Modern::primary_text()).size(text_style.body()),
+                    scrollable(
+                        column(
+                            changes.iter().map(|change| {
+                                text(format!("{}: {} → {}", change.item_id, change.old_name, change.new_name))
+                                    .size(text_style.caption())
+                                    .into()
+                            })
+                        )
+                        .spacing(2)
+                    )
+                    .height(200),
+                ].spacing(5).into(),
+                None => text("Preview the changes before applying them.")
+                    .style(Modern::secondary_text())
+                    .size(text_style.caption())
+                    .into(),
+            };
 
-                        self.screen = Screen::Settings(self.settings.clone());
-                        Task::none()
-                    }
-                    settings::Operation::Back => {
-                        self.screen = Screen::Items(items::Mode::View);
-                        self.error_message = None;
-                        Task::none()
-                    }
-                    settings::Operation::ShowError(error) => {
-                        self.error_message = Some(error);
-                        self.screen = Screen::Settings(self.settings.clone());
-                        Task::none()
-                    }
-                    settings::Operation::ThemeChanged(theme) => {
+            let can_apply = find_replace.preview.as_ref().is_some_and(|changes| !changes.is_empty());
 
-                        self.theme = match theme {
-                            settings::ThemeChoice::Light => Modern::light_theme(),
-                            settings::ThemeChoice::Dark => Modern::dark_theme(),
-                        };
+            container(
+                container(
+                    column![
+                        text("Find and Replace in Item Names").style(Modern::primary_text()).size(text_style.heading()),
+                        iced::widget::text_input("Find", &find_replace.find)
+                            .on_input(Message::UpdateFindText)
+                            .style(Modern::inline_text_input())
+                            .padding(5),
+                        iced::widget::text_input("Replace with", &find_replace.replace)
+                            .on_input(Message::UpdateReplaceText)
+                            .style(Modern::inline_text_input())
+                            .padding(5),
+                        row![
+                            iced::widget::checkbox("Case sensitive", find_replace.case_sensitive)
+                                .on_toggle(Message::ToggleFindCaseSensitive)
+                                .style(Modern::checkbox()),
+                            iced::widget::checkbox("Whole word", find_replace.whole_word)
+                                .on_toggle(Message::ToggleFindWholeWord)
+                                .style(Modern::checkbox()),
+                        ].spacing(15),
+                        preview,
+                        row![
+                            button("Preview")
+                                .on_press_maybe((!find_replace.find.is_empty()).then(|| Message::FindReplaceNames {
+                                    find: find_replace.find.clone(),
+                                    replace: find_replace.replace.clone(),
+                                    preview: true,
+                                }))
+                                .style(Modern::system_button()),
+                            button("Apply")
+                                .on_press_maybe(can_apply.then(|| Message::FindReplaceNames {
+                                    find: find_replace.find.clone(),
+                                    replace: find_replace.replace.clone(),
+                                    preview: false,
+                                }))
+                                .style(Modern::warning_button()),
+                            iced::widget::horizontal_space(),
+                            button("Close").on_press(Message::CloseFindReplace).style(Modern::system_button()),
+                        ].spacing(10),
+                    ].width(420).spacing(8).padding(10)
+                ).style(Modern::accent_container())
+            ).padding(200)
+        } else {
+            container(column![])
+        };
 
-                        self.screen = Screen::Settings(self.settings.clone());
-                        Task::none()
-                    }
-                    settings::Operation::RequestItemsList(path) => {
-                        println!("Direct handling - bypassing task system");
-    
-                        self.update(
-                            Message::Settings(
-                                settings::Message::ProcessItems(
-                                    ( self.items.clone(), path )))
-                            )
-                    }
-                    settings::Operation::UpdateExportMessage(msg) => {
-                        println!("Updating Export Message to: {}", &msg);
-                        self.settings.export_message = msg;
-                        self.screen = Screen::Settings(self.settings.clone());
-                        Task::none()
-                    }
-                    settings::Operation::UpdateExportSuccess(success) => {
-                        println!("Updating Export Success value: {:?}", &success);
-                        self.settings.export_success = success;
-                        self.screen = Screen::Settings(self.settings.clone());
-                        Task::none()
-                    }
-                }
-            }
-            Operation::Items(item_id, op) => {
-                match op {
-                    items::Operation::Save(mut item) => {
-                        println!("Saving Item ID: {}, with prices: {:?}", item.id, item.item_prices);
-                        println!("EditState information: {:?}", self.item_edit_state.prices);
+        let name_case_popup = if let Some(name_case) = &self.name_case {
+            let changes = name_case.preview(&self.items);
+
+            let table = scrollable(
+                column(
+                    changes.iter().map(|change| {
+                        row![
+                            text(&change.old_name).size(text_style.caption()).width(Length::FillPortion(1)),
+                            text(&change.new_name).size(text_style.caption()).width(Length::FillPortion(1)),
+                        ]
+                        .spacing(10)
+                        .into()
+                    })
+                )
+                .spacing(2)
+            )
+            .height(250);
+
+            let actions = if name_case.confirming {
+                row![
+                    text(format!("Rename {} items?", changes.len())).style(Modern::red_text()),
+                    iced::widget::horizontal_space(),
+                    button("Confirm").on_press(Message::ApplyNameCase).style(Modern::warning_button()),
+                    button("Back").on_press(Message::SelectNameCase(name_case.case)).style(Modern::system_button()),
+                ]
+            } else {
+                row![
+                    button("Apply")
+                        .on_press_maybe((!changes.is_empty()).then_some(Message::ReviewNameCase))
+                        .style(Modern::warning_button()),
+                    iced::widget::horizontal_space(),
+                    button("Close").on_press(Message::CloseNameCase).style(Modern::system_button()),
+                ]
+            };
+
+            container(
+                container(
+                    column![
+                        text("Normalize Item Name Casing").style(Modern::primary_text()).size(text_style.heading()),
+                        row![
+                            iced::widget::pick_list(
+                                items::name_case::NameCase::ALL,
+                                Some(name_case.case),
+                                Message::SelectNameCase,
+                            ).style(Modern::pick_list()),
+                            text(format!(
+                                "{} of {} listed items will change",
+                                changes.len(),
+                                name_case.item_ids.len()
+                            )).size(text_style.caption()).style(Modern::secondary_text()),
+                        ].spacing(10).align_y(iced::Alignment::Center),
+                        row![
+                            text("Before").size(text_style.body()).width(Length::FillPortion(1)),
+                            text("After").size(text_style.body()).width(Length::FillPortion(1)),
+                        ].spacing(10),
+                        table,
+                        actions.spacing(10).align_y(iced::Alignment::Center),
+                    ].width(480).spacing(8).padding(10)
+                ).style(Modern::accent_container())
+            ).padding(150)
+        } else {
+            container(column![])
+        };
+
+        let button_fill_popup = if let Some(fills) = &self.button_fill {
+            let table = scrollable(
+                column(
+                    fills.iter().map(|fill| {
+                        row![
+                            text(fill.item_id.to_string()).size(text_style.caption()).width(60),
+                            text(&fill.name).size(text_style.caption()).width(Length::FillPortion(2)),
+                            text(&fill.button1).size(text_style.caption()).width(Length::FillPortion(1)),
+                        ]
+                        .spacing(10)
+                        .into()
+                    })
+                )
+                .spacing(2)
+            )
+            .height(250);
+
+            container(
+                container(
+                    column![
+                        text("Fill Empty Button 1 Text").style(Modern::primary_text()).size(text_style.heading()),
+                        text(format!(
+                            "{} items have no Button 1 text. Each gets its name, abbreviated and cut to {} characters.",
+                            fills.len(),
+                            items::abbreviate::BUTTON_TEXT_MAX
+                        )).size(text_style.caption()).style(Modern::secondary_text()),
+                        row![
+                            text("ID").size(text_style.body()).width(60),
+                            text("Name").size(text_style.body()).width(Length::FillPortion(2)),
+                            text("Button 1").size(text_style.body()).width(Length::FillPortion(1)),
+                        ].spacing(10),
+                        table,
+                        row![
+                            button("Apply")
+                                .on_press_maybe((!fills.is_empty()).then_some(Message::ApplyButtonFill))
+                                .style(Modern::warning_button()),
+                            iced::widget::horizontal_space(),
+                            button("Close").on_press(Message::CloseButtonFill).style(Modern::system_button()),
+                        ].spacing(10).align_y(iced::Alignment::Center),
+                    ].width(520).spacing(8).padding(10)
+                ).style(Modern::accent_container())
+            ).padding(150)
+        } else {
+            container(column![])
+        };
+
+        let template_popup = if let Some(picker) = &self.template_picker {
+            let replacing = !self.is_empty_database();
+
+            let choices = column(
+                templates::StarterTemplate::ALL.iter().map(|template| {
+                    let selected = picker.selected == Some(*template);
+                    row![
+                        button(text(template.to_string()))
+                            .on_press(Message::SelectTemplate(*template))
+                            .style(if selected { Modern::primary_button() } else { Modern::system_button() })
+                            .width(140),
+                        text(template.description()).size(text_style.caption()).style(Modern::secondary_text()),
+                    ]
+                    .spacing(10)
+                    .align_y(iced::Alignment::Center)
+                    .into()
+                })
+            )
+            .spacing(8);
+
+            container(
+                container(
+                    column![
+                        text("Starter Templates").style(Modern::primary_text()).size(text_style.heading()),
+                        text(if picker.first_run {
+                            "Start from a template with common tax groups, price levels and categories, or start empty."
+                        } else {
+                            "Load common tax groups, price levels and categories for a new database."
+                        }).size(text_style.caption()).style(Modern::secondary_text()),
+                        choices,
+                        if replacing {
+                            Element::from(
+                                text(format!(
+                                    "Loading a template replaces the current {} items and {} entities. A snapshot is saved first and the load can be undone.",
+                                    self.items.len(),
+                                    self.reference_entity_count()
+                                )).size(text_style.caption()).style(Modern::red_text())
+                            )
+                        } else {
+                            Element::from(column![])
+                        },
+                        row![
+                            button(if replacing { "Replace and Load" } else { "Load Template" })
+                                .on_press_maybe(picker.selected.map(|_| Message::ApplyTemplate))
+                                .style(if replacing { Modern::warning_button() } else { Modern::primary_button() }),
+                            iced::widget::horizontal_space(),
+                            button(if picker.first_run { "Start Empty" } else { "Cancel" })
+                                .on_press(Message::CloseTemplates)
+                                .style(Modern::system_button()),
+                        ].spacing(10).align_y(iced::Alignment::Center),
+                    ].width(560).spacing(10).padding(10)
+                ).style(Modern::accent_container())
+            ).padding(150)
+        } else {
+            container(column![])
+        };
+
+        let reroute_popup = match &self.printer_reroute {
+            Some(reroute) => container(
+                printer_logicals::reroute_view(reroute, &self.printer_logicals, &self.items, text_style)
+                    .map(move |msg| Message::PrinterLogicals(-1, msg))
+            ).padding(200),
+            None => container(column![]),
+        };
+
+        let import_message_popup = container(
+            container(
+                column![
+                    vertical_space().height(10),
+                    row![
+                        iced::widget::horizontal_space().width(6),
+                        text("Notice").style(Modern::primary_text()).size(text_style.heading()),
+                        iced::widget::horizontal_space().width(6),
+                    ],
+                    iced::widget::vertical_space().height(10),
+                    row![
+                        iced::widget::horizontal_space().width(6),
+                        text(self.import_message.clone().unwrap_or_default()).style(Modern::secondary_text()).size(text_style.body()),
+                        iced::widget::horizontal_space().width(6),
+                    ],
+                    if self.show_import_warnings {
+                        Element::from(
+                            container(
+                                scrollable(
+                                    column(
+                                        self.import_warnings.iter().map(|warning| {
+                                            text(warning.to_string()).size(text_style.caption()).into()
+                                        })
+                                    )
+                                    .spacing(4)
+                                    .padding(6)
+                                )
+                                .height(200)
+                            )
+                            .padding([10, 6])
+                        )
+                    } else {
+                        Element::from(iced::widget::horizontal_space().width(0))
+                    },
+                    iced::widget::vertical_space().height(15),
+                    row![
+                        iced::widget::horizontal_space(),
+                        if !self.import_warnings.is_empty() {
+                            Element::from(
+                                button(if self.show_import_warnings { "Hide Warnings" } else { "Show Warnings" })
+                                    .on_press(Message::ToggleImportWarnings)
+                                    .style(Modern::gray_button())
+                            )
+                        } else {
+                            Element::from(iced::widget::horizontal_space().width(0))
+                        },
+                        iced::widget::horizontal_space().width(6),
+                        if self.import_undo.is_some() {
+                            Element::from(button("Undo").on_press(Message::UndoImport).style(Modern::warning_button()))
+                        } else {
+                            Element::from(iced::widget::horizontal_space().width(0))
+                        },
+                        iced::widget::horizontal_space().width(6),
+                        button("OK").on_press(Message::DismissImportMessage).style(Modern::primary_button()),
+                        iced::widget::horizontal_space().width(6),
+                    ],
+                    vertical_space().height(10),
+                ].width(335)
+            ).style(Modern::accent_container())
+        ).padding(250);
+
+        let draft_recovery_popup = container(
+            container(
+                column![
+                    vertical_space().height(10),
+                    row![
+                        iced::widget::horizontal_space().width(6),
+                        text("Recover Item Edit").style(Modern::primary_text()).size(text_style.heading()),
+                        iced::widget::horizontal_space().width(6),
+                    ],
+                    iced::widget::vertical_space().height(10),
+                    row![
+                        iced::widget::horizontal_space().width(6),
+                        text(self.pending_draft_recovery.as_ref().map_or(String::new(), |draft| format!(
+                            "Recover unsaved edit of \"{}\" from {}?",
+                            draft.item.name,
+                            draft.saved_at
+                        ))).style(Modern::secondary_text()).size(text_style.body()),
+                        iced::widget::horizontal_space().width(6),
+                    ],
+                    iced::widget::vertical_space().height(15),
+                    row![
+                        iced::widget::horizontal_space().width(6),
+                        button("Recover").on_press(Message::RecoverDraft).style(Modern::primary_button()),
+                        iced::widget::horizontal_space(),
+                        button("Discard").on_press(Message::DiscardDraftRecovery).style(Modern::danger_button()),
+                        iced::widget::horizontal_space().width(6),
+                    ],
+                    vertical_space().height(10),
+                ].width(335)
+            ).style(Modern::accent_container())
+        ).padding(250);
+
+        let confirm_quit_popup = container(
+            container(
+                column![
+                    vertical_space().height(10),
+                    row![
+                        iced::widget::horizontal_space().width(6),
+                        text("Quit with Unsaved Changes?").style(Modern::primary_text()).size(text_style.heading()),
+                        iced::widget::horizontal_space().width(6),
+                    ],
+                    iced::widget::vertical_space().height(10),
+                    row![
+                        iced::widget::horizontal_space().width(6),
+                        text(if self.confirm_quit.is_some() {
+                            self.unsaved_changes().join("\n")
+                        } else {
+                            String::new()
+                        })
+                        .style(Modern::secondary_text())
+                        .size(text_style.body()),
+                        iced::widget::horizontal_space().width(6),
+                    ],
+                    iced::widget::vertical_space().height(5),
+                    row![
+                        iced::widget::horizontal_space().width(6),
+                        text("Save keeps the item being edited. Rows being edited on other screens are not saved.")
+                            .style(Modern::secondary_text())
+                            .size(text_style.caption()),
+                        iced::widget::horizontal_space().width(6),
+                    ],
+                    iced::widget::vertical_space().height(15),
+                    row![
+                        iced::widget::horizontal_space().width(6),
+                        button("Save").on_press(Message::SaveAndQuit).style(Modern::primary_button()),
+                        button("Discard").on_press(Message::DiscardAndQuit).style(Modern::danger_button()),
+                        iced::widget::horizontal_space(),
+                        button("Cancel").on_press(Message::CancelQuit).style(Modern::secondary_button()),
+                        iced::widget::horizontal_space().width(6),
+                    ].spacing(10),
+                    vertical_space().height(10),
+                ].width(400)
+            ).style(Modern::accent_container())
+        ).padding(250);
+
+        let leave_edit_popup = container(
+            container(
+                column![
+                    vertical_space().height(10),
+                    row![
+                        iced::widget::horizontal_space().width(6),
+                        text("Unsaved Changes").style(Modern::primary_text()).size(text_style.heading()),
+                        iced::widget::horizontal_space().width(6),
+                    ],
+                    iced::widget::vertical_space().height(10),
+                    row![
+                        iced::widget::horizontal_space().width(6),
+                        text(format!("Save your changes to \"{}\" before leaving?", self.draft_item.name))
+                            .style(Modern::secondary_text())
+                            .size(text_style.body()),
+                        iced::widget::horizontal_space().width(6),
+                    ],
+                    iced::widget::vertical_space().height(15),
+                    row![
+                        iced::widget::horizontal_space().width(6),
+                        button("Keep Editing").on_press(Message::KeepEditingItem).style(Modern::secondary_button()),
+                        iced::widget::horizontal_space(),
+                        button("Discard").on_press(Message::DiscardItemEdit).style(Modern::danger_button()),
+                        iced::widget::horizontal_space().width(10),
+                        button("Save").on_press(Message::SaveItemEdit).style(Modern::primary_button()),
+                        iced::widget::horizontal_space().width(6),
+                    ],
+                    vertical_space().height(10),
+                ].width(400)
+            ).style(Modern::accent_container())
+        ).padding(250);
+
+        //iced::widget::stack
+        let app_view = row![
+            sidebar,
+            container(content)
+                .width(Length::Fill)
+                .padding(20),
+        ];
+        
+        let window_view = match self.windows.get(&window_id) {
+            Some(window) => match &window.kind {
+                WindowKind::Main => {
+                    println!("Launched Main Window!");
+                    if let Some(problem) = &self.startup_error { // No usable data directory yet
+                        startup_error_view(problem, text_style)
+                    } else if self.show_modal { //Show Deletion confirmation popup
+                        stack![
+                            app_view,
+                            opaque(delete_confirmation_popup)
+                        ].into()
+                    } else if self.printer_reroute.is_some() { // Move items off a printer
+                        stack![
+                            app_view,
+                            opaque(reroute_popup)
+                        ].into()
+                    } else if self.name_case.is_some() { // Normalize item name casing
+                        stack![
+                            app_view,
+                            opaque(name_case_popup)
+                        ].into()
+                    } else if self.button_fill.is_some() { // Fill in missing button text
+                        stack![
+                            app_view,
+                            opaque(button_fill_popup)
+                        ].into()
+                    } else if self.template_picker.is_some() { // Start from a starter template
+                        stack![
+                            app_view,
+                            opaque(template_popup)
+                        ].into()
+                    } else if self.export_preview.is_some() { // Check an export before it is written
+                        stack![
+                            app_view,
+                            opaque(export_preview_popup)
+                        ].into()
+                    } else if self.find_replace.is_some() { // Find and replace in item names
+                        stack![
+                            app_view,
+                            opaque(find_replace_popup)
+                        ].into()
+                    } else if self.overwrite_review.is_some() { // Compare before wiping the database
+                        stack![
+                            app_view,
+                            opaque(overwrite_review_popup)
+                        ].into()
+                    } else if self.json_merge_review.is_some() { // What adding a JSON export will do
+                        stack![
+                            app_view,
+                            opaque(json_merge_popup)
+                        ].into()
+                    } else if self.show_item_import_confirmation { // Show Item Import Confirmation popup
+                        stack![
+                            app_view,
+                            opaque(import_items_confirmation)
+                        ].into()
+                    } else if self.import_message.is_some() { // Show import result / error popup
+                        stack![
+                            app_view,
+                            opaque(import_message_popup)
+                        ].into()
+                    } else if self.confirm_quit.is_some() { // Closing with unsaved changes
+                        stack![
+                            app_view,
+                            opaque(confirm_quit_popup)
+                        ].into()
+                    } else if self.confirm_leave_edit { // Leaving an item edit with changes
+                        stack![
+                            app_view,
+                            opaque(leave_edit_popup)
+                        ].into()
+                    } else if self.pending_draft_recovery.is_some() { // Offer to recover a crashed item edit
+                        stack![
+                            app_view,
+                            opaque(draft_recovery_popup)
+                        ].into()
+                    }
+                    else {
+                        app_view.into()
+                    }
+                }
+                WindowKind::SuperEdit(editor) => {
+                    editor.view(
+                        &self.items,
+                        &self.price_levels
+                    ).map(move |msg| Message::SuperEdit(window_id, msg))
+                }
+            }
+            None => { 
+                let content = column![
+                    text(format!("Something has gone terribly wrong. Window Id: {:?}", window_id)),
+                ];
+                container(
+                    content
+                ).into() 
+            }
+        };
+
+        window_view
+
+     }
+
+
+    fn perform(&mut self, operation: Operation) -> Task<Message> {
+        match operation {
+            Operation::Settings(op) => {
+                match op {
+                    settings::Operation::Save(new_settings) => {
+                        self.settings = new_settings;
+
+                        self.error_message = None;
+                        self.save_state();
+
+                        self.screen = Screen::Settings(self.settings.clone());
+                        Task::none()
+                    }
+                    settings::Operation::Back => {
+                        self.screen = Screen::Items(items::Mode::View);
+                        self.error_message = None;
+                        Task::none()
+                    }
+                    settings::Operation::ShowError(tab, error) => {
+                        self.settings_nav.error = Some((tab, error.clone()));
+                        self.error_message = Some(error);
+                        self.screen = Screen::Settings(self.settings.clone());
+                        Task::none()
+                    }
+                    settings::Operation::SelectSettingsTab(tab) => {
+                        // Picking a tab from the search results shows the whole tab
+                        self.settings_nav.tab = tab;
+                        self.settings_nav.search.clear();
+                        Task::none()
+                    }
+                    settings::Operation::UpdateSettingsSearch(query) => {
+                        self.settings_nav.search = query;
+                        Task::none()
+                    }
+                    settings::Operation::ThemeChanged(theme) => {
+
+                        self.theme = match theme {
+                            settings::ThemeChoice::Light => Modern::light_theme(),
+                            settings::ThemeChoice::Dark => Modern::dark_theme(),
+                        };
+
+                        self.screen = Screen::Settings(self.settings.clone());
+                        Task::none()
+                    }
+                    settings::Operation::TextStyleChanged => {
+                        // Persist right away so the choice survives a restart without pressing Save
+                        self.save_state();
+
+                        // Open SuperEdit windows keep their own copy, like the page size
+                        let text_style = TextStyle::from_settings(&self.settings);
+                        for window in self.windows.values_mut() {
+                            if let WindowKind::SuperEdit(editor) = &mut window.kind {
+                                editor.set_text_style(text_style);
+                            }
+                        }
+
+                        self.screen = Screen::Settings(self.settings.clone());
+                        Task::none()
+                    }
+                    settings::Operation::RequestItemsList(path) => {
+                        println!("Direct handling - bypassing task system");
+    
+                        self.update(
+                            Message::Settings(
+                                settings::Message::ProcessItems(
+                                    ( self.items_for_export(), path )))
+                            )
+                    }
+                    settings::Operation::RequestDeltaExport(path, since) => {
+                        let export_items = self.items_for_export();
+                        let changed: Vec<Item> = items::delta_export::modified_since(&export_items, since)
+                            .into_iter()
+                            .cloned()
+                            .collect();
+                        let deleted: Vec<items::delta_export::ItemTombstone> = items::delta_export::deleted_since(&self.deleted_items, since)
+                            .into_iter()
+                            .cloned()
+                            .collect();
+                        println!("Delta export since {}: {} changed, {} deleted", since, changed.len(), deleted.len());
+
+                        self.update(
+                            Message::Settings(
+                                settings::Message::ProcessDeltaExport(( changed, deleted, path )))
+                            )
+                    }
+                    settings::Operation::RequestJsonExport(path) => {
+                        let export = json_interchange::ItemsExport::new(
+                            &self.items,
+                            &self.item_groups,
+                            &self.price_levels,
+                            &self.product_classes,
+                            &self.tax_groups,
+                            &self.security_levels,
+                            &self.revenue_categories,
+                            &self.report_categories,
+                            &self.choice_groups,
+                            &self.printer_logicals,
+                        );
+
+                        self.update(
+                            Message::Settings(
+                                settings::Message::ProcessJsonExport(( export, path )))
+                            )
+                    }
+                    settings::Operation::RequestPrintableMenu(path) => {
+                        let html = items::printable_menu::menu_html(
+                            &self.items,
+                            &self.item_groups,
+                            &self.report_categories,
+                            self.settings.menu_grouping,
+                            self.settings.menu_include_inactive,
+                        );
+
+                        self.update(
+                            Message::Settings(
+                                settings::Message::ProcessPrintableMenu(( html, path )))
+                            )
+                    }
+                    settings::Operation::PreviewExport(preview) => {
+                        println!("Previewing export to {:?}: {} bytes", preview.target(), preview.byte_count());
+                        self.export_preview = Some(preview);
+                        Task::none()
+                    }
+                    settings::Operation::RequestPricePivot(path) => {
+                        let csv = items::price_pivot::price_pivot_csv(
+                            &self.items,
+                            &self.price_levels,
+                            &self.settings.pivot_excluded_levels,
+                        );
+
+                        self.update(
+                            Message::Settings(
+                                settings::Message::ProcessPricePivot(( csv, path )))
+                            )
+                    }
+                    settings::Operation::ApplyPatch(patch) => {
+                        let snapshot_note = self.snapshot_before("patch");
+                        let undo = self.app_state();
+
+                        match patch.apply(&mut self.items, &timestamp_now()) {
+                            Ok(changed) => {
+                                self.import_undo = Some(undo);
+                                self.save_state();
+                                self.rebuild_search_index();
+                                self.run_data_checks();
+                                self.import_message = Some(with_note(
+                                    format!(
+                                        "Applied {} changes to {} items, {} items were updated.",
+                                        patch.changes.len(),
+                                        patch.item_count(),
+                                        changed
+                                    ),
+                                    snapshot_note,
+                                ));
+                            }
+                            Err(conflicts) => {
+                                println!("Patch rejected with {} conflicts", conflicts.len());
+                                self.import_message = Some(items::item_patch::conflict_summary(&conflicts));
+                            }
+                        }
+                        Task::none()
+                    }
+                    settings::Operation::RestoreBackup(path) => {
+                        let state = match persistence::load_repaired(&path.to_string_lossy()) {
+                            Ok((state, _)) => state,
+                            Err(e) => {
+                                println!("Restore failed: {}", e);
+                                self.import_message = Some(format!("Could not restore {}: {}", path.display(), e));
+                                return Task::none();
+                            }
+                        };
+                        // The data being replaced gets its own snapshot, and the notice can undo the restore
+                        let snapshot_note = Some(self.write_snapshot("restore"));
+                        self.import_undo = Some(self.app_state());
+
+                        println!("Restoring {} items from {:?}", state.items.len(), path);
+                        self.restore_app_state(state);
+                        self.save_state();
+                        self.run_data_checks();
+                        self.import_message = Some(with_note(
+                            format!("Restored {} items from {}", self.items.len(), path.display()),
+                            snapshot_note,
+                        ));
+                        Task::none()
+                    }
+                    settings::Operation::ImportEntities(import) => {
+                        let snapshot_note = self.snapshot_before("entity-import");
+                        self.import_undo = Some(self.app_state());
+
+                        let report = match import.rows {
+                            EntityRows::TaxGroups(rows) => import_entities::merge_entities(rows, &mut self.tax_groups),
+                            EntityRows::PriceLevels(rows) => import_entities::merge_entities(rows, &mut self.price_levels),
+                            EntityRows::ReportCategories(rows) => import_entities::merge_entities(rows, &mut self.report_categories),
+                            EntityRows::RevenueCategories(rows) => import_entities::merge_entities(rows, &mut self.revenue_categories),
+                            EntityRows::PrinterLogicals(rows) => import_entities::merge_entities(rows, &mut self.printer_logicals),
+                        };
+
+                        self.save_state();
+                        self.rebuild_search_index();
+                        self.run_data_checks();
+
+                        let mut summary = report.summary(import.kind);
+                        if !import.warnings.is_empty() {
+                            summary.push_str(&format!("\n{} rows were skipped or replaced, see warnings.", import.warnings.len()));
+                        }
+                        self.import_warnings = import.warnings;
+                        self.import_message = Some(with_note(summary, snapshot_note));
+                        Task::none()
+                    }
+                    settings::Operation::RequestGroupExport(folder) => {
+                        let export_items = self.items_for_export();
+                        let mut groups: Vec<(String, Vec<Item>)> = self.item_groups
+                            .values()
+                            .map(|group| {
+                                let group_items = export_items
+                                    .values()
+                                    .filter(|item| item.item_group == Some(group.id))
+                                    .cloned()
+                                    .collect();
+                                (format!("{}_{}", group.id, group.name), group_items)
+                            })
+                            .collect();
+
+                        // Items without a group, or pointing at a group that no longer exists
+                        let ungrouped: Vec<Item> = export_items
+                            .values()
+                            .filter(|item| item.item_group.map_or(true, |id| !self.item_groups.contains_key(&id)))
+                            .cloned()
+                            .collect();
+                        groups.push(("ungrouped".to_string(), ungrouped));
+
+                        self.update(
+                            Message::Settings(
+                                settings::Message::ProcessGroupExport(( groups, folder )))
+                            )
+                    }
+                    settings::Operation::PreviewIdFix => {
+                        self.data_checks.id_fix_preview = self.plan_out_of_range_id_fix();
+                        if self.data_checks.id_fix_preview.is_empty() {
+                            self.data_checks.report = vec!["All item IDs are inside their item group's range.".to_string()];
+                        }
+                        self.screen = Screen::Settings(self.settings.clone());
+                        Task::none()
+                    }
+                    settings::Operation::ApplyIdFix => {
+                        // Apply what the preview showed. If items changed since then a
+                        // target could be taken, so show a new preview instead.
+                        let moves: Vec<(EntityId, EntityId)> = self.data_checks.id_fix_preview
+                            .iter()
+                            .filter_map(|(old_id, new_id)| new_id.map(|new_id| (*old_id, new_id)))
+                            .collect();
+                        let mut targets = BTreeSet::new();
+                        let still_valid = moves.iter().all(|(old_id, new_id)| {
+                            self.items.contains_key(old_id) && !self.items.contains_key(new_id) && targets.insert(*new_id)
+                        });
+                        if !still_valid {
+                            println!("Item IDs changed since the ID fix preview, previewing again");
+                            self.data_checks.id_fix_preview = self.plan_out_of_range_id_fix();
+                            self.data_checks.report = vec!["Item IDs changed since the preview. Check the new preview and apply again.".to_string()];
+                            self.screen = Screen::Settings(self.settings.clone());
+                            return Task::none();
+                        }
+
+                        self.import_message = self.snapshot_before("id-fix");
+                        for (old_id, new_id) in moves {
+                            if let Some(mut item) = self.items.remove(&old_id) {
+                                println!("Moving item {} to {}", old_id, new_id);
+                                item.id = new_id;
+                                self.items.insert(new_id, item);
+
+                                if self.selected_item_id == Some(old_id) {
+                                    self.selected_item_id = Some(new_id);
+                                }
+                            }
+                        }
+
+                        self.save_state();
+
+                        self.data_checks.id_fix_preview.clear();
+                        self.rebuild_search_index();
+                        self.run_data_checks();
+                        self.screen = Screen::Settings(self.settings.clone());
+                        Task::none()
+                    }
+                    settings::Operation::CancelIdFix => {
+                        self.data_checks.id_fix_preview.clear();
+                        self.screen = Screen::Settings(self.settings.clone());
+                        Task::none()
+                    }
+                    settings::Operation::ShowUnusedEntities => {
+                        self.data_checks.unused_entities = self.unused_entities();
+                        self.data_checks.pending_unused_delete = None;
+                        self.screen = Screen::Settings(self.settings.clone());
+                        Task::none()
+                    }
+                    settings::Operation::DeleteUnused(entity_type) => {
+                        self.data_checks.pending_unused_delete = Some(entity_type);
+                        self.screen = Screen::Settings(self.settings.clone());
+                        Task::none()
+                    }
+                    settings::Operation::ConfirmDeleteUnused => {
+                        if let Some(entity_type) = self.data_checks.pending_unused_delete.take() {
+                            // Recompute so anything that became used since the report isn't deleted
+                            let unused_ids: Vec<EntityId> = self.unused_entities()
+                                .into_iter()
+                                .find(|unused| unused.entity_type == entity_type)
+                                .map(|unused| unused.entities.into_iter().map(|(id, _)| id).collect())
+                                .unwrap_or_default();
+
+                            println!("Deleting {} unused {}", unused_ids.len(), entity_type);
+
+                            for id in unused_ids {
+                                match entity_type.as_str() {
+                                    "ItemGroup" => { self.item_groups.remove(&id); }
+                                    "PriceLevel" => {
+                                        self.price_levels.remove(&id);
+                                        item_groups::remove_default_price_level(&mut self.item_groups, id);
+                                    }
+                                    "ProductClass" => { self.product_classes.remove(&id); }
+                                    "TaxGroup" => { self.tax_groups.remove(&id); }
+                                    "SecurityLevel" => { self.security_levels.remove(&id); }
+                                    "RevenueCategory" => { self.revenue_categories.remove(&id); }
+                                    "ReportCategory" => { self.report_categories.remove(&id); }
+                                    "ChoiceGroup" => { self.choice_groups.remove(&id); }
+                                    "PrinterLogical" => { self.printer_logicals.remove(&id); }
+                                    _ => {}
+                                }
+                            }
+
+                            self.save_state();
+                        }
+
+                        self.data_checks.unused_entities = self.unused_entities();
+                        self.screen = Screen::Settings(self.settings.clone());
+                        Task::none()
+                    }
+                    settings::Operation::OpenTemplates => {
+                        self.template_picker = Some(templates::TemplatePicker { selected: None, first_run: false });
+                        Task::none()
+                    }
+                    settings::Operation::OpenButtonFill => {
+                        self.button_fill = Some(items::abbreviate::fill_empty_button1(
+                            &self.items,
+                            &self.settings.abbreviations,
+                        ));
+                        Task::none()
+                    }
+                    settings::Operation::CancelDeleteUnused => {
+                        self.data_checks.pending_unused_delete = None;
+                        self.screen = Screen::Settings(self.settings.clone());
+                        Task::none()
+                    }
+                    settings::Operation::ValidateAll => {
+                        self.run_data_checks();
+                        self.screen = Screen::Settings(self.settings.clone());
+                        Task::none()
+                    }
+                    settings::Operation::OpenItem(id) => {
+                        self.selected_item_id = Some(id);
+                        self.item_quick_edit = None;
+                        self.screen = Screen::Items(items::Mode::View);
+                        Task::none()
+                    }
+                    settings::Operation::SyncPriceLevels => {
+                        let synced = self.items
+                            .values_mut()
+                            .map(|item| item.sync_price_levels())
+                            .filter(|changed| *changed)
+                            .count();
+                        println!("Synced price levels on {} items", synced);
+
+                        if synced > 0 {
+                            self.save_state();
+                        }
+
+                        self.run_data_checks();
+                        self.screen = Screen::Settings(self.settings.clone());
+                        Task::none()
+                    }
+                    settings::Operation::FixPrimaryPrinters => {
+                        let fixed = self.items
+                            .values_mut()
+                            .map(|item| item.fix_primary_printer(&self.printer_logicals))
+                            .filter(|changed| *changed)
+                            .count();
+                        println!("Set the primary printer on {} items", fixed);
+
+                        if fixed > 0 {
+                            self.save_state();
+                        }
+
+                        self.run_data_checks();
+                        self.screen = Screen::Settings(self.settings.clone());
+                        Task::none()
+                    }
+                    settings::Operation::PrepareDataDirectory(target) => {
+                        if target == self.file_manager.data_dir() {
+                            self.data_dir_state.status = "That folder is already the data directory".to_string();
+                            return Task::none();
+                        }
+
+                        match persistence::validate_data_dir(&target) {
+                            Ok(()) => {
+                                let save_file = std::path::Path::new(&self.settings.file_path);
+                                let target_has_data = save_file.file_name().is_some_and(|name| target.join(name).exists());
+
+                                self.data_dir_state.change = Some(settings::DataDirChange {
+                                    files: self.file_manager.data_files(save_file),
+                                    target,
+                                    target_has_data,
+                                    transfer: None,
+                                    copied: Vec::new(),
+                                    error: None,
+                                });
+                                self.data_dir_state.status.clear();
+                            }
+                            Err(e) => self.data_dir_state.status = e,
+                        }
+                        Task::none()
+                    }
+                    settings::Operation::TransferDataDirectory(transfer) => {
+                        let Some(mut change) = self.data_dir_state.change.take() else {
+                            return Task::none();
+                        };
+                        change.error = None;
+                        change.copied.clear();
+
+                        if transfer == settings::DataDirTransfer::SwitchOnly {
+                            self.finish_data_dir_change(change, transfer);
+                            return Task::none();
+                        }
+
+                        // Copy from an up to date save file
+                        if let Err(e) = self.save_state_now() {
+                            change.error = Some(e);
+                            self.data_dir_state.change = Some(change);
+                            return Task::none();
+                        }
+                        change.files = self.file_manager.data_files(std::path::Path::new(&self.settings.file_path));
+                        change.transfer = Some(transfer);
+
+                        self.data_dir_state.change = Some(change);
+                        self.continue_data_dir_change()
+                    }
+                    settings::Operation::DataFileCopied(Ok(path)) => {
+                        if let Some(change) = self.data_dir_state.change.as_mut() {
+                            change.copied.push(path);
+                        }
+                        self.continue_data_dir_change()
+                    }
+                    settings::Operation::DataFileCopied(Err(e)) => {
+                        println!("Data directory copy failed: {}", e);
+
+                        // Take back the partial copy so the new folder isn't left half filled
+                        if let Some(change) = self.data_dir_state.change.as_mut() {
+                            let leftovers: Vec<String> = change.copied
+                                .drain(..)
+                                .filter(|path| std::fs::remove_file(path).is_err())
+                                .map(|path| path.display().to_string())
+                                .collect();
+
+                            change.transfer = None;
+                            change.error = Some(if leftovers.is_empty() {
+                                format!("{}. Nothing was switched, the files copied so far were removed again.", e)
+                            } else {
+                                format!("{}. Nothing was switched, but these copies could not be removed: {}", e, leftovers.join(", "))
+                            });
+                        }
+                        Task::none()
+                    }
+                    settings::Operation::CancelDataDirectoryChange => {
+                        self.data_dir_state.change = None;
+                        Task::none()
+                    }
+                    settings::Operation::UpdateExportMessage(msg) => {
+                        println!("Updating Export Message to: {}", &msg);
+                        self.settings.export_message = msg;
+                        self.screen = Screen::Settings(self.settings.clone());
+                        Task::none()
+                    }
+                    settings::Operation::UpdateExportSuccess(success) => {
+                        println!("Updating Export Success value: {:?}", &success);
+                        self.settings.export_success = success;
+                        self.screen = Screen::Settings(self.settings.clone());
+                        Task::none()
+                    }
+                }
+            }
+            Operation::Items(item_id, op) => {
+                // Moving to another item mid-edit asks first when the draft has changes,
+                // otherwise the edit is closed properly before switching
+                let leaves_edit = matches!(
+                    op,
+                    items::Operation::Select(_) | items::Operation::CreateNew(_) | items::Operation::CopyItem(_)
+                );
+                if leaves_edit && matches!(self.screen, Screen::Items(items::Mode::Edit)) {
+                    if self.has_unsaved_item_edit() {
+                        self.pending_item_navigation = Some((item_id, op));
+                        self.confirm_leave_edit = true;
+                        return Task::none();
+                    }
+                    let cancel_task = self.perform(Operation::Items(self.draft_item.id, items::Operation::Cancel));
+                    return Task::batch([cancel_task, self.perform(Operation::Items(item_id, op))]);
+                }
+
+                match op {
+                    items::Operation::Save(mut item) => {
+                        println!("Saving Item ID: {}, with prices: {:?}", item.id, item.item_prices);
+
+                        let store_problems = items::store_fields::store_field_problems(&item, self.settings.max_store_id);
+                        if let Some(problem) = store_problems.first() {
+                            println!("Not saving item {}: {}", item.id, problem);
+                            self.item_edit_state.validation_error = Some(problem.clone());
+                            self.item_edit_state.error_section = Some(items::edit::EditSection::Store);
+                            return items::edit::scroll_to_section(items::edit::EditSection::Store);
+                        }
+
+                        if self.settings.block_duplicate_button_text {
+                            if let Some(warning) = items::button_collisions::collision_warning(&self.items, &item) {
+                                println!("Not saving item {}: {}", item.id, warning);
+                                self.item_edit_state.validation_error = Some(warning);
+                                self.item_edit_state.error_section = Some(items::edit::EditSection::Basic);
+                                return items::edit::scroll_to_section(items::edit::EditSection::Basic);
+                            }
+                        }
+                        println!("EditState information: {:?}", self.item_edit_state.prices);
 
                         let edit_state_prices = self.item_edit_state.prices.clone();
                         //Copy prices from edit_state,to item
@@ -1373,377 +3382,1264 @@ impl MenuBuilder {
                                 price_level_id: price.0,
                                 price: price.1.parse::<Decimal>().unwrap_or(Decimal::new(0, 2))
                             }
-                        ).collect::<Vec<_>>();
+                        ).collect::<Vec<_>>();
+
+                        item.item_prices = Some(item_prices);
+
+                        // The same checks a quick edit runs, against the context the item views use
+                        let stored_id = self.items.get(&item_id).map(|stored| stored.id);
+                        if let Err(e) = item.validate(&self.view_context(), stored_id) {
+                            println!("Not saving item {}: {}", item.id, e);
+                            self.item_edit_state.validation_error = Some(e.to_string());
+                            return Task::none();
+                        }
+
+                        // Drafts get their id from the range when committed
+                        if item.id != DRAFT_ID {
+                            if let Some(problem) = self.settings.item_id_range.problem(item.id) {
+                                println!("Not saving item {}: {}", item.id, problem);
+                                self.item_edit_state.validation_error = Some(problem);
+                                self.item_edit_state.error_section = Some(items::edit::EditSection::Basic);
+                                return items::edit::scroll_to_section(items::edit::EditSection::Basic);
+                            }
+                        }
+
+                        self.quick_edit_undo = None;
+                        self.commit_item(item);
+                        self.file_manager.remove_draft_recovery();
+                        self.last_draft_autosave = None;
+                        self.list_scroll.restore(items::LIST_ID, self.items.len())
+                    }
+                    items::Operation::StartQuickEdit(field) => {
+                        if let Some(item) = self.items.get(&item_id) {
+                            let value = match field {
+                                items::QuickEditField::Name => item.name.clone(),
+                                items::QuickEditField::Price(price_level_id) => item.item_prices
+                                    .iter()
+                                    .flatten()
+                                    .find(|p| p.price_level_id == price_level_id)
+                                    .map(|p| p.price.to_string())
+                                    .unwrap_or_default(),
+                            };
+
+                            self.item_quick_edit = Some(items::QuickEdit::new(item_id, field, value));
+                        }
+                        Task::none()
+                    }
+                    items::Operation::UpdateQuickEdit(value) => {
+                        if let Some(quick_edit) = self.item_quick_edit.as_mut() {
+                            quick_edit.value = value;
+                            quick_edit.error = None;
+                        }
+                        Task::none()
+                    }
+                    items::Operation::CommitQuickEdit => {
+                        let Some(quick_edit) = self.item_quick_edit.clone() else {
+                            return Task::none();
+                        };
+                        let Some(original) = self.items.get(&quick_edit.item_id).cloned() else {
+                            self.item_quick_edit = None;
+                            return Task::none();
+                        };
+                        // Nothing typed, nothing to save or undo
+                        if !quick_edit.is_dirty() {
+                            self.item_quick_edit = None;
+                            return Task::none();
+                        }
+
+                        match quick_edit.apply(&original, &self.view_context()) {
+                            Ok(item) => {
+                                println!("Quick edit saved on item {}", item.id);
+                                let id = item.id;
+                                self.item_quick_edit = None;
+                                self.commit_item(item);
+                                self.quick_edit_undo = self.items.get(&id).cloned().map(|after| (original, after));
+                            }
+                            Err(e) => {
+                                if let Some(quick_edit) = self.item_quick_edit.as_mut() {
+                                    quick_edit.error = Some(e);
+                                }
+                            }
+                        }
+                        Task::none()
+                    }
+                    items::Operation::UndoQuickEdit(id) => {
+                        // Only while the item is as the quick edit left it, so undo can't
+                        // bring back a deleted item or throw away a later change
+                        match self.quick_edit_undo.take() {
+                            Some((before, after)) if before.id == id && self.items.get(&id) == Some(&after) => {
+                                println!("Undoing quick edit on item {}", id);
+                                self.item_quick_edit = None;
+                                self.commit_item(before);
+                            }
+                            _ => println!("Quick edit on item {} can no longer be undone", id),
+                        }
+                        Task::none()
+                    }
+                    items::Operation::CancelQuickEdit => {
+                        self.item_quick_edit = None;
+                        Task::none()
+                    }
+                    items::Operation::StartEdit => {
+                        println!("Item ID: {}", item_id);
+                        // Start editing an existing Item
+                        self.draft_item_id = Some(item_id);
+                        self.draft_item = self.items[&item_id].clone();
+
+                        let item = self.items.get_mut(&item_id).expect("Item should exist");
+                        let available_choice_groups: Vec<ChoiceGroup> = self.choice_groups.values().cloned().collect();
+                        let available_printer_logicals: Vec<PrinterLogical> = self.printer_logicals.values().cloned().collect();
+                        let available_price_levels: Vec<PriceLevel> = self.price_levels.values().cloned().collect();
+
+                        // We only do this once, when the user explicitly begins editing:
+                        self.item_edit_state = items::EditState::new(
+                            item,
+                            available_choice_groups,
+                            available_printer_logicals,
+                            available_price_levels,
+                        );
+
+                        println!("Prices: {:?}", item.item_prices);
+                        self.item_edit_state.pickers = self.classification_pickers();
+                        self.last_draft_autosave = Some(draft_fingerprint(&self.draft_item, &self.item_edit_state.prices));
+                        self.item_edit_baseline = self.last_draft_autosave.clone();
+                        self.screen = Screen::Items(items::Mode::Edit);
+                        Task::none()
+                    }
+                    items::Operation::Cancel => {
+                        self.file_manager.remove_draft_recovery();
+                        self.last_draft_autosave = None;
+                        if self.draft_item_id.is_some() {
+                            self.draft_item_id = None;
+                            self.draft_item = Item::default();
+                        }
+                        self.screen = Screen::Items(items::Mode::View);
+                        Task::none()
+                    }
+                    items::Operation::ToggleEditSection(section) => {
+                        let collapsed = &mut self.settings.collapsed_edit_sections;
+                        if collapsed.contains(&section) {
+                            collapsed.retain(|s| *s != section);
+                        } else {
+                            collapsed.push(section);
+                        }
+                        self.save_state();
+                        Task::none()
+                    }
+                    items::Operation::SetAllEditSectionsCollapsed(collapse) => {
+                        self.settings.collapsed_edit_sections = if collapse {
+                            items::edit::EditSection::ALL.to_vec()
+                        } else {
+                            Vec::new()
+                        };
+                        self.save_state();
+                        Task::none()
+                    }
+                    items::Operation::Back => {
+                        self.screen = Screen::Items(items::Mode::View);
+                        Task::none()
+                    }
+                    items::Operation::ListScrolled(viewport) => {
+                        self.list_scroll.remember(items::LIST_ID, viewport, self.items.len());
+                        Task::none()
+                    }
+                    items::Operation::CreateNew(mut item) => {
+                        // Items created from a group header take the first free id in that group
+                        let free_group_id = item.item_group
+                            .and_then(|id| self.item_groups.get(&id))
+                            .and_then(|group| item_groups::plan_reassignment(&[item.id], &group.id_range, &self.items)[0].1);
+
+                        let next_id = match free_group_id {
+                            Some(id) => id,
+                            None => match self.settings.item_id_range.next_id(&self.items) {
+                                Ok(id) => id,
+                                Err(e) => {
+                                    self.import_message = Some(e);
+                                    return Task::none();
+                                }
+                            },
+                        };
+                        item.id = next_id;
+                        if item.tax_group.is_none() {
+                            item.tax_group = self.default_tax_group();
+                        }
+
+                        self.items.insert(next_id, item.clone());
+                        self.search_index.index_item(&item);
+                        self.draft_item = item;
+                        self.draft_item_id = Some(next_id);
+                        self.item_edit_baseline = None;
+                        self.selected_item_id = Some(next_id);
+                        self.apply_group_defaults_to_draft();
+                        self.screen = Screen::Items(items::Mode::Edit);
+                        Task::none()
+                    },
+                    items::Operation::Select(id) => {
+                        let test = self.items.get(&id).unwrap();
+                        self.selected_item_id = Some(id);
+                        self.item_quick_edit = None;
+                        self.screen = Screen::Items(items::Mode::View);
+                        items::export_items::item_to_export_string(test, &self.settings.export_profile.columns());
+                        Task::none()
+                    },
+                    items::Operation::UpdateSearchQuery(query) => {
+                        self.item_search = query;
+                        Task::none()
+                    }
+                    items::Operation::ClearPrinterFilter => {
+                        self.item_printer_filter = None;
+                        Task::none()
+                    }
+                    items::Operation::ClearGroupFilter => {
+                        self.item_group_filter = None;
+                        Task::none()
+                    }
+                    items::Operation::TogglePin(id) => {
+                        let pinned = &mut self.settings.pinned_items;
+                        if pinned.contains(&id) {
+                            pinned.retain(|pinned_id| *pinned_id != id);
+                        } else {
+                            pinned.push(id);
+                        }
+                        self.save_state();
+                        Task::none()
+                    }
+                    items::Operation::FixPrimaryPrinter(id) => {
+                        let Some(mut item) = self.items.get(&id).cloned() else {
+                            return Task::none();
+                        };
+                        if item.fix_primary_printer(&self.printer_logicals) {
+                            println!("Set the primary printer on item {}", id);
+                            self.commit_item(item);
+                            self.run_data_checks();
+                        }
+                        Task::none()
+                    }
+                    items::Operation::ToggleMissingDashboard => {
+                        self.missing_classification_counts = match self.missing_classification_counts {
+                            Some(_) => None,
+                            None => Some(items::missing_classifications(
+                                &self.items,
+                                &self.settings.required_classifications,
+                            )),
+                        };
+                        Task::none()
+                    }
+                    items::Operation::FilterMissing(classification) => {
+                        self.item_missing_filter = Some(classification);
+                        // Refresh the counts so they match the list being filtered
+                        self.missing_classification_counts = Some(items::missing_classifications(
+                            &self.items,
+                            &self.settings.required_classifications,
+                        ));
+                        Task::none()
+                    }
+                    items::Operation::ClearMissingFilter => {
+                        self.item_missing_filter = None;
+                        Task::none()
+                    }
+                    items::Operation::OpenCopyAssignments => {
+                        // Any other item can be the source, the one being edited can't
+                        let options = self.items
+                            .values()
+                            .filter(|item| item.id != self.draft_item.id)
+                            .map(|item| entity_component::EntityOption { id: item.id, name: item.name.clone() })
+                            .collect();
+                        self.item_edit_state.assignment_copy = Some(items::copy_assignments::AssignmentCopy::new(options));
+                        Task::none()
+                    }
+                    items::Operation::ApplyGroupDefaults => {
+                        self.apply_group_defaults_to_draft();
+                        Task::none()
+                    }
+                    items::Operation::ItemGroupSelected => {
+                        // Only a new item starts with the group's price levels, existing ones use the button
+                        if self.item_edit_baseline.is_none() {
+                            self.apply_group_defaults_to_draft();
+                        }
+                        Task::none()
+                    }
+                    items::Operation::SelectCopySource(source_id) => {
+                        if let Some(copy) = self.item_edit_state.assignment_copy.as_mut() {
+                            copy.source = self.items.get(&source_id).cloned();
+                        }
+                        Task::none()
+                    }
+                    items::Operation::SuggestButtonText(slot) => {
+                        let suggestion = items::abbreviate::suggest_button_text(
+                            &self.draft_item.name,
+                            &self.settings.abbreviations,
+                        );
+                        self.item_edit_state.button_suggestion = (!suggestion.is_empty()).then_some((slot, suggestion));
+                        Task::none()
+                    }
+                    items::Operation::OpenNameCase(item_ids) => {
+                        self.name_case = Some(items::name_case::NameCaseTool::new(item_ids));
+                        Task::none()
+                    }
+                    items::Operation::OpenFindReplace => {
+                        self.find_replace = Some(items::find_replace::FindReplace::default());
+                        Task::none()
+                    }
+                    items::Operation::ToggleGroupCollapsed(group_id) => {
+                        if !self.collapsed_item_groups.remove(&group_id) {
+                            self.collapsed_item_groups.insert(group_id);
+                        }
+                        Task::none()
+                    }
+                     items::Operation::RequestDelete(id) => {
+                        println!("Deleting Item id: {}", id);
+                        self.deletion_info = data_types::DeletionInfo { 
+                            entity_type: "Item".to_string(),
+                            entity_id: id,
+                            affected_items: Vec::new()
+                        };
+                        self.show_modal = true;
+                        Task::none()
+                    }
+                    items::Operation::CopyItem(id) => {
+                        println!("Copying Item: {}", id);
+                        let copy_item = self.items.get(&id).unwrap();
+                        let next_id = match self.settings.item_id_range.next_id(&self.items) {
+                            Ok(id) => id,
+                            Err(e) => {
+                                self.import_message = Some(e);
+                                return Task::none();
+                            }
+                        };
+                        
+                        let new_item = Item {
+                            id: next_id,
+                            name: copy_item.name.clone() + "(" + next_id.to_string().as_str() + ")",
+                            created_at: Some(timestamp_now()),
+                            updated_at: Some(timestamp_now()),
+                            ..copy_item.clone()
+                        };
+
+                        self.items.insert(next_id, new_item.clone());
+                        self.search_index.index_item(&new_item);
+                        self.draft_item_id = Some(next_id);
+                        self.item_edit_baseline = None;
+                        self.draft_item = new_item;
+                        self.selected_item_id = Some(next_id);
+                        self.screen = Screen::Items(items::Mode::Edit);
+
+                        Task::none()
+                    }
+                    items::Operation::HideModal => {
+                        self.show_modal = false;
+                        Task::none()
+                    }
+                    items::Operation::ShowModal => {
+                        self.show_modal = true;
+                        Task::none()
+                    }
+                    items::Operation::UpdatePrice(item_id, price_level_id, item_price) => {
+                        // Use a mutable reference instead of cloning:
+                        let state = &mut self.item_edit_state;
+                
+                        // Update the persistent edit state directly:
+                        if let Some(prices) = &mut state.prices {
+                            if let Some((_, price_str)) = prices.iter_mut().find(|(id, _)| *id == price_level_id) {
+                                *price_str = item_price;
+                            } else {
+                                prices.push((price_level_id, item_price));
+                            }
+                        } else {
+                            state.prices = Some(vec![(price_level_id, item_price)]);
+                        }
+
+                        Task::none()
+                    }
+                    items::Operation::LaunchMassItemEditWindow => {
+                        self.show_super_edit = !self.show_super_edit;
+                        self.update(Message::RequestOpenWindow(WindowEnum::SuperEdit))
+                    }
+                }
+            } 
+            Operation::ItemGroups(id, op) => {
+                match op {
+                    item_groups::Operation::RequestDelete(id) => {
+                        self.deletion_info = data_types::DeletionInfo { 
+                            entity_type: "ItemGroup".to_string(),
+                            entity_id: id,
+                            affected_items: Vec::new()
+                        };
+                        self.show_modal = true;
+                        Task::none()
+                    }
+                    item_groups::Operation::CopyItemGroup(id) => {
+                        let copy_item = self.item_groups.get(&id).unwrap();
+                        let next_id = entity_component::next_id(&self.item_groups);
+                        
+                        let new_item = ItemGroup {
+                            id: next_id,
+                            name: copy_item.name.clone() + "(" + next_id.to_string().as_str() + ")",
+                            created_at: Some(timestamp_now()),
+                            updated_at: Some(timestamp_now()),
+                            ..copy_item.clone()
+                        };
+
+                        self.item_groups.insert(next_id, new_item.clone());
+                        self.screen = Screen::ItemGroups;
+
+                        Task::none()
+                    }
+                    item_groups::Operation::EditItemGroup(id) => {
+                        self.references_panel = Some((SearchKind::ItemGroup, id));
+                        // First check if we already have an edit state for this item_group
+                        let already_editing = self.item_group_edit_state_vec
+                            .iter()
+                            .any(|state| state.base.id.parse::<i32>().unwrap() == id);
+
+                        // Only create new edit state if we're not already editing this item_group
+                        if !already_editing {
+                            if let Some(item_group) = self.item_groups.get(&id) {
+                                let edit_state = item_groups::ItemGroupEditState::new(&item_group);
+                                
+                                self.item_group_edit_state_vec.push(edit_state);
+                            }
+                        }
+
+                        self.screen = Screen::ItemGroups;
+                        Task::none()
+                    },
+                    item_groups::Operation::ListScrolled(viewport) => {
+                        self.list_scroll.remember(item_groups::LIST_ID, viewport, self.item_groups.len());
+                        Task::none()
+                    }
+                    item_groups::Operation::Save(id, edit_state) => {
+                        // Names must be unique, keep editing until it is renamed
+                        if let Some(edit_state) = self.item_group_edit_state_vec
+                            .iter_mut()
+                            .find(|state| state.base.id.parse::<i32>().unwrap() == id)
+                        {
+                            if let Some(error) = entity_component::duplicate_name_error(&self.item_groups, id, &edit_state.base.name) {
+                                edit_state.base.name_validation_error = Some(error);
+                                return Task::none();
+                            }
+                        }
 
-                        item.item_prices = Some(item_prices);
+                        // First, find the edit state for this item_group
+                        if let Some(edit_state) = self.item_group_edit_state_vec
+                            .iter()
+                            .find(|state| state.base.id.parse::<i32>().unwrap() == id)
+                        {
+                            // Parse range values
+                            if let (Ok(start), Ok(end)) = (
+                                edit_state.id_range_start.parse::<i32>(), 
+                                edit_state.id_range_end.parse::<i32>()
+                            ) {
+                                // Create a temporary ItemGroup with the new values for validation
+                                let updated_group = item_groups::ItemGroup {
+                                    id: id,
+                                    name: edit_state.base.name.clone(),
+                                    id_range: Range {
+                                        start: start,
+                                        end: end
+                                    },
+                                    ..item_groups::ItemGroup::default()
+                                };
+                                
+                                // Get a list of other groups for validation
+                                let other_groups: Vec<&item_groups::ItemGroup> = self.item_groups.values()
+                                    .filter(|g| g.id != id)  // Exclude the current group
+                                    .collect();
+                                
+                                // Items in this group that the new range would leave behind
+                                let new_range = Range { start: start, end: end };
+                                let outside_ids = item_groups::items_outside_range(id, &new_range, &self.items);
+                                let resolution = edit_state.out_of_range_resolution;
 
-                        if item.id < 0 {
-                            let next_id = self.items
-                                .keys()
-                                .max()
-                                .map_or(1, |max_id| max_id + 1);
-                            item.id = next_id;
+                                // Validate the updated group
+                                match updated_group.validate(&other_groups, self.settings.item_id_range.min) {
+                                    Ok(()) if !outside_ids.is_empty() && resolution.is_none() => {
+                                        let preview = item_groups::plan_reassignment(&outside_ids, &new_range, &self.items);
 
-                            self.items.insert(next_id, item.clone());
-                            self.draft_item_id = None;
-                            self.draft_item = Item::default();
-                            self.selected_item_id = Some(next_id);
-                        } else {
-                            self.items.insert(item.id, item.clone());
-                            self.selected_item_id = Some(item.id);
+                                        if let Some(edit_state) = self.item_group_edit_state_vec
+                                            .iter_mut()
+                                            .find(|state| state.base.id.parse::<i32>().unwrap() == id)
+                                        {
+                                            edit_state.range_validation_error = Some(format!(
+                                                "{} items would fall outside the new range: {}",
+                                                outside_ids.len(),
+                                                outside_ids.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(", ")
+                                            ));
+                                            edit_state.reassign_preview = preview;
+                                        }
+                                    },
+                                    Ok(()) => {
+                                        if resolution == Some(item_groups::OutOfRangeResolution::Reassign) {
+                                            let moves = item_groups::plan_reassignment(&outside_ids, &new_range, &self.items);
+                                            for (old_id, new_id) in moves {
+                                                let Some(new_id) = new_id else {
+                                                    println!("No free ID left in range for item {}", old_id);
+                                                    continue;
+                                                };
+
+                                                if let Some(mut item) = self.items.remove(&old_id) {
+                                                    println!("Re-assigning item {} to {}", old_id, new_id);
+                                                    item.id = new_id;
+                                                    self.items.insert(new_id, item);
+
+                                                    if self.selected_item_id == Some(old_id) {
+                                                        self.selected_item_id = Some(new_id);
+                                                    }
+                                                }
+                                            }
+                                        }
+
+                                        // Validation passed, update the item_group
+                                        if let Some(item_group) = self.item_groups.get_mut(&id) {
+                                            item_group.name = edit_state.base.name.clone();
+                                            item_group.id_range = Range {
+                                                start: start,
+                                                end: end
+                                            };
+                                            item_group.default_price_levels = edit_state.default_price_levels.clone();
+                                            item_group.updated_at = Some(timestamp_now());
+                                        }
+                                        
+                                        // Remove the edit state
+                                        self.item_group_edit_state_vec.retain(|edit| {
+                                            edit.base.id.parse::<i32>().unwrap() != id
+                                        });
+                                    },
+                                    Err(error) => {
+                                        // Validation failed, update the edit state with the error
+                                        if let Some(edit_state) = self.item_group_edit_state_vec
+                                            .iter_mut()
+                                            .find(|state| state.base.id.parse::<i32>().unwrap() == id)
+                                        {
+                                            match error {
+                                                ValidationError::InvalidId(msg) | 
+                                                ValidationError::DuplicateId(msg) => {
+                                                    edit_state.base.id_validation_error = Some(msg);
+                                                },
+                                                ValidationError::EmptyName(msg) | 
+                                                ValidationError::NameTooLong(msg) => {
+                                                    edit_state.base.name_validation_error = Some(msg);
+                                                },
+                                                ValidationError::RangeOverlap(msg) |
+                                                ValidationError::InvalidValue(msg) => {
+                                                    edit_state.range_validation_error = Some(msg);
+                                                },
+                                                _ => {
+                                                    // Fall back for other validation errors
+                                                    edit_state.range_validation_error = Some(format!("{:?}", error));
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            } else {
+                                // Invalid range format
+                                if let Some(edit_state) = self.item_group_edit_state_vec
+                                    .iter_mut()
+                                    .find(|state| state.base.id.parse::<i32>().unwrap() == id)
+                                {
+                                    edit_state.range_validation_error = Some("Invalid range format".to_string());
+                                }
+                            }
+                        }
+
+                        self.save_state();
+                        self.rebuild_search_index();
+                        self.run_data_checks();
+                        self.screen = Screen::ItemGroups;
+                        self.list_scroll.restore(item_groups::LIST_ID, self.item_groups.len())
+                    },
+                    item_groups::Operation::SaveAnyway(id) => {
+                        self.save_item_group_with_resolution(id, item_groups::OutOfRangeResolution::SaveAnyway)
+                    },
+                    item_groups::Operation::ReassignAndSave(id) => {
+                        self.save_item_group_with_resolution(id, item_groups::OutOfRangeResolution::Reassign)
+                    },
+                    item_groups::Operation::UpdateName(id, new_name) => {
+                        if let Some(edit_state) = self.item_group_edit_state_vec
+                        .iter_mut()
+                        .find(|state| state.base.id.parse::<i32>().unwrap() == id) 
+                        { 
+                            //check if name var is less than 17 characters
+                            if new_name.len() < 17 {
+                                edit_state.base.name_validation_error = entity_component::name_input_error(&self.item_groups, id, &new_name);
+                                // Update the name
+                                edit_state.base.name = new_name;
+                            } else {
+                                //set validation error message if it's longer than 16 characters
+                                edit_state.base.name_validation_error = Some("Must be less than 16 characters".to_string());
+                            }
+                        }
+                        Task::none()
+                    },
+                    item_groups::Operation::UpdateIdRangeStart(id, new_range) => {
+                        if let Some(edit_state) = self.item_group_edit_state_vec
+                        .iter_mut()
+                        .find(|state| state.base.id.parse::<i32>().unwrap() == id) 
+                        { // Update the range start
+                            edit_state.id_range_start = new_range;
+                        }
+                        Task::none()
+                    },
+                    item_groups::Operation::ToggleDefaultPriceLevel(id, level_id, checked) => {
+                        if let Some(edit_state) = self.item_group_edit_state_vec
+                        .iter_mut()
+                        .find(|state| state.base.id.parse::<i32>().unwrap() == id)
+                        {
+                            edit_state.default_price_levels.retain(|existing| *existing != level_id);
+                            if checked {
+                                edit_state.default_price_levels.push(level_id);
+                            }
+                        }
+                        Task::none()
+                    },
+                    item_groups::Operation::UpdateIdRangeEnd(id, new_range) => {
+                        if let Some(edit_state) = self.item_group_edit_state_vec
+                        .iter_mut()
+                        .find(|state| state.base.id.parse::<i32>().unwrap() == id) 
+                        { // Update the range end
+                            edit_state.id_range_end = new_range;
+                        }
+                        Task::none()
+                    },
+                    item_groups::Operation::CreateNew => {
+                        let next_id = entity_component::next_id(&self.item_groups);
+
+                        //Create a new ItemGroup
+                        let item_group = ItemGroup {
+                            id: next_id,
+                            id_range: Range { 
+                                start: 0, 
+                                end: 0 
+                                },
+                            name: String::new(),
+                            default_price_levels: Vec::new(),
+                            created_at: Some(timestamp_now()),
+                            updated_at: Some(timestamp_now()),
+                        };
+
+                        //Add new ItemGroup to the app state
+                        self.item_groups.insert(next_id, item_group.clone());
+
+                        //Create a new edit_state for the new item_group
+                        let edit_state = item_groups::ItemGroupEditState::new(&item_group);
+                        
+                        //Add new item_group edit_state to app state
+                        self.item_group_edit_state_vec.push(edit_state);
+
+                        Task::none()
+                    },
+                    item_groups::Operation::CancelEdit(id) => {
+                        let item_group = self.item_groups.get(&id).expect("I created an editstate without an item_group?");
+
+                        // Find the edit state and reset it before removing
+                        if let Some(edit_state) = self.item_group_edit_state_vec
+                        .iter_mut()
+                        .find(|state| state.base.id.parse::<i32>().unwrap() == id) 
+                        { // If all editable fields are blank, and the ItemGroup never had a name saved, delete ItemGroup without confirmation.
+                            if (    !(edit_state.base.name.len()        > 0) 
+                                ||  !(edit_state.id_range_end.len()     > 0) 
+                                ||  !(edit_state.id_range_start.len()   > 0)) 
+                                &&  item_group.name.len() < 1
+                            {
+                                self.item_groups.remove(&id);
+                            }
+
+                            // Reset the data to original values if needed
+                            edit_state.reset();
+                        }
+
+                        // Remove the edit state from the vec
+                        self.item_group_edit_state_vec.retain(|state| {
+                        state.base.id.parse::<i32>().unwrap() != id
+                        });
+
+                        self.screen = Screen::ItemGroups;
+                        Task::none()
+                    },
+                    item_groups::Operation::ToggleStatistics => {
+                        self.show_group_statistics = !self.show_group_statistics;
+                        self.group_statistics_message.clear();
+                        Task::none()
+                    }
+                    item_groups::Operation::SortStatistics(column) => {
+                        self.group_statistics_sort = self.group_statistics_sort.toggle(column);
+                        Task::none()
+                    }
+                    item_groups::Operation::ShowGroupItems(id) => {
+                        self.item_group_filter = Some(id);
+                        self.screen = Screen::Items(items::Mode::View);
+                        Task::none()
+                    }
+                    item_groups::Operation::ExportStatistics => {
+                        // Export in the order the table is showing
+                        let mut stats = item_groups::group_statistics(&self.item_groups, &self.items);
+                        self.group_statistics_sort.apply(&mut stats);
+
+                        item_groups::export_statistics_task(stats)
+                            .map(|msg| Message::ItemGroups(-1, msg))
+                    }
+                    item_groups::Operation::PreviewExport(preview) => {
+                        println!("Previewing export to {:?}: {} bytes", preview.target(), preview.byte_count());
+                        self.export_preview = Some(preview);
+                        Task::none()
+                    }
+                    item_groups::Operation::UpdateStatisticsMessage(msg) => {
+                        self.group_statistics_message = msg;
+                        Task::none()
+                    }
+                }
+            }
+            Operation::TaxGroups(id, op) => {
+                match op {
+                    tax_groups::Operation::RequestDelete(id) => {
+                        self.deletion_info = data_types::DeletionInfo { 
+                           entity_type: "TaxGroup".to_string(),
+                           entity_id: id,
+                           affected_items: Vec::new()
+                       };
+                        self.show_modal = true;
+                       Task::none()
+                   }
+                    tax_groups::Operation::CopyTaxGroup(id) => {
+                        let copy_item = self.tax_groups.get(&id).unwrap();
+                        let next_id = entity_component::next_id(&self.tax_groups);
+                       
+                        let name = copy_item.name.clone() + "(" + next_id.to_string().as_str() + ")";
+                        let new_item = TaxGroup {
+                            id: next_id,
+                            export_key: name.clone(),
+                            name,
+                            created_at: Some(timestamp_now()),
+                            updated_at: Some(timestamp_now()),
+                            ..copy_item.clone()
+                        };
+
+                       self.tax_groups.insert(next_id, new_item.clone());
+                       self.screen = Screen::TaxGroups;
+
+                       Task::none()
+                   }
+                    tax_groups::Operation::EditTaxGroup(id) => {
+                        self.references_panel = Some((SearchKind::TaxGroup, id));
+                    // First check if we already have an edit state for this tax_group
+                    let already_editing = self.tax_group_edit_state_vec
+                        .iter()
+                        .any(|state| state.base.id.parse::<i32>().unwrap() == id);
+
+                    // Only create new edit state if we're not already editing this tax_group
+                    if !already_editing {
+                        if let Some(tax_group) = self.tax_groups.get(&id) {
+                            let edit_state = tax_groups::TaxGroupEditState::new(&tax_group);
+                            
+                            self.tax_group_edit_state_vec.push(edit_state);
+                        }
+                    }
+
+                    self.screen = Screen::TaxGroups;
+                    Task::none()
+                    },
+                    tax_groups::Operation::ListScrolled(viewport) => {
+                        self.list_scroll.remember(tax_groups::LIST_ID, viewport, self.tax_groups.len());
+                        Task::none()
+                    }
+                    tax_groups::Operation::SaveAll(id, edit_state) => {
+                        // Names must be unique, keep editing until it is renamed
+                        if let Some(edit_state) = self.tax_group_edit_state_vec
+                            .iter_mut()
+                            .find(|state| state.base.id.parse::<i32>().unwrap() == id)
+                        {
+                            if let Some(error) = entity_component::duplicate_name_error(&self.tax_groups, id, &edit_state.base.name) {
+                                edit_state.base.name_validation_error = Some(error);
+                                return Task::none();
+                            }
                         }
-                        self.screen = Screen::Items(items::Mode::View);
 
-                        if self.settings.auto_save {
-                            if let Err(e) = self.save_state() {
-                                self.handle_save_error(e);
+                        // First, find the edit state for this tax_group
+                        if let Some(edit_state) = self.tax_group_edit_state_vec
+                            .iter()
+                            .find(|state| state.base.id.parse::<i32>().unwrap() == id)
+                        {
+                            // Clone the edit state name since we'll need it after removing the edit state
+                            let new_name = edit_state.base.name.clone();
+                            let new_rate = edit_state.rate.clone();
+                            
+                            // Get a mutable reference to the tax_group and update it
+                            if let Some(tax_group) = self.tax_groups.get_mut(&id) {
+                                // A new tax group starts out exported under its first name
+                                tax_group.export_key = if edit_state.export_key.is_empty() && edit_state.base.original_name.is_empty() {
+                                    new_name.clone()
+                                } else {
+                                    edit_state.export_key.clone()
+                                };
+                                if let Some(warning) = entity_component::export_key_rename_warning(&edit_state.base.original_name, &new_name, &tax_group.export_key) {
+                                    self.import_message = Some(warning);
+                                }
+                                tax_group.name = new_name;
+                                tax_group.updated_at = Some(timestamp_now());
+                                tax_group.rate = data_types::string_to_decimal(&new_rate)
+                                    .expect("Rate should be validated before message is triggered");
                             }
                         }
 
-                        if let Err(e) = self.save_state() {
-                            self.error_message = Some(e);
-                        } else {
-                            self.error_message = None;
-                        }
+                        self.tax_group_edit_state_vec.retain(|edit| {
+                            edit.base.id.parse::<i32>().unwrap() != id
+                        });
 
+                        self.save_state();
+                        self.screen = Screen::TaxGroups;
+                        self.list_scroll.restore(tax_groups::LIST_ID, self.tax_groups.len())
+                    },
+                    tax_groups::Operation::UpdateName(id, new_name) => {
+                        if let Some(edit_state) = self.tax_group_edit_state_vec
+                        .iter_mut()
+                        .find(|state| state.base.id.parse::<i32>().unwrap() == id) 
+                        { 
+                            //check if name var is less than 17 characters
+                            if new_name.len() < 17 {
+                                edit_state.base.name_validation_error = entity_component::name_input_error(&self.tax_groups, id, &new_name);
+                                // Update the name
+                                edit_state.base.name = new_name;
+                            } else {
+                                //set validation error message if it's longer than 16 characters
+                                edit_state.base.name_validation_error = Some("Must be less than 16 characters".to_string());
+                            }
+                        }
                         Task::none()
-                    }
-                    items::Operation::StartEdit => {
-                        println!("Item ID: {}", item_id);
-                        // Start editing an existing Item
-                        self.draft_item_id = Some(item_id);
-                        self.draft_item = self.items[&item_id].clone();
+                    },
+                    tax_groups::Operation::UpdateExportKey(id, key) => {
+                        if let Some(edit_state) = self.tax_group_edit_state_vec
+                        .iter_mut()
+                        .find(|state| state.base.id.parse::<i32>().unwrap() == id) 
+                        { 
+                            edit_state.export_key = key;
+                        }
+                        Task::none()
+                    },
+                    tax_groups::Operation::UpdateTaxRate(id, new_rate) => {
+                        if let Some(edit_state) = self.tax_group_edit_state_vec
+                        .iter_mut()
+                        .find(|state| state.base.id.parse::<i32>().unwrap() == id) 
+                        { 
+                            // Update the tax_rate
+                            edit_state.rate = new_rate;
+                        }
+                        Task::none()
+                    },
+                    tax_groups::Operation::CreateNew => {
+                        let next_id = entity_component::next_id(&self.tax_groups);
 
-                        let item = self.items.get_mut(&item_id).expect("Item should exist");
-                        let available_choice_groups: Vec<ChoiceGroup> = self.choice_groups.values().cloned().collect();
-                        let available_printer_logicals: Vec<PrinterLogical> = self.printer_logicals.values().cloned().collect();
-                        let available_price_levels: Vec<PriceLevel> = self.price_levels.values().cloned().collect();
+                        //Create a new TaxGroup
+                        let tax_group = TaxGroup {
+                            id: next_id,
+                            name: String::new(),
+                            rate: Decimal::new( 000, 2),
+                            export_key: String::new(),
+                            created_at: Some(timestamp_now()),
+                            updated_at: Some(timestamp_now()),
+                        };
 
-                        // We only do this once, when the user explicitly begins editing:
-                        self.item_edit_state = items::EditState::new(
-                            item,
-                            available_choice_groups,
-                            available_printer_logicals,
-                            available_price_levels,
-                        );
+                        //Add new TaxGroup to the app state
+                        self.tax_groups.insert(next_id, tax_group.clone());
+
+                        //Create a new edit_state for the new choice_group
+                        let edit_state = tax_groups::TaxGroupEditState::new(&tax_group);
+                        
+                        //Add new choice_group edit_state to app state
+                        self.tax_group_edit_state_vec.push(edit_state);
 
-                        println!("Prices: {:?}", item.item_prices);
-                        self.screen = Screen::Items(items::Mode::Edit);
                         Task::none()
-                    }
-                    items::Operation::Cancel => {
-                        if self.draft_item_id.is_some() {
-                            self.draft_item_id = None;
-                            self.draft_item = Item::default();
+                    },
+                    tax_groups::Operation::CancelEdit(id) => {
+                        let tax_group = self.tax_groups.get(&id).expect("I created and editstate without a TaxGroup?");
+
+
+                        // Find the edit state and reset it before removing
+                        if let Some(edit_state) = self.tax_group_edit_state_vec
+                        .iter_mut()
+                        .find(|state| state.base.id.parse::<i32>().unwrap() == id) 
+                        {
+                            if (    !(edit_state.base.name.len()    > 0)
+                                ||  !(edit_state.rate.len()         > 0 ))
+                                && tax_group.name.len() < 1 
+                            {
+                                self.tax_groups.remove(&id);
+                            }
+
+                            // Reset the data to original values if needed
+                            edit_state.reset();
                         }
-                        self.screen = Screen::Items(items::Mode::View);
-                        Task::none()
-                    }
-                    items::Operation::Back => {
-                        self.screen = Screen::Items(items::Mode::View);
-                        Task::none()
-                    }
-                    items::Operation::CreateNew(mut item) => {
-                        let next_id = self.items
-                            .keys()
-                            .max()
-                            .map_or(1, |max_id| max_id + 1);
-                        item.id = next_id;
 
-                        self.items.insert(next_id, item.clone());
-                        self.draft_item = item;
-                        self.draft_item_id = Some(next_id);
-                        self.selected_item_id = Some(next_id);
-                        self.screen = Screen::Items(items::Mode::Edit);
+                        // Remove the edit state from the vec
+                        self.tax_group_edit_state_vec.retain(|state| {
+                        state.base.id.parse::<i32>().unwrap() != id
+                        });
+
+                        self.screen = Screen::TaxGroups;
                         Task::none()
                     },
-                    items::Operation::Select(id) => {
-                        let test = self.items.get(&id).unwrap();
-                        self.selected_item_id = Some(id);
-                        self.screen = Screen::Items(items::Mode::View);
-                        items::export_items::item_to_export_string(test);
+                    tax_groups::Operation::SetTaxIncluded(id, included) => {
+                        let snapshot_note = self.snapshot_before("tax-included");
+                        self.import_undo = Some(self.app_state());
+
+                        let changed = tax_groups::set_tax_included(&mut self.items, id, included, &timestamp_now());
+                        println!("Set tax included to {} on {} items in tax group {}", included, changed, id);
+
+                        self.save_state();
+
+                        let name = self.tax_groups.get(&id).map_or_else(|| id.to_string(), |group| group.name.clone());
+                        self.import_message = Some(with_note(
+                            format!(
+                                "{} {} items in {}.",
+                                if included { "Included tax on" } else { "Removed included tax from" },
+                                changed,
+                                name
+                            ),
+                            snapshot_note,
+                        ));
                         Task::none()
                     },
-                    items::Operation::UpdateSearchQuery(query) => {
-                        self.item_search = query;
-                        Task::none()
-                    }
-                     items::Operation::RequestDelete(id) => {
-                        println!("Deleting Item id: {}", id);
-                        self.deletion_info = data_types::DeletionInfo { 
-                            entity_type: "Item".to_string(),
-                            entity_id: id,
-                            affected_items: Vec::new()
-                        };
-                        self.show_modal = true;
+                    tax_groups::Operation::ShowUncoveredItems => {
+                        self.item_missing_filter = Some(items::Classification::TaxGroup);
+                        self.missing_classification_counts = Some(items::missing_classifications(
+                            &self.items,
+                            &self.settings.required_classifications,
+                        ));
+                        self.screen = Screen::Items(items::Mode::View);
                         Task::none()
-                    }
-                    items::Operation::CopyItem(id) => {
-                        println!("Copying Item: {}", id);
-                        let copy_item = self.items.get(&id).unwrap();
-                        let next_id = self.items
-                            .keys()
-                            .max()
-                            .map_or(1, |max_id| max_id + 1);
-                        
-                        let new_item = Item {
-                            id: next_id,
-                            name: copy_item.name.clone() + "(" + next_id.to_string().as_str() + ")",
-                            ..copy_item.clone()
+                    },
+                    tax_groups::Operation::AssignDefaultToUncovered => {
+                        let Some(default_id) = self.default_tax_group() else {
+                            return Task::none();
                         };
 
-                        self.items.insert(next_id, new_item.clone());
-                        self.draft_item_id = Some(next_id);
-                        self.draft_item = new_item;
-                        self.selected_item_id = Some(next_id);
-                        self.screen = Screen::Items(items::Mode::Edit);
+                        let snapshot_note = self.snapshot_before("default-tax-group");
+                        self.import_undo = Some(self.app_state());
 
-                        Task::none()
-                    }
-                    items::Operation::HideModal => {
-                        self.show_modal = false;
-                        Task::none()
-                    }
-                    items::Operation::ShowModal => {
-                        self.show_modal = true;
-                        Task::none()
-                    }
-                    items::Operation::UpdatePrice(item_id, price_level_id, item_price) => {
-                        // Use a mutable reference instead of cloning:
-                        let state = &mut self.item_edit_state;
-                
-                        // Update the persistent edit state directly:
-                        if let Some(prices) = &mut state.prices {
-                            if let Some((_, price_str)) = prices.iter_mut().find(|(id, _)| *id == price_level_id) {
-                                *price_str = item_price;
-                            } else {
-                                prices.push((price_level_id, item_price));
-                            }
-                        } else {
-                            state.prices = Some(vec![(price_level_id, item_price)]);
-                        }
+                        let changed = tax_groups::assign_to_uncovered(self.items.values_mut(), default_id, &timestamp_now());
+                        println!("Assigned default tax group {} to {} items", default_id, changed);
 
+                        self.save_state();
+
+                        self.import_message = Some(with_note(
+                            format!("Assigned {} to {} items that had no tax group.", self.tax_groups[&default_id].name, changed),
+                            snapshot_note,
+                        ));
                         Task::none()
-                    }
-                    items::Operation::LaunchMassItemEditWindow => {
-                        self.show_super_edit = !self.show_super_edit;
-                        self.update(Message::RequestOpenWindow(WindowEnum::SuperEdit))
-                    }
+                    },
                 }
-            } 
-            Operation::ItemGroups(id, op) => {
+            }    
+            Operation::SecurityLevels(id, op) => {
                 match op {
-                    item_groups::Operation::RequestDelete(id) => {
+                    security_levels::Operation::RequestDelete(id) => {
                         self.deletion_info = data_types::DeletionInfo { 
-                            entity_type: "ItemGroup".to_string(),
-                            entity_id: id,
-                            affected_items: Vec::new()
-                        };
+                           entity_type: "SecurityLevel".to_string(),
+                           entity_id: id,
+                           affected_items: Vec::new()
+                       };
                         self.show_modal = true;
-                        Task::none()
-                    }
-                    item_groups::Operation::CopyItemGroup(id) => {
-                        let copy_item = self.item_groups.get(&id).unwrap();
-                        let next_id = self.item_groups
-                            .keys()
-                            .max()
-                            .map_or(1, |max_id| max_id + 1);
-                        
-                        let new_item = ItemGroup {
-                            id: next_id,
-                            name: copy_item.name.clone() + "(" + next_id.to_string().as_str() + ")",
-                            ..copy_item.clone()
-                        };
+                       Task::none()
+                   }
+                    security_levels::Operation::CopySecurityLevel(id) => {
+                        let copy_item = self.security_levels.get(&id).unwrap();
+                       let next_id = entity_component::next_id(&self.security_levels);
+                       
+                       let new_item = SecurityLevel {
+                           id: next_id,
+                           name: copy_item.name.clone() + "(" + next_id.to_string().as_str() + ")",
+                           created_at: Some(timestamp_now()),
+                           updated_at: Some(timestamp_now()),
+                           ..copy_item.clone()
+                       };
 
-                        self.item_groups.insert(next_id, new_item.clone());
-                        self.screen = Screen::ItemGroups;
+                       self.security_levels.insert(next_id, new_item.clone());
+                       self.screen = Screen::SecurityLevels;
 
-                        Task::none()
-                    }
-                    item_groups::Operation::EditItemGroup(id) => {
-                        // First check if we already have an edit state for this item_group
-                        let already_editing = self.item_group_edit_state_vec
+                       Task::none()
+                   }
+                    security_levels::Operation::EditSecurityLevel(id) => {
+                        self.references_panel = Some((SearchKind::SecurityLevel, id));
+                        // First check if we already have an edit state for this security_level
+                        let already_editing = self.security_level_edit_state_vec
                             .iter()
-                            .any(|state| state.base.id.parse::<i32>().unwrap() == id);
-
-                        // Only create new edit state if we're not already editing this item_group
+                            .any(|state| state.id.parse::<i32>().unwrap() == id);
+    
+                        // Only create new edit state if we're not already editing this security_level
                         if !already_editing {
-                            if let Some(item_group) = self.item_groups.get(&id) {
-                                let edit_state = item_groups::ItemGroupEditState::new(&item_group);
+                            if let Some(security_level) = self.security_levels.get(&id) {
+                                let edit_state = entity_component::EditState {
+                                    name: security_level.name.clone(),
+                                    original_name: security_level.name.clone(),
+                                    id: security_level.id.to_string(),
+                                    id_validation_error: None,
+                                    name_validation_error: None,
+                                };
                                 
-                                self.item_group_edit_state_vec.push(edit_state);
+                                self.security_level_edit_state_vec.push(edit_state);
                             }
                         }
-
-                        self.screen = Screen::ItemGroups;
+    
+                        self.screen = Screen::SecurityLevels;
                         Task::none()
                     },
-                    item_groups::Operation::Save(id, edit_state) => {
-                        // First, find the edit state for this item_group
-                        if let Some(edit_state) = self.item_group_edit_state_vec
+                    security_levels::Operation::ListScrolled(viewport) => {
+                        self.list_scroll.remember(security_levels::LIST_ID, viewport, self.security_levels.len());
+                        Task::none()
+                    }
+                    security_levels::Operation::SaveAll(id, edit_state) => {
+                        // Names must be unique, keep editing until it is renamed
+                        if let Some(edit_state) = self.security_level_edit_state_vec
+                            .iter_mut()
+                            .find(|state| state.id.parse::<i32>().unwrap() == id)
+                        {
+                            if let Some(error) = entity_component::duplicate_name_error(&self.security_levels, id, &edit_state.name) {
+                                edit_state.name_validation_error = Some(error);
+                                return Task::none();
+                            }
+                        }
+
+                        // First, find the edit state for this security_level
+                        if let Some(edit_state) = self.security_level_edit_state_vec
                             .iter()
-                            .find(|state| state.base.id.parse::<i32>().unwrap() == id)
+                            .find(|state| state.id.parse::<i32>().unwrap() == id)
                         {
-                            // Parse range values
-                            if let (Ok(start), Ok(end)) = (
-                                edit_state.id_range_start.parse::<i32>(), 
-                                edit_state.id_range_end.parse::<i32>()
-                            ) {
-                                // Create a temporary ItemGroup with the new values for validation
-                                let updated_group = item_groups::ItemGroup {
-                                    id: id,
-                                    name: edit_state.base.name.clone(),
-                                    id_range: Range {
-                                        start: start,
-                                        end: end
-                                    }
-                                };
-                                
-                                // Get a list of other groups for validation
-                                let other_groups: Vec<&item_groups::ItemGroup> = self.item_groups.values()
-                                    .filter(|g| g.id != id)  // Exclude the current group
-                                    .collect();
-                                
-                                // Validate the updated group
-                                match updated_group.validate(&other_groups) {
-                                    Ok(()) => {
-                                        // Validation passed, update the item_group
-                                        if let Some(item_group) = self.item_groups.get_mut(&id) {
-                                            item_group.name = edit_state.base.name.clone();
-                                            item_group.id_range = Range {
-                                                start: start,
-                                                end: end
-                                            };
-                                        }
-                                        
-                                        // Remove the edit state
-                                        self.item_group_edit_state_vec.retain(|edit| {
-                                            edit.base.id.parse::<i32>().unwrap() != id
-                                        });
-                                    },
-                                    Err(error) => {
-                                        // Validation failed, update the edit state with the error
-                                        if let Some(edit_state) = self.item_group_edit_state_vec
-                                            .iter_mut()
-                                            .find(|state| state.base.id.parse::<i32>().unwrap() == id)
-                                        {
-                                            match error {
-                                                ValidationError::InvalidId(msg) | 
-                                                ValidationError::DuplicateId(msg) => {
-                                                    edit_state.base.id_validation_error = Some(msg);
-                                                },
-                                                ValidationError::EmptyName(msg) | 
-                                                ValidationError::NameTooLong(msg) => {
-                                                    edit_state.base.name_validation_error = Some(msg);
-                                                },
-                                                ValidationError::RangeOverlap(msg) |
-                                                ValidationError::InvalidValue(msg) => {
-                                                    edit_state.range_validation_error = Some(msg);
-                                                },
-                                                _ => {
-                                                    // Fall back for other validation errors
-                                                    edit_state.range_validation_error = Some(format!("{:?}", error));
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
-                            } else {
-                                // Invalid range format
-                                if let Some(edit_state) = self.item_group_edit_state_vec
-                                    .iter_mut()
-                                    .find(|state| state.base.id.parse::<i32>().unwrap() == id)
-                                {
-                                    edit_state.range_validation_error = Some("Invalid range format".to_string());
-                                }
+                            // Clone the edit state name since we'll need it after removing the edit state
+                            let new_name = edit_state.name.clone();
+                            
+                            // Get a mutable reference to the security_level and update it
+                            if let Some(security_group) = self.security_levels.get_mut(&id) {
+                                security_group.name = new_name;
+                                security_group.updated_at = Some(timestamp_now());
                             }
                         }
 
-                        self.save_state().expect("Failed to save to file.");
-                        self.screen = Screen::ItemGroups;
-                        Task::none()
+                        self.security_level_edit_state_vec.retain(|edit| {
+                            edit.id.parse::<i32>().unwrap() != id
+                        });
+
+                        self.save_state();
+                        self.screen = Screen::SecurityLevels;
+                        self.list_scroll.restore(security_levels::LIST_ID, self.security_levels.len())
                     },
-                    item_groups::Operation::UpdateName(id, new_name) => {
-                        if let Some(edit_state) = self.item_group_edit_state_vec
+                    security_levels::Operation::UpdateName(id, new_name) => {
+                        if let Some(edit_state) = self.security_level_edit_state_vec
                         .iter_mut()
-                        .find(|state| state.base.id.parse::<i32>().unwrap() == id) 
+                        .find(|state| state.id.parse::<i32>().unwrap() == id) 
                         { 
                             //check if name var is less than 17 characters
                             if new_name.len() < 17 {
+                                edit_state.name_validation_error = entity_component::name_input_error(&self.security_levels, id, &new_name);
                                 // Update the name
-                                edit_state.base.name = new_name;
+                                edit_state.name = new_name;
                             } else {
                                 //set validation error message if it's longer than 16 characters
-                                edit_state.base.name_validation_error = Some("Must be less than 16 characters".to_string());
+                                edit_state.name_validation_error = Some("Must be less than 16 characters".to_string());
                             }
                         }
-    
-                        self.screen = Screen::ItemGroups;
                         Task::none()
                     },
-                    item_groups::Operation::UpdateIdRangeStart(id, new_range) => {
-                        if let Some(edit_state) = self.item_group_edit_state_vec
+                    security_levels::Operation::CreateNew => {
+                        let next_id = entity_component::next_id(&self.security_levels);
+
+                        //Create a new SecurityLevel
+                        let security_level = SecurityLevel {
+                            id: next_id,
+                            name: String::new(),
+                            created_at: Some(timestamp_now()),
+                            updated_at: Some(timestamp_now()),
+                        };
+
+                        //Add new SecurityLevel to the app state
+                        self.security_levels.insert(next_id, security_level.clone());
+
+                        //Create a new edit_state for the new security_level
+                        let edit_state = entity_component::EditState {
+                            name: security_level.name.clone(),
+                            original_name: security_level.name.clone(),
+                            id: security_level.id.to_string(),
+                            id_validation_error: None,
+                            name_validation_error: None,
+                        };
+                        
+                        //Add new security_level edit_state to app state
+                        self.security_level_edit_state_vec.push(edit_state);
+
+                        Task::none()
+                    },
+                    security_levels::Operation::CancelEdit(id) => {
+                        let security_level = self.security_levels.get(&id).expect("I created an editstate without a SecurityLevel?");
+
+                        // Find the edit state and reset it before removing
+                        if let Some(edit_state) = self.security_level_edit_state_vec
                         .iter_mut()
-                        .find(|state| state.base.id.parse::<i32>().unwrap() == id) 
-                        { // Update the range start
-                            edit_state.id_range_start = new_range;
+                        .find(|state| state.id.parse::<i32>().unwrap() == id) 
+                        {
+                            if security_level.name.len() < 1 
+                            {
+                                self.security_levels.remove(&id);
+                            }
+                            // Reset the data to original values if needed
+                            edit_state.reset();
                         }
-    
-                        self.screen = Screen::ItemGroups;
+
+                        // Remove the edit state from the vec
+                        self.security_level_edit_state_vec.retain(|state| {
+                        state.id.parse::<i32>().unwrap() != id
+                        });
+
+                        self.screen = Screen::SecurityLevels;
                         Task::none()
                     },
-                    item_groups::Operation::UpdateIdRangeEnd(id, new_range) => {
-                        if let Some(edit_state) = self.item_group_edit_state_vec
+                }
+            }    
+            Operation::RevenueCategories(id, op) => {
+                match op {
+                    revenue_categories::Operation::RequestDelete(id) => {
+                        self.deletion_info = data_types::DeletionInfo { 
+                           entity_type: "RevenueCategory".to_string(),
+                           entity_id: id,
+                           affected_items: Vec::new()
+                       };
+                        self.show_modal = true;
+                       Task::none()
+                   }
+                    revenue_categories::Operation::CopyRevenueCategory(id) => {
+                        let copy_item = self.revenue_categories.get(&id).unwrap();
+                       let next_id = entity_component::next_id(&self.revenue_categories);
+                       
+                       let new_item = RevenueCategory {
+                           id: next_id,
+                           name: copy_item.name.clone() + "(" + next_id.to_string().as_str() + ")",
+                           created_at: Some(timestamp_now()),
+                           updated_at: Some(timestamp_now()),
+                           ..copy_item.clone()
+                       };
+
+                       self.revenue_categories.insert(next_id, new_item.clone());
+                       self.screen = Screen::RevenueCategories;
+
+                       Task::none()
+                   }
+                   revenue_categories::Operation::EditRevenueCategory(id) => {
+                       self.references_panel = Some((SearchKind::RevenueCategory, id));
+                    // First check if we already have an edit state for this revenue_category
+                    let already_editing = self.revenue_category_edit_state_vec
+                        .iter()
+                        .any(|state| state.id.parse::<i32>().unwrap() == id);
+
+                    // Only create new edit state if we're not already editing this revenue_category
+                    if !already_editing {
+                        if let Some(revenue_category) = self.revenue_categories.get(&id) {
+                            let edit_state = entity_component::EditState {
+                                name: revenue_category.name.clone(),
+                                original_name: revenue_category.name.clone(),
+                                id: revenue_category.id.to_string(),
+                                id_validation_error: None,
+                                name_validation_error: None,
+                            };
+                            
+                            self.revenue_category_edit_state_vec.push(edit_state);
+                        }
+                    }
+
+                    self.screen = Screen::RevenueCategories;
+                    Task::none()
+                   },
+                    revenue_categories::Operation::ListScrolled(viewport) => {
+                        self.list_scroll.remember(revenue_categories::LIST_ID, viewport, self.revenue_categories.len());
+                        Task::none()
+                    }
+                    revenue_categories::Operation::SaveAll(id, edit_state) => {
+                        // Names must be unique, keep editing until it is renamed
+                        if let Some(edit_state) = self.revenue_category_edit_state_vec
+                            .iter_mut()
+                            .find(|state| state.id.parse::<i32>().unwrap() == id)
+                        {
+                            if let Some(error) = entity_component::duplicate_name_error(&self.revenue_categories, id, &edit_state.name) {
+                                edit_state.name_validation_error = Some(error);
+                                return Task::none();
+                            }
+                        }
+
+                        // First, find the edit state for this revenue_category
+                        if let Some(edit_state) = self.revenue_category_edit_state_vec
+                        .iter()
+                        .find(|state| state.id.parse::<i32>().unwrap() == id)
+                        {
+                        // Clone the edit state name since we'll need it after removing the edit state
+                        let new_name = edit_state.name.clone();
+
+                        // Get a mutable reference to the revenue_category and update it
+                        if let Some(revenue_category) = self.revenue_categories.get_mut(&id) {
+                            revenue_category.name = new_name;
+                            revenue_category.updated_at = Some(timestamp_now());
+                        }
+                        }
+
+                        self.revenue_category_edit_state_vec.retain(|edit| {
+                        edit.id.parse::<i32>().unwrap() != id
+                        });
+
+                        self.save_state();
+                        self.screen = Screen::RevenueCategories;
+                        self.list_scroll.restore(revenue_categories::LIST_ID, self.revenue_categories.len())
+                    },
+                    revenue_categories::Operation::UpdateName(id, new_name) => {
+                        if let Some(edit_state) = self.revenue_category_edit_state_vec
                         .iter_mut()
-                        .find(|state| state.base.id.parse::<i32>().unwrap() == id) 
-                        { // Update the range end
-                            edit_state.id_range_end = new_range;
+                        .find(|state| state.id.parse::<i32>().unwrap() == id) 
+                        { 
+                            //check if name var is less than 17 characters
+                            if new_name.len() < 17 {
+                                edit_state.name_validation_error = entity_component::name_input_error(&self.revenue_categories, id, &new_name);
+                                // Update the name
+                                edit_state.name = new_name;
+                            } else {
+                                //set validation error message if it's longer than 16 characters
+                                edit_state.name_validation_error = Some("Must be less than 16 characters".to_string());
+                            }
                         }
-    
-                        self.screen = Screen::ItemGroups;
                         Task::none()
                     },
-                    item_groups::Operation::CreateNew => {
-                        let next_id = self.item_groups
-                            .keys()
-                            .max()
-                            .map_or(1, |max_id| max_id + 1);
+                    revenue_categories::Operation::CreateNew => {
+                        let next_id = entity_component::next_id(&self.revenue_categories);
 
-                        //Create a new ItemGroup
-                        let item_group = ItemGroup {
+                        //Create a new RevenueCategory
+                        let revenue_category = RevenueCategory {
                             id: next_id,
-                            id_range: Range { 
-                                start: 0, 
-                                end: 0 
-                                },
-                            name: String::new()
+                            name: String::new(),
+                            created_at: Some(timestamp_now()),
+                            updated_at: Some(timestamp_now()),
                         };
 
-                        //Add new ItemGroup to the app state
-                        self.item_groups.insert(next_id, item_group.clone());
+                        //Add new RevenueCategory to the app state
+                        self.revenue_categories.insert(next_id, revenue_category.clone());
 
-                        //Create a new edit_state for the new item_group
-                        let edit_state = item_groups::ItemGroupEditState::new(&item_group);
+                        //Create a new edit_state for the new revenue_category
+                        let edit_state = entity_component::EditState {
+                            name: revenue_category.name.clone(),
+                            original_name: revenue_category.name.clone(),
+                            id: revenue_category.id.to_string(),
+                            id_validation_error: None,
+                            name_validation_error: None,
+                        };
                         
-                        //Add new item_group edit_state to app state
-                        self.item_group_edit_state_vec.push(edit_state);
+                        //Add new revenue_category edit_state to app state
+                        self.revenue_category_edit_state_vec.push(edit_state);
 
                         Task::none()
                     },
-                    item_groups::Operation::CancelEdit(id) => {
-                        let item_group = self.item_groups.get(&id).expect("I created an editstate without an item_group?");
+                    revenue_categories::Operation::CancelEdit(id) => {
+                        let revenue_category = self.revenue_categories.get(&id).expect("I created an editstate without a RevenueCategory?");
 
                         // Find the edit state and reset it before removing
-                        if let Some(edit_state) = self.item_group_edit_state_vec
+                        if let Some(edit_state) = self.revenue_category_edit_state_vec
                         .iter_mut()
-                        .find(|state| state.base.id.parse::<i32>().unwrap() == id) 
-                        { // If all editable fields are blank, and the ItemGroup never had a name saved, delete ItemGroup without confirmation.
-                            if (    !(edit_state.base.name.len()        > 0) 
-                                ||  !(edit_state.id_range_end.len()     > 0) 
-                                ||  !(edit_state.id_range_start.len()   > 0)) 
-                                &&  item_group.name.len() < 1
+                        .find(|state| state.id.parse::<i32>().unwrap() == id) 
+                        {
+                            if revenue_category.name.len() < 1 
                             {
-                                self.item_groups.remove(&id);
+                                self.revenue_categories.remove(&id);
                             }
 
                             // Reset the data to original values if needed
@@ -1751,158 +4647,163 @@ impl MenuBuilder {
                         }
 
                         // Remove the edit state from the vec
-                        self.item_group_edit_state_vec.retain(|state| {
-                        state.base.id.parse::<i32>().unwrap() != id
+                        self.revenue_category_edit_state_vec.retain(|state| {
+                        state.id.parse::<i32>().unwrap() != id
                         });
 
-                        self.screen = Screen::ItemGroups;
+                        self.screen = Screen::RevenueCategories;
                         Task::none()
                     },
-                    
                 }
-            }
-            Operation::TaxGroups(id, op) => {
+            }    
+            Operation::ReportCategories(id, op) => {
                 match op {
-                    tax_groups::Operation::RequestDelete(id) => {
+                    report_categories::Operation::RequestDelete(id) => {
                         self.deletion_info = data_types::DeletionInfo { 
-                           entity_type: "TaxGroup".to_string(),
+                           entity_type: "ReportCategory".to_string(),
                            entity_id: id,
                            affected_items: Vec::new()
-                       };
+                        };
                         self.show_modal = true;
-                       Task::none()
+                        Task::none()
                    }
-                    tax_groups::Operation::CopyTaxGroup(id) => {
-                        let copy_item = self.tax_groups.get(&id).unwrap();
-                        let next_id = self.tax_groups
-                            .keys()
-                            .max()
-                            .map_or(1, |max_id| max_id + 1);
+                    report_categories::Operation::CopyReportCategory(id) => {
+                        let copy_item = self.report_categories.get(&id).unwrap();
+                        let next_id = entity_component::next_id(&self.report_categories);
                        
-                        let new_item = TaxGroup {
+                        let new_item = ReportCategory {
                             id: next_id,
                             name: copy_item.name.clone() + "(" + next_id.to_string().as_str() + ")",
+                            created_at: Some(timestamp_now()),
+                            updated_at: Some(timestamp_now()),
                             ..copy_item.clone()
                         };
 
-                       self.tax_groups.insert(next_id, new_item.clone());
-                       self.screen = Screen::TaxGroups;
+                        self.report_categories.insert(next_id, new_item.clone());
+                        self.screen = Screen::ReportCategories;
 
-                       Task::none()
+                        Task::none()
                    }
-                    tax_groups::Operation::EditTaxGroup(id) => {
-                    // First check if we already have an edit state for this tax_group
-                    let already_editing = self.tax_group_edit_state_vec
-                        .iter()
-                        .any(|state| state.base.id.parse::<i32>().unwrap() == id);
+                    report_categories::Operation::EditReportCategory(id) => {
+                        self.references_panel = Some((SearchKind::ReportCategory, id));
+                        // First check if we already have an edit state for this report_category
+                        let already_editing = self.report_category_edit_state_vec
+                            .iter()
+                            .any(|state| state.id.parse::<i32>().unwrap() == id);
 
-                    // Only create new edit state if we're not already editing this tax_group
-                    if !already_editing {
-                        if let Some(tax_group) = self.tax_groups.get(&id) {
-                            let edit_state = tax_groups::TaxGroupEditState::new(&tax_group);
-                            
-                            self.tax_group_edit_state_vec.push(edit_state);
+                        // Only create new edit state if we're not already editing this report_category
+                        if !already_editing {
+                            if let Some(report_category) = self.report_categories.get(&id) {
+                                let edit_state = entity_component::EditState {
+                                    name: report_category.name.clone(),
+                                    original_name: report_category.name.clone(),
+                                    id: report_category.id.to_string(),
+                                    id_validation_error: None,
+                                    name_validation_error: None,
+                                };
+                                
+                                self.report_category_edit_state_vec.push(edit_state);
+                            }
                         }
-                    }
 
-                    self.screen = Screen::TaxGroups;
-                    Task::none()
+                        self.screen = Screen::ReportCategories;
+                        Task::none()
                     },
-                    tax_groups::Operation::SaveAll(id, edit_state) => {
+                    report_categories::Operation::ListScrolled(viewport) => {
+                        self.list_scroll.remember(report_categories::LIST_ID, viewport, self.report_categories.len());
+                        Task::none()
+                    }
+                    report_categories::Operation::SaveAll(id, edit_state) => {
+                        // Names must be unique, keep editing until it is renamed
+                        if let Some(edit_state) = self.report_category_edit_state_vec
+                            .iter_mut()
+                            .find(|state| state.id.parse::<i32>().unwrap() == id)
+                        {
+                            if let Some(error) = entity_component::duplicate_name_error(&self.report_categories, id, &edit_state.name) {
+                                edit_state.name_validation_error = Some(error);
+                                return Task::none();
+                            }
+                        }
 
-                        // First, find the edit state for this tax_group
-                        if let Some(edit_state) = self.tax_group_edit_state_vec
+                        // First, find the edit state for this report_category
+                        if let Some(edit_state) = self.report_category_edit_state_vec
                             .iter()
-                            .find(|state| state.base.id.parse::<i32>().unwrap() == id)
+                            .find(|state| state.id.parse::<i32>().unwrap() == id)
                         {
                             // Clone the edit state name since we'll need it after removing the edit state
-                            let new_name = edit_state.base.name.clone();
-                            let new_rate = edit_state.rate.clone();
-                            
-                            // Get a mutable reference to the tax_group and update it
-                            if let Some(tax_group) = self.tax_groups.get_mut(&id) {
-                                tax_group.name = new_name;
-                                tax_group.rate = data_types::string_to_decimal(&new_rate)
-                                    .expect("Rate should be validated before message is triggered");
+                            let new_name = edit_state.name.clone();
+                        
+                            // Get a mutable reference to the report_category and update it
+                            if let Some(report_category) = self.report_categories.get_mut(&id) {
+                                report_category.name = new_name;
+                                report_category.updated_at = Some(timestamp_now());
                             }
                         }
 
-                        self.tax_group_edit_state_vec.retain(|edit| {
-                            edit.base.id.parse::<i32>().unwrap() != id
+                        self.report_category_edit_state_vec.retain(|edit| {
+                            edit.id.parse::<i32>().unwrap() != id
                         });
 
-                        self.save_state().expect("Failed to save to file.");
-                        self.screen = Screen::TaxGroups;
-                        Task::none()
+                        self.save_state();
+                        self.screen = Screen::ReportCategories;
+                        self.list_scroll.restore(report_categories::LIST_ID, self.report_categories.len())
                     },
-                    tax_groups::Operation::UpdateName(id, new_name) => {
-                        if let Some(edit_state) = self.tax_group_edit_state_vec
+                    report_categories::Operation::UpdateName(id, new_name) => {
+                        if let Some(edit_state) = self.report_category_edit_state_vec
                         .iter_mut()
-                        .find(|state| state.base.id.parse::<i32>().unwrap() == id) 
+                        .find(|state| state.id.parse::<i32>().unwrap() == id) 
                         { 
                             //check if name var is less than 17 characters
                             if new_name.len() < 17 {
+                                edit_state.name_validation_error = entity_component::name_input_error(&self.report_categories, id, &new_name);
                                 // Update the name
-                                edit_state.base.name = new_name;
+                                edit_state.name = new_name;
                             } else {
                                 //set validation error message if it's longer than 16 characters
-                                edit_state.base.name_validation_error = Some("Must be less than 16 characters".to_string());
+                                edit_state.name_validation_error = Some("Must be less than 16 characters".to_string());
                             }
                         }
-    
-                        self.screen = Screen::TaxGroups;
-                        Task::none()
-                    },
-                    tax_groups::Operation::UpdateTaxRate(id, new_rate) => {
-                        if let Some(edit_state) = self.tax_group_edit_state_vec
-                        .iter_mut()
-                        .find(|state| state.base.id.parse::<i32>().unwrap() == id) 
-                        { 
-                            // Update the tax_rate
-                            edit_state.rate = new_rate;
-                        }
-    
-                        self.screen = Screen::TaxGroups;
                         Task::none()
                     },
-                    tax_groups::Operation::CreateNew => {
-                        let next_id = self.tax_groups
-                            .keys()
-                            .max()
-                            .map_or(1, |max_id| max_id + 1);
+                    report_categories::Operation::CreateNew => {
+                        let next_id = entity_component::next_id(&self.report_categories);
 
-                        //Create a new TaxGroup
-                        let tax_group = TaxGroup {
+                        //Create a new ReportCategory
+                        let report_category = ReportCategory {
                             id: next_id,
                             name: String::new(),
-                            rate: Decimal::new( 000, 2),
+                            created_at: Some(timestamp_now()),
+                            updated_at: Some(timestamp_now()),
                         };
 
-                        //Add new TaxGroup to the app state
-                        self.tax_groups.insert(next_id, tax_group.clone());
+                        //Add new ReportCategory to the app state
+                        self.report_categories.insert(next_id, report_category.clone());
 
-                        //Create a new edit_state for the new choice_group
-                        let edit_state = tax_groups::TaxGroupEditState::new(&tax_group);
+                        //Create a new edit_state for the new report_category
+                        let edit_state = entity_component::EditState {
+                            name: report_category.name.clone(),
+                            original_name: report_category.name.clone(),
+                            id: report_category.id.to_string(),
+                            id_validation_error: None,
+                            name_validation_error: None,
+                        };
                         
-                        //Add new choice_group edit_state to app state
-                        self.tax_group_edit_state_vec.push(edit_state);
+                        //Add new report_category edit_state to app state
+                        self.report_category_edit_state_vec.push(edit_state);
 
                         Task::none()
                     },
-                    tax_groups::Operation::CancelEdit(id) => {
-                        let tax_group = self.tax_groups.get(&id).expect("I created and editstate without a TaxGroup?");
-
+                    report_categories::Operation::CancelEdit(id) => {
+                        let report_category = self.report_categories.get(&id).expect("I created an editstate without a ReportCategory?");
 
                         // Find the edit state and reset it before removing
-                        if let Some(edit_state) = self.tax_group_edit_state_vec
+                        if let Some(edit_state) = self.report_category_edit_state_vec
                         .iter_mut()
-                        .find(|state| state.base.id.parse::<i32>().unwrap() == id) 
+                        .find(|state| state.id.parse::<i32>().unwrap() == id) 
                         {
-                            if (    !(edit_state.base.name.len()    > 0)
-                                ||  !(edit_state.rate.len()         > 0 ))
-                                && tax_group.name.len() < 1 
+                            if report_category.name.len() < 1 
                             {
-                                self.tax_groups.remove(&id);
+                                self.report_categories.remove(&id);
                             }
 
                             // Reset the data to original values if needed
@@ -1910,98 +4811,115 @@ impl MenuBuilder {
                         }
 
                         // Remove the edit state from the vec
-                        self.tax_group_edit_state_vec.retain(|state| {
-                        state.base.id.parse::<i32>().unwrap() != id
+                        self.report_category_edit_state_vec.retain(|state| {
+                        state.id.parse::<i32>().unwrap() != id
                         });
 
-                        self.screen = Screen::TaxGroups;
+                        self.screen = Screen::ReportCategories;
                         Task::none()
                     },
                 }
             }    
-            Operation::SecurityLevels(id, op) => {
+            Operation::ProductClasses(id, op) => {
                 match op {
-                    security_levels::Operation::RequestDelete(id) => {
+                    product_classes::Operation::RequestDelete(id) => {
                         self.deletion_info = data_types::DeletionInfo { 
-                           entity_type: "SecurityLevel".to_string(),
+                           entity_type: "ProductClass".to_string(),
                            entity_id: id,
                            affected_items: Vec::new()
-                       };
+                        };
                         self.show_modal = true;
-                       Task::none()
+                        Task::none()
                    }
-                    security_levels::Operation::CopySecurityLevel(id) => {
-                        let copy_item = self.security_levels.get(&id).unwrap();
-                       let next_id = self.security_levels
-                           .keys()
-                           .max()
-                           .map_or(1, |max_id| max_id + 1);
+                    product_classes::Operation::CopyProductClass(id) => {
+                        let copy_item = self.product_classes.get(&id).unwrap();
+                        let next_id = entity_component::next_id(&self.product_classes);
                        
-                       let new_item = SecurityLevel {
-                           id: next_id,
-                           name: copy_item.name.clone() + "(" + next_id.to_string().as_str() + ")",
-                           ..copy_item.clone()
-                       };
+                        let new_item = ProductClass {
+                            id: next_id,
+                            name: copy_item.name.clone() + "(" + next_id.to_string().as_str() + ")",
+                            created_at: Some(timestamp_now()),
+                            updated_at: Some(timestamp_now()),
+                            ..copy_item.clone()
+                        };
 
-                       self.security_levels.insert(next_id, new_item.clone());
-                       self.screen = Screen::SecurityLevels;
+                       self.product_classes.insert(next_id, new_item.clone());
+                       self.screen = Screen::ProductClasses;
 
                        Task::none()
                    }
-                    security_levels::Operation::EditSecurityLevel(id) => {
-                        // First check if we already have an edit state for this security_level
-                        let already_editing = self.security_level_edit_state_vec
+                    product_classes::Operation::EditProductClass(id) => {
+                        self.references_panel = Some((SearchKind::ProductClass, id));
+                        // First check if we already have an edit state for this product_class
+                        let already_editing = self.product_class_edit_state_vec
                             .iter()
                             .any(|state| state.id.parse::<i32>().unwrap() == id);
     
-                        // Only create new edit state if we're not already editing this security_level
+                        // Only create new edit state if we're not already editing this product_class
                         if !already_editing {
-                            if let Some(security_level) = self.security_levels.get(&id) {
+                            if let Some(product_class) = self.product_classes.get(&id) {
                                 let edit_state = entity_component::EditState {
-                                    name: security_level.name.clone(),
-                                    original_name: security_level.name.clone(),
-                                    id: security_level.id.to_string(),
+                                    name: product_class.name.clone(),
+                                    original_name: product_class.name.clone(),
+                                    id: product_class.id.to_string(),
                                     id_validation_error: None,
                                     name_validation_error: None,
                                 };
                                 
-                                self.security_level_edit_state_vec.push(edit_state);
+                                self.product_class_edit_state_vec.push(edit_state);
                             }
                         }
     
-                        self.screen = Screen::SecurityLevels;
+                        self.screen = Screen::ProductClasses;
                         Task::none()
                     },
-                    security_levels::Operation::SaveAll(id, edit_state) => {
-                        // First, find the edit state for this security_level
-                        if let Some(edit_state) = self.security_level_edit_state_vec
+                    product_classes::Operation::ListScrolled(viewport) => {
+                        self.list_scroll.remember(product_classes::LIST_ID, viewport, self.product_classes.len());
+                        Task::none()
+                    }
+                    product_classes::Operation::SaveAll(id, edit_state) => {
+                        // Names must be unique, keep editing until it is renamed
+                        if let Some(edit_state) = self.product_class_edit_state_vec
+                            .iter_mut()
+                            .find(|state| state.id.parse::<i32>().unwrap() == id)
+                        {
+                            if let Some(error) = entity_component::duplicate_name_error(&self.product_classes, id, &edit_state.name) {
+                                edit_state.name_validation_error = Some(error);
+                                return Task::none();
+                            }
+                        }
+
+                        // First, find the edit state for this product_class
+                        if let Some(edit_state) = self.product_class_edit_state_vec
                             .iter()
                             .find(|state| state.id.parse::<i32>().unwrap() == id)
                         {
                             // Clone the edit state name since we'll need it after removing the edit state
                             let new_name = edit_state.name.clone();
                             
-                            // Get a mutable reference to the security_level and update it
-                            if let Some(security_group) = self.security_levels.get_mut(&id) {
-                                security_group.name = new_name;
+                            // Get a mutable reference to the product_class and update it
+                            if let Some(product_class) = self.product_classes.get_mut(&id) {
+                                product_class.name = new_name;
+                                product_class.updated_at = Some(timestamp_now());
                             }
                         }
 
-                        self.security_level_edit_state_vec.retain(|edit| {
+                        self.product_class_edit_state_vec.retain(|edit| {
                             edit.id.parse::<i32>().unwrap() != id
                         });
 
-                        self.save_state().expect("Failed to save to file.");
-                        self.screen = Screen::SecurityLevels;
-                        Task::none()
+                        self.save_state();
+                        self.screen = Screen::ProductClasses;
+                        self.list_scroll.restore(product_classes::LIST_ID, self.product_classes.len())
                     },
-                    security_levels::Operation::UpdateName(id, new_name) => {
-                        if let Some(edit_state) = self.security_level_edit_state_vec
+                    product_classes::Operation::UpdateName(id, new_name) => {
+                        if let Some(edit_state) = self.product_class_edit_state_vec
                         .iter_mut()
                         .find(|state| state.id.parse::<i32>().unwrap() == id) 
                         { 
                             //check if name var is less than 17 characters
                             if new_name.len() < 17 {
+                                edit_state.name_validation_error = entity_component::name_input_error(&self.product_classes, id, &new_name);
                                 // Update the name
                                 edit_state.name = new_name;
                             } else {
@@ -2009,349 +4927,373 @@ impl MenuBuilder {
                                 edit_state.name_validation_error = Some("Must be less than 16 characters".to_string());
                             }
                         }
-    
-                        self.screen = Screen::SecurityLevels;
                         Task::none()
                     },
-                    security_levels::Operation::CreateNew => {
-                        let next_id = self.security_levels
-                            .keys()
-                            .max()
-                            .map_or(1, |max_id| max_id + 1);
+                    product_classes::Operation::CreateNew => {
+                        let next_id = entity_component::next_id(&self.product_classes);
 
-                        //Create a new SecurityLevel
-                        let security_level = SecurityLevel {
+                        //Create a new ProductClass
+                        let product_class = ProductClass {
                             id: next_id,
-                            name: String::new()
+                            name: String::new(),
+                            created_at: Some(timestamp_now()),
+                            updated_at: Some(timestamp_now()),
                         };
 
-                        //Add new SecurityLevel to the app state
-                        self.security_levels.insert(next_id, security_level.clone());
+                        //Add new ProductClass to the app state
+                        self.product_classes.insert(next_id, product_class.clone());
 
-                        //Create a new edit_state for the new security_level
+                        //Create a new edit_state for the new product_class
                         let edit_state = entity_component::EditState {
-                            name: security_level.name.clone(),
-                            original_name: security_level.name.clone(),
-                            id: security_level.id.to_string(),
+                            name: product_class.name.clone(),
+                            original_name: product_class.name.clone(),
+                            id: product_class.id.to_string(),
                             id_validation_error: None,
                             name_validation_error: None,
                         };
                         
-                        //Add new security_level edit_state to app state
-                        self.security_level_edit_state_vec.push(edit_state);
+                        //Add new product_class edit_state to app state
+                        self.product_class_edit_state_vec.push(edit_state);
 
                         Task::none()
                     },
-                    security_levels::Operation::CancelEdit(id) => {
-                        let security_level = self.security_levels.get(&id).expect("I created an editstate without a SecurityLevel?");
+                    product_classes::Operation::CancelEdit(id) => {
+                        let product_class = self.product_classes.get(&id).expect("I created an editstate without a ProductClass?");
 
                         // Find the edit state and reset it before removing
-                        if let Some(edit_state) = self.security_level_edit_state_vec
+                        if let Some(edit_state) = self.product_class_edit_state_vec
                         .iter_mut()
                         .find(|state| state.id.parse::<i32>().unwrap() == id) 
                         {
-                            if security_level.name.len() < 1 
+                            if product_class.name.len() < 1 
                             {
-                                self.security_levels.remove(&id);
+                                self.product_classes.remove(&id);
                             }
+
                             // Reset the data to original values if needed
                             edit_state.reset();
                         }
 
                         // Remove the edit state from the vec
-                        self.security_level_edit_state_vec.retain(|state| {
+                        self.product_class_edit_state_vec.retain(|state| {
                         state.id.parse::<i32>().unwrap() != id
                         });
 
-                        self.screen = Screen::SecurityLevels;
+                        self.screen = Screen::ProductClasses;
                         Task::none()
                     },
                 }
-            }    
-            Operation::RevenueCategories(id, op) => {
-                match op {
-                    revenue_categories::Operation::RequestDelete(id) => {
-                        self.deletion_info = data_types::DeletionInfo { 
-                           entity_type: "RevenueCategory".to_string(),
-                           entity_id: id,
-                           affected_items: Vec::new()
-                       };
-                        self.show_modal = true;
-                       Task::none()
-                   }
-                    revenue_categories::Operation::CopyRevenueCategory(id) => {
-                        let copy_item = self.revenue_categories.get(&id).unwrap();
-                       let next_id = self.revenue_categories
-                           .keys()
-                           .max()
-                           .map_or(1, |max_id| max_id + 1);
-                       
-                       let new_item = RevenueCategory {
-                           id: next_id,
-                           name: copy_item.name.clone() + "(" + next_id.to_string().as_str() + ")",
-                           ..copy_item.clone()
-                       };
+            }    
+            Operation::ChoiceGroups(id, op) => match op {
+                choice_groups::Operation::ToggleSortByUsage => {
+                    self.sort_entities_by_usage = !self.sort_entities_by_usage;
+                    Task::none()
+                }
+                choice_groups::Operation::RequestDelete(id) => {
+
+                    self.deletion_info = data_types::DeletionInfo { 
+                        entity_type: "ChoiceGroup".to_string(),
+                        entity_id: id,
+                        affected_items: Vec::new()
+                    };
+                     self.show_modal = true;
+                    Task::none()
+                },
+                choice_groups::Operation::CopyChoiceGroup(id) => {
+                    let copy_item = self.choice_groups.get(&id).unwrap();
+                    let next_id = entity_component::next_id(&self.choice_groups);
+                    
+                    let new_item = ChoiceGroup {
+                        id: next_id,
+                        name: copy_item.name.clone() + "(" + next_id.to_string().as_str() + ")",
+                        created_at: Some(timestamp_now()),
+                        updated_at: Some(timestamp_now()),
+                        ..copy_item.clone()
+                    };
+
+                    self.choice_groups.insert(next_id, new_item.clone());
+                    self.screen = Screen::ChoiceGroups;
+
+                    Task::none()
+                }
+                choice_groups::Operation::EditChoiceGroup(id) => {
+                    self.references_panel = Some((SearchKind::ChoiceGroup, id));
+                    // First check if we already have an edit state for this choice_group
+                    let already_editing = self.choice_group_edit_state_vec
+                        .iter()
+                        .any(|state| state.id.parse::<i32>().unwrap() == id);
+
+                    // Only create new edit state if we're not already editing this choice_group
+                    if !already_editing {
+                        if let Some(choice_group) = self.choice_groups.get(&id) {
+                            let edit_state = entity_component::EditState {
+                                name: choice_group.name.clone(),
+                                original_name: choice_group.name.clone(),
+                                id: choice_group.id.to_string(),
+                                id_validation_error: None,
+                                name_validation_error: None,
+                            };
+                            
+                            self.choice_group_edit_state_vec.push(edit_state);
+                        }
+                    }
+
+                    self.screen = Screen::ChoiceGroups;
+                    Task::none()
+
+                },
+                choice_groups::Operation::ListScrolled(viewport) => {
+                    self.list_scroll.remember(choice_groups::LIST_ID, viewport, self.choice_groups.len());
+                    Task::none()
+                }
+                choice_groups::Operation::SaveAll(id, edit_state) => {
+                    // Names must be unique, keep editing until it is renamed
+                    if let Some(edit_state) = self.choice_group_edit_state_vec
+                        .iter_mut()
+                        .find(|state| state.id.parse::<i32>().unwrap() == id)
+                    {
+                        if let Some(error) = entity_component::duplicate_name_error(&self.choice_groups, id, &edit_state.name) {
+                            edit_state.name_validation_error = Some(error);
+                            return Task::none();
+                        }
+                    }
+
+                    // First, find the edit state for this choice_group
+                    if let Some(edit_state) = self.choice_group_edit_state_vec
+                        .iter()
+                        .find(|state| state.id.parse::<i32>().unwrap() == id)
+                    {
+                        // Clone the edit state name since we'll need it after removing the edit state
+                        let new_name = edit_state.name.clone();
+                        
+                        // Get a mutable reference to the choice_group and update it
+                        if let Some(choice_group) = self.choice_groups.get_mut(&id) {
+                            choice_group.name = new_name;
+                            choice_group.updated_at = Some(timestamp_now());
+                        }
+                    }
+
+                    self.choice_group_edit_state_vec.retain(|edit| {
+                        edit.id.parse::<i32>().unwrap() != id
+                    });
+
+                    self.save_state();
+                    self.screen = Screen::ChoiceGroups;
+                    self.list_scroll.restore(choice_groups::LIST_ID, self.choice_groups.len())
+                },
+                choice_groups::Operation::UpdateName(id, new_name) => {
+                    if let Some(edit_state) = self.choice_group_edit_state_vec
+                    .iter_mut()
+                    .find(|state| state.id.parse::<i32>().unwrap() == id) 
+                    { 
+                        //check if name var is less than 17 characters
+                        if new_name.len() < 17 {
+                            edit_state.name_validation_error = entity_component::name_input_error(&self.choice_groups, id, &new_name);
+                            // Update the name
+                            edit_state.name = new_name;
+                        } else {
+                            //set validation error message if it's longer than 16 characters
+                            edit_state.name_validation_error = Some("Must be less than 16 characters".to_string());
+                        }
+                    }
+                    Task::none()
+                },
+                choice_groups::Operation::CreateNew => {
+                    let next_id = entity_component::next_id(&self.choice_groups);
+
+                    //Create a new ChoiceGroup
+                    let choice_group = ChoiceGroup {
+                        id: next_id,
+                        name: String::new(),
+                        created_at: Some(timestamp_now()),
+                        updated_at: Some(timestamp_now()),
+                    };
+
+                    //Add new ChoiceGroup to the app state
+                    self.choice_groups.insert(next_id, choice_group.clone());
+
+                    //Create a new edit_state for the new choice_group
+                    let edit_state = entity_component::EditState {
+                        name: choice_group.name.clone(),
+                        original_name: choice_group.name.clone(),
+                        id: choice_group.id.to_string(),
+                        id_validation_error: None,
+                        name_validation_error: None,
+                    };
+                    
+                    //Add new choice_group edit_state to app state
+                    self.choice_group_edit_state_vec.push(edit_state);
+
+                    Task::none()
+                },
+                choice_groups::Operation::CancelEdit(id) => {
+                    let choice_group = self.choice_groups.get(&id).expect("I created an editstate without a ChoiceGroup?");
+
+                    // Find the edit state and reset it before removing
+                    if let Some(edit_state) = self.choice_group_edit_state_vec
+                    .iter_mut()
+                    .find(|state| state.id.parse::<i32>().unwrap() == id) 
+                    {
+                        if choice_group.name.len() < 1 
+                        {
+                            self.choice_groups.remove(&id);
+                        }
+
+                        // Reset the data to original values if needed
+                        edit_state.reset();
+                    }
+
+                    // Remove the edit state from the vec
+                    self.choice_group_edit_state_vec.retain(|state| {
+                    state.id.parse::<i32>().unwrap() != id
+                    });
+
+                    self.screen = Screen::ChoiceGroups;
+                    Task::none()
+                },
+            },    
+            Operation::PrinterLogicals(id, op) => match op {
+                printer_logicals::Operation::RequestDelete(id) => {
+                    self.deletion_info = data_types::DeletionInfo { 
+                       entity_type: "PrinterLogical".to_string(),
+                       entity_id: id,
+                       affected_items: Vec::new()
+                    };
+                    
+                    self.show_modal = true;
+                    Task::none()
+                }
+                printer_logicals::Operation::CopyPrinterLogical(id) => {
+                    let copy_item = self.printer_logicals.get(&id).unwrap();
+                    let next_id = entity_component::next_id(&self.printer_logicals);
+                   
+                    let name = copy_item.name.clone() + "(" + next_id.to_string().as_str() + ")";
+                    let new_item = PrinterLogical {
+                        id: next_id,
+                        export_key: name.clone(),
+                        name,
+                        created_at: Some(timestamp_now()),
+                        updated_at: Some(timestamp_now()),
+                        ..copy_item.clone()
+                    };
 
-                       self.revenue_categories.insert(next_id, new_item.clone());
-                       self.screen = Screen::RevenueCategories;
+                    self.printer_logicals.insert(next_id, new_item.clone());
+                    self.screen = Screen::PrinterLogicals;
 
-                       Task::none()
-                   }
-                   revenue_categories::Operation::EditRevenueCategory(id) => {
-                    // First check if we already have an edit state for this revenue_category
-                    let already_editing = self.revenue_category_edit_state_vec
+                    Task::none()
+                }
+                printer_logicals::Operation::EditPrinterLogical(id) => {
+                    self.references_panel = Some((SearchKind::PrinterLogical, id));
+                    // First check if we already have an edit state for this printer
+                    let already_editing = self.printer_logical_edit_state_vec
                         .iter()
                         .any(|state| state.id.parse::<i32>().unwrap() == id);
 
-                    // Only create new edit state if we're not already editing this revenue_category
+                    // Only create new edit state if we're not already editing this printer
                     if !already_editing {
-                        if let Some(revenue_category) = self.revenue_categories.get(&id) {
+                        if let Some(printer) = self.printer_logicals.get(&id) {
                             let edit_state = entity_component::EditState {
-                                name: revenue_category.name.clone(),
-                                original_name: revenue_category.name.clone(),
-                                id: revenue_category.id.to_string(),
+                                name: printer.name.clone(),
+                                original_name: printer.name.clone(),
+                                id: printer.id.to_string(),
                                 id_validation_error: None,
                                 name_validation_error: None,
                             };
                             
-                            self.revenue_category_edit_state_vec.push(edit_state);
+                            self.printer_logical_edit_state_vec.push(edit_state);
                         }
                     }
 
-                    self.screen = Screen::RevenueCategories;
+                    self.screen = Screen::PrinterLogicals;
                     Task::none()
-                   },
-                    revenue_categories::Operation::SaveAll(id, edit_state) => {
-                        // First, find the edit state for this revenue_category
-                        if let Some(edit_state) = self.revenue_category_edit_state_vec
-                        .iter()
-                        .find(|state| state.id.parse::<i32>().unwrap() == id)
-                        {
-                        // Clone the edit state name since we'll need it after removing the edit state
-                        let new_name = edit_state.name.clone();
-
-                        // Get a mutable reference to the revenue_category and update it
-                        if let Some(revenue_category) = self.revenue_categories.get_mut(&id) {
-                            revenue_category.name = new_name;
-                        }
-                        }
-
-                        self.revenue_category_edit_state_vec.retain(|edit| {
-                        edit.id.parse::<i32>().unwrap() != id
-                        });
-
-                        self.save_state().expect("Failed to save to file.");
-                        self.screen = Screen::RevenueCategories;
-                        Task::none()
-                    },
-                    revenue_categories::Operation::UpdateName(id, new_name) => {
-                        if let Some(edit_state) = self.revenue_category_edit_state_vec
-                        .iter_mut()
-                        .find(|state| state.id.parse::<i32>().unwrap() == id) 
-                        { 
-                            //check if name var is less than 17 characters
-                            if new_name.len() < 17 {
-                                // Update the name
-                                edit_state.name = new_name;
-                            } else {
-                                //set validation error message if it's longer than 16 characters
-                                edit_state.name_validation_error = Some("Must be less than 16 characters".to_string());
-                            }
-                        }
-    
-                        self.screen = Screen::RevenueCategories;
-                        Task::none()
-                    },
-                    revenue_categories::Operation::CreateNew => {
-                        let next_id = self.revenue_categories
-                            .keys()
-                            .max()
-                            .map_or(1, |max_id| max_id + 1);
-
-                        //Create a new RevenueCategory
-                        let revenue_category = RevenueCategory {
-                            id: next_id,
-                            name: String::new()
-                        };
-
-                        //Add new RevenueCategory to the app state
-                        self.revenue_categories.insert(next_id, revenue_category.clone());
-
-                        //Create a new edit_state for the new revenue_category
-                        let edit_state = entity_component::EditState {
-                            name: revenue_category.name.clone(),
-                            original_name: revenue_category.name.clone(),
-                            id: revenue_category.id.to_string(),
-                            id_validation_error: None,
-                            name_validation_error: None,
-                        };
-                        
-                        //Add new revenue_category edit_state to app state
-                        self.revenue_category_edit_state_vec.push(edit_state);
-
-                        Task::none()
-                    },
-                    revenue_categories::Operation::CancelEdit(id) => {
-                        let revenue_category = self.revenue_categories.get(&id).expect("I created an editstate without a RevenueCategory?");
-
-                        // Find the edit state and reset it before removing
-                        if let Some(edit_state) = self.revenue_category_edit_state_vec
-                        .iter_mut()
-                        .find(|state| state.id.parse::<i32>().unwrap() == id) 
-                        {
-                            if revenue_category.name.len() < 1 
-                            {
-                                self.revenue_categories.remove(&id);
-                            }
-
-                            // Reset the data to original values if needed
-                            edit_state.reset();
-                        }
-
-                        // Remove the edit state from the vec
-                        self.revenue_category_edit_state_vec.retain(|state| {
-                        state.id.parse::<i32>().unwrap() != id
-                        });
-
-                        self.screen = Screen::RevenueCategories;
-                        Task::none()
-                    },
                 }
-            }    
-            Operation::ReportCategories(id, op) => {
-                match op {
-                    report_categories::Operation::RequestDelete(id) => {
-                        self.deletion_info = data_types::DeletionInfo { 
-                           entity_type: "ReportCategory".to_string(),
-                           entity_id: id,
-                           affected_items: Vec::new()
-                        };
-                        self.show_modal = true;
-                        Task::none()
-                   }
-                    report_categories::Operation::CopyReportCategory(id) => {
-                        let copy_item = self.report_categories.get(&id).unwrap();
-                        let next_id = self.report_categories
-                            .keys()
-                            .max()
-                            .map_or(1, |max_id| max_id + 1);
-                       
-                        let new_item = ReportCategory {
-                            id: next_id,
-                            name: copy_item.name.clone() + "(" + next_id.to_string().as_str() + ")",
-                            ..copy_item.clone()
-                        };
+                printer_logicals::Operation::CreateNew => {
+                    let next_id = entity_component::next_id(&self.printer_logicals);
 
-                        self.report_categories.insert(next_id, new_item.clone());
-                        self.screen = Screen::ReportCategories;
+                    //Create a new PrinterLogical
+                    let printer = PrinterLogical {
+                        id: next_id,
+                        name: String::new(),
+                        active: true,
+                        export_key: String::new(),
+                        created_at: Some(timestamp_now()),
+                        updated_at: Some(timestamp_now()),
+                    };
 
-                        Task::none()
-                   }
-                    report_categories::Operation::EditReportCategory(id) => {
-                        // First check if we already have an edit state for this report_category
-                        let already_editing = self.report_category_edit_state_vec
-                            .iter()
-                            .any(|state| state.id.parse::<i32>().unwrap() == id);
+                    //Add new PrinterLogical to the app state
+                    self.printer_logicals.insert(next_id, printer.clone());
 
-                        // Only create new edit state if we're not already editing this report_category
-                        if !already_editing {
-                            if let Some(report_category) = self.report_categories.get(&id) {
-                                let edit_state = entity_component::EditState {
-                                    name: report_category.name.clone(),
-                                    original_name: report_category.name.clone(),
-                                    id: report_category.id.to_string(),
-                                    id_validation_error: None,
-                                    name_validation_error: None,
-                                };
-                                
-                                self.report_category_edit_state_vec.push(edit_state);
-                            }
-                        }
+                    //Create a new edit_state for the new printer
+                    let edit_state = entity_component::EditState {
+                        name: printer.name.clone(),
+                        original_name: printer.name.clone(),
+                        id: printer.id.to_string(),
+                        id_validation_error: None,
+                        name_validation_error: None,
+                    };
+                    
+                    //Add new printer edit_state to app state
+                    self.printer_logical_edit_state_vec.push(edit_state);
 
-                        self.screen = Screen::ReportCategories;
-                        Task::none()
-                    },
-                    report_categories::Operation::SaveAll(id, edit_state) => {
-                        // First, find the edit state for this report_category
-                        if let Some(edit_state) = self.report_category_edit_state_vec
-                            .iter()
-                            .find(|state| state.id.parse::<i32>().unwrap() == id)
-                        {
-                            // Clone the edit state name since we'll need it after removing the edit state
-                            let new_name = edit_state.name.clone();
-                        
-                            // Get a mutable reference to the report_category and update it
-                            if let Some(report_category) = self.report_categories.get_mut(&id) {
-                                report_category.name = new_name;
-                            }
+                    Task::none()
+                }
+                printer_logicals::Operation::ListScrolled(viewport) => {
+                    self.list_scroll.remember(printer_logicals::LIST_ID, viewport, self.printer_logicals.len());
+                    Task::none()
+                }
+                printer_logicals::Operation::Save(id, edit_state) => {
+                    // Names must be unique, keep editing until it is renamed
+                    if let Some(edit_state) = self.printer_logical_edit_state_vec
+                        .iter_mut()
+                        .find(|state| state.id.parse::<i32>().unwrap() == id)
+                    {
+                        if let Some(error) = entity_component::duplicate_name_error(&self.printer_logicals, id, &edit_state.name) {
+                            edit_state.name_validation_error = Some(error);
+                            return Task::none();
                         }
+                    }
 
-                        self.report_category_edit_state_vec.retain(|edit| {
-                            edit.id.parse::<i32>().unwrap() != id
-                        });
-
-                        self.save_state().expect("Failed to save to file.");
-                        self.screen = Screen::ReportCategories;
-                        Task::none()
-                    },
-                    report_categories::Operation::UpdateName(id, new_name) => {
-                        if let Some(edit_state) = self.report_category_edit_state_vec
-                        .iter_mut()
-                        .find(|state| state.id.parse::<i32>().unwrap() == id) 
-                        { 
-                            //check if name var is less than 17 characters
-                            if new_name.len() < 17 {
-                                // Update the name
-                                edit_state.name = new_name;
-                            } else {
-                                //set validation error message if it's longer than 16 characters
-                                edit_state.name_validation_error = Some("Must be less than 16 characters".to_string());
+                    // First, find the edit state for this printer
+                    if let Some(edit_state) = self.printer_logical_edit_state_vec
+                        .iter()
+                        .find(|state| state.id.parse::<i32>().unwrap() == id)
+                    {
+                        // Clone the edit state name since we'll need it after removing the edit state
+                        let new_name = edit_state.name.clone();
+                        
+                        // Get a mutable reference to the printer and update it
+                        if let Some(printer) = self.printer_logicals.get_mut(&id) {
+                            // A new printer starts out exported under its first name
+                            if printer.export_key.is_empty() && edit_state.original_name.is_empty() {
+                                printer.export_key = new_name.clone();
                             }
+                            if let Some(warning) = entity_component::export_key_rename_warning(&edit_state.original_name, &new_name, &printer.export_key) {
+                                self.import_message = Some(warning);
+                            }
+                            printer.name = new_name;
+                            printer.updated_at = Some(timestamp_now());
                         }
-    
-                        self.screen = Screen::ReportCategories;
-                        Task::none()
-                    },
-                    report_categories::Operation::CreateNew => {
-                        let next_id = self.report_categories
-                            .keys()
-                            .max()
-                            .map_or(1, |max_id| max_id + 1);
-
-                        //Create a new ReportCategory
-                        let report_category = ReportCategory {
-                            id: next_id,
-                            name: String::new()
-                        };
-
-                        //Add new ReportCategory to the app state
-                        self.report_categories.insert(next_id, report_category.clone());
+                    }
 
-                        //Create a new edit_state for the new report_category
-                        let edit_state = entity_component::EditState {
-                            name: report_category.name.clone(),
-                            original_name: report_category.name.clone(),
-                            id: report_category.id.to_string(),
-                            id_validation_error: None,
-                            name_validation_error: None,
-                        };
-                        
-                        //Add new report_category edit_state to app state
-                        self.report_category_edit_state_vec.push(edit_state);
+                    self.printer_logical_edit_state_vec.retain(|edit| {
+                        edit.id.parse::<i32>().unwrap() != id
+                    });
 
-                        Task::none()
-                    },
-                    report_categories::Operation::CancelEdit(id) => {
-                        let report_category = self.report_categories.get(&id).expect("I created an editstate without a ReportCategory?");
+                    self.save_state();
+                    self.screen = Screen::PrinterLogicals;
+                    self.list_scroll.restore(printer_logicals::LIST_ID, self.printer_logicals.len())
+                }
+                printer_logicals::Operation::CancelEdit(id) => {
+                    let printer_logical = self.printer_logicals.get(&id).expect("I created an editstate without a PrinterLogical?");
 
-                        // Find the edit state and reset it before removing
-                        if let Some(edit_state) = self.report_category_edit_state_vec
+                    // Find the edit state and reset it before removing
+                    if let Some(edit_state) = self.printer_logical_edit_state_vec
                         .iter_mut()
                         .find(|state| state.id.parse::<i32>().unwrap() == id) 
                         {
-                            if report_category.name.len() < 1 
+                            if printer_logical.name.len() < 1
                             {
-                                self.report_categories.remove(&id);
+                                self.printer_logicals.remove(&id);
                             }
 
                             // Reset the data to original values if needed
@@ -2359,636 +5301,1218 @@ impl MenuBuilder {
                         }
 
                         // Remove the edit state from the vec
-                        self.report_category_edit_state_vec.retain(|state| {
+                        self.printer_logical_edit_state_vec.retain(|state| {
                         state.id.parse::<i32>().unwrap() != id
-                        });
+                    });
 
-                        self.screen = Screen::ReportCategories;
-                        Task::none()
-                    },
+                    self.screen = Screen::PrinterLogicals;
+                    Task::none()
                 }
-            }    
-            Operation::ProductClasses(id, op) => {
-                match op {
-                    product_classes::Operation::RequestDelete(id) => {
-                        self.deletion_info = data_types::DeletionInfo { 
-                           entity_type: "ProductClass".to_string(),
-                           entity_id: id,
-                           affected_items: Vec::new()
-                        };
-                        self.show_modal = true;
-                        Task::none()
-                   }
-                    product_classes::Operation::CopyProductClass(id) => {
-                        let copy_item = self.product_classes.get(&id).unwrap();
-                        let next_id = self.product_classes
-                            .keys()
-                            .max()
-                            .map_or(1, |max_id| max_id + 1);
-                       
-                        let new_item = ProductClass {
-                            id: next_id,
-                            name: copy_item.name.clone() + "(" + next_id.to_string().as_str() + ")",
-                            ..copy_item.clone()
-                        };
+                printer_logicals::Operation::ToggleActive(id, active) => {
+                    if let Some(printer) = self.printer_logicals.get_mut(&id) {
+                        printer.active = active;
+                        printer.updated_at = Some(timestamp_now());
+                    }
+                    self.save_state();
+                    self.run_data_checks();
+                    Task::none()
+                }
+                printer_logicals::Operation::UpdateExportKey(id, key) => {
+                    // Like the Active toggle this edits the printer directly, Save writes it out
+                    if let Some(printer) = self.printer_logicals.get_mut(&id) {
+                        printer.export_key = key;
+                    }
+                    Task::none()
+                }
+                printer_logicals::Operation::ToggleLoadReport => {
+                    self.show_printer_load_report = !self.show_printer_load_report;
+                    self.printer_load_report_message.clear();
+                    Task::none()
+                }
+                printer_logicals::Operation::ShowPrinterItems(id) => {
+                    self.item_printer_filter = Some(id);
+                    self.screen = Screen::Items(items::Mode::View);
+                    Task::none()
+                }
+                printer_logicals::Operation::ExportLoadReport => {
+                    let report = printer_logicals::PrinterLoadReport::new(
+                        &self.printer_logicals,
+                        &self.items,
+                        &self.item_groups,
+                    );
 
-                       self.product_classes.insert(next_id, new_item.clone());
-                       self.screen = Screen::ProductClasses;
+                    printer_logicals::export_load_report_task(report)
+                        .map(|msg| Message::PrinterLogicals(-1, msg))
+                }
+                printer_logicals::Operation::ExportRoutingMap => {
+                    let map = printer_logicals::PrinterRoutingMap::new(&self.printer_logicals, &self.items);
 
-                       Task::none()
-                   }
-                    product_classes::Operation::EditProductClass(id) => {
-                        // First check if we already have an edit state for this product_class
-                        let already_editing = self.product_class_edit_state_vec
-                            .iter()
-                            .any(|state| state.id.parse::<i32>().unwrap() == id);
-    
-                        // Only create new edit state if we're not already editing this product_class
-                        if !already_editing {
-                            if let Some(product_class) = self.product_classes.get(&id) {
-                                let edit_state = entity_component::EditState {
-                                    name: product_class.name.clone(),
-                                    original_name: product_class.name.clone(),
-                                    id: product_class.id.to_string(),
-                                    id_validation_error: None,
-                                    name_validation_error: None,
-                                };
-                                
-                                self.product_class_edit_state_vec.push(edit_state);
-                            }
+                    printer_logicals::export_routing_map_task(map)
+                        .map(|msg| Message::PrinterLogicals(-1, msg))
+                }
+                printer_logicals::Operation::PreviewExport(preview) => {
+                    println!("Previewing export to {:?}: {} bytes", preview.target(), preview.byte_count());
+                    self.export_preview = Some(preview);
+                    Task::none()
+                }
+                printer_logicals::Operation::UpdateLoadReportMessage(msg) => {
+                    self.printer_load_report_message = msg;
+                    Task::none()
+                }
+                printer_logicals::Operation::RequestReroute(id) => {
+                    self.printer_reroute = Some(printer_logicals::Reroute {
+                        source: id,
+                        destination: None,
+                    });
+                    Task::none()
+                }
+                printer_logicals::Operation::SelectRerouteDestination(id) => {
+                    if let Some(reroute) = &mut self.printer_reroute {
+                        reroute.destination = Some(id);
+                    }
+                    Task::none()
+                }
+                printer_logicals::Operation::ConfirmReroute => {
+                    let Some(reroute) = self.printer_reroute.take() else {
+                        return Task::none();
+                    };
+                    let Some(destination) = reroute.destination else {
+                        return Task::none();
+                    };
+
+                    let snapshot_note = self.snapshot_before("reroute");
+                    self.import_undo = Some(self.app_state());
+
+                    let changed = printer_logicals::reroute_items(
+                        &mut self.items,
+                        reroute.source,
+                        destination,
+                        &timestamp_now(),
+                    );
+                    println!("Re-routed {} items from printer {} to {}", changed, reroute.source, destination);
+
+                    self.save_state();
+
+                    let name = |id: EntityId| self.printer_logicals
+                        .get(&id)
+                        .map_or_else(|| id.to_string(), |printer| printer.name.clone());
+                    self.import_message = Some(with_note(
+                        format!("Re-routed {} items from {} to {}.", changed, name(reroute.source), name(destination)),
+                        snapshot_note,
+                    ));
+                    Task::none()
+                }
+                printer_logicals::Operation::CancelReroute => {
+                    self.printer_reroute = None;
+                    Task::none()
+                }
+                printer_logicals::Operation::ToggleSortByUsage => {
+                    self.sort_entities_by_usage = !self.sort_entities_by_usage;
+                    Task::none()
+                }
+                printer_logicals::Operation::UpdateName(id, new_name) => {
+                    if let Some(edit_state) = self.printer_logical_edit_state_vec
+                    .iter_mut()
+                    .find(|state| state.id.parse::<i32>().unwrap() == id) 
+                    {
+                        //check if name var is less than 17 characters
+                        if new_name.len() < 17 {
+                            edit_state.name_validation_error = entity_component::name_input_error(&self.printer_logicals, id, &new_name);
+                            // Update the name
+                            edit_state.name = new_name;
+                        } else {
+                            //set validation error message if it's longer than 16 characters
+                            edit_state.name_validation_error = Some("Must be less than 16 characters".to_string());
                         }
-    
-                        self.screen = Screen::ProductClasses;
-                        Task::none()
-                    },
-                    product_classes::Operation::SaveAll(id, edit_state) => {
-                        // First, find the edit state for this product_class
-                        if let Some(edit_state) = self.product_class_edit_state_vec
-                            .iter()
-                            .find(|state| state.id.parse::<i32>().unwrap() == id)
-                        {
-                            // Clone the edit state name since we'll need it after removing the edit state
-                            let new_name = edit_state.name.clone();
+
+                    }
+                    Task::none()
+                }
+            },
+            Operation::PriceLevels(id, op) => match op {
+                price_levels::Operation::ToggleSortByUsage => {
+                    self.sort_entities_by_usage = !self.sort_entities_by_usage;
+                    Task::none()
+                }
+                price_levels::Operation::RequestDelete(id) => {
+                    // Items that would be left without any price
+                    let sole_priced: Vec<String> = self.items
+                        .values()
+                        .filter(|item| item.has_sole_price_level(id))
+                        .map(|item| format!("{} {}", item.id, item.name))
+                        .collect();
+
+                    self.deletion_info = data_types::DeletionInfo { 
+                       entity_type: "PriceLevel".to_string(),
+                       entity_id: id,
+                       affected_items: sole_priced
+                    };
+                    self.copy_sole_prices_to_default = false;
+                    self.show_modal = true;
+                    Task::none()
+               }
+                price_levels::Operation::CopyPriceLevel(id) => {
+                    let copy_item = self.price_levels.get(&id).unwrap();
+                    let next_id = entity_component::next_id(&self.price_levels);
+                   
+                    let new_item = PriceLevel {
+                        id: next_id,
+                        name: copy_item.name.clone() + "(" + next_id.to_string().as_str() + ")",
+                        created_at: Some(timestamp_now()),
+                        updated_at: Some(timestamp_now()),
+                        ..copy_item.clone()
+                    };
+
+                   self.price_levels.insert(next_id, new_item.clone());
+                   self.screen = Screen::PriceLevels;
+
+                   Task::none()
+               }
+                price_levels::Operation::EditPriceLevel(id) => {
+                    self.references_panel = Some((SearchKind::PriceLevel, id));
+                    // First check if we already have an edit state for this price_level
+                    let already_editing = self.price_level_edit_state_vec
+                        .iter()
+                        .any(|state| state.base.id.parse::<i32>().unwrap() == id);
+
+                    // Only create new edit state if we're not already editing this price_level
+                    if !already_editing {
+                        if let Some(price_level) = self.price_levels.get(&id) {
+                            let edit_state = price_levels::PriceLevelEditState::new(&price_level);
                             
-                            // Get a mutable reference to the product_class and update it
-                            if let Some(product_class) = self.product_classes.get_mut(&id) {
-                                product_class.name = new_name;
+                            self.price_level_edit_state_vec.push(edit_state);
+                        }
+                    }
+
+                    self.screen = Screen::PriceLevels;
+                    Task::none()
+                },
+                price_levels::Operation::ListScrolled(viewport) => {
+                    self.list_scroll.remember(price_levels::LIST_ID, viewport, self.price_levels.len());
+                    Task::none()
+                }
+                price_levels::Operation::SaveAll(id, edit_state) => {
+                    // Names must be unique, keep editing until it is renamed
+                    if let Some(edit_state) = self.price_level_edit_state_vec
+                        .iter_mut()
+                        .find(|state| state.base.id.parse::<i32>().unwrap() == id)
+                    {
+                        if let Some(error) = entity_component::duplicate_name_error(&self.price_levels, id, &edit_state.base.name) {
+                            edit_state.base.name_validation_error = Some(error);
+                            return Task::none();
+                        }
+                    }
+
+                    // First, find the edit state for this price_level
+                    if let Some(edit_state) = self.price_level_edit_state_vec
+                        .iter()
+                        .find(|state| state.base.id.parse::<i32>().unwrap() == id)
+                    {
+                        let new_decimals = match edit_state.decimals.parse::<u8>() {
+                            Ok(decimals) if decimals <= price_levels::MAX_DECIMALS => decimals,
+                            _ => {
+                                println!("Invalid decimal places: {}", edit_state.decimals);
+                                return Task::none();
                             }
+                        };
+
+                        // Clone the edit state name since we'll need it after removing the edit state
+                        let new_name = edit_state.base.name.clone();
+                        
+                        // Get a mutable reference to the price_level and update it
+                        if let Some(price_level) = self.price_levels.get_mut(&id) {
+                            price_level.name = new_name;
+                            price_level.updated_at = Some(timestamp_now());
+                            price_level.decimals = new_decimals;
                         }
+                    }
 
-                        self.product_class_edit_state_vec.retain(|edit| {
-                            edit.id.parse::<i32>().unwrap() != id
-                        });
+                    self.price_level_edit_state_vec.retain(|edit| {
+                        edit.base.id.parse::<i32>().unwrap() != id
+                    });
 
-                        self.save_state().expect("Failed to save to file.");
-                        self.screen = Screen::ProductClasses;
-                        Task::none()
-                    },
-                    product_classes::Operation::UpdateName(id, new_name) => {
-                        if let Some(edit_state) = self.product_class_edit_state_vec
+                    self.save_state();
+                    self.screen = Screen::PriceLevels;
+                    self.list_scroll.restore(price_levels::LIST_ID, self.price_levels.len())
+                },
+                price_levels::Operation::UpdateName(id, new_name) => {
+
+                    if let Some(edit_state) = self.price_level_edit_state_vec
                         .iter_mut()
-                        .find(|state| state.id.parse::<i32>().unwrap() == id) 
+                        .find(|state| state.base.id.parse::<i32>().unwrap() == id) 
                         { 
                             //check if name var is less than 17 characters
                             if new_name.len() < 17 {
+                                edit_state.base.name_validation_error = entity_component::name_input_error(&self.price_levels, id, &new_name);
                                 // Update the name
-                                edit_state.name = new_name;
+                                edit_state.base.name = new_name;
                             } else {
                                 //set validation error message if it's longer than 16 characters
-                                edit_state.name_validation_error = Some("Must be less than 16 characters".to_string());
+                                edit_state.base.name_validation_error = Some("Must be less than 16 characters".to_string());
                             }
                         }
-    
-                        self.screen = Screen::ProductClasses;
-                        Task::none()
-                    },
-                    product_classes::Operation::CreateNew => {
-                        let next_id = self.product_classes
-                            .keys()
-                            .max()
-                            .map_or(1, |max_id| max_id + 1);
+                    Task::none()
+                },
+                price_levels::Operation::UpdateDecimals(id, decimals) => {
+                    if let Some(edit_state) = self.price_level_edit_state_vec
+                        .iter_mut()
+                        .find(|state| state.base.id.parse::<i32>().unwrap() == id)
+                    {
+                        if decimals.chars().all(|c| c.is_ascii_digit()) && decimals.len() <= 1 {
+                            edit_state.decimals = decimals;
+                        }
+                    }
+                    Task::none()
+                },
+                price_levels::Operation::CreateNew => {
+                    let next_id = entity_component::next_id(&self.price_levels);
 
-                        //Create a new ProductClass
-                        let product_class = ProductClass {
-                            id: next_id,
-                            name: String::new()
-                        };
+                    let price_level = PriceLevel {
+                        id: next_id,
+                        name: String::new(),
+                        level_type: PriceLevelType::Enterprise,
+                        price: Decimal::new(000, 2),
+                        decimals: price_levels::DEFAULT_DECIMALS,
+                        last_rounding: price_levels::PriceRounding::None,
+                        created_at: Some(timestamp_now()),
+                        updated_at: Some(timestamp_now()),
+                    };
 
-                        //Add new ProductClass to the app state
-                        self.product_classes.insert(next_id, product_class.clone());
+                    self.price_levels.insert(next_id, price_level.clone());
 
-                        //Create a new edit_state for the new product_class
-                        let edit_state = entity_component::EditState {
-                            name: product_class.name.clone(),
-                            original_name: product_class.name.clone(),
-                            id: product_class.id.to_string(),
-                            id_validation_error: None,
-                            name_validation_error: None,
-                        };
-                        
-                        //Add new product_class edit_state to app state
-                        self.product_class_edit_state_vec.push(edit_state);
+                    let edit_state = price_levels::PriceLevelEditState::new(&price_level);
+                    
+                    self.price_level_edit_state_vec.push(edit_state);
 
-                        Task::none()
-                    },
-                    product_classes::Operation::CancelEdit(id) => {
-                        let product_class = self.product_classes.get(&id).expect("I created an editstate without a ProductClass?");
+                    Task::none()
+                },
+                price_levels::Operation::CancelEdit(id) => {
+                    let price_level = self.price_levels.get(&id).expect("I created an editstate without a PriceLevel?");
 
-                        // Find the edit state and reset it before removing
-                        if let Some(edit_state) = self.product_class_edit_state_vec
+                    // Find the edit state and reset it before removing
+                    if let Some(edit_state) = self.price_level_edit_state_vec
                         .iter_mut()
-                        .find(|state| state.id.parse::<i32>().unwrap() == id) 
+                        .find(|state| state.base.id.parse::<i32>().unwrap() == id) 
                         {
-                            if product_class.name.len() < 1 
+                            if price_level.name.len() < 1 
                             {
-                                self.product_classes.remove(&id);
-                            }
-
+                                self.price_levels.remove(&id);
+                            }   
+                            
                             // Reset the data to original values if needed
                             edit_state.reset();
                         }
 
-                        // Remove the edit state from the vec
-                        self.product_class_edit_state_vec.retain(|state| {
-                        state.id.parse::<i32>().unwrap() != id
-                        });
-
-                        self.screen = Screen::ProductClasses;
-                        Task::none()
-                    },
-                }
-            }    
-            Operation::ChoiceGroups(id, op) => match op {
-                choice_groups::Operation::RequestDelete(id) => {
+                    // Remove the edit state from the vec
+                    self.price_level_edit_state_vec.retain(|state| {
+                    state.base.id.parse::<i32>().unwrap() != id
+                    });
 
-                    self.deletion_info = data_types::DeletionInfo { 
-                        entity_type: "ChoiceGroup".to_string(),
-                        entity_id: id,
-                        affected_items: Vec::new()
-                    };
-                     self.show_modal = true;
+                    self.screen = Screen::PriceLevels;
                     Task::none()
                 },
-                choice_groups::Operation::CopyChoiceGroup(id) => {
-                    let copy_item = self.choice_groups.get(&id).unwrap();
-                    let next_id = self.choice_groups
-                        .keys()
-                        .max()
-                        .map_or(1, |max_id| max_id + 1);
-                    
-                    let new_item = ChoiceGroup {
-                        id: next_id,
-                        name: copy_item.name.clone() + "(" + next_id.to_string().as_str() + ")",
-                        ..copy_item.clone()
-                    };
+            },
+            Operation::SuperEdit(op) => match op {
+                superedit::Operation::UpdateItem(modified_item) => {
+                    Task::none()
+                }
+                superedit::Operation::FlagsApplied { items, flags } => {
+                    self.save_state();
+                    self.run_data_checks();
+
+                    let snapshot_note = self.import_message.take();
+                    self.import_message = Some(with_note(
+                        format!("Changed {} flags on {} items.", flags, items),
+                        snapshot_note,
+                    ));
+                    Task::none()
+                }
+                superedit::Operation::OpenItem(id) => {
+                    Task::batch([
+                        self.perform(Operation::Items(id, items::Operation::Select(id))),
+                        self.focus_main_window(),
+                    ])
+                }
+                superedit::Operation::DuplicateItem(id) => {
+                    Task::batch([
+                        self.perform(Operation::Items(id, items::Operation::CopyItem(id))),
+                        self.focus_main_window(),
+                    ])
+                }
+                superedit::Operation::RequestDeleteItem(id) => {
+                    Task::batch([
+                        self.perform(Operation::Items(id, items::Operation::RequestDelete(id))),
+                        self.focus_main_window(),
+                    ])
+                }
+                superedit::Operation::ItemsDeleted(deleted) => {
+                    let deleted_at = timestamp_now();
+                    for item in &deleted {
+                        self.deleted_items.push(items::delta_export::ItemTombstone::new(item, deleted_at.clone()));
+                    }
+                    self.settings.pinned_items.retain(|id| self.items.contains_key(id));
+                    if self.selected_item_id.is_some_and(|id| !self.items.contains_key(&id)) {
+                        self.selected_item_id = None;
+                    }
 
-                    self.choice_groups.insert(next_id, new_item.clone());
-                    self.screen = Screen::ChoiceGroups;
+                    self.rebuild_search_index();
+                    self.save_state();
+                    self.run_data_checks();
 
+                    let snapshot_note = self.import_message.take();
+                    self.import_message = Some(with_note(format!("Deleted {} items.", deleted.len()), snapshot_note));
                     Task::none()
                 }
-                choice_groups::Operation::EditChoiceGroup(id) => {
-                    // First check if we already have an edit state for this choice_group
-                    let already_editing = self.choice_group_edit_state_vec
-                        .iter()
-                        .any(|state| state.id.parse::<i32>().unwrap() == id);
-
-                    // Only create new edit state if we're not already editing this choice_group
-                    if !already_editing {
-                        if let Some(choice_group) = self.choice_groups.get(&id) {
-                            let edit_state = entity_component::EditState {
-                                name: choice_group.name.clone(),
-                                original_name: choice_group.name.clone(),
-                                id: choice_group.id.to_string(),
-                                id_validation_error: None,
-                                name_validation_error: None,
-                            };
-                            
-                            self.choice_group_edit_state_vec.push(edit_state);
+                superedit::Operation::PreviewExport(preview) => {
+                    println!("Previewing export to {:?}: {} bytes", preview.target(), preview.byte_count());
+                    self.export_preview = Some(preview);
+                    self.focus_main_window()
+                }
+                superedit::Operation::PinItems(ids) => {
+                    for id in ids {
+                        if !self.settings.pinned_items.contains(&id) {
+                            self.settings.pinned_items.push(id);
                         }
                     }
-
-                    self.screen = Screen::ChoiceGroups;
+                    self.save_state();
                     Task::none()
-
-                },
-                choice_groups::Operation::SaveAll(id, edit_state) => {
-                    // First, find the edit state for this choice_group
-                    if let Some(edit_state) = self.choice_group_edit_state_vec
-                        .iter()
-                        .find(|state| state.id.parse::<i32>().unwrap() == id)
-                    {
-                        // Clone the edit state name since we'll need it after removing the edit state
-                        let new_name = edit_state.name.clone();
-                        
-                        // Get a mutable reference to the choice_group and update it
-                        if let Some(choice_group) = self.choice_groups.get_mut(&id) {
-                            choice_group.name = new_name;
+                }
+                superedit::Operation::ChangesAccepted { changed, rounding } => {
+                    for id in changed {
+                        self.search_index.reindex_item(&self.items, id);
+                    }
+                    for (price_level_id, rounding) in rounding {
+                        if let Some(price_level) = self.price_levels.get_mut(&price_level_id) {
+                            price_level.last_rounding = rounding;
                         }
                     }
+                    self.save_state();
+                    Task::none()
+                }
+            }
+        }
+    }
 
-                    self.choice_group_edit_state_vec.retain(|edit| {
-                        edit.id.parse::<i32>().unwrap() != id
-                    });
+    /// Queues a save, it's written in the background once the current message is handled
+    pub fn save_state(&mut self) {
+        self.save_requested = true;
+    }
 
-                    self.save_state().expect("Failed to save to file.");
-                    self.screen = Screen::ChoiceGroups;
+    /// Writes the state before returning, for when the file has to be current right away
+    pub fn save_state_now(&mut self) -> Result<(), String> {
+        if let Some(problem) = &self.startup_error {
+            return Err(problem.clone());
+        }
+
+        // A background write still going could land after this one, so write again once it's done
+        self.save_requested = self.save_in_flight;
+
+        persistence::save_now(
+            &self.state_to_save(),
+            std::path::Path::new(&self.settings.file_path),
+            self.settings.create_backups,
+        )
+    }
+
+    /// Starts the queued save, unless one is still writing; saves asked for meanwhile go out together after it
+    fn start_pending_save(&mut self) -> Task<Message> {
+        if !self.save_requested || self.save_in_flight || self.startup_error.is_some() {
+            return Task::none();
+        }
+        self.save_requested = false;
+        self.save_in_flight = true;
+
+        Task::perform(
+            persistence::save_in_background(
+                self.state_to_save(),
+                std::path::PathBuf::from(&self.settings.file_path),
+                self.settings.create_backups,
+            ),
+            Message::SaveComplete,
+        )
+    }
+
+    /// Puts the list of an entity type back where it was scrolled, after a change rebuilt it
+    fn restore_list_scroll(&self, entity_type: &str) -> Task<Message> {
+        let (list_id, rows) = match entity_type {
+            "Item" => (items::LIST_ID, self.items.len()),
+            "ChoiceGroup" => (choice_groups::LIST_ID, self.choice_groups.len()),
+            "ItemGroup" => (item_groups::LIST_ID, self.item_groups.len()),
+            "PriceLevel" => (price_levels::LIST_ID, self.price_levels.len()),
+            "PrinterLogical" => (printer_logicals::LIST_ID, self.printer_logicals.len()),
+            "ProductClass" => (product_classes::LIST_ID, self.product_classes.len()),
+            "ReportCategory" => (report_categories::LIST_ID, self.report_categories.len()),
+            "RevenueCategory" => (revenue_categories::LIST_ID, self.revenue_categories.len()),
+            "SecurityLevel" => (security_levels::LIST_ID, self.security_levels.len()),
+            "TaxGroup" => (tax_groups::LIST_ID, self.tax_groups.len()),
+            _ => return Task::none(),
+        };
+        self.list_scroll.restore(list_id, rows)
+    }
+
+    /// Closes the main window once the newest state is on disk. A background save still
+    /// writing could rename its file over a write made now, so that save is waited for first.
+    fn close_after_saving(&mut self, id: iced::window::Id, stay_open_on_error: bool) -> Task<Message> {
+        if self.save_in_flight {
+            println!("Waiting for the background save before closing");
+            self.save_requested = true;
+            self.close_after_save = Some((id, stay_open_on_error));
+            return Task::none();
+        }
+
+        if self.save_requested {
+            if let Err(e) = self.save_state_now() {
+                println!("Save before closing failed: {}", e);
+                if stay_open_on_error {
+                    self.error_message = Some(e);
+                    return Task::none();
+                }
+            }
+        }
+        iced::window::close(id)
+    }
+
+    fn state_to_save(&self) -> persistence::AppState {
+        let mut state = self.app_state();
+        items::delta_export::prune_tombstones(&mut state.deleted_items, self.settings.tombstone_retention_days);
+        state
+    }
+
+    fn app_state(&self) -> persistence::AppState {
+        persistence::AppState {
+            items: self.items.values().cloned().collect(),
+            item_groups: self.item_groups.values().cloned().collect(),
+            price_levels: self.price_levels.values().cloned().collect(),
+            product_classes: self.product_classes.values().cloned().collect(),
+            tax_groups: self.tax_groups.values().cloned().collect(),
+            security_levels: self.security_levels.values().cloned().collect(),
+            revenue_categories: self.revenue_categories.values().cloned().collect(),
+            report_categories: self.report_categories.values().cloned().collect(),
+            choice_groups: self.choice_groups.values().cloned().collect(),
+            printer_logicals: self.printer_logicals.values().cloned().collect(),
+            settings: self.settings.clone(),
+            deleted_items: self.deleted_items.clone(),
+        }
+    }
+
+    /// Replaces all entities and items with the given state, settings are left alone
+    fn restore_app_state(&mut self, state: persistence::AppState) {
+        self.items = state.items.into_iter().map(|i| (i.id, i)).collect();
+        self.item_groups = state.item_groups.into_iter().map(|i| (i.id, i)).collect();
+        self.price_levels = state.price_levels.into_iter().map(|i| (i.id, i)).collect();
+        self.product_classes = state.product_classes.into_iter().map(|i| (i.id, i)).collect();
+        self.tax_groups = state.tax_groups.into_iter().map(|i| (i.id, i)).collect();
+        self.security_levels = state.security_levels.into_iter().map(|i| (i.id, i)).collect();
+        self.revenue_categories = state.revenue_categories.into_iter().map(|i| (i.id, i)).collect();
+        self.report_categories = state.report_categories.into_iter().map(|i| (i.id, i)).collect();
+        self.choice_groups = state.choice_groups.into_iter().map(|i| (i.id, i)).collect();
+        self.printer_logicals = state.printer_logicals.into_iter().map(|i| (i.id, i)).collect();
+        self.deleted_items = state.deleted_items;
+        self.selected_item_id = None;
+        self.rebuild_search_index();
+        self.reset_super_editors();
+    }
+
+    /// A SuperEdit over the current data, with the page size and text style from settings
+    fn new_super_editor(&self) -> SuperEdit {
+        let mut editor = SuperEdit::new();
+        editor.set_page_size(self.settings.preview_page_size);
+        editor.set_text_style(TextStyle::from_settings(&self.settings));
+        editor.fill_table(
+            &self.items,
+            &self.item_groups,
+            &self.tax_groups,
+            &self.security_levels,
+            &self.revenue_categories,
+            &self.report_categories,
+            &self.product_classes,
+            &self.choice_groups,
+            &self.printer_logicals,
+            &self.price_levels
+        );
+        editor
+    }
+
+    /// Starts open SuperEdit windows over after the data was replaced. Their filters and
+    /// preview refer to the old data, Accept would write it back.
+    fn reset_super_editors(&mut self) {
+        let super_edit_ids: Vec<window::Id> = self.windows
+            .iter()
+            .filter(|(_, window)| matches!(window.kind, WindowKind::SuperEdit(_)))
+            .map(|(id, _)| *id)
+            .collect();
+
+        for id in super_edit_ids {
+            let editor = self.new_super_editor();
+            if let Some(window) = self.windows.get_mut(&id) {
+                window.kind = WindowKind::SuperEdit(editor);
+            }
+        }
+    }
+
+    /// Re-indexes everything, after loads, imports and bulk changes
+    fn rebuild_search_index(&mut self) {
+        self.search_index.reindex_items(&self.items);
+        println!("Search index rebuilt, {} entries", self.search_index.len());
+    }
+
+    /// The item being edited differs from when editing started
+    fn has_unsaved_item_edit(&self) -> bool {
+        let current = draft_fingerprint(&self.draft_item, &self.item_edit_state.prices);
+        self.item_edit_baseline.as_ref() != Some(&current)
+    }
+
+    /// Escape closes the topmost thing first: the popup showing, then a quick edit,
+    /// then the item edit, then the newest entity row being edited
+    fn escape(&mut self) -> Task<Message> {
+        // Popups close in the order the main window stacks them, the one showing goes first
+        if self.show_modal {
+            return self.update(Message::CancelDelete);
+        }
+        if self.printer_reroute.is_some() {
+            self.printer_reroute = None;
+            return Task::none();
+        }
+        if self.name_case.is_some() {
+            return self.update(Message::CloseNameCase);
+        }
+        if self.button_fill.is_some() {
+            return self.update(Message::CloseButtonFill);
+        }
+        if self.template_picker.is_some() {
+            return self.update(Message::CloseTemplates);
+        }
+        if self.export_preview.is_some() {
+            return self.update(Message::CancelExport);
+        }
+        if self.find_replace.is_some() {
+            return self.update(Message::CloseFindReplace);
+        }
+        if self.overwrite_review.is_some() {
+            return self.update(Message::CancelOverwriteImport);
+        }
+        if self.json_merge_review.is_some() {
+            return self.update(Message::BackFromJsonMerge);
+        }
+        if self.show_item_import_confirmation {
+            return self.update(Message::CancelItemImport);
+        }
+        if self.confirm_quit.is_some() {
+            return self.update(Message::CancelQuit);
+        }
+        if self.confirm_leave_edit {
+            return self.update(Message::KeepEditingItem);
+        }
+        if self.item_quick_edit.is_some() {
+            self.item_quick_edit = None;
+            return Task::none();
+        }
+
+        match self.screen {
+            // Same as the edit toggle, changes get the discard prompt
+            Screen::Items(items::Mode::Edit) => {
+                if self.has_unsaved_item_edit() {
+                    self.confirm_leave_edit = true;
                     Task::none()
-                },
-                choice_groups::Operation::UpdateName(id, new_name) => {
-                    if let Some(edit_state) = self.choice_group_edit_state_vec
-                    .iter_mut()
-                    .find(|state| state.id.parse::<i32>().unwrap() == id) 
-                    { 
-                        //check if name var is less than 17 characters
-                        if new_name.len() < 17 {
-                            // Update the name
-                            edit_state.name = new_name;
-                        } else {
-                            //set validation error message if it's longer than 16 characters
-                            edit_state.name_validation_error = Some("Must be less than 16 characters".to_string());
-                        }
-                    }
+                } else {
+                    self.perform(Operation::Items(self.draft_item.id, items::Operation::Cancel))
+                }
+            }
+            _ => match self.cancel_entity_edit_operation() {
+                Some(operation) => self.perform(operation),
+                None => Task::none(),
+            },
+        }
+    }
+
+    // Cancels the most recently opened edit row on the current entity screen
+    fn cancel_entity_edit_operation(&self) -> Option<Operation> {
+        let last_id = |id: Option<&String>| id.and_then(|id| id.parse::<EntityId>().ok());
+
+        match self.screen {
+            Screen::ItemGroups => last_id(self.item_group_edit_state_vec.last().map(|state| &state.base.id))
+                .map(|id| Operation::ItemGroups(id, item_groups::Operation::CancelEdit(id))),
+            Screen::PriceLevels => last_id(self.price_level_edit_state_vec.last().map(|state| &state.base.id))
+                .map(|id| Operation::PriceLevels(id, price_levels::Operation::CancelEdit(id))),
+            Screen::ProductClasses => last_id(self.product_class_edit_state_vec.last().map(|state| &state.id))
+                .map(|id| Operation::ProductClasses(id, product_classes::Operation::CancelEdit(id))),
+            Screen::TaxGroups => last_id(self.tax_group_edit_state_vec.last().map(|state| &state.base.id))
+                .map(|id| Operation::TaxGroups(id, tax_groups::Operation::CancelEdit(id))),
+            Screen::SecurityLevels => last_id(self.security_level_edit_state_vec.last().map(|state| &state.id))
+                .map(|id| Operation::SecurityLevels(id, security_levels::Operation::CancelEdit(id))),
+            Screen::RevenueCategories => last_id(self.revenue_category_edit_state_vec.last().map(|state| &state.id))
+                .map(|id| Operation::RevenueCategories(id, revenue_categories::Operation::CancelEdit(id))),
+            Screen::ReportCategories => last_id(self.report_category_edit_state_vec.last().map(|state| &state.id))
+                .map(|id| Operation::ReportCategories(id, report_categories::Operation::CancelEdit(id))),
+            Screen::ChoiceGroups => last_id(self.choice_group_edit_state_vec.last().map(|state| &state.id))
+                .map(|id| Operation::ChoiceGroups(id, choice_groups::Operation::CancelEdit(id))),
+            Screen::PrinterLogicals => last_id(self.printer_logical_edit_state_vec.last().map(|state| &state.id))
+                .map(|id| Operation::PrinterLogicals(id, printer_logicals::Operation::CancelEdit(id))),
+            Screen::Settings(_) | Screen::Items(_) => None,
+        }
+    }
 
-                    self.screen = Screen::ChoiceGroups;
-                    Task::none()
-                },
-                choice_groups::Operation::CreateNew => {
-                    let next_id = self.choice_groups
-                        .keys()
-                        .max()
-                        .map_or(1, |max_id| max_id + 1);
+    // Saves the item being edited, or the most recently opened edit row on the current
+    // entity screen. Goes through the screen's own Save so it validates the same way.
+    fn save_entity_edit_message(&self) -> Option<Message> {
+        let last_id = |id: &String| id.parse::<EntityId>().ok();
+
+        match &self.screen {
+            Screen::Items(items::Mode::Edit) => Some(Message::Items(
+                self.draft_item.id,
+                items::Message::Edit(items::edit::Message::Save),
+            )),
+            Screen::ItemGroups => self.item_group_edit_state_vec.last().and_then(|state| {
+                let id = last_id(&state.base.id)?;
+                Some(Message::ItemGroups(id, item_groups::Message::Save(id, state.clone())))
+            }),
+            Screen::PriceLevels => self.price_level_edit_state_vec.last().and_then(|state| {
+                let id = last_id(&state.base.id)?;
+                Some(Message::PriceLevels(id, price_levels::Message::SaveAll(id, state.clone())))
+            }),
+            Screen::ProductClasses => self.product_class_edit_state_vec.last().and_then(|state| {
+                let id = last_id(&state.id)?;
+                Some(Message::ProductClasses(id, product_classes::Message::SaveAll(id, state.clone())))
+            }),
+            Screen::TaxGroups => self.tax_group_edit_state_vec.last().and_then(|state| {
+                let id = last_id(&state.base.id)?;
+                Some(Message::TaxGroups(id, tax_groups::Message::SaveAll(id, state.clone())))
+            }),
+            Screen::SecurityLevels => self.security_level_edit_state_vec.last().and_then(|state| {
+                let id = last_id(&state.id)?;
+                Some(Message::SecurityLevels(id, security_levels::Message::SaveAll(id, state.clone())))
+            }),
+            Screen::RevenueCategories => self.revenue_category_edit_state_vec.last().and_then(|state| {
+                let id = last_id(&state.id)?;
+                Some(Message::RevenueCategories(id, revenue_categories::Message::SaveAll(id, state.clone())))
+            }),
+            Screen::ReportCategories => self.report_category_edit_state_vec.last().and_then(|state| {
+                let id = last_id(&state.id)?;
+                Some(Message::ReportCategories(id, report_categories::Message::SaveAll(id, state.clone())))
+            }),
+            Screen::ChoiceGroups => self.choice_group_edit_state_vec.last().and_then(|state| {
+                let id = last_id(&state.id)?;
+                Some(Message::ChoiceGroups(id, choice_groups::Message::SaveAll(id, state.clone())))
+            }),
+            Screen::PrinterLogicals => self.printer_logical_edit_state_vec.last().and_then(|state| {
+                let id = last_id(&state.id)?;
+                Some(Message::PrinterLogicals(id, printer_logicals::Message::Save(id, state.clone())))
+            }),
+            Screen::Settings(_) | Screen::Items(_) => None,
+        }
+    }
 
-                    //Create a new ChoiceGroup
-                    let choice_group = ChoiceGroup {
-                        id: next_id,
-                        name: String::new()
-                    };
+    /// Edits in progress that a database overwrite would throw away
+    fn unsaved_changes(&self) -> Vec<String> {
+        let mut changes = Vec::new();
 
-                    //Add new ChoiceGroup to the app state
-                    self.choice_groups.insert(next_id, choice_group.clone());
+        if matches!(self.screen, Screen::Items(items::Mode::Edit)) {
+            changes.push(format!("- Item being edited: {}", self.item_edit_state.name));
+        }
+        if self.draft_item_id.is_some() {
+            changes.push("- A new item that hasn't been saved".to_string());
+        }
+        if let Some(quick_edit) = self.item_quick_edit.as_ref().filter(|edit| edit.is_dirty()) {
+            changes.push(format!("- Quick edit on item {}", quick_edit.item_id));
+        }
 
-                    //Create a new edit_state for the new choice_group
-                    let edit_state = entity_component::EditState {
-                        name: choice_group.name.clone(),
-                        original_name: choice_group.name.clone(),
-                        id: choice_group.id.to_string(),
-                        id_validation_error: None,
-                        name_validation_error: None,
-                    };
-                    
-                    //Add new choice_group edit_state to app state
-                    self.choice_group_edit_state_vec.push(edit_state);
+        for (label, count) in [
+            ("item groups", self.item_group_edit_state_vec.len()),
+            ("price levels", self.price_level_edit_state_vec.len()),
+            ("product classes", self.product_class_edit_state_vec.len()),
+            ("tax groups", self.tax_group_edit_state_vec.len()),
+            ("security levels", self.security_level_edit_state_vec.len()),
+            ("revenue categories", self.revenue_category_edit_state_vec.len()),
+            ("report categories", self.report_category_edit_state_vec.len()),
+            ("choice groups", self.choice_group_edit_state_vec.len()),
+            ("printer logicals", self.printer_logical_edit_state_vec.len()),
+        ] {
+            if count > 0 {
+                changes.push(format!("- {} {} being edited", count, label));
+            }
+        }
 
-                    Task::none()
-                },
-                choice_groups::Operation::CancelEdit(id) => {
-                    let choice_group = self.choice_groups.get(&id).expect("I created an editstate without a ChoiceGroup?");
+        changes
+    }
 
-                    // Find the edit state and reset it before removing
-                    if let Some(edit_state) = self.choice_group_edit_state_vec
-                    .iter_mut()
-                    .find(|state| state.id.parse::<i32>().unwrap() == id) 
-                    {
-                        if choice_group.name.len() < 1 
-                        {
-                            self.choice_groups.remove(&id);
-                        }
+    /// Panel of the items referencing the entity picked on the current screen
+    fn references_view(&self) -> Option<Element<'_, Message>> {
+        let (kind, id) = self.references_panel?;
+        let (on_screen, name) = match kind {
+            SearchKind::ItemGroup => (Screen::ItemGroups, self.item_groups.get(&id).map(|e| e.name.as_str())),
+            SearchKind::TaxGroup => (Screen::TaxGroups, self.tax_groups.get(&id).map(|e| e.name.as_str())),
+            SearchKind::SecurityLevel => (Screen::SecurityLevels, self.security_levels.get(&id).map(|e| e.name.as_str())),
+            SearchKind::RevenueCategory => (Screen::RevenueCategories, self.revenue_categories.get(&id).map(|e| e.name.as_str())),
+            SearchKind::ReportCategory => (Screen::ReportCategories, self.report_categories.get(&id).map(|e| e.name.as_str())),
+            SearchKind::ProductClass => (Screen::ProductClasses, self.product_classes.get(&id).map(|e| e.name.as_str())),
+            SearchKind::ChoiceGroup => (Screen::ChoiceGroups, self.choice_groups.get(&id).map(|e| e.name.as_str())),
+            SearchKind::PrinterLogical => (Screen::PrinterLogicals, self.printer_logicals.get(&id).map(|e| e.name.as_str())),
+            SearchKind::PriceLevel => (Screen::PriceLevels, self.price_levels.get(&id).map(|e| e.name.as_str())),
+            SearchKind::Item => return None,
+        };
 
-                        // Reset the data to original values if needed
-                        edit_state.reset();
-                    }
+        // Only beside its own screen, and not for an entity that's since been deleted
+        if std::mem::discriminant(&on_screen) != std::mem::discriminant(&self.screen) {
+            return None;
+        }
+        let name = name?;
+
+        Some(references::panel(
+            format!("{} (#{})", name, id),
+            references::referencing_items(&self.items, kind, id),
+            Message::OpenReferencingItem,
+            Message::CloseReferences,
+            TextStyle::from_settings(&self.settings),
+        ))
+    }
 
-                    // Remove the edit state from the vec
-                    self.choice_group_edit_state_vec.retain(|state| {
-                    state.id.parse::<i32>().unwrap() != id
-                    });
+    /// How many items still point at the entity about to be deleted
+    fn deletion_references(&self) -> Element<'_, Message> {
+        let text_style = TextStyle::from_settings(&self.settings);
+        let Some(kind) = SearchKind::from_entity_type(&self.deletion_info.entity_type) else {
+            return vertical_space().height(0).into();
+        };
+        let count = references::referencing_items(&self.items, kind, self.deletion_info.entity_id).len();
+        if kind == SearchKind::Item || count == 0 {
+            return vertical_space().height(0).into();
+        }
 
-                    self.screen = Screen::ChoiceGroups;
-                    Task::none()
-                },
-            },    
-            Operation::PrinterLogicals(id, op) => match op {
-                printer_logicals::Operation::RequestDelete(id) => {
-                    self.deletion_info = data_types::DeletionInfo { 
-                       entity_type: "PrinterLogical".to_string(),
-                       entity_id: id,
-                       affected_items: Vec::new()
-                    };
-                    
-                    self.show_modal = true;
-                    Task::none()
-                }
-                printer_logicals::Operation::CopyPrinterLogical(id) => {
-                    let copy_item = self.printer_logicals.get(&id).unwrap();
-                    let next_id = self.printer_logicals
-                        .keys()
-                        .max()
-                        .map_or(1, |max_id| max_id + 1);
-                   
-                    let new_item = PrinterLogical {
-                        id: next_id,
-                        name: copy_item.name.clone() + "(" + next_id.to_string().as_str() + ")",
-                        ..copy_item.clone()
-                    };
+        column![
+            iced::widget::vertical_space().height(10),
+            row![
+                iced::widget::horizontal_space().width(6),
+                text(format!(
+                    "Referenced by {} item{}, they will lose this {}",
+                    count,
+                    if count == 1 { "" } else { "s" },
+                    self.deletion_info.entity_type,
+                ))
+                .style(Modern::secondary_text())
+                .size(text_style.body()),
+                iced::widget::horizontal_space().width(6),
+            ],
+        ].into()
+    }
 
-                    self.printer_logicals.insert(next_id, new_item.clone());
-                    self.screen = Screen::PrinterLogicals;
+    /// Warning for the delete popup when a price level is the only price some items have
+    fn sole_price_warning(&self) -> Element<'_, Message> {
+        let text_style = TextStyle::from_settings(&self.settings);
+        let sole_priced = &self.deletion_info.affected_items;
+        if self.deletion_info.entity_type != "PriceLevel" || sole_priced.is_empty() {
+            return vertical_space().height(0).into();
+        }
 
-                    Task::none()
-                }
-                printer_logicals::Operation::EditPrinterLogical(id) => {
-                    // First check if we already have an edit state for this printer
-                    let already_editing = self.printer_logical_edit_state_vec
-                        .iter()
-                        .any(|state| state.id.parse::<i32>().unwrap() == id);
+        column![
+            iced::widget::vertical_space().height(10),
+            row![
+                iced::widget::horizontal_space().width(6),
+                text(format!(
+                    "{} item{} will have no price left: {}",
+                    sole_priced.len(),
+                    if sole_priced.len() == 1 { "" } else { "s" },
+                    sole_priced.iter().take(5).cloned().collect::<Vec<_>>().join(", ")
+                        + if sole_priced.len() > 5 { ", ..." } else { "" }
+                ))
+                .style(Modern::red_text())
+                .size(text_style.body()),
+                iced::widget::horizontal_space().width(6),
+            ],
+            iced::widget::vertical_space().height(5),
+            row![
+                iced::widget::horizontal_space().width(6),
+                iced::widget::checkbox("Copy these prices to default price before deleting", self.copy_sole_prices_to_default)
+                    .on_toggle(Message::ToggleCopySolePrices)
+                    .style(Modern::checkbox()),
+                iced::widget::horizontal_space().width(6),
+            ],
+        ].into()
+    }
 
-                    // Only create new edit state if we're not already editing this printer
-                    if !already_editing {
-                        if let Some(printer) = self.printer_logicals.get(&id) {
-                            let edit_state = entity_component::EditState {
-                                name: printer.name.clone(),
-                                original_name: printer.name.clone(),
-                                id: printer.id.to_string(),
-                                id_validation_error: None,
-                                name_validation_error: None,
-                            };
-                            
-                            self.printer_logical_edit_state_vec.push(edit_state);
-                        }
-                    }
+    /// Searchable pickers for the item edit view, built from the current entity lists
+    fn classification_pickers(&self) -> items::ClassificationPickers {
+        items::ClassificationPickers::new(
+            &self.item_groups,
+            &self.product_classes,
+            &self.revenue_categories,
+            &self.tax_groups,
+            &self.security_levels,
+            &self.report_categories,
+        )
+    }
 
-                    self.screen = Screen::PrinterLogicals;
-                    Task::none()
-                }
-                printer_logicals::Operation::CreateNew => {
-                    let next_id = self.printer_logicals
-                        .keys()
-                        .max()
-                        .map_or(1, |max_id| max_id + 1);
+    /// Brings the main window forward, for actions started from a SuperEdit window
+    /// Gives the draft its item group's default price levels, with blank price inputs to fill in
+    fn apply_group_defaults_to_draft(&mut self) {
+        let Some(group) = self.draft_item.item_group.and_then(|id| self.item_groups.get(&id)) else {
+            return;
+        };
 
-                    //Create a new PrinterLogical
-                    let printer = PrinterLogical {
-                        id: next_id,
-                        name: String::new(),
-                    };
+        let added = group.apply_default_price_levels(&mut self.draft_item);
+        println!("Added group default price levels {:?} to item {}", added, self.draft_item.id);
 
-                    //Add new PrinterLogical to the app state
-                    self.printer_logicals.insert(next_id, printer.clone());
+        let prices = self.item_edit_state.prices.get_or_insert_with(Vec::new);
+        for level_id in added {
+            if !prices.iter().any(|(id, _)| *id == level_id) {
+                prices.push((level_id, String::new()));
+            }
+        }
+    }
 
-                    //Create a new edit_state for the new printer
-                    let edit_state = entity_component::EditState {
-                        name: printer.name.clone(),
-                        original_name: printer.name.clone(),
-                        id: printer.id.to_string(),
-                        id_validation_error: None,
-                        name_validation_error: None,
-                    };
-                    
-                    //Add new printer edit_state to app state
-                    self.printer_logical_edit_state_vec.push(edit_state);
+    fn focus_main_window(&self) -> Task<Message> {
+        match self.windows.iter().find(|(_, w)| w.is_main()) {
+            Some((id, _)) => iced::window::gain_focus(*id),
+            None => Task::none(),
+        }
+    }
 
-                    Task::none()
-                }
-                printer_logicals::Operation::Save(id, edit_state) => {
+    /// Snapshots the in-memory data before an import or bulk edit, returns a note for the notice popup
+    fn snapshot_before(&self, operation: &str) -> Option<String> {
+        if !self.settings.snapshot_before_bulk_changes {
+            return None;
+        }
 
-                    // First, find the edit state for this printer
-                    if let Some(edit_state) = self.printer_logical_edit_state_vec
-                        .iter()
-                        .find(|state| state.id.parse::<i32>().unwrap() == id)
-                    {
-                        // Clone the edit state name since we'll need it after removing the edit state
-                        let new_name = edit_state.name.clone();
-                        
-                        // Get a mutable reference to the printer and update it
-                        if let Some(printer) = self.printer_logicals.get_mut(&id) {
-                            printer.name = new_name;
-                        }
-                    }
+        Some(self.write_snapshot(operation))
+    }
 
-                    self.printer_logical_edit_state_vec.retain(|edit| {
-                        edit.id.parse::<i32>().unwrap() != id
-                    });
+    /// Writes a snapshot and returns a note saying where it went, or why it failed
+    fn write_snapshot(&self, operation: &str) -> String {
+        match self.file_manager.create_snapshot(
+            &self.app_state(),
+            std::path::Path::new(&self.settings.file_path),
+            operation,
+        ) {
+            Ok(path) => {
+                println!("Snapshot written to {:?}", path);
+                format!("A snapshot of the previous data was saved to:\n{}", path.display())
+            }
+            Err(e) => {
+                println!("Snapshot failed: {}", e);
+                format!("Could not save a snapshot of the previous data: {}", e)
+            }
+        }
+    }
 
-                    self.save_state().expect("Failed to save to file.");
-                    self.screen = Screen::PrinterLogicals;
-                    Task::none()
-                }
-                printer_logicals::Operation::CancelEdit(id) => {
-                    let printer_logical = self.printer_logicals.get(&id).expect("I created an editstate without a PrinterLogical?");
+    /// Items and entities borrowed together, for item views and validation
+    fn view_context(&self) -> ViewContext<'_> {
+        ViewContext {
+            available_items: &self.items,
+            available_item_groups: &self.item_groups,
+            available_tax_groups: &self.tax_groups,
+            available_security_levels: &self.security_levels,
+            available_revenue_categories: &self.revenue_categories,
+            available_report_categories: &self.report_categories,
+            available_product_classes: &self.product_classes,
+            available_choice_groups: &self.choice_groups,
+            available_printer_logicals: &self.printer_logicals,
+            available_price_levels: &self.price_levels,
+        }
+    }
 
-                    // Find the edit state and reset it before removing
-                    if let Some(edit_state) = self.printer_logical_edit_state_vec
-                        .iter_mut()
-                        .find(|state| state.id.parse::<i32>().unwrap() == id) 
-                        {
-                            if printer_logical.name.len() < 1
-                            {
-                                self.printer_logicals.remove(&id);
-                            }
+    /// Nothing but the Default item a new database starts with
+    fn is_empty_database(&self) -> bool {
+        self.reference_entity_count() == 0 && self.items.len() <= 1
+    }
 
-                            // Reset the data to original values if needed
-                            edit_state.reset();
-                        }
+    fn reference_entity_count(&self) -> usize {
+        self.item_groups.len()
+            + self.price_levels.len()
+            + self.product_classes.len()
+            + self.tax_groups.len()
+            + self.security_levels.len()
+            + self.revenue_categories.len()
+            + self.report_categories.len()
+            + self.choice_groups.len()
+            + self.printer_logicals.len()
+    }
 
-                        // Remove the edit state from the vec
-                        self.printer_logical_edit_state_vec.retain(|state| {
-                        state.id.parse::<i32>().unwrap() != id
-                    });
+    /// The default tax group from settings, if it still exists
+    fn default_tax_group(&self) -> Option<EntityId> {
+        self.settings.default_tax_group.filter(|id| self.tax_groups.contains_key(id))
+    }
 
-                    self.screen = Screen::PrinterLogicals;
-                    Task::none()
-                }
-                printer_logicals::Operation::UpdateName(id, new_name) => {
-                    if let Some(edit_state) = self.printer_logical_edit_state_vec
-                    .iter_mut()
-                    .find(|state| state.id.parse::<i32>().unwrap() == id) 
-                    {
-                        //check if name var is less than 17 characters
-                        if new_name.len() < 17 {
-                            // Update the name
-                            edit_state.name = new_name;
-                        } else {
-                            //set validation error message if it's longer than 16 characters
-                            edit_state.name_validation_error = Some("Must be less than 16 characters".to_string());
-                        }
+    /// Gives the default tax group to imported items without one, noting it in the import summary
+    fn apply_default_tax_group(&mut self, summary: String, imported: impl Fn(EntityId) -> bool) -> String {
+        let Some(default_id) = self.default_tax_group() else {
+            return summary;
+        };
 
-                    }
+        let changed = tax_groups::assign_to_uncovered(
+            self.items.iter_mut().filter(|(id, _)| imported(**id)).map(|(_, item)| item),
+            default_id,
+            &timestamp_now(),
+        );
+        if changed == 0 {
+            return summary;
+        }
 
-                    self.screen = Screen::PrinterLogicals;
-                    Task::none()
+        format!(
+            "{}\n{} items without a tax group were given the default, {}.",
+            summary, changed, self.tax_groups[&default_id].name
+        )
+    }
+
+    /// Stores an item and saves, shared by the full edit form and quick edits
+    fn commit_item(&mut self, mut item: Item) {
+        item.updated_at = Some(timestamp_now());
+
+        if item.id < 0 {
+            let next_id = match self.settings.item_id_range.next_id(&self.items) {
+                Ok(id) => id,
+                Err(e) => {
+                    println!("Not saving new item: {}", e);
+                    self.import_message = Some(e);
+                    return;
                 }
-            },
-            Operation::PriceLevels(id, op) => match op {
-                price_levels::Operation::RequestDelete(id) => {
-                    self.deletion_info = data_types::DeletionInfo { 
-                       entity_type: "PriceLevel".to_string(),
-                       entity_id: id,
-                       affected_items: Vec::new()
-                    };
-                    self.show_modal = true;
-                    Task::none()
-               }
-                price_levels::Operation::CopyPriceLevel(id) => {
-                    let copy_item = self.price_levels.get(&id).unwrap();
-                    let next_id = self.price_levels
-                        .keys()
-                        .max()
-                        .map_or(1, |max_id| max_id + 1);
-                   
-                    let new_item = PriceLevel {
-                        id: next_id,
-                        name: copy_item.name.clone() + "(" + next_id.to_string().as_str() + ")",
-                        ..copy_item.clone()
-                    };
+            };
+            item.created_at = item.updated_at.clone();
+            item.id = next_id;
 
-                   self.price_levels.insert(next_id, new_item.clone());
-                   self.screen = Screen::PriceLevels;
+            self.items.insert(next_id, item.clone());
+            self.draft_item_id = None;
+            self.draft_item = Item::default();
+            self.selected_item_id = Some(next_id);
+        } else {
+            self.items.insert(item.id, item.clone());
+            self.selected_item_id = Some(item.id);
+        }
+        self.search_index.index_item(&item);
+        self.screen = Screen::Items(items::Mode::View);
 
-                   Task::none()
-               }
-                price_levels::Operation::EditPriceLevel(id) => {
-                    // First check if we already have an edit state for this price_level
-                    let already_editing = self.price_level_edit_state_vec
-                        .iter()
-                        .any(|state| state.base.id.parse::<i32>().unwrap() == id);
+        self.save_state();
+    }
+
+    fn save_item_group_with_resolution(&mut self, id: EntityId, resolution: item_groups::OutOfRangeResolution) -> Task<Message> {
+        let edit_state = self.item_group_edit_state_vec
+            .iter_mut()
+            .find(|state| state.base.id.parse::<i32>().unwrap() == id);
+
+        match edit_state {
+            Some(edit_state) => {
+                edit_state.out_of_range_resolution = Some(resolution);
+                let edit_state = edit_state.clone();
+                self.perform(Operation::ItemGroups(id, item_groups::Operation::Save(id, edit_state)))
+            }
+            None => Task::none(),
+        }
+    }
+
+    /// (old id, new id) for every item outside its item group's range
+    fn plan_out_of_range_id_fix(&self) -> Vec<(EntityId, Option<EntityId>)> {
+        self.item_groups
+            .values()
+            .flat_map(|group| {
+                let outside = item_groups::items_outside_range(group.id, &group.id_range, &self.items);
+                item_groups::plan_reassignment(&outside, &group.id_range, &self.items)
+            })
+            .collect()
+    }
+
+    /// Entities of each type that no item references
+    fn unused_entities(&self) -> Vec<settings::UnusedEntities> {
+        fn unused<T: entity_component::Entity>(
+            entity_type: &str,
+            label: &str,
+            entities: &BTreeMap<EntityId, T>,
+            used: &BTreeSet<EntityId>,
+        ) -> Option<settings::UnusedEntities> {
+            let unused: Vec<(EntityId, String)> = entities
+                .values()
+                .filter(|entity| !used.contains(&entity.id()))
+                .map(|entity| (entity.id(), entity.name().to_string()))
+                .collect();
+
+            (!unused.is_empty()).then(|| settings::UnusedEntities {
+                entity_type: entity_type.to_string(),
+                label: label.to_string(),
+                entities: unused,
+            })
+        }
+
+        // Every entity id some item points at, by kind
+        let used = |kind: SearchKind| -> BTreeSet<EntityId> {
+            self.items.values().flat_map(|item| references::referenced_ids(item, kind)).collect()
+        };
+
+        [
+            unused("ItemGroup", "Item Groups", &self.item_groups, &used(SearchKind::ItemGroup)),
+            unused("PriceLevel", "Price Levels", &self.price_levels, &used(SearchKind::PriceLevel)),
+            unused("ProductClass", "Product Classes", &self.product_classes, &used(SearchKind::ProductClass)),
+            unused("TaxGroup", "Tax Groups", &self.tax_groups, &used(SearchKind::TaxGroup)),
+            unused("SecurityLevel", "Security Levels", &self.security_levels, &used(SearchKind::SecurityLevel)),
+            unused("RevenueCategory", "Revenue Categories", &self.revenue_categories, &used(SearchKind::RevenueCategory)),
+            unused("ReportCategory", "Report Categories", &self.report_categories, &used(SearchKind::ReportCategory)),
+            unused("ChoiceGroup", "Choice Groups", &self.choice_groups, &used(SearchKind::ChoiceGroup)),
+            unused("PrinterLogical", "Printer Logicals", &self.printer_logicals, &used(SearchKind::PrinterLogical)),
+        ]
+        .into_iter()
+        .flatten()
+        .collect()
+    }
+
+    /// Runs the data checks that aren't covered by per-entity validation on save
+    /// Copies the next file of a data directory change, or switches over once all are copied
+    fn continue_data_dir_change(&mut self) -> Task<Message> {
+        let Some(change) = self.data_dir_state.change.take() else {
+            return Task::none();
+        };
+        let Some(transfer) = change.transfer else {
+            self.data_dir_state.change = Some(change);
+            return Task::none();
+        };
 
-                    // Only create new edit state if we're not already editing this price_level
-                    if !already_editing {
-                        if let Some(price_level) = self.price_levels.get(&id) {
-                            let edit_state = price_levels::PriceLevelEditState::new(&price_level);
-                            
-                            self.price_level_edit_state_vec.push(edit_state);
-                        }
-                    }
+        match change.files.get(change.copied.len()).cloned() {
+            Some(from) => {
+                let to = change.target.join(from.file_name().unwrap_or_default());
+                self.data_dir_state.change = Some(change);
 
-                    self.screen = Screen::PriceLevels;
-                    Task::none()
-                },
-                price_levels::Operation::SaveAll(id, edit_state) => {
-                    // First, find the edit state for this price_level
-                    if let Some(edit_state) = self.price_level_edit_state_vec
-                        .iter()
-                        .find(|state| state.base.id.parse::<i32>().unwrap() == id)
-                    {
-                        // Clone the edit state name since we'll need it after removing the edit state
-                        let new_name = edit_state.base.name.clone();
-                        
-                        // Get a mutable reference to the price_level and update it
-                        if let Some(price_level) = self.price_levels.get_mut(&id) {
-                            price_level.name = new_name;
-                        }
-                    }
+                Task::perform(
+                    persistence::copy_data_file(from, to),
+                    |result| Message::Settings(settings::Message::DataFileCopied(result))
+                )
+            }
+            None => {
+                self.finish_data_dir_change(change, transfer);
+                Task::none()
+            }
+        }
+    }
 
-                    self.price_level_edit_state_vec.retain(|edit| {
-                        edit.base.id.parse::<i32>().unwrap() != id
-                    });
+    /// Points the file manager and the save file at the new data directory, and clears
+    /// out the old one after a move
+    fn finish_data_dir_change(&mut self, mut change: settings::DataDirChange, transfer: settings::DataDirTransfer) {
+        let old_dir = self.file_manager.data_dir().to_path_buf();
 
-                    self.save_state().expect("Failed to save to file.");
-                    self.screen = Screen::PriceLevels;
-                    Task::none()
-                },
-                price_levels::Operation::UpdateName(id, new_name) => {
+        if let Err(e) = self.file_manager.set_data_dir(change.target.clone()) {
+            change.transfer = None;
+            change.error = Some(e);
+            self.data_dir_state.change = Some(change);
+            return;
+        }
 
-                    if let Some(edit_state) = self.price_level_edit_state_vec
-                        .iter_mut()
-                        .find(|state| state.base.id.parse::<i32>().unwrap() == id) 
-                        { 
-                            //check if name var is less than 17 characters
-                            if new_name.len() < 17 {
-                                // Update the name
-                                edit_state.base.name = new_name;
-                            } else {
-                                //set validation error message if it's longer than 16 characters
-                                edit_state.base.name_validation_error = Some("Must be less than 16 characters".to_string());
-                            }
-                        }
+        // The save file follows the directory, unless it was kept somewhere else
+        let save_file = std::path::PathBuf::from(&self.settings.file_path);
+        if save_file.parent() == Some(old_dir.as_path()) {
+            if let Some(name) = save_file.file_name() {
+                self.settings.file_path = change.target.join(name).to_string_lossy().into_owned();
+            }
+        }
 
-                    self.screen = Screen::PriceLevels;
-                    Task::none()
-                },
-                price_levels::Operation::CreateNew => {
-                    let next_id = self.price_levels
-                        .keys()
-                        .max()
-                        .map_or(1, |max_id| max_id + 1);
+        let result = if transfer == settings::DataDirTransfer::SwitchOnly && change.target_has_data {
+            self.load_state().map(|_| self.run_data_checks())
+        } else {
+            self.save_state_now()
+        };
+        if let Err(e) = result {
+            self.error_message = Some(e);
+        }
 
-                    let price_level = PriceLevel {
-                        id: next_id,
-                        name: String::new(),
-                        level_type: PriceLevelType::Enterprise,
-                        price: Decimal::new(000, 2),
-                    };
+        self.data_dir_state.status = match transfer {
+            settings::DataDirTransfer::SwitchOnly => format!("Now using {}", change.target.display()),
+            settings::DataDirTransfer::Copy => format!(
+                "Copied {} files to {}, the old folder was left as it was",
+                change.copied.len(),
+                change.target.display()
+            ),
+            settings::DataDirTransfer::Move => {
+                let leftovers: Vec<String> = change.files
+                    .iter()
+                    .filter(|path| std::fs::remove_file(path).is_err())
+                    .map(|path| path.display().to_string())
+                    .collect();
 
-                    self.price_levels.insert(next_id, price_level.clone());
+                if leftovers.is_empty() {
+                    format!("Moved {} files to {}", change.copied.len(), change.target.display())
+                } else {
+                    format!(
+                        "Moved to {}, but these could not be removed from the old folder: {}",
+                        change.target.display(),
+                        leftovers.join(", ")
+                    )
+                }
+            }
+        };
+        println!("{}", self.data_dir_state.status);
 
-                    let edit_state = price_levels::PriceLevelEditState::new(&price_level);
-                    
-                    self.price_level_edit_state_vec.push(edit_state);
+        self.data_dir_state.change = None;
+        self.screen = Screen::Settings(self.settings.clone());
+    }
 
-                    Task::none()
-                },
-                price_levels::Operation::CancelEdit(id) => {
-                    let price_level = self.price_levels.get(&id).expect("I created an editstate without a PriceLevel?");
+    /// Items as the POS exports write them, see printer_logicals::items_for_export
+    fn items_for_export(&self) -> BTreeMap<EntityId, Item> {
+        printer_logicals::items_for_export(&self.items, &self.printer_logicals, self.settings.inactive_printer_remap)
+    }
 
-                    // Find the edit state and reset it before removing
-                    if let Some(edit_state) = self.price_level_edit_state_vec
-                        .iter_mut()
-                        .find(|state| state.base.id.parse::<i32>().unwrap() == id) 
-                        {
-                            if price_level.name.len() < 1 
-                            {
-                                self.price_levels.remove(&id);
-                            }   
-                            
-                            // Reset the data to original values if needed
-                            edit_state.reset();
-                        }
+    /// Refreshes the data checks report, run after loads, imports and bulk changes
+    fn run_data_checks(&mut self) {
+        self.data_checks.report = self.validate_all();
+        self.data_checks.button_collisions = items::button_collisions::button_collisions(&self.items, &self.item_groups);
+    }
 
-                    // Remove the edit state from the vec
-                    self.price_level_edit_state_vec.retain(|state| {
-                    state.base.id.parse::<i32>().unwrap() != id
-                    });
+    pub fn validate_all(&self) -> Vec<String> {
+        let mut report = Vec::new();
 
-                    self.screen = Screen::PriceLevels;
-                    Task::none()
-                },
-            },
-            Operation::SuperEdit(op) => match op {
-                superedit::Operation::UpdateItem(modified_item) => {
-                    Task::none()
+        for item in self.items.values() {
+            if let Some(group) = item.item_group.and_then(|id| self.item_groups.get(&id)) {
+                if item.id < group.id_range.start || item.id > group.id_range.end {
+                    report.push(format!(
+                        "Item {} ({}): ID is outside item group '{}' range ({}-{})",
+                        item.id, item.name, group.name, group.id_range.start, group.id_range.end
+                    ));
                 }
             }
+
+            for problem in items::store_fields::store_field_problems(item, self.settings.max_store_id) {
+                report.push(format!("Item {} ({}): {}", item.id, item.name, problem));
+            }
+            if let Some(problem) = self.settings.item_id_range.problem(item.id) {
+                report.push(format!("Item {} ({}): {}", item.id, item.name, problem));
+            }
+
+            // The POS rejects items without any price, e.g. after deleting their only price level
+            if items::Classification::Price.is_missing(item) {
+                report.push(format!("Item {} ({}): has no default price or price level prices", item.id, item.name));
+            }
+            if self.settings.require_ask_price_prompt
+                && item.ask_price
+                && item.ask_price_prompt.as_deref().unwrap_or_default().trim().is_empty()
+            {
+                report.push(format!("Item {} ({}): asks for a price but has no till prompt", item.id, item.name));
+            }
+
+            let mismatches = item.price_level_mismatches();
+            if !mismatches.is_empty() {
+                report.push(format!(
+                    "Item {} ({}): price levels and item prices are out of sync for level(s) {}",
+                    item.id,
+                    item.name,
+                    mismatches.iter().map(|id| id.to_string()).collect::<Vec<_>>().join(", ")
+                ));
+            }
         }
-    }
 
-    pub fn save_state(&self) -> Result<(), String> {
-        //println!("Save State Triggered!");
-        let state = persistence::AppState {
-            items: self.items.values().cloned().collect(),
-            item_groups: self.item_groups.values().cloned().collect(),
-            price_levels: self.price_levels.values().cloned().collect(),
-            product_classes: self.product_classes.values().cloned().collect(),
-            tax_groups: self.tax_groups.values().cloned().collect(),
-            security_levels: self.security_levels.values().cloned().collect(),
-            revenue_categories: self.revenue_categories.values().cloned().collect(),
-            report_categories: self.report_categories.values().cloned().collect(),
-            choice_groups: self.choice_groups.values().cloned().collect(),
-            printer_logicals: self.printer_logicals.values().cloned().collect(),
-            settings: self.settings.clone(),
-        };
+        for item in self.items.values() {
+            if let Some(problem) = item.primary_printer_problem(&self.printer_logicals) {
+                report.push(format!("Item {} ({}): {}", item.id, item.name, problem));
+            }
+            for problem in item.reference_problems(&self.choice_groups, &self.price_levels) {
+                report.push(format!("Item {} ({}): {}", item.id, item.name, problem));
+            }
+        }
 
-        if self.settings.create_backups {
-            self.file_manager.create_backup(std::path::Path::new(&self.settings.file_path))?;
+        for item in printer_logicals::items_without_active_printer(&self.items, &self.printer_logicals) {
+            report.push(format!("Item {} ({}): every printer it routes to is inactive", item.id, item.name));
         }
 
-        persistence::save_to_file(&state, &self.settings.file_path)
-    }
+        report.extend(entity_component::export_key_problems(
+            "Printer Logical",
+            self.printer_logicals.values().map(|printer| (printer.id, printer.name.as_str(), printer.export_key.as_str())),
+        ));
+        report.extend(entity_component::export_key_problems(
+            "Tax Group",
+            self.tax_groups.values().map(|group| (group.id, group.name.as_str(), group.export_key.as_str())),
+        ));
 
-    fn handle_save_error(&mut self, error: String) {
-        self.error_message = Some(error);
-        // Switch to settings screen to show error
-        self.screen = Screen::Settings(self.settings.clone());
+        report
     }
 
     pub fn load_state(&mut self) -> Result<(), String> {
@@ -2999,7 +6523,15 @@ impl MenuBuilder {
             return Ok(());  // Not an error if file doesn't exist yet
         }
 
-        let state = persistence::load_from_file(&self.settings.file_path)?;
+        let loaded_from = self.settings.file_path.clone();
+        let (mut state, repairs) = persistence::load_repaired(&loaded_from)?;
+        if !repairs.is_empty() {
+            println!("Repaired {} duplicate IDs in save file", repairs.len());
+            self.import_message = Some(format!(
+                "The save file contained duplicate IDs. These entries were given new IDs:\n{}",
+                repairs.join("\n")
+            ));
+        }
 
         // Convert Vec to BTreeMap using id as key
         self.items = state.items.into_iter().map(|i| (i.id, i)).collect();
@@ -3012,7 +6544,9 @@ impl MenuBuilder {
         self.report_categories = state.report_categories.into_iter().map(|i| (i.id, i)).collect();
         self.choice_groups = state.choice_groups.into_iter().map(|i| (i.id, i)).collect();
         self.printer_logicals = state.printer_logicals.into_iter().map(|i| (i.id, i)).collect();
+        self.deleted_items = std::mem::take(&mut state.deleted_items);
         self.settings = state.settings.clone();
+        self.rebuild_search_index();
 
         // Only update settings if they exist in the loaded state
         if state.settings.file_path.is_empty() {
@@ -3027,11 +6561,27 @@ impl MenuBuilder {
             self.settings = state.settings;
         }
 
+        // A synced folder carries another machine's path, keep saving where this was read from
+        self.settings.file_path = loaded_from;
+
+        // Items can be deleted by an import or another copy of the save file
+        self.settings.pinned_items.retain(|id| self.items.contains_key(id));
+
         Ok(())
     }
 
     fn subscription(&self) -> Subscription<Message> {
-        event::listen_with(handle_event)
+        // Only tick while an item is being edited
+        let draft_autosave = match self.screen {
+            Screen::Items(items::Mode::Edit) => iced::time::every(std::time::Duration::from_secs(30))
+                .map(|_| Message::AutosaveDraft),
+            _ => Subscription::none(),
+        };
+
+        Subscription::batch([
+            event::listen_with(handle_event),
+            draft_autosave,
+        ])
     }
 }
 
@@ -3087,43 +6637,648 @@ impl EntityResolver for MenuBuilder {
 pub enum HotKey {
     Escape,
     Tab(Modifiers),
+    ToggleEdit,
+    SaveEdit,
+}
+
+/// Shown instead of the app when the data directory can't be used at launch
+fn startup_error_view(problem: &str, text_style: TextStyle) -> Element<'_, Message> {
+    container(
+        container(
+            column![
+                text("Can't Open the Menu Data").style(Modern::primary_text()).size(text_style.heading()),
+                text(problem).style(Modern::red_text()).size(text_style.body()),
+                text("Pick a folder you can write to, like one in your Documents. The menu data will be kept there from now on.")
+                    .style(Modern::secondary_text())
+                    .size(text_style.body()),
+                row![
+                    button("Choose Data Folder").on_press(Message::ChooseStartupDataDir).style(Modern::primary_button()),
+                    iced::widget::horizontal_space(),
+                    button("Quit").on_press(Message::Quit).style(Modern::secondary_button()),
+                ],
+            ]
+            .spacing(15)
+            .padding(20)
+            .width(500)
+        ).style(Modern::accent_container())
+    )
+    .center_x(Length::Fill)
+    .center_y(Length::Fill)
+    .into()
 }
 
-fn handle_event(event: event::Event, _status: event::Status, id: iced::window::Id) -> Option<Message> {
+fn handle_event(event: event::Event, status: event::Status, id: iced::window::Id) -> Option<Message> {
     match event {
         event::Event::Keyboard(keyboard::Event::KeyPressed { key, modifiers, .. }) => {
             match key {
                 Key::Named(keyboard::key::Named::Escape) => Some(Message::HotKey(HotKey::Escape)),
                 Key::Named(keyboard::key::Named::Tab) => Some(Message::HotKey(HotKey::Tab(modifiers))),
+                // Ctrl+E (Cmd+E on macOS), left alone while a text input has the key
+                Key::Character(c) if c.as_str() == "e"
+                    && modifiers.command()
+                    && status == event::Status::Ignored => Some(Message::HotKey(HotKey::ToggleEdit)),
+                // Ctrl+S (Cmd+S on macOS) saves the form being edited, also while typing in it
+                Key::Character(c) if c.as_str() == "s" && modifiers.command() => Some(Message::HotKey(HotKey::SaveEdit)),
+                // Row focus and selection in a SuperEdit table, unless a text input has the key
+                Key::Named(keyboard::key::Named::ArrowDown) if status == event::Status::Ignored =>
+                    Some(Message::TableKey(id, items::preview_changes::Message::MoveFocus(true))),
+                Key::Named(keyboard::key::Named::ArrowUp) if status == event::Status::Ignored =>
+                    Some(Message::TableKey(id, items::preview_changes::Message::MoveFocus(false))),
+                Key::Named(keyboard::key::Named::Space) if status == event::Status::Ignored =>
+                    Some(Message::TableKey(id, items::preview_changes::Message::ToggleFocused)),
                 _ => None,
             }
         }
+        // Clicks can't tell which keys are held, tables keep track for ctrl- and shift-click
+        event::Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) =>
+            Some(Message::TableKey(id, items::preview_changes::Message::ModifiersChanged(modifiers))),
         event::Event::Window(window::Event::FileDropped(path)) => Some(Message::FileDropped(path)),
+        event::Event::Window(window::Event::CloseRequested) => Some(Message::WindowCloseRequested(id)),
         event::Event::Window(window::Event::Closed) => Some(Message::WindowClosed(id)),
-        event::Event::Window(window::Event::Resized(size)) => Some(Message::WindowResized(size)),
+        event::Event::Window(window::Event::Resized(size)) => Some(Message::WindowResized(id, size)),
+        event::Event::Window(window::Event::Focused) => Some(Message::WindowFocused(id, true)),
+        event::Event::Window(window::Event::Unfocused) => Some(Message::WindowFocused(id, false)),
         //event::Event::Window(window::Event::Opened { position: _, size: _ }) => Some(Message::WindowMessage),
         _ => None,
     }
 }
 
-#[derive(Default, Debug, Clone, PartialEq)]
+/// Which kind of window to open
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
 pub enum WindowEnum {
     #[default]
     MainWindow,
     SuperEdit,
 }
 
+/// What a window shows, along with the state only that window uses.
+/// The entity maps stay on MenuBuilder and are shared by every window,
+/// the main window's screens too since there is only ever one of it.
+#[derive(Debug, Clone)]
+pub enum WindowKind {
+    Main,
+    SuperEdit(SuperEdit),
+}
+
 #[derive(Debug, Clone,)]
 pub struct Window {
     pub title: String,
-    pub windowtype: WindowEnum,
+    pub kind: WindowKind,
+    pub size: Size,
+    pub focused: bool,
 }
 
 impl Window {
-    pub fn new(id: window::Id, title: String, window_type: WindowEnum) -> Self {
+    pub fn new(title: String, kind: WindowKind) -> Self {
         Self {
             title: title,
-            windowtype: window_type,
+            kind,
+            size: Size::ZERO,
+            focused: true,
+        }
+    }
+
+    pub fn windowtype(&self) -> WindowEnum {
+        match self.kind {
+            WindowKind::Main => WindowEnum::MainWindow,
+            WindowKind::SuperEdit(_) => WindowEnum::SuperEdit,
+        }
+    }
+
+    pub fn is_main(&self) -> bool {
+        self.windowtype() == WindowEnum::MainWindow
+    }
+}
+
+// Databases with more items than this need a typed confirmation before an overwrite import
+const TYPED_CONFIRMATION_THRESHOLD: usize = 50;
+
+/// What an overwrite import would replace, shown before anything is deleted
+#[derive(Debug, Clone)]
+pub struct OverwriteReview {
+    pub current_items: usize,
+    pub current_entities: usize,
+    pub last_saved: Option<String>,
+    pub incoming: Result<usize, String>, // Parsed item count, or why the file couldn't be read
+    pub confirmation: String,
+}
+
+impl OverwriteReview {
+    pub fn needs_typed_confirmation(&self) -> bool {
+        self.current_items > TYPED_CONFIRMATION_THRESHOLD
+    }
+
+    /// Typing DELETE or the current item count both count as confirming
+    pub fn is_confirmed(&self) -> bool {
+        if self.incoming.is_err() {
+            return false;
+        }
+        if !self.needs_typed_confirmation() {
+            return true;
+        }
+
+        let typed = self.confirmation.trim();
+        typed == "DELETE" || typed == self.current_items.to_string()
+    }
+}
+
+/// Appends an optional note, like a snapshot location, to a notice message
+fn with_note(message: String, note: Option<String>) -> String {
+    match note {
+        Some(note) => format!("{}\n\n{}", message, note),
+        None => message,
+    }
+}
+
+/// Compact form of an item draft, used to skip autosaves when nothing changed
+fn draft_fingerprint(item: &Item, prices: &Option<Vec<(EntityId, String)>>) -> String {
+    ron::to_string(&(item, prices)).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn view_context_borrows_the_app_data() {
+        let app = MenuBuilder::default();
+        let context = app.view_context();
+
+        assert!(std::ptr::eq(context.available_items, &app.items));
+        assert!(std::ptr::eq(context.available_item_groups, &app.item_groups));
+        assert!(std::ptr::eq(context.available_tax_groups, &app.tax_groups));
+        assert!(std::ptr::eq(context.available_security_levels, &app.security_levels));
+        assert!(std::ptr::eq(context.available_revenue_categories, &app.revenue_categories));
+        assert!(std::ptr::eq(context.available_report_categories, &app.report_categories));
+        assert!(std::ptr::eq(context.available_product_classes, &app.product_classes));
+        assert!(std::ptr::eq(context.available_choice_groups, &app.choice_groups));
+        assert!(std::ptr::eq(context.available_printer_logicals, &app.printer_logicals));
+        assert!(std::ptr::eq(context.available_price_levels, &app.price_levels));
+    }
+
+    fn app_with_item(id: EntityId, name: &str) -> MenuBuilder {
+        let mut app = MenuBuilder::default();
+        app.items.insert(id, Item { id, name: name.to_string(), ..Item::default() });
+        app
+    }
+
+    fn quick_edit_name(app: &mut MenuBuilder, id: EntityId, name: &str) {
+        let _ = app.perform(Operation::Items(id, items::Operation::StartQuickEdit(items::QuickEditField::Name)));
+        let _ = app.perform(Operation::Items(id, items::Operation::UpdateQuickEdit(name.to_string())));
+        let _ = app.perform(Operation::Items(id, items::Operation::CommitQuickEdit));
+    }
+
+    #[test]
+    fn quick_edit_saves_and_can_be_undone() {
+        let mut app = app_with_item(1, "Cola");
+
+        quick_edit_name(&mut app, 1, "Coke");
+        assert_eq!(app.items[&1].name, "Coke");
+        assert!(app.save_requested);
+
+        let _ = app.perform(Operation::Items(1, items::Operation::UndoQuickEdit(1)));
+        assert_eq!(app.items[&1].name, "Cola");
+        assert!(app.quick_edit_undo.is_none());
+    }
+
+    #[test]
+    fn unchanged_quick_edit_saves_nothing() {
+        let mut app = app_with_item(1, "Cola");
+
+        quick_edit_name(&mut app, 1, "Cola");
+        assert!(!app.save_requested);
+        assert!(app.quick_edit_undo.is_none());
+        assert!(app.items[&1].updated_at.is_none());
+    }
+
+    #[test]
+    fn quick_edit_undo_keeps_later_changes() {
+        let mut app = app_with_item(1, "Cola");
+
+        quick_edit_name(&mut app, 1, "Coke");
+        app.items.get_mut(&1).unwrap().name = "Pepsi".to_string();
+
+        let _ = app.perform(Operation::Items(1, items::Operation::UndoQuickEdit(1)));
+        assert_eq!(app.items[&1].name, "Pepsi");
+    }
+
+    #[test]
+    fn save_load_save_leaves_the_file_and_backups_alone() {
+        let path = std::env::temp_dir().join(format!("menu_builder_{}_app_round_trip.ron", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let mut app = app_with_item(1, "Cola");
+        app.startup_error = None;
+        app.settings.file_path = path.to_string_lossy().to_string();
+        app.settings.create_backups = true;
+        app.save_state_now().unwrap();
+        let saved = std::fs::read(&path).unwrap();
+
+        let mut reloaded = MenuBuilder::default();
+        reloaded.startup_error = None;
+        reloaded.settings.file_path = path.to_string_lossy().to_string();
+        reloaded.load_state().unwrap();
+        reloaded.save_state_now().unwrap();
+
+        assert_eq!(std::fs::read(&path).unwrap(), saved);
+        let backups = std::fs::read_dir(path.parent().unwrap())
+            .unwrap()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with(&format!("menu_builder_{}_app_round_trip_backup_", std::process::id())))
+            .count();
+        assert_eq!(backups, 0);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    fn escape(app: &mut MenuBuilder) {
+        let _ = app.update(Message::HotKey(HotKey::Escape));
+    }
+
+    #[test]
+    fn escape_closes_popups_in_stacking_order() {
+        let mut app = MenuBuilder::default();
+        app.template_picker = Some(templates::TemplatePicker::default());
+        app.export_preview = Some(export_preview::ExportPreview::new(export_preview::ExportKind::Items, Vec::new()));
+        app.find_replace = Some(items::find_replace::FindReplace::default());
+        app.overwrite_review = Some(OverwriteReview {
+            current_items: 0,
+            current_entities: 0,
+            last_saved: None,
+            incoming: Ok(0),
+            confirmation: String::new(),
+        });
+        app.json_merge_review = Some(json_interchange::JsonImportReport::default());
+        app.confirm_quit = Some(iced::window::Id::unique());
+
+        escape(&mut app);
+        assert!(app.template_picker.is_none());
+        assert!(app.export_preview.is_some());
+
+        escape(&mut app);
+        assert!(app.export_preview.is_none());
+        assert!(app.find_replace.is_some());
+
+        escape(&mut app);
+        assert!(app.find_replace.is_none());
+        assert!(app.overwrite_review.is_some());
+
+        escape(&mut app);
+        assert!(app.overwrite_review.is_none());
+        assert!(app.json_merge_review.is_some());
+
+        escape(&mut app);
+        assert!(app.json_merge_review.is_none());
+        assert!(app.confirm_quit.is_some());
+
+        escape(&mut app);
+        assert!(app.confirm_quit.is_none());
+    }
+
+    #[test]
+    fn escape_closes_the_popup_before_the_edit_under_it() {
+        let mut app = app_with_item(1, "Cola");
+        let _ = app.perform(Operation::Items(1, items::Operation::StartQuickEdit(items::QuickEditField::Name)));
+        app.confirm_quit = Some(iced::window::Id::unique());
+
+        escape(&mut app);
+        assert!(app.confirm_quit.is_none());
+        assert!(app.item_quick_edit.is_some());
+
+        escape(&mut app);
+        assert!(app.item_quick_edit.is_none());
+    }
+
+    #[test]
+    fn escape_with_nothing_open_changes_nothing() {
+        let mut app = app_with_item(1, "Cola");
+
+        escape(&mut app);
+        assert_eq!(app.items[&1].name, "Cola");
+        assert!(!app.save_requested);
+    }
+
+    fn app_editing_item(dir: &str) -> MenuBuilder {
+        let mut app = app_with_item(1, "Cola");
+        app.items.insert(2, Item { id: 2, name: "Lemonade".to_string(), ..Item::default() });
+        // Cancelling an edit removes the draft recovery file, keep that away from the real data folder
+        app.file_manager.use_data_dir(std::env::temp_dir().join(format!("menu_builder_{}_{}", dir, std::process::id())));
+        let _ = app.perform(Operation::Items(1, items::Operation::StartEdit));
+        app
+    }
+
+    #[test]
+    fn selecting_another_item_from_a_clean_edit_does_not_prompt() {
+        let mut app = app_editing_item("clean_edit");
+        assert!(!app.has_unsaved_item_edit());
+
+        let _ = app.perform(Operation::Items(2, items::Operation::Select(2)));
+        assert!(!app.confirm_leave_edit);
+        assert_eq!(app.selected_item_id, Some(2));
+        assert!(matches!(app.screen, Screen::Items(items::Mode::View)));
+    }
+
+    #[test]
+    fn selecting_another_item_from_a_dirty_edit_prompts_first() {
+        let mut app = app_editing_item("dirty_edit");
+        app.draft_item.name = "Coke".to_string();
+        assert!(app.has_unsaved_item_edit());
+
+        let _ = app.perform(Operation::Items(2, items::Operation::Select(2)));
+        assert!(app.confirm_leave_edit);
+        assert!(matches!(app.screen, Screen::Items(items::Mode::Edit)));
+
+        // Keep editing leaves the draft as it was
+        let _ = app.update(Message::KeepEditingItem);
+        assert!(!app.confirm_leave_edit);
+        assert_eq!(app.draft_item.name, "Coke");
+
+        // Discarding drops the draft and goes on to the item that was picked
+        let _ = app.perform(Operation::Items(2, items::Operation::Select(2)));
+        let _ = app.update(Message::DiscardItemEdit);
+        assert_eq!(app.selected_item_id, Some(2));
+        assert_eq!(app.items[&1].name, "Cola");
+        assert!(matches!(app.screen, Screen::Items(items::Mode::View)));
+    }
+
+    #[test]
+    fn overwrite_import_keeps_the_light_theme() {
+        let dir = std::env::temp_dir().join(format!("menu_builder_{}_theme_import", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let mut app = app_with_item(1, "Cola");
+        app.settings.file_path = dir.join("menu.ron").to_string_lossy().to_string();
+        app.settings.app_theme = settings::ThemeChoice::Light;
+        app.theme = Modern::light_theme();
+        app.pending_json_import = Some(serde_json::from_str(r#"{"schema_version": 1, "items": []}"#).unwrap());
+        app.overwrite_review = Some(OverwriteReview {
+            current_items: 1,
+            current_entities: 0,
+            last_saved: None,
+            incoming: Ok(0),
+            confirmation: String::new(),
+        });
+
+        let _ = app.update(Message::ImportItemsOverwriteExisting);
+
+        assert!(app.items.is_empty(), "the import should have replaced the data");
+        assert_eq!(app.settings.app_theme, settings::ThemeChoice::Light);
+        assert_eq!(app.theme, Modern::light_theme());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn typing_in_an_entity_row_leaves_the_screen_alone() {
+        let mut app = MenuBuilder::default();
+        app.item_groups.insert(1, ItemGroup { id: 1, name: "Drinks".to_string(), id_range: 1..100, ..ItemGroup::default() });
+        app.tax_groups.insert(2, TaxGroup { id: 2, name: "Food".to_string(), ..TaxGroup::default() });
+        let _ = app.perform(Operation::ItemGroups(1, item_groups::Operation::EditItemGroup(1)));
+        let _ = app.perform(Operation::TaxGroups(2, tax_groups::Operation::EditTaxGroup(2)));
+
+        // Reassigning the screen rebuilds the list and loses its scroll position,
+        // so the handlers must leave whatever screen is showing in place
+        app.screen = Screen::ItemGroups;
+        let _ = app.perform(Operation::ItemGroups(1, item_groups::Operation::UpdateName(1, "Beverages".to_string())));
+        let _ = app.perform(Operation::ItemGroups(1, item_groups::Operation::UpdateIdRangeStart(1, "5".to_string())));
+        let _ = app.perform(Operation::ItemGroups(1, item_groups::Operation::UpdateIdRangeEnd(1, "50".to_string())));
+        let _ = app.perform(Operation::TaxGroups(2, tax_groups::Operation::UpdateName(2, "Meals".to_string())));
+        let _ = app.perform(Operation::TaxGroups(2, tax_groups::Operation::UpdateTaxRate(2, "0.07".to_string())));
+        assert!(matches!(app.screen, Screen::ItemGroups));
+
+        assert_eq!(app.item_group_edit_state_vec[0].base.name, "Beverages");
+        assert_eq!(app.item_group_edit_state_vec[0].id_range_start, "5");
+        assert_eq!(app.tax_group_edit_state_vec[0].base.name, "Meals");
+        assert!(!app.save_requested, "typing shouldn't save until the row is saved");
+    }
+
+    #[test]
+    fn whitespace_only_entity_names_show_an_error_while_typing() {
+        let mut app = MenuBuilder::default();
+        app.choice_groups.insert(1, ChoiceGroup { id: 1, name: "Sides".to_string(), ..ChoiceGroup::default() });
+        let _ = app.perform(Operation::ChoiceGroups(1, choice_groups::Operation::EditChoiceGroup(1)));
+
+        let _ = app.perform(Operation::ChoiceGroups(1, choice_groups::Operation::UpdateName(1, "   ".to_string())));
+        assert_eq!(app.choice_group_edit_state_vec[0].name_validation_error, Some("Name cannot be only spaces".to_string()));
+
+        let _ = app.perform(Operation::ChoiceGroups(1, choice_groups::Operation::UpdateName(1, "Sauces".to_string())));
+        assert_eq!(app.choice_group_edit_state_vec[0].name_validation_error, None);
+    }
+
+    #[test]
+    fn undo_import_drops_a_superedit_preview_of_the_replaced_data() {
+        let dir = std::env::temp_dir().join(format!("menu_builder_{}_undo_superedit", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let mut app = app_with_item(1, "Cola");
+        app.settings.file_path = dir.join("menu.ron").to_string_lossy().to_string();
+        for id in 1..=2 {
+            app.item_groups.insert(id, ItemGroup { id, name: format!("Group {}", id), ..ItemGroup::default() });
+        }
+        app.items.get_mut(&1).unwrap().item_group = Some(1);
+        app.import_undo = Some(app.app_state());
+        app.items.get_mut(&1).unwrap().name = "Imported".to_string();
+
+        // Preview a swap over the imported data, then undo the import under it
+        let window_id = iced::window::Id::unique();
+        app.windows.insert(window_id, Window::new("SuperEdit".to_string(), WindowKind::SuperEdit(app.new_super_editor())));
+        for message in [
+            superedit::Message::UpdateActionSwapFrom(0, 1),
+            superedit::Message::UpdateActionEntity(0, 2),
+            superedit::Message::PreviewChanges,
+        ] {
+            let _ = app.update(Message::SuperEdit(window_id, message));
         }
+        let _ = app.update(Message::UndoImport);
+        let _ = app.update(Message::SuperEdit(window_id, superedit::Message::AcceptChanges));
+
+        assert_eq!(app.items[&1].name, "Cola");
+        assert_eq!(app.items[&1].item_group, Some(1));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    fn files_in(dir: &std::path::Path) -> usize {
+        std::fs::read_dir(dir).map_or(0, |entries| entries.count())
+    }
+
+    #[test]
+    fn superedit_snapshots_only_when_there_is_something_to_accept() {
+        let dir = std::env::temp_dir().join(format!("menu_builder_{}_superedit_snapshot", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut app = app_with_item(1, "Cola");
+        app.settings.file_path = dir.join("menu.ron").to_string_lossy().to_string();
+
+        // A window that has closed
+        let _ = app.update(Message::SuperEdit(iced::window::Id::unique(), superedit::Message::AcceptChanges));
+        assert_eq!(files_in(&dir), 0);
+
+        // An open window without a preview
+        let window_id = iced::window::Id::unique();
+        app.windows.insert(window_id, Window::new("SuperEdit".to_string(), WindowKind::SuperEdit(app.new_super_editor())));
+        let _ = app.update(Message::SuperEdit(window_id, superedit::Message::AcceptChanges));
+        assert_eq!(files_in(&dir), 0);
+        assert!(app.import_message.is_none());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn deleting_a_used_entity_tells_where_the_snapshot_went() {
+        let dir = std::env::temp_dir().join(format!("menu_builder_{}_delete_snapshot", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut app = app_with_item(1, "Cola");
+        app.settings.file_path = dir.join("menu.ron").to_string_lossy().to_string();
+        app.tax_groups.insert(2, TaxGroup { id: 2, name: "Food".to_string(), ..TaxGroup::default() });
+        app.items.get_mut(&1).unwrap().tax_group = Some(2);
+
+        let _ = app.update(Message::ConfirmDelete(data_types::DeletionInfo {
+            entity_type: "TaxGroup".to_string(),
+            entity_id: 2,
+            affected_items: vec!["Cola".to_string()],
+        }));
+
+        assert_eq!(app.items[&1].tax_group, None);
+        assert_eq!(files_in(&dir), 1);
+        assert!(app.import_message.as_ref().is_some_and(|message| message.contains("snapshot")));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn typing_an_item_name_does_not_requery_the_search() {
+        let mut app = app_editing_item("search_typing");
+        app.rebuild_search_index();
+        let _ = app.update(Message::Items(1, items::Message::SearchItems("cola".to_string())));
+        let generation = app.search_hits.generation();
+        assert!(app.search_hits.hits().contains(&(SearchKind::Item, 1)));
+
+        let _ = app.update(Message::Items(1, items::Message::Edit(items::edit::Message::UpdateName("Coke".to_string()))));
+        assert_eq!(app.search_hits.generation(), generation);
+        assert_eq!(app.search_index.generation(), generation);
+    }
+
+    #[test]
+    fn form_save_keeps_what_the_edit_did_not_touch() {
+        let mut app = app_with_item(1, "Cola");
+        app.tax_groups.insert(3, TaxGroup { id: 3, name: "Soft Drinks".to_string(), ..TaxGroup::default() });
+        app.items.get_mut(&1).unwrap().tax_group = Some(3);
+        app.file_manager.use_data_dir(std::env::temp_dir().join(format!("menu_builder_form_save_{}", std::process::id())));
+        let _ = app.perform(Operation::Items(1, items::Operation::StartEdit));
+
+        let _ = app.update(Message::Items(1, items::Message::Edit(items::edit::Message::UpdateName("Coke".to_string()))));
+        let draft = app.draft_item.clone();
+        let _ = app.perform(Operation::Items(1, items::Operation::Save(draft)));
+
+        assert_eq!(app.items[&1].name, "Coke");
+        assert_eq!(app.items[&1].tax_group, Some(3));
+        assert_eq!(app.item_edit_state.validation_error, None);
+    }
+
+    #[test]
+    fn form_save_and_quick_edit_store_the_same_rename() {
+        let mut form = app_editing_item("form_rename");
+        let _ = form.update(Message::Items(1, items::Message::Edit(items::edit::Message::UpdateName("Coke".to_string()))));
+        let draft = form.draft_item.clone();
+        let _ = form.perform(Operation::Items(1, items::Operation::Save(draft)));
+
+        let mut quick = app_with_item(1, "Cola");
+        quick_edit_name(&mut quick, 1, "Coke");
+
+        assert_eq!(form.items[&1].name, quick.items[&1].name);
+        assert_eq!(form.items[&1].id, quick.items[&1].id);
+        assert_eq!(form.search_index.query("coke").len(), 1);
+        assert_eq!(quick.search_index.query("coke").len(), 1);
+    }
+
+    #[test]
+    fn form_save_and_quick_edit_refuse_the_same_missing_reference() {
+        let mut form = app_with_item(1, "Cola");
+        form.items.get_mut(&1).unwrap().tax_group = Some(9);
+        form.file_manager.use_data_dir(std::env::temp_dir().join(format!("menu_builder_form_reference_{}", std::process::id())));
+        let _ = form.perform(Operation::Items(1, items::Operation::StartEdit));
+        let _ = form.update(Message::Items(1, items::Message::Edit(items::edit::Message::UpdateName("Coke".to_string()))));
+        let draft = form.draft_item.clone();
+        let _ = form.perform(Operation::Items(1, items::Operation::Save(draft)));
+
+        let mut quick = app_with_item(1, "Cola");
+        quick.items.get_mut(&1).unwrap().tax_group = Some(9);
+        quick_edit_name(&mut quick, 1, "Coke");
+
+        assert_eq!(form.items[&1].name, "Cola");
+        assert_eq!(quick.items[&1].name, "Cola");
+        assert!(form.item_edit_state.validation_error.is_some());
+    }
+
+    fn app_with_out_of_range_item(dir: &str) -> MenuBuilder {
+        let mut app = app_with_item(500, "Cola");
+        app.items.get_mut(&500).unwrap().item_group = Some(1);
+        app.item_groups.insert(1, ItemGroup { id: 1, name: "Drinks".to_string(), id_range: 100..199, ..ItemGroup::default() });
+        let dir = std::env::temp_dir().join(format!("menu_builder_{}_{}", std::process::id(), dir));
+        std::fs::create_dir_all(&dir).unwrap();
+        app.settings.file_path = dir.join("menu.ron").to_string_lossy().to_string();
+        app
+    }
+
+    #[test]
+    fn id_fix_applies_the_previewed_moves() {
+        let mut app = app_with_out_of_range_item("id_fix_apply");
+        let _ = app.perform(Operation::Settings(settings::Operation::PreviewIdFix));
+        assert_eq!(app.data_checks.id_fix_preview, vec![(500, Some(100))]);
+
+        let _ = app.perform(Operation::Settings(settings::Operation::ApplyIdFix));
+        assert!(!app.items.contains_key(&500));
+        assert_eq!(app.items[&100].name, "Cola");
+        assert!(app.data_checks.id_fix_preview.is_empty());
+
+        let _ = std::fs::remove_dir_all(std::path::Path::new(&app.settings.file_path).parent().unwrap());
+    }
+
+    #[test]
+    fn id_fix_previews_again_when_a_target_was_taken() {
+        let mut app = app_with_out_of_range_item("id_fix_taken");
+        let _ = app.perform(Operation::Settings(settings::Operation::PreviewIdFix));
+        app.items.insert(100, Item { id: 100, name: "Tea".to_string(), item_group: Some(1), ..Item::default() });
+
+        let _ = app.perform(Operation::Settings(settings::Operation::ApplyIdFix));
+        assert_eq!(app.items[&500].name, "Cola");
+        assert_eq!(app.items[&100].name, "Tea");
+        assert_eq!(app.data_checks.id_fix_preview, vec![(500, Some(101))]);
+
+        let _ = std::fs::remove_dir_all(std::path::Path::new(&app.settings.file_path).parent().unwrap());
+    }
+
+    #[test]
+    fn loading_a_file_with_duplicate_ids_keeps_every_entry() {
+        let dir = std::env::temp_dir().join(format!("menu_builder_{}_load_duplicates", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("menu.ron");
+        let state = persistence::AppState {
+            items: vec![
+                Item { id: 1, name: "Cola".to_string(), item_group: Some(10), ..Item::default() },
+                Item { id: 1, name: "Fries".to_string(), item_group: Some(10), ..Item::default() },
+            ],
+            item_groups: vec![
+                ItemGroup { id: 10, name: "Drinks".to_string(), ..ItemGroup::default() },
+                ItemGroup { id: 10, name: "Food".to_string(), ..ItemGroup::default() },
+            ],
+            ..persistence::AppState::default()
+        };
+        persistence::save_now(&state, &path, false).unwrap();
+
+        let mut app = MenuBuilder::default();
+        app.settings.file_path = path.to_string_lossy().to_string();
+        app.load_state().unwrap();
+
+        assert_eq!(app.items[&1].name, "Cola");
+        assert_eq!(app.items[&2].name, "Fries");
+        assert_eq!(app.item_groups[&10].name, "Drinks");
+        assert_eq!(app.item_groups[&11].name, "Food");
+        assert!(app.items.values().all(|item| item.item_group == Some(10)));
+        let message = app.import_message.clone().unwrap();
+        assert!(message.contains("Item Group 'Food' had duplicate ID 10, moved to 11"));
+        assert!(message.contains("Item 'Fries' had duplicate ID 1, moved to 2"));
+
+        let _ = std::fs::remove_dir_all(&dir);
     }
 }
@@ -5,6 +5,7 @@ use directories::ProjectDirs;
 use serde::{Serialize, Deserialize};
 use crate::{
     items::Item, 
+    items::delta_export::ItemTombstone,
     item_groups::ItemGroup,
     price_levels::PriceLevel,
     product_classes::ProductClass,
@@ -15,9 +16,12 @@ use crate::{
     choice_groups::ChoiceGroup,
     printer_logicals::PrinterLogical,
     settings::AppSettings,
+    data_types::EntityId,
+    entity_component::Entity,
 };
+use std::collections::HashSet;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppState {
     pub items: Vec<Item>,
     pub item_groups: Vec<ItemGroup>,
@@ -29,14 +33,43 @@ pub struct AppState {
     pub report_categories: Vec<ReportCategory>,
     pub choice_groups: Vec<ChoiceGroup>,
     pub printer_logicals: Vec<PrinterLogical>,
-    pub settings: AppSettings,
+    #[serde(default)]
+    pub settings: AppSettings, // Starter templates carry no settings
+    #[serde(default)]
+    pub deleted_items: Vec<ItemTombstone>, // Recent item deletions, for delta exports
+}
+
+/// Fixed layout for everything written as RON. The default uses the platform's
+/// line ending, which made the same data save differently on Windows.
+fn pretty_config() -> ron::ser::PrettyConfig {
+    ron::ser::PrettyConfig::new()
+        .indentor("    ".to_string())
+        .new_line("\n".to_string())
+        .separate_tuple_members(false)
+        .enumerate_arrays(false)
+}
+
+/// The save file text for a state. Entities are written in id order whatever order
+/// they're held in, so saving the same data always gives the same bytes.
+pub fn serialize_state(state: &AppState) -> Result<String, String> {
+    let serialized = if state.is_sorted_by_id() {
+        ron::ser::to_string_pretty(state, pretty_config())
+    } else {
+        let mut sorted = state.clone();
+        sorted.sort_by_id();
+        ron::ser::to_string_pretty(&sorted, pretty_config())
+    };
+
+    serialized.map_err(|e| format!("Failed to serialize state: {}", e))
 }
 
 pub fn save_to_file(state: &AppState, path: &str) -> Result<(), String> {
-    let serialized = ron::ser::to_string_pretty(
-        state,
-        ron::ser::PrettyConfig::default(),
-    ).map_err(|e| format!("Failed to serialize state: {}", e))?;
+    let serialized = serialize_state(state)?;
+
+    // Nothing changed, leave the file and its modified time alone
+    if fs::read_to_string(path).is_ok_and(|current| current == serialized) {
+        return Ok(());
+    }
 
     fs::write(path, serialized)
         .map_err(|e| format!("Failed to write file: {}", e))?;
@@ -56,6 +89,18 @@ pub fn load_from_file(path: &str) -> Result<AppState, String> {
         .map_err(|e| format!("Failed to parse file: {}", e))
 }
 
+/// Loads a whole database the way every load should, with entries that share an id
+/// given new ones. Returns a line for each entry that was moved.
+pub fn load_repaired(path: &str) -> Result<(AppState, Vec<String>), String> {
+    let mut state = load_from_file(path)?;
+
+    // Collecting into maps would silently drop entries that share an id
+    let repairs = state.repair_duplicate_ids();
+    state.fill_export_keys();
+
+    Ok((state, repairs))
+}
+
 impl Default for AppState {
     fn default() -> Self {
         Self {
@@ -70,45 +115,312 @@ impl Default for AppState {
             choice_groups: Vec::new(),
             printer_logicals: Vec::new(),
             settings: AppSettings::default(),
+            deleted_items: Vec::new(),
+        }
+    }
+}
+
+impl AppState {
+    /// Puts every entity list in id order. Deletions keep their order within an id.
+    pub fn sort_by_id(&mut self) {
+        self.items.sort_by_key(|item| item.id);
+        self.item_groups.sort_by_key(|e| e.id());
+        self.price_levels.sort_by_key(|e| e.id());
+        self.product_classes.sort_by_key(|e| e.id());
+        self.tax_groups.sort_by_key(|e| e.id());
+        self.security_levels.sort_by_key(|e| e.id());
+        self.revenue_categories.sort_by_key(|e| e.id());
+        self.report_categories.sort_by_key(|e| e.id());
+        self.choice_groups.sort_by_key(|e| e.id());
+        self.printer_logicals.sort_by_key(|e| e.id());
+        self.deleted_items.sort_by_key(|tombstone| tombstone.id);
+    }
+
+    // States built from the app's maps are already in order, so saving doesn't need a copy
+    fn is_sorted_by_id(&self) -> bool {
+        in_id_order(&self.items, |item| item.id)
+            && in_id_order(&self.item_groups, |e| e.id())
+            && in_id_order(&self.price_levels, |e| e.id())
+            && in_id_order(&self.product_classes, |e| e.id())
+            && in_id_order(&self.tax_groups, |e| e.id())
+            && in_id_order(&self.security_levels, |e| e.id())
+            && in_id_order(&self.revenue_categories, |e| e.id())
+            && in_id_order(&self.report_categories, |e| e.id())
+            && in_id_order(&self.choice_groups, |e| e.id())
+            && in_id_order(&self.printer_logicals, |e| e.id())
+            && in_id_order(&self.deleted_items, |tombstone| tombstone.id)
+    }
+
+    /// Gives fresh ids to entries that share an id with an earlier entry of the same
+    /// type, so nothing is dropped when the vectors are collected into maps.
+    /// The first entry with an id keeps it, so item references keep pointing at it.
+    /// Entries are processed in file order, which keeps the repair deterministic.
+    /// Returns a line per re-assigned entry, empty if nothing needed repairing.
+    pub fn repair_duplicate_ids(&mut self) -> Vec<String> {
+        let mut summary = Vec::new();
+
+        repair_entity_ids(&mut self.item_groups, "Item Group", &mut summary);
+        repair_entity_ids(&mut self.price_levels, "Price Level", &mut summary);
+        repair_entity_ids(&mut self.product_classes, "Product Class", &mut summary);
+        repair_entity_ids(&mut self.tax_groups, "Tax Group", &mut summary);
+        repair_entity_ids(&mut self.security_levels, "Security Level", &mut summary);
+        repair_entity_ids(&mut self.revenue_categories, "Revenue Category", &mut summary);
+        repair_entity_ids(&mut self.report_categories, "Report Category", &mut summary);
+        repair_entity_ids(&mut self.choice_groups, "Choice Group", &mut summary);
+        repair_entity_ids(&mut self.printer_logicals, "Printer Logical", &mut summary);
+
+        let mut seen: HashSet<EntityId> = HashSet::new();
+        let mut next_id = self.items.iter().map(|item| item.id).max().unwrap_or(0) + 1;
+
+        for item in self.items.iter_mut() {
+            if !seen.insert(item.id) {
+                summary.push(format!(
+                    "Item '{}' had duplicate ID {}, moved to {}",
+                    item.name, item.id, next_id
+                ));
+                item.id = next_id;
+                seen.insert(next_id);
+                next_id += 1;
+            }
+        }
+
+        summary
+    }
+
+    /// Files from before export keys were added export under the current name
+    pub fn fill_export_keys(&mut self) {
+        for printer in self.printer_logicals.iter_mut().filter(|printer| printer.export_key.is_empty()) {
+            printer.export_key = printer.name.clone();
+        }
+        for group in self.tax_groups.iter_mut().filter(|group| group.export_key.is_empty()) {
+            group.export_key = group.name.clone();
+        }
+    }
+}
+
+fn in_id_order<T>(entities: &[T], id: impl Fn(&T) -> EntityId) -> bool {
+    entities.windows(2).all(|pair| id(&pair[0]) <= id(&pair[1]))
+}
+
+fn repair_entity_ids<T: Entity>(entities: &mut Vec<T>, type_name: &str, summary: &mut Vec<String>) {
+    let mut seen: HashSet<EntityId> = HashSet::new();
+    let mut next_id = entities.iter().map(|e| e.id()).max().unwrap_or(0) + 1;
+
+    for entity in entities.iter_mut() {
+        if !seen.insert(entity.id()) {
+            summary.push(format!(
+                "{} '{}' had duplicate ID {}, moved to {}",
+                type_name, entity.name(), entity.id(), next_id
+            ));
+            *entity = entity.with_id(next_id);
+            seen.insert(next_id);
+            next_id += 1;
         }
     }
 }
 
+/// An unsaved item edit, written periodically so it survives a crash
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DraftRecovery {
+    pub saved_at: String,
+    pub draft_item_id: Option<EntityId>,
+    pub item: Item,
+    pub prices: Option<Vec<(EntityId, String)>>,
+}
+
+/// How many snapshots of each operation are kept, older ones are pruned when a new one is written.
+/// Ordinary save backups are never pruned.
+const SNAPSHOTS_TO_KEEP: usize = 10;
+
+/// Name of the sidecar holding an unsaved item edit
+const DRAFT_RECOVERY_FILE: &str = "item_draft_recovery.ron";
+
+/// Where the data directory is, kept in the OS config dir since AppSettings
+/// is saved inside the data directory and moves with it
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct BootstrapConfig {
+    #[serde(default)]
+    data_dir: Option<PathBuf>, // None uses the OS data dir
+}
+
+/// Used when the OS has no per-user folders, the startup check asks for a real one
+const FALLBACK_DATA_DIR: &str = "menu_builder_data";
+
 pub struct FileManager {
-    project_dirs: ProjectDirs,
+    project_dirs: Option<ProjectDirs>, // None when the OS can't tell us a home folder
+    data_dir: PathBuf,
 }
 
 impl FileManager {
-    pub fn new() -> Option<Self> {
-        ProjectDirs::from("com", "MenuBuilder", "menu_builder").map(|dirs| Self { project_dirs: dirs })
+    pub fn new() -> Self {
+        let project_dirs = ProjectDirs::from("com", "MenuBuilder", "menu_builder");
+        let data_dir = project_dirs
+            .as_ref()
+            .and_then(|dirs| read_bootstrap_config(dirs).data_dir)
+            .or_else(|| project_dirs.as_ref().map(|dirs| dirs.data_dir().to_path_buf()))
+            .unwrap_or_else(|| PathBuf::from(FALLBACK_DATA_DIR));
+
+        Self { project_dirs, data_dir }
+    }
+
+    /// Kept to one folder without reading the app's config, for command line runs
+    /// that shouldn't create or check the app's own data directory
+    pub fn in_dir(data_dir: PathBuf) -> Self {
+        Self { project_dirs: None, data_dir }
+    }
+
+    /// Why the app can't keep its data where it's pointed, checked once at launch
+    pub fn startup_problem(&self) -> Option<String> {
+        if self.project_dirs.is_none() {
+            return Some("No home folder was found for this user, so there is nowhere to keep the menu data.".to_string());
+        }
+
+        validate_data_dir(&self.data_dir)
+            .err()
+            .map(|e| format!("The data folder {} can't be used. {}", self.data_dir.display(), e))
     }
 
     pub fn get_default_path(&self) -> PathBuf {
-        self.project_dirs.data_dir().join("menu_data.ron")
+        self.data_dir.join("menu_data.ron")
+    }
+
+    pub fn data_dir(&self) -> &Path {
+        &self.data_dir
+    }
+
+    pub fn is_default_data_dir(&self) -> bool {
+        self.project_dirs.as_ref().is_some_and(|dirs| self.data_dir == dirs.data_dir())
     }
 
     pub fn ensure_data_dir(&self) -> std::io::Result<()> {
-        fs::create_dir_all(self.project_dirs.data_dir())
+        fs::create_dir_all(&self.data_dir)
     }
 
-    pub fn create_backup(&self, path: &Path) -> Result<(), String> {
-        if !path.exists() {
-            return Ok(());
+    /// Uses a data directory until the app closes, for when it can't be remembered
+    pub fn use_data_dir(&mut self, dir: PathBuf) {
+        self.data_dir = dir;
+    }
+
+    /// Points the app at another data directory and remembers it for the next start.
+    /// Moving the files is up to the caller, see data_files.
+    pub fn set_data_dir(&mut self, dir: PathBuf) -> Result<(), String> {
+        let Some(project_dirs) = &self.project_dirs else {
+            return Err("No config folder to remember the data directory in".to_string());
+        };
+        let config = BootstrapConfig {
+            data_dir: (dir != project_dirs.data_dir()).then(|| dir.clone()),
+        };
+
+        let config_dir = project_dirs.config_dir();
+        fs::create_dir_all(config_dir)
+            .map_err(|e| format!("Failed to create config directory: {}", e))?;
+
+        let serialized = ron::ser::to_string_pretty(&config, pretty_config())
+            .map_err(|e| format!("Failed to serialize config: {}", e))?;
+
+        fs::write(config_dir.join("bootstrap.ron"), serialized)
+            .map_err(|e| format!("Failed to write config: {}", e))?;
+
+        self.use_data_dir(dir);
+        Ok(())
+    }
+
+    /// The OS data dir, for going back to the default location
+    pub fn default_data_dir(&self) -> PathBuf {
+        self.project_dirs
+            .as_ref()
+            .map_or_else(|| PathBuf::from(FALLBACK_DATA_DIR), |dirs| dirs.data_dir().to_path_buf())
+    }
+
+    /// Files that belong in the data directory: the save file when it lives there,
+    /// its backups and snapshots, and the item draft
+    pub fn data_files(&self, save_file: &Path) -> Vec<PathBuf> {
+        let Ok(entries) = fs::read_dir(&self.data_dir) else {
+            return Vec::new();
+        };
+
+        let stem = save_file.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default();
+        let backup_prefix = format!("{}_backup_", stem);
+        let save_name = save_file.file_name().map(|name| name.to_string_lossy().into_owned());
+
+        let mut files: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file())
+            .filter(|path| {
+                let name = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+                Some(&name) == save_name.as_ref()
+                    || name.starts_with(&backup_prefix)
+                    || name.starts_with("before-")
+                    || name == DRAFT_RECOVERY_FILE
+            })
+            .collect();
+
+        files.sort();
+        files
+    }
+
+    /// Sidecar for the item draft, kept apart from the main save file
+    fn draft_recovery_path(&self) -> PathBuf {
+        self.data_dir.join(DRAFT_RECOVERY_FILE)
+    }
+
+    pub fn write_draft_recovery(&self, draft: &DraftRecovery) -> Result<(), String> {
+        self.ensure_data_dir()
+            .map_err(|e| format!("Failed to create data directory: {}", e))?;
+
+        let serialized = ron::ser::to_string_pretty(draft, pretty_config())
+            .map_err(|e| format!("Failed to serialize draft: {}", e))?;
+
+        fs::write(self.draft_recovery_path(), serialized)
+            .map_err(|e| format!("Failed to write draft: {}", e))
+    }
+
+    pub fn read_draft_recovery(&self) -> Option<DraftRecovery> {
+        let content = fs::read_to_string(self.draft_recovery_path()).ok()?;
+
+        match ron::from_str(&content) {
+            Ok(draft) => Some(draft),
+            Err(e) => {
+                println!("Ignoring unreadable draft recovery file: {}", e);
+                None
+            }
         }
+    }
 
-        let backup_name = format!(
-            "{}_backup_{}.ron",
-            path.file_stem().unwrap().to_string_lossy(),
-            Local::now().format("%Y%m%d_%H%M%S")
-        );
-        
-        let backup_path = path.with_file_name(backup_name);
-        fs::copy(path, backup_path)
-            .map_err(|e| format!("Failed to create backup: {}", e))?;
-        
+    pub fn remove_draft_recovery(&self) {
+        let path = self.draft_recovery_path();
+        if path.exists() {
+            if let Err(e) = fs::remove_file(&path) {
+                println!("Failed to remove draft recovery file: {}", e);
+            }
+        }
+    }
+
+    /// Writes export output exactly as it was previewed, stopping at the first failure
+    pub fn write_export(&self, files: &[(PathBuf, String)]) -> std::io::Result<()> {
+        for (path, content) in files {
+            println!("Writing {} bytes to {:?}", content.len(), path);
+            fs::write(path, content)?;
+        }
         Ok(())
     }
 
+    /// Writes the given state next to the data file before a risky operation, returns the snapshot path
+    pub fn create_snapshot(&self, state: &AppState, path: &Path, operation: &str) -> Result<PathBuf, String> {
+        let snapshot_path = unused_path(path, &format!(
+            "before-{}-{}",
+            operation,
+            Local::now().format("%Y-%m-%dT%H%M%S%3f")
+        ));
+        save_to_file(state, &snapshot_path.to_string_lossy())
+            .map_err(|e| format!("Failed to create snapshot: {}", e))?;
+
+        prune_snapshots(&snapshot_path, &format!("before-{}-", operation));
+
+        Ok(snapshot_path)
+    }
+
     pub fn validate_path(&self, path: &str) -> Result<(), String> {
         let path = Path::new(path);
         
@@ -130,4 +442,364 @@ impl FileManager {
 
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+fn read_bootstrap_config(project_dirs: &ProjectDirs) -> BootstrapConfig {
+    let path = project_dirs.config_dir().join("bootstrap.ron");
+    let Ok(content) = fs::read_to_string(&path) else {
+        return BootstrapConfig::default();
+    };
+
+    match ron::from_str(&content) {
+        Ok(config) => config,
+        Err(e) => {
+            println!("Ignoring unreadable bootstrap config {:?}: {}", path, e);
+            BootstrapConfig::default()
+        }
+    }
+}
+
+/// Checks a folder can hold the data directory, creating it if needed
+pub fn validate_data_dir(dir: &Path) -> Result<(), String> {
+    fs::create_dir_all(dir)
+        .map_err(|e| format!("Cannot create directory: {}", e))?;
+
+    let test_file = dir.join(".menu_builder_write_test");
+    fs::write(&test_file, "")
+        .map_err(|e| format!("Cannot write to {}: {}", dir.display(), e))?;
+    fs::remove_file(&test_file)
+        .map_err(|e| format!("Cannot remove test file: {}", e))?;
+
+    Ok(())
+}
+
+/// Copies one data file, run a file at a time so the move can show progress
+pub async fn copy_data_file(from: PathBuf, to: PathBuf) -> Result<PathBuf, String> {
+    tokio::fs::copy(&from, &to)
+        .await
+        .map_err(|e| format!("Failed to copy {}: {}", from.display(), e))?;
+
+    Ok(to)
+}
+
+/// Backs up and writes the state before returning, for when the file has to be current
+/// right away. Same steps as save_in_background, an unchanged file isn't touched or backed up.
+pub fn save_now(state: &AppState, path: &Path, create_backup: bool) -> Result<(), String> {
+    let serialized = serialize_state(state)?;
+
+    if fs::read_to_string(path).is_ok_and(|current| current == serialized) {
+        return Ok(());
+    }
+
+    if create_backup && path.exists() {
+        fs::copy(path, backup_path(path))
+            .map_err(|e| format!("Failed to create backup: {}", e))?;
+    }
+
+    let partial_path = path.with_extension("ron.partial");
+    fs::write(&partial_path, serialized)
+        .map_err(|e| format!("Failed to write file: {}", e))?;
+    fs::rename(&partial_path, path)
+        .map_err(|e| format!("Failed to write file: {}", e))
+}
+
+/// Backs up and writes the state off the UI thread. The file is written beside the
+/// save file and renamed over it, so a write cut short never leaves half a file
+pub async fn save_in_background(state: AppState, path: PathBuf, create_backup: bool) -> Result<(), String> {
+    let serialized = serialize_state(&state)?;
+
+    // Nothing changed, leave the file and its modified time alone, and don't back it up again
+    if tokio::fs::read_to_string(&path).await.is_ok_and(|current| current == serialized) {
+        return Ok(());
+    }
+
+    if create_backup && path.exists() {
+        let backup_path = backup_path(&path);
+        tokio::fs::copy(&path, &backup_path)
+            .await
+            .map_err(|e| format!("Failed to create backup: {}", e))?;
+
+    }
+
+    let partial_path = path.with_extension("ron.partial");
+    tokio::fs::write(&partial_path, serialized)
+        .await
+        .map_err(|e| format!("Failed to write file: {}", e))?;
+    tokio::fs::rename(&partial_path, &path)
+        .await
+        .map_err(|e| format!("Failed to write file: {}", e))
+}
+
+fn backup_prefix(path: &Path) -> String {
+    format!("{}_backup_", path.file_stem().unwrap().to_string_lossy())
+}
+
+fn backup_path(path: &Path) -> PathBuf {
+    unused_path(path, &format!("{}{}", backup_prefix(path), Local::now().format("%Y%m%d_%H%M%S%3f")))
+}
+
+// A .ron file named `stem` beside `path`, with a counter added if that name is taken,
+// so two copies made in the same millisecond don't overwrite each other
+fn unused_path(path: &Path, stem: &str) -> PathBuf {
+    let mut candidate = path.with_file_name(format!("{}.ron", stem));
+    let mut counter = 2;
+    while candidate.exists() {
+        candidate = path.with_file_name(format!("{}-{}.ron", stem, counter));
+        counter += 1;
+    }
+    candidate
+}
+
+/// Removes all but the newest snapshots starting with prefix, names carry their timestamp so they sort by age
+fn prune_snapshots(latest: &Path, prefix: &str) {
+    let Some(dir) = latest.parent() else { return };
+
+    let Ok(entries) = fs::read_dir(dir) else { return };
+
+    let mut snapshots: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .map(|name| name.to_string_lossy().starts_with(prefix))
+                .unwrap_or(false)
+        })
+        .collect();
+
+    if snapshots.len() <= SNAPSHOTS_TO_KEEP {
+        return;
+    }
+
+    snapshots.sort();
+    let excess = snapshots.len() - SNAPSHOTS_TO_KEEP;
+    for old in snapshots.into_iter().take(excess) {
+        println!("Pruning old snapshot: {:?}", old);
+        if let Err(e) = fs::remove_file(&old) {
+            println!("Failed to prune snapshot {:?}: {}", old, e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data_types::ItemPrice;
+    use rust_decimal::Decimal;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("menu_builder_{}_{}.ron", std::process::id(), name))
+    }
+
+    fn backups_of(path: &Path) -> usize {
+        let prefix = backup_prefix(path);
+        fs::read_dir(path.parent().unwrap())
+            .unwrap()
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.file_name().to_string_lossy().starts_with(&prefix))
+            .count()
+    }
+
+    fn cleanup(path: &Path) {
+        let prefix = backup_prefix(path);
+        for entry in fs::read_dir(path.parent().unwrap()).unwrap().filter_map(|entry| entry.ok()) {
+            if entry.file_name().to_string_lossy().starts_with(&prefix) {
+                let _ = fs::remove_file(entry.path());
+            }
+        }
+        let _ = fs::remove_file(path);
+    }
+
+    fn fixture_state() -> AppState {
+        AppState {
+            items: vec![
+                Item {
+                    id: 1,
+                    name: "Cola".to_string(),
+                    item_group: Some(10),
+                    item_prices: Some(vec![ItemPrice { price_level_id: 1, price: Decimal::new(250, 2) }]),
+                    ..Item::default()
+                },
+                Item { id: 2, name: "Fries \"large\"".to_string(), ..Item::default() },
+            ],
+            item_groups: vec![ItemGroup { id: 10, name: "Drinks".to_string(), id_range: 1..100, ..ItemGroup::default() }],
+            price_levels: vec![PriceLevel { id: 1, name: "Regular".to_string(), ..PriceLevel::default() }],
+            deleted_items: vec![ItemTombstone { id: 3, name: "Gone".to_string(), deleted_at: "2025-03-01T12:00:00Z".to_string() }],
+            ..AppState::default()
+        }
+    }
+
+    #[test]
+    fn saving_the_same_state_twice_gives_the_same_bytes() {
+        let path = temp_path("save_save");
+        cleanup(&path);
+        let state = fixture_state();
+
+        save_now(&state, &path, true).unwrap();
+        let first = fs::read(&path).unwrap();
+        save_now(&state, &path, true).unwrap();
+        let second = fs::read(&path).unwrap();
+
+        assert_eq!(first, second);
+        assert_eq!(backups_of(&path), 0, "an unchanged save shouldn't back up the file");
+
+        cleanup(&path);
+    }
+
+    #[test]
+    fn loading_and_saving_again_gives_the_same_bytes() {
+        let path = temp_path("load_save");
+        let again = temp_path("load_save_again");
+        cleanup(&path);
+        cleanup(&again);
+
+        save_now(&fixture_state(), &path, false).unwrap();
+        let loaded = load_from_file(&path.to_string_lossy()).unwrap();
+        save_now(&loaded, &again, false).unwrap();
+
+        assert_eq!(fs::read(&path).unwrap(), fs::read(&again).unwrap());
+
+        cleanup(&path);
+        cleanup(&again);
+    }
+
+    #[test]
+    fn entity_order_doesnt_change_the_bytes() {
+        let state = fixture_state();
+        let mut reversed = state.clone();
+        reversed.items.reverse();
+
+        assert_eq!(serialize_state(&state), serialize_state(&reversed));
+    }
+
+    #[test]
+    fn a_changed_state_is_backed_up_before_writing() {
+        let path = temp_path("changed");
+        cleanup(&path);
+        let mut state = fixture_state();
+
+        save_now(&state, &path, true).unwrap();
+        state.items[0].name = "Diet Cola".to_string();
+        save_now(&state, &path, true).unwrap();
+
+        assert_eq!(backups_of(&path), 1);
+        assert!(fs::read_to_string(&path).unwrap().contains("Diet Cola"));
+        assert!(!path.with_extension("ron.partial").exists());
+
+        cleanup(&path);
+    }
+
+    fn ids<T: Entity>(entities: &[T]) -> Vec<EntityId> {
+        entities.iter().map(|entity| entity.id()).collect()
+    }
+
+    #[test]
+    fn duplicate_ids_are_moved_past_the_highest_id_of_their_type() {
+        let mut state = AppState {
+            item_groups: vec![
+                ItemGroup { id: 10, name: "Drinks".to_string(), ..ItemGroup::default() },
+                ItemGroup { id: 10, name: "Food".to_string(), ..ItemGroup::default() },
+            ],
+            tax_groups: vec![
+                TaxGroup { id: 1, name: "Food Tax".to_string(), ..TaxGroup::default() },
+                TaxGroup { id: 2, name: "Liquor Tax".to_string(), ..TaxGroup::default() },
+                TaxGroup { id: 1, name: "No Tax".to_string(), ..TaxGroup::default() },
+            ],
+            choice_groups: vec![
+                ChoiceGroup { id: 5, name: "Sides".to_string(), ..ChoiceGroup::default() },
+                ChoiceGroup { id: 5, name: "Sauces".to_string(), ..ChoiceGroup::default() },
+                ChoiceGroup { id: 5, name: "Toppings".to_string(), ..ChoiceGroup::default() },
+            ],
+            ..AppState::default()
+        };
+
+        let summary = state.repair_duplicate_ids();
+
+        assert_eq!(ids(&state.item_groups), vec![10, 11]);
+        assert_eq!(ids(&state.tax_groups), vec![1, 2, 3]);
+        assert_eq!(ids(&state.choice_groups), vec![5, 6, 7]);
+        // The first entry keeps the id, so references keep pointing at it
+        assert_eq!(state.tax_groups[0].name, "Food Tax");
+        assert_eq!(summary, vec![
+            "Item Group 'Food' had duplicate ID 10, moved to 11".to_string(),
+            "Tax Group 'No Tax' had duplicate ID 1, moved to 3".to_string(),
+            "Choice Group 'Sauces' had duplicate ID 5, moved to 6".to_string(),
+            "Choice Group 'Toppings' had duplicate ID 5, moved to 7".to_string(),
+        ]);
+    }
+
+    #[test]
+    fn the_same_id_in_different_types_is_not_a_duplicate() {
+        let mut state = AppState {
+            price_levels: vec![PriceLevel { id: 1, name: "Regular".to_string(), ..PriceLevel::default() }],
+            security_levels: vec![SecurityLevel { id: 1, name: "Manager".to_string(), ..SecurityLevel::default() }],
+            printer_logicals: vec![PrinterLogical { id: 1, name: "Kitchen".to_string(), ..PrinterLogical::default() }],
+            ..AppState::default()
+        };
+
+        assert!(state.repair_duplicate_ids().is_empty());
+        assert_eq!(ids(&state.price_levels), vec![1]);
+        assert_eq!(ids(&state.security_levels), vec![1]);
+        assert_eq!(ids(&state.printer_logicals), vec![1]);
+    }
+
+    #[test]
+    fn duplicate_item_ids_are_repaired_and_clean_data_is_left_alone() {
+        let mut state = fixture_state();
+        assert!(state.repair_duplicate_ids().is_empty());
+
+        state.items.push(Item { id: 1, name: "Root Beer".to_string(), ..Item::default() });
+        let summary = state.repair_duplicate_ids();
+
+        assert_eq!(state.items.iter().map(|item| item.id).collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert_eq!(state.items[0].name, "Cola");
+        assert_eq!(summary, vec!["Item 'Root Beer' had duplicate ID 1, moved to 3".to_string()]);
+
+        // A second pass finds nothing left to fix
+        assert!(state.repair_duplicate_ids().is_empty());
+    }
+
+    #[test]
+    fn duplicate_ids_in_a_saved_file_are_repaired_on_load() {
+        let path = temp_path("duplicates");
+        cleanup(&path);
+        let state = AppState {
+            items: vec![
+                Item { id: 1, name: "Cola".to_string(), item_group: Some(10), tax_group: Some(1), ..Item::default() },
+                Item { id: 1, name: "Fries".to_string(), item_group: Some(10), tax_group: Some(1), ..Item::default() },
+            ],
+            item_groups: vec![
+                ItemGroup { id: 10, name: "Drinks".to_string(), ..ItemGroup::default() },
+                ItemGroup { id: 10, name: "Food".to_string(), ..ItemGroup::default() },
+            ],
+            tax_groups: vec![
+                TaxGroup { id: 1, name: "Food Tax".to_string(), ..TaxGroup::default() },
+                TaxGroup { id: 1, name: "No Tax".to_string(), ..TaxGroup::default() },
+            ],
+            ..AppState::default()
+        };
+        save_now(&state, &path, false).unwrap();
+
+        let (loaded, repairs) = load_repaired(&path.to_string_lossy()).unwrap();
+
+        assert_eq!(repairs, vec![
+            "Item Group 'Food' had duplicate ID 10, moved to 11".to_string(),
+            "Tax Group 'No Tax' had duplicate ID 1, moved to 2".to_string(),
+            "Item 'Fries' had duplicate ID 1, moved to 2".to_string(),
+        ]);
+        assert_eq!(ids(&loaded.item_groups), vec![10, 11]);
+        assert_eq!(ids(&loaded.tax_groups), vec![1, 2]);
+        assert_eq!(loaded.items.iter().map(|item| item.id).collect::<Vec<_>>(), vec![1, 2]);
+
+        // References keep the id they had, so they stay with the entry that kept it
+        for item in &loaded.items {
+            assert_eq!(item.item_group, Some(10));
+            assert_eq!(item.tax_group, Some(1));
+        }
+        assert_eq!(loaded.item_groups[0].name, "Drinks");
+        assert_eq!(loaded.tax_groups[0].name, "Food Tax");
+        // Export keys are filled in on the same load
+        assert_eq!(loaded.tax_groups[1].export_key, "No Tax");
+
+        cleanup(&path);
+    }
+}
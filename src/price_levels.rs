@@ -1,7 +1,9 @@
-use crate::data_types::{ EntityId, ValidationError, Currency };
+use crate::data_types::{ EntityId, DRAFT_ID, ValidationError, Currency };
 use crate::Action;
 use crate::entity_component::{self, Entity, EditState as BaseEditState};
 use crate::icon;
+use crate::text_style::TextStyle;
+use crate::items::Item;
 use iced_modern_theme::Modern;
 use serde::{Serialize, Deserialize};
 use iced::{Element, Length};
@@ -16,8 +18,11 @@ pub enum Message {
     EditPriceLevel(EntityId),
     SaveAll(EntityId, PriceLevelEditState),
     UpdateName(EntityId, String),
+    UpdateDecimals(EntityId, String),
     CreateNew,
     CancelEdit(EntityId),
+    ToggleSortByUsage,
+    ListScrolled(scrollable::Viewport),
 }
 
 #[derive(Debug, Clone)]
@@ -27,8 +32,21 @@ pub enum Operation {
     EditPriceLevel(EntityId),
     SaveAll(EntityId, PriceLevelEditState),
     UpdateName(EntityId, String),
+    UpdateDecimals(EntityId, String),
     CreateNew,
     CancelEdit(EntityId),
+    ToggleSortByUsage,
+    ListScrolled(scrollable::Viewport),
+}
+
+/// Decimal places used for price levels saved before the setting existed
+pub const DEFAULT_DECIMALS: u8 = 2;
+
+/// Most decimal places a price level can be set to
+pub const MAX_DECIMALS: u8 = 6;
+
+fn default_decimals() -> u8 {
+    DEFAULT_DECIMALS
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Eq, Hash)]
@@ -52,11 +70,140 @@ impl std::fmt::Display for PriceLevelType {
     }
 }
 
+/// Rounding applied to prices after a bulk price change
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub enum PriceRounding {
+    #[default]
+    None,
+    Nearest(Decimal),      // Step to round to, like 0.05 or 0.25
+    Endings(Vec<Decimal>), // Allowed cents like .49 and .99, prices go up to the next one
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RoundingKind {
+    #[default]
+    None,
+    Nearest,
+    Endings,
+}
+
+impl RoundingKind {
+    pub const ALL: [RoundingKind; 3] = [RoundingKind::None, RoundingKind::Nearest, RoundingKind::Endings];
+}
+
+impl std::fmt::Display for RoundingKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RoundingKind::None => write!(f, "No rounding"),
+            RoundingKind::Nearest => write!(f, "Nearest"),
+            RoundingKind::Endings => write!(f, "Ending in"),
+        }
+    }
+}
+
+impl PriceRounding {
+    /// Reads the typed value for a rounding kind, endings can be listed like ".49, .99"
+    pub fn parse(kind: RoundingKind, input: &str) -> Result<Self, String> {
+        let number = |value: &str| {
+            let value = value.trim();
+            let value = if value.starts_with('.') { format!("0{}", value) } else { value.to_string() };
+            value.parse::<Decimal>().map_err(|_| format!("Invalid rounding value \"{}\"", value))
+        };
+
+        match kind {
+            RoundingKind::None => Ok(PriceRounding::None),
+            RoundingKind::Nearest => {
+                let step = number(input)?;
+                if step <= Decimal::ZERO {
+                    return Err("Rounding step must be more than 0".to_string());
+                }
+                Ok(PriceRounding::Nearest(step))
+            }
+            RoundingKind::Endings => {
+                let mut endings = input
+                    .split(|c: char| c == ',' || c.is_whitespace())
+                    .filter(|value| !value.is_empty())
+                    .map(number)
+                    .collect::<Result<Vec<_>, _>>()?;
+
+                if endings.is_empty() {
+                    return Err("Enter at least one price ending, like .99".to_string());
+                }
+                if endings.iter().any(|ending| *ending < Decimal::ZERO || *ending >= Decimal::ONE) {
+                    return Err("Price endings must be between .00 and .99".to_string());
+                }
+
+                endings.sort();
+                endings.dedup();
+                Ok(PriceRounding::Endings(endings))
+            }
+        }
+    }
+
+    pub fn kind(&self) -> RoundingKind {
+        match self {
+            PriceRounding::None => RoundingKind::None,
+            PriceRounding::Nearest(_) => RoundingKind::Nearest,
+            PriceRounding::Endings(_) => RoundingKind::Endings,
+        }
+    }
+
+    /// The value as it would be typed back in
+    pub fn input_text(&self) -> String {
+        match self {
+            PriceRounding::None => String::new(),
+            PriceRounding::Nearest(step) => step.to_string(),
+            PriceRounding::Endings(endings) => endings
+                .iter()
+                .map(|ending| {
+                    let ending = ending.to_string();
+                    ending.strip_prefix("0.").map_or(ending.clone(), |cents| format!(".{}", cents))
+                })
+                .collect::<Vec<_>>()
+                .join(", "),
+        }
+    }
+
+    /// Prices already on an allowed ending stay as they are. Negative prices
+    /// have no sensible ending and are left alone, a step rounds them like any other.
+    /// None when the rounded price is out of range for a Decimal.
+    pub fn apply(&self, price: Decimal) -> Option<Decimal> {
+        match self {
+            PriceRounding::None => Some(price),
+            PriceRounding::Nearest(step) => {
+                let steps = price.checked_div(*step)?
+                    .round_dp_with_strategy(0, rust_decimal::RoundingStrategy::MidpointAwayFromZero);
+                steps.checked_mul(*step)
+            }
+            PriceRounding::Endings(endings) => {
+                if price < Decimal::ZERO {
+                    return Some(price);
+                }
+
+                let whole = price.floor();
+                let next = whole.checked_add(Decimal::ONE)?;
+                let candidates = [whole, next]
+                    .into_iter()
+                    .flat_map(|whole| endings.iter().map(move |ending| whole.checked_add(*ending)))
+                    .collect::<Option<Vec<_>>>()?;
+
+                Some(candidates
+                    .into_iter()
+                    .filter(|candidate| *candidate >= price)
+                    .min()
+                    .unwrap_or(price))
+            }
+        }
+    }
+}
+
 #[derive(Default, Debug, Clone)]
 pub struct PriceLevelEditState {
     pub base: BaseEditState,
     pub price: String,
     pub original_price: String,
+    pub decimals: String,
+    pub original_decimals: String,
     pub level_type: PriceLevelType,
     pub range_validation_error: Option<String>,
 }
@@ -67,6 +214,8 @@ impl PriceLevelEditState {
             base: BaseEditState::new(price_level),
             price: price_level.price.to_string(),
             original_price: price_level.price.to_string(),
+            decimals: price_level.decimals.to_string(),
+            original_decimals: price_level.decimals.to_string(),
             level_type: price_level.level_type.clone(),
             range_validation_error: None,
         }
@@ -75,6 +224,7 @@ impl PriceLevelEditState {
     pub fn reset(&mut self) {
         self.base.reset();
         self.price = self.original_price.clone();
+        self.decimals = self.original_decimals.clone();
         self.level_type = PriceLevelType::default();
         self.range_validation_error = None;
     }
@@ -104,6 +254,15 @@ impl PriceLevelEditState {
             ));
         }
 
+        match self.decimals.parse::<u8>() {
+            Ok(decimals) if decimals <= MAX_DECIMALS => {}
+            _ => {
+                return Err(ValidationError::InvalidValue(
+                    format!("Decimal places must be between 0 and {}", MAX_DECIMALS)
+                ));
+            }
+        }
+
         Ok(())
     }
 }
@@ -114,6 +273,14 @@ pub struct PriceLevel {
     pub name: String,
     pub price: Currency,
     pub level_type: PriceLevelType,
+    #[serde(default = "default_decimals")]
+    pub decimals: u8,
+    #[serde(default)]
+    pub last_rounding: PriceRounding, // Rule last used by a bulk price change at this level
+    #[serde(default)]
+    pub created_at: Option<String>,
+    #[serde(default)]
+    pub updated_at: Option<String>,
 }
 
 impl std::fmt::Display for PriceLevel {
@@ -125,10 +292,14 @@ impl std::fmt::Display for PriceLevel {
 impl Default for PriceLevel {
     fn default() -> Self {
         Self {
-            id: -1,
+            id: DRAFT_ID,
             name: String::new(),
             price: Decimal::ZERO,
             level_type: PriceLevelType::default(),
+            decimals: DEFAULT_DECIMALS,
+            last_rounding: PriceRounding::None,
+            created_at: None,
+            updated_at: None,
         }
     }
 }
@@ -157,6 +328,14 @@ impl Entity for PriceLevel {
     fn default_new() -> Self {
         Self::default()
     }
+
+    fn created_at(&self) -> Option<&str> {
+        self.created_at.as_deref()
+    }
+
+    fn updated_at(&self) -> Option<&str> {
+        self.updated_at.as_deref()
+    }
 }
 
 impl PriceLevel {
@@ -164,6 +343,21 @@ impl PriceLevel {
         Self::default()
     }
 
+    /// Rounds a price to this level's decimal places
+    pub fn round_price(&self, price: Decimal) -> Decimal {
+        price.round_dp(self.decimals as u32)
+    }
+
+    /// Formats a price with exactly this level's decimal places
+    pub fn format_price(&self, price: Decimal) -> String {
+        format!("{:.*}", self.decimals as usize, self.round_price(price))
+    }
+
+    /// Whether a price fits in this level's decimal places without rounding
+    pub fn fits_precision(&self, price: Decimal) -> bool {
+        price.normalize().scale() <= self.decimals as u32
+    }
+
     fn validate(&self, other_groups: &[&PriceLevel]) -> Result<(), ValidationError> {
         if !(1..=999).contains(&self.id) {
             return Err(ValidationError::InvalidId(
@@ -190,8 +384,12 @@ impl PriceLevel {
     }
 }
 
+// Title of the screen, and the id of its scrollable list
+pub const LIST_ID: &str = "Price Levels";
+
 pub fn update(
     message: Message,
+    levels: &BTreeMap<EntityId, PriceLevel>,
 ) -> Action<Operation, Message> {
     match message {
         Message::RequestDelete(id) => {
@@ -205,27 +403,48 @@ pub fn update(
         },
         Message::CreateNew => {
             Action::operation(Operation::CreateNew)
+                .with_task(entity_component::reveal_new_row(LIST_ID, levels))
         },
         Message::SaveAll(id, edit_state) => {
-            Action::operation(Operation::SaveAll(id, edit_state))
+            let decimals_valid = edit_state.decimals.parse::<u8>()
+                .is_ok_and(|decimals| decimals <= MAX_DECIMALS);
+            let focus = if decimals_valid {
+                entity_component::focus_duplicate_name(LIST_ID, levels, id, &edit_state.base.name)
+            } else {
+                text_input::focus(entity_component::field_input_id(LIST_ID, id, "decimals"))
+            };
+            Action::operation(Operation::SaveAll(id, edit_state)).with_task(focus)
         }
         Message::UpdateName(id, new_name) => {
             Action::operation(Operation::UpdateName(id, new_name))
         }
+        Message::UpdateDecimals(id, decimals) => {
+            Action::operation(Operation::UpdateDecimals(id, decimals))
+        }
         Message::CancelEdit(id) => {
             Action::operation(Operation::CancelEdit(id))
         }
+        Message::ToggleSortByUsage => {
+            Action::operation(Operation::ToggleSortByUsage)
+        }
+        Message::ListScrolled(viewport) => {
+            Action::operation(Operation::ListScrolled(viewport))
+        }
     }
 }
 
 pub fn view<'a>(
     all_prices: &'a BTreeMap<EntityId, PriceLevel>,
     edit_states: &'a Vec<PriceLevelEditState>,
+    usage: BTreeMap<EntityId, usize>,
+    sort_by_usage: bool,
+    text_style: TextStyle,
 ) -> Element<'a, Message> {
     let title_row = entity_component::render_title_row(
-        "Price Levels", 
+        LIST_ID,
         Message::CreateNew,
-        505.0 // view width
+        575.0, // view width
+        text_style,
     );
 
     // Custom header row for columns including range fields
@@ -233,24 +452,34 @@ pub fn view<'a>(
         text("ID").width(Length::Fixed(75.0)),
         text("Name").width(Length::Fixed(250.0)),
         text("Actions").width(Length::Fixed(150.0)),
+        button(text(if sort_by_usage { "Used ▼" } else { "Used" }))
+            .on_press(Message::ToggleSortByUsage)
+            .style(Modern::system_button())
+            .width(Length::Fixed(70.0)),
     ]
+    .align_y(iced::Alignment::Center)
     .padding(15);
 
     // List of price levels
     let price_list = scrollable(
         column(
-            all_prices
-                .values()
-                .map(|group| 
+            entity_component::sorted_by_usage(all_prices, &usage, sort_by_usage)
+                .into_iter()
+                .map(|(group, count)| 
                     row![
-                        render_price_level_row(group, edit_states)
+                        render_price_level_row(group, edit_states, text_style),
+                        text(count.to_string()).width(Length::Fixed(60.0)),
                     ]
+                    .align_y(iced::Alignment::Center)
                     .padding(5)
                     .into()
                 )
                 .collect::<Vec<_>>()
         )
-    ).height(Length::Fill);
+    )
+    .id(scrollable::Id::new(LIST_ID))
+    .on_scroll(Message::ListScrolled)
+    .height(Length::Fill);
 
     // Combine all elements
     let all_content = column![title_row, header_row, price_list];
@@ -263,9 +492,32 @@ pub fn view<'a>(
     .into()
 }
 
+/// Number of items with a price (or legacy price level entry) for each price level
+pub fn usage_counts(items: &BTreeMap<EntityId, Item>) -> BTreeMap<EntityId, usize> {
+    let mut counts = BTreeMap::new();
+
+    for item in items.values() {
+        let mut level_ids: Vec<EntityId> = item.item_prices
+            .iter()
+            .flatten()
+            .map(|price| price.price_level_id)
+            .chain(item.price_levels.iter().flatten().copied())
+            .collect();
+        level_ids.sort();
+        level_ids.dedup();
+
+        for id in level_ids {
+            *counts.entry(id).or_insert(0) += 1;
+        }
+    }
+
+    counts
+}
+
 fn render_price_level_row<'a>(
     price_level: &'a PriceLevel,
-    edit_states: &'a Vec<PriceLevelEditState>
+    edit_states: &'a Vec<PriceLevelEditState>,
+    text_style: TextStyle,
 ) -> Element<'a, Message> {
     // Find edit state for this price_level if it exists
     let edit_state = edit_states.iter()
@@ -298,13 +550,14 @@ fn render_price_level_row<'a>(
                 tooltip::Position::Top,
             ).into()
         } else {
-            input.into()
+            entity_component::with_timestamps(input, price_level, text_style)
         }
     };
 
     // Name input with validation
     let name_input: Element<'_, Message> = {
         let input = text_input("Price Level Name", &display_name)
+            .id(entity_component::name_input_id(LIST_ID, price_level.id))
             .on_input_maybe(
                 if editing {
                     Some(|name| Message::UpdateName(price_level.id, name))
@@ -326,6 +579,23 @@ fn render_price_level_row<'a>(
         }
     };
 
+    let display_decimals = edit_state
+        .map(|state| state.decimals.clone())
+        .unwrap_or_else(|| price_level.decimals.to_string());
+
+    // Decimal places used when showing and rounding this level's prices
+    let decimals_input = text_input("Dec.", &display_decimals)
+        .id(entity_component::field_input_id(LIST_ID, price_level.id, "decimals"))
+        .on_input_maybe(
+            if editing {
+                Some(|decimals| Message::UpdateDecimals(price_level.id, decimals))
+            } else {
+                None
+            }
+        )
+        .style(Modern::inline_text_input())
+        .width(Length::Fixed(45.0));
+
     // Action buttons
     let action_row = row![
         button(if editing { icon::save().size(14) } else { icon::edit().size(14) })
@@ -359,9 +629,75 @@ fn render_price_level_row<'a>(
         id_input,
         name_input,
         iced::widget::horizontal_space().width(5),
+        decimals_input,
+        iced::widget::horizontal_space().width(5),
         action_row,
     ]
     .align_y(iced::Alignment::Center)
-    .width(Length::Fixed(495.0))
+    .width(Length::Fixed(545.0))
     .into()
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn dec(value: &str) -> Decimal {
+        Decimal::from_str(value).unwrap()
+    }
+
+    #[test]
+    fn nearest_step_rounds_half_away_from_zero() {
+        let rounding = PriceRounding::Nearest(dec("0.05"));
+
+        assert_eq!(rounding.apply(dec("3.22")), Some(dec("3.20")));
+        assert_eq!(rounding.apply(dec("3.225")), Some(dec("3.25")));
+        assert_eq!(rounding.apply(dec("-3.225")), Some(dec("-3.25")));
+    }
+
+    #[test]
+    fn endings_move_up_to_the_next_allowed_ending() {
+        let rounding = PriceRounding::Endings(vec![dec("0.49"), dec("0.99")]);
+
+        assert_eq!(rounding.apply(dec("3.10")), Some(dec("3.49")));
+        assert_eq!(rounding.apply(dec("3.50")), Some(dec("3.99")));
+        assert_eq!(rounding.apply(dec("3.995")), Some(dec("4.49")));
+    }
+
+    #[test]
+    fn compliant_prices_are_unchanged() {
+        let endings = PriceRounding::Endings(vec![dec("0.49"), dec("0.99")]);
+        assert_eq!(endings.apply(dec("3.49")), Some(dec("3.49")));
+        assert_eq!(endings.apply(dec("3.99")), Some(dec("3.99")));
+
+        let step = PriceRounding::Nearest(dec("0.25"));
+        assert_eq!(step.apply(dec("4.75")), Some(dec("4.75")));
+        assert_eq!(step.apply(Decimal::ZERO), Some(Decimal::ZERO));
+    }
+
+    #[test]
+    fn negative_prices_keep_their_value_with_endings() {
+        let rounding = PriceRounding::Endings(vec![dec("0.99")]);
+
+        assert_eq!(rounding.apply(dec("-2.10")), Some(dec("-2.10")));
+    }
+
+    #[test]
+    fn tiny_step_on_a_huge_price_is_out_of_range() {
+        let rounding = PriceRounding::Nearest(dec("0.0000000001"));
+
+        assert_eq!(rounding.apply(Decimal::MAX), None);
+    }
+
+    #[test]
+    fn ending_past_the_largest_price_is_out_of_range() {
+        let rounding = PriceRounding::Endings(vec![dec("0.99")]);
+
+        assert_eq!(rounding.apply(Decimal::MAX), None);
+    }
+
+    #[test]
+    fn no_rounding_leaves_price_alone() {
+        assert_eq!(PriceRounding::None.apply(Decimal::MAX), Some(Decimal::MAX));
+    }
+}
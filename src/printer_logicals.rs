@@ -1,9 +1,17 @@
-use crate::data_types::{EntityId, ValidationError};
+use crate::data_types::{EntityId, DRAFT_ID, ValidationError};
 use crate::Action;
 use crate::entity_component::{self, Entity, EditState};
+use crate::items::Item;
+use crate::settings::DIALOG_CLOSED;
+use crate::text_style::TextStyle;
+use crate::item_groups::ItemGroup;
+use crate::export_preview::{ExportKind, ExportPreview};
 use serde::{Serialize, Deserialize};
-use iced::Element;
+use iced::{Element, Length, Task};
+use iced::widget::{button, checkbox, column, container, pick_list, row, text, scrollable};
+use iced_modern_theme::Modern;
 use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone)]
 pub enum Message {
@@ -14,6 +22,22 @@ pub enum Message {
     UpdateName(EntityId, String),
     CreateNew,
     CancelEdit(EntityId),
+    ToggleActive(EntityId, bool),
+    UpdateExportKey(EntityId, String),
+    ToggleLoadReport,
+    ShowPrinterItems(EntityId),
+    ExportLoadReport,
+    LoadReportReady(Result<ExportPreview, String>),
+    LoadReportExported(Result<PathBuf, String>),
+    ExportRoutingMap,
+    RoutingMapReady(Result<ExportPreview, String>),
+    RoutingMapExported(Result<PathBuf, String>),
+    ToggleSortByUsage,
+    RequestReroute(EntityId),
+    SelectRerouteDestination(EntityId),
+    ConfirmReroute,
+    CancelReroute,
+    ListScrolled(scrollable::Viewport),
 }
 
 #[derive(Debug, Clone)]
@@ -25,12 +49,38 @@ pub enum Operation {
     UpdateName(EntityId, String),
     CreateNew,
     CancelEdit(EntityId),
+    ToggleActive(EntityId, bool),
+    UpdateExportKey(EntityId, String),
+    ToggleLoadReport,
+    ShowPrinterItems(EntityId),
+    ExportLoadReport,
+    ExportRoutingMap,
+    PreviewExport(ExportPreview),
+    UpdateLoadReportMessage(String),
+    ToggleSortByUsage,
+    RequestReroute(EntityId),
+    SelectRerouteDestination(EntityId),
+    ConfirmReroute,
+    CancelReroute,
+    ListScrolled(scrollable::Viewport),
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PrinterLogical {
     pub id: EntityId,
     pub name: String,
+    #[serde(default = "default_active")]
+    pub active: bool, // Inactive printers keep their item routing but are left out of exports
+    #[serde(default)]
+    pub export_key: String, // Name the exports use, filled from the name when older files load
+    #[serde(default)]
+    pub created_at: Option<String>,
+    #[serde(default)]
+    pub updated_at: Option<String>,
+}
+
+fn default_active() -> bool {
+    true
 }
 
 impl std::fmt::Display for PrinterLogical {
@@ -42,8 +92,12 @@ impl std::fmt::Display for PrinterLogical {
 impl Default for PrinterLogical {
     fn default() -> Self {
         Self {
-            id: -1,
+            id: DRAFT_ID,
             name: String::new(),
+            active: true,
+            export_key: String::new(),
+            created_at: None,
+            updated_at: None,
         }
     }
 }
@@ -72,6 +126,14 @@ impl Entity for PrinterLogical {
     fn default_new() -> Self {
         Self::default()
     }
+
+    fn created_at(&self) -> Option<&str> {
+        self.created_at.as_deref()
+    }
+
+    fn updated_at(&self) -> Option<&str> {
+        self.updated_at.as_deref()
+    }
 }
 
 impl PrinterLogical {
@@ -79,6 +141,11 @@ impl PrinterLogical {
         Self::default()
     }
 
+    /// What exports call this printer. Renaming it in the app doesn't change this.
+    pub fn export_name(&self) -> &str {
+        if self.export_key.trim().is_empty() { &self.name } else { &self.export_key }
+    }
+
     fn validate(&self, other_printers: &[&PrinterLogical]) -> Result<(), ValidationError> {
         // Validate ID range (0-25 based on your screenshot)
         if !(0..=25).contains(&self.id) {
@@ -114,8 +181,12 @@ impl PrinterLogical {
     }
 }
 
+// Title of the screen, and the id of its scrollable list
+pub const LIST_ID: &str = "Printer Logicals";
+
 pub fn update(
     message: Message,
+    printers: &BTreeMap<EntityId, PrinterLogical>,
 ) -> Action<Operation, Message> {
     match message {
         Message::RequestDelete(id) => {
@@ -129,9 +200,11 @@ pub fn update(
         }
         Message::CreateNew => {
             Action::operation(Operation::CreateNew)
+                .with_task(entity_component::reveal_new_row(LIST_ID, printers))
         }
         Message::Save(id, edit_state) => {
-            Action::operation(Operation::Save(id, edit_state))
+            let focus = entity_component::focus_duplicate_name(LIST_ID, printers, id, &edit_state.name);
+            Action::operation(Operation::Save(id, edit_state)).with_task(focus)
         }
         Message::UpdateName(id, new_name) => {
             Action::operation(Operation::UpdateName(id, new_name))
@@ -139,35 +212,714 @@ pub fn update(
         Message::CancelEdit(id) => {
             Action::operation(Operation::CancelEdit(id))
         }
+        Message::ToggleSortByUsage => {
+            Action::operation(Operation::ToggleSortByUsage)
+        }
+        Message::ToggleActive(id, active) => {
+            Action::operation(Operation::ToggleActive(id, active))
+        }
+        Message::UpdateExportKey(id, key) => {
+            Action::operation(Operation::UpdateExportKey(id, key))
+        }
+        Message::ToggleLoadReport => {
+            Action::operation(Operation::ToggleLoadReport)
+        }
+        Message::ShowPrinterItems(id) => {
+            Action::operation(Operation::ShowPrinterItems(id))
+        }
+        Message::ExportLoadReport => {
+            Action::operation(Operation::ExportLoadReport)
+        }
+        Message::ExportRoutingMap => {
+            Action::operation(Operation::ExportRoutingMap)
+        }
+        Message::RequestReroute(id) => {
+            Action::operation(Operation::RequestReroute(id))
+        }
+        Message::SelectRerouteDestination(id) => {
+            Action::operation(Operation::SelectRerouteDestination(id))
+        }
+        Message::ConfirmReroute => {
+            Action::operation(Operation::ConfirmReroute)
+        }
+        Message::CancelReroute => {
+            Action::operation(Operation::CancelReroute)
+        }
+        Message::LoadReportReady(result) | Message::RoutingMapReady(result) => {
+            match result {
+                Ok(preview) => Action::operation(Operation::PreviewExport(preview)),
+                Err(e) if e == DIALOG_CLOSED => Action::none(),
+                Err(e) => Action::operation(Operation::UpdateLoadReportMessage(format!("Printer export failed: {}", e))),
+            }
+        }
+        Message::LoadReportExported(result) => {
+            let message = match result {
+                Ok(path) => format!("Printer load report exported to {}", path.to_string_lossy()),
+                Err(e) if e == DIALOG_CLOSED => return Action::none(),
+                Err(e) => format!("Printer load report export failed: {}", e),
+            };
+            Action::operation(Operation::UpdateLoadReportMessage(message))
+        }
+        Message::RoutingMapExported(result) => {
+            let message = match result {
+                Ok(path) => format!("Printer routing map exported to {}", path.to_string_lossy()),
+                Err(e) if e == DIALOG_CLOSED => return Action::none(),
+                Err(e) => format!("Printer routing map export failed: {}", e),
+            };
+            Action::operation(Operation::UpdateLoadReportMessage(message))
+        }
+        Message::ListScrolled(viewport) => {
+            Action::operation(Operation::ListScrolled(viewport))
+        }
     }
 }
 
 pub fn view<'a>(
     all_printers: &'a BTreeMap<EntityId, PrinterLogical>,
     edit_states: &'a Vec<EditState>,
+    items: &'a BTreeMap<EntityId, Item>,
+    item_groups: &'a BTreeMap<EntityId, ItemGroup>,
+    show_load_report: bool,
+    load_report_message: &'a str,
+    sort_by_usage: bool,
+    text_style: TextStyle,
 ) -> Element<'a, Message> {
-    entity_component::entity_view(
-        "Printer Logicals",
+    let printer_list = entity_component::entity_usage_view(
+        LIST_ID,
         Message::CreateNew,
         all_printers,
         edit_states,
-        |printer, edit_states| render_printer_row(printer, edit_states),
+        usage_counts(items),
+        sort_by_usage,
+        Message::ToggleSortByUsage,
+        Message::ListScrolled,
+        move |printer, edit_states| render_printer_row(printer, edit_states, text_style),
+        text_style,
+    );
+
+    let report_toggle = row![
+        button(if show_load_report { "Hide Printer Load Report" } else { "Printer Load Report" })
+            .on_press(Message::ToggleLoadReport)
+            .style(Modern::system_button()),
+        button("Export Routing Map")
+            .on_press(Message::ExportRoutingMap)
+            .style(Modern::system_button()),
+        // The report card shows export messages while it's open
+        text(if show_load_report { "" } else { load_report_message })
+            .size(text_style.caption())
+            .style(Modern::secondary_text()),
+    ]
+    .spacing(10)
+    .align_y(iced::Alignment::Center);
+
+    if show_load_report {
+        let report = PrinterLoadReport::new(all_printers, items, item_groups);
+
+        column![
+            report_toggle,
+            row![
+                printer_list,
+                load_report_view(report, load_report_message, text_style),
+            ].spacing(20),
+        ]
+        .spacing(10)
+        .into()
+    } else {
+        column![
+            report_toggle,
+            printer_list,
+        ]
+        .spacing(10)
+        .into()
+    }
+}
+
+/// Number of items routing to each printer logical
+pub fn usage_counts(items: &BTreeMap<EntityId, Item>) -> BTreeMap<EntityId, usize> {
+    let mut counts = BTreeMap::new();
+
+    for item in items.values() {
+        for (id, _) in item.printer_logicals.iter().flatten() {
+            *counts.entry(*id).or_insert(0) += 1;
+        }
+    }
+
+    counts
+}
+
+/// Per-printer routing counts, used to balance load between kitchen stations
+#[derive(Debug, Clone)]
+pub struct PrinterLoadRow {
+    pub printer_id: EntityId,
+    pub name: String,
+    pub export_name: String,
+    pub item_count: usize,
+    pub primary_count: usize,
+    pub by_item_group: Vec<(String, usize)>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PrinterLoadReport {
+    pub rows: Vec<PrinterLoadRow>,
+    pub items_without_printer: usize,
+}
+
+impl PrinterLoadReport {
+    pub fn new(
+        printers: &BTreeMap<EntityId, PrinterLogical>,
+        items: &BTreeMap<EntityId, Item>,
+        item_groups: &BTreeMap<EntityId, ItemGroup>,
+    ) -> Self {
+        let mut rows = Vec::new();
+
+        for printer in printers.values() {
+            let mut item_count = 0;
+            let mut primary_count = 0;
+            let mut by_group: BTreeMap<String, usize> = BTreeMap::new();
+
+            for item in items.values() {
+                let routing = item.printer_logicals
+                    .as_ref()
+                    .and_then(|printers| printers.iter().find(|(id, _)| *id == printer.id));
+
+                if let Some((_, is_primary)) = routing {
+                    item_count += 1;
+                    if *is_primary {
+                        primary_count += 1;
+                    }
+
+                    let group_name = item.item_group
+                        .and_then(|id| item_groups.get(&id))
+                        .map(|group| group.name.clone())
+                        .unwrap_or_else(|| "No Item Group".to_string());
+
+                    *by_group.entry(group_name).or_insert(0) += 1;
+                }
+            }
+
+            rows.push(PrinterLoadRow {
+                printer_id: printer.id,
+                name: printer.name.clone(),
+                export_name: printer.export_name().to_string(),
+                item_count,
+                primary_count,
+                by_item_group: by_group.into_iter().collect(),
+            });
+        }
+
+        let items_without_printer = items.values()
+            .filter(|item| item.printer_logicals.as_ref().map_or(true, |printers| printers.is_empty()))
+            .count();
+
+        Self {
+            rows,
+            items_without_printer,
+        }
+    }
+
+    pub fn to_csv_string(&self) -> String {
+        let mut content = String::from("Printer ID,Printer,Items,Primary,Item Group,Group Items\n");
+
+        for row in &self.rows {
+            content.push_str(&format!(
+                "{},{},{},{},,\n",
+                row.printer_id, csv_quoted(&row.export_name), row.item_count, row.primary_count
+            ));
+            for (group, count) in &row.by_item_group {
+                content.push_str(&format!(
+                    "{},{},,,{},{}\n",
+                    row.printer_id, csv_quoted(&row.export_name), csv_quoted(group), count
+                ));
+            }
+        }
+
+        content.push_str(&format!(",\"No Printer\",{},,,\n", self.items_without_printer));
+        content
+    }
+}
+
+/// A CSV field in quotes, with quotes inside it doubled
+fn csv_quoted(value: &str) -> String {
+    format!("\"{}\"", value.replace('"', "\"\""))
+}
+
+fn load_report_view<'a>(
+    report: PrinterLoadReport,
+    load_report_message: &'a str,
+    text_style: TextStyle,
+) -> Element<'a, Message> {
+    let max_count = report.rows.iter().map(|r| r.item_count).max().unwrap_or(0).max(1);
+
+    let header_row = row![
+        text("Printer").width(Length::Fixed(150.0)),
+        text("Items").width(Length::Fixed(60.0)),
+        text("Primary").width(Length::Fixed(60.0)),
+        text("Load").width(Length::Fixed(200.0)),
+    ]
+    .padding(5);
+
+    let rows = report.rows.into_iter().map(|printer_row| {
+        let bar_width = 200.0 * printer_row.item_count as f32 / max_count as f32;
+
+        let group_breakdown = column(
+            printer_row.by_item_group.into_iter().map(|(group, count)| {
+                text(format!("{}: {}", group, count))
+                    .size(text_style.caption())
+                    .style(Modern::secondary_text())
+                    .into()
+            })
+            .collect::<Vec<_>>()
+        );
+
+        button(
+            column![
+                row![
+                    text(printer_row.name).width(Length::Fixed(150.0)),
+                    text(printer_row.item_count.to_string()).width(Length::Fixed(60.0)),
+                    text(printer_row.primary_count.to_string()).width(Length::Fixed(60.0)),
+                    container(iced::widget::horizontal_space())
+                        .width(Length::Fixed(bar_width.max(1.0)))
+                        .height(12)
+                        .style(Modern::accent_container()),
+                ].align_y(iced::Alignment::Center),
+                group_breakdown,
+            ]
+        )
+        .on_press(Message::ShowPrinterItems(printer_row.printer_id))
+        .style(Modern::system_button())
+        .width(Length::Fixed(495.0))
+        .into()
+    })
+    .collect::<Vec<_>>();
+
+    container(
+        column![
+            row![
+                text("Printer Load").size(text_style.heading()).style(Modern::primary_text()),
+                iced::widget::horizontal_space(),
+                button("Export CSV")
+                    .on_press(Message::ExportLoadReport)
+                    .style(Modern::system_button()),
+            ],
+            header_row,
+            scrollable(column(rows).spacing(5))
+                .id(scrollable::Id::new("Printer Load"))
+                .height(Length::Fill),
+            text(format!("Items with no printer: {}", report.items_without_printer)),
+            text(load_report_message).size(text_style.caption()).style(Modern::secondary_text()),
+        ]
+        .spacing(10)
+        .padding(15)
+        .width(Length::Fixed(525.0))
     )
+    .style(Modern::card_container())
+    .into()
+}
+
+/// Asks where to save the report, it's written once the preview is confirmed
+pub async fn preview_load_report(report: PrinterLoadReport) -> Result<ExportPreview, String> {
+    let path = rfd::AsyncFileDialog::new()
+        .set_title("Save Printer Load Report")
+        .add_filter("CSV Files", &["csv"])
+        .save_file()
+        .await
+        .as_ref()
+        .map(rfd::FileHandle::path)
+        .map(Path::to_owned)
+        .ok_or(DIALOG_CLOSED.to_string())?;
+
+    Ok(ExportPreview::new(ExportKind::LoadReport, vec![(path, report.to_csv_string())]))
+}
+
+pub fn export_load_report_task(report: PrinterLoadReport) -> Task<Message> {
+    Task::perform(preview_load_report(report), Message::LoadReportReady)
+}
+
+/// Items routed to one printer logical, for the install team's setup sheet
+#[derive(Debug, Clone)]
+pub struct RoutedPrinter {
+    pub printer_id: EntityId,
+    pub name: String, // The printer's export key
+    pub items: Vec<(EntityId, String, bool)>, // Item id, name and whether this is its primary printer
+}
+
+#[derive(Debug, Clone)]
+pub struct PrinterRoutingMap {
+    pub printers: Vec<RoutedPrinter>,
+}
+
+impl PrinterRoutingMap {
+    pub fn new(
+        printers: &BTreeMap<EntityId, PrinterLogical>,
+        items: &BTreeMap<EntityId, Item>,
+    ) -> Self {
+        let printers = printers
+            .values()
+            .map(|printer| RoutedPrinter {
+                printer_id: printer.id,
+                name: printer.export_name().to_string(),
+                items: items
+                    .values()
+                    .filter_map(|item| {
+                        item.printer_logicals
+                            .iter()
+                            .flatten()
+                            .find(|(id, _)| *id == printer.id)
+                            .map(|(_, is_primary)| (item.id, item.name.clone(), *is_primary))
+                    })
+                    .collect(),
+            })
+            .collect();
+
+        Self { printers }
+    }
+
+    pub fn to_csv_string(&self) -> String {
+        let mut content = String::from("Printer ID,Printer,Printer Items,Item ID,Item,Primary\n");
+
+        for printer in &self.printers {
+            if printer.items.is_empty() {
+                content.push_str(&format!("{},{},0,,,\n", printer.printer_id, csv_quoted(&printer.name)));
+            }
+            for (id, name, is_primary) in &printer.items {
+                content.push_str(&format!(
+                    "{},{},{},{},{},{}\n",
+                    printer.printer_id,
+                    csv_quoted(&printer.name),
+                    printer.items.len(),
+                    id,
+                    csv_quoted(name),
+                    if *is_primary { "Yes" } else { "No" },
+                ));
+            }
+        }
+
+        content
+    }
+
+    pub fn to_markdown(&self) -> String {
+        // Pipes would end a table cell early
+        let cell = |value: &str| value.replace('|', "\\|");
+        let mut content = String::from("# Printer Routing\n");
+
+        for printer in &self.printers {
+            content.push_str(&format!(
+                "\n## {} ({}), {} item{}\n\n",
+                cell(&printer.name),
+                printer.printer_id,
+                printer.items.len(),
+                if printer.items.len() == 1 { "" } else { "s" },
+            ));
+
+            if printer.items.is_empty() {
+                content.push_str("No items route to this printer.\n");
+                continue;
+            }
+
+            content.push_str("| Item ID | Item | Primary |\n|---|---|---|\n");
+            for (id, name, is_primary) in &printer.items {
+                content.push_str(&format!(
+                    "| {} | {} | {} |\n",
+                    id,
+                    cell(name),
+                    if *is_primary { "Yes" } else { "" },
+                ));
+            }
+        }
+
+        content
+    }
+}
+
+/// Saves as Markdown when the file is named .md, CSV otherwise
+/// Asks where to save the map, the extension picks CSV or Markdown
+pub async fn preview_routing_map(map: PrinterRoutingMap) -> Result<ExportPreview, String> {
+    let path = rfd::AsyncFileDialog::new()
+        .set_title("Save Printer Routing Map")
+        .add_filter("CSV Files", &["csv"])
+        .add_filter("Markdown Files", &["md"])
+        .save_file()
+        .await
+        .as_ref()
+        .map(rfd::FileHandle::path)
+        .map(Path::to_owned)
+        .ok_or(DIALOG_CLOSED.to_string())?;
+
+    let markdown = path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("md"));
+    let content = if markdown { map.to_markdown() } else { map.to_csv_string() };
+
+    Ok(ExportPreview::new(ExportKind::RoutingMap, vec![(path, content)]))
+}
+
+pub fn export_routing_map_task(map: PrinterRoutingMap) -> Task<Message> {
+    Task::perform(preview_routing_map(map), Message::RoutingMapReady)
 }
 
 fn render_printer_row<'a>(
     printer: &'a PrinterLogical,
-    edit_states: &'a Vec<EditState>
+    edit_states: &'a Vec<EditState>,
+    text_style: TextStyle,
 ) -> Element<'a, Message> {
-    entity_component::entity_quick_edit_view(
-        printer,
-        edit_states,
-        Message::EditPrinterLogical,
-        Message::Save,
-        Message::CopyPrinterLogical,
-        Message::RequestDelete,
-        Message::CancelEdit,
-        Message::UpdateName,
-        "Printer Name"
+    let editing = edit_states.iter().any(|state| state.id == printer.id.to_string());
+
+    // Switched off from the edit row, the badge shows either way
+    let active_toggle: Element<'a, Message> = if editing {
+        row![
+            entity_component::export_key_input(LIST_ID, printer.id, &printer.export_key, move |key| Message::UpdateExportKey(printer.id, key), text_style),
+            checkbox("Active", printer.active)
+                .on_toggle(move |active| Message::ToggleActive(printer.id, active))
+                .style(Modern::checkbox()),
+        ]
+        .spacing(5)
+        .align_y(iced::Alignment::Center)
+        .into()
+    } else {
+        row![].into()
+    };
+
+    let inactive_badge: Element<'a, Message> = if printer.active {
+        row![].into()
+    } else {
+        container(text("Inactive").size(text_style.caption()))
+            .padding([2, 6])
+            .style(Modern::accent_container())
+            .into()
+    };
+
+    row![
+        entity_component::entity_quick_edit_view(
+            LIST_ID,
+            printer,
+            edit_states,
+            Message::EditPrinterLogical,
+            Message::Save,
+            Message::CopyPrinterLogical,
+            Message::RequestDelete,
+            Message::CancelEdit,
+            Message::UpdateName,
+            "Printer Name",
+            text_style,
+        ),
+        inactive_badge,
+        active_toggle,
+        button(text("Re-route").size(text_style.caption()))
+            .on_press(Message::RequestReroute(printer.id))
+            .style(Modern::gray_button()),
+    ]
+    .spacing(5)
+    .align_y(iced::Alignment::Center)
+    .into()
+}
+
+/// Item routing as exports should see it. Inactive printers are dropped, or moved to
+/// `remap_to` when that is an active printer, keeping the primary flag.
+pub fn export_routing(
+    item: &Item,
+    printers: &BTreeMap<EntityId, PrinterLogical>,
+    remap_to: Option<EntityId>,
+) -> Option<Vec<(EntityId, bool)>> {
+    let is_active = |id: EntityId| printers.get(&id).map_or(true, |printer| printer.active);
+    let remap_to = remap_to.filter(|id| printers.get(id).is_some_and(|printer| printer.active));
+
+    item.printer_logicals.as_ref().map(|routing| {
+        let mut exported: Vec<(EntityId, bool)> = Vec::new();
+
+        for (id, is_primary) in routing {
+            let id = match (is_active(*id), remap_to) {
+                (true, _) => *id,
+                (false, Some(remap_to)) => remap_to,
+                (false, None) => continue,
+            };
+
+            match exported.iter_mut().find(|(exported_id, _)| *exported_id == id) {
+                Some((_, primary)) => *primary |= *is_primary,
+                None => exported.push((id, *is_primary)),
+            }
+        }
+
+        exported
+    })
+}
+
+/// The items with export routing applied, unchanged when every printer is active
+pub fn items_for_export(
+    items: &BTreeMap<EntityId, Item>,
+    printers: &BTreeMap<EntityId, PrinterLogical>,
+    remap_to: Option<EntityId>,
+) -> BTreeMap<EntityId, Item> {
+    let mut exported = items.clone();
+    if printers.values().all(|printer| printer.active) {
+        return exported;
+    }
+
+    for item in exported.values_mut() {
+        item.printer_logicals = export_routing(item, printers, remap_to);
+    }
+    exported
+}
+
+/// Items with printers, all of which are inactive, so nothing would print them
+pub fn items_without_active_printer<'a>(
+    items: &'a BTreeMap<EntityId, Item>,
+    printers: &BTreeMap<EntityId, PrinterLogical>,
+) -> Vec<&'a Item> {
+    items
+        .values()
+        .filter(|item| {
+            let routing = item.printer_logicals.as_deref().unwrap_or_default();
+            !routing.is_empty()
+                && routing.iter().all(|(id, _)| printers.get(id).is_some_and(|printer| !printer.active))
+        })
+        .collect()
+}
+
+/// Moving every item off one printer, e.g. when a station closes for the season.
+/// The source printer is kept so it can be used again later.
+#[derive(Debug, Clone)]
+pub struct Reroute {
+    pub source: EntityId,
+    pub destination: Option<EntityId>,
+}
+
+/// Number of items routed to a printer
+pub fn routed_item_count(items: &BTreeMap<EntityId, Item>, printer_id: EntityId) -> usize {
+    items.values()
+        .filter(|item| item.printer_logicals
+            .as_ref()
+            .map_or(false, |printers| printers.iter().any(|(id, _)| *id == printer_id)))
+        .count()
+}
+
+/// Replaces the source printer with the destination on every item, returning how many items changed
+pub fn reroute_items(
+    items: &mut BTreeMap<EntityId, Item>,
+    source: EntityId,
+    destination: EntityId,
+    updated_at: &str,
+) -> usize {
+    let mut changed = 0;
+
+    for item in items.values_mut() {
+        let Some(printers) = &mut item.printer_logicals else {
+            continue;
+        };
+
+        if let Some(rerouted) = rerouted(printers, source, destination) {
+            *printers = rerouted;
+            item.updated_at = Some(updated_at.to_string());
+            changed += 1;
+        }
+    }
+
+    changed
+}
+
+// None when the item doesn't print to the source
+fn rerouted(
+    printers: &[(EntityId, bool)],
+    source: EntityId,
+    destination: EntityId,
+) -> Option<Vec<(EntityId, bool)>> {
+    let source_index = printers.iter().position(|(id, _)| *id == source)?;
+    let source_primary = printers[source_index].1;
+
+    let mut result = printers.to_vec();
+
+    match result.iter().position(|(id, _)| *id == destination) {
+        // Already prints to the destination, merge and only take over the primary flag
+        Some(destination_index) => {
+            if source_primary {
+                result[destination_index].1 = true;
+            }
+            result.remove(source_index);
+        }
+        // Takes the source's place, primary flag included
+        None => result[source_index] = (destination, source_primary),
+    }
+
+    Some(result)
+}
+
+pub fn reroute_view<'a>(
+    reroute: &'a Reroute,
+    printers: &'a BTreeMap<EntityId, PrinterLogical>,
+    items: &'a BTreeMap<EntityId, Item>,
+    text_style: TextStyle,
+) -> Element<'a, Message> {
+    let source_name = printers
+        .get(&reroute.source)
+        .map_or_else(|| reroute.source.to_string(), |printer| printer.name.clone());
+
+    let destinations: Vec<PrinterLogical> = printers
+        .values()
+        .filter(|printer| printer.id != reroute.source)
+        .cloned()
+        .collect();
+
+    let destination = reroute.destination.and_then(|id| printers.get(&id)).cloned();
+    let affected = routed_item_count(items, reroute.source);
+
+    let summary = match &destination {
+        Some(printer) => format!("{} items will move from {} to {}.", affected, source_name, printer.name),
+        None => format!("{} items print to {}. Pick where they should go.", affected, source_name),
+    };
+
+    container(
+        column![
+            text(format!("Re-route all items from {}", source_name))
+                .style(Modern::primary_text())
+                .size(text_style.heading()),
+            pick_list(
+                destinations,
+                destination.clone(),
+                |printer: PrinterLogical| Message::SelectRerouteDestination(printer.id),
+            )
+            .placeholder("Destination printer")
+            .style(Modern::pick_list())
+            .width(Length::Fill),
+            text(summary).size(text_style.body()),
+            text(format!("{} itself is kept, so it can be used again later.", source_name))
+                .size(text_style.caption())
+                .style(Modern::secondary_text()),
+            row![
+                iced::widget::horizontal_space(),
+                button("Cancel").on_press(Message::CancelReroute).style(Modern::system_button()),
+                button("Re-route")
+                    .on_press_maybe((destination.is_some() && affected > 0).then_some(Message::ConfirmReroute))
+                    .style(Modern::warning_button()),
+            ].spacing(10),
+        ]
+        .spacing(10)
+        .padding(10)
+        .width(380)
     )
-}
\ No newline at end of file
+    .style(Modern::accent_container())
+    .into()
+}
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_report_doubles_quotes_in_names() {
+        let report = PrinterLoadReport {
+            rows: vec![PrinterLoadRow {
+                printer_id: 1,
+                name: "Kitchen".to_string(),
+                export_name: "Kitchen \"Hot\"".to_string(),
+                item_count: 2,
+                primary_count: 1,
+                by_item_group: vec![("12\" Pizzas".to_string(), 2)],
+            }],
+            items_without_printer: 3,
+        };
+
+        assert_eq!(
+            report.to_csv_string(),
+            "Printer ID,Printer,Items,Primary,Item Group,Group Items\n\
+            1,\"Kitchen \"\"Hot\"\"\",2,1,,\n\
+            1,\"Kitchen \"\"Hot\"\"\",,,\"12\"\" Pizzas\",2\n\
+            ,\"No Printer\",3,,,\n"
+        );
+    }
+}
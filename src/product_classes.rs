@@ -1,8 +1,10 @@
-use crate::data_types::{ EntityId, ValidationError };
+use crate::data_types::{ EntityId, DRAFT_ID, ValidationError };
 use crate::Action;
 use crate::entity_component::{self, Entity, EditState};
+use crate::text_style::TextStyle;
 use serde::{Serialize, Deserialize};
 use iced::Element;
+use iced::widget::scrollable;
 use std::collections::BTreeMap;
 
 #[derive(Debug, Clone)]
@@ -14,6 +16,7 @@ pub enum Message {
     UpdateName(EntityId, String),
     CreateNew,
     CancelEdit(EntityId),
+    ListScrolled(scrollable::Viewport),
 }
 
 #[derive(Debug, Clone)]
@@ -25,12 +28,17 @@ pub enum Operation {
     UpdateName(EntityId, String),
     CreateNew,
     CancelEdit(EntityId),
+    ListScrolled(scrollable::Viewport),
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ProductClass {
     pub id: EntityId,
     pub name: String,
+    #[serde(default)]
+    pub created_at: Option<String>,
+    #[serde(default)]
+    pub updated_at: Option<String>,
 }
 
 impl std::fmt::Display for ProductClass {
@@ -42,8 +50,10 @@ impl std::fmt::Display for ProductClass {
 impl Default for ProductClass {
     fn default() -> Self {
         Self {
-            id: -1,
+            id: DRAFT_ID,
             name: String::new(),
+            created_at: None,
+            updated_at: None,
         }
     }
 }
@@ -72,6 +82,14 @@ impl Entity for ProductClass {
     fn default_new() -> Self {
         Self::default()
     }
+
+    fn created_at(&self) -> Option<&str> {
+        self.created_at.as_deref()
+    }
+
+    fn updated_at(&self) -> Option<&str> {
+        self.updated_at.as_deref()
+    }
 }
 
 impl ProductClass {
@@ -104,11 +122,12 @@ impl ProductClass {
     }
 }
 
+// Title of the screen, and the id of its scrollable list
+pub const LIST_ID: &str = "Product classes";
+
 pub fn update(
-//    product_class: &mut ProductClass,
     message: Message,
-/*     state: &mut EditState,
-    other_classes: &[&ProductClass] */
+    classes: &BTreeMap<EntityId, ProductClass>,
 ) -> Action<Operation, Message> {
     match message {
         Message::RequestDelete(id) => {
@@ -122,9 +141,11 @@ pub fn update(
         },
         Message::CreateNew => {
             Action::operation(Operation::CreateNew)
+                .with_task(entity_component::reveal_new_row(LIST_ID, classes))
         },
         Message::SaveAll(id, edit_state) => {
-            Action::operation(Operation::SaveAll(id, edit_state))
+            let focus = entity_component::focus_duplicate_name(LIST_ID, classes, id, &edit_state.name);
+            Action::operation(Operation::SaveAll(id, edit_state)).with_task(focus)
         }
         Message::UpdateName(id, new_name) => {
             Action::operation(Operation::UpdateName(id, new_name))
@@ -132,27 +153,35 @@ pub fn update(
         Message::CancelEdit(id) => {
             Action::operation(Operation::CancelEdit(id))
         }
+        Message::ListScrolled(viewport) => {
+            Action::operation(Operation::ListScrolled(viewport))
+        }
     }
 }
 
 pub fn view<'a>(
     all_groups: &'a BTreeMap<EntityId, ProductClass>,
     edit_states: &'a Vec<EditState>,
+    text_style: TextStyle,
 ) -> Element<'a, Message> {
     entity_component::entity_view(
-        "Product classes",
+        LIST_ID,
         Message::CreateNew,
         all_groups,
         edit_states,
-        |product_class, edit_states| render_product_class_row(product_class, edit_states),
+        Message::ListScrolled,
+        move |product_class, edit_states| render_product_class_row(product_class, edit_states, text_style),
+        text_style,
     )
 }
 
 fn render_product_class_row<'a>(
     product_class: &'a ProductClass,
-    edit_states: &'a Vec<EditState>
+    edit_states: &'a Vec<EditState>,
+    text_style: TextStyle,
 ) -> Element<'a, Message> {
     entity_component::entity_quick_edit_view(
+        LIST_ID,
         product_class,
         edit_states,
         Message::EditProductClass,
@@ -161,13 +190,7 @@ fn render_product_class_row<'a>(
         Message::RequestDelete,
         Message::CancelEdit,
         Message::UpdateName,
-        "Product class Name"
+        "Product class Name",
+        text_style,
     )
 }
-
-fn get_next_id(classes: &BTreeMap<EntityId, ProductClass>) -> EntityId {
-    classes
-        .keys()
-        .max()
-        .map_or(1, |max_id| max_id + 1)
-}
\ No newline at end of file
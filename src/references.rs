@@ -0,0 +1,94 @@
+use crate::data_types::EntityId;
+use crate::items::Item;
+use crate::search_index::SearchKind;
+use crate::text_style::TextStyle;
+use iced_modern_theme::Modern;
+use iced::{Element, Length};
+use iced::widget::{button, column, container, row, scrollable, text};
+use std::collections::BTreeMap;
+
+/// Ids of the entities of one kind an item points at. Price levels count both
+/// item prices and the legacy price level and store price level lists.
+pub fn referenced_ids(item: &Item, kind: SearchKind) -> Vec<EntityId> {
+    let mut ids: Vec<EntityId> = match kind {
+        SearchKind::Item => Vec::new(),
+        SearchKind::ItemGroup => item.item_group.into_iter().collect(),
+        SearchKind::TaxGroup => item.tax_group.into_iter().collect(),
+        SearchKind::SecurityLevel => item.security_level.into_iter().collect(),
+        SearchKind::RevenueCategory => item.revenue_category.into_iter().collect(),
+        SearchKind::ReportCategory => item.report_category.into_iter().collect(),
+        SearchKind::ProductClass => item.product_class.into_iter().collect(),
+        SearchKind::ChoiceGroup => item.choice_groups.iter().flatten().map(|(id, _)| *id).collect(),
+        SearchKind::PrinterLogical => item.printer_logicals.iter().flatten().map(|(id, _)| *id).collect(),
+        SearchKind::PriceLevel => item.item_prices.iter().flatten().map(|price| price.price_level_id)
+            .chain(item.price_levels.iter().flatten().copied())
+            .chain(item.store_price_level.iter().flatten().copied())
+            .collect(),
+    };
+    ids.sort();
+    ids.dedup();
+    ids
+}
+
+/// Items that point at an entity, in id order
+pub fn referencing_items(
+    items: &BTreeMap<EntityId, Item>,
+    kind: SearchKind,
+    id: EntityId,
+) -> Vec<&Item> {
+    items
+        .values()
+        .filter(|item| referenced_ids(item, kind).contains(&id))
+        .collect()
+}
+
+/// "Referenced by N items" panel, each item a link to open it
+pub fn panel<'a, Message>(
+    entity_label: String,
+    referencing: Vec<&'a Item>,
+    on_open: impl Fn(EntityId) -> Message,
+    on_close: Message,
+    text_style: TextStyle,
+) -> Element<'a, Message>
+where
+    Message: Clone + 'a,
+{
+    let count = referencing.len();
+    let heading = format!("Referenced by {} item{}", count, if count == 1 { "" } else { "s" });
+
+    let links = column(
+        referencing
+            .into_iter()
+            .map(|item| {
+                button(text(format!("{} {}", item.id, item.name)).size(text_style.body()))
+                    .on_press(on_open(item.id))
+                    .style(Modern::system_button())
+                    .width(Length::Fill)
+                    .into()
+            })
+            .collect::<Vec<_>>()
+    )
+    .spacing(2);
+
+    container(
+        column![
+            row![
+                text(heading).size(text_style.subheading()).style(Modern::primary_text()),
+                iced::widget::horizontal_space(),
+                button("Close").on_press(on_close).style(Modern::system_button()),
+            ]
+            .align_y(iced::Alignment::Center),
+            text(entity_label).size(text_style.caption()).style(Modern::secondary_text()),
+            if count == 0 {
+                Element::from(text("Nothing uses this yet, it can be deleted safely").size(text_style.caption()).style(Modern::secondary_text()))
+            } else {
+                scrollable(links).height(Length::Fill).into()
+            },
+        ]
+        .spacing(10)
+    )
+    .width(Length::Fixed(300.0))
+    .padding(15)
+    .style(Modern::card_container())
+    .into()
+}
@@ -1,8 +1,10 @@
-use crate::data_types::{ EntityId, ValidationError };
+use crate::data_types::{ EntityId, DRAFT_ID, ValidationError };
 use crate::Action;
 use crate::entity_component::{self, Entity, EditState};
+use crate::text_style::TextStyle;
 use serde::{Serialize, Deserialize};
 use iced::Element;
+use iced::widget::scrollable;
 use std::collections::BTreeMap;
 
 #[derive(Debug, Clone)]
@@ -14,6 +16,7 @@ pub enum Message {
     UpdateName(EntityId, String),
     CreateNew,
     CancelEdit(EntityId),
+    ListScrolled(scrollable::Viewport),
 }
 
 #[derive(Debug, Clone)]
@@ -25,12 +28,17 @@ pub enum Operation {
     UpdateName(EntityId, String),
     CreateNew,
     CancelEdit(EntityId),
+    ListScrolled(scrollable::Viewport),
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct RevenueCategory {
     pub id: EntityId,
     pub name: String,
+    #[serde(default)]
+    pub created_at: Option<String>,
+    #[serde(default)]
+    pub updated_at: Option<String>,
 }
 
 impl std::fmt::Display for RevenueCategory {
@@ -42,8 +50,10 @@ impl std::fmt::Display for RevenueCategory {
 impl Default for RevenueCategory {
     fn default() -> Self {
         Self {
-            id: -1,
+            id: DRAFT_ID,
             name: String::new(),
+            created_at: None,
+            updated_at: None,
         }
     }
 }
@@ -72,6 +82,14 @@ impl Entity for RevenueCategory {
     fn default_new() -> Self {
         Self::default()
     }
+
+    fn created_at(&self) -> Option<&str> {
+        self.created_at.as_deref()
+    }
+
+    fn updated_at(&self) -> Option<&str> {
+        self.updated_at.as_deref()
+    }
 }
 
 impl RevenueCategory {
@@ -114,8 +132,12 @@ impl RevenueCategory {
     }
 }
 
+// Title of the screen, and the id of its scrollable list
+pub const LIST_ID: &str = "Revenue Categories";
+
 pub fn update(
     message: Message,
+    categories: &BTreeMap<EntityId, RevenueCategory>,
 ) -> Action<Operation, Message> {
     match message {
         Message::RequestDelete(id) => {
@@ -130,9 +152,11 @@ pub fn update(
         }
         Message::CreateNew => {
             Action::operation(Operation::CreateNew)
+                .with_task(entity_component::reveal_new_row(LIST_ID, categories))
         }
         Message::SaveAll(id, edit_state) => {
-            Action::operation(Operation::SaveAll(id, edit_state))
+            let focus = entity_component::focus_duplicate_name(LIST_ID, categories, id, &edit_state.name);
+            Action::operation(Operation::SaveAll(id, edit_state)).with_task(focus)
         }
         Message::UpdateName(id, new_name) => {
             Action::operation(Operation::UpdateName(id, new_name))
@@ -140,27 +164,35 @@ pub fn update(
         Message::CancelEdit(id) => {
             Action::operation(Operation::CancelEdit(id))
         }
+        Message::ListScrolled(viewport) => {
+            Action::operation(Operation::ListScrolled(viewport))
+        }
     }
 }
 
 pub fn view<'a>(
     all_categories: &'a BTreeMap<EntityId, RevenueCategory>,
     edit_states: &'a Vec<EditState>,
+    text_style: TextStyle,
 ) -> Element<'a, Message> {
     entity_component::entity_view(
-        "Revenue Categories",
+        LIST_ID,
         Message::CreateNew,
         all_categories,
         edit_states,
-        |category, edit_states| render_category_row(category, edit_states),
+        Message::ListScrolled,
+        move |category, edit_states| render_category_row(category, edit_states, text_style),
+        text_style,
     )
 }
 
 fn render_category_row<'a>(
     category: &'a RevenueCategory,
-    edit_states: &'a Vec<EditState>
+    edit_states: &'a Vec<EditState>,
+    text_style: TextStyle,
 ) -> Element<'a, Message> {
     entity_component::entity_quick_edit_view(
+        LIST_ID,
         category,
         edit_states,
         Message::EditRevenueCategory,
@@ -169,6 +201,7 @@ fn render_category_row<'a>(
         Message::RequestDelete,
         Message::CancelEdit,
         Message::UpdateName,
-        "Revenue Category Name"
+        "Revenue Category Name",
+        text_style,
     )
 }
\ No newline at end of file
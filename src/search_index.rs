@@ -0,0 +1,272 @@
+use std::collections::{BTreeMap, BTreeSet};
+use crate::data_types::EntityId;
+use crate::items::Item;
+
+/// What a search hit points at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SearchKind {
+    Item,
+    ItemGroup,
+    TaxGroup,
+    SecurityLevel,
+    RevenueCategory,
+    ReportCategory,
+    ProductClass,
+    ChoiceGroup,
+    PrinterLogical,
+    PriceLevel,
+}
+
+impl SearchKind {
+    /// From the entity type names used when deleting
+    pub fn from_entity_type(entity_type: &str) -> Option<Self> {
+        match entity_type {
+            "Item" => Some(Self::Item),
+            "ItemGroup" => Some(Self::ItemGroup),
+            "TaxGroup" => Some(Self::TaxGroup),
+            "SecurityLevel" => Some(Self::SecurityLevel),
+            "RevenueCategory" => Some(Self::RevenueCategory),
+            "ReportCategory" => Some(Self::ReportCategory),
+            "ProductClass" => Some(Self::ProductClass),
+            "ChoiceGroup" => Some(Self::ChoiceGroup),
+            "PrinterLogical" => Some(Self::PrinterLogical),
+            "PriceLevel" => Some(Self::PriceLevel),
+            _ => None,
+        }
+    }
+}
+
+/// Which part of the item matched, the name is checked first
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchField {
+    Name,
+    Id,
+    Sku,
+    Button,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchHit {
+    pub kind: SearchKind,
+    pub id: EntityId,
+    pub field: SearchField,
+}
+
+// Lowercased once when the item changes, so a query only has to compare
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Entry {
+    name: String,
+    id: String,
+    sku: String,
+    buttons: String,
+}
+
+/// Precomputed search text for every item, kept up to date as items are
+/// saved, created, copied and deleted
+#[derive(Debug, Clone, Default)]
+pub struct SearchIndex {
+    entries: BTreeMap<(SearchKind, EntityId), Entry>,
+    generation: u64, // Bumped when an entry changes, so cached hits know when they're stale
+}
+
+impl SearchIndex {
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    pub fn index_item(&mut self, item: &Item) {
+        let sku = [item.sku.as_deref(), item.bar_gun_code.as_deref()]
+            .into_iter()
+            .flatten()
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let entry = Entry {
+            name: item.name.to_lowercase(),
+            id: item.id.to_string(),
+            sku: sku.to_lowercase(),
+            buttons: format!("{}\n{}", item.button1, item.button2.as_deref().unwrap_or("")).to_lowercase(),
+        };
+
+        // Saving without changing any searched field leaves cached hits valid
+        if self.entries.get(&(SearchKind::Item, item.id)) == Some(&entry) {
+            return;
+        }
+        self.generation += 1;
+        self.entries.insert((SearchKind::Item, item.id), entry);
+    }
+
+    pub fn remove_item(&mut self, id: EntityId) {
+        if self.entries.remove(&(SearchKind::Item, id)).is_some() {
+            self.generation += 1;
+        }
+    }
+
+    /// Re-indexes one item, or drops it if it no longer exists
+    pub fn reindex_item(&mut self, items: &BTreeMap<EntityId, Item>, id: EntityId) {
+        match items.get(&id) {
+            Some(item) => self.index_item(item),
+            None => self.remove_item(id),
+        }
+    }
+
+    /// Replaces every item entry, for loads, imports and bulk edits
+    pub fn reindex_items(&mut self, items: &BTreeMap<EntityId, Item>) {
+        self.generation += 1;
+        self.entries.clear();
+        for item in items.values() {
+            self.index_item(item);
+        }
+    }
+
+    /// Items whose name contains the query, whose ID starts with it or whose
+    /// SKU, bar gun code or button text contains it
+    pub fn query(&self, query: &str) -> Vec<SearchHit> {
+        let query = query.trim().to_lowercase();
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        self.entries
+            .iter()
+            .filter_map(|(&(kind, id), entry)| {
+                let field = if entry.name.contains(&query) {
+                    SearchField::Name
+                } else if entry.id.starts_with(&query) {
+                    SearchField::Id
+                } else if entry.sku.contains(&query) {
+                    SearchField::Sku
+                } else if entry.buttons.contains(&query) {
+                    SearchField::Button
+                } else {
+                    return None;
+                };
+                Some(SearchHit { kind, id, field })
+            })
+            .collect()
+    }
+}
+
+/// Hits as a set, for checking what an item references
+pub fn hit_set(hits: &[SearchHit]) -> BTreeSet<(SearchKind, EntityId)> {
+    hits.iter().map(|hit| (hit.kind, hit.id)).collect()
+}
+
+/// Hits for the search box, only queried again when the text or the index changes
+#[derive(Debug, Clone, Default)]
+pub struct CachedHits {
+    query: String,
+    generation: u64,
+    hits: BTreeSet<(SearchKind, EntityId)>,
+}
+
+impl CachedHits {
+    pub fn refresh(&mut self, index: &SearchIndex, query: &str) {
+        if self.query == query && self.generation == index.generation() {
+            return;
+        }
+
+        self.hits = hit_set(&index.query(query));
+        self.query = query.to_string();
+        self.generation = index.generation();
+    }
+
+    pub fn hits(&self) -> &BTreeSet<(SearchKind, EntityId)> {
+        &self.hits
+    }
+
+    /// Index generation the hits were queried at
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, Instant};
+
+    fn item(id: EntityId, name: &str) -> Item {
+        Item {
+            id,
+            name: name.to_string(),
+            button1: name.chars().take(15).collect(),
+            ..Item::default()
+        }
+    }
+
+    fn items(count: EntityId) -> BTreeMap<EntityId, Item> {
+        (1..=count)
+            .map(|id| (id, item(id, &format!("Menu Item {} Special", id))))
+            .collect()
+    }
+
+    #[test]
+    fn query_matches_name_id_and_buttons() {
+        let mut index = SearchIndex::default();
+        index.index_item(&Item { sku: Some("ABC-1".to_string()), ..item(1234, "Cola") });
+
+        assert_eq!(index.query("COLA")[0].field, SearchField::Name);
+        assert_eq!(index.query("12")[0].field, SearchField::Id);
+        assert_eq!(index.query("abc")[0].field, SearchField::Sku);
+        assert!(index.query("34").is_empty());
+        assert!(index.query("   ").is_empty());
+    }
+
+    #[test]
+    fn cached_hits_follow_query_and_index_changes() {
+        let mut index = SearchIndex::default();
+        index.reindex_items(&items(3));
+        let mut cached = CachedHits::default();
+
+        cached.refresh(&index, "item 2");
+        assert_eq!(cached.hits().len(), 1);
+
+        index.index_item(&item(20, "Item 2 Large"));
+        cached.refresh(&index, "item 2");
+        assert_eq!(cached.hits().len(), 2);
+
+        index.remove_item(20);
+        cached.refresh(&index, "item 2");
+        assert_eq!(cached.hits().len(), 1);
+
+        cached.refresh(&index, "");
+        assert!(cached.hits().is_empty());
+    }
+
+    #[test]
+    fn reindexing_an_unchanged_item_keeps_the_generation() {
+        let mut index = SearchIndex::default();
+        index.reindex_items(&items(3));
+        let generation = index.generation();
+
+        index.index_item(&item(2, "Menu Item 2 Special"));
+        index.remove_item(40);
+        assert_eq!(index.generation(), generation);
+
+        index.index_item(&item(2, "Menu Item 2 Large"));
+        assert_eq!(index.generation(), generation + 1);
+    }
+
+    // Timing only means something in an optimized build: cargo test --release
+    #[test]
+    #[cfg_attr(debug_assertions, ignore)]
+    fn query_on_ten_thousand_items_is_under_a_millisecond() {
+        let mut index = SearchIndex::default();
+        index.reindex_items(&items(10_000));
+        assert_eq!(index.len(), 10_000);
+
+        let runs = 100;
+        let start = Instant::now();
+        for _ in 0..runs {
+            assert!(!index.query("special").is_empty());
+        }
+        let per_query = start.elapsed() / runs;
+
+        assert!(per_query < Duration::from_millis(1), "query took {:?}", per_query);
+    }
+}
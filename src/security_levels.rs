@@ -1,8 +1,10 @@
-use crate::data_types::{ EntityId, ValidationError };
+use crate::data_types::{ EntityId, DRAFT_ID, ValidationError };
 use crate::Action;
 use crate::entity_component::{self, Entity, EditState};
+use crate::text_style::TextStyle;
 use serde::{Serialize, Deserialize};
 use iced::Element;
+use iced::widget::scrollable;
 use std::collections::BTreeMap;
 
 #[derive(Debug, Clone)]
@@ -14,6 +16,7 @@ pub enum Message {
     UpdateName(EntityId, String),
     CreateNew,
     CancelEdit(EntityId),
+    ListScrolled(scrollable::Viewport),
 }
 
 #[derive(Debug, Clone)]
@@ -25,12 +28,17 @@ pub enum Operation {
     UpdateName(EntityId, String),
     CreateNew,
     CancelEdit(EntityId),
+    ListScrolled(scrollable::Viewport),
 }
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct SecurityLevel {
     pub id: EntityId,
     pub name: String,
+    #[serde(default)]
+    pub created_at: Option<String>,
+    #[serde(default)]
+    pub updated_at: Option<String>,
 }
 
 impl std::fmt::Display for SecurityLevel {
@@ -42,8 +50,10 @@ impl std::fmt::Display for SecurityLevel {
 impl Default for SecurityLevel {
     fn default() -> Self {
         Self {
-            id: -1,
+            id: DRAFT_ID,
             name: String::new(),
+            created_at: None,
+            updated_at: None,
         }
     }
 }
@@ -72,6 +82,14 @@ impl Entity for SecurityLevel {
     fn default_new() -> Self {
         Self::default()
     }
+
+    fn created_at(&self) -> Option<&str> {
+        self.created_at.as_deref()
+    }
+
+    fn updated_at(&self) -> Option<&str> {
+        self.updated_at.as_deref()
+    }
 }
 
 impl SecurityLevel {
@@ -104,8 +122,12 @@ impl SecurityLevel {
     }
 }
 
+// Title of the screen, and the id of its scrollable list
+pub const LIST_ID: &str = "Security Levels";
+
 pub fn update(
     message: Message,
+    levels: &BTreeMap<EntityId, SecurityLevel>,
 ) -> Action<Operation, Message> {
     match message {
         Message::RequestDelete(id) => {
@@ -119,9 +141,11 @@ pub fn update(
         },
         Message::CreateNew => {
             Action::operation(Operation::CreateNew)
+                .with_task(entity_component::reveal_new_row(LIST_ID, levels))
         },
         Message::SaveAll(id, edit_state) => {
-            Action::operation(Operation::SaveAll(id, edit_state))
+            let focus = entity_component::focus_duplicate_name(LIST_ID, levels, id, &edit_state.name);
+            Action::operation(Operation::SaveAll(id, edit_state)).with_task(focus)
         }
         Message::UpdateName(id, new_name) => {
             Action::operation(Operation::UpdateName(id, new_name))
@@ -129,27 +153,35 @@ pub fn update(
         Message::CancelEdit(id) => {
             Action::operation(Operation::CancelEdit(id))
         }
+        Message::ListScrolled(viewport) => {
+            Action::operation(Operation::ListScrolled(viewport))
+        }
     }
 }
 
 pub fn view<'a>(
     all_levels: &'a BTreeMap<EntityId, SecurityLevel>,
     edit_states: &'a Vec<EditState>,
+    text_style: TextStyle,
 ) -> Element<'a, Message> {
     entity_component::entity_view(
-        "Security Levels",
+        LIST_ID,
         Message::CreateNew,
         all_levels,
         edit_states,
-        |security_level, edit_states| render_security_level_row(security_level, edit_states),
+        Message::ListScrolled,
+        move |security_level, edit_states| render_security_level_row(security_level, edit_states, text_style),
+        text_style,
     )
 }
 
 fn render_security_level_row<'a>(
     security_level: &'a SecurityLevel,
-    edit_states: &'a Vec<EditState>
+    edit_states: &'a Vec<EditState>,
+    text_style: TextStyle,
 ) -> Element<'a, Message> {
     entity_component::entity_quick_edit_view(
+        LIST_ID,
         security_level,
         edit_states,
         Message::EditSecurityLevel,
@@ -158,13 +190,7 @@ fn render_security_level_row<'a>(
         Message::RequestDelete,
         Message::CancelEdit,
         Message::UpdateName,
-        "Security Level Name"
+        "Security Level Name",
+        text_style,
     )
 }
-
-fn get_next_id(levels: &BTreeMap<EntityId, SecurityLevel>) -> EntityId {
-    levels
-        .keys()
-        .max()
-        .map_or(1, |max_id| max_id + 1)
-}
\ No newline at end of file
@@ -1,9 +1,28 @@
-use iced::widget::{button, checkbox, column, container, row, text, text_input};
-use iced::{Element, Task};
+use iced::widget::{button, checkbox, column, container, pick_list, row, scrollable, text, text_input};
+use iced::{Element, Length, Task};
 pub use iced::window::Settings;
 use iced_modern_theme::Modern;
 use serde::{Serialize, Deserialize};
 use crate::persistence;
+use crate::text_style::TextStyle;
+use crate::data_types::{EntityId, IdRange};
+use crate::entity_component::{self, EntityOption};
+use crate::tax_groups::TaxGroup;
+use crate::printer_logicals::PrinterLogical;
+use crate::price_levels::PriceLevel;
+use crate::items::Classification;
+use crate::items::printable_menu::{self, MenuGrouping};
+use crate::items::item_patch::{self, ItemPatch};
+use crate::items::store_fields;
+use crate::items::edit::EditSection;
+use crate::items::export_layout::{ExportPreset, ExportProfile};
+use crate::items::abbreviate::{self, Abbreviation};
+use crate::items::delta_export::{self, ItemTombstone};
+use crate::items::price_compare::PriceSanityRules;
+use crate::items::button_collisions::ButtonCollision;
+use crate::import_entities::{self, EntityCsvKind, EntityImport};
+use crate::sidebar::{self, SidebarScreen};
+use crate::export_preview::{ExportKind, ExportPreview};
 use std::collections::BTreeMap;
 use std::fmt;
 use std::sync::Arc;
@@ -12,31 +31,233 @@ use std::io;
 
 #[derive(Debug, Clone)]
 pub enum Message {
+    SelectSettingsTab(SettingsTab),
+    UpdateSettingsSearch(String),
     UpdateFilePath(String),
     ToggleAutoSave(bool),
     ToggleBackups(bool),
+    ToggleSnapshots(bool),
+    ToggleRequiredClassification(Classification, bool),
+    UpdateMaxStoreId(String),
+    UpdateMinItemId(String),
+    UpdateMaxItemId(String),
+    UpdatePreviewPageSize(String),
+    UpdateCustomFlagLabel(usize, String),
+    TogglePriceAboveDefaultCheck(bool),
+    TogglePriceBelowCostCheck(bool),
+    DefaultTaxGroupChanged(EntityOption),
+    ClearDefaultTaxGroup,
+    InactivePrinterRemapChanged(EntityOption),
+    ClearInactivePrinterRemap,
+    AddAbbreviation,
+    UpdateAbbreviationFull(usize, String),
+    UpdateAbbreviationShort(usize, String),
+    RemoveAbbreviation(usize),
+    OpenButtonFill,
+    OpenTemplates,
     ValidateAndSave,
     Back,
     ShowError(String),
     ThemeChanged(ThemeChoice),
+    TextSizeChanged(TextSize),
+    ToggleMonospaceData(bool),
     ExportItemsToCSV,
+    ExportPresetChanged(ExportPreset),
+    ResetExportLayout,
+    ToggleExportHeaderRow(bool),
+    ToggleExportColumn(usize, bool),
+    UpdateExportHeader(usize, String),
+    MoveExportColumn(usize, bool),
     OpenFile,
     FileOpened(Result<(PathBuf, Option<Arc<String>>), Error>),
     ProcessItems((BTreeMap<i32, crate::items::Item>, PathBuf)),
     ExportMessage(Result<PathBuf, Error>),
+    UpdateDeltaSince(String),
+    UseLastExportDate,
+    UpdateTombstoneRetention(String),
+    ExportChangedItems,
+    DeltaExportPathChosen(Result<PathBuf, Error>),
+    ProcessDeltaExport((Vec<crate::items::Item>, Vec<ItemTombstone>, PathBuf)),
+    DeltaExportFinished(Result<(PathBuf, usize, usize), Error>),
     UpdateExportSuccess(bool),
     UpdateExportMessage(String),
+    ExportItemsToJson,
+    JsonExportPathChosen(Result<PathBuf, Error>),
+    ProcessJsonExport((crate::items::json_interchange::ItemsExport, PathBuf)),
+    MenuGroupingChanged(MenuGrouping),
+    ToggleMenuIncludeInactive(bool),
+    TogglePivotPriceLevel(EntityId, bool),
+    ExportPricePivot,
+    PricePivotPathChosen(Result<PathBuf, Error>),
+    ProcessPricePivot((String, PathBuf)),
+    PricePivotExported(Result<PathBuf, Error>),
+    ExportPrintableMenu,
+    PrintableMenuPathChosen(Result<PathBuf, Error>),
+    ProcessPrintableMenu((String, PathBuf)),
+    PrintableMenuExported(Result<PathBuf, Error>),
+    DiffDatabases,
+    DatabaseDiffReady(Result<ExportPreview, String>),
+    DatabaseDiffSaved(Result<(usize, PathBuf), String>),
+    ApplyPatch,
+    PatchOpened(Result<ItemPatch, String>),
+    RestoreBackup,
+    BackupChosen(Result<PathBuf, String>),
+    EntityImportKindChanged(EntityCsvKind),
+    UpdateCsvLeadingLines(String),
+    ToggleLatin1Fallback(bool),
+    ImportEntities,
+    EntitiesOpened(Result<EntityImport, String>),
+    ValidateAll,
+    ToggleBlockDuplicateButtons(bool),
+    ToggleRequireAskPricePrompt(bool),
+    OpenItem(EntityId),
+    SyncPriceLevels,
+    FixPrimaryPrinters,
+    ExportItemsByGroup,
+    GroupExportFolderChosen(Result<PathBuf, Error>),
+    ProcessGroupExport((Vec<(String, Vec<crate::items::Item>)>, PathBuf)),
+    GroupExportFinished(Result<(usize, PathBuf), Error>),
+    PreviewIdFix,
+    ApplyIdFix,
+    CancelIdFix,
+    ShowUnusedEntities,
+    DeleteUnused(String),
+    ConfirmDeleteUnused,
+    CancelDeleteUnused,
+    ChooseDataDirectory,
+    DataDirectoryChosen(Result<PathBuf, Error>),
+    TransferDataDirectory(DataDirTransfer),
+    DataFileCopied(Result<PathBuf, String>),
+    CancelDataDirectoryChange,
+    ExportWritten(ExportPreview, Result<(), Error>),
 }
 
 #[derive(Debug, Clone)]
 pub enum Operation {
+    SelectSettingsTab(SettingsTab),
+    UpdateSettingsSearch(String),
     Save(AppSettings),
     Back,
-    ShowError(String),
+    ShowError(SettingsTab, String), // The tab with the setting the error is about
     ThemeChanged(ThemeChoice),
+    TextStyleChanged,
     RequestItemsList(PathBuf),
+    RequestDeltaExport(PathBuf, chrono::DateTime<chrono::Utc>),
     UpdateExportSuccess(bool),
     UpdateExportMessage(String),
+    RequestJsonExport(PathBuf),
+    RequestPrintableMenu(PathBuf),
+    RequestPricePivot(PathBuf),
+    PreviewExport(ExportPreview),
+    ApplyPatch(ItemPatch),
+    RestoreBackup(PathBuf),
+    ImportEntities(EntityImport),
+    ValidateAll,
+    OpenItem(EntityId),
+    SyncPriceLevels,
+    FixPrimaryPrinters,
+    RequestGroupExport(PathBuf),
+    PreviewIdFix,
+    ApplyIdFix,
+    CancelIdFix,
+    ShowUnusedEntities,
+    DeleteUnused(String),
+    ConfirmDeleteUnused,
+    CancelDeleteUnused,
+    OpenButtonFill,
+    OpenTemplates,
+    PrepareDataDirectory(PathBuf),
+    TransferDataDirectory(DataDirTransfer),
+    DataFileCopied(Result<PathBuf, String>),
+    CancelDataDirectoryChange,
+}
+
+/// What happens to the current files when switching data directories
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataDirTransfer {
+    Copy,
+    Move,
+    SwitchOnly, // Use whatever is in the new folder, e.g. a team's synced copy
+}
+
+/// A data directory switch waiting on the user's choice, or partway through copying
+#[derive(Debug, Clone)]
+pub struct DataDirChange {
+    pub target: PathBuf,
+    pub files: Vec<PathBuf>,      // Data files in the current directory
+    pub target_has_data: bool,    // The new folder already holds a save file
+    pub transfer: Option<DataDirTransfer>, // Set while copying
+    pub copied: Vec<PathBuf>,     // Written to the new folder so far
+    pub error: Option<String>,
+}
+
+/// A tab of the settings screen
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SettingsTab {
+    #[default]
+    General,
+    Appearance,
+    FilesAndBackups,
+    Exports,
+    Validation,
+    Advanced,
+}
+
+impl SettingsTab {
+    pub const ALL: &'static [Self] = &[
+        Self::General,
+        Self::Appearance,
+        Self::FilesAndBackups,
+        Self::Exports,
+        Self::Validation,
+        Self::Advanced,
+    ];
+}
+
+impl fmt::Display for SettingsTab {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::General => write!(f, "General"),
+            Self::Appearance => write!(f, "Appearance"),
+            Self::FilesAndBackups => write!(f, "Files & Backups"),
+            Self::Exports => write!(f, "Exports"),
+            Self::Validation => write!(f, "Validation"),
+            Self::Advanced => write!(f, "Advanced"),
+        }
+    }
+}
+
+/// Which settings tab is open and what the search has in it, kept for the session only
+#[derive(Debug, Clone, Default)]
+pub struct SettingsNav {
+    pub tab: SettingsTab,
+    pub search: String,
+    pub error: Option<(SettingsTab, String)>, // The last settings error and the tab it belongs on
+}
+
+/// The data directory section of the settings screen
+#[derive(Debug, Clone, Default)]
+pub struct DataDirState {
+    pub change: Option<DataDirChange>,
+    pub status: String,
+}
+
+/// Results of the data checks shown on the settings screen
+#[derive(Debug, Clone, Default)]
+pub struct DataChecks {
+    pub report: Vec<String>,
+    pub button_collisions: Vec<ButtonCollision>, // Same button text within an item group
+    pub id_fix_preview: Vec<(EntityId, Option<EntityId>)>,
+    pub unused_entities: Vec<UnusedEntities>,
+    pub pending_unused_delete: Option<String>,
+}
+
+/// Entities of one type that no item references
+#[derive(Debug, Clone)]
+pub struct UnusedEntities {
+    pub entity_type: String,
+    pub label: String,
+    pub entities: Vec<(EntityId, String)>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -44,25 +265,132 @@ pub struct AppSettings {
     pub file_path: String,
     pub auto_save: bool,
     pub create_backups: bool,
+    #[serde(default = "default_true")]
+    pub snapshot_before_bulk_changes: bool,
     pub app_theme: ThemeChoice,
+    #[serde(default = "default_required_classifications")]
+    pub required_classifications: Vec<Classification>,
+    #[serde(default = "default_max_store_id")]
+    pub max_store_id: i32, // Until stores are their own entity
+    #[serde(default)]
+    pub text_size: TextSize,
+    #[serde(default)]
+    pub monospace_data: bool,
     pub export_success: bool,
     pub export_message: String,
+    #[serde(default)]
+    pub menu_grouping: MenuGrouping,
+    #[serde(default)]
+    pub menu_include_inactive: bool,
+    #[serde(default)]
+    pub pivot_excluded_levels: Vec<EntityId>, // Price levels left out of the price pivot export
+    #[serde(default = "sidebar::default_order")]
+    pub sidebar_order: Vec<SidebarScreen>,
+    #[serde(default)]
+    pub favorite_screens: Vec<SidebarScreen>,
+    #[serde(default)]
+    pub collapsed_edit_sections: Vec<EditSection>, // Item edit form sections the user closed
+    #[serde(default)]
+    pub export_profile: ExportProfile,
+    #[serde(default = "default_custom_flag_labels")]
+    pub custom_flag_labels: [String; 2], // Names for the items' reserved1 and reserved2 flags
+    #[serde(default)]
+    pub default_tax_group: Option<EntityId>, // Given to new items and imported rows without one
+    #[serde(default = "abbreviate::default_abbreviations")]
+    pub abbreviations: Vec<Abbreviation>, // House style for fitting names into button text
+    #[serde(default)]
+    pub entity_import_kind: EntityCsvKind,
+    #[serde(default)]
+    pub delta_since: String, // Date typed for "Only items modified after"
+    #[serde(default)]
+    pub last_export_at: Option<String>,
+    #[serde(default = "default_tombstone_retention_days")]
+    pub tombstone_retention_days: u32, // Item deletions older than this are dropped on save, 0 keeps them
+    #[serde(default)]
+    pub price_sanity: PriceSanityRules,
+    #[serde(default)]
+    pub csv_leading_lines: Option<usize>, // Title lines above the item CSV data, None finds them by the header
+    #[serde(default = "default_true")]
+    pub import_latin1_fallback: bool, // Read item CSVs that aren't UTF-8 as Latin-1 instead of refusing them
+    #[serde(default)]
+    pub pinned_items: Vec<EntityId>, // Shown above the item list for quick access, in the order pinned
+    #[serde(default)]
+    pub block_duplicate_button_text: bool, // Refuse to save items whose button text repeats in their group
+    #[serde(default)]
+    pub require_ask_price_prompt: bool, // Validate All flags ask-price items without a till prompt
+    #[serde(default)]
+    pub inactive_printer_remap: Option<EntityId>, // Exports route inactive printers' items here, None leaves them off
+    #[serde(default)]
+    pub item_id_range: IdRange, // New item ids are handed out inside this, Validate All flags items outside it
+    #[serde(default = "default_preview_page_size")]
+    pub preview_page_size: usize, // SuperEdit preview rows shown at once
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_required_classifications() -> Vec<Classification> {
+    Classification::ALL.to_vec()
+}
+
+fn default_custom_flag_labels() -> [String; 2] {
+    ["Custom Flag 1".to_string(), "Custom Flag 2".to_string()]
+}
+
+fn default_tombstone_retention_days() -> u32 {
+    delta_export::DEFAULT_TOMBSTONE_RETENTION_DAYS
+}
+
+fn default_preview_page_size() -> usize {
+    200
+}
+
+fn default_max_store_id() -> i32 {
+    store_fields::DEFAULT_MAX_STORE_ID
 }
 
 impl Default for AppSettings {
     fn default() -> Self {
-        let file_manager = persistence::FileManager::new()
-            .expect("Failed to initialize file manager");
-        
+        let file_manager = persistence::FileManager::new();
+
         Self {
             file_path: file_manager.get_default_path()
                 .to_string_lossy()
                 .into_owned(),
             auto_save: true,
             create_backups: true,
+            snapshot_before_bulk_changes: true,
             app_theme: ThemeChoice::Dark,
+            required_classifications: default_required_classifications(),
+            max_store_id: default_max_store_id(),
+            text_size: TextSize::Medium,
+            monospace_data: false,
             export_success: true,
             export_message: String::new(),
+            menu_grouping: MenuGrouping::default(),
+            menu_include_inactive: false,
+            pivot_excluded_levels: Vec::new(),
+            sidebar_order: sidebar::default_order(),
+            favorite_screens: Vec::new(),
+            collapsed_edit_sections: Vec::new(),
+            export_profile: ExportProfile::default(),
+            custom_flag_labels: default_custom_flag_labels(),
+            default_tax_group: None,
+            abbreviations: abbreviate::default_abbreviations(),
+            entity_import_kind: EntityCsvKind::default(),
+            delta_since: String::new(),
+            last_export_at: None,
+            tombstone_retention_days: default_tombstone_retention_days(),
+            price_sanity: PriceSanityRules::default(),
+            csv_leading_lines: None,
+            import_latin1_fallback: true,
+            pinned_items: Vec::new(),
+            block_duplicate_button_text: false,
+            require_ask_price_prompt: false,
+            inactive_printer_remap: None,
+            item_id_range: IdRange::default(),
+            preview_page_size: default_preview_page_size(),
         }
     }
 }
@@ -85,15 +413,129 @@ pub fn update(
             settings.create_backups = enabled;
             crate::Action::none()
         }
+        Message::ToggleSnapshots(enabled) => {
+            settings.snapshot_before_bulk_changes = enabled;
+            crate::Action::none()
+        }
+        Message::ToggleRequiredClassification(classification, required) => {
+            settings.required_classifications.retain(|c| *c != classification);
+            if required {
+                settings.required_classifications.push(classification);
+            }
+            // Keep the dashboard order stable
+            settings.required_classifications.sort_by_key(|c| {
+                Classification::ALL.iter().position(|other| other == c)
+            });
+            crate::Action::none()
+        }
+        Message::UpdateMaxStoreId(value) => {
+            if value.is_empty() {
+                settings.max_store_id = 0;
+            } else if let Ok(max) = value.parse::<u16>() {
+                settings.max_store_id = max as i32;
+            }
+            crate::Action::none()
+        }
+        Message::UpdatePreviewPageSize(value) => {
+            if value.is_empty() {
+                settings.preview_page_size = 0;
+            } else if let Ok(size) = value.parse::<u16>() {
+                settings.preview_page_size = size as usize;
+            }
+            crate::Action::none()
+        }
+        Message::UpdateMinItemId(value) => {
+            if value.is_empty() {
+                settings.item_id_range.min = 0;
+            } else if let Ok(min) = value.parse::<u32>() {
+                settings.item_id_range.min = min.min(EntityId::MAX as u32) as EntityId;
+            }
+            crate::Action::none()
+        }
+        Message::UpdateMaxItemId(value) => {
+            if value.is_empty() {
+                settings.item_id_range.max = 0;
+            } else if let Ok(max) = value.parse::<u32>() {
+                settings.item_id_range.max = max.min(EntityId::MAX as u32) as EntityId;
+            }
+            crate::Action::none()
+        }
+        Message::UpdateCustomFlagLabel(index, label) => {
+            if let Some(existing) = settings.custom_flag_labels.get_mut(index) {
+                *existing = label;
+            }
+            crate::Action::none()
+        }
+        Message::TogglePriceAboveDefaultCheck(enabled) => {
+            settings.price_sanity.level_above_default = enabled;
+            crate::Action::none()
+        }
+        Message::TogglePriceBelowCostCheck(enabled) => {
+            settings.price_sanity.below_cost = enabled;
+            crate::Action::none()
+        }
+        Message::DefaultTaxGroupChanged(option) => {
+            settings.default_tax_group = Some(option.id);
+            crate::Action::none()
+        }
+        Message::ClearDefaultTaxGroup => {
+            settings.default_tax_group = None;
+            crate::Action::none()
+        }
+        Message::InactivePrinterRemapChanged(option) => {
+            settings.inactive_printer_remap = Some(option.id);
+            crate::Action::none()
+        }
+        Message::ClearInactivePrinterRemap => {
+            settings.inactive_printer_remap = None;
+            crate::Action::none()
+        }
+        Message::AddAbbreviation => {
+            settings.abbreviations.push(Abbreviation { full: String::new(), short: String::new() });
+            crate::Action::none()
+        }
+        Message::UpdateAbbreviationFull(index, full) => {
+            if let Some(entry) = settings.abbreviations.get_mut(index) {
+                entry.full = full;
+            }
+            crate::Action::none()
+        }
+        Message::UpdateAbbreviationShort(index, short) => {
+            if let Some(entry) = settings.abbreviations.get_mut(index) {
+                entry.short = short;
+            }
+            crate::Action::none()
+        }
+        Message::RemoveAbbreviation(index) => {
+            if index < settings.abbreviations.len() {
+                settings.abbreviations.remove(index);
+            }
+            crate::Action::none()
+        }
+        Message::OpenButtonFill => crate::Action::operation(Operation::OpenButtonFill),
+        Message::OpenTemplates => crate::Action::operation(Operation::OpenTemplates),
         Message::ValidateAndSave => {
+            if settings.item_id_range.min < 1 {
+                return crate::Action::operation(Operation::ShowError(SettingsTab::General, "Item IDs must start at 1 or higher".to_string()));
+            }
+            if settings.item_id_range.min > settings.item_id_range.max {
+                return crate::Action::operation(Operation::ShowError(SettingsTab::General, format!(
+                    "The item ID range starts at {} but ends at {}", settings.item_id_range.min, settings.item_id_range.max
+                )));
+            }
+            if settings.preview_page_size < 1 {
+                return crate::Action::operation(Operation::ShowError(SettingsTab::Advanced, "SuperEdit previews need at least 1 row per page".to_string()));
+            }
             match file_manager.validate_path(&settings.file_path) {
                 Ok(()) => crate::Action::operation(Operation::Save(settings.clone())),
-                Err(e) => crate::Action::operation(Operation::ShowError(e)),
+                Err(e) => crate::Action::operation(Operation::ShowError(SettingsTab::FilesAndBackups, e)),
             }
         }
         Message::Back => {
             crate::Action::operation(Operation::Back)
         }
+        Message::SelectSettingsTab(tab) => crate::Action::operation(Operation::SelectSettingsTab(tab)),
+        Message::UpdateSettingsSearch(query) => crate::Action::operation(Operation::UpdateSettingsSearch(query)),
         Message::ShowError(_) => {
             crate::Action::none()
         }
@@ -101,9 +543,47 @@ pub fn update(
             settings.app_theme = theme.clone();
             crate::Action::operation(Operation::ThemeChanged(theme))
         }
+        Message::TextSizeChanged(text_size) => {
+            settings.text_size = text_size;
+            crate::Action::operation(Operation::TextStyleChanged)
+        }
+        Message::ToggleMonospaceData(enabled) => {
+            settings.monospace_data = enabled;
+            crate::Action::operation(Operation::TextStyleChanged)
+        }
         Message::ExportItemsToCSV => {
             crate::Action::none()
         }
+        Message::ExportPresetChanged(preset) => {
+            settings.export_profile.preset = preset;
+            crate::Action::none()
+        }
+        Message::ResetExportLayout => {
+            settings.export_profile = settings.export_profile.preset.profile();
+            crate::Action::none()
+        }
+        Message::ToggleExportHeaderRow(include) => {
+            settings.export_profile.include_header = include;
+            crate::Action::none()
+        }
+        Message::ToggleExportColumn(index, included) => {
+            settings.export_profile.fill_missing();
+            if let Some(column) = settings.export_profile.columns.get_mut(index) {
+                column.included = included;
+            }
+            crate::Action::none()
+        }
+        Message::UpdateExportHeader(index, header) => {
+            settings.export_profile.fill_missing();
+            if let Some(column) = settings.export_profile.columns.get_mut(index) {
+                column.header = header;
+            }
+            crate::Action::none()
+        }
+        Message::MoveExportColumn(index, up) => {
+            settings.export_profile.move_column(index, up);
+            crate::Action::none()
+        }
         Message::OpenFile => {
             let task = Task::perform(open_or_create_file(), Message::FileOpened);
 
@@ -119,6 +599,10 @@ pub fn update(
 
                     return crate::Action::operation(Operation::RequestItemsList(path)).with_task(update_message)
                 }
+                Err(Error::DialogClosed) => {
+                    println!("Dialog closed");
+                    crate::Action::none()
+                }
                 Err(e) => {
                     println!("Error with the path: {:?}", e);
                     let update_success_task = Task::done(Message::UpdateExportSuccess(false));
@@ -135,20 +619,15 @@ pub fn update(
             println!("Item Count: {}", &items.len());
             println!("Path: {:?}", &path);
 
-            let task = Task::perform(
-                write_to_item_export(items, Some(path)),
-                Message::ExportMessage
-            );
-            println!("Task Created");
-            println!("export message: {:?}", settings.export_message);
-            println!("export success: {:?}", settings.export_success);
+            let content = crate::items::export_items::items_to_export_string(items.values(), &settings.export_profile);
 
-            return crate::Action::none().with_task(task)
+            crate::Action::operation(Operation::PreviewExport(ExportPreview::new(ExportKind::Items, vec![(path, content)])))
         }
         Message::ExportMessage(result) => {
             println!("Export Message triggered: {:?}", &result);
             match result {
                 Ok(saved_path) => {
+                    settings.last_export_at = Some(crate::data_types::timestamp_now());
                     let update_success_task = Task::done(Message::UpdateExportSuccess(true));
                     let update_message_task = Task::done(Message::UpdateExportMessage(format!("Items successfully exported to {}", saved_path.to_string_lossy().to_string())));
 
@@ -158,7 +637,7 @@ pub fn update(
                 }
                 Err(e) => {
                     let update_success_task = Task::done(Message::UpdateExportSuccess(false));
-                    let update_message_task = Task::done(Message::UpdateExportMessage(format!("Items successfully exported to {:?}", e)));
+                    let update_message_task = Task::done(Message::UpdateExportMessage(format!("Item export failed: {:?}", e)));
 
                     let combined_task = update_success_task.chain(update_message_task);
 
@@ -166,88 +645,1266 @@ pub fn update(
                 }
             }
         }
+        Message::UpdateDeltaSince(since) => {
+            settings.delta_since = since;
+            crate::Action::none()
+        }
+        Message::UseLastExportDate => {
+            if let Some(last_export_at) = &settings.last_export_at {
+                settings.delta_since = crate::data_types::display_timestamp(last_export_at);
+            }
+            crate::Action::none()
+        }
+        Message::UpdateCsvLeadingLines(value) => {
+            if value.trim().is_empty() {
+                settings.csv_leading_lines = None;
+            } else if let Ok(lines) = value.trim().parse::<usize>() {
+                settings.csv_leading_lines = Some(lines);
+            }
+            crate::Action::none()
+        }
+        Message::ToggleLatin1Fallback(enabled) => {
+            settings.import_latin1_fallback = enabled;
+            crate::Action::none()
+        }
+        Message::UpdateTombstoneRetention(value) => {
+            if value.is_empty() {
+                settings.tombstone_retention_days = 0;
+            } else if let Ok(days) = value.parse::<u32>() {
+                settings.tombstone_retention_days = days;
+            }
+            crate::Action::none()
+        }
+        Message::ExportChangedItems => {
+            if let Err(e) = delta_export::parse_since(&settings.delta_since) {
+                settings.export_success = false;
+                settings.export_message = e;
+                return crate::Action::none();
+            }
+
+            let task = Task::perform(choose_delta_export_path(), Message::DeltaExportPathChosen);
+
+            crate::Action::none().with_task(task)
+        }
+        Message::DeltaExportPathChosen(result) => {
+            match result {
+                Ok(path) => match delta_export::parse_since(&settings.delta_since) {
+                    Ok(since) => crate::Action::operation(Operation::RequestDeltaExport(path, since)),
+                    Err(e) => {
+                        settings.export_success = false;
+                        settings.export_message = e;
+                        crate::Action::none()
+                    }
+                },
+                Err(Error::DialogClosed) => crate::Action::none(),
+                Err(e) => {
+                    let update_success_task = Task::done(Message::UpdateExportSuccess(false));
+                    let update_message_task = Task::done(Message::UpdateExportMessage(format!("Error with the path: {:?}", e)));
+
+                    crate::Action::none().with_task(update_success_task.chain(update_message_task))
+                }
+            }
+        }
+        Message::ProcessDeltaExport((items, deleted, path)) => {
+            let preview = delta_export_preview(items, deleted, path, &settings.export_profile);
+
+            crate::Action::operation(Operation::PreviewExport(preview))
+        }
+        Message::DeltaExportFinished(result) => {
+            let (success, message) = match result {
+                Ok((path, changed, deleted)) => {
+                    settings.last_export_at = Some(crate::data_types::timestamp_now());
+                    (true, format!(
+                        "Exported {} changed items to {}, {} deletions to {}",
+                        changed,
+                        path.to_string_lossy(),
+                        deleted,
+                        delta_export::deletions_path(&path).to_string_lossy()
+                    ))
+                }
+                Err(e) => (false, format!("Delta export failed: {:?}", e)),
+            };
+
+            let update_success_task = Task::done(Message::UpdateExportSuccess(success));
+            let update_message_task = Task::done(Message::UpdateExportMessage(message));
+
+            crate::Action::none().with_task(update_success_task.chain(update_message_task))
+        }
         Message::UpdateExportSuccess(b) => crate::Action::operation(Operation::UpdateExportSuccess(b)),
         Message::UpdateExportMessage(msg) => crate::Action::operation(Operation::UpdateExportMessage(msg)),
+        Message::ExportItemsToJson => {
+            let task = Task::perform(choose_json_export_path(), Message::JsonExportPathChosen);
+
+            crate::Action::none().with_task(task)
+        }
+        Message::JsonExportPathChosen(result) => {
+            match result {
+                Ok(path) => {
+                    println!("Exporting JSON export to: {:?}", path);
+                    crate::Action::operation(Operation::RequestJsonExport(path))
+                }
+                Err(Error::DialogClosed) => {
+                    println!("Dialog closed");
+                    crate::Action::none()
+                }
+                Err(e) => {
+                    println!("Error with the path: {:?}", e);
+                    let update_success_task = Task::done(Message::UpdateExportSuccess(false));
+                    let update_message_task = Task::done(Message::UpdateExportMessage(format!("Error with the path: {:?}", e)));
+
+                    crate::Action::none().with_task(update_success_task.chain(update_message_task))
+                }
+            }
+        }
+        Message::ProcessJsonExport( (export, path) ) => {
+            println!("Processing JSON export, item count: {}", export.items.len());
+
+            match export.to_json_string() {
+                Ok(content) => crate::Action::operation(Operation::PreviewExport(ExportPreview::new(ExportKind::Json, vec![(path, content)]))),
+                Err(e) => {
+                    let update_success_task = Task::done(Message::UpdateExportSuccess(false));
+                    let update_message_task = Task::done(Message::UpdateExportMessage(format!("JSON export failed: {}", e)));
+
+                    crate::Action::none().with_task(update_success_task.chain(update_message_task))
+                }
+            }
+        }
+        Message::MenuGroupingChanged(grouping) => {
+            settings.menu_grouping = grouping;
+            crate::Action::none()
+        }
+        Message::ToggleMenuIncludeInactive(include) => {
+            settings.menu_include_inactive = include;
+            crate::Action::none()
+        }
+        Message::ExportPrintableMenu => {
+            let task = Task::perform(choose_printable_menu_path(), Message::PrintableMenuPathChosen);
+
+            crate::Action::none().with_task(task)
+        }
+        Message::PrintableMenuPathChosen(result) => {
+            match result {
+                Ok(path) => {
+                    println!("Exporting printable menu to: {:?}", path);
+                    crate::Action::operation(Operation::RequestPrintableMenu(path))
+                }
+                Err(Error::DialogClosed) => {
+                    println!("Dialog closed");
+                    crate::Action::none()
+                }
+                Err(e) => {
+                    println!("Error with the path: {:?}", e);
+                    let update_success_task = Task::done(Message::UpdateExportSuccess(false));
+                    let update_message_task = Task::done(Message::UpdateExportMessage(format!("Error with the path: {:?}", e)));
+
+                    crate::Action::none().with_task(update_success_task.chain(update_message_task))
+                }
+            }
+        }
+        Message::ProcessPrintableMenu( (html, path) ) => {
+            crate::Action::operation(Operation::PreviewExport(ExportPreview::new(ExportKind::PrintableMenu, vec![(path, html)])))
+        }
+        Message::PrintableMenuExported(result) => {
+            let (success, message) = match result {
+                Ok(path) => {
+                    printable_menu::open_in_browser(&path);
+                    (true, format!("Printable menu saved to {}", path.to_string_lossy()))
+                }
+                Err(e) => (false, format!("Printable menu export failed: {:?}", e)),
+            };
+
+            let update_success_task = Task::done(Message::UpdateExportSuccess(success));
+            let update_message_task = Task::done(Message::UpdateExportMessage(message));
+
+            crate::Action::none().with_task(update_success_task.chain(update_message_task))
+        }
+        Message::TogglePivotPriceLevel(id, include) => {
+            settings.pivot_excluded_levels.retain(|excluded| *excluded != id);
+            if !include {
+                settings.pivot_excluded_levels.push(id);
+            }
+            crate::Action::none()
+        }
+        Message::ExportPricePivot => {
+            let task = Task::perform(choose_price_pivot_path(), Message::PricePivotPathChosen);
+
+            crate::Action::none().with_task(task)
+        }
+        Message::PricePivotPathChosen(result) => {
+            match result {
+                Ok(path) => {
+                    println!("Exporting price pivot to: {:?}", path);
+                    crate::Action::operation(Operation::RequestPricePivot(path))
+                }
+                Err(Error::DialogClosed) => {
+                    println!("Dialog closed");
+                    crate::Action::none()
+                }
+                Err(e) => {
+                    println!("Error with the path: {:?}", e);
+                    let update_success_task = Task::done(Message::UpdateExportSuccess(false));
+                    let update_message_task = Task::done(Message::UpdateExportMessage(format!("Error with the path: {:?}", e)));
+
+                    crate::Action::none().with_task(update_success_task.chain(update_message_task))
+                }
+            }
+        }
+        Message::ProcessPricePivot( (csv, path) ) => {
+            crate::Action::operation(Operation::PreviewExport(ExportPreview::new(ExportKind::PricePivot, vec![(path, csv)])))
+        }
+        Message::PricePivotExported(result) => {
+            let (success, message) = match result {
+                Ok(path) => (true, format!("Price levels exported to {}", path.to_string_lossy())),
+                Err(e) => (false, format!("Price level export failed: {:?}", e)),
+            };
+
+            let update_success_task = Task::done(Message::UpdateExportSuccess(success));
+            let update_message_task = Task::done(Message::UpdateExportMessage(message));
+
+            crate::Action::none().with_task(update_success_task.chain(update_message_task))
+        }
+        Message::DiffDatabases => {
+            let task = Task::perform(item_patch::diff_database_files(), Message::DatabaseDiffReady);
+
+            crate::Action::none().with_task(task)
+        }
+        Message::DatabaseDiffReady(result) => {
+            match result {
+                Ok(preview) => crate::Action::operation(Operation::PreviewExport(preview)),
+                Err(e) => crate::Action::none().with_task(Task::done(Message::DatabaseDiffSaved(Err(e)))),
+            }
+        }
+        Message::DatabaseDiffSaved(result) => {
+            let (success, message) = match result {
+                Ok((count, path)) => (true, format!("Saved {} changes as a patch to {}", count, path.to_string_lossy())),
+                Err(e) if e == DIALOG_CLOSED => return crate::Action::none(),
+                Err(e) => (false, format!("Database diff failed: {}", e)),
+            };
+
+            let update_success_task = Task::done(Message::UpdateExportSuccess(success));
+            let update_message_task = Task::done(Message::UpdateExportMessage(message));
+
+            crate::Action::none().with_task(update_success_task.chain(update_message_task))
+        }
+        Message::ApplyPatch => {
+            let task = Task::perform(item_patch::open_patch(), Message::PatchOpened);
+
+            crate::Action::none().with_task(task)
+        }
+        Message::RestoreBackup => {
+            // Backups and snapshots are written beside the save file
+            let dir = Path::new(&settings.file_path)
+                .parent()
+                .map(Path::to_path_buf)
+                .unwrap_or_else(|| file_manager.data_dir().to_path_buf());
+            let task = Task::perform(choose_backup(dir), Message::BackupChosen);
+
+            crate::Action::none().with_task(task)
+        }
+        Message::BackupChosen(result) => {
+            match result {
+                Ok(path) => crate::Action::operation(Operation::RestoreBackup(path)),
+                Err(e) if e == DIALOG_CLOSED => crate::Action::none(),
+                Err(e) => crate::Action::none().with_task(Task::done(Message::UpdateExportMessage(e))),
+            }
+        }
+        Message::PatchOpened(result) => {
+            match result {
+                Ok(patch) => {
+                    println!("Applying patch with {} changes", patch.changes.len());
+                    crate::Action::operation(Operation::ApplyPatch(patch))
+                }
+                Err(e) if e == DIALOG_CLOSED => crate::Action::none(),
+                Err(e) => {
+                    let update_success_task = Task::done(Message::UpdateExportSuccess(false));
+                    let update_message_task = Task::done(Message::UpdateExportMessage(format!("Could not open patch: {}", e)));
+
+                    crate::Action::none().with_task(update_success_task.chain(update_message_task))
+                }
+            }
+        }
+        Message::EntityImportKindChanged(kind) => {
+            settings.entity_import_kind = kind;
+            crate::Action::none()
+        }
+        Message::ImportEntities => {
+            let task = Task::perform(import_entities::open_entity_csv(settings.entity_import_kind), Message::EntitiesOpened);
+
+            crate::Action::none().with_task(task)
+        }
+        Message::EntitiesOpened(result) => {
+            match result {
+                Ok(import) => {
+                    println!("Importing {} {} with {} warnings", import.rows.len(), import.kind, import.warnings.len());
+                    crate::Action::operation(Operation::ImportEntities(import))
+                }
+                Err(e) if e == DIALOG_CLOSED => crate::Action::none(),
+                Err(e) => {
+                    let update_success_task = Task::done(Message::UpdateExportSuccess(false));
+                    let update_message_task = Task::done(Message::UpdateExportMessage(format!("Could not import {}: {}", settings.entity_import_kind, e)));
+
+                    crate::Action::none().with_task(update_success_task.chain(update_message_task))
+                }
+            }
+        }
+        Message::ValidateAll => crate::Action::operation(Operation::ValidateAll),
+        Message::ToggleBlockDuplicateButtons(block) => {
+            settings.block_duplicate_button_text = block;
+            crate::Action::none()
+        }
+        Message::ToggleRequireAskPricePrompt(require) => {
+            settings.require_ask_price_prompt = require;
+            // The report changes with it, so run the checks again
+            crate::Action::operation(Operation::ValidateAll)
+        }
+        Message::OpenItem(id) => crate::Action::operation(Operation::OpenItem(id)),
+        Message::SyncPriceLevels => crate::Action::operation(Operation::SyncPriceLevels),
+        Message::FixPrimaryPrinters => crate::Action::operation(Operation::FixPrimaryPrinters),
+        Message::PreviewIdFix => crate::Action::operation(Operation::PreviewIdFix),
+        Message::ApplyIdFix => crate::Action::operation(Operation::ApplyIdFix),
+        Message::CancelIdFix => crate::Action::operation(Operation::CancelIdFix),
+        Message::ShowUnusedEntities => crate::Action::operation(Operation::ShowUnusedEntities),
+        Message::DeleteUnused(entity_type) => crate::Action::operation(Operation::DeleteUnused(entity_type)),
+        Message::ConfirmDeleteUnused => crate::Action::operation(Operation::ConfirmDeleteUnused),
+        Message::CancelDeleteUnused => crate::Action::operation(Operation::CancelDeleteUnused),
+        Message::ChooseDataDirectory => {
+            let task = Task::perform(choose_data_directory(), Message::DataDirectoryChosen);
+
+            crate::Action::none().with_task(task)
+        }
+        Message::DataDirectoryChosen(result) => {
+            match result {
+                Ok(folder) => crate::Action::operation(Operation::PrepareDataDirectory(folder)),
+                Err(Error::DialogClosed) => {
+                    println!("Dialog closed");
+                    crate::Action::none()
+                }
+                Err(e) => crate::Action::operation(Operation::ShowError(SettingsTab::FilesAndBackups, format!("Error with the folder: {:?}", e))),
+            }
+        }
+        Message::TransferDataDirectory(transfer) => crate::Action::operation(Operation::TransferDataDirectory(transfer)),
+        Message::DataFileCopied(result) => crate::Action::operation(Operation::DataFileCopied(result)),
+        Message::CancelDataDirectoryChange => crate::Action::operation(Operation::CancelDataDirectoryChange),
+        Message::ExportItemsByGroup => {
+            let task = Task::perform(choose_export_folder(), Message::GroupExportFolderChosen);
+
+            crate::Action::none().with_task(task)
+        }
+        Message::GroupExportFolderChosen(result) => {
+            match result {
+                Ok(folder) => {
+                    println!("Exporting items by group to: {:?}", folder);
+                    crate::Action::operation(Operation::RequestGroupExport(folder))
+                }
+                Err(Error::DialogClosed) => {
+                    println!("Dialog closed");
+                    crate::Action::none()
+                }
+                Err(e) => {
+                    println!("Error with the folder: {:?}", e);
+                    let update_success_task = Task::done(Message::UpdateExportSuccess(false));
+                    let update_message_task = Task::done(Message::UpdateExportMessage(format!("Error with the folder: {:?}", e)));
+
+                    crate::Action::none().with_task(update_success_task.chain(update_message_task))
+                }
+            }
+        }
+        Message::ProcessGroupExport( (groups, folder) ) => {
+            println!("Processing group export, group count: {}", groups.len());
+
+            let preview = group_export_preview(groups, folder, &settings.export_profile);
+
+            crate::Action::operation(Operation::PreviewExport(preview))
+        }
+        Message::ExportWritten(preview, result) => {
+            // Report it the way each export did when it wrote its own files
+            let target = preview.target();
+            let finished = match preview.kind {
+                ExportKind::Items | ExportKind::Json => Message::ExportMessage(result.map(|_| target)),
+                ExportKind::ChangedItems { changed, deleted } => Message::DeltaExportFinished(result.map(|_| (target, changed, deleted))),
+                ExportKind::PrintableMenu => Message::PrintableMenuExported(result.map(|_| target)),
+                ExportKind::PricePivot => Message::PricePivotExported(result.map(|_| target)),
+                ExportKind::ByGroup { .. } => Message::GroupExportFinished(result.map(|_| (preview.files.len(), target))),
+                ExportKind::DatabaseDiff { changes } => Message::DatabaseDiffSaved(
+                    result.map(|_| (changes, target)).map_err(|e| format!("{:?}", e))
+                ),
+                // Reported on the screen that asked for them, see Message::ConfirmExport in main
+                ExportKind::LoadReport | ExportKind::RoutingMap | ExportKind::GroupStatistics | ExportKind::Patch { .. } => {
+                    return crate::Action::none();
+                }
+            };
+
+            crate::Action::none().with_task(Task::done(finished))
+        }
+        Message::GroupExportFinished(result) => {
+            let (success, message) = match result {
+                Ok((count, folder)) => (true, format!("Wrote {} files to {}", count, folder.to_string_lossy())),
+                Err(e) => (false, format!("Group export failed: {:?}", e)),
+            };
+
+            let update_success_task = Task::done(Message::UpdateExportSuccess(success));
+            let update_message_task = Task::done(Message::UpdateExportMessage(message));
+
+            crate::Action::none().with_task(update_success_task.chain(update_message_task))
+        }
     }
 }
 
 pub fn view<'a>(
     settings: &'a AppSettings,
+    nav: &'a SettingsNav,
     error_message: Option<&'a str>,
+    data_checks: &'a DataChecks,
+    tax_groups: &'a BTreeMap<EntityId, TaxGroup>,
+    printer_logicals: &'a BTreeMap<EntityId, PrinterLogical>,
+    price_levels: &'a BTreeMap<EntityId, PriceLevel>,
+    data_dir: &'a Path,
+    data_dir_state: &'a DataDirState,
 ) -> Element<'a, Message> {
+    let text_style = TextStyle::from_settings(settings);
+    let controls = |tab: SettingsTab| -> Vec<SettingControl<'a>> {
+        match tab {
+            SettingsTab::General => general_settings(settings, tax_groups),
+            SettingsTab::Appearance => appearance_settings(settings),
+            SettingsTab::FilesAndBackups => file_settings(settings, data_dir, data_dir_state),
+            SettingsTab::Exports => export_settings(settings, printer_logicals, price_levels),
+            SettingsTab::Validation => validation_settings(settings, data_checks),
+            SettingsTab::Advanced => advanced_settings(settings),
+        }
+    };
 
     let title_row = row![
-        text("Settings").style(Modern::primary_text()).size(18)
-    ].padding(15);
-
-    let setting_column = column![
-        text("Data File Path:"),
-        text_input("Path to RON file", &settings.file_path)
-            .on_input(Message::UpdateFilePath)
+        text("Settings").style(Modern::primary_text()).size(text_style.heading()),
+        iced::widget::horizontal_space(),
+        text_input("Search settings", &nav.search)
+            .on_input(Message::UpdateSettingsSearch)
             .style(Modern::inline_text_input())
+            .width(250)
             .padding(5),
-        
-        row![
-            checkbox("Auto-save on changes", settings.auto_save)
-            .on_toggle(Message::ToggleAutoSave)
-            .style(Modern::checkbox()),
-        
-            checkbox("Create backups before saving", settings.create_backups)
-                .on_toggle(Message::ToggleBackups)
-                .style(Modern::checkbox()),
-        ].spacing(15),
-        
-        if let Some(error) = error_message {
-            text(error).style(Modern::error_text())
-        } else {
-            text("")
-        },
+    ]
+    .align_y(iced::Alignment::Center);
 
-        row![
-            button("Save Settings")
-                .on_press(Message::ValidateAndSave)
-                .style(Modern::primary_button()),
-        ]
-        .spacing(10),
-    ].spacing(10);
+    let tabs = row(
+        SettingsTab::ALL.iter().map(|tab| {
+            let selected = *tab == nav.tab && nav.search.is_empty();
+            button(text(tab.to_string()))
+                .on_press(Message::SelectSettingsTab(*tab))
+                .style(if selected { Modern::primary_button() } else { Modern::system_button() })
+                .into()
+        })
+        .collect::<Vec<_>>()
+    )
+    .spacing(5)
+    .wrap();
+
+    let banner: Element<'a, Message> = match error_message {
+        Some(error) => container(text(error).style(Modern::error_text()))
+            .padding(10)
+            .width(Length::Fill)
+            .style(Modern::accent_container())
+            .into(),
+        None => column![].into(),
+    };
+
+    let content: Element<'a, Message> = if nav.search.trim().is_empty() {
+        // The error again next to the settings it's about, if this tab has them
+        let tab_error: Element<'a, Message> = match (&nav.error, error_message) {
+            (Some((tab, error)), Some(shown)) if *tab == nav.tab && error == shown => {
+                text(error).size(text_style.caption()).style(Modern::error_text()).into()
+            }
+            _ => column![].into(),
+        };
 
-    let setting_container = container(
         column![
-            title_row,
-            setting_column,
+            tab_error,
+            column(controls(nav.tab).into_iter().map(|control| control.view).collect::<Vec<_>>()).spacing(15),
         ]
         .spacing(10)
-        .padding(15)
+        .into()
+    } else {
+        let query = nav.search.trim().to_lowercase();
+        let found = SettingsTab::ALL.iter()
+            .map(|tab| {
+                let matching: Vec<SettingControl<'a>> = controls(*tab)
+                    .into_iter()
+                    .filter(|control| control.label.to_lowercase().contains(&query))
+                    .collect();
+                (*tab, matching)
+            })
+            .filter(|(_, matching)| !matching.is_empty())
+            .map(|(tab, matching)| {
+                column![
+                    row![
+                        text(tab.to_string()).size(text_style.subheading()).style(Modern::primary_text()),
+                        button(text("Open tab").size(text_style.caption()))
+                            .on_press(Message::SelectSettingsTab(tab))
+                            .style(Modern::gray_button()),
+                    ]
+                    .spacing(10)
+                    .align_y(iced::Alignment::Center),
+                    column(matching.into_iter().map(|control| control.view).collect::<Vec<_>>()).spacing(15),
+                ]
+                .spacing(10)
+                .into()
+            })
+            .collect::<Vec<Element<'a, Message>>>();
+
+        if found.is_empty() {
+            text(format!("No settings match \"{}\"", nav.search.trim()))
+                .style(Modern::secondary_text())
+                .into()
+        } else {
+            column(found).spacing(20).into()
+        }
+    };
+
+    let export_status: Element<'a, Message> = if settings.export_message.is_empty() {
+        column![].into()
+    } else {
+        text(&settings.export_message)
+            .size(text_style.caption())
+            .style(Modern::validated_text(!settings.export_success))
+            .into()
+    };
+
+    scrollable(
+        container(
+            column![
+                title_row,
+                tabs,
+                banner,
+                export_status,
+                content,
+                row![
+                    button("Save Settings")
+                        .on_press(Message::ValidateAndSave)
+                        .style(Modern::primary_button()),
+                ]
+                .spacing(10),
+            ]
+            .spacing(15)
+            .padding(15)
+        )
+        .width(805)
+        .style(Modern::card_container())
     )
-    .width(805)
-    .style(Modern::card_container());
+    .into()
+}
 
-    let import_export = container(
-        // Add an export section
-        column![
-            text("Data Export").size(18),
+/// A control on the settings screen and the label search matches against
+struct SettingControl<'a> {
+    label: &'static str,
+    view: Element<'a, Message>,
+}
+
+impl<'a> SettingControl<'a> {
+    fn new(label: &'static str, view: impl Into<Element<'a, Message>>) -> Self {
+        Self { label, view: view.into() }
+    }
+}
+
+// Heading for a control that groups several settings
+fn group_heading<'a>(label: &'a str, text_style: TextStyle) -> Element<'a, Message> {
+    text(label).size(text_style.subheading()).style(Modern::primary_text()).into()
+}
+
+fn general_settings<'a>(
+    settings: &'a AppSettings,
+    tax_groups: &'a BTreeMap<EntityId, TaxGroup>,
+) -> Vec<SettingControl<'a>> {
+    let text_style = TextStyle::from_settings(settings);
+    let tax_group_options = entity_component::entity_options(tax_groups.values());
+    let selected_tax_group = settings.default_tax_group
+        .and_then(|id| tax_group_options.iter().find(|option| option.id == id).cloned());
+
+    vec![
+        SettingControl::new(
+            "Default tax group",
+            row![
+                text("Default tax group:"),
+                pick_list(
+                    tax_group_options,
+                    selected_tax_group,
+                    Message::DefaultTaxGroupChanged
+                )
+                .placeholder("None")
+                .style(Modern::pick_list())
+                .width(220),
+                button("Clear")
+                    .on_press_maybe(settings.default_tax_group.map(|_| Message::ClearDefaultTaxGroup))
+                    .style(Modern::system_button()),
+                text("Given to new items and to imported rows without a tax group.")
+                    .style(Modern::secondary_text())
+                    .size(text_style.caption()),
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center),
+        ),
+        SettingControl::new(
+            "Item ID range",
+            row![
+                text("Item IDs from"),
+                text_input("1", &settings.item_id_range.min.to_string())
+                    .on_input(Message::UpdateMinItemId)
+                    .style(Modern::inline_text_input())
+                    .width(110)
+                    .padding(5),
+                text("to"),
+                text_input(&EntityId::MAX.to_string(), &settings.item_id_range.max.to_string())
+                    .on_input(Message::UpdateMaxItemId)
+                    .style(Modern::inline_text_input())
+                    .width(110)
+                    .padding(5),
+                text("New items get the next free ID in this range.")
+                    .style(Modern::secondary_text())
+                    .size(text_style.caption()),
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center),
+        ),
+        SettingControl::new(
+            "Custom item flags",
+            row![
+                text("Custom item flags:"),
+                text_input("Custom Flag 1", &settings.custom_flag_labels[0])
+                    .on_input(|label| Message::UpdateCustomFlagLabel(0, label))
+                    .style(Modern::inline_text_input())
+                    .width(180)
+                    .padding(5),
+                text_input("Custom Flag 2", &settings.custom_flag_labels[1])
+                    .on_input(|label| Message::UpdateCustomFlagLabel(1, label))
+                    .style(Modern::inline_text_input())
+                    .width(180)
+                    .padding(5),
+                text("Shown with the other flags on the item form.")
+                    .style(Modern::secondary_text())
+                    .size(text_style.caption()),
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center),
+        ),
+        SettingControl::new(
+            "Button abbreviations, fill empty button 1 text",
+            column![
+                row![
+                    group_heading("Button Abbreviations", text_style),
+                    iced::widget::horizontal_space(),
+                    button("Fill Empty Button 1 Text")
+                        .on_press(Message::OpenButtonFill)
+                        .style(Modern::system_button()),
+                ]
+                .align_y(iced::Alignment::Center),
+                text(format!(
+                    "Used by Suggest on the item form to fit names into {} characters. Longer entries are applied first.",
+                    abbreviate::BUTTON_TEXT_MAX
+                ))
+                .size(text_style.caption())
+                .style(Modern::secondary_text()),
+                column(
+                    settings.abbreviations.iter().enumerate().map(|(index, entry)| {
+                        row![
+                            text_input("Full word", &entry.full)
+                                .on_input(move |full| Message::UpdateAbbreviationFull(index, full))
+                                .style(Modern::inline_text_input())
+                                .width(250)
+                                .padding(5),
+                            text("→"),
+                            text_input("Short", &entry.short)
+                                .on_input(move |short| Message::UpdateAbbreviationShort(index, short))
+                                .style(Modern::inline_text_input())
+                                .width(150)
+                                .padding(5),
+                            button(text("×").size(text_style.caption()))
+                                .on_press(Message::RemoveAbbreviation(index))
+                                .style(Modern::danger_button()),
+                        ]
+                        .spacing(5)
+                        .align_y(iced::Alignment::Center)
+                        .into()
+                    })
+                    .collect::<Vec<_>>()
+                )
+                .spacing(2),
+                row![
+                    button("Add Abbreviation")
+                        .on_press(Message::AddAbbreviation)
+                        .style(Modern::system_button()),
+                    text("Abbreviations are kept with Save Settings.")
+                        .size(text_style.caption())
+                        .style(Modern::secondary_text()),
+                ]
+                .spacing(10)
+                .align_y(iced::Alignment::Center),
+            ]
+            .spacing(10),
+        ),
+    ]
+}
+
+fn appearance_settings<'a>(settings: &'a AppSettings) -> Vec<SettingControl<'a>> {
+    vec![
+        SettingControl::new(
+            "Text size, monospace ID and price columns",
+            row![
+                text("Text size:"),
+                pick_list(
+                    TextSize::ALL,
+                    Some(settings.text_size),
+                    Message::TextSizeChanged
+                )
+                .style(Modern::pick_list()),
+                checkbox("Monospace ID and price columns", settings.monospace_data)
+                    .on_toggle(Message::ToggleMonospaceData)
+                    .style(Modern::checkbox()),
+            ]
+            .spacing(15)
+            .align_y(iced::Alignment::Center),
+        ),
+        SettingControl::new(
+            "Flag prices on the item view, level price above the default, price below cost",
+            row![
+                text("Flag prices on the item view:"),
+                checkbox("Level price above the default", settings.price_sanity.level_above_default)
+                    .on_toggle(Message::TogglePriceAboveDefaultCheck)
+                    .style(Modern::checkbox()),
+                checkbox("Price below cost", settings.price_sanity.below_cost)
+                    .on_toggle(Message::TogglePriceBelowCostCheck)
+                    .style(Modern::checkbox()),
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center),
+        ),
+    ]
+}
+
+fn file_settings<'a>(
+    settings: &'a AppSettings,
+    data_dir: &'a Path,
+    data_dir_state: &'a DataDirState,
+) -> Vec<SettingControl<'a>> {
+    let text_style = TextStyle::from_settings(settings);
+    vec![
+        SettingControl::new(
+            "Data file path",
+            column![
+                text("Data File Path:"),
+                text_input("Path to RON file", &settings.file_path)
+                    .on_input(Message::UpdateFilePath)
+                    .style(Modern::inline_text_input())
+                    .padding(5),
+            ]
+            .spacing(10),
+        ),
+        SettingControl::new("Data directory", data_directory_view(data_dir, data_dir_state, text_style)),
+        SettingControl::new(
+            "Auto-save on changes, create backups before saving, snapshot data before imports and SuperEdit changes",
+            row![
+                checkbox("Auto-save on changes", settings.auto_save)
+                    .on_toggle(Message::ToggleAutoSave)
+                    .style(Modern::checkbox()),
+                checkbox("Create backups before saving", settings.create_backups)
+                    .on_toggle(Message::ToggleBackups)
+                    .style(Modern::checkbox()),
+                checkbox("Snapshot data before imports and SuperEdit changes", settings.snapshot_before_bulk_changes)
+                    .on_toggle(Message::ToggleSnapshots)
+                    .style(Modern::checkbox()),
+            ]
+            .spacing(15),
+        ),
+        SettingControl::new(
+            "Restore backup or snapshot",
+            row![
+                button("Restore Backup or Snapshot")
+                    .on_press(Message::RestoreBackup)
+                    .style(Modern::system_button()),
+                text("Overwrite imports, deletions that clear item references and accepted SuperEdit changes always leave a snapshot, even with the options above off.")
+                    .size(text_style.caption())
+                    .style(Modern::secondary_text()),
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center),
+        ),
+        SettingControl::new(
+            "Load starter template",
+            row![
+                button("Load Starter Template")
+                    .on_press(Message::OpenTemplates)
+                    .style(Modern::system_button()),
+                text("Common tax groups, price levels and categories for a new database.")
+                    .size(text_style.caption())
+                    .style(Modern::secondary_text()),
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center),
+        ),
+        SettingControl::new(
+            "Import from CSV",
+            row![
+                button("Import from CSV")
+                    .on_press(Message::ImportEntities)
+                    .style(Modern::system_button()),
+                pick_list(
+                    EntityCsvKind::ALL,
+                    Some(settings.entity_import_kind),
+                    Message::EntityImportKindChanged
+                )
+                .style(Modern::pick_list()),
+                text(format!("Columns: {}", settings.entity_import_kind.columns()))
+                    .size(text_style.caption())
+                    .style(Modern::secondary_text()),
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center),
+        ),
+        SettingControl::new(
+            "Skip lines at the top of item CSV imports",
+            row![
+                text("Skip"),
+                text_input(
+                    "Auto",
+                    &settings.csv_leading_lines.map(|lines| lines.to_string()).unwrap_or_default()
+                )
+                    .on_input(Message::UpdateCsvLeadingLines)
+                    .style(Modern::inline_text_input())
+                    .width(60)
+                    .padding(5),
+                text("lines at the top of item CSV imports. Leave empty to skip title lines and the header row automatically.")
+                    .size(text_style.caption())
+                    .style(Modern::secondary_text()),
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center),
+        ),
+        SettingControl::new(
+            "Read non-UTF-8 files as Latin-1",
+            row![
+                checkbox("Read non-UTF-8 files as Latin-1", settings.import_latin1_fallback)
+                    .on_toggle(Message::ToggleLatin1Fallback)
+                    .style(Modern::checkbox()),
+                text("For CSVs saved by older Windows tools. The import warnings say when this was used.")
+                    .size(text_style.caption())
+                    .style(Modern::secondary_text()),
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center),
+        ),
+    ]
+}
+
+fn export_settings<'a>(
+    settings: &'a AppSettings,
+    printer_logicals: &'a BTreeMap<EntityId, PrinterLogical>,
+    price_levels: &'a BTreeMap<EntityId, PriceLevel>,
+) -> Vec<SettingControl<'a>> {
+    let text_style = TextStyle::from_settings(settings);
+    let printer_options = entity_component::entity_options(printer_logicals.values().filter(|printer| printer.active));
+    let selected_printer = settings.inactive_printer_remap
+        .and_then(|id| printer_options.iter().find(|option| option.id == id).cloned());
+
+    let export_columns = settings.export_profile.columns();
+    let included_columns = export_columns.iter().filter(|column| column.included).count();
+
+    vec![
+        SettingControl::new(
+            "Export menu items to CSV, JSON or by item group",
             row![
                 button("Export Menu Items to CSV")
                     .on_press(Message::OpenFile)
                     .style(Modern::system_button()),
+                button("Export Menu Items to JSON")
+                    .on_press(Message::ExportItemsToJson)
+                    .style(Modern::system_button()),
+                button("Export by Item Group")
+                    .on_press(Message::ExportItemsByGroup)
+                    .style(Modern::system_button()),
             ]
             .spacing(10),
-            text(&settings.export_message).size(12).style(
-                Modern::validated_text(!settings.export_success.clone())
-            ),
-        ]
+        ),
+        SettingControl::new(
+            "Export changed items modified after a date",
+            row![
+                button("Export Changed Items")
+                    .on_press(Message::ExportChangedItems)
+                    .style(Modern::system_button()),
+                text("Only items modified after:"),
+                text_input("YYYY-MM-DD", &settings.delta_since)
+                    .on_input(Message::UpdateDeltaSince)
+                    .on_submit(Message::ExportChangedItems)
+                    .style(Modern::inline_text_input())
+                    .width(170)
+                    .padding(5),
+                button("Since last export")
+                    .on_press_maybe(settings.last_export_at.as_ref().map(|_| Message::UseLastExportDate))
+                    .style(Modern::gray_button()),
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center),
+        ),
+        SettingControl::new(
+            "Remember deleted items for days",
+            row![
+                text("Remember deleted items for"),
+                text_input("90", &settings.tombstone_retention_days.to_string())
+                    .on_input(Message::UpdateTombstoneRetention)
+                    .style(Modern::inline_text_input())
+                    .width(60)
+                    .padding(5),
+                text("days. Deletions go into a _deleted file next to the changed items export, 0 keeps them forever.")
+                    .size(text_style.caption())
+                    .style(Modern::secondary_text()),
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center),
+        ),
+        SettingControl::new(
+            "Export printable menu, sections, include inactive items",
+            row![
+                button("Export Printable Menu")
+                    .on_press(Message::ExportPrintableMenu)
+                    .style(Modern::system_button()),
+                text("Sections by:"),
+                pick_list(
+                    MenuGrouping::ALL,
+                    Some(settings.menu_grouping),
+                    Message::MenuGroupingChanged
+                )
+                .style(Modern::pick_list()),
+                checkbox("Include inactive items", settings.menu_include_inactive)
+                    .on_toggle(Message::ToggleMenuIncludeInactive)
+                    .style(Modern::checkbox()),
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center),
+        ),
+        SettingControl::new(
+            "Export price levels",
+            column![
+                row![
+                    button("Export Price Levels")
+                        .on_press(Message::ExportPricePivot)
+                        .style(Modern::system_button()),
+                    text("One column per level, blank where an item has no price there:")
+                        .size(text_style.caption())
+                        .style(Modern::secondary_text()),
+                ]
+                .spacing(10)
+                .align_y(iced::Alignment::Center),
+                row(
+                    price_levels.values().map(|level| {
+                        let id = level.id;
+                        checkbox(level.name.clone(), !settings.pivot_excluded_levels.contains(&id))
+                            .on_toggle(move |include| Message::TogglePivotPriceLevel(id, include))
+                            .style(Modern::checkbox())
+                            .into()
+                    })
+                    .collect::<Vec<_>>()
+                )
+                .spacing(15)
+                .wrap(),
+            ]
+            .spacing(10),
+        ),
+        SettingControl::new(
+            "Inactive printers export as",
+            row![
+                text("Inactive printers export as:"),
+                pick_list(
+                    printer_options,
+                    selected_printer,
+                    Message::InactivePrinterRemapChanged
+                )
+                .placeholder("Left off")
+                .style(Modern::pick_list())
+                .width(220),
+                button("Clear")
+                    .on_press_maybe(settings.inactive_printer_remap.map(|_| Message::ClearInactivePrinterRemap))
+                    .style(Modern::system_button()),
+                text("Items keep their inactive printers, exports leave them off or send them to this printer.")
+                    .style(Modern::secondary_text())
+                    .size(text_style.caption()),
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center),
+        ),
+        SettingControl::new(
+            "CSV export layout, preset, header row, columns",
+            column![
+                group_heading("CSV Export Layout", text_style),
+                row![
+                    text("Preset:"),
+                    pick_list(
+                        ExportPreset::ALL,
+                        Some(settings.export_profile.preset),
+                        Message::ExportPresetChanged
+                    )
+                    .style(Modern::pick_list()),
+                    button("Reset to Preset")
+                        .on_press(Message::ResetExportLayout)
+                        .style(Modern::system_button()),
+                    checkbox("Write a header row", settings.export_profile.include_header)
+                        .on_toggle(Message::ToggleExportHeaderRow)
+                        .style(Modern::checkbox()),
+                ]
+                .spacing(10)
+                .align_y(iced::Alignment::Center),
+                text(format!(
+                    "{} of {} columns are exported, top to bottom. Fields added in later versions start out excluded.",
+                    included_columns,
+                    export_columns.len()
+                ))
+                .size(text_style.caption())
+                .style(Modern::secondary_text()),
+                scrollable(
+                    column(
+                        export_columns.into_iter().enumerate().map(|(index, export_column)| {
+                            row![
+                                button(text("▲").size(text_style.caption()))
+                                    .on_press(Message::MoveExportColumn(index, true))
+                                    .style(Modern::system_button()),
+                                button(text("▼").size(text_style.caption()))
+                                    .on_press(Message::MoveExportColumn(index, false))
+                                    .style(Modern::system_button()),
+                                checkbox(export_column.field.to_string(), export_column.included)
+                                    .on_toggle(move |included| Message::ToggleExportColumn(index, included))
+                                    .style(Modern::checkbox())
+                                    .width(250),
+                                text_input("Header", &export_column.header)
+                                    .on_input(move |header| Message::UpdateExportHeader(index, header))
+                                    .style(Modern::inline_text_input())
+                                    .width(300)
+                                    .padding(5),
+                            ]
+                            .spacing(5)
+                            .align_y(iced::Alignment::Center)
+                            .into()
+                        })
+                        .collect::<Vec<_>>()
+                    )
+                    .spacing(2)
+                )
+                .height(250),
+                text("Layout changes are kept with Save Settings.")
+                    .size(text_style.caption())
+                    .style(Modern::secondary_text()),
+            ]
+            .spacing(10),
+        ),
+    ]
+}
 
-        .spacing(10)
-        .padding(10), 
-    )
-    .style(Modern::card_container())
-    .width(805)
-    .padding(15);
+fn validation_settings<'a>(settings: &'a AppSettings, data_checks: &'a DataChecks) -> Vec<SettingControl<'a>> {
+    let text_style = TextStyle::from_settings(settings);
+    let validation_report = &data_checks.report;
+    let id_fix_preview = &data_checks.id_fix_preview;
 
+    vec![
+        SettingControl::new(
+            "Required item classifications",
+            column![
+                text("Required item classifications:"),
+                row(
+                    Classification::ALL.iter().map(|classification| {
+                        let classification = *classification;
+                        checkbox(
+                            classification.to_string(),
+                            settings.required_classifications.contains(&classification)
+                        )
+                        .on_toggle(move |required| Message::ToggleRequiredClassification(classification, required))
+                        .style(Modern::checkbox())
+                        .into()
+                    })
+                    .collect::<Vec<_>>()
+                )
+                .spacing(15),
+            ]
+            .spacing(10),
+        ),
+        SettingControl::new(
+            "Highest store ID",
+            row![
+                text("Highest store ID:"),
+                text_input("99", &settings.max_store_id.to_string())
+                    .on_input(Message::UpdateMaxStoreId)
+                    .style(Modern::inline_text_input())
+                    .width(80)
+                    .padding(5),
+                text("Items with a higher store ID are flagged by Validate All.")
+                    .style(Modern::secondary_text())
+                    .size(text_style.caption()),
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center),
+        ),
+        SettingControl::new(
+            "Data checks, validate all, sync item price levels, fix primary printers, fix out-of-range item IDs",
+            column![
+                group_heading("Data Checks", text_style),
+                row![
+                    button("Validate All")
+                        .on_press(Message::ValidateAll)
+                        .style(Modern::system_button()),
+                    button("Sync Item Price Levels")
+                        .on_press(Message::SyncPriceLevels)
+                        .style(Modern::system_button()),
+                    button("Fix Primary Printers")
+                        .on_press(Message::FixPrimaryPrinters)
+                        .style(Modern::system_button()),
+                    button("Fix Out-of-Range Item IDs")
+                        .on_press(Message::PreviewIdFix)
+                        .style(Modern::system_button()),
+                ]
+                .spacing(10),
+                if id_fix_preview.is_empty() {
+                    Element::from(column![])
+                } else {
+                    column![
+                        text("These items will get new IDs inside their item group's range:").size(text_style.caption()),
+                        scrollable(
+                            column(
+                                id_fix_preview.iter().map(|(old_id, new_id)| {
+                                    match new_id {
+                                        Some(new_id) => text(format!("{} -> {}", old_id, new_id)).size(text_style.caption()).into(),
+                                        None => text(format!("{} -> no free ID left in its group, will not be changed", old_id))
+                                            .size(text_style.caption())
+                                            .style(Modern::error_text())
+                                            .into(),
+                                    }
+                                })
+                                .collect::<Vec<_>>()
+                            )
+                        )
+                        .height(120),
+                        row![
+                            button("Apply")
+                                .on_press(Message::ApplyIdFix)
+                                .style(Modern::warning_button()),
+                            button("Cancel")
+                                .on_press(Message::CancelIdFix)
+                                .style(Modern::system_button()),
+                        ].spacing(10),
+                    ]
+                    .spacing(5)
+                    .into()
+                },
+                if validation_report.is_empty() && data_checks.button_collisions.is_empty() {
+                    Element::from(text("No problems found.").size(text_style.caption()).style(Modern::secondary_text()))
+                } else if validation_report.is_empty() {
+                    Element::from(column![])
+                } else {
+                    scrollable(
+                        column(
+                            validation_report.iter().map(|line| {
+                                text(line).size(text_style.caption()).style(Modern::error_text()).into()
+                            })
+                            .collect::<Vec<_>>()
+                        )
+                    )
+                    .height(150)
+                    .into()
+                },
+                button_collisions_view(&data_checks.button_collisions, text_style),
+            ]
+            .spacing(10),
+        ),
+        SettingControl::new(
+            "Block saving items with duplicate button text in their item group",
+            checkbox("Block saving items with duplicate button text in their item group", settings.block_duplicate_button_text)
+                .on_toggle(Message::ToggleBlockDuplicateButtons)
+                .style(Modern::checkbox()),
+        ),
+        SettingControl::new(
+            "Flag items that ask for a price without a till prompt",
+            checkbox("Flag items that ask for a price without a till prompt", settings.require_ask_price_prompt)
+                .on_toggle(Message::ToggleRequireAskPricePrompt)
+                .style(Modern::checkbox()),
+        ),
+        SettingControl::new("Unused entities, find unused, delete all unused", unused_entities_view(data_checks, text_style)),
+    ]
+}
 
+fn advanced_settings<'a>(settings: &'a AppSettings) -> Vec<SettingControl<'a>> {
+    let text_style = TextStyle::from_settings(settings);
+    vec![
+        SettingControl::new(
+            "SuperEdit preview rows per page",
+            row![
+                text("SuperEdit preview rows per page:"),
+                text_input("200", &settings.preview_page_size.to_string())
+                    .on_input(Message::UpdatePreviewPageSize)
+                    .style(Modern::inline_text_input())
+                    .width(80)
+                    .padding(5),
+                text("Accept still applies every change, not just the page shown.")
+                    .style(Modern::secondary_text())
+                    .size(text_style.caption()),
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center),
+        ),
+        SettingControl::new(
+            "Diff two databases, apply patch",
+            row![
+                button("Diff Two Databases")
+                    .on_press(Message::DiffDatabases)
+                    .style(Modern::system_button()),
+                button("Apply Patch")
+                    .on_press(Message::ApplyPatch)
+                    .style(Modern::system_button()),
+                text("Patches only apply if the items still match what they were changed from.")
+                    .size(text_style.caption())
+                    .style(Modern::secondary_text()),
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center),
+        ),
+    ]
+}
+
+fn unused_entities_view<'a>(data_checks: &'a DataChecks, text_style: TextStyle) -> Element<'a, Message> {
     column![
-        setting_container,
-        import_export,
+        row![
+            group_heading("Unused Entities", text_style),
+            iced::widget::horizontal_space(),
+            button("Find Unused")
+                .on_press(Message::ShowUnusedEntities)
+                .style(Modern::system_button()),
+        ],
+        column(
+            data_checks.unused_entities.iter().map(|unused| {
+                let names = unused.entities
+                    .iter()
+                    .map(|(id, name)| format!("{} {}", id, name))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+
+                let action: Element<'a, Message> = if data_checks.pending_unused_delete.as_deref() == Some(unused.entity_type.as_str()) {
+                    row![
+                        button("Confirm Delete")
+                            .on_press(Message::ConfirmDeleteUnused)
+                            .style(Modern::danger_button()),
+                        button("Cancel")
+                            .on_press(Message::CancelDeleteUnused)
+                            .style(Modern::system_button()),
+                    ].spacing(5).into()
+                } else {
+                    button("Delete all unused")
+                        .on_press(Message::DeleteUnused(unused.entity_type.clone()))
+                        .style(Modern::danger_button())
+                        .into()
+                };
+
+                column![
+                    row![
+                        text(format!("{} ({})", unused.label, unused.entities.len())).width(Length::Fill),
+                        action,
+                    ].align_y(iced::Alignment::Center),
+                    text(names).size(text_style.caption()).style(Modern::secondary_text()),
+                ]
+                .spacing(5)
+                .into()
+            })
+            .collect::<Vec<_>>()
+        ).spacing(10),
     ]
     .spacing(10)
     .into()
@@ -267,6 +1924,32 @@ impl ThemeChoice {
     ];
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum TextSize {
+    Small,
+    #[default]
+    Medium,
+    Large,
+}
+
+impl TextSize {
+    pub const ALL: &'static [Self] = &[
+        Self::Small,
+        Self::Medium,
+        Self::Large,
+    ];
+}
+
+impl fmt::Display for TextSize {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Small => write!(f, "Small"),
+            Self::Medium => write!(f, "Medium"),
+            Self::Large => write!(f, "Large"),
+        }
+    }
+}
+
 impl fmt::Display for ThemeChoice {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -311,9 +1994,13 @@ pub fn settings() -> Settings {
 #[derive(Debug, Clone)]
 pub enum Error {
     DialogClosed,
-    IoError(std::io::ErrorKind),
+    IoError(io::ErrorKind),
 }
 
+/// Error text for a canceled file dialog, for the functions that return String errors.
+/// Canceling isn't a failure, so it gets no error message.
+pub const DIALOG_CLOSED: &str = "Dialog closed";
+
 pub async fn open_or_create_file() -> Result<(PathBuf, Option<Arc<String>>), Error> {
     // Use AsyncFileDialog to let user pick a file or create one
     let file_handle = rfd::AsyncFileDialog::new()
@@ -353,47 +2040,260 @@ pub async fn load_file(
     Ok((path, contents))
 }
 
-pub async fn write_to_item_export(
-    items: BTreeMap<i32, crate::items::Item>, 
-    path: Option<PathBuf>
-) -> Result<PathBuf, Error> {
-    println!("write-to-items-export function triggered");
-    // If path is None, prompt for a save location
-    let path = if let Some(path) = path {
-        path
-    } else {
-        rfd::AsyncFileDialog::new()
-            .add_filter("CSV Files", &["csv"])
-            .add_filter("Text Files", &["txt"])
-            .set_title("Save Items Export")
-            .save_file()
-            .await
-            .as_ref()
-            .map(rfd::FileHandle::path)
-            .map(Path::to_owned)
-            .ok_or(Error::DialogClosed)?
-    };
+pub async fn choose_delta_export_path() -> Result<PathBuf, Error> {
+    rfd::AsyncFileDialog::new()
+        .set_title("Save Changed Items Export")
+        .add_filter("CSV Files", &["csv"])
+        .add_filter("Text Files", &["txt"])
+        .save_file()
+        .await
+        .as_ref()
+        .map(rfd::FileHandle::path)
+        .map(Path::to_owned)
+        .ok_or(Error::DialogClosed)
+}
 
-    // Convert items to export strings
-    let mut content = String::new();
+/// The changed items like a normal export, plus the deletions file next to it
+pub fn delta_export_preview(
+    items: Vec<crate::items::Item>,
+    deleted: Vec<ItemTombstone>,
+    path: PathBuf,
+    profile: &ExportProfile,
+) -> ExportPreview {
+    let content = crate::items::export_items::items_to_export_string(&items, profile);
+    let deletions_path = delta_export::deletions_path(&path);
 
-    let items_vec: Vec<&crate::items::Item> = items.values().collect();
-    
-    for (i, item) in items_vec.iter().enumerate() {
-        // Convert each item to its export string representation
-        let export_string = crate::items::export_items::item_to_export_string(item);
-        content.push_str(&export_string);
-        
-        // Add newline if not the last item
-        if i < items.len() - 1 {
-            content.push('\n');
+    ExportPreview::new(
+        ExportKind::ChangedItems { changed: items.len(), deleted: deleted.len() },
+        vec![
+            (path, content),
+            (deletions_path, delta_export::deletions_to_csv_string(&deleted)),
+        ],
+    )
+}
+
+pub async fn choose_json_export_path() -> Result<PathBuf, Error> {
+    rfd::AsyncFileDialog::new()
+        .set_title("Save JSON Export")
+        .add_filter("JSON Files", &["json"])
+        .save_file()
+        .await
+        .as_ref()
+        .map(rfd::FileHandle::path)
+        .map(Path::to_owned)
+        .ok_or(Error::DialogClosed)
+}
+
+pub async fn choose_printable_menu_path() -> Result<PathBuf, Error> {
+    rfd::AsyncFileDialog::new()
+        .set_title("Save Printable Menu")
+        .add_filter("HTML Files", &["html"])
+        .set_file_name("menu.html")
+        .save_file()
+        .await
+        .as_ref()
+        .map(rfd::FileHandle::path)
+        .map(Path::to_owned)
+        .ok_or(Error::DialogClosed)
+}
+
+pub async fn choose_price_pivot_path() -> Result<PathBuf, Error> {
+    rfd::AsyncFileDialog::new()
+        .set_title("Save Price Levels")
+        .add_filter("CSV Files", &["csv"])
+        .set_file_name("price_levels.csv")
+        .save_file()
+        .await
+        .as_ref()
+        .map(rfd::FileHandle::path)
+        .map(Path::to_owned)
+        .ok_or(Error::DialogClosed)
+}
+
+pub async fn choose_data_directory() -> Result<PathBuf, Error> {
+    rfd::AsyncFileDialog::new()
+        .set_title("Choose a data directory")
+        .pick_folder()
+        .await
+        .as_ref()
+        .map(rfd::FileHandle::path)
+        .map(Path::to_owned)
+        .ok_or(Error::DialogClosed)
+}
+
+/// Picks a backup or snapshot to restore, starting in the folder they're written to
+pub async fn choose_backup(dir: PathBuf) -> Result<PathBuf, String> {
+    rfd::AsyncFileDialog::new()
+        .set_title("Restore Backup or Snapshot")
+        .set_directory(dir)
+        .add_filter("RON Files", &["ron"])
+        .pick_file()
+        .await
+        .as_ref()
+        .map(rfd::FileHandle::path)
+        .map(Path::to_owned)
+        .ok_or(DIALOG_CLOSED.to_string())
+}
+
+pub async fn choose_export_folder() -> Result<PathBuf, Error> {
+    rfd::AsyncFileDialog::new()
+        .set_title("Choose a folder for the item group exports")
+        .pick_folder()
+        .await
+        .as_ref()
+        .map(rfd::FileHandle::path)
+        .map(Path::to_owned)
+        .ok_or(Error::DialogClosed)
+}
+
+/// One CSV per item group in `folder`, named after the group. Empty groups get no file.
+pub fn group_export_preview(
+    groups: Vec<(String, Vec<crate::items::Item>)>,
+    folder: PathBuf,
+    profile: &ExportProfile,
+) -> ExportPreview {
+    let files = groups
+        .into_iter()
+        .filter(|(_, items)| !items.is_empty())
+        .map(|(group_name, items)| {
+            let path = folder.join(format!("{}.csv", export_file_name(&group_name)));
+            (path, crate::items::export_items::items_to_export_string(&items, profile))
+        })
+        .collect();
+
+    ExportPreview::new(ExportKind::ByGroup { folder }, files)
+}
+
+fn export_file_name(group_name: &str) -> String {
+    let name: String = group_name
+        .trim()
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+        .collect();
+
+    if name.is_empty() { "unnamed".to_string() } else { name }
+}
+
+// Duplicate button texts under their item group, each item opens on the items screen
+fn button_collisions_view<'a>(collisions: &'a [ButtonCollision], text_style: TextStyle) -> Element<'a, Message> {
+    if collisions.is_empty() {
+        return column![].into();
+    }
+
+    let mut rows = Vec::new();
+    let mut last_group: Option<&str> = None;
+    for collision in collisions {
+        if last_group != Some(collision.group_name.as_str()) {
+            rows.push(text(&collision.group_name).size(text_style.body()).into());
+            last_group = Some(collision.group_name.as_str());
         }
+
+        rows.push(
+            row![
+                text(format!("\"{}\":", collision.button_text))
+                    .size(text_style.caption())
+                    .style(Modern::error_text()),
+                row(
+                    collision.items.iter().map(|(id, name)| {
+                        button(text(format!("{} ({})", name, id)).size(text_style.caption()))
+                            .on_press(Message::OpenItem(*id))
+                            .style(Modern::gray_button())
+                            .into()
+                    })
+                    .collect::<Vec<_>>()
+                )
+                .spacing(5)
+                .wrap(),
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center)
+            .into()
+        );
     }
 
-    // Write the content to the file
-    tokio::fs::write(&path, content)
-        .await
-        .map_err(|error| Error::IoError(error.kind()))?;
+    column![
+        text(format!("Duplicate button text ({})", collisions.len())).size(text_style.subheading()),
+        scrollable(column(rows).spacing(5)).height(150),
+    ]
+    .spacing(5)
+    .into()
+}
+
+/// Current data directory, and the copy or move prompt while switching to another one
+fn data_directory_view<'a>(data_dir: &'a Path, state: &'a DataDirState, text_style: TextStyle) -> Element<'a, Message> {
+    let current = row![
+        text("Data directory:"),
+        text(data_dir.display().to_string())
+            .size(text_style.caption())
+            .font(text_style.data_font()),
+        button("Browse...")
+            .on_press_maybe(state.change.is_none().then_some(Message::ChooseDataDirectory))
+            .style(Modern::system_button()),
+    ]
+    .spacing(10)
+    .align_y(iced::Alignment::Center);
+
+    let Some(change) = &state.change else {
+        return column![
+            current,
+            text(&state.status).size(text_style.caption()).style(Modern::secondary_text()),
+        ]
+        .spacing(5)
+        .into();
+    };
+
+    let prompt: Element<'a, Message> = if change.transfer.is_some() {
+        text(format!(
+            "Copying file {} of {} to {}...",
+            (change.copied.len() + 1).min(change.files.len()),
+            change.files.len(),
+            change.target.display()
+        ))
+        .size(text_style.caption())
+        .into()
+    } else {
+        let can_copy = !change.target_has_data;
 
-    Ok(path)
-}
\ No newline at end of file
+        column![
+            text(format!("Switch the data directory to {}?", change.target.display())),
+            text(if change.target_has_data {
+                "That folder already has a data file. Use it, or choose an empty folder to copy your data into.".to_string()
+            } else {
+                format!("{} data files (save file, backups and snapshots) are in the current directory.", change.files.len())
+            })
+            .size(text_style.caption())
+            .style(Modern::secondary_text()),
+            row![
+                button("Copy Files")
+                    .on_press_maybe(can_copy.then_some(Message::TransferDataDirectory(DataDirTransfer::Copy)))
+                    .style(Modern::primary_button()),
+                button("Move Files")
+                    .on_press_maybe(can_copy.then_some(Message::TransferDataDirectory(DataDirTransfer::Move)))
+                    .style(Modern::system_button()),
+                button(if change.target_has_data { "Use Files There" } else { "Switch Without Copying" })
+                    .on_press(Message::TransferDataDirectory(DataDirTransfer::SwitchOnly))
+                    .style(Modern::warning_button()),
+                button("Cancel")
+                    .on_press(Message::CancelDataDirectoryChange)
+                    .style(Modern::gray_button()),
+            ]
+            .spacing(10),
+        ]
+        .spacing(5)
+        .into()
+    };
+
+    let error: Element<'a, Message> = match &change.error {
+        Some(error) => text(error).size(text_style.caption()).style(Modern::red_text()).into(),
+        None => row![].into(),
+    };
+
+    column![
+        current,
+        container(column![prompt, error].spacing(5))
+            .padding(10)
+            .style(Modern::accent_container()),
+    ]
+    .spacing(5)
+    .into()
+}
@@ -0,0 +1,127 @@
+use serde::{Serialize, Deserialize};
+use std::fmt;
+use crate::{items, Screen};
+
+/// A screen that has a button in the sidebar. Kept separate from `Screen`
+/// because `Screen` carries state that shouldn't be saved with the settings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SidebarScreen {
+    Items,
+    ItemGroups,
+    PriceLevels,
+    ProductClasses,
+    TaxGroups,
+    SecurityLevels,
+    RevenueCategories,
+    ReportCategories,
+    ChoiceGroups,
+    PrinterLogicals,
+}
+
+impl SidebarScreen {
+    pub const ALL: &'static [Self] = &[
+        Self::Items,
+        Self::ItemGroups,
+        Self::PriceLevels,
+        Self::ProductClasses,
+        Self::TaxGroups,
+        Self::SecurityLevels,
+        Self::RevenueCategories,
+        Self::ReportCategories,
+        Self::ChoiceGroups,
+        Self::PrinterLogicals,
+    ];
+
+    pub fn screen(&self) -> Screen {
+        match self {
+            Self::Items => Screen::Items(items::Mode::View),
+            Self::ItemGroups => Screen::ItemGroups,
+            Self::PriceLevels => Screen::PriceLevels,
+            Self::ProductClasses => Screen::ProductClasses,
+            Self::TaxGroups => Screen::TaxGroups,
+            Self::SecurityLevels => Screen::SecurityLevels,
+            Self::RevenueCategories => Screen::RevenueCategories,
+            Self::ReportCategories => Screen::ReportCategories,
+            Self::ChoiceGroups => Screen::ChoiceGroups,
+            Self::PrinterLogicals => Screen::PrinterLogicals,
+        }
+    }
+
+    pub fn is_selected(&self, screen: &Screen) -> bool {
+        match self {
+            Self::Items => matches!(screen, Screen::Items(_)),
+            Self::ItemGroups => matches!(screen, Screen::ItemGroups),
+            Self::PriceLevels => matches!(screen, Screen::PriceLevels),
+            Self::ProductClasses => matches!(screen, Screen::ProductClasses),
+            Self::TaxGroups => matches!(screen, Screen::TaxGroups),
+            Self::SecurityLevels => matches!(screen, Screen::SecurityLevels),
+            Self::RevenueCategories => matches!(screen, Screen::RevenueCategories),
+            Self::ReportCategories => matches!(screen, Screen::ReportCategories),
+            Self::ChoiceGroups => matches!(screen, Screen::ChoiceGroups),
+            Self::PrinterLogicals => matches!(screen, Screen::PrinterLogicals),
+        }
+    }
+}
+
+impl fmt::Display for SidebarScreen {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Items => write!(f, "Items"),
+            Self::ItemGroups => write!(f, "Item Groups"),
+            Self::PriceLevels => write!(f, "Price Levels"),
+            Self::ProductClasses => write!(f, "Product Classes"),
+            Self::TaxGroups => write!(f, "Tax Groups"),
+            Self::SecurityLevels => write!(f, "Security Levels"),
+            Self::RevenueCategories => write!(f, "Revenue Categories"),
+            Self::ReportCategories => write!(f, "Report Categories"),
+            Self::ChoiceGroups => write!(f, "Choice Groups"),
+            Self::PrinterLogicals => write!(f, "Printer Logicals"),
+        }
+    }
+}
+
+pub fn default_order() -> Vec<SidebarScreen> {
+    SidebarScreen::ALL.to_vec()
+}
+
+/// The saved order with favorites on top. Screens missing from an older
+/// save file are added at the end so they never disappear from the sidebar.
+pub fn ordered(order: &[SidebarScreen], favorites: &[SidebarScreen]) -> Vec<SidebarScreen> {
+    let mut screens: Vec<SidebarScreen> = Vec::new();
+    for screen in order.iter().chain(SidebarScreen::ALL) {
+        if !screens.contains(screen) {
+            screens.push(*screen);
+        }
+    }
+
+    // Stable, so favorites and the rest both keep their saved order
+    screens.sort_by_key(|screen| !favorites.contains(screen));
+    screens
+}
+
+/// Swaps a screen with its neighbour in the displayed order, `up` towards the top
+pub fn move_screen(
+    order: &mut Vec<SidebarScreen>,
+    favorites: &[SidebarScreen],
+    screen: SidebarScreen,
+    up: bool,
+) {
+    let mut displayed = ordered(order, favorites);
+    let Some(index) = displayed.iter().position(|s| *s == screen) else {
+        return;
+    };
+
+    let target = if up {
+        index.checked_sub(1)
+    } else {
+        Some(index + 1).filter(|i| *i < displayed.len())
+    };
+
+    // Favorites stay above everything else, so only move within the same section
+    if let Some(target) = target {
+        if favorites.contains(&displayed[target]) == favorites.contains(&screen) {
+            displayed.swap(index, target);
+            *order = displayed;
+        }
+    }
+}
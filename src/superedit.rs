@@ -1,12 +1,12 @@
 use iced::{Element, Task, Length};
-use iced::widget::{button, column, container, horizontal_space, pick_list, row, text, text_input, scrollable};
+use iced::widget::{button, column, container, horizontal_space, pick_list, row, text, text_input, scrollable, tooltip};
 use iced_modern_theme::Modern;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use rust_decimal::Decimal;
 use crate::Action;
 use crate::{
-    items::{Item, ViewContext},
-    data_types::{EntityId, ItemPrice},
+    items::Item,
+    data_types::{self, EntityId, ItemPrice},
     tax_groups::TaxGroup,
     security_levels::SecurityLevel,
     revenue_categories::RevenueCategory,
@@ -15,10 +15,16 @@ use crate::{
     product_classes::ProductClass,
     choice_groups::ChoiceGroup,
     printer_logicals::PrinterLogical,
-    price_levels::PriceLevel,
+    price_levels::{PriceLevel, PriceRounding, RoundingKind},
+    entity_component::{self, EntityOption, EntityPicker},
     icon,
+    focus_style,
+    text_style::TextStyle,
 };
-use crate::items::preview_changes::{ItemsTableView, Message as PreviewMessage};
+use crate::items::preview_changes::{ItemsTableView, ItemFlag, ScrollMemory, Message as PreviewMessage};
+use crate::items::item_patch::{self, ItemPatch};
+use crate::export_preview::{ExportKind, ExportPreview};
+use crate::items::store_fields;
 use iced_table::{ColumnVisibilityMessage, table::Column};
 
 #[derive(Debug, Clone)]
@@ -31,6 +37,9 @@ pub enum Message {
     UpdateConditionOperator(usize, FilterOperator),
     UpdateConditionValue(usize, String),
     UpdateConditionEntity(usize, EntityId), // For entity dropdowns
+    UpdateConditionQuery(usize, String),
+    CycleConditionOpen(usize),
+    CycleConditionClose(usize),
     
     // Action management
     AddAction,
@@ -39,12 +48,26 @@ pub enum Message {
     UpdateActionOperation(usize, ActionOperation),
     UpdateActionValue(usize, String),
     UpdateActionPriceLevel(usize, EntityId),
+    UpdateActionRounding(usize, RoundingKind),
+    UpdateActionRoundingValue(usize, String),
     UpdateActionSwapFrom(usize, EntityId),
+    UpdateActionQuery(usize, String),
+    UpdateActionSwapFromQuery(usize, String),
 
     // Entity Selection
     UpdateActionEntity(usize, EntityId),
+
+    // Apply scope
+    UpdateApplyScope(ApplyScope),
+    UpdateApplyScopeValue(String),
+
     AcceptChanges,
     CancelPreview,
+    NextPreviewPage,
+    PreviousPreviewPage,
+    ExportPatch,
+    PatchReady(Result<ExportPreview, String>),
+    PatchExported(Result<std::path::PathBuf, String>),
 
     //Save Changes to App State
     CommitChanges(Item),
@@ -52,6 +75,13 @@ pub enum Message {
     //Table Preview
     Preview(PreviewMessage),
     PreviewChanges, 
+    ToggleExplainMatches(bool),
+
+    // Flag edits from the filtered table
+    ConfirmBulkFlag,
+    CancelBulkFlag,
+    ApplyFlagChanges,
+    DiscardFlagChanges,
 }
 
 #[derive(Debug, Clone)]
@@ -62,6 +92,23 @@ struct FilterAction {
     entity_id: Option<EntityId>, // For entity selections
     swap_from_id: Option<EntityId>, // For swap from selections
     price_level: Option<EntityId>, // For price-related operations
+    picker: EntityPicker, // Options follow the category, see sync_pickers
+    swap_from_picker: EntityPicker,
+    rounding_kind: RoundingKind, // For price operations, applied after the new price is worked out
+    rounding_value: String,
+}
+
+impl FilterAction {
+    // Swapping an entity for itself changes nothing
+    fn is_same_entity_swap(&self) -> bool {
+        self.operation == ActionOperation::SwapTo
+            && self.swap_from_id.is_some()
+            && self.swap_from_id == self.entity_id
+    }
+
+    fn rounding(&self) -> Result<PriceRounding, String> {
+        PriceRounding::parse(self.rounding_kind, &self.rounding_value)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -71,6 +118,21 @@ struct FilterCondition {
     operator: FilterOperator,   // begins with, contains, etc.
     value: String,             // user input value for text fields
     entity_id: Option<EntityId>, // For entity dropdowns
+    picker: EntityPicker,
+    open_groups: u8,  // "(" before this condition
+    close_groups: u8, // ")" after it
+}
+
+/// Most parentheses one condition can open or close
+const MAX_GROUP_DEPTH: u8 = 3;
+
+// Conditions and their parentheses, flattened for fold_groups
+#[derive(Debug, Clone)]
+enum GroupToken {
+    Open,
+    Close,
+    Logic(ConditionLogic),
+    Result(bool),
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -107,6 +169,7 @@ enum FilterOperator {
     GreaterOrEqual, // For Price
     LessOrEqual,    // For Price
     Between,        // For ID
+    RoutesToInactive, // For Printer Logical
 }
 
 impl FilterOperator {
@@ -133,7 +196,14 @@ impl FilterOperator {
                 FilterOperator::IsNotEmpty,
             ],
             FilterCategory::PriceLevel |
-            FilterCategory::ChoiceGroup |
+            FilterCategory::ChoiceGroup => vec![
+                FilterOperator::Contains,
+                FilterOperator::DoesNotContain,
+                FilterOperator::Equals,
+                FilterOperator::NotEquals,
+                FilterOperator::IsEmpty,
+                FilterOperator::IsNotEmpty,
+            ],
             FilterCategory::PrinterLogical => vec![
                 FilterOperator::Contains,
                 FilterOperator::DoesNotContain,
@@ -141,6 +211,7 @@ impl FilterOperator {
                 FilterOperator::NotEquals,
                 FilterOperator::IsEmpty,
                 FilterOperator::IsNotEmpty,
+                FilterOperator::RoutesToInactive,
             ],
             FilterCategory::Price => vec![
                 FilterOperator::Equals,
@@ -157,6 +228,20 @@ impl FilterOperator {
                 FilterOperator::LessThan,
                 FilterOperator::Between,
             ],
+            FilterCategory::KdsCategory => vec![
+                FilterOperator::Contains,
+                FilterOperator::BeginsWith,
+                FilterOperator::Equals,
+                FilterOperator::NotEquals,
+                FilterOperator::IsEmpty,
+                FilterOperator::IsNotEmpty,
+            ],
+            FilterCategory::KdsDept | FilterCategory::KdsCookTime | FilterCategory::Covers => vec![
+                FilterOperator::Equals,
+                FilterOperator::NotEquals,
+                FilterOperator::GreaterThan,
+                FilterOperator::LessThan,
+            ],
         }
     }
 }
@@ -177,6 +262,7 @@ impl std::fmt::Display for FilterOperator {
             FilterOperator::GreaterOrEqual => write!(f, "greater or equal"),
             FilterOperator::LessOrEqual => write!(f, "less or equal"),
             FilterOperator::Between => write!(f, "between"),
+            FilterOperator::RoutesToInactive => write!(f, "routes to an inactive printer"),
         }
     }
 }
@@ -194,6 +280,15 @@ enum ActionOperation {
     
     // For all entity fields
     SwapTo,
+
+    // For the KDS fields
+    SetTo,
+
+    // For Printer Logical, takes another item's printers as they are
+    CopyFromItem,
+
+    // For Price Level, adds the levels each item's group gives by default
+    ApplyGroupDefaults,
 }
 
 impl ActionOperation {
@@ -205,10 +300,25 @@ impl ActionOperation {
                 ActionOperation::SubtractFromPrice,
                 ActionOperation::SetPrice,
             ],
-            FilterCategory::PrinterLogical | FilterCategory::PriceLevel | FilterCategory::ChoiceGroup => vec![
+            FilterCategory::PrinterLogical => vec![
                 ActionOperation::Add,
                 ActionOperation::Remove,
                 ActionOperation::SwapTo,
+                ActionOperation::CopyFromItem,
+            ],
+            FilterCategory::PriceLevel => vec![
+                ActionOperation::Add,
+                ActionOperation::Remove,
+                ActionOperation::SwapTo,
+                ActionOperation::ApplyGroupDefaults,
+            ],
+            FilterCategory::ChoiceGroup => vec![
+                ActionOperation::Add,
+                ActionOperation::Remove,
+                ActionOperation::SwapTo,
+            ],
+            FilterCategory::KdsCategory | FilterCategory::KdsDept | FilterCategory::KdsCookTime | FilterCategory::Covers => vec![
+                ActionOperation::SetTo,
             ],
             FilterCategory::Name | FilterCategory::Id => vec![], // These shouldn't appear in actions
             _ => vec![
@@ -227,6 +337,32 @@ impl std::fmt::Display for ActionOperation {
             ActionOperation::SwapTo => write!(f, "Swap"),
             ActionOperation::Add => write!(f, "Add"),
             ActionOperation::Remove => write!(f, "Remove"),
+            ActionOperation::SetTo => write!(f, "Set to"),
+            ActionOperation::CopyFromItem => write!(f, "Copy from item #"),
+            ActionOperation::ApplyGroupDefaults => write!(f, "Apply group defaults"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApplyScope {
+    All,
+    FirstN,
+    RandomPercent,
+    Selected, // Rows picked in the table with "Use as SuperEdit Scope"
+}
+
+impl ApplyScope {
+    const ALL: [ApplyScope; 4] = [ApplyScope::All, ApplyScope::FirstN, ApplyScope::RandomPercent, ApplyScope::Selected];
+}
+
+impl std::fmt::Display for ApplyScope {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ApplyScope::All => write!(f, "All matched items"),
+            ApplyScope::FirstN => write!(f, "First N items"),
+            ApplyScope::RandomPercent => write!(f, "Random % sample"),
+            ApplyScope::Selected => write!(f, "Selected rows"),
         }
     }
 }
@@ -234,6 +370,16 @@ impl std::fmt::Display for ActionOperation {
 #[derive(Debug, Clone)]
 pub enum Operation {
     UpdateItem(Item),
+    // Items the accepted actions changed, and each price level's rounding for the next bulk change there
+    ChangesAccepted { changed: Vec<EntityId>, rounding: Vec<(EntityId, PriceRounding)> },
+    FlagsApplied { items: usize, flags: usize },
+    // Actions on rows selected in the filtered table, handled like the item list's own
+    OpenItem(EntityId),
+    DuplicateItem(EntityId),
+    RequestDeleteItem(EntityId),
+    ItemsDeleted(Vec<Item>),
+    PinItems(Vec<EntityId>),
+    PreviewExport(ExportPreview), // Shown in the main window, written once confirmed there
 }
 
 #[derive(Debug, Clone)]
@@ -241,10 +387,27 @@ pub struct SuperEdit{
     conditions: Vec<FilterCondition>,
     actions: Vec<FilterAction>,
     filtered_items: Option<BTreeMap<EntityId, Item>>,
+    used_entity_ids: HashMap<FilterCategory, HashSet<EntityId>>, // Entities referenced by filtered items, rebuilt with the filter
     modified_items: Option<BTreeMap<EntityId, Item>>,
     preview_table: Option<ItemsTableView>,
     show_preview: bool,
     changed_item_ids: Vec<EntityId>, // Track which items were actually changed
+    matched_item_count: usize, // Items matching the filters at the last preview, before sampling
+    apply_scope: ApplyScope,
+    apply_scope_value: String, // N for FirstN, X for RandomPercent
+    scope_ids: BTreeSet<EntityId>, // Items for ApplyScope::Selected
+    patch_message: String,
+    action_error: Option<String>, // Set when an action value can't be applied, blocks the preview
+    overflow_warnings: Vec<String>, // Price changes left out of the last preview because the math overflowed
+    scroll_memory: ScrollMemory,
+    restore_scroll: bool, // The table was rebuilt over the same rows, scroll it back after this update
+    explain_matches: bool, // Opt-in, records which conditions each matched item passed
+    match_results: BTreeMap<EntityId, String>, // Per-item condition results, only filled while explaining
+    pending_flags: BTreeMap<(EntityId, ItemFlag), bool>, // Checkbox edits in the filtered table, until applied
+    confirm_bulk_flag: Option<(ItemFlag, bool, usize)>, // Header checkbox waiting on confirmation, with the rows it changes
+    page_size: usize, // Changed items shown per preview page, from settings
+    text_style: TextStyle, // From settings, like page_size
+    preview_page: usize,
 }
 
 impl SuperEdit {
@@ -256,6 +419,9 @@ impl SuperEdit {
             operator: FilterOperator::IsNotEmpty,
             value: String::new(),
             entity_id: None,
+            picker: EntityPicker::default(),
+            open_groups: 0,
+            close_groups: 0,
         };
 
         let default_action = FilterAction {
@@ -265,16 +431,54 @@ impl SuperEdit {
             entity_id: None,
             swap_from_id: None,
             price_level: None,
+            picker: EntityPicker::default(),
+            swap_from_picker: EntityPicker::default(),
+            rounding_kind: RoundingKind::None,
+            rounding_value: String::new(),
         };
 
         Self {
             conditions: vec![default_condition],
             actions: vec![default_action],
             filtered_items: None,
+            used_entity_ids: HashMap::new(),
             modified_items: None,
             preview_table: None,
             show_preview: false,
             changed_item_ids: Vec::new(),
+            matched_item_count: 0,
+            apply_scope: ApplyScope::All,
+            apply_scope_value: String::new(),
+            scope_ids: BTreeSet::new(),
+            patch_message: String::new(),
+            action_error: None,
+            overflow_warnings: Vec::new(),
+            scroll_memory: ScrollMemory::default(),
+            restore_scroll: false,
+            explain_matches: false,
+            match_results: BTreeMap::new(),
+            pending_flags: BTreeMap::new(),
+            confirm_bulk_flag: None,
+            page_size: 200,
+            text_style: TextStyle::default(),
+            preview_page: 0,
+        }
+    }
+
+    /// Rows a preview shows at once, Accept still applies every change
+    pub fn set_page_size(&mut self, page_size: usize) {
+        self.page_size = page_size.max(1);
+    }
+
+    /// Whether a preview is waiting on Accept
+    pub fn has_preview(&self) -> bool {
+        self.modified_items.is_some()
+    }
+
+    pub fn set_text_style(&mut self, text_style: TextStyle) {
+        self.text_style = text_style;
+        if let Some(table) = &mut self.preview_table {
+            table.set_text_style(text_style);
         }
     }
 
@@ -292,7 +496,7 @@ impl SuperEdit {
         printer_logicals: &BTreeMap<EntityId, PrinterLogical>,
         price_levels: &BTreeMap<EntityId, PriceLevel>,
     ) -> Action<Operation, Message> {
-        match message {
+        let action = match message {
             Message::AddCondition => {
                 let new_condition = FilterCondition {
                     logic: ConditionLogic::And,
@@ -300,6 +504,9 @@ impl SuperEdit {
                     operator: FilterOperator::Contains,
                     value: String::new(),
                     entity_id: None,
+                    picker: EntityPicker::default(),
+                    open_groups: 0,
+                    close_groups: 0,
                 };
                 self.conditions.push(new_condition);
                 Action::none()
@@ -334,7 +541,8 @@ impl SuperEdit {
                     
                     // Clear entity_id for text fields, clear value for entity fields
                     match field {
-                        FilterCategory::Name | FilterCategory::Price | FilterCategory::Id => {
+                        FilterCategory::Name | FilterCategory::Price | FilterCategory::Id |
+                        FilterCategory::KdsCategory | FilterCategory::KdsDept | FilterCategory::KdsCookTime | FilterCategory::Covers => {
                             condition.entity_id = None;
                         }
                         _ => {
@@ -368,6 +576,24 @@ impl SuperEdit {
 
                 Action::none()
             }
+            Message::CycleConditionOpen(index) => {
+                if let Some(condition) = self.conditions.get_mut(index) {
+                    condition.open_groups = (condition.open_groups + 1) % (MAX_GROUP_DEPTH + 1);
+                    self.refresh_filtered_items(items, item_groups, tax_groups, security_levels,
+                        revenue_categories, report_categories, product_classes, choice_groups,
+                        printer_logicals, price_levels);
+                }
+                Action::none()
+            }
+            Message::CycleConditionClose(index) => {
+                if let Some(condition) = self.conditions.get_mut(index) {
+                    condition.close_groups = (condition.close_groups + 1) % (MAX_GROUP_DEPTH + 1);
+                    self.refresh_filtered_items(items, item_groups, tax_groups, security_levels,
+                        revenue_categories, report_categories, product_classes, choice_groups,
+                        printer_logicals, price_levels);
+                }
+                Action::none()
+            }
             Message::UpdateConditionEntity(index, entity_id) => {
                 if let Some(condition) = self.conditions.get_mut(index) {
                     condition.entity_id = Some(entity_id);
@@ -389,6 +615,10 @@ impl SuperEdit {
                     entity_id: None,
                     swap_from_id: None,
                     price_level: None,
+                    picker: EntityPicker::default(),
+                    swap_from_picker: EntityPicker::default(),
+                    rounding_kind: RoundingKind::None,
+                    rounding_value: String::new(),
                 };
                 self.actions.push(new_action);
                 Action::none()
@@ -433,11 +663,31 @@ impl SuperEdit {
                 if let Some(action) = self.actions.get_mut(index) {
                     action.value = value;
                 }
+                self.action_error = None;
                 Action::none()
             }
             Message::UpdateActionPriceLevel(index, price_level_id) => {
                 if let Some(action) = self.actions.get_mut(index) {
                     action.price_level = Some(price_level_id);
+
+                    // Start from the rounding last used at this level
+                    if let Some(price_level) = price_levels.get(&price_level_id) {
+                        action.rounding_kind = price_level.last_rounding.kind();
+                        action.rounding_value = price_level.last_rounding.input_text();
+                    }
+                }
+                Action::none()
+            }
+            Message::UpdateActionRounding(index, kind) => {
+                if let Some(action) = self.actions.get_mut(index) {
+                    action.rounding_kind = kind;
+                    action.rounding_value = String::new();
+                }
+                Action::none()
+            }
+            Message::UpdateActionRoundingValue(index, value) => {
+                if let Some(action) = self.actions.get_mut(index) {
+                    action.rounding_value = value;
                 }
                 Action::none()
             }
@@ -448,23 +698,119 @@ impl SuperEdit {
                 if let Some(action) = self.actions.get_mut(index) {
                     action.entity_id = Some(entity_id);
                 }
+                if self.validate_actions(items).is_err() {
+                    return Action::none();
+                }
                 self.preview_changes(items, item_groups, tax_groups, security_levels,
                     revenue_categories, report_categories, product_classes, choice_groups,
                     printer_logicals, price_levels);
                 Action::none()
             }
             Message::PreviewChanges => {
+                if let Err(e) = self.check_groups().and_then(|_| self.validate_actions(items)) {
+                    self.action_error = Some(e);
+                    return Action::none();
+                }
+                self.action_error = None;
                 self.preview_changes(items, item_groups, tax_groups, security_levels,
                     revenue_categories, report_categories, product_classes, choice_groups,
                     printer_logicals, price_levels);
                 Action::none()
             }
+            Message::UpdateApplyScope(scope) => {
+                self.apply_scope = scope;
+                self.apply_scope_value = String::new();
+                Action::none()
+            }
+            Message::UpdateApplyScopeValue(value) => {
+                if value.chars().all(|c| c.is_ascii_digit()) {
+                    self.apply_scope_value = value;
+                }
+                Action::none()
+            }
             Message::UpdateActionSwapFrom(index, entity_id) => {
                 if let Some(action) = self.actions.get_mut(index) {
                     action.swap_from_id = Some(entity_id);
                 }
                 Action::none()
             }
+            Message::UpdateConditionQuery(index, query) => {
+                if let Some(condition) = self.conditions.get_mut(index) {
+                    condition.picker.set_query(query);
+                }
+                Action::none()
+            }
+            Message::UpdateActionQuery(index, query) => {
+                if let Some(action) = self.actions.get_mut(index) {
+                    action.picker.set_query(query);
+                }
+                Action::none()
+            }
+            Message::UpdateActionSwapFromQuery(index, query) => {
+                if let Some(action) = self.actions.get_mut(index) {
+                    action.swap_from_picker.set_query(query);
+                }
+                Action::none()
+            }
+            Message::ExportPatch => {
+                let Some(modified_items) = &self.modified_items else {
+                    return Action::none();
+                };
+
+                // Only the items this preview would change
+                let before: BTreeMap<EntityId, Item> = self.changed_item_ids.iter()
+                    .filter_map(|id| items.get(id).map(|item| (*id, item.clone())))
+                    .collect();
+                let after: BTreeMap<EntityId, Item> = self.changed_item_ids.iter()
+                    .filter_map(|id| modified_items.get(id).map(|item| (*id, item.clone())))
+                    .collect();
+
+                let patch = ItemPatch::diff(&before, &after);
+                println!("Exporting patch with {} changes", patch.changes.len());
+
+                let kind = ExportKind::Patch { changes: patch.changes.len() };
+                Action::task(Task::perform(item_patch::preview_patch(patch, kind), Message::PatchReady))
+            }
+            Message::PatchReady(result) => {
+                match result {
+                    Ok(preview) => Action::operation(Operation::PreviewExport(preview)),
+                    Err(e) if e == crate::settings::DIALOG_CLOSED => Action::none(),
+                    Err(e) => {
+                        self.patch_message = format!("Patch export failed: {}", e);
+                        Action::none()
+                    }
+                }
+            }
+            Message::PatchExported(result) => {
+                self.patch_message = match result {
+                    Ok(path) => format!("Patch saved to {}", path.to_string_lossy()),
+                    Err(e) if e == crate::settings::DIALOG_CLOSED => return Action::none(),
+                    Err(e) => format!("Patch export failed: {}", e),
+                };
+                Action::none()
+            }
+            Message::NextPreviewPage | Message::PreviousPreviewPage => {
+                let page = match message {
+                    Message::NextPreviewPage => self.preview_page + 1,
+                    _ => self.preview_page.saturating_sub(1),
+                };
+                if self.show_preview && page != self.preview_page && page < self.preview_page_count() {
+                    self.preview_page = page;
+                    self.show_preview_page(items, item_groups, tax_groups, security_levels,
+                        revenue_categories, report_categories, product_classes, choice_groups,
+                        printer_logicals, price_levels);
+                }
+                Action::none()
+            }
+            Message::ToggleExplainMatches(enabled) => {
+                self.explain_matches = enabled;
+                if !self.show_preview {
+                    self.refresh_filtered_items(items, item_groups, tax_groups, security_levels,
+                        revenue_categories, report_categories, product_classes, choice_groups,
+                        printer_logicals, price_levels);
+                }
+                Action::none()
+            }
             Message::CancelPreview => {
                 self.show_preview = false;
                 self.modified_items = None;
@@ -477,12 +823,26 @@ impl SuperEdit {
                 Action::none()
             }
             Message::AcceptChanges => {
+                // Remember each price level's rounding before the actions are reset
+                let rounding_rules: Vec<(EntityId, PriceRounding)> = self.actions
+                    .iter()
+                    .filter(|action| action.category == FilterCategory::Price)
+                    .filter_map(|action| match (action.price_level, action.rounding()) {
+                        (Some(price_level_id), Ok(rounding)) if price_level_id != 0 => Some((price_level_id, rounding)),
+                        _ => None,
+                    })
+                    .collect();
+
                 if let Some(modified_items) = &self.modified_items {
+                    let updated_at = data_types::timestamp_now();
+                    let accepted_offset = self.preview_table.as_ref().map(|table| table.scroll_offset);
+
                     // Update only the changed items
                     for id in &self.changed_item_ids {
                         if let Some(modified_item) = modified_items.get(id) {
                             if let Some(original) = items.get_mut(id) {
                                 *original = modified_item.clone();
+                                original.updated_at = Some(updated_at.clone());
                             }
                         }
                     }
@@ -494,6 +854,9 @@ impl SuperEdit {
                         operator: FilterOperator::IsNotEmpty,
                         value: String::new(),
                         entity_id: None,
+                        picker: EntityPicker::default(),
+                        open_groups: 0,
+                        close_groups: 0,
                     }];
                     
                     self.actions = vec![FilterAction {
@@ -503,23 +866,38 @@ impl SuperEdit {
                         entity_id: None,
                         swap_from_id: None,
                         price_level: None,
+                        picker: EntityPicker::default(),
+                        swap_from_picker: EntityPicker::default(),
+                        rounding_kind: RoundingKind::None,
+                        rounding_value: String::new(),
                     }];
                     
                     self.show_preview = false;
                     self.modified_items = None;
-                    self.changed_item_ids.clear();
+                    let changed = std::mem::take(&mut self.changed_item_ids);
                     
                     // Refresh the table with all items
                     self.refresh_filtered_items(items, item_groups, tax_groups, security_levels,
                         revenue_categories, report_categories, product_classes, choice_groups,
                         printer_logicals, price_levels);
+
+                    // Stay where the preview was scrolled to. The table now lists every item,
+                    // so it's never shorter than the preview and the offset can't fall past its end
+                    if let (Some(offset), Some(table)) = (accepted_offset, self.preview_table.as_mut()) {
+                        table.scroll_offset = offset;
+                        self.restore_scroll = true;
+                    }
+
+                    Action::operation(Operation::ChangesAccepted { changed, rounding: rounding_rules })
+                } else {
+                    Action::none()
                 }
-                Action::none()
             }
             Message::Preview(preview_msg) => {
                 match preview_msg {
                     PreviewMessage::SyncHeader(offset) => {
                         if let Some(preview) = &mut self.preview_table {
+                            preview.scroll_offset = offset;
                             let task = iced::widget::scrollable::scroll_to(preview.header_id.clone(), offset);
                             return Action::task(task)
                         }
@@ -570,8 +948,202 @@ impl SuperEdit {
                         }
                         Action::none()
                     }
+                    PreviewMessage::ToggleFlag(item_id, flag, value) => {
+                        self.set_pending_flag(items, item_id, flag, value);
+                        self.show_pending_flags();
+                        Action::none()
+                    }
+                    PreviewMessage::SetFlagForAll(flag) => {
+                        if let Some(table) = &self.preview_table {
+                            let values = table.flag_values(flag);
+                            // Mixed or all clear sets the flag, all set clears it
+                            let value = !values.iter().all(|(_, value)| *value);
+                            let count = values.iter().filter(|(_, shown)| *shown != value).count();
+
+                            if count > 0 {
+                                self.confirm_bulk_flag = Some((flag, value, count));
+                            }
+                        }
+                        Action::none()
+                    }
+                    PreviewMessage::ModifiersChanged(modifiers) => {
+                        if let Some(table) = &mut self.preview_table {
+                            table.set_modifiers(modifiers);
+                        }
+                        Action::none()
+                    }
+                    selection_msg => {
+                        let Some(table) = self.preview_table.as_mut().filter(|table| table.is_selectable()) else {
+                            return Action::none();
+                        };
+                        let selected = table.selected_ids();
+
+                        match selection_msg {
+                            PreviewMessage::PressRow(id) => table.press_row(id),
+                            PreviewMessage::MoveFocus(down) => table.move_focus(down),
+                            PreviewMessage::ToggleFocused => table.toggle_focused(),
+                            PreviewMessage::ClearSelection => table.clear_selection(),
+                            PreviewMessage::OpenSelected => {
+                                if let [id] = selected[..] {
+                                    return Action::operation(Operation::OpenItem(id));
+                                }
+                            }
+                            PreviewMessage::DuplicateSelected => {
+                                if let [id] = selected[..] {
+                                    return Action::operation(Operation::DuplicateItem(id));
+                                }
+                            }
+                            PreviewMessage::RequestDeleteSelected => {
+                                // One item gets the same confirmation as deleting it from the list
+                                if let [id] = selected[..] {
+                                    return Action::operation(Operation::RequestDeleteItem(id));
+                                }
+                                table.confirm_delete = true;
+                            }
+                            PreviewMessage::CancelDeleteSelected => table.confirm_delete = false,
+                            PreviewMessage::ConfirmDeleteSelected => {
+                                let deleted: Vec<Item> = selected.iter().filter_map(|id| items.remove(id)).collect();
+                                println!("Deleted {} items from the SuperEdit table", deleted.len());
+
+                                self.refresh_filtered_items(items, item_groups, tax_groups, security_levels,
+                                    revenue_categories, report_categories, product_classes, choice_groups,
+                                    printer_logicals, price_levels);
+                                return Action::operation(Operation::ItemsDeleted(deleted));
+                            }
+                            PreviewMessage::PinSelected => {
+                                return Action::operation(Operation::PinItems(selected));
+                            }
+                            PreviewMessage::UseSelectionAsScope => {
+                                self.scope_ids = selected.into_iter().collect();
+                                self.apply_scope = ApplyScope::Selected;
+                                self.apply_scope_value.clear();
+                            }
+                            _ => {}
+                        }
+                        Action::none()
+                    }
                 }
             }
+            Message::ConfirmBulkFlag => {
+                if let Some((flag, value, _)) = self.confirm_bulk_flag.take() {
+                    let rows = self.preview_table.as_ref().map(|table| table.flag_values(flag)).unwrap_or_default();
+                    for (item_id, _) in rows {
+                        self.set_pending_flag(items, item_id, flag, value);
+                    }
+                    self.show_pending_flags();
+                }
+                Action::none()
+            }
+            Message::CancelBulkFlag => {
+                self.confirm_bulk_flag = None;
+                Action::none()
+            }
+            Message::DiscardFlagChanges => {
+                self.pending_flags.clear();
+                self.confirm_bulk_flag = None;
+                self.show_pending_flags();
+                Action::none()
+            }
+            Message::ApplyFlagChanges => {
+                let updated_at = data_types::timestamp_now();
+                let mut changed_items = HashSet::new();
+                let mut changed_flags = 0;
+
+                for ((item_id, flag), value) in std::mem::take(&mut self.pending_flags) {
+                    if let Some(item) = items.get_mut(&item_id) {
+                        if flag.get(item) != value {
+                            flag.set(item, value);
+                            item.updated_at = Some(updated_at.clone());
+                            changed_items.insert(item_id);
+                            changed_flags += 1;
+                        }
+                    }
+                }
+                println!("Applied {} flag changes to {} items", changed_flags, changed_items.len());
+
+                self.confirm_bulk_flag = None;
+                self.refresh_filtered_items(items, item_groups, tax_groups, security_levels,
+                    revenue_categories, report_categories, product_classes, choice_groups,
+                    printer_logicals, price_levels);
+
+                Action::operation(Operation::FlagsApplied { items: changed_items.len(), flags: changed_flags })
+            }
+        };
+
+        self.sync_pickers(item_groups, tax_groups, security_levels, revenue_categories,
+            report_categories, product_classes, choice_groups, printer_logicals, price_levels);
+
+        let mut action = action;
+        if std::mem::take(&mut self.restore_scroll) {
+            if let Some(table) = &self.preview_table {
+                action.task = Task::batch([action.task, table.restore_scroll()]);
+            }
+        }
+
+        action
+    }
+
+    // Swaps in a rebuilt table, scrolled back to where that table was if it shows the same rows
+    fn replace_table(&mut self, mut table: ItemsTableView) {
+        if let Some(previous) = self.preview_table.take() {
+            table.keep_selection(&previous);
+            self.scroll_memory.remember(previous);
+        }
+        self.restore_scroll = self.scroll_memory.recall(&mut table);
+        table.set_text_style(self.text_style);
+        self.preview_table = Some(table);
+        self.show_pending_flags();
+    }
+
+    // A pending flag that matches the item again is dropped rather than kept as a no-op
+    fn set_pending_flag(&mut self, items: &BTreeMap<EntityId, Item>, item_id: EntityId, flag: ItemFlag, value: bool) {
+        if items.get(&item_id).is_some_and(|item| flag.get(item) == value) {
+            self.pending_flags.remove(&(item_id, flag));
+        } else {
+            self.pending_flags.insert((item_id, flag), value);
+        }
+    }
+
+    // Flags are only editable in the filtered table, the change preview stays read only
+    fn show_pending_flags(&mut self) {
+        if let Some(table) = &mut self.preview_table {
+            if !table.show_diff {
+                table.edit_flags(&self.pending_flags);
+            }
+        }
+    }
+
+    // Keeps each row's searchable picker in step with its category and the entity lists
+    fn sync_pickers(
+        &mut self,
+        item_groups: &BTreeMap<EntityId, ItemGroup>,
+        tax_groups: &BTreeMap<EntityId, TaxGroup>,
+        security_levels: &BTreeMap<EntityId, SecurityLevel>,
+        revenue_categories: &BTreeMap<EntityId, RevenueCategory>,
+        report_categories: &BTreeMap<EntityId, ReportCategory>,
+        product_classes: &BTreeMap<EntityId, ProductClass>,
+        choice_groups: &BTreeMap<EntityId, ChoiceGroup>,
+        printer_logicals: &BTreeMap<EntityId, PrinterLogical>,
+        price_levels: &BTreeMap<EntityId, PriceLevel>,
+    ) {
+        for condition in &mut self.conditions {
+            condition.picker.sync(category_options(&condition.field, item_groups, tax_groups,
+                security_levels, revenue_categories, report_categories, product_classes,
+                choice_groups, printer_logicals, price_levels));
+        }
+
+        let no_entities = HashSet::new();
+        for action in &mut self.actions {
+            let options = category_options(&action.category, item_groups, tax_groups,
+                security_levels, revenue_categories, report_categories, product_classes,
+                choice_groups, printer_logicals, price_levels);
+
+            // Only entities that appear in the filtered items can be swapped from
+            let used = self.used_entity_ids.get(&action.category).unwrap_or(&no_entities);
+            action.swap_from_picker.sync(
+                options.iter().filter(|option| used.contains(&option.id)).cloned().collect()
+            );
+            action.picker.sync(options);
         }
     }
 
@@ -589,9 +1161,13 @@ impl SuperEdit {
         printer_logicals: &BTreeMap<EntityId, PrinterLogical>,
         price_levels: &BTreeMap<EntityId, PriceLevel>,
     ) {
-        self.filtered_items = Some(items
-            .iter()
-            .filter(|(_, item)| self.applies_to_item(
+        let mut filtered_items = BTreeMap::new();
+        let mut match_results = BTreeMap::new();
+
+        for (id, item) in items {
+            // Only collect per-condition results when asked, normal filtering stays as it was
+            let mut results = Vec::new();
+            let matched = self.evaluate_conditions(
                 item,
                 item_groups,
                 tax_groups,
@@ -602,11 +1178,23 @@ impl SuperEdit {
                 choice_groups,
                 printer_logicals,
                 price_levels,
-            ))
-            .map(|(id, item)| (*id, item.clone()))
-            .collect());
+                self.explain_matches.then_some(&mut results),
+            );
+
+            if matched {
+                filtered_items.insert(*id, item.clone());
+                if self.explain_matches {
+                    match_results.insert(*id, self.describe_results(&results));
+                }
+            }
+        }
+
+        self.filtered_items = Some(filtered_items);
+        self.match_results = match_results;
 
-        let table = match &self.filtered_items {
+        self.refresh_used_entity_ids();
+
+        let mut table = match &self.filtered_items {
             Some(filtered_items) => {
                 ItemsTableView::new(
                     &filtered_items,
@@ -636,39 +1224,59 @@ impl SuperEdit {
                 )
             } 
         };
-        self.preview_table = Some(table);
+        if self.explain_matches {
+            table.show_matched_conditions(&self.match_results);
+        }
+        self.replace_table(table);
+    }
+
+    // "1 Name ✓  2 Item Group ✗", numbered the same as the rows in the If section
+    fn describe_results(&self, results: &[bool]) -> String {
+        self.conditions
+            .iter()
+            .zip(results)
+            .enumerate()
+            .map(|(index, (condition, passed))| {
+                format!("{} {} {}", index + 1, condition.field, if *passed { "✓" } else { "✗" })
+            })
+            .collect::<Vec<_>>()
+            .join("  ")
+    }
+
+    // Cache the entities used by the filtered items, so the swap dropdowns don't rescan on every render
+    fn refresh_used_entity_ids(&mut self) {
+        self.used_entity_ids.clear();
+
+        if let Some(items) = &self.filtered_items {
+            for category in FilterCategory::ALL_ACTIONS.iter() {
+                let used: HashSet<EntityId> = items.values()
+                    .flat_map(|item| category.entity_ids(item))
+                    .collect();
+                self.used_entity_ids.insert(category.clone(), used);
+            }
+        }
     }
 
     pub fn view<'a>(
         &'a self,
         items: &'a BTreeMap<EntityId, Item>,
-        item_groups: &'a BTreeMap<EntityId, ItemGroup>,
-        tax_groups: &'a BTreeMap<EntityId, TaxGroup>,
-        security_levels: &'a BTreeMap<EntityId, SecurityLevel>,
-        revenue_categories: &'a BTreeMap<EntityId, RevenueCategory>,
-        report_categories: &'a BTreeMap<EntityId, ReportCategory>,
-        product_classes: &'a BTreeMap<EntityId, ProductClass>,
-        choice_groups: &'a BTreeMap<EntityId, ChoiceGroup>,
-        printer_logicals: &'a BTreeMap<EntityId, PrinterLogical>,
         price_levels: &'a BTreeMap<EntityId, PriceLevel>,
     ) -> Element<'a, Message> {
         let header = row![
-            text("Super Editor").style(Modern::primary_text()).size(18),
+            text("Super Editor").style(Modern::primary_text()).size(self.text_style.heading()),
         ];
 
         // If section (conditions)
         let if_section = container(
             column![
-                text("Filters").style(Modern::primary_text()).size(16),
+                text("Filters").style(Modern::primary_text()).size(self.text_style.subheading()),
                 // Conditions
                 column(
                     self.conditions
                         .iter()
                         .enumerate()
                         .map(|(index, condition)| {
-                            self.render_condition(index, condition, item_groups, tax_groups,
-                                security_levels, revenue_categories, report_categories,
-                                product_classes, choice_groups, printer_logicals, price_levels)
+                            self.render_condition(index, condition)
                         })
                         .collect::<Vec<_>>()
                 )
@@ -679,8 +1287,18 @@ impl SuperEdit {
                     button("+ Add condition")
                         .on_press(Message::AddCondition)
                         .style(Modern::primary_button())
-                        .padding([5, 15])
+                        .padding([5, 15]),
+                    iced::widget::horizontal_space().width(15),
+                    match self.check_groups() {
+                        Ok(()) => text("( and ) group conditions, grouped ones are combined first")
+                            .size(self.text_style.caption())
+                            .style(Modern::secondary_text()),
+                        Err(e) => text(format!("{}, filtering without groups", e))
+                            .size(self.text_style.caption())
+                            .style(Modern::red_text()),
+                    },
                 ]
+                .align_y(iced::Alignment::Center)
                 .width(Length::Fill)
             ]
             .spacing(15)
@@ -692,21 +1310,55 @@ impl SuperEdit {
         // Then section
         let then_section = container(
             column![
-                text("Editor").style(Modern::primary_text()).size(16),
+                text("Editor").style(Modern::primary_text()).size(self.text_style.subheading()),
                 // Actions
                 column(
                     self.actions
                         .iter()
                         .enumerate()
                         .map(|(index, action)| {
-                            self.render_action(index, action, item_groups, tax_groups,
-                                security_levels, revenue_categories, report_categories,
-                                product_classes, choice_groups, printer_logicals, price_levels) 
+                            self.render_action(index, action, price_levels)
                         })
                         .collect::<Vec<_>>()
                 )
                 .spacing(8),
                 
+                // Apply scope
+                row![
+                    text("Apply to").style(Modern::primary_text()).width(60),
+                    iced::widget::horizontal_space().width(10),
+                    pick_list(
+                        &ApplyScope::ALL[..],
+                        Some(&self.apply_scope),
+                        Message::UpdateApplyScope
+                    )
+                    .style(Modern::pick_list())
+                    .width(150),
+                    iced::widget::horizontal_space().width(10),
+                    match self.apply_scope {
+                        ApplyScope::All => Element::from(horizontal_space().width(150)),
+                        ApplyScope::FirstN => text_input("N", &self.apply_scope_value)
+                            .on_input(Message::UpdateApplyScopeValue)
+                            .style(focus_style::text_input(Modern::inline_text_input()))
+                            .width(150)
+                            .into(),
+                        ApplyScope::RandomPercent => text_input("Percent", &self.apply_scope_value)
+                            .on_input(Message::UpdateApplyScopeValue)
+                            .style(focus_style::text_input(Modern::inline_text_input()))
+                            .width(150)
+                            .into(),
+                        ApplyScope::Selected => text(match self.scope_ids.len() {
+                                0 => "No rows selected yet".to_string(),
+                                count => format!("{} rows", count),
+                            })
+                            .size(self.text_style.caption())
+                            .style(Modern::secondary_text())
+                            .width(150)
+                            .into(),
+                    },
+                ]
+                .align_y(iced::Alignment::Center),
+
                 // Add action button
                 row![
                     button("+ Add action")
@@ -718,6 +1370,24 @@ impl SuperEdit {
                     
                     if self.show_preview {
                         row![
+                            if self.overflow_warnings.is_empty() {
+                                Element::from(horizontal_space().width(0))
+                            } else {
+                                Element::from(
+                                    tooltip(
+                                        text(format!("{} price changes skipped, out of range", self.overflow_warnings.len()))
+                                            .size(self.text_style.caption())
+                                            .style(Modern::red_text()),
+                                        container(text(self.overflow_warnings.join("\n"))).padding(10).style(Modern::danger_tooltip_container()),
+                                        tooltip::Position::Top,
+                                    )
+                                )
+                            },
+                            text(&self.patch_message).size(self.text_style.caption()).style(Modern::secondary_text()),
+                            button("Export Patch")
+                                .on_press(Message::ExportPatch)
+                                .style(Modern::secondary_button())
+                                .padding([8, 20]),
                             button("Cancel Preview")
                                 .on_press(Message::CancelPreview)
                                 .style(Modern::secondary_button())
@@ -729,11 +1399,12 @@ impl SuperEdit {
                         ].spacing(10)
                     } else {
                         row![
+                            text(self.action_error.as_deref().unwrap_or("")).style(Modern::red_text()),
                             button("Preview Changes")
                                 .on_press(Message::PreviewChanges)
                                 .style(Modern::secondary_button())
                                 .padding([8, 20]),
-                        ]
+                        ].spacing(10).align_y(iced::Alignment::Center)
                     }
                 ]
                 .width(Length::Fill)
@@ -750,10 +1421,25 @@ impl SuperEdit {
             column![
                 row![
                     text(if self.show_preview { "Showing Potential Changes" } else { "Matching Items" })
-                        .style(Modern::primary_text()).size(16),
+                        .style(Modern::primary_text()).size(self.text_style.subheading()),
                     horizontal_space(),
-                    text(format!("Showing {} items", filtered_item_count)).style(Modern::primary_text()),
-                ],
+                    if self.show_preview {
+                        text(format!(
+                            "Applying to {} of {} matched items",
+                            self.changed_item_ids.len(),
+                            self.matched_item_count
+                        )).style(Modern::primary_text())
+                    } else {
+                        text(format!("Showing {} items", filtered_item_count)).style(Modern::primary_text())
+                    },
+                    self.preview_page_controls(),
+                    horizontal_space().width(20),
+                    iced::widget::checkbox("Explain matches", self.explain_matches)
+                        .on_toggle(Message::ToggleExplainMatches)
+                        .style(Modern::checkbox()),
+                ]
+                .align_y(iced::Alignment::Center),
+                self.flag_changes_bar(),
                 
                 // Items list   
                 if let Some(table) = &self.preview_table {
@@ -787,19 +1473,79 @@ impl SuperEdit {
             .into()
     }
 
+    // Only shown once a preview needs more than one page
+    fn preview_page_controls(&self) -> Element<'_, Message> {
+        let pages = self.preview_page_count();
+        if !self.show_preview || pages < 2 {
+            return horizontal_space().width(0).into();
+        }
+
+        let first = self.preview_page * self.page_size + 1;
+        let last = ((self.preview_page + 1) * self.page_size).min(self.changed_item_ids.len());
+
+        row![
+            horizontal_space().width(20),
+            text(format!("Showing {}-{}, page {} of {}", first, last, self.preview_page + 1, pages))
+                .style(Modern::secondary_text()),
+            button("Previous")
+                .on_press_maybe((self.preview_page > 0).then_some(Message::PreviousPreviewPage))
+                .style(Modern::system_button()),
+            button("Next")
+                .on_press_maybe((self.preview_page + 1 < pages).then_some(Message::NextPreviewPage))
+                .style(Modern::system_button()),
+        ]
+        .spacing(10)
+        .align_y(iced::Alignment::Center)
+        .into()
+    }
+
+    // Confirmation for a header checkbox, then the Apply / Discard bar for pending flag edits
+    fn flag_changes_bar(&self) -> Element<'_, Message> {
+        if let Some((flag, value, count)) = self.confirm_bulk_flag {
+            return row![
+                text(format!(
+                    "{} {} on {} items?",
+                    if value { "Set" } else { "Clear" },
+                    flag,
+                    count
+                )),
+                button("Confirm")
+                    .on_press(Message::ConfirmBulkFlag)
+                    .style(Modern::primary_button()),
+                button("Cancel")
+                    .on_press(Message::CancelBulkFlag)
+                    .style(Modern::secondary_button()),
+            ]
+            .spacing(10)
+            .padding([5, 0])
+            .align_y(iced::Alignment::Center)
+            .into();
+        }
+
+        if self.pending_flags.is_empty() {
+            return row![].into();
+        }
+
+        row![
+            text(format!("{} flag changes pending", self.pending_flags.len())).style(Modern::secondary_text()),
+            horizontal_space(),
+            button("Discard")
+                .on_press(Message::DiscardFlagChanges)
+                .style(Modern::secondary_button()),
+            button("Apply Flag Changes")
+                .on_press(Message::ApplyFlagChanges)
+                .style(Modern::primary_button()),
+        ]
+        .spacing(10)
+        .padding([5, 0])
+        .align_y(iced::Alignment::Center)
+        .into()
+    }
+
     fn render_condition<'a>(
         &'a self, 
         index: usize, 
         condition: &'a FilterCondition,
-        item_groups: &'a BTreeMap<EntityId, ItemGroup>,
-        tax_groups: &'a BTreeMap<EntityId, TaxGroup>,
-        security_levels: &'a BTreeMap<EntityId, SecurityLevel>,
-        revenue_categories: &'a BTreeMap<EntityId, RevenueCategory>,
-        report_categories: &'a BTreeMap<EntityId, ReportCategory>,
-        product_classes: &'a BTreeMap<EntityId, ProductClass>,
-        choice_groups: &'a BTreeMap<EntityId, ChoiceGroup>,
-        printer_logicals: &'a BTreeMap<EntityId, PrinterLogical>,
-        price_levels: &'a BTreeMap<EntityId, PriceLevel>,
     ) -> Element<'a, Message> {
         let logic_picker = if index == 0 {
             container(
@@ -835,21 +1581,23 @@ impl SuperEdit {
         // Determine if value input should be shown based on operator
         let needs_value_input = !matches!(
             condition.operator,
-            FilterOperator::IsEmpty | FilterOperator::IsNotEmpty
+            FilterOperator::IsEmpty | FilterOperator::IsNotEmpty | FilterOperator::RoutesToInactive
         );
 
         let value_input = if needs_value_input {
             match condition.field {
-                FilterCategory::Name | FilterCategory::Price => {
+                FilterCategory::Name | FilterCategory::Price |
+                FilterCategory::KdsCategory | FilterCategory::KdsDept | FilterCategory::KdsCookTime | FilterCategory::Covers => {
                     text_input(
                         match condition.field {
                             FilterCategory::Price => "Amount",
+                            FilterCategory::KdsCookTime => "Seconds",
                             _ => "Value",
                         },
                         &condition.value
                     )
                     .on_input(move |s| Message::UpdateConditionValue(index, s))
-                    .style(Modern::inline_text_input())
+                    .style(focus_style::text_input(Modern::inline_text_input()))
                     .width(150)
                     .into()
                 }
@@ -867,65 +1615,66 @@ impl SuperEdit {
                                     let to_value = values.get(1).unwrap_or(&"").to_string();
                                     Message::UpdateConditionValue(index, format!("{}-{}", s, to_value))
                                 })
-                                .style(Modern::inline_text_input())
+                                .style(focus_style::text_input(Modern::inline_text_input()))
                                 .width(70),
-                            text(" to ").size(14),
+                            text(" to ").size(self.text_style.body()),
                             text_input("To", &to_value)
                                 .on_input(move |s| {
                                     let values: Vec<&str> = condition.value.split('-').collect();
                                     let from_value = values.get(0).unwrap_or(&"").to_string();
                                     Message::UpdateConditionValue(index, format!("{}-{}", from_value, s))
                                 })
-                                .style(Modern::inline_text_input())
+                                .style(focus_style::text_input(Modern::inline_text_input()))
                                 .width(70),
                         ].into()
                     } else {
                         text_input("ID", &condition.value)
                             .on_input(move |s| Message::UpdateConditionValue(index, s))
-                            .style(Modern::inline_text_input())
+                            .style(focus_style::text_input(Modern::inline_text_input()))
                             .width(150)
                             .into()
                     }
                 }
-                // Entity dropdowns for all entity-based filters
-                FilterCategory::ItemGroup => {
-                    create_condition_entity_dropdown(index, condition, item_groups)
-                }
-                FilterCategory::TaxGroup => {
-                    create_condition_entity_dropdown(index, condition, tax_groups)
-                }
-                FilterCategory::SecurityLevel => {
-                    create_condition_entity_dropdown(index, condition, security_levels)
-                }
-                FilterCategory::RevenueCategory => {
-                    create_condition_entity_dropdown(index, condition, revenue_categories)
-                }
-                FilterCategory::ReportCategory => {
-                    create_condition_entity_dropdown(index, condition, report_categories)
-                }
-                FilterCategory::ProductClass => {
-                    create_condition_entity_dropdown(index, condition, product_classes)
-                }
-                FilterCategory::ChoiceGroup => {
-                    create_condition_entity_dropdown(index, condition, choice_groups)
-                }
-                FilterCategory::PrinterLogical => {
-                    create_condition_entity_dropdown(index, condition, printer_logicals)
-                }
+                // Searchable picker for all entity-based filters
+                FilterCategory::ItemGroup |
+                FilterCategory::TaxGroup |
+                FilterCategory::SecurityLevel |
+                FilterCategory::RevenueCategory |
+                FilterCategory::ReportCategory |
+                FilterCategory::ProductClass |
+                FilterCategory::ChoiceGroup |
+                FilterCategory::PrinterLogical |
                 FilterCategory::PriceLevel => {
-                    create_condition_entity_dropdown(index, condition, price_levels)
+                    condition.picker.view(
+                        "Type to search",
+                        condition.entity_id,
+                        move |id| Message::UpdateConditionEntity(index, id),
+                        move |query| Message::UpdateConditionQuery(index, query),
+                        150.0,
+                        self.text_style,
+                    )
                 }
             }
         } else {
-            // Show disabled/empty input for operators that don't need values
-            text_input("", "")
-                .style(Modern::inline_text_input())
-                .width(150)
-                .into()
+            // Keep the column width, but leave nothing for Tab to stop on
+            horizontal_space().width(150).into()
         };
 
+        // Cycles through no parentheses up to MAX_GROUP_DEPTH of them
+        let group_button = |count: u8, paren: &str, message: Message| {
+            button(
+                text(if count == 0 { paren.to_string() } else { paren.repeat(count as usize) })
+                    .font(self.text_style.data_font())
+            )
+            .on_press(message)
+            .style(if count == 0 { Modern::gray_button() } else { Modern::primary_button() })
+            .width(40)
+        };
+        let open_button = group_button(condition.open_groups, "(", Message::CycleConditionOpen(index));
+        let close_button = group_button(condition.close_groups, ")", Message::CycleConditionClose(index));
+
         let remove_button: Element<Message> = if index != 0 {
-            button(text("×").size(16))
+            button(text("×").size(self.text_style.subheading()))
                 .on_press(Message::RemoveCondition(index))
                 .style(Modern::danger_button())
                 .width(30)
@@ -937,11 +1686,15 @@ impl SuperEdit {
         row![
             logic_picker,
             iced::widget::horizontal_space().width(10),
+            open_button,
+            iced::widget::horizontal_space().width(5),
             field_picker,
             iced::widget::horizontal_space().width(10),
             operator_picker,
             iced::widget::horizontal_space().width(10),
             value_input,
+            iced::widget::horizontal_space().width(5),
+            close_button,
             iced::widget::horizontal_space().width(10),
             remove_button,
         ]
@@ -954,14 +1707,6 @@ impl SuperEdit {
         &'a self, 
         index: usize, 
         action: &'a FilterAction,
-        item_groups: &'a BTreeMap<EntityId, ItemGroup>,
-        tax_groups: &'a BTreeMap<EntityId, TaxGroup>,
-        security_levels: &'a BTreeMap<EntityId, SecurityLevel>,
-        revenue_categories: &'a BTreeMap<EntityId, RevenueCategory>,
-        report_categories: &'a BTreeMap<EntityId, ReportCategory>,
-        product_classes: &'a BTreeMap<EntityId, ProductClass>,
-        choice_groups: &'a BTreeMap<EntityId, ChoiceGroup>,
-        printer_logicals: &'a BTreeMap<EntityId, PrinterLogical>,
         price_levels: &'a BTreeMap<EntityId, PriceLevel>,
     ) -> Element<'a, Message> {
         let category_picker = pick_list(
@@ -997,7 +1742,7 @@ impl SuperEdit {
                 row![
                     text_input("Amount", &action.value)
                         .on_input(move |value| Message::UpdateActionValue(index, value))
-                        .style(Modern::inline_text_input())
+                        .style(focus_style::text_input(Modern::inline_text_input()))
                         .width(100),
                     iced::widget::horizontal_space().width(5),
                     text("at").style(Modern::secondary_text()),
@@ -1013,50 +1758,123 @@ impl SuperEdit {
                             Message::UpdateActionPriceLevel(index, id)
                         }
                     ).style(Modern::pick_list())
-                    .width(100)
-                ].into()
+                    .width(100),
+                    iced::widget::horizontal_space().width(5),
+                    pick_list(
+                        &RoundingKind::ALL[..],
+                        Some(action.rounding_kind),
+                        move |kind| Message::UpdateActionRounding(index, kind)
+                    ).style(Modern::pick_list())
+                    .width(120),
+                    if action.rounding_kind == RoundingKind::None {
+                        Element::from(row![])
+                    } else {
+                        row![
+                            iced::widget::horizontal_space().width(5),
+                            text_input(
+                                if action.rounding_kind == RoundingKind::Nearest { "0.05" } else { ".49, .99" },
+                                &action.rounding_value
+                            )
+                            .on_input(move |value| Message::UpdateActionRoundingValue(index, value))
+                            .style(focus_style::text_input(Modern::inline_text_input()))
+                            .width(80),
+                        ].into()
+                    },
+                ].align_y(iced::Alignment::Center).into()
             }
 
             // Regular entity selections for Add/Remove operations on multi-value fields
-            (FilterCategory::PrinterLogical, ActionOperation::Add | ActionOperation::Remove) => {
-                create_entity_dropdown(index, action, printer_logicals)
-            }
-            (FilterCategory::ChoiceGroup, ActionOperation::Add | ActionOperation::Remove) => {
-                create_entity_dropdown(index, action, choice_groups)
+            (FilterCategory::PrinterLogical | FilterCategory::ChoiceGroup | FilterCategory::PriceLevel,
+            ActionOperation::Add | ActionOperation::Remove) => {
+                action.picker.view(
+                    "Type to search",
+                    action.entity_id,
+                    move |id| Message::UpdateActionEntity(index, id),
+                    move |query| Message::UpdateActionQuery(index, query),
+                    185.0,
+                    self.text_style,
+                )
             }
-            (FilterCategory::PriceLevel, ActionOperation::Add | ActionOperation::Remove) => {
-                create_entity_dropdown(index, action, price_levels)
+
+            // All entity swap operations use a from and a to picker
+            (FilterCategory::ItemGroup |
+            FilterCategory::TaxGroup |
+            FilterCategory::SecurityLevel |
+            FilterCategory::RevenueCategory |
+            FilterCategory::ReportCategory |
+            FilterCategory::ProductClass |
+            FilterCategory::ChoiceGroup |
+            FilterCategory::PrinterLogical |
+            FilterCategory::PriceLevel, ActionOperation::SwapTo) => {
+                let same_entity_warning: Element<'a, Message> = if action.is_same_entity_swap() {
+                    row![
+                        iced::widget::horizontal_space().width(5),
+                        text("Same entity, nothing to swap").size(self.text_style.caption()).style(Modern::red_text()),
+                    ]
+                    .into()
+                } else {
+                    row![].into()
+                };
+
+                row![
+                    action.swap_from_picker.view(
+                        "Swap from",
+                        action.swap_from_id,
+                        move |id| Message::UpdateActionSwapFrom(index, id),
+                        move |query| Message::UpdateActionSwapFromQuery(index, query),
+                        150.0,
+                        self.text_style,
+                    ),
+                    iced::widget::horizontal_space().width(5),
+                    text("to").style(Modern::secondary_text()).center(),
+                    iced::widget::horizontal_space().width(5),
+                    action.picker.view(
+                        "Swap to",
+                        action.entity_id,
+                        move |id| Message::UpdateActionEntity(index, id),
+                        move |query| Message::UpdateActionQuery(index, query),
+                        150.0,
+                        self.text_style,
+                    ),
+                    same_entity_warning,
+                ]
+                .align_y(iced::Alignment::Center)
+                .into()
             }
             
-            // All entity swap operations now use swap dropdowns
-            (FilterCategory::ItemGroup, ActionOperation::SwapTo) => {
-                create_swap_dropdowns(index, action, item_groups, self.filtered_items.as_ref())
-            }
-            (FilterCategory::TaxGroup, ActionOperation::SwapTo) => {
-                create_swap_dropdowns(index, action, tax_groups, self.filtered_items.as_ref())
-            }
-            (FilterCategory::SecurityLevel, ActionOperation::SwapTo) => {
-                create_swap_dropdowns(index, action, security_levels, self.filtered_items.as_ref())
-            }
-            (FilterCategory::RevenueCategory, ActionOperation::SwapTo) => {
-                create_swap_dropdowns(index, action, revenue_categories, self.filtered_items.as_ref())
-            }
-            (FilterCategory::ReportCategory, ActionOperation::SwapTo) => {
-                create_swap_dropdowns(index, action, report_categories, self.filtered_items.as_ref())
-            }
-            (FilterCategory::ProductClass, ActionOperation::SwapTo) => {
-                create_swap_dropdowns(index, action, product_classes, self.filtered_items.as_ref())
-            }
-            (FilterCategory::ChoiceGroup, ActionOperation::SwapTo) => {
-                create_swap_dropdowns(index, action, choice_groups, self.filtered_items.as_ref())
+            // KDS fields and covers are set to a typed value, the numeric ones are checked on preview
+            (FilterCategory::KdsCategory | FilterCategory::KdsDept | FilterCategory::KdsCookTime | FilterCategory::Covers,
+            ActionOperation::SetTo) => {
+                text_input(
+                    match action.category {
+                        FilterCategory::KdsCategory => "Category (blank clears)",
+                        FilterCategory::KdsCookTime => "Seconds",
+                        FilterCategory::Covers => "Covers",
+                        _ => "Department",
+                    },
+                    &action.value
+                )
+                .on_input(move |value| Message::UpdateActionValue(index, value))
+                .style(focus_style::text_input(Modern::inline_text_input()))
+                .width(185)
+                .into()
             }
-            (FilterCategory::PrinterLogical, ActionOperation::SwapTo) => {
-                create_swap_dropdowns(index, action, printer_logicals, self.filtered_items.as_ref())
+
+            (FilterCategory::PriceLevel, ActionOperation::ApplyGroupDefaults) => {
+                text("Adds the item group's default price levels at a zero price")
+                    .size(self.text_style.caption())
+                    .style(Modern::secondary_text())
+                    .into()
             }
-            (FilterCategory::PriceLevel, ActionOperation::SwapTo) => {
-                create_swap_dropdowns(index, action, price_levels, self.filtered_items.as_ref())
+
+            (FilterCategory::PrinterLogical, ActionOperation::CopyFromItem) => {
+                text_input("Item ID", &action.value)
+                    .on_input(move |value| Message::UpdateActionValue(index, value))
+                    .style(focus_style::text_input(Modern::inline_text_input()))
+                    .width(185)
+                    .into()
             }
-            
+
             // This shouldn't happen with current constraints
             _ => {
                 row![
@@ -1066,7 +1884,7 @@ impl SuperEdit {
         };
 
         let remove_button: Element<Message> = if index != 0 {
-            button(text("×").size(16))
+            button(text("×").size(self.text_style.subheading()))
                 .on_press(Message::RemoveAction(index))
                 .style(Modern::danger_button())
                 .width(30)
@@ -1104,37 +1922,95 @@ impl SuperEdit {
         price_levels: &BTreeMap<EntityId, PriceLevel>,
     ) {
         let mut modified_items = items.clone();
-        self.changed_item_ids.clear();
-        
-        // Apply actions to filtered items and track changes
-        for (id, item) in &mut modified_items {
-            if self.applies_to_item(item, item_groups, tax_groups, security_levels,
+
+        let matched_ids: Vec<EntityId> = items.iter()
+            .filter(|(_, item)| self.applies_to_item(item, item_groups, tax_groups, security_levels,
                 revenue_categories, report_categories, product_classes, choice_groups,
-                printer_logicals, price_levels) {
-                
-                // Apply each action
-                for action in &self.actions {
-                    self.apply_action_to_item(item, action);
+                printer_logicals, price_levels))
+            .map(|(id, _)| *id)
+            .collect();
+
+        // Store the sample so Accept commits exactly what was previewed
+        self.matched_item_count = matched_ids.len();
+        self.changed_item_ids = self.select_sample(matched_ids);
+
+        // Swaps of an entity for itself are skipped, if that's all there is nothing changes
+        if self.actions.iter().all(FilterAction::is_same_entity_swap) {
+            self.changed_item_ids.clear();
+        }
+
+        println!("Applying to {} of {} matched items", self.changed_item_ids.len(), self.matched_item_count);
+
+        // Apply each action to the sampled items
+        let mut overflow_warnings = Vec::new();
+        for id in &self.changed_item_ids {
+            if let Some(item) = modified_items.get_mut(id) {
+                for action in self.actions.iter().filter(|action| !action.is_same_entity_swap()) {
+                    if let Err(warning) = self.apply_action_to_item(item, action, items, item_groups) {
+                        println!("{}", warning);
+                        overflow_warnings.push(warning);
+                    }
+                }
+
+                // Keep price math at each price level's precision
+                if let Some(ref mut prices) = item.item_prices {
+                    for item_price in prices.iter_mut() {
+                        if let Some(level) = price_levels.get(&item_price.price_level_id) {
+                            item_price.price = level.round_price(item_price.price);
+                        }
+                    }
                 }
-                
-                self.changed_item_ids.push(*id);
             }
         }
         
-        self.modified_items = Some(modified_items.clone());
-        
-        // Create diff table showing only changed items
-        let items_to_show: BTreeMap<EntityId, Item> = self.changed_item_ids.iter()
+        self.modified_items = Some(modified_items);
+        self.overflow_warnings = overflow_warnings;
+
+        // A new preview starts from its first page
+        self.preview_page = 0;
+        self.show_preview_page(items, item_groups, tax_groups, security_levels,
+            revenue_categories, report_categories, product_classes, choice_groups,
+            printer_logicals, price_levels);
+        self.show_preview = true;
+    }
+
+    fn preview_page_count(&self) -> usize {
+        self.changed_item_ids.len().div_ceil(self.page_size).max(1)
+    }
+
+    // Builds the diff table for one page of the last preview. The changes were worked out
+    // once in preview_changes, paging only slices what was kept.
+    fn show_preview_page(
+        &mut self,
+        items: &BTreeMap<EntityId, Item>,
+        item_groups: &BTreeMap<EntityId, ItemGroup>,
+        tax_groups: &BTreeMap<EntityId, TaxGroup>,
+        security_levels: &BTreeMap<EntityId, SecurityLevel>,
+        revenue_categories: &BTreeMap<EntityId, RevenueCategory>,
+        report_categories: &BTreeMap<EntityId, ReportCategory>,
+        product_classes: &BTreeMap<EntityId, ProductClass>,
+        choice_groups: &BTreeMap<EntityId, ChoiceGroup>,
+        printer_logicals: &BTreeMap<EntityId, PrinterLogical>,
+        price_levels: &BTreeMap<EntityId, PriceLevel>,
+    ) {
+        let Some(modified_items) = &self.modified_items else {
+            return;
+        };
+
+        let start = (self.preview_page * self.page_size).min(self.changed_item_ids.len());
+        let end = (start + self.page_size).min(self.changed_item_ids.len());
+        let page_ids = &self.changed_item_ids[start..end];
+
+        let items_to_show: BTreeMap<EntityId, Item> = page_ids.iter()
             .filter_map(|id| items.get(id).map(|item| (*id, item.clone())))
             .collect();
-        
-        let modified_items_to_show: BTreeMap<EntityId, Item> = self.changed_item_ids.iter()
+
+        let modified_items_to_show: BTreeMap<EntityId, Item> = page_ids.iter()
             .filter_map(|id| modified_items.get(id).map(|item| (*id, item.clone())))
             .collect();
 
-        println!("Items to show: {:?}", items_to_show);
-        println!("Modified Items to show: {:?}", modified_items_to_show);
-        
+        println!("Showing preview page {} of {}, {} items", self.preview_page + 1, self.preview_page_count(), page_ids.len());
+
         let table = ItemsTableView::new_with_diff(
             &items_to_show,
             &modified_items_to_show,
@@ -1148,12 +2024,80 @@ impl SuperEdit {
             printer_logicals,
             price_levels,
         );
-        
-        self.preview_table = Some(table);
-        self.show_preview = true;
+
+        self.replace_table(table);
+    }
+
+    // Same rules as the item editor, a blank number is rejected since there is nothing to set
+    fn validate_actions(&self, items: &BTreeMap<EntityId, Item>) -> Result<(), String> {
+        for action in &self.actions {
+            if action.category == FilterCategory::Price {
+                action.rounding()?;
+            }
+
+            if action.operation == ActionOperation::CopyFromItem {
+                copy_source(items, &action.value)?;
+                continue;
+            }
+
+            let field_name = match action.category {
+                FilterCategory::KdsDept => "KDS Department",
+                FilterCategory::KdsCookTime => "KDS Cook Time",
+                FilterCategory::Covers => "Covers",
+                _ => continue,
+            };
+
+            match action.value.trim().parse::<i32>() {
+                Err(_) => return Err(format!("Invalid {} format", field_name)),
+                Ok(covers) if action.category == FilterCategory::Covers && !(0..=store_fields::MAX_COVERS).contains(&covers) => {
+                    return Err(format!("Covers must be between 0 and {}", store_fields::MAX_COVERS));
+                }
+                Ok(_) => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    // Narrow the matched items down to the chosen apply scope, a fresh random sample is drawn on every preview
+    fn select_sample(&self, matched_ids: Vec<EntityId>) -> Vec<EntityId> {
+        let value = self.apply_scope_value.parse::<usize>().ok();
+
+        match (&self.apply_scope, value) {
+            (ApplyScope::FirstN, Some(n)) => matched_ids.into_iter().take(n).collect(),
+            (ApplyScope::Selected, _) => matched_ids.into_iter().filter(|id| self.scope_ids.contains(id)).collect(),
+            (ApplyScope::RandomPercent, Some(percent)) => {
+                let percent = percent.min(100);
+                let count = (matched_ids.len() * percent).div_ceil(100);
+
+                let seed = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .map(|d| d.as_nanos() as u64)
+                    .unwrap_or(0x2545_F491_4F6C_DD1D);
+                println!("Sampling {}% of matched items with seed {}", percent, seed);
+
+                let mut sample = shuffled(matched_ids, seed);
+                sample.truncate(count);
+                sample.sort();
+                sample
+            }
+            _ => matched_ids,
+        }
     }
 
-    fn apply_action_to_item(&self, item: &mut Item, action: &FilterAction) {
+    /// Applies one action, price math that would overflow a Decimal is skipped and reported
+    fn apply_action_to_item(
+        &self,
+        item: &mut Item,
+        action: &FilterAction,
+        items: &BTreeMap<EntityId, Item>,
+        item_groups: &BTreeMap<EntityId, ItemGroup>,
+    ) -> Result<(), String> {
+        let overflow = |item: &Item| format!(
+            "Item {} ({}): price change skipped, {} {} is out of range",
+            item.id, item.name, action.operation, action.value.trim()
+        );
+
         match (&action.category, &action.operation) {
             // Single entity fields (ItemGroup, TaxGroup, etc.)
             (FilterCategory::ItemGroup, ActionOperation::SwapTo) => {
@@ -1219,6 +2163,11 @@ impl SuperEdit {
                     }
                 }
             }
+            (FilterCategory::PrinterLogical, ActionOperation::CopyFromItem) => {
+                // Primary flags come along, so every item routes like the source
+                let source = copy_source(items, &action.value)?;
+                item.printer_logicals = source.printer_logicals.clone();
+            }
             (FilterCategory::PrinterLogical, ActionOperation::SwapTo) => {
                 if let (Some(from_id), Some(to_id)) = (action.swap_from_id, action.entity_id) {
                     if let Some(ref mut logicals) = item.printer_logicals {
@@ -1238,7 +2187,8 @@ impl SuperEdit {
                 if let Some(entity_id) = action.entity_id {
                     if let Some(ref mut groups) = item.choice_groups {
                         if !groups.iter().any(|(id, _)| *id == entity_id) {
-                            groups.push((entity_id, 0)); // Default sort order 0
+                            let next_sequence = crate::items::next_choice_sequence(groups);
+                            groups.push((entity_id, next_sequence)); // After the groups it already has
                         }
                     } else {
                         item.choice_groups = Some(vec![(entity_id, 0)]);
@@ -1285,6 +2235,11 @@ impl SuperEdit {
                     }
                 }
             }
+            (FilterCategory::PriceLevel, ActionOperation::ApplyGroupDefaults) => {
+                if let Some(group) = item.item_group.and_then(|id| item_groups.get(&id)) {
+                    group.apply_default_price_levels(item);
+                }
+            }
             (FilterCategory::PriceLevel, ActionOperation::SwapTo) => {
                 if let (Some(from_id), Some(to_id)) = (action.swap_from_id, action.entity_id) {
                     if let Some(ref mut levels) = item.price_levels {
@@ -1332,15 +2287,17 @@ impl SuperEdit {
                     if action.price_level == Some(0) || action.price_level.is_none() {
                         // Update default price
                         if let Some(current_price) = item.default_price {
-                            item.default_price = Some(current_price + add_amount);
+                            let new_price = current_price.checked_add(add_amount).ok_or_else(|| overflow(item))?;
+                            item.default_price = Some(new_price);
                         } else {
                             item.default_price = Some(add_amount);
                         }
                     } else if let Some(price_level_id) = action.price_level {
                         // Update specific price level
+                        let warning = overflow(item);
                         if let Some(ref mut prices) = item.item_prices {
                             if let Some(price) = prices.iter_mut().find(|p| p.price_level_id == price_level_id) {
-                                price.price += add_amount;
+                                price.price = price.price.checked_add(add_amount).ok_or(warning)?;
                             } else {
                                 // Add new price if it doesn't exist
                                 prices.push(ItemPrice {
@@ -1362,22 +2319,66 @@ impl SuperEdit {
                     if action.price_level == Some(0) || action.price_level.is_none() {
                         // Update default price
                         if let Some(current_price) = item.default_price {
-                            item.default_price = Some(current_price - sub_amount);
+                            let new_price = current_price.checked_sub(sub_amount).ok_or_else(|| overflow(item))?;
+                            item.default_price = Some(new_price);
                         }
                     } else if let Some(price_level_id) = action.price_level {
                         // Update specific price level
+                        let warning = overflow(item);
                         if let Some(ref mut prices) = item.item_prices {
                             if let Some(price) = prices.iter_mut().find(|p| p.price_level_id == price_level_id) {
-                                price.price -= sub_amount;
+                                price.price = price.price.checked_sub(sub_amount).ok_or(warning)?;
                             }
                         }
                     }
                 }
             }
+
+
+            // KDS fields
+            (FilterCategory::KdsCategory, ActionOperation::SetTo) => {
+                item.kds_category = action.value.trim().to_string();
+            }
+            (FilterCategory::KdsDept, ActionOperation::SetTo) => {
+                if let Ok(dept) = action.value.trim().parse::<i32>() {
+                    item.kds_dept = dept;
+                }
+            }
+            (FilterCategory::KdsCookTime, ActionOperation::SetTo) => {
+                if let Ok(time) = action.value.trim().parse::<i32>() {
+                    item.kds_cooktime = time;
+                }
+            }
+            (FilterCategory::Covers, ActionOperation::SetTo) => {
+                if let Ok(covers) = action.value.trim().parse::<i32>() {
+                    item.covers = covers;
+                }
+            }
             
             // Catch-all for invalid combinations
             _ => {}
         }
+
+        // Rounding runs on the price the action just worked out, before the level's decimal places
+        if action.category == FilterCategory::Price {
+            if let Ok(rounding) = action.rounding() {
+                let warning = overflow(item);
+                let price = match action.price_level {
+                    None | Some(0) => item.default_price.as_mut(),
+                    Some(price_level_id) => item.item_prices
+                        .iter_mut()
+                        .flatten()
+                        .find(|price| price.price_level_id == price_level_id)
+                        .map(|price| &mut price.price),
+                };
+
+                if let Some(price) = price {
+                    *price = rounding.apply(*price).ok_or(warning)?;
+                }
+            }
+        }
+
+        Ok(())
     }
 
     // Helper function to evaluate a single condition
@@ -1491,7 +2492,12 @@ impl SuperEdit {
                 }
             }
             FilterCategory::PrinterLogical => {
-                if condition.entity_id.is_some() {
+                if condition.operator == FilterOperator::RoutesToInactive {
+                    item.printer_logicals
+                        .iter()
+                        .flatten()
+                        .any(|(id, _)| printer_logicals.get(id).is_some_and(|printer| !printer.active))
+                } else if condition.entity_id.is_some() {
                     self.evaluate_multi_entity_by_id(
                         item.printer_logicals.as_ref().map(|v| v.iter().map(|(id, _)| *id).collect()),
                         condition.entity_id,
@@ -1529,6 +2535,18 @@ impl SuperEdit {
                     &condition.value
                 )
             }
+            FilterCategory::KdsCategory => {
+                self.evaluate_string_field(&item.kds_category, &condition.operator, &condition.value)
+            }
+            FilterCategory::KdsDept => {
+                self.evaluate_number_field(item.kds_dept, &condition.operator, &condition.value)
+            }
+            FilterCategory::KdsCookTime => {
+                self.evaluate_number_field(item.kds_cooktime, &condition.operator, &condition.value)
+            }
+            FilterCategory::Covers => {
+                self.evaluate_number_field(item.covers, &condition.operator, &condition.value)
+            }
         }
     }
 
@@ -1546,6 +2564,20 @@ impl SuperEdit {
         }
     }
 
+    fn evaluate_number_field(&self, field_value: i32, operator: &FilterOperator, condition_value: &str) -> bool {
+        let Ok(value) = condition_value.trim().parse::<i32>() else {
+            return false;
+        };
+
+        match operator {
+            FilterOperator::Equals => field_value == value,
+            FilterOperator::NotEquals => field_value != value,
+            FilterOperator::GreaterThan => field_value > value,
+            FilterOperator::LessThan => field_value < value,
+            _ => false,
+        }
+    }
+
     fn evaluate_id_field(&self, id: EntityId, operator: &FilterOperator, condition_value: &str) -> bool {
         match operator {
             FilterOperator::GreaterThan => {
@@ -1817,29 +2849,34 @@ impl SuperEdit {
         choice_groups: &BTreeMap<EntityId, ChoiceGroup>,
         printer_logicals: &BTreeMap<EntityId, PrinterLogical>,
         price_levels: &BTreeMap<EntityId, PriceLevel>,
+    ) -> bool {
+        self.evaluate_conditions(item, item_groups, tax_groups, security_levels, revenue_categories,
+            report_categories, product_classes, choice_groups, printer_logicals, price_levels, None)
+    }
+
+    // Folds the conditions with their And/Or logic. When `record` is given, each
+    // condition's own result is pushed to it in order.
+    fn evaluate_conditions(
+        &self,
+        item: &Item,
+        item_groups: &BTreeMap<EntityId, ItemGroup>,
+        tax_groups: &BTreeMap<EntityId, TaxGroup>,
+        security_levels: &BTreeMap<EntityId, SecurityLevel>,
+        revenue_categories: &BTreeMap<EntityId, RevenueCategory>,
+        report_categories: &BTreeMap<EntityId, ReportCategory>,
+        product_classes: &BTreeMap<EntityId, ProductClass>,
+        choice_groups: &BTreeMap<EntityId, ChoiceGroup>,
+        printer_logicals: &BTreeMap<EntityId, PrinterLogical>,
+        price_levels: &BTreeMap<EntityId, PriceLevel>,
+        mut record: Option<&mut Vec<bool>>,
     ) -> bool {
         if self.conditions.is_empty() {
             return true;
         }
 
-        // Start with the first condition
-        let mut result = self.evaluate_condition(
-            &self.conditions[0],
-            item,
-            item_groups,
-            tax_groups,
-            security_levels,
-            revenue_categories,
-            report_categories,
-            product_classes,
-            choice_groups,
-            printer_logicals,
-            price_levels,
-        );
-
-        // Apply AND/OR logic for subsequent conditions
-        for (index, condition) in self.conditions.iter().enumerate().skip(1) {
-            let condition_result = self.evaluate_condition(
+        let results: Vec<bool> = self.conditions
+            .iter()
+            .map(|condition| self.evaluate_condition(
                 condition,
                 item,
                 item_groups,
@@ -1851,15 +2888,56 @@ impl SuperEdit {
                 choice_groups,
                 printer_logicals,
                 price_levels,
-            );
+            ))
+            .collect();
+
+        if let Some(record) = record.as_deref_mut() {
+            record.extend(results.iter().copied());
+        }
+
+        // Unbalanced parentheses can't be read, fall back to the plain left to right fold
+        if self.check_groups().is_err() {
+            let mut result = results[0];
+            for (condition, condition_result) in self.conditions.iter().zip(&results).skip(1) {
+                match condition.logic {
+                    ConditionLogic::And => result = result && *condition_result,
+                    ConditionLogic::Or => result = result || *condition_result,
+                }
+            }
+            return result;
+        }
+
+        let mut tokens = Vec::new();
+        for (index, (condition, result)) in self.conditions.iter().zip(&results).enumerate() {
+            if index > 0 {
+                tokens.push(GroupToken::Logic(condition.logic.clone()));
+            }
+            tokens.extend(std::iter::repeat(GroupToken::Open).take(condition.open_groups as usize));
+            tokens.push(GroupToken::Result(*result));
+            tokens.extend(std::iter::repeat(GroupToken::Close).take(condition.close_groups as usize));
+        }
+
+        let mut position = 0;
+        fold_groups(&tokens, &mut position)
+    }
+
+    /// Every "(" needs a ")" after it
+    fn check_groups(&self) -> Result<(), String> {
+        let mut depth: i32 = 0;
 
-            match condition.logic {
-                ConditionLogic::And => result = result && condition_result,
-                ConditionLogic::Or => result = result || condition_result,
+        for (index, condition) in self.conditions.iter().enumerate() {
+            depth += condition.open_groups as i32;
+            depth -= condition.close_groups as i32;
+            if depth < 0 {
+                return Err(format!("Condition {} closes a group that was never opened", index + 1));
             }
         }
 
-        result
+        match depth {
+            0 => Ok(()),
+            1 => Err("One group is missing its \")\"".to_string()),
+            open => Err(format!("{} groups are missing their \")\"", open)),
+        }
     }
 
     pub fn fill_table(
@@ -1887,7 +2965,47 @@ impl SuperEdit {
             printer_logicals,
             price_levels,
         );
-        self.preview_table = Some(table);
+        self.replace_table(table);
+
+        // Opening the window is the first chance to fill the pickers
+        self.sync_pickers(item_groups, tax_groups, security_levels, revenue_categories,
+            report_categories, product_classes, choice_groups, printer_logicals, price_levels);
+    }
+}
+
+// Folds one group left to right, the same as a list without parentheses. A "("
+// starts a nested group that is folded first and used as a single result.
+fn fold_groups(tokens: &[GroupToken], position: &mut usize) -> bool {
+    let mut result = group_operand(tokens, position);
+
+    while let Some(GroupToken::Logic(logic)) = tokens.get(*position) {
+        *position += 1;
+        let value = group_operand(tokens, position);
+        result = match logic {
+            ConditionLogic::And => result && value,
+            ConditionLogic::Or => result || value,
+        };
+    }
+
+    result
+}
+
+fn group_operand(tokens: &[GroupToken], position: &mut usize) -> bool {
+    match tokens.get(*position) {
+        Some(GroupToken::Open) => {
+            *position += 1;
+            let result = fold_groups(tokens, position);
+            if let Some(GroupToken::Close) = tokens.get(*position) {
+                *position += 1;
+            }
+            result
+        }
+        Some(GroupToken::Result(result)) => {
+            *position += 1;
+            *result
+        }
+        // check_groups keeps Close and Logic from turning up here
+        _ => false,
     }
 }
 
@@ -1933,7 +3051,7 @@ impl HasName for PriceLevel  {
     fn name (&self) -> &str { &self.name }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 enum FilterCategory {
     Name,
     Id,
@@ -1947,11 +3065,15 @@ enum FilterCategory {
     PrinterLogical,
     ReportCategory,
     Price,
+    KdsCategory,
+    KdsDept,
+    KdsCookTime,
+    Covers,
 }
 
 impl FilterCategory {
     // Categories available for conditions
-    const ALL_CONDITIONS: [FilterCategory; 12] = [
+    const ALL_CONDITIONS: [FilterCategory; 16] = [
         FilterCategory::Name,
         FilterCategory::Id,
         FilterCategory::ItemGroup,
@@ -1964,10 +3086,14 @@ impl FilterCategory {
         FilterCategory::PrinterLogical,
         FilterCategory::ReportCategory,
         FilterCategory::Price,
+        FilterCategory::KdsCategory,
+        FilterCategory::KdsDept,
+        FilterCategory::KdsCookTime,
+        FilterCategory::Covers,
     ];
     
     // Categories available for actions (excludes Name and Id)
-    const ALL_ACTIONS: [FilterCategory; 10] = [
+    const ALL_ACTIONS: [FilterCategory; 14] = [
         FilterCategory::ItemGroup,
         FilterCategory::ProductClass,
         FilterCategory::TaxGroup,
@@ -1978,7 +3104,37 @@ impl FilterCategory {
         FilterCategory::PrinterLogical,
         FilterCategory::ReportCategory,
         FilterCategory::Price,
+        FilterCategory::KdsCategory,
+        FilterCategory::KdsDept,
+        FilterCategory::KdsCookTime,
+        FilterCategory::Covers,
     ];
+
+    // Entity IDs an item references for this category, empty for non-entity categories
+    fn entity_ids(&self, item: &Item) -> Vec<EntityId> {
+        match self {
+            FilterCategory::ItemGroup => item.item_group.into_iter().collect(),
+            FilterCategory::ProductClass => item.product_class.into_iter().collect(),
+            FilterCategory::TaxGroup => item.tax_group.into_iter().collect(),
+            FilterCategory::SecurityLevel => item.security_level.into_iter().collect(),
+            FilterCategory::RevenueCategory => item.revenue_category.into_iter().collect(),
+            FilterCategory::ReportCategory => item.report_category.into_iter().collect(),
+            FilterCategory::PrinterLogical => item.printer_logicals.iter()
+                .flatten()
+                .map(|(id, _)| *id)
+                .collect(),
+            FilterCategory::ChoiceGroup => item.choice_groups.iter()
+                .flatten()
+                .map(|(id, _)| *id)
+                .collect(),
+            FilterCategory::PriceLevel => item.price_levels.iter()
+                .flatten()
+                .copied()
+                .collect(),
+            FilterCategory::Name | FilterCategory::Id | FilterCategory::Price |
+            FilterCategory::KdsCategory | FilterCategory::KdsDept | FilterCategory::KdsCookTime | FilterCategory::Covers => vec![],
+        }
+    }
 }
 
 impl std::fmt::Display for FilterCategory {
@@ -1999,189 +3155,234 @@ impl std::fmt::Display for FilterCategory {
                 FilterCategory::PrinterLogical => "Printer Logical",
                 FilterCategory::ReportCategory => "Report Category",
                 FilterCategory::Price => "Price",
+                FilterCategory::KdsCategory => "KDS Category",
+                FilterCategory::KdsDept => "KDS Department",
+                FilterCategory::KdsCookTime => "KDS Cook Time",
+                FilterCategory::Covers => "Covers",
             }
         )
     }
 }
 
-// Helper function to create entity dropdown for conditions
-fn create_condition_entity_dropdown<'a, T: HasName + Clone>(
-    index: usize,
-    condition: &FilterCondition,
-    entities: &'a BTreeMap<EntityId, T>
-) -> Element<'a, Message> {
-    let options: Vec<(EntityId, String)> = entities.iter()
-        .map(|(id, entity)| (*id, entity.name().to_string()))
-        .collect();
-    
-    let selected_name = condition.entity_id
-        .and_then(|id| entities.get(&id))
-        .map(|e| e.name().to_string());
-    
-    row![
-        pick_list(
-            options.iter().map(|(_, name)| name.clone()).collect::<Vec<_>>(),
-            selected_name,
-            move |name| {
-                let id = options.iter()
-                    .find(|(_, n)| n == &name)
-                    .map(|(id, _)| *id)
-                    .unwrap_or(0);
-                Message::UpdateConditionEntity(index, id)
-            }
-        )
-        .style(Modern::pick_list())
-        .width(150)
-    ].into()
+// The item a "Copy from item #" action takes its printers from
+fn copy_source<'a>(items: &'a BTreeMap<EntityId, Item>, value: &str) -> Result<&'a Item, String> {
+    let id = value.trim().parse::<EntityId>()
+        .map_err(|_| "Enter the ID of the item to copy printers from".to_string())?;
+    items.get(&id).ok_or_else(|| format!("No item with ID {} to copy printers from", id))
 }
 
-// Helper function to create swap from/to dropdowns
-fn create_swap_dropdowns<'a, T: HasName + Clone>(
-    index: usize,
-    action: &FilterAction,
-    all_entities: &'a BTreeMap<EntityId, T>,
-    filtered_items: Option<&BTreeMap<EntityId, Item>>,
-) -> Element<'a, Message> {
-    // Get entities that appear in filtered items
-    let mut used_entity_ids = std::collections::HashSet::new();
-    
-    if let Some(items) = filtered_items {
-        for item in items.values() {
-            match std::any::type_name::<T>() {
-                name if name.contains("ItemGroup") => {
-                    if let Some(id) = item.item_group {
-                        used_entity_ids.insert(id);
-                    }
-                }
-                name if name.contains("ProductClass") => {
-                    if let Some(id) = item.product_class {
-                        used_entity_ids.insert(id);
-                    }
-                }
-                name if name.contains("TaxGroup") => {
-                    if let Some(id) = item.tax_group {
-                        used_entity_ids.insert(id);
-                    }
-                }
-                name if name.contains("SecurityLevel") => {
-                    if let Some(id) = item.security_level {
-                        used_entity_ids.insert(id);
-                    }
-                }
-                name if name.contains("RevenueCategory") => {
-                    if let Some(id) = item.revenue_category {
-                        used_entity_ids.insert(id);
-                    }
-                }
-                name if name.contains("ReportCategory") => {
-                    if let Some(id) = item.report_category {
-                        used_entity_ids.insert(id);
-                    }
-                }
-                name if name.contains("PrinterLogical") => {
-                    if let Some(logicals) = &item.printer_logicals {
-                        for (id, _) in logicals {
-                            used_entity_ids.insert(*id);
-                        }
-                    }
-                }
-                name if name.contains("ChoiceGroup") => {
-                    if let Some(groups) = &item.choice_groups {
-                        for (id, _) in groups {
-                            used_entity_ids.insert(*id);
-                        }
-                    }
-                }
-                name if name.contains("PriceLevel") => {
-                    if let Some(levels) = &item.price_levels {
-                        for id in levels {
-                            used_entity_ids.insert(*id);
-                        }
-                    }
-                }
-                _ => {}
-            }
-        }
+// Fisher-Yates shuffle driven by xorshift64, so a sample can be reproduced from its seed
+fn shuffled(mut ids: Vec<EntityId>, seed: u64) -> Vec<EntityId> {
+    let mut state = seed.max(1);
+
+    for i in (1..ids.len()).rev() {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        let j = (state % (i as u64 + 1)) as usize;
+        ids.swap(i, j);
     }
-    
-    // Create options for swap from (only entities used in filtered items)
-    let swap_from_options: Vec<(EntityId, String)> = all_entities.iter()
-        .filter(|(id, _)| used_entity_ids.contains(id))
-        .map(|(id, entity)| (*id, entity.name().to_string()))
-        .collect();
-    
-    let swap_from_selected = action.swap_from_id
-        .and_then(|id| all_entities.get(&id))
-        .map(|e| e.name().to_string());
-    
-    // Create options for swap to (all available entities)
-    let swap_to_options: Vec<(EntityId, String)> = all_entities.iter()
-        .map(|(id, entity)| (*id, entity.name().to_string()))
-        .collect();
-    
-    let swap_to_selected = action.entity_id
-        .and_then(|id| all_entities.get(&id))
-        .map(|e| e.name().to_string());
-    
-    row![
-        pick_list(
-            swap_from_options.iter().map(|(_, name)| name.clone()).collect::<Vec<_>>(),
-            swap_from_selected,
-            move |name| {
-                let id = swap_from_options.iter()
-                    .find(|(_, n)| n == &name)
-                    .map(|(id, _)| *id)
-                    .unwrap_or(0);
-                Message::UpdateActionSwapFrom(index, id)
-            }
-        )
-        .style(Modern::pick_list())
-        .width(150),
-        iced::widget::horizontal_space().width(5),
-        text("to").style(Modern::secondary_text()).center(),
-        iced::widget::horizontal_space().width(5),
-        pick_list(
-            swap_to_options.iter().map(|(_, name)| name.clone()).collect::<Vec<_>>(),
-            swap_to_selected,
-            move |name| {
-                let id = swap_to_options.iter()
-                    .find(|(_, n)| n == &name)
-                    .map(|(id, _)| *id)
-                    .unwrap_or(0);
-                Message::UpdateActionEntity(index, id)
-            }
-        )
-        .style(Modern::pick_list())
-        .width(150)
-    ].into()
+
+    ids
 }
 
-fn create_entity_dropdown<'a, T: HasName + Clone>(
-    index: usize,
-    action: &FilterAction,
-    entities: &'a BTreeMap<EntityId, T>
-) -> Element<'a, Message> {
-    let options: Vec<(EntityId, String)> = entities.iter()
-        .map(|(id, entity)| (*id, entity.name().to_string()))
-        .collect();
-    
-    let selected_name = action.entity_id
-        .and_then(|id| entities.get(&id))
-        .map(|e| e.name().to_string());
-    
-    row![
-        pick_list(
-            options.iter().map(|(_, name)| name.clone()).collect::<Vec<_>>(),
-            selected_name,
-            move |name| {
-                let id = options.iter()
-                    .find(|(_, n)| n == &name)
-                    .map(|(id, _)| *id)
-                    .unwrap_or(0);
-                Message::UpdateActionEntity(index, id)
-            }
-        )
-        .style(Modern::pick_list())
-        .width(185)
-    ].into()
-}
\ No newline at end of file
+// Picker options for an entity category, empty for Name, Price, ID and the KDS fields
+fn category_options(
+    category: &FilterCategory,
+    item_groups: &BTreeMap<EntityId, ItemGroup>,
+    tax_groups: &BTreeMap<EntityId, TaxGroup>,
+    security_levels: &BTreeMap<EntityId, SecurityLevel>,
+    revenue_categories: &BTreeMap<EntityId, RevenueCategory>,
+    report_categories: &BTreeMap<EntityId, ReportCategory>,
+    product_classes: &BTreeMap<EntityId, ProductClass>,
+    choice_groups: &BTreeMap<EntityId, ChoiceGroup>,
+    printer_logicals: &BTreeMap<EntityId, PrinterLogical>,
+    price_levels: &BTreeMap<EntityId, PriceLevel>,
+) -> Vec<EntityOption> {
+    match category {
+        FilterCategory::ItemGroup => entity_component::entity_options(item_groups.values()),
+        FilterCategory::TaxGroup => entity_component::entity_options(tax_groups.values()),
+        FilterCategory::SecurityLevel => entity_component::entity_options(security_levels.values()),
+        FilterCategory::RevenueCategory => entity_component::entity_options(revenue_categories.values()),
+        FilterCategory::ReportCategory => entity_component::entity_options(report_categories.values()),
+        FilterCategory::ProductClass => entity_component::entity_options(product_classes.values()),
+        FilterCategory::ChoiceGroup => entity_component::entity_options(choice_groups.values()),
+        FilterCategory::PrinterLogical => entity_component::entity_options(printer_logicals.values()),
+        FilterCategory::PriceLevel => entity_component::entity_options(price_levels.values()),
+        FilterCategory::Name | FilterCategory::Price | FilterCategory::Id |
+        FilterCategory::KdsCategory | FilterCategory::KdsDept | FilterCategory::KdsCookTime | FilterCategory::Covers => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entity_component::Entity;
+
+    fn option_ids(picker: &EntityPicker) -> Vec<EntityId> {
+        picker.options().iter().map(|option| option.id).collect()
+    }
+
+    fn entities<T: Entity>(label: &str) -> BTreeMap<EntityId, T> {
+        (1..=3)
+            .map(|id| (id, T::default_new().with_id(id).with_name(format!("{} {}", label, id))))
+            .collect()
+    }
+
+    #[test]
+    fn swap_from_lists_only_entities_the_filtered_items_use_for_that_category() {
+        let item_groups = entities::<ItemGroup>("Group");
+        let tax_groups = entities::<TaxGroup>("Tax");
+        let security_levels = entities::<SecurityLevel>("Security");
+        let revenue_categories = entities::<RevenueCategory>("Revenue");
+        let report_categories = entities::<ReportCategory>("Report");
+        let product_classes = entities::<ProductClass>("Class");
+        let choice_groups = entities::<ChoiceGroup>("Choices");
+        let printer_logicals = entities::<PrinterLogical>("Printer");
+        let price_levels = entities::<PriceLevel>("Level");
+
+        // Every category uses a different part of the three entities
+        let cola = Item {
+            id: 1,
+            name: "Cola".to_string(),
+            item_group: Some(1),
+            tax_group: Some(2),
+            security_level: Some(3),
+            revenue_category: Some(1),
+            report_category: Some(2),
+            product_class: Some(3),
+            choice_groups: Some(vec![(1, 0), (3, 1)]),
+            printer_logicals: Some(vec![(2, true)]),
+            price_levels: Some(vec![1]),
+            ..Item::default()
+        };
+        let tea = Item {
+            id: 2,
+            name: "Tea".to_string(),
+            item_group: Some(2),
+            tax_group: Some(2),
+            printer_logicals: Some(vec![(3, false)]),
+            price_levels: Some(vec![1, 2]),
+            ..Item::default()
+        };
+        let expected = [
+            (FilterCategory::ItemGroup, vec![1, 2]),
+            (FilterCategory::TaxGroup, vec![2]),
+            (FilterCategory::SecurityLevel, vec![3]),
+            (FilterCategory::RevenueCategory, vec![1]),
+            (FilterCategory::ReportCategory, vec![2]),
+            (FilterCategory::ProductClass, vec![3]),
+            (FilterCategory::ChoiceGroup, vec![1, 3]),
+            (FilterCategory::PrinterLogical, vec![2, 3]),
+            (FilterCategory::PriceLevel, vec![1, 2]),
+        ];
+
+        let mut editor = SuperEdit::new();
+        editor.filtered_items = Some(BTreeMap::from([(1, cola), (2, tea)]));
+        editor.refresh_used_entity_ids();
+        let template = editor.actions[0].clone();
+        editor.actions = expected
+            .iter()
+            .map(|(category, _)| FilterAction { category: category.clone(), ..template.clone() })
+            .collect();
+
+        editor.sync_pickers(&item_groups, &tax_groups, &security_levels, &revenue_categories, &report_categories,
+            &product_classes, &choice_groups, &printer_logicals, &price_levels);
+
+        // Each row follows its own category, swapping to can pick any entity
+        for (action, (category, used)) in editor.actions.iter().zip(&expected) {
+            assert_eq!(&option_ids(&action.swap_from_picker), used, "swap from {:?}", category);
+            assert_eq!(option_ids(&action.picker), vec![1, 2, 3], "swap to {:?}", category);
+        }
+    }
+
+    fn price_action(editor: &SuperEdit, operation: ActionOperation, value: &str) -> FilterAction {
+        let mut action = editor.actions[0].clone();
+        action.category = FilterCategory::Price;
+        action.operation = operation;
+        action.value = value.to_string();
+        action
+    }
+
+    fn priced_item(price: Decimal) -> Item {
+        Item {
+            id: 1,
+            name: "Cola".to_string(),
+            default_price: Some(price),
+            item_prices: Some(vec![ItemPrice { price_level_id: 2, price }]),
+            ..Item::default()
+        }
+    }
+
+    #[test]
+    fn price_changes_that_overflow_are_skipped() {
+        let editor = SuperEdit::new();
+        let no_items = BTreeMap::new();
+        let no_groups = BTreeMap::new();
+
+        let mut item = priced_item(Decimal::new(250, 2));
+        let add = price_action(&editor, ActionOperation::AddToPrice, "1");
+        assert!(editor.apply_action_to_item(&mut item, &add, &no_items, &no_groups).is_ok());
+        assert_eq!(item.default_price, Some(Decimal::new(350, 2)));
+
+        let mut item = priced_item(Decimal::MAX);
+        assert!(editor.apply_action_to_item(&mut item, &add, &no_items, &no_groups).is_err());
+        assert_eq!(item.default_price, Some(Decimal::MAX));
+
+        let mut at_level = add.clone();
+        at_level.price_level = Some(2);
+        assert!(editor.apply_action_to_item(&mut item, &at_level, &no_items, &no_groups).is_err());
+        assert_eq!(item.item_prices.as_ref().unwrap()[0].price, Decimal::MAX);
+
+        let mut item = priced_item(Decimal::MIN);
+        let subtract = price_action(&editor, ActionOperation::SubtractFromPrice, "1");
+        assert!(editor.apply_action_to_item(&mut item, &subtract, &no_items, &no_groups).is_err());
+        assert_eq!(item.default_price, Some(Decimal::MIN));
+    }
+
+    #[test]
+    fn a_huge_rounding_multiplier_is_skipped_instead_of_panicking() {
+        let editor = SuperEdit::new();
+        let mut action = price_action(&editor, ActionOperation::AddToPrice, "0");
+        // Rounds the price up to 8 steps, which is past the largest Decimal
+        action.rounding_kind = RoundingKind::Nearest;
+        action.rounding_value = "10000000000000000000000000000".to_string();
+
+        let mut item = priced_item(Decimal::MAX);
+        let result = editor.apply_action_to_item(&mut item, &action, &BTreeMap::new(), &BTreeMap::new());
+
+        assert!(result.unwrap_err().contains("out of range"));
+        assert_eq!(item.default_price, Some(Decimal::MAX));
+    }
+
+    #[test]
+    fn swapping_an_entity_for_itself_changes_no_items() {
+        let item_groups: BTreeMap<EntityId, ItemGroup> = (1..=2)
+            .map(|id| (id, ItemGroup { id, name: format!("Group {}", id), ..ItemGroup::default() }))
+            .collect();
+        let items: BTreeMap<EntityId, Item> = (1..=2)
+            .map(|id| (id, Item { id, name: format!("Item {}", id), item_group: Some(1), ..Item::default() }))
+            .collect();
+        let empty = BTreeMap::new();
+
+        let mut editor = SuperEdit::new();
+        editor.actions[0].swap_from_id = Some(1);
+        editor.actions[0].entity_id = Some(1);
+        editor.preview_changes(&items, &item_groups, &BTreeMap::new(), &BTreeMap::new(), &BTreeMap::new(),
+            &BTreeMap::new(), &BTreeMap::new(), &BTreeMap::new(), &BTreeMap::new(), &empty);
+
+        assert_eq!(editor.matched_item_count, 2);
+        assert!(editor.changed_item_ids.is_empty());
+
+        // A real swap next to it still goes through
+        let mut real_swap = editor.actions[0].clone();
+        real_swap.entity_id = Some(2);
+        editor.actions.push(real_swap);
+        editor.preview_changes(&items, &item_groups, &BTreeMap::new(), &BTreeMap::new(), &BTreeMap::new(),
+            &BTreeMap::new(), &BTreeMap::new(), &BTreeMap::new(), &BTreeMap::new(), &empty);
+
+        assert_eq!(editor.changed_item_ids, vec![1, 2]);
+        let modified = editor.modified_items.as_ref().unwrap();
+        assert!(modified.values().all(|item| item.item_group == Some(2)));
+    }
+}
@@ -1,7 +1,9 @@
-use crate::data_types::{ EntityId, ValidationError };
+use crate::data_types::{ EntityId, DRAFT_ID, ValidationError };
 use crate::Action;
 use crate::entity_component::{self, Entity, EditState as BaseEditState};
 use crate::icon;
+use crate::items::Item;
+use crate::text_style::TextStyle;
 use iced_modern_theme::Modern;
 use serde::{Serialize, Deserialize};
 use iced::{Element, Length};
@@ -18,8 +20,13 @@ pub enum Message {
     SaveAll(EntityId, TaxGroupEditState),
     UpdateName(EntityId, String),
     UpdateTaxRate(EntityId, String),
+    UpdateExportKey(EntityId, String),
     CreateNew,
     CancelEdit(EntityId),
+    SetTaxIncluded(EntityId, bool),
+    ShowUncoveredItems,
+    AssignDefaultToUncovered,
+    ListScrolled(scrollable::Viewport),
 }
 
 #[derive(Debug, Clone)]
@@ -30,8 +37,13 @@ pub enum Operation {
     SaveAll(EntityId, TaxGroupEditState),
     UpdateName(EntityId, String),
     UpdateTaxRate(EntityId, String),
+    UpdateExportKey(EntityId, String),
     CreateNew,
     CancelEdit(EntityId),
+    SetTaxIncluded(EntityId, bool),
+    ShowUncoveredItems,
+    AssignDefaultToUncovered,
+    ListScrolled(scrollable::Viewport),
 }
 
 #[derive(Default, Debug, Clone)]
@@ -40,6 +52,8 @@ pub struct TaxGroupEditState {
     pub rate: String,
     pub original_rate: String,
     pub rate_validation_error: Option<String>,
+    pub export_key: String,
+    pub original_export_key: String,
 }
 
 impl TaxGroupEditState {
@@ -49,6 +63,8 @@ impl TaxGroupEditState {
             rate: tax_group.rate_percentage().to_string(),
             original_rate: tax_group.rate_percentage().to_string(),
             rate_validation_error: None,
+            export_key: tax_group.export_key.clone(),
+            original_export_key: tax_group.export_key.clone(),
         }
     }
 
@@ -56,6 +72,7 @@ impl TaxGroupEditState {
         self.base.reset();
         self.rate = self.original_rate.clone();
         self.rate_validation_error = None;
+        self.export_key = self.original_export_key.clone();
     }
  
     pub fn validate(&self) -> Result<(), ValidationError> {
@@ -98,6 +115,12 @@ pub struct TaxGroup {
     pub id: EntityId,
     pub name: String,
     pub rate: Decimal, // Stored as decimal (e.g., 0.08 for 8%)
+    #[serde(default)]
+    pub export_key: String, // Name the exports use, filled from the name when older files load
+    #[serde(default)]
+    pub created_at: Option<String>,
+    #[serde(default)]
+    pub updated_at: Option<String>,
 }
 
 impl std::fmt::Display for TaxGroup {
@@ -109,9 +132,12 @@ impl std::fmt::Display for TaxGroup {
 impl Default for TaxGroup {
     fn default() -> Self {
         Self {
-            id: -1,
+            id: DRAFT_ID,
             name: String::new(),
             rate: Decimal::ZERO,
+            export_key: String::new(),
+            created_at: None,
+            updated_at: None,
         }
     }
 }
@@ -140,6 +166,14 @@ impl Entity for TaxGroup {
     fn default_new() -> Self {
         Self::default()
     }
+
+    fn created_at(&self) -> Option<&str> {
+        self.created_at.as_deref()
+    }
+
+    fn updated_at(&self) -> Option<&str> {
+        self.updated_at.as_deref()
+    }
 }
 
 impl TaxGroup {
@@ -183,8 +217,104 @@ impl TaxGroup {
     }
 }
 
+/// How many items in a tax group include tax in their price and how many don't
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TaxIncludedCounts {
+    pub included: usize,
+    pub excluded: usize,
+}
+
+impl TaxIncludedCounts {
+    pub fn is_mixed(&self) -> bool {
+        self.included > 0 && self.excluded > 0
+    }
+}
+
+/// Tax groups whose items disagree on tax_included, which is almost always a setup mistake
+pub fn tax_included_mismatches(items: &BTreeMap<EntityId, Item>) -> BTreeMap<EntityId, TaxIncludedCounts> {
+    let mut counts: BTreeMap<EntityId, TaxIncludedCounts> = BTreeMap::new();
+
+    for item in items.values() {
+        let Some(tax_group) = item.tax_group else {
+            continue;
+        };
+        let entry = counts.entry(tax_group).or_default();
+        if item.tax_included {
+            entry.included += 1;
+        } else {
+            entry.excluded += 1;
+        }
+    }
+
+    counts.retain(|_, counts| counts.is_mixed());
+    counts
+}
+
+/// Sets tax_included on every item in the tax group, returning how many items changed
+pub fn set_tax_included(
+    items: &mut BTreeMap<EntityId, Item>,
+    tax_group_id: EntityId,
+    included: bool,
+    updated_at: &str,
+) -> usize {
+    let mut changed = 0;
+
+    for item in items.values_mut() {
+        if item.tax_group == Some(tax_group_id) && item.tax_included != included {
+            item.tax_included = included;
+            item.updated_at = Some(updated_at.to_string());
+            changed += 1;
+        }
+    }
+
+    changed
+}
+
+/// Gives the tax group to each of the items that has none, returning how many items changed
+pub fn assign_to_uncovered<'a>(
+    items: impl IntoIterator<Item = &'a mut Item>,
+    tax_group_id: EntityId,
+    updated_at: &str,
+) -> usize {
+    let mut changed = 0;
+
+    for item in items {
+        if item.tax_group.is_none() {
+            item.tax_group = Some(tax_group_id);
+            item.updated_at = Some(updated_at.to_string());
+            changed += 1;
+        }
+    }
+
+    changed
+}
+
+/// How many items have a tax group, out of all items
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TaxCoverage {
+    pub covered: usize,
+    pub total: usize,
+}
+
+impl TaxCoverage {
+    pub fn of(items: &BTreeMap<EntityId, Item>) -> Self {
+        Self {
+            covered: items.values().filter(|item| item.tax_group.is_some()).count(),
+            total: items.len(),
+        }
+    }
+
+    pub fn uncovered(&self) -> usize {
+        self.total - self.covered
+    }
+}
+
+// Title of the screen, and the id of its scrollable list
+pub const LIST_ID: &str = "Tax Groups";
+
 pub fn update(
     message: Message,
+    groups: &BTreeMap<EntityId, TaxGroup>,
 ) -> Action<Operation, Message> {
     match message {
         Message::RequestDelete(id) => {
@@ -198,12 +328,23 @@ pub fn update(
         },
         Message::UpdateTaxRate(id, rate) => {
             Action::operation(Operation::UpdateTaxRate(id, rate))
+        }
+        Message::UpdateExportKey(id, key) => {
+            Action::operation(Operation::UpdateExportKey(id, key))
         },
         Message::CreateNew => {
             Action::operation(Operation::CreateNew)
+                .with_task(entity_component::reveal_new_row(LIST_ID, groups))
         },
         Message::SaveAll(id, edit_state) => {
-            Action::operation(Operation::SaveAll(id, edit_state))
+            let rate_valid = edit_state.rate.parse::<Decimal>()
+                .is_ok_and(|rate| (Decimal::ZERO..=Decimal::from(100)).contains(&rate));
+            let focus = if rate_valid {
+                entity_component::focus_duplicate_name(LIST_ID, groups, id, &edit_state.base.name)
+            } else {
+                text_input::focus(entity_component::field_input_id(LIST_ID, id, "rate"))
+            };
+            Action::operation(Operation::SaveAll(id, edit_state)).with_task(focus)
         }
         Message::UpdateName(id, new_name) => {
             Action::operation(Operation::UpdateName(id, new_name))
@@ -211,17 +352,34 @@ pub fn update(
         Message::CancelEdit(id) => {
             Action::operation(Operation::CancelEdit(id))
         }
+        Message::SetTaxIncluded(id, included) => {
+            Action::operation(Operation::SetTaxIncluded(id, included))
+        }
+        Message::ShowUncoveredItems => {
+            Action::operation(Operation::ShowUncoveredItems)
+        }
+        Message::AssignDefaultToUncovered => {
+            Action::operation(Operation::AssignDefaultToUncovered)
+        }
+        Message::ListScrolled(viewport) => {
+            Action::operation(Operation::ListScrolled(viewport))
+        }
     }
 }
 
 pub fn view<'a>(
     all_groups: &'a BTreeMap<EntityId, TaxGroup>,
     edit_states: &'a Vec<TaxGroupEditState>,
+    mismatches: &BTreeMap<EntityId, TaxIncludedCounts>,
+    coverage: TaxCoverage,
+    default_group: Option<&'a TaxGroup>,
+    text_style: TextStyle,
 ) -> Element<'a, Message> {
     let title_row = entity_component::render_title_row(
         "Tax groups", 
         Message::CreateNew,
-        605.0 // view width
+        605.0, // view width
+        text_style,
     );
 
     // Custom header row for columns including range fields
@@ -238,19 +396,47 @@ pub fn view<'a>(
         column(
             all_groups
                 .values()
-                .map(|group| 
-                    row![
-                        render_tax_group_row(group, edit_states)
+                .map(|group| {
+                    let mismatch = mismatches.get(&group.id).copied();
+                    column![
+                        render_tax_group_row(group, edit_states, text_style),
+                        match mismatch {
+                            Some(counts) => render_mismatch(group.id, counts, text_style),
+                            None => column![].into(),
+                        },
                     ]
                     .padding(5)
                     .into()
-                )
+                })
                 .collect::<Vec<_>>()
         )
-    ).height(Length::Fill);
+    )
+    .id(scrollable::Id::new(LIST_ID))
+    .on_scroll(Message::ListScrolled)
+    .height(Length::Fill);
+
+    let mismatch_summary = if mismatches.is_empty() {
+        column![]
+    } else {
+        column![
+            text(format!(
+                "{} tax groups have items that disagree on whether tax is included",
+                mismatches.len()
+            ))
+            .size(text_style.caption())
+            .style(Modern::red_text())
+        ]
+        .padding([0, 15])
+    };
 
     // Combine all elements
-    let all_content = column![title_row, header_row, groups_list];
+    let all_content = column![
+        title_row,
+        render_coverage(coverage, default_group, text_style),
+        mismatch_summary,
+        header_row,
+        groups_list,
+    ];
 
     column![
         container(all_content)
@@ -262,7 +448,8 @@ pub fn view<'a>(
 
 fn render_tax_group_row<'a>(
     tax_group: &'a TaxGroup,
-    edit_states: &'a Vec<TaxGroupEditState>
+    edit_states: &'a Vec<TaxGroupEditState>,
+    text_style: TextStyle,
 ) -> Element<'a, Message> {
     // Find edit state for this tax_group if it exists
     let edit_state = edit_states.iter()
@@ -302,13 +489,14 @@ fn render_tax_group_row<'a>(
                 tooltip::Position::Top,
             ).into()
         } else {
-            input.into()
+            entity_component::with_timestamps(input, tax_group, text_style)
         }
     };
 
     // Name input with validation
     let name_input: Element<'_, Message> = {
         let input = text_input("Tax group Name", &display_name)
+            .id(entity_component::name_input_id(LIST_ID, tax_group.id))
             .on_input_maybe(
                 if editing {
                     Some(|name| Message::UpdateName(tax_group.id, name))
@@ -332,6 +520,7 @@ fn render_tax_group_row<'a>(
 
     let rate_input: Element<'_, Message> = {
         let input = text_input("Tax Rate", &tax_rate)
+            .id(entity_component::field_input_id(LIST_ID, tax_group.id, "rate"))
             .on_input_maybe(
                 if editing {
                     Some( |a_tax_rate| Message::UpdateTaxRate(tax_group.id, a_tax_rate) )
@@ -353,6 +542,18 @@ fn render_tax_group_row<'a>(
         }
     };
 
+    // Only shown while editing, like the printer logical rows
+    let export_key_input: Element<'_, Message> = match edit_state {
+        Some(state) => entity_component::export_key_input(
+            LIST_ID,
+            tax_group.id,
+            &state.export_key,
+            move |key| Message::UpdateExportKey(tax_group.id, key),
+            text_style,
+        ),
+        None => row![].into(),
+    };
+
     // Action buttons
     let action_row = row![
         button(if editing { icon::save().size(14) } else { icon::edit().size(14) })
@@ -386,10 +587,92 @@ fn render_tax_group_row<'a>(
         id_input,
         name_input,
         rate_input,
+        export_key_input,
         iced::widget::horizontal_space().width(5),
         action_row,
     ]
     .align_y(iced::Alignment::Center)
-    .width(Length::Fixed(595.0))
+    .width(Length::Fixed(if editing { 715.0 } else { 595.0 }))
+    .into()
+}
+
+// Warning under a tax group whose items disagree on tax_included, with the fix for either way
+fn render_mismatch<'a>(tax_group_id: EntityId, counts: TaxIncludedCounts, text_style: TextStyle) -> Element<'a, Message> {
+    row![
+        iced::widget::horizontal_space().width(8),
+        text(format!(
+            "{} items include tax in the price and {} don't",
+            counts.included, counts.excluded
+        ))
+        .size(text_style.caption())
+        .style(Modern::red_text()),
+        iced::widget::horizontal_space().width(10),
+        button(text("All include tax").size(text_style.caption()))
+            .on_press(Message::SetTaxIncluded(tax_group_id, true))
+            .style(Modern::secondary_button()),
+        button(text("None include tax").size(text_style.caption()))
+            .on_press(Message::SetTaxIncluded(tax_group_id, false))
+            .style(Modern::secondary_button()),
+    ]
+    .spacing(5)
+    .padding([5, 0])
+    .align_y(iced::Alignment::Center)
     .into()
-}
\ No newline at end of file
+}
+
+// "1,180 of 1,203 items have a tax group", with a way to the uncovered items and to fix them
+fn render_coverage<'a>(coverage: TaxCoverage, default_group: Option<&'a TaxGroup>, text_style: TextStyle) -> Element<'a, Message> {
+    let uncovered = coverage.uncovered();
+
+    let summary = text(format!(
+        "{} of {} items have a tax group",
+        grouped(coverage.covered),
+        grouped(coverage.total)
+    ))
+    .size(text_style.caption())
+    .style(if uncovered > 0 { Modern::red_text() } else { Modern::secondary_text() });
+
+    if uncovered == 0 {
+        return row![summary].padding([0, 15]).into();
+    }
+
+    let assign: Element<'a, Message> = match default_group {
+        Some(group) => button(text(format!(
+            "Assign {} to all {} uncovered items",
+            group.name,
+            grouped(uncovered)
+        )).size(text_style.caption()))
+            .on_press(Message::AssignDefaultToUncovered)
+            .style(Modern::warning_button())
+            .into(),
+        None => text("Pick a default tax group in Settings to fill them in")
+            .size(text_style.caption())
+            .style(Modern::secondary_text())
+            .into(),
+    };
+
+    row![
+        summary,
+        button(text("Show uncovered items").size(text_style.caption()))
+            .on_press(Message::ShowUncoveredItems)
+            .style(Modern::secondary_button()),
+        assign,
+    ]
+    .spacing(10)
+    .padding([0, 15])
+    .align_y(iced::Alignment::Center)
+    .into()
+}
+
+// Thousands separators for counts
+fn grouped(count: usize) -> String {
+    let digits = count.to_string();
+    let mut out = String::new();
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            out.push(',');
+        }
+        out.push(digit);
+    }
+    out
+}
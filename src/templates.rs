@@ -0,0 +1,57 @@
+use std::fmt;
+use crate::persistence::AppState;
+
+/// Built-in starting points for a new database, shipped inside the binary
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StarterTemplate {
+    Bar,
+    QuickService,
+    FullService,
+}
+
+impl StarterTemplate {
+    pub const ALL: [StarterTemplate; 3] = [
+        StarterTemplate::Bar,
+        StarterTemplate::QuickService,
+        StarterTemplate::FullService,
+    ];
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            Self::Bar => "Liquor tax, happy hour pricing and categories for well, call and premium drinks.",
+            Self::QuickService => "Size and combo price levels with entree, side and fountain drink categories.",
+            Self::FullService => "Lunch and dinner pricing with categories for a full dining menu and bar.",
+        }
+    }
+
+    fn json(&self) -> &'static str {
+        match self {
+            Self::Bar => include_str!("../templates/bar.json"),
+            Self::QuickService => include_str!("../templates/quick_service.json"),
+            Self::FullService => include_str!("../templates/full_service.json"),
+        }
+    }
+
+    /// Parses the template like a save file, settings are left at their defaults
+    pub fn load(&self) -> Result<AppState, String> {
+        serde_json::from_str(self.json())
+            .map_err(|e| format!("The {} template could not be read: {}", self, e))
+    }
+}
+
+impl fmt::Display for StarterTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bar => write!(f, "Bar"),
+            Self::QuickService => write!(f, "Quick Service"),
+            Self::FullService => write!(f, "Full Service"),
+        }
+    }
+}
+
+/// The template chooser, shown on first run or from Settings
+#[derive(Debug, Clone, Default)]
+pub struct TemplatePicker {
+    pub selected: Option<StarterTemplate>,
+    pub first_run: bool,
+}
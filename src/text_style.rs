@@ -0,0 +1,97 @@
+use iced::Font;
+use crate::settings::{AppSettings, TextSize};
+
+/// Text size and monospace settings, handed down to the views like the rest of the settings
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TextStyle {
+    pub size: TextSize,
+    pub monospace_data: bool,
+}
+
+impl TextStyle {
+    pub fn from_settings(settings: &AppSettings) -> Self {
+        Self {
+            size: settings.text_size,
+            monospace_data: settings.monospace_data,
+        }
+    }
+
+    /// Scales a medium size to the chosen text size
+    pub fn scaled(&self, medium: u16) -> u16 {
+        match self.size {
+            TextSize::Small => medium.saturating_sub(2),
+            TextSize::Medium => medium,
+            TextSize::Large => medium + 3,
+        }
+    }
+
+    /// Fine print, like the theme toggle label
+    pub fn tiny(&self) -> u16 {
+        self.scaled(10)
+    }
+
+    /// Hints, status messages and secondary details
+    pub fn caption(&self) -> u16 {
+        self.scaled(12)
+    }
+
+    /// Regular text in tables and forms
+    pub fn body(&self) -> u16 {
+        self.scaled(14)
+    }
+
+    /// Section titles inside a card
+    pub fn subheading(&self) -> u16 {
+        self.scaled(16)
+    }
+
+    /// Card and screen titles
+    pub fn heading(&self) -> u16 {
+        self.scaled(18)
+    }
+
+    /// Large titles, like the window header
+    pub fn title(&self) -> u16 {
+        self.scaled(24)
+    }
+
+    /// Font for ID and price columns
+    pub fn data_font(&self) -> Font {
+        if self.monospace_data {
+            Font::MONOSPACE
+        } else {
+            Font::DEFAULT
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sizes_follow_the_settings() {
+        let mut settings = AppSettings::default();
+        assert_eq!(TextStyle::from_settings(&settings).body(), 14);
+
+        settings.text_size = TextSize::Small;
+        assert_eq!(TextStyle::from_settings(&settings).body(), 12);
+
+        settings.text_size = TextSize::Large;
+        settings.monospace_data = true;
+        let style = TextStyle::from_settings(&settings);
+        assert_eq!(style.title(), 27);
+        assert_eq!(style.data_font(), Font::MONOSPACE);
+    }
+
+    #[test]
+    fn styles_are_independent_values() {
+        // Two windows can render with different styles, nothing is shared
+        let small = TextStyle { size: TextSize::Small, monospace_data: false };
+        let large = TextStyle { size: TextSize::Large, monospace_data: true };
+
+        assert_eq!(small.caption(), 10);
+        assert_eq!(large.caption(), 15);
+        assert_eq!(small.data_font(), Font::DEFAULT);
+    }
+}